@@ -1,3 +1,5 @@
+#![allow(unused_imports)]
+
 use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
 
 // NOTE: This is only possible because this module is designed to be standalone
@@ -1,3 +1,5 @@
+#![allow(clippy::needless_borrow)]
+
 use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
 
 // 2026-01-27:
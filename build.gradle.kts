@@ -1,3 +1,5 @@
+val allocationTableFeatures = listOf("fat12", "fat16", "fat32")
+
 val featuresMatrix = listOf(
     listOf("async"),
     listOf("sync"),
@@ -6,6 +8,16 @@ val featuresMatrix = listOf(
     listOf("async", "unicode-case-folding"),
     listOf("sync", "unicode-case-folding"),
     listOf("async", "sync", "unicode-case-folding"),
+).map { it + allocationTableFeatures } + listOf(
+    listOf("async", "sync", "fat12"),
+    listOf("async", "sync", "fat16"),
+    listOf("async", "sync", "fat32"),
+    listOf("async", "sync", "fat12", "fat16"),
+    listOf("async", "dangerous") + allocationTableFeatures,
+    listOf("ffi") + allocationTableFeatures,
+    listOf("sync", "code-page-437", "code-page-850") + allocationTableFeatures,
+    listOf("sync", "unicode-case-folding", "unicode-normalization") + allocationTableFeatures,
+    listOf("async", "sync", "config") + allocationTableFeatures,
 );
 
 tasks {
@@ -55,14 +67,14 @@ tasks {
     }
 
     val cargoTestWithoutUnicodeCasesFolding by registering(Exec::class) {
-        shouldRunAfter(cargoClippy, cargoFormat, cargoTest, "cargoBuildFeaturesAsyncSync")
+        shouldRunAfter(cargoClippy, cargoFormat, cargoTest, "cargoBuildFeaturesAsyncSyncFat12Fat16Fat32")
 
         commandLine(
             "cargo", "llvm-cov", "test",
             "--output-dir", "target/coverage/without-unicode-case-folding",
             "--html",
             "--no-default-features",
-            "--features", "async,sync")
+            "--features", (listOf("async", "sync") + allocationTableFeatures).joinToString(","))
     }
 
     val cargoClean by registering(Exec::class) {
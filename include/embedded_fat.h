@@ -0,0 +1,104 @@
+/*
+ * C bindings for embedded-fat, available when the crate is built with the `ffi` feature.
+ *
+ * Every handle lives in storage owned by the caller.  File and directory handles borrow the file
+ * system handle they were opened from, which must remain mounted and must not be moved until they
+ * are closed.
+ *
+ * Functions return EMBEDDED_FAT_OK or a negative EMBEDDED_FAT_ERROR_* code unless stated otherwise.
+ */
+
+#ifndef EMBEDDED_FAT_H
+#define EMBEDDED_FAT_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <sys/types.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define EMBEDDED_FAT_OK 0
+#define EMBEDDED_FAT_ERROR_IO (-1)
+#define EMBEDDED_FAT_ERROR_NOT_FOUND (-2)
+#define EMBEDDED_FAT_ERROR_INVALID_ARGUMENT (-3)
+#define EMBEDDED_FAT_ERROR_UNSUPPORTED (-4)
+#define EMBEDDED_FAT_ERROR_CORRUPTED (-5)
+
+#define EMBEDDED_FAT_SEEK_SET 0
+#define EMBEDDED_FAT_SEEK_CUR 1
+#define EMBEDDED_FAT_SEEK_END 2
+
+#define EMBEDDED_FAT_FILE_SYSTEM_SIZE 256
+#define EMBEDDED_FAT_FILE_SIZE 1080
+#define EMBEDDED_FAT_DIR_SIZE 672
+
+#define EMBEDDED_FAT_SHORT_NAME_SIZE 13
+#define EMBEDDED_FAT_LONG_NAME_SIZE 766
+
+#define EMBEDDED_FAT_PATH_SIZE 256
+
+/*
+ * Callbacks used to access the bytes of the underlying volume, each receives `context` as its
+ * first argument.  `read` and `seek` are required, `write` and `flush` may be NULL for read-only
+ * volumes.
+ */
+typedef struct embedded_fat_io {
+    void *context;
+
+    ssize_t (*read)(void *context, uint8_t *buffer, size_t length);
+    ssize_t (*write)(void *context, const uint8_t *buffer, size_t length);
+    int64_t (*seek)(void *context, int64_t offset, int whence);
+    int (*flush)(void *context);
+} embedded_fat_io;
+
+typedef struct embedded_fat_file_system {
+    _Alignas(8) uint8_t storage[EMBEDDED_FAT_FILE_SYSTEM_SIZE];
+} embedded_fat_file_system;
+
+typedef struct embedded_fat_file {
+    _Alignas(8) uint8_t storage[EMBEDDED_FAT_FILE_SIZE];
+} embedded_fat_file;
+
+typedef struct embedded_fat_dir {
+    _Alignas(8) uint8_t storage[EMBEDDED_FAT_DIR_SIZE];
+} embedded_fat_dir;
+
+typedef struct embedded_fat_dir_entry {
+    char short_name[EMBEDDED_FAT_SHORT_NAME_SIZE];
+    char long_name[EMBEDDED_FAT_LONG_NAME_SIZE];
+    uint32_t file_size;
+    bool is_directory;
+    bool is_volume_label;
+} embedded_fat_dir_entry;
+
+int embedded_fat_mount(embedded_fat_file_system *fs, const embedded_fat_io *io);
+int embedded_fat_unmount(embedded_fat_file_system *fs);
+
+int embedded_fat_open(const embedded_fat_file_system *fs, const char *path, embedded_fat_file *file);
+/*
+ * Opens an existing file for writing, copying `path` of at most EMBEDDED_FAT_PATH_SIZE bytes into
+ * `file`, which must not be moved until it's closed.  The written size is recorded in the file's
+ * directory entry by embedded_fat_close.
+ */
+int embedded_fat_open_writer(const embedded_fat_file_system *fs, const char *path, embedded_fat_file *file);
+/* Returns the number of bytes read, less than `length` only at the end of the file. */
+ssize_t embedded_fat_read(embedded_fat_file *file, uint8_t *buffer, size_t length);
+/* Returns the number of bytes written, INVALID_ARGUMENT for files opened by embedded_fat_open. */
+ssize_t embedded_fat_write(embedded_fat_file *file, const uint8_t *buffer, size_t length);
+/* Releases the file even when recording the written size fails. */
+int embedded_fat_close(embedded_fat_file *file);
+
+/* An empty path refers to the root directory. */
+int embedded_fat_opendir(const embedded_fat_file_system *fs, const char *path, embedded_fat_dir *dir);
+/* Returns 1 if an entry was read, 0 at the end of the listing, may be continued after CORRUPTED. */
+int embedded_fat_readdir(embedded_fat_dir *dir, embedded_fat_dir_entry *entry);
+int embedded_fat_closedir(embedded_fat_dir *dir);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -3,75 +3,310 @@ mod entry_offset;
 mod error;
 mod kind;
 mod physical_entry;
+mod reserved_entries;
+mod statistics;
 
 pub use entry::*;
 pub use entry_offset::*;
 pub use error::*;
 pub use kind::*;
 pub use physical_entry::*;
+pub(crate) use reserved_entries::*;
+pub use statistics::*;
 
+use crate::units::{ByteAddress, ClusterNumber};
 use crate::utils::read_le_u32;
+use core::ops::RangeInclusive;
 use embedded_io::{ErrorType, SeekFrom};
 
 #[cfg(feature = "sync")]
-use embedded_io::{Read, Seek};
+use {
+    crate::erased_stream::{ErasedRead, ErasedWrite, ErasingStream, StreamFailed},
+    embedded_io::{Read, Seek, Write},
+};
 
 #[cfg(feature = "async")]
-use embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek};
+use embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek, Write as AsyncWrite};
 
 #[derive(Clone, Debug)]
 pub struct AllocationTable {
     kind: AllocationTableKind,
-    base_address: u64,
+    base_address: ByteAddress,
+
+    mirror_count: u8,
+    bytes_per_table: u64,
+
+    reserved_cluster_ranges: &'static [RangeInclusive<u32>],
 }
 
 impl AllocationTable {
-    pub fn new(kind: AllocationTableKind, base_address: u64) -> Self {
-        Self { kind, base_address }
+    pub fn new(kind: AllocationTableKind, base_address: ByteAddress) -> Self {
+        Self {
+            kind,
+            base_address,
+
+            mirror_count: 0,
+            bytes_per_table: 0,
+
+            reserved_cluster_ranges: &[],
+        }
+    }
+
+    /// Keeps the given number of copies, stored directly after the table, up to date when entries
+    /// are written.
+    pub fn with_mirrors(self, mirror_count: u8, bytes_per_table: u64) -> Self {
+        Self {
+            mirror_count,
+            bytes_per_table,
+            ..self
+        }
+    }
+
+    /// Keeps the clusters numbered within the given ranges from being allocated, even while the
+    /// table records them as free.
+    pub fn with_reserved_clusters(
+        self,
+        reserved_cluster_ranges: &'static [RangeInclusive<u32>],
+    ) -> Self {
+        Self {
+            reserved_cluster_ranges,
+            ..self
+        }
+    }
+
+    pub(crate) fn reserved_cluster_ranges(&self) -> &'static [RangeInclusive<u32>] {
+        self.reserved_cluster_ranges
     }
 
     pub(crate) fn kind(&self) -> AllocationTableKind {
         self.kind
     }
 
+    /// The copy of the table at the given index, the table itself being the first, `None` if there
+    /// is no such copy.  The returned table doesn't keep any mirrors up to date.
+    pub(crate) fn copy(&self, table_index: u8) -> Option<AllocationTable> {
+        if table_index > self.mirror_count {
+            return None;
+        }
+
+        Some(
+            AllocationTable::new(
+                self.kind,
+                self.base_address
+                    .offset_by(u64::from(table_index) * self.bytes_per_table),
+            )
+            .with_reserved_clusters(self.reserved_cluster_ranges),
+        )
+    }
+
     #[cfg(feature = "sync")]
     pub fn read_entry<S>(
         &self,
         stream: &mut S,
-        cluster_number: u32,
+        cluster_number: ClusterNumber,
     ) -> Result<AllocationTableEntry, AllocationTableError<S::Error>>
     where
         S: Read + Seek,
     {
-        let mut entry_value_bytes = [0u8; 4];
-        let entry_offest = self.resolve_entry_offset(cluster_number);
+        ErasingStream::run(stream, |stream| {
+            self.read_entry_erased(stream, cluster_number)
+        })
+    }
+
+    /// Writes the entry to the table and each of its mirrors.
+    #[cfg(feature = "sync")]
+    pub fn write_entry<S>(
+        &self,
+        stream: &mut S,
+        cluster_number: ClusterNumber,
+        entry: AllocationTableEntry,
+    ) -> Result<(), AllocationTableError<S::Error>>
+    where
+        S: Read + Write + Seek,
+    {
+        let physical_entry = self.physical_entry(cluster_number, &entry)?;
 
-        stream.seek(SeekFrom::Start(
-            self.base_address + entry_offest.byte_offset,
-        ))?;
+        ErasingStream::run(stream, |stream| {
+            self.write_entry_erased(stream, cluster_number, &physical_entry)
+        })
+    }
 
-        match self.kind {
-            AllocationTableKind::Fat12 | AllocationTableKind::Fat16 => {
-                stream.read_exact(&mut entry_value_bytes[0..2])?;
-            }
-            AllocationTableKind::Fat32 => {
-                stream.read_exact(&mut entry_value_bytes)?;
-            }
-        }
+    /// Finds the first free cluster numbered between `start_cluster_number` and
+    /// `last_cluster_number`, inclusive, skipping reserved clusters.
+    #[cfg(feature = "sync")]
+    pub fn find_free_cluster<S>(
+        &self,
+        stream: &mut S,
+        start_cluster_number: ClusterNumber,
+        last_cluster_number: ClusterNumber,
+    ) -> Result<Option<ClusterNumber>, AllocationTableError<S::Error>>
+    where
+        S: Read + Seek,
+    {
+        ErasingStream::run(stream, |stream| {
+            self.find_free_cluster_erased(stream, start_cluster_number, last_cluster_number)
+        })
+    }
+
+    /// Counts the free clusters numbered up to `last_cluster_number`, inclusive, excluding reserved
+    /// clusters.
+    #[cfg(feature = "sync")]
+    pub fn count_free_clusters<S>(
+        &self,
+        stream: &mut S,
+        last_cluster_number: ClusterNumber,
+    ) -> Result<u32, AllocationTableError<S::Error>>
+    where
+        S: Read + Seek,
+    {
+        ErasingStream::run(stream, |stream| {
+            self.count_free_clusters_erased(stream, last_cluster_number)
+        })
+    }
+
+    /// Gathers the statistics of the entries of the clusters numbered up to `last_cluster_number`,
+    /// inclusive, in a single pass over the table.  Reserved clusters recorded as free are counted
+    /// as reserved.
+    #[cfg(feature = "sync")]
+    pub fn statistics<S>(
+        &self,
+        stream: &mut S,
+        last_cluster_number: ClusterNumber,
+    ) -> Result<AllocationTableStatistics, AllocationTableError<S::Error>>
+    where
+        S: Read + Seek,
+    {
+        ErasingStream::run(stream, |stream| {
+            self.statistics_erased(stream, last_cluster_number)
+        })
+    }
+
+    /// Marks every cluster of the chain starting at `first_cluster_number` as free.
+    ///
+    /// At most as many clusters as are numbered up to `last_cluster_number` are released, so a
+    /// corrupted, cyclic chain can't loop forever.
+    #[cfg(feature = "sync")]
+    pub fn release_chain<S>(
+        &self,
+        stream: &mut S,
+        first_cluster_number: ClusterNumber,
+        last_cluster_number: ClusterNumber,
+    ) -> Result<(), AllocationTableError<S::Error>>
+    where
+        S: Read + Write + Seek,
+    {
+        ErasingStream::run(stream, |stream| {
+            self.release_chain_erased(stream, first_cluster_number, last_cluster_number)
+        })
+    }
+
+    #[cfg(feature = "sync")]
+    fn read_entry_erased(
+        &self,
+        stream: &mut dyn ErasedRead,
+        cluster_number: ClusterNumber,
+    ) -> Result<AllocationTableEntry, StreamFailed> {
+        let mut entry_value_bytes = [0u8; 4];
+        let entry_offset = self.resolve_entry_offset(cluster_number);
+
+        stream.seek_to(self.base_address.offset_by(entry_offset.byte_offset))?;
+        stream.read_exact(&mut entry_value_bytes[0..self.kind.entry_read_size()])?;
 
         Ok(PhysicalAllocationTableEntry::from_bytes(
             self.kind,
             &entry_value_bytes,
-            entry_offest.is_nibble_offset,
+            entry_offset.is_nibble_offset,
         )
         .as_logical_entry())
     }
 
+    #[cfg(feature = "sync")]
+    fn write_entry_erased(
+        &self,
+        stream: &mut dyn ErasedWrite,
+        cluster_number: ClusterNumber,
+        physical_entry: &PhysicalAllocationTableEntry,
+    ) -> Result<(), StreamFailed> {
+        let entry_offset = self.resolve_entry_offset(cluster_number);
+        let entry_size = self.kind.entry_read_size();
+
+        for table_index in 0..=self.mirror_count as u64 {
+            let entry_address = self
+                .base_address
+                .offset_by((table_index * self.bytes_per_table) + entry_offset.byte_offset);
+            let mut entry_value_bytes = [0u8; 4];
+
+            // Entries may share bytes with their neighbors, so the existing bytes are merged
+            stream.seek_to(entry_address)?;
+            stream.read_exact(&mut entry_value_bytes[0..entry_size])?;
+
+            physical_entry.write(&mut entry_value_bytes, entry_offset.is_nibble_offset);
+
+            stream.seek_to(entry_address)?;
+            stream.write_all(&entry_value_bytes[0..entry_size])?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "sync")]
+    fn find_free_cluster_erased(
+        &self,
+        stream: &mut dyn ErasedRead,
+        start_cluster_number: ClusterNumber,
+        last_cluster_number: ClusterNumber,
+    ) -> Result<Option<ClusterNumber>, StreamFailed> {
+        let first_cluster_number = start_cluster_number.max(ClusterNumber::FIRST_DATA_CLUSTER);
+
+        for cluster_number in first_cluster_number.value()..=last_cluster_number.value() {
+            let cluster_number = ClusterNumber::new(cluster_number);
+
+            if !self.is_reserved(cluster_number)
+                && self.read_entry_erased(stream, cluster_number)? == AllocationTableEntry::Free
+            {
+                return Ok(Some(cluster_number));
+            }
+        }
+
+        Ok(None)
+    }
+
+    #[cfg(feature = "sync")]
+    fn count_free_clusters_erased(
+        &self,
+        stream: &mut dyn ErasedRead,
+        last_cluster_number: ClusterNumber,
+    ) -> Result<u32, StreamFailed> {
+        Ok(self
+            .statistics_erased(stream, last_cluster_number)?
+            .free_cluster_count())
+    }
+
+    #[cfg(feature = "sync")]
+    fn statistics_erased(
+        &self,
+        stream: &mut dyn ErasedRead,
+        last_cluster_number: ClusterNumber,
+    ) -> Result<AllocationTableStatistics, StreamFailed> {
+        let mut statistics = AllocationTableStatistics::default();
+
+        for cluster_number in
+            ClusterNumber::FIRST_DATA_CLUSTER.value()..=last_cluster_number.value()
+        {
+            let cluster_number = ClusterNumber::new(cluster_number);
+            let entry = self.read_entry_erased(stream, cluster_number)?;
+
+            statistics.record(cluster_number, &self.effective_entry(cluster_number, entry));
+        }
+
+        Ok(statistics)
+    }
+
     #[cfg(feature = "async")]
     pub async fn read_entry_async<S>(
         &self,
         stream: &mut S,
-        cluster_number: u32,
+        cluster_number: ClusterNumber,
     ) -> Result<AllocationTableEntry, AllocationTableError<S::Error>>
     where
         S: AsyncRead + AsyncSeek,
@@ -81,18 +316,15 @@ impl AllocationTable {
 
         stream
             .seek(SeekFrom::Start(
-                self.base_address + entry_offset.byte_offset,
+                self.base_address
+                    .offset_by(entry_offset.byte_offset)
+                    .value(),
             ))
             .await?;
 
-        match self.kind {
-            AllocationTableKind::Fat12 | AllocationTableKind::Fat16 => {
-                stream.read_exact(&mut entry_value_bytes[0..2]).await?;
-            }
-            AllocationTableKind::Fat32 => {
-                stream.read_exact(&mut entry_value_bytes).await?;
-            }
-        }
+        stream
+            .read_exact(&mut entry_value_bytes[0..self.kind.entry_read_size()])
+            .await?;
 
         Ok(PhysicalAllocationTableEntry::from_bytes(
             self.kind,
@@ -102,18 +334,177 @@ impl AllocationTable {
         .as_logical_entry())
     }
 
-    fn resolve_entry_offset(&self, cluster_number: u32) -> AllocationTableEntryOffset {
-        let entry_index = cluster_number as u64;
-        let byte_offset = match self.kind {
-            AllocationTableKind::Fat12 => entry_index + (entry_index / 2),
-            AllocationTableKind::Fat16 => entry_index * 2,
-            AllocationTableKind::Fat32 => entry_index * 4,
+    /// Writes the entry to the table and each of its mirrors.
+    #[cfg(feature = "async")]
+    pub async fn write_entry_async<S>(
+        &self,
+        stream: &mut S,
+        cluster_number: ClusterNumber,
+        entry: AllocationTableEntry,
+    ) -> Result<(), AllocationTableError<S::Error>>
+    where
+        S: AsyncRead + AsyncWrite + AsyncSeek,
+    {
+        let physical_entry = self.physical_entry(cluster_number, &entry)?;
+        let entry_offset = self.resolve_entry_offset(cluster_number);
+        let entry_size = self.kind.entry_read_size();
+
+        for table_index in 0..=self.mirror_count as u64 {
+            let entry_address = self
+                .base_address
+                .offset_by((table_index * self.bytes_per_table) + entry_offset.byte_offset);
+            let mut entry_value_bytes = [0u8; 4];
+
+            // Entries may share bytes with their neighbors, so the existing bytes are merged
+            stream.seek(SeekFrom::Start(entry_address.value())).await?;
+            stream
+                .read_exact(&mut entry_value_bytes[0..entry_size])
+                .await?;
+
+            physical_entry.write(&mut entry_value_bytes, entry_offset.is_nibble_offset);
+
+            stream.seek(SeekFrom::Start(entry_address.value())).await?;
+            stream.write_all(&entry_value_bytes[0..entry_size]).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Finds the first free cluster numbered between `start_cluster_number` and
+    /// `last_cluster_number`, inclusive, skipping reserved clusters.
+    #[cfg(feature = "async")]
+    pub async fn find_free_cluster_async<S>(
+        &self,
+        stream: &mut S,
+        start_cluster_number: ClusterNumber,
+        last_cluster_number: ClusterNumber,
+    ) -> Result<Option<ClusterNumber>, AllocationTableError<S::Error>>
+    where
+        S: AsyncRead + AsyncSeek,
+    {
+        let first_cluster_number = start_cluster_number.max(ClusterNumber::FIRST_DATA_CLUSTER);
+
+        for cluster_number in first_cluster_number.value()..=last_cluster_number.value() {
+            let cluster_number = ClusterNumber::new(cluster_number);
+
+            if !self.is_reserved(cluster_number)
+                && self.read_entry_async(stream, cluster_number).await?
+                    == AllocationTableEntry::Free
+            {
+                return Ok(Some(cluster_number));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Gathers the statistics of the entries of the clusters numbered up to `last_cluster_number`,
+    /// inclusive, in a single pass over the table.  Reserved clusters recorded as free are counted
+    /// as reserved.
+    #[cfg(feature = "async")]
+    pub async fn statistics_async<S>(
+        &self,
+        stream: &mut S,
+        last_cluster_number: ClusterNumber,
+    ) -> Result<AllocationTableStatistics, AllocationTableError<S::Error>>
+    where
+        S: AsyncRead + AsyncSeek,
+    {
+        let mut statistics = AllocationTableStatistics::default();
+
+        for cluster_number in
+            ClusterNumber::FIRST_DATA_CLUSTER.value()..=last_cluster_number.value()
+        {
+            let cluster_number = ClusterNumber::new(cluster_number);
+            let entry = self.read_entry_async(stream, cluster_number).await?;
+
+            statistics.record(cluster_number, &self.effective_entry(cluster_number, entry));
+        }
+
+        Ok(statistics)
+    }
+
+    #[cfg(feature = "sync")]
+    fn release_chain_erased(
+        &self,
+        stream: &mut dyn ErasedWrite,
+        first_cluster_number: ClusterNumber,
+        last_cluster_number: ClusterNumber,
+    ) -> Result<(), StreamFailed> {
+        let mut cluster_number = first_cluster_number;
+
+        for _ in ClusterNumber::FIRST_DATA_CLUSTER.value()..=last_cluster_number.value() {
+            let entry = self.read_entry_erased(stream, cluster_number)?;
+
+            self.write_entry_erased(
+                stream,
+                cluster_number,
+                &PhysicalAllocationTableEntry::free(self.kind),
+            )?;
+
+            match entry {
+                AllocationTableEntry::NextClusterNumber(next_cluster_number) => {
+                    cluster_number = next_cluster_number
+                }
+                _ => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The entry as stored in the table, failing for entries too large for the table such as links
+    /// to cluster numbers it can't address.
+    fn physical_entry<E>(
+        &self,
+        cluster_number: ClusterNumber,
+        entry: &AllocationTableEntry,
+    ) -> Result<PhysicalAllocationTableEntry, AllocationTableError<E>>
+    where
+        E: embedded_io::Error,
+    {
+        entry
+            .as_physical_entry(self.kind)
+            .map_err(|()| AllocationTableError::EntryValueInvalid {
+                cluster_number: cluster_number.value(),
+            })
+    }
+
+    fn is_reserved(&self, cluster_number: ClusterNumber) -> bool {
+        self.reserved_cluster_ranges
+            .iter()
+            .any(|range| range.contains(&cluster_number.value()))
+    }
+
+    /// The entry as seen by the allocator, which treats reserved clusters as reserved even while
+    /// the table records them as free.
+    fn effective_entry(
+        &self,
+        cluster_number: ClusterNumber,
+        entry: AllocationTableEntry,
+    ) -> AllocationTableEntry {
+        match entry {
+            AllocationTableEntry::Free if self.is_reserved(cluster_number) => {
+                AllocationTableEntry::Reserved
+            }
+            _ => entry,
+        }
+    }
+
+    fn resolve_entry_offset(&self, cluster_number: ClusterNumber) -> AllocationTableEntryOffset {
+        let entry_index = cluster_number.value() as u64;
+        let (byte_offset, is_nibble_offset) = match self.kind {
+            #[cfg(feature = "fat12")]
+            AllocationTableKind::Fat12 => (entry_index + (entry_index / 2), entry_index % 2 == 1),
+            #[cfg(feature = "fat16")]
+            AllocationTableKind::Fat16 => (entry_index * 2, false),
+            #[cfg(feature = "fat32")]
+            AllocationTableKind::Fat32 => (entry_index * 4, false),
         };
 
         AllocationTableEntryOffset {
             byte_offset,
-            is_nibble_offset: matches!(self.kind, AllocationTableKind::Fat12)
-                && cluster_number % 2 == 1,
+            is_nibble_offset,
         }
     }
 }
@@ -133,121 +524,132 @@ mod tests {
         #[test]
         fn returns_construction_value() {
             for kind in AllocationTableKind::iter() {
-                let allocation_table = AllocationTable::new(kind, 0);
+                let allocation_table = AllocationTable::new(kind, ByteAddress::new(0));
 
                 assert_eq!(allocation_table.kind(), kind);
             }
         }
     }
 
+    #[cfg(feature = "sync")]
     mod read_entry {
         use super::*;
 
+        #[cfg(feature = "fat12")]
         #[test]
         fn fat_12_entry_values_read_successfully() {
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat12, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat12, ByteAddress::new(0));
             let mut stream = DataStream::from_bytes([0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC]);
 
             assert_eq!(
                 allocation_table
-                    .read_entry(&mut stream, 0)
+                    .read_entry(&mut stream, ClusterNumber::new(0))
                     .expect("Read should succeed"),
-                AllocationTableEntry::NextClusterNumber(0x412),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x412)),
                 "Non-offset value should read correctly"
             );
 
             assert_eq!(
                 allocation_table
-                    .read_entry(&mut stream, 1)
+                    .read_entry(&mut stream, ClusterNumber::new(1))
                     .expect("Read should succeed"),
-                AllocationTableEntry::NextClusterNumber(0x563),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x563)),
                 "Nibble-offset value should read correctly"
             );
 
             assert_eq!(
                 allocation_table
-                    .read_entry(&mut stream, 2)
+                    .read_entry(&mut stream, ClusterNumber::new(2))
                     .expect("Read should succeed"),
-                AllocationTableEntry::NextClusterNumber(0xA78),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0xA78)),
                 "Byte offset value should read correctly"
             );
 
             assert_eq!(
                 allocation_table
-                    .read_entry(&mut stream, 3)
+                    .read_entry(&mut stream, ClusterNumber::new(3))
                     .expect("Read should succeed"),
-                AllocationTableEntry::NextClusterNumber(0xBC9),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0xBC9)),
                 "Byte and nibble offset value should read correctly"
             );
         }
 
+        #[cfg(feature = "fat16")]
         #[test]
         fn fat_16_offset_entry_values_read_successfully() {
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat16, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(0));
             let mut stream = DataStream::from_bytes([0x12, 0x34, 0x56, 0x78]);
 
             assert_eq!(
                 allocation_table
-                    .read_entry(&mut stream, 0)
+                    .read_entry(&mut stream, ClusterNumber::new(0))
                     .expect("Read should succeed"),
-                AllocationTableEntry::NextClusterNumber(0x3412),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x3412)),
                 "Non-offset value should read correctly"
             );
 
             assert_eq!(
                 allocation_table
-                    .read_entry(&mut stream, 1)
+                    .read_entry(&mut stream, ClusterNumber::new(1))
                     .expect("Read should succeed"),
-                AllocationTableEntry::NextClusterNumber(0x7856),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x7856)),
                 "Offset value should read correctly"
             );
         }
 
+        #[cfg(feature = "fat32")]
         #[test]
         fn fat_32_offset_entry_values_read_successfully() {
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
             let mut stream =
                 DataStream::from_bytes([0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xFF]);
 
             // NOTE: Fat32 only uses the lower 28 of the 32 bits
             assert_eq!(
                 allocation_table
-                    .read_entry(&mut stream, 0)
+                    .read_entry(&mut stream, ClusterNumber::new(0))
                     .expect("Read should succeed"),
-                AllocationTableEntry::NextClusterNumber(0x08563412),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x08563412)),
                 "Non-offset value should read correctly"
             );
 
             assert_eq!(
                 allocation_table
-                    .read_entry(&mut stream, 1)
+                    .read_entry(&mut stream, ClusterNumber::new(1))
                     .expect("Read should succeed"),
-                AllocationTableEntry::NextClusterNumber(0x0FDEBC9A),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x0FDEBC9A)),
                 "Offset value should read correctly"
             );
         }
 
+        #[cfg(feature = "fat16")]
         #[test]
         fn base_address_honored() {
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat16, 2);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(2));
             let mut stream = DataStream::from_bytes([0x12, 0x34, 0x56, 0x78]);
 
             assert_eq!(
                 allocation_table
-                    .read_entry(&mut stream, 0)
+                    .read_entry(&mut stream, ClusterNumber::new(0))
                     .expect("Read should succeed"),
-                AllocationTableEntry::NextClusterNumber(0x7856),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x7856)),
                 "Value should read correctly"
             );
         }
 
+        #[cfg(feature = "fat32")]
         #[test]
         fn stream_not_long_enough_returns_error() {
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
             let mut stream = DataStream::from_bytes([0x12, 0x34]);
 
             let result = allocation_table
-                .read_entry(&mut stream, 0)
+                .read_entry(&mut stream, ClusterNumber::new(0))
                 .expect_err("Read should fail");
 
             assert!(
@@ -256,9 +658,11 @@ mod tests {
             );
         }
 
+        #[cfg(feature = "fat32")]
         #[test]
         fn stream_seek_error_propagated() {
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
             let mut stream = ErroringStream::new(
                 DataStream::from_bytes([0, 0, 0, 0]),
                 IoError::default(),
@@ -266,7 +670,7 @@ mod tests {
             );
 
             let result = allocation_table
-                .read_entry(&mut stream, 0)
+                .read_entry(&mut stream, ClusterNumber::new(0))
                 .expect_err("Read should fail");
 
             assert!(
@@ -275,9 +679,11 @@ mod tests {
             );
         }
 
+        #[cfg(feature = "fat32")]
         #[test]
         fn stream_read_error_propagated() {
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
             let mut stream = ErroringStream::new(
                 DataStream::from_bytes([0, 0, 0, 0]),
                 IoError::default(),
@@ -285,7 +691,7 @@ mod tests {
             );
 
             let result = allocation_table
-                .read_entry(&mut stream, 0)
+                .read_entry(&mut stream, ClusterNumber::new(0))
                 .expect_err("Read should fail");
 
             assert!(
@@ -295,123 +701,696 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "sync")]
+    mod write_entry {
+        use super::*;
+
+        #[cfg(feature = "fat12")]
+        #[test]
+        fn fat_12_neighboring_entries_preserved() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat12, ByteAddress::new(0));
+            let mut stream = DataStream::from_bytes([0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC]);
+
+            allocation_table
+                .write_entry(
+                    &mut stream,
+                    ClusterNumber::new(1),
+                    AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0xABC)),
+                )
+                .expect("Write should succeed");
+            allocation_table
+                .write_entry(
+                    &mut stream,
+                    ClusterNumber::new(2),
+                    AllocationTableEntry::EndOfFile,
+                )
+                .expect("Write should succeed");
+
+            assert_eq!(
+                allocation_table
+                    .read_entry(&mut stream, ClusterNumber::new(0))
+                    .unwrap(),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x412)),
+                "Preceding entry should be unchanged"
+            );
+            assert_eq!(
+                allocation_table
+                    .read_entry(&mut stream, ClusterNumber::new(1))
+                    .unwrap(),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0xABC))
+            );
+            assert_eq!(
+                allocation_table
+                    .read_entry(&mut stream, ClusterNumber::new(2))
+                    .unwrap(),
+                AllocationTableEntry::EndOfFile
+            );
+            assert_eq!(
+                allocation_table
+                    .read_entry(&mut stream, ClusterNumber::new(3))
+                    .unwrap(),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0xBC9)),
+                "Following entry should be unchanged"
+            );
+        }
+
+        #[cfg(feature = "fat16")]
+        #[test]
+        fn mirrors_written() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(0))
+                    .with_mirrors(1, 4);
+            let mut stream = DataStream::from_bytes([0u8; 8]);
+
+            allocation_table
+                .write_entry(
+                    &mut stream,
+                    ClusterNumber::new(1),
+                    AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x1234)),
+                )
+                .expect("Write should succeed");
+
+            let mirror_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(4));
+
+            assert_eq!(
+                mirror_table
+                    .read_entry(&mut stream, ClusterNumber::new(1))
+                    .unwrap(),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x1234)),
+                "Mirror should be written"
+            );
+        }
+
+        #[cfg(feature = "fat16")]
+        #[test]
+        fn fat_16_entry_encoded() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(0));
+            let mut bytes = [0x12, 0x34, 0x56, 0x78];
+
+            allocation_table
+                .write_entry(
+                    &mut DataStream::from_bytes(&mut bytes[..]),
+                    ClusterNumber::new(1),
+                    AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0xABCD)),
+                )
+                .expect("Write should succeed");
+
+            assert_eq!(bytes, [0x12, 0x34, 0xCD, 0xAB]);
+        }
+
+        #[cfg(feature = "fat32")]
+        #[test]
+        fn fat_32_reserved_bits_preserved() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
+            let mut bytes = [0x00, 0x00, 0x00, 0xF0];
+
+            allocation_table
+                .write_entry(
+                    &mut DataStream::from_bytes(&mut bytes[..]),
+                    ClusterNumber::new(0),
+                    AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x0123_4567)),
+                )
+                .expect("Write should succeed");
+
+            assert_eq!(
+                bytes,
+                [0x67, 0x45, 0x23, 0xF1],
+                "Upper 4 bits should be unchanged"
+            );
+        }
+
+        #[cfg(feature = "fat32")]
+        #[test]
+        fn stream_not_long_enough_returns_error() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
+            let mut stream = DataStream::from_bytes([0x12, 0x34]);
+
+            let result = allocation_table
+                .write_entry(
+                    &mut stream,
+                    ClusterNumber::new(0),
+                    AllocationTableEntry::Free,
+                )
+                .expect_err("Write should fail");
+
+            assert!(
+                matches!(result, AllocationTableError::StreamEndReached),
+                "Error should be StreamEndReached"
+            );
+        }
+
+        #[cfg(feature = "fat16")]
+        #[test]
+        fn cluster_number_beyond_entry_size_returns_error() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(0));
+            let mut bytes = [0x12, 0x34, 0x56, 0x78];
+
+            let result = allocation_table
+                .write_entry(
+                    &mut DataStream::from_bytes(&mut bytes[..]),
+                    ClusterNumber::new(1),
+                    AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x0001_0000)),
+                )
+                .expect_err("Write should fail");
+
+            assert!(
+                matches!(
+                    result,
+                    AllocationTableError::EntryValueInvalid { cluster_number: 1 }
+                ),
+                "Error should be EntryValueInvalid"
+            );
+            assert_eq!(bytes, [0x12, 0x34, 0x56, 0x78], "Table should be unchanged");
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    mod find_free_cluster {
+        use super::*;
+
+        #[cfg(feature = "fat16")]
+        #[test]
+        fn first_free_cluster_returned() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(0));
+            let mut stream =
+                DataStream::from_bytes([0xF8, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00]);
+
+            assert_eq!(
+                allocation_table
+                    .find_free_cluster(&mut stream, ClusterNumber::new(2), ClusterNumber::new(3))
+                    .expect("Search should succeed"),
+                Some(ClusterNumber::new(3))
+            );
+        }
+
+        #[cfg(feature = "fat16")]
+        #[test]
+        fn reserved_clusters_skipped() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(0))
+                    .with_reserved_clusters(&[2..=3]);
+            let mut stream = DataStream::from_bytes([0xF8, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0, 0, 0]);
+
+            assert_eq!(
+                allocation_table
+                    .find_free_cluster(&mut stream, ClusterNumber::new(2), ClusterNumber::new(4))
+                    .expect("Search should succeed"),
+                Some(ClusterNumber::new(4))
+            );
+        }
+
+        #[cfg(feature = "fat16")]
+        #[test]
+        fn no_free_cluster_returns_none() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(0));
+            let mut stream =
+                DataStream::from_bytes([0xF8, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00]);
+
+            assert_eq!(
+                allocation_table
+                    .find_free_cluster(&mut stream, ClusterNumber::new(2), ClusterNumber::new(2))
+                    .expect("Search should succeed"),
+                None
+            );
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    mod count_free_clusters {
+        use super::*;
+
+        #[cfg(feature = "fat16")]
+        #[test]
+        fn free_clusters_counted() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(0));
+            let mut stream = DataStream::from_bytes([
+                0xF8, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00,
+            ]);
+
+            assert_eq!(
+                allocation_table
+                    .count_free_clusters(&mut stream, ClusterNumber::new(5))
+                    .expect("Counting should succeed"),
+                3
+            );
+            assert_eq!(
+                allocation_table
+                    .count_free_clusters(&mut stream, ClusterNumber::new(3))
+                    .expect("Counting should succeed"),
+                1
+            );
+        }
+
+        #[cfg(feature = "fat16")]
+        #[test]
+        fn reserved_clusters_excluded() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(0))
+                    .with_reserved_clusters(&[3..=4, 10..=20]);
+            let mut stream = DataStream::from_bytes([
+                0xF8, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00,
+            ]);
+
+            assert_eq!(
+                allocation_table
+                    .count_free_clusters(&mut stream, ClusterNumber::new(5))
+                    .expect("Counting should succeed"),
+                2
+            );
+        }
+    }
+
+    mod copy {
+        use super::*;
+
+        #[cfg(feature = "fat16")]
+        #[test]
+        fn copies_addressed_after_table() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(8))
+                    .with_mirrors(1, 4);
+
+            let copy = allocation_table.copy(1).expect("Copy should exist");
+
+            assert_eq!(copy.base_address, ByteAddress::new(12));
+            assert_eq!(copy.mirror_count, 0);
+            assert!(allocation_table.copy(2).is_none());
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    mod statistics {
+        use super::*;
+
+        #[cfg(feature = "fat16")]
+        #[test]
+        fn entries_gathered() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(0));
+            let mut stream = DataStream::from_bytes([
+                0xF8, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0xF7, 0xFF, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF,
+            ]);
+
+            let statistics = allocation_table
+                .statistics(&mut stream, ClusterNumber::new(6))
+                .expect("Gathering should succeed");
+
+            assert_eq!(statistics.free_cluster_count(), 3);
+            assert_eq!(statistics.bad_cluster_count(), 1);
+            assert_eq!(statistics.allocated_cluster_count(), 1);
+            assert_eq!(statistics.longest_free_run_start(), Some(4));
+            assert_eq!(statistics.longest_free_run_length(), 2);
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    mod release_chain {
+        use super::*;
+
+        #[cfg(feature = "fat16")]
+        #[test]
+        fn chain_clusters_freed() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(0));
+            let mut stream = DataStream::from_bytes([
+                0xF8, 0xFF, 0xFF, 0xFF, 0x04, 0x00, 0x34, 0x12, 0xFF, 0xFF,
+            ]);
+
+            allocation_table
+                .release_chain(&mut stream, ClusterNumber::new(2), ClusterNumber::new(4))
+                .expect("Release should succeed");
+
+            for cluster_number in [2, 4] {
+                assert_eq!(
+                    allocation_table
+                        .read_entry(&mut stream, ClusterNumber::new(cluster_number))
+                        .unwrap(),
+                    AllocationTableEntry::Free
+                );
+            }
+            assert_eq!(
+                allocation_table
+                    .read_entry(&mut stream, ClusterNumber::new(3))
+                    .unwrap(),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x1234)),
+                "Clusters outside the chain should be unchanged"
+            );
+        }
+
+        #[cfg(feature = "fat16")]
+        #[test]
+        fn cyclic_chain_released() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(0));
+            let mut stream =
+                DataStream::from_bytes([0xF8, 0xFF, 0xFF, 0xFF, 0x03, 0x00, 0x02, 0x00]);
+
+            allocation_table
+                .release_chain(&mut stream, ClusterNumber::new(2), ClusterNumber::new(3))
+                .expect("Release should succeed");
+
+            for cluster_number in [2, 3] {
+                assert_eq!(
+                    allocation_table
+                        .read_entry(&mut stream, ClusterNumber::new(cluster_number))
+                        .unwrap(),
+                    AllocationTableEntry::Free
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "async")]
+    mod write_entry_async {
+        use super::*;
+
+        #[cfg(feature = "fat12")]
+        #[tokio::test]
+        async fn fat_12_neighboring_entries_preserved() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat12, ByteAddress::new(0));
+            let mut stream = DataStream::from_bytes([0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC]);
+
+            allocation_table
+                .write_entry_async(
+                    &mut stream,
+                    ClusterNumber::new(1),
+                    AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0xABC)),
+                )
+                .await
+                .expect("Write should succeed");
+
+            assert_eq!(
+                allocation_table
+                    .read_entry_async(&mut stream, ClusterNumber::new(0))
+                    .await
+                    .unwrap(),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x412)),
+                "Preceding entry should be unchanged"
+            );
+            assert_eq!(
+                allocation_table
+                    .read_entry_async(&mut stream, ClusterNumber::new(1))
+                    .await
+                    .unwrap(),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0xABC))
+            );
+            assert_eq!(
+                allocation_table
+                    .read_entry_async(&mut stream, ClusterNumber::new(2))
+                    .await
+                    .unwrap(),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0xA78)),
+                "Following entry should be unchanged"
+            );
+        }
+
+        #[cfg(feature = "fat16")]
+        #[tokio::test]
+        async fn mirrors_written() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(0))
+                    .with_mirrors(1, 4);
+            let mut stream = DataStream::from_bytes([0u8; 8]);
+
+            allocation_table
+                .write_entry_async(
+                    &mut stream,
+                    ClusterNumber::new(1),
+                    AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x1234)),
+                )
+                .await
+                .expect("Write should succeed");
+
+            let mirror_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(4));
+
+            assert_eq!(
+                mirror_table
+                    .read_entry_async(&mut stream, ClusterNumber::new(1))
+                    .await
+                    .unwrap(),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x1234)),
+                "Mirror should be written"
+            );
+        }
+
+        #[cfg(feature = "fat32")]
+        #[tokio::test]
+        async fn fat_32_reserved_bits_preserved() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
+            let mut bytes = [0x00, 0x00, 0x00, 0xF0];
+
+            allocation_table
+                .write_entry_async(
+                    &mut DataStream::from_bytes(&mut bytes[..]),
+                    ClusterNumber::new(0),
+                    AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x0123_4567)),
+                )
+                .await
+                .expect("Write should succeed");
+
+            assert_eq!(
+                bytes,
+                [0x67, 0x45, 0x23, 0xF1],
+                "Upper 4 bits should be unchanged"
+            );
+        }
+
+        #[cfg(feature = "fat32")]
+        #[tokio::test]
+        async fn stream_not_long_enough_returns_error() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
+            let mut stream = DataStream::from_bytes([0x12, 0x34]);
+
+            let result = allocation_table
+                .write_entry_async(
+                    &mut stream,
+                    ClusterNumber::new(0),
+                    AllocationTableEntry::Free,
+                )
+                .await
+                .expect_err("Write should fail");
+
+            assert!(
+                matches!(result, AllocationTableError::StreamEndReached),
+                "Error should be StreamEndReached"
+            );
+        }
+
+        #[cfg(feature = "fat16")]
+        #[tokio::test]
+        async fn cluster_number_beyond_entry_size_returns_error() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(0));
+            let mut bytes = [0x12, 0x34, 0x56, 0x78];
+
+            let result = allocation_table
+                .write_entry_async(
+                    &mut DataStream::from_bytes(&mut bytes[..]),
+                    ClusterNumber::new(1),
+                    AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x0001_0000)),
+                )
+                .await
+                .expect_err("Write should fail");
+
+            assert!(
+                matches!(
+                    result,
+                    AllocationTableError::EntryValueInvalid { cluster_number: 1 }
+                ),
+                "Error should be EntryValueInvalid"
+            );
+            assert_eq!(bytes, [0x12, 0x34, 0x56, 0x78], "Table should be unchanged");
+        }
+    }
+
+    #[cfg(feature = "async")]
+    mod find_free_cluster_async {
+        use super::*;
+
+        #[cfg(feature = "fat16")]
+        #[tokio::test]
+        async fn reserved_clusters_skipped() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(0))
+                    .with_reserved_clusters(&[2..=3]);
+            let mut stream = DataStream::from_bytes([0xF8, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0, 0, 0]);
+
+            assert_eq!(
+                allocation_table
+                    .find_free_cluster_async(
+                        &mut stream,
+                        ClusterNumber::new(2),
+                        ClusterNumber::new(4)
+                    )
+                    .await
+                    .expect("Search should succeed"),
+                Some(ClusterNumber::new(4))
+            );
+        }
+
+        #[cfg(feature = "fat16")]
+        #[tokio::test]
+        async fn no_free_cluster_returns_none() {
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(0));
+            let mut stream =
+                DataStream::from_bytes([0xF8, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00]);
+
+            assert_eq!(
+                allocation_table
+                    .find_free_cluster_async(
+                        &mut stream,
+                        ClusterNumber::new(2),
+                        ClusterNumber::new(2)
+                    )
+                    .await
+                    .expect("Search should succeed"),
+                None
+            );
+        }
+    }
+
+    #[cfg(feature = "async")]
     mod read_entry_async {
         use super::*;
 
+        #[cfg(feature = "fat12")]
         #[tokio::test]
         async fn fat_12_entry_values_read_successfully() {
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat12, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat12, ByteAddress::new(0));
             let mut stream = DataStream::from_bytes([0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC]);
 
             assert_eq!(
                 allocation_table
-                    .read_entry_async(&mut stream, 0)
+                    .read_entry_async(&mut stream, ClusterNumber::new(0))
                     .await
                     .expect("Read should succeed"),
-                AllocationTableEntry::NextClusterNumber(0x412),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x412)),
                 "Non-offset value should read correctly"
             );
 
             assert_eq!(
                 allocation_table
-                    .read_entry_async(&mut stream, 1)
+                    .read_entry_async(&mut stream, ClusterNumber::new(1))
                     .await
                     .expect("Read should succeed"),
-                AllocationTableEntry::NextClusterNumber(0x563),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x563)),
                 "Nibble-offset value should read correctly"
             );
 
             assert_eq!(
                 allocation_table
-                    .read_entry_async(&mut stream, 2)
+                    .read_entry_async(&mut stream, ClusterNumber::new(2))
                     .await
                     .expect("Read should succeed"),
-                AllocationTableEntry::NextClusterNumber(0xA78),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0xA78)),
                 "Byte offset value should read correctly"
             );
 
             assert_eq!(
                 allocation_table
-                    .read_entry_async(&mut stream, 3)
+                    .read_entry_async(&mut stream, ClusterNumber::new(3))
                     .await
                     .expect("Read should succeed"),
-                AllocationTableEntry::NextClusterNumber(0xBC9),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0xBC9)),
                 "Byte and nibble offset value should read correctly"
             );
         }
 
+        #[cfg(feature = "fat16")]
         #[tokio::test]
         async fn fat_16_offset_entry_values_read_successfully() {
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat16, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(0));
             let mut stream = DataStream::from_bytes([0x12, 0x34, 0x56, 0x78]);
 
             assert_eq!(
                 allocation_table
-                    .read_entry_async(&mut stream, 0)
+                    .read_entry_async(&mut stream, ClusterNumber::new(0))
                     .await
                     .expect("Read should succeed"),
-                AllocationTableEntry::NextClusterNumber(0x3412),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x3412)),
                 "Non-offset value should read correctly"
             );
 
             assert_eq!(
                 allocation_table
-                    .read_entry_async(&mut stream, 1)
+                    .read_entry_async(&mut stream, ClusterNumber::new(1))
                     .await
                     .expect("Read should succeed"),
-                AllocationTableEntry::NextClusterNumber(0x7856),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x7856)),
                 "Offset value should read correctly"
             );
         }
 
+        #[cfg(feature = "fat32")]
         #[tokio::test]
         async fn fat_32_offset_entry_values_read_successfully() {
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
             let mut stream =
                 DataStream::from_bytes([0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xFF]);
 
             // NOTE: Fat32 only uses the lower 28 of the 32 bits
             assert_eq!(
                 allocation_table
-                    .read_entry_async(&mut stream, 0)
+                    .read_entry_async(&mut stream, ClusterNumber::new(0))
                     .await
                     .expect("Read should succeed"),
-                AllocationTableEntry::NextClusterNumber(0x08563412),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x08563412)),
                 "Non-offset value should read correctly"
             );
 
             assert_eq!(
                 allocation_table
-                    .read_entry_async(&mut stream, 1)
+                    .read_entry_async(&mut stream, ClusterNumber::new(1))
                     .await
                     .expect("Read should succeed"),
-                AllocationTableEntry::NextClusterNumber(0x0FDEBC9A),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x0FDEBC9A)),
                 "Offset value should read correctly"
             );
         }
 
+        #[cfg(feature = "fat16")]
         #[tokio::test]
         async fn base_address_honored() {
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat16, 2);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat16, ByteAddress::new(2));
             let mut stream = DataStream::from_bytes([0x12, 0x34, 0x56, 0x78]);
 
             assert_eq!(
                 allocation_table
-                    .read_entry_async(&mut stream, 0)
+                    .read_entry_async(&mut stream, ClusterNumber::new(0))
                     .await
                     .expect("Read should succeed"),
-                AllocationTableEntry::NextClusterNumber(0x7856),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x7856)),
                 "Value should read correctly"
             );
         }
 
+        #[cfg(feature = "fat32")]
         #[tokio::test]
         async fn stream_not_long_enough_returns_error() {
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
             let mut stream = DataStream::from_bytes([0x12, 0x34]);
 
             let result = allocation_table
-                .read_entry_async(&mut stream, 0)
+                .read_entry_async(&mut stream, ClusterNumber::new(0))
                 .await
                 .expect_err("Read should fail");
 
@@ -421,9 +1400,11 @@ mod tests {
             );
         }
 
+        #[cfg(feature = "fat32")]
         #[tokio::test]
         async fn stream_seek_error_propagated() {
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
             let mut stream = ErroringStream::new(
                 DataStream::from_bytes([0, 0, 0, 0]),
                 IoError::default(),
@@ -431,7 +1412,7 @@ mod tests {
             );
 
             let result = allocation_table
-                .read_entry_async(&mut stream, 0)
+                .read_entry_async(&mut stream, ClusterNumber::new(0))
                 .await
                 .expect_err("Read should fail");
 
@@ -441,9 +1422,11 @@ mod tests {
             );
         }
 
+        #[cfg(feature = "fat32")]
         #[tokio::test]
         async fn stream_read_error_propagated() {
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
             let mut stream = ErroringStream::new(
                 DataStream::from_bytes([0, 0, 0, 0]),
                 IoError::default(),
@@ -451,7 +1434,7 @@ mod tests {
             );
 
             let result = allocation_table
-                .read_entry_async(&mut stream, 0)
+                .read_entry_async(&mut stream, ClusterNumber::new(0))
                 .await
                 .expect_err("Read should fail");
 
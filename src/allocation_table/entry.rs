@@ -1,4 +1,5 @@
 use crate::allocation_table::{AllocationTableKind, PhysicalAllocationTableEntry};
+use crate::units::ClusterNumber;
 
 /// Represents a single logical entry in the allocation table.
 ///
@@ -7,7 +8,7 @@ use crate::allocation_table::{AllocationTableKind, PhysicalAllocationTableEntry}
 pub enum AllocationTableEntry {
     Free,
     Reserved,
-    NextClusterNumber(u32),
+    NextClusterNumber(ClusterNumber),
     EndOfFile,
     BadSector,
 }
@@ -20,7 +21,7 @@ impl AllocationTableEntry {
             1 => AllocationTableEntry::Reserved,
             _ => {
                 if entry_value < table_kind.bad_sector_value() {
-                    AllocationTableEntry::NextClusterNumber(entry_value)
+                    AllocationTableEntry::NextClusterNumber(ClusterNumber::new(entry_value))
                 } else if entry_value == table_kind.bad_sector_value() {
                     AllocationTableEntry::BadSector
                 } else {
@@ -37,7 +38,7 @@ impl AllocationTableEntry {
         let value = match self {
             AllocationTableEntry::Free => 0,
             AllocationTableEntry::Reserved => 1,
-            AllocationTableEntry::NextClusterNumber(cluster_number) => *cluster_number,
+            AllocationTableEntry::NextClusterNumber(cluster_number) => cluster_number.value(),
             AllocationTableEntry::BadSector => table_kind.bad_sector_value(),
             AllocationTableEntry::EndOfFile => table_kind.end_of_chain_value(),
         };
@@ -71,12 +72,18 @@ mod tests {
         #[test]
         fn allocated_cluster_value_parsed_successfully() {
             for table_kind in AllocationTableKind::iter() {
-                verify_parses_correctly(table_kind, 2, AllocationTableEntry::NextClusterNumber(2));
+                verify_parses_correctly(
+                    table_kind,
+                    2,
+                    AllocationTableEntry::NextClusterNumber(ClusterNumber::new(2)),
+                );
 
                 verify_parses_correctly(
                     table_kind,
                     table_kind.bad_sector_value() - 1,
-                    AllocationTableEntry::NextClusterNumber(table_kind.bad_sector_value() - 1),
+                    AllocationTableEntry::NextClusterNumber(ClusterNumber::new(
+                        table_kind.bad_sector_value() - 1,
+                    )),
                 );
             }
         }
@@ -128,7 +135,7 @@ mod tests {
             let values = [
                 AllocationTableEntry::Free,
                 AllocationTableEntry::Reserved,
-                AllocationTableEntry::NextClusterNumber(2),
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(2)),
                 AllocationTableEntry::BadSector,
                 AllocationTableEntry::EndOfFile,
             ];
@@ -144,9 +151,10 @@ mod tests {
             }
         }
 
+        #[cfg(feature = "fat16")]
         #[test]
         fn unsupported_cluster_number_returns_none() {
-            let entry = AllocationTableEntry::NextClusterNumber(0x00FF_FFFF);
+            let entry = AllocationTableEntry::NextClusterNumber(ClusterNumber::new(0x00FF_FFFF));
             let result = entry.as_physical_entry(AllocationTableKind::Fat16);
 
             assert!(result.is_err(), "Err should be returned");
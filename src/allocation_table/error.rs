@@ -7,6 +7,11 @@ pub enum AllocationTableError<E>
 where
     E: embedded_io::Error,
 {
+    /// The entry to write for the cluster holds a value too large for the table's entries, such as
+    /// a link to a cluster number beyond what the table can address.
+    EntryValueInvalid {
+        cluster_number: u32,
+    },
     StreamError(E),
     StreamEndReached,
 }
@@ -19,6 +24,10 @@ where
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
+            AllocationTableError::EntryValueInvalid { cluster_number } => write!(
+                f,
+                "the entry for cluster {cluster_number} doesn't fit the allocation table"
+            ),
             AllocationTableError::StreamEndReached => {
                 write!(f, "stream end was reached when not expected")
             }
@@ -61,6 +70,7 @@ mod tests {
         #[test]
         fn produces_non_empty_value() {
             let values = [
+                AllocationTableError::EntryValueInvalid { cluster_number: 2 },
                 AllocationTableError::StreamEndReached,
                 AllocationTableError::StreamError(IoError::default()),
             ];
@@ -1,46 +1,101 @@
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[cfg_attr(test, derive(strum::EnumIter))]
 pub enum AllocationTableKind {
+    #[cfg(feature = "fat12")]
     Fat12,
+    #[cfg(feature = "fat16")]
     Fat16,
+    #[cfg(feature = "fat32")]
     Fat32,
 }
 
 impl AllocationTableKind {
-    pub(crate) const fn new(data_cluster_count: u32) -> AllocationTableKind {
+    /// Determines the kind of table used for the given number of data clusters, returning `None`
+    /// if support for that kind wasn't enabled at compile time.
+    pub(crate) const fn new(data_cluster_count: u32) -> Option<AllocationTableKind> {
         // NOTE: Values aren't round, spec promises they're right despite that being the case
         match data_cluster_count {
-            0..4085 => AllocationTableKind::Fat12,
-            4085..65525 => AllocationTableKind::Fat16,
-            65525.. => AllocationTableKind::Fat32,
+            #[cfg(feature = "fat12")]
+            0..4085 => Some(AllocationTableKind::Fat12),
+            #[cfg(feature = "fat16")]
+            4085..65525 => Some(AllocationTableKind::Fat16),
+            #[cfg(feature = "fat32")]
+            65525.. => Some(AllocationTableKind::Fat32),
+            #[allow(unreachable_patterns)]
+            _ => None,
         }
     }
 
+    /// The kind of table used by FAT32 volumes, returning `None` if support for it wasn't enabled
+    /// at compile time.
+    pub(crate) const fn fat32() -> Option<AllocationTableKind> {
+        #[cfg(feature = "fat32")]
+        return Some(AllocationTableKind::Fat32);
+
+        #[cfg(not(feature = "fat32"))]
+        return None;
+    }
+
     pub(crate) const fn bad_sector_value(&self) -> u32 {
         match self {
+            #[cfg(feature = "fat12")]
             AllocationTableKind::Fat12 => 0x0000_0FF7,
+            #[cfg(feature = "fat16")]
             AllocationTableKind::Fat16 => 0x0000_FFF7,
+            #[cfg(feature = "fat32")]
             AllocationTableKind::Fat32 => 0x0FFF_0FF7,
         }
     }
 
     pub(crate) const fn end_of_chain_value(&self) -> u32 {
         match self {
+            #[cfg(feature = "fat12")]
             AllocationTableKind::Fat12 => 0x0000_0FF8,
+            #[cfg(feature = "fat16")]
             AllocationTableKind::Fat16 => 0x0000_FFF8,
+            #[cfg(feature = "fat32")]
             AllocationTableKind::Fat32 => 0x0FFF_FFF8,
         }
     }
 
     pub(crate) const fn entry_mask(self) -> u32 {
         let bit_count = match self {
+            #[cfg(feature = "fat12")]
             AllocationTableKind::Fat12 => 12,
+            #[cfg(feature = "fat16")]
             AllocationTableKind::Fat16 => 16,
+            #[cfg(feature = "fat32")]
             AllocationTableKind::Fat32 => 28,
         };
 
         !(!0 << bit_count)
     }
+
+    /// Whether the root directory is stored as a regular directory file instead of a fixed-size
+    /// table following the allocation tables.
+    pub(crate) const fn has_root_directory_file(self) -> bool {
+        match self {
+            #[cfg(feature = "fat12")]
+            AllocationTableKind::Fat12 => false,
+            #[cfg(feature = "fat16")]
+            AllocationTableKind::Fat16 => false,
+            #[cfg(feature = "fat32")]
+            AllocationTableKind::Fat32 => true,
+        }
+    }
+
+    /// The number of bytes which must be read to get the full value of a single entry.
+    pub(crate) const fn entry_read_size(self) -> usize {
+        match self {
+            // FAT12 entries are 1.5 bytes long, so they can span up to 2 bytes
+            #[cfg(feature = "fat12")]
+            AllocationTableKind::Fat12 => 2,
+            #[cfg(feature = "fat16")]
+            AllocationTableKind::Fat16 => 2,
+            #[cfg(feature = "fat32")]
+            AllocationTableKind::Fat32 => 4,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -54,22 +109,34 @@ mod tests {
         #[test]
         fn values_map_correctly() {
             let values = [
+                #[cfg(feature = "fat12")]
                 (1, AllocationTableKind::Fat12),
+                #[cfg(feature = "fat12")]
                 (1024, AllocationTableKind::Fat12),
+                #[cfg(feature = "fat12")]
                 (4000, AllocationTableKind::Fat12),
+                #[cfg(feature = "fat12")]
                 (4084, AllocationTableKind::Fat12),
+                #[cfg(feature = "fat16")]
                 (4085, AllocationTableKind::Fat16),
+                #[cfg(feature = "fat16")]
                 (4096, AllocationTableKind::Fat16),
+                #[cfg(feature = "fat16")]
                 (65000, AllocationTableKind::Fat16),
+                #[cfg(feature = "fat16")]
                 (65524, AllocationTableKind::Fat16),
+                #[cfg(feature = "fat32")]
                 (65525, AllocationTableKind::Fat32),
+                #[cfg(feature = "fat32")]
                 (65536, AllocationTableKind::Fat32),
+                #[cfg(feature = "fat32")]
                 (131_072, AllocationTableKind::Fat32),
+                #[cfg(feature = "fat32")]
                 (1_000_000, AllocationTableKind::Fat32),
             ];
 
             for (value, expected_kind) in values {
-                assert_eq!(AllocationTableKind::new(value), expected_kind);
+                assert_eq!(AllocationTableKind::new(value), Some(expected_kind));
             }
         }
     }
@@ -119,8 +186,11 @@ mod tests {
 
         #[test]
         fn matches_expectations() {
+            #[cfg(feature = "fat12")]
             assert_eq!(AllocationTableKind::Fat12.entry_mask(), 0x0000_0FFF);
+            #[cfg(feature = "fat16")]
             assert_eq!(AllocationTableKind::Fat16.entry_mask(), 0x0000_FFFF);
+            #[cfg(feature = "fat32")]
             assert_eq!(AllocationTableKind::Fat32.entry_mask(), 0x0FFF_FFFF);
         }
     }
@@ -20,6 +20,14 @@ impl PhysicalAllocationTableEntry {
         }
     }
 
+    /// The entry of a free cluster, which every kind of table can hold.
+    pub const fn free(table_kind: AllocationTableKind) -> Self {
+        Self {
+            table_kind,
+            value: 0,
+        }
+    }
+
     pub fn from_bytes(
         table_kind: AllocationTableKind,
         bytes: &[u8; 4],
@@ -27,6 +35,7 @@ impl PhysicalAllocationTableEntry {
     ) -> Self {
         let mut value = read_le_u32(bytes, 0);
 
+        #[cfg(feature = "fat12")]
         if is_nibble_offset {
             assert_eq!(
                 table_kind,
@@ -50,6 +59,7 @@ impl PhysicalAllocationTableEntry {
         let mut mask = self.table_kind.entry_mask();
         let mut entry_value = self.value;
 
+        #[cfg(feature = "fat12")]
         if is_nibble_offset {
             assert_eq!(
                 self.table_kind,
@@ -79,11 +89,17 @@ mod tests {
         #[test]
         fn valid_values_allowed() {
             let values = [
+                #[cfg(feature = "fat12")]
                 (AllocationTableKind::Fat12, 0),
+                #[cfg(feature = "fat12")]
                 (AllocationTableKind::Fat12, 0x0FFF),
+                #[cfg(feature = "fat16")]
                 (AllocationTableKind::Fat16, 0),
+                #[cfg(feature = "fat16")]
                 (AllocationTableKind::Fat16, 0xFFFF),
+                #[cfg(feature = "fat32")]
                 (AllocationTableKind::Fat32, 0),
+                #[cfg(feature = "fat32")]
                 (AllocationTableKind::Fat32, 0x0FFF_FFFF),
             ];
 
@@ -99,8 +115,11 @@ mod tests {
         #[test]
         fn larger_than_mask_returns_none() {
             let values = [
+                #[cfg(feature = "fat12")]
                 (AllocationTableKind::Fat12, 0x1000),
+                #[cfg(feature = "fat16")]
                 (AllocationTableKind::Fat16, 0x1_0000),
+                #[cfg(feature = "fat32")]
                 (AllocationTableKind::Fat32, 0x8000_0000),
             ];
 
@@ -140,6 +159,7 @@ mod tests {
             }
         }
 
+        #[cfg(feature = "fat12")]
         #[test]
         fn fat12_nibble_offset_parses_correctly() {
             let value = 0x123;
@@ -152,12 +172,14 @@ mod tests {
             assert_eq!(result.value, value);
         }
 
+        #[cfg(feature = "fat16")]
         #[test]
         #[should_panic]
         fn fat_16_nibble_offset_panics() {
             PhysicalAllocationTableEntry::from_bytes(AllocationTableKind::Fat16, &[0x00; 4], true);
         }
 
+        #[cfg(feature = "fat32")]
         #[test]
         #[should_panic]
         fn fat_32_nibble_offset_panics() {
@@ -168,6 +190,7 @@ mod tests {
     mod write {
         use super::*;
 
+        #[cfg(feature = "fat12")]
         #[test]
         fn fat12_writes_contained_value_without_disturbing_extra_bits() {
             let source_bytes = [0x12, 0xF3, 0xFF, 0xFF];
@@ -183,6 +206,7 @@ mod tests {
             assert_eq!(output_bytes, [0x12, 0xA3, 0xAA, 0xAA]);
         }
 
+        #[cfg(feature = "fat12")]
         #[test]
         fn fat12_nibble_offset_writes_contained_value_without_disturbing_extra_bits() {
             let source_bytes = [0x12, 0xF3, 0xFF, 0xFF];
@@ -198,6 +222,7 @@ mod tests {
             assert_eq!(output_bytes, [0x2A, 0x31, 0xAA, 0xAA]);
         }
 
+        #[cfg(feature = "fat16")]
         #[test]
         fn fat16_writes_contained_value_without_disturbing_extra_bits() {
             let source_bytes = [0x12, 0x34, 0xFF, 0xFF];
@@ -213,6 +238,7 @@ mod tests {
             assert_eq!(output_bytes, [0x12, 0x34, 0xAA, 0xAA]);
         }
 
+        #[cfg(feature = "fat32")]
         #[test]
         fn fat32_writes_contained_value_without_disturbing_extra_bits() {
             let source_bytes = [0x12, 0x34, 0x56, 0xF8];
@@ -0,0 +1,239 @@
+use crate::AllocationTableKind;
+use crate::utils::{read_le_u32, write_le_u32};
+
+/// The number of bytes at the start of an allocation table which hold its reserved entries.
+pub(crate) const RESERVED_ENTRIES_SIZE: usize = 8;
+
+/// The two entries at the start of an allocation table which don't describe a cluster: the first
+/// echoes the media descriptor of the boot sector and the second holds an end of chain marker,
+/// whose top bits FAT16 and FAT32 volumes use as flags.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct ReservedEntries {
+    kind: AllocationTableKind,
+    media_entry: u32,
+    end_of_chain_entry: u32,
+}
+
+impl ReservedEntries {
+    pub(crate) fn from_bytes(
+        kind: AllocationTableKind,
+        bytes: &[u8; RESERVED_ENTRIES_SIZE],
+    ) -> Self {
+        let entry_mask = kind.entry_mask();
+        let (media_entry, end_of_chain_entry) = match kind {
+            #[cfg(feature = "fat12")]
+            AllocationTableKind::Fat12 => {
+                let value = read_le_u32(bytes, 0);
+
+                (value & entry_mask, (value >> 12) & entry_mask)
+            }
+            #[cfg(feature = "fat16")]
+            AllocationTableKind::Fat16 => {
+                let value = read_le_u32(bytes, 0);
+
+                (value & entry_mask, value >> 16)
+            }
+            #[cfg(feature = "fat32")]
+            AllocationTableKind::Fat32 => (
+                read_le_u32(bytes, 0) & entry_mask,
+                read_le_u32(bytes, 4) & entry_mask,
+            ),
+        };
+
+        Self {
+            kind,
+            media_entry,
+            end_of_chain_entry,
+        }
+    }
+
+    /// Writes the entries over the bytes they were read from, leaving the bits of any following
+    /// entry and the reserved top bits of FAT32 entries untouched.
+    pub(crate) fn write(&self, bytes: &mut [u8; RESERVED_ENTRIES_SIZE]) {
+        match self.kind {
+            #[cfg(feature = "fat12")]
+            AllocationTableKind::Fat12 => {
+                let value = read_le_u32(bytes, 0) & 0xFF00_0000;
+
+                write_le_u32(
+                    bytes,
+                    0,
+                    value | self.media_entry | (self.end_of_chain_entry << 12),
+                );
+            }
+            #[cfg(feature = "fat16")]
+            AllocationTableKind::Fat16 => {
+                write_le_u32(bytes, 0, self.media_entry | (self.end_of_chain_entry << 16));
+            }
+            #[cfg(feature = "fat32")]
+            AllocationTableKind::Fat32 => {
+                let entry_mask = self.kind.entry_mask();
+
+                for (offset, entry) in [(0, self.media_entry), (4, self.end_of_chain_entry)] {
+                    let value = read_le_u32(bytes, offset) & !entry_mask;
+
+                    write_le_u32(bytes, offset, value | entry);
+                }
+            }
+        }
+    }
+
+    /// Whether the first entry holds the media descriptor with all of its other bits set.
+    pub(crate) fn is_media_entry_valid(&self, media_type: u8) -> bool {
+        self.media_entry == self.expected_media_entry(media_type)
+    }
+
+    /// Whether the second entry holds an end of chain marker once its flags are disregarded.
+    pub(crate) fn is_end_of_chain_entry_valid(&self) -> bool {
+        let flag_bits = self
+            .volume_flag_bits()
+            .map_or(0, |(clean_bit, no_errors_bit)| clean_bit | no_errors_bit);
+
+        (self.end_of_chain_entry | flag_bits) >= self.kind.end_of_chain_value()
+    }
+
+    /// Whether the volume was cleanly unmounted and whether no disk errors were encountered
+    /// according to the second entry's flags, `None` for FAT12 volumes which have no such flags.
+    pub(crate) fn volume_flags(&self) -> Option<(bool, bool)> {
+        let (clean_bit, no_errors_bit) = self.volume_flag_bits()?;
+
+        Some((
+            self.end_of_chain_entry & clean_bit != 0,
+            self.end_of_chain_entry & no_errors_bit != 0,
+        ))
+    }
+
+    /// The entries with each invalid one replaced by the value formatters write, which for the
+    /// second entry marks the volume clean and free of errors.  Valid entries are kept as they
+    /// are, including their flags.
+    pub(crate) fn repaired(&self, media_type: u8) -> Self {
+        let mut repaired = *self;
+
+        if !self.is_media_entry_valid(media_type) {
+            repaired.media_entry = self.expected_media_entry(media_type);
+        }
+
+        if !self.is_end_of_chain_entry_valid() {
+            repaired.end_of_chain_entry = self.kind.entry_mask();
+        }
+
+        repaired
+    }
+
+    fn expected_media_entry(&self, media_type: u8) -> u32 {
+        (self.kind.entry_mask() & !0xFF) | u32::from(media_type)
+    }
+
+    /// The bits of the second entry set while the volume is cleanly unmounted and while no disk
+    /// errors were encountered.
+    fn volume_flag_bits(&self) -> Option<(u32, u32)> {
+        match self.kind {
+            #[cfg(feature = "fat12")]
+            AllocationTableKind::Fat12 => None,
+            #[cfg(feature = "fat16")]
+            AllocationTableKind::Fat16 => Some((0x8000, 0x4000)),
+            #[cfg(feature = "fat32")]
+            AllocationTableKind::Fat32 => Some((0x0800_0000, 0x0400_0000)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod from_bytes {
+        use super::*;
+
+        #[test]
+        #[cfg(feature = "fat12")]
+        fn fat12_entries_unpacked() {
+            let entries = ReservedEntries::from_bytes(
+                AllocationTableKind::Fat12,
+                &[0xF8, 0xFF, 0xFF, 0x03, 0, 0, 0, 0],
+            );
+
+            assert!(entries.is_media_entry_valid(0xF8));
+            assert!(entries.is_end_of_chain_entry_valid());
+            assert_eq!(entries.volume_flags(), None);
+        }
+
+        #[test]
+        #[cfg(feature = "fat16")]
+        fn fat16_flags_read() {
+            let entries = ReservedEntries::from_bytes(
+                AllocationTableKind::Fat16,
+                &[0xF0, 0xFF, 0xFF, 0x7F, 0, 0, 0, 0],
+            );
+
+            assert!(entries.is_media_entry_valid(0xF0));
+            assert!(entries.is_end_of_chain_entry_valid());
+            assert_eq!(entries.volume_flags(), Some((false, true)));
+        }
+
+        #[test]
+        #[cfg(feature = "fat32")]
+        fn fat32_reserved_bits_ignored() {
+            let entries = ReservedEntries::from_bytes(
+                AllocationTableKind::Fat32,
+                &[0xF8, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFB],
+            );
+
+            assert!(entries.is_media_entry_valid(0xF8));
+            assert!(entries.is_end_of_chain_entry_valid());
+            assert_eq!(entries.volume_flags(), Some((true, false)));
+        }
+    }
+
+    mod is_end_of_chain_entry_valid {
+        use super::*;
+
+        #[test]
+        #[cfg(feature = "fat32")]
+        fn cluster_numbers_rejected() {
+            let entries = ReservedEntries::from_bytes(
+                AllocationTableKind::Fat32,
+                &[0xF8, 0xFF, 0xFF, 0x0F, 0x02, 0, 0, 0],
+            );
+
+            assert!(!entries.is_end_of_chain_entry_valid());
+        }
+    }
+
+    mod repaired {
+        use super::*;
+
+        #[test]
+        #[cfg(feature = "fat12")]
+        fn fat12_invalid_entries_replaced() {
+            let mut bytes = [0x00, 0x00, 0x00, 0xAB, 0, 0, 0, 0];
+            let entries = ReservedEntries::from_bytes(AllocationTableKind::Fat12, &bytes);
+
+            entries.repaired(0xF0).write(&mut bytes);
+
+            assert_eq!(bytes, [0xF0, 0xFF, 0xFF, 0xAB, 0, 0, 0, 0]);
+        }
+
+        #[test]
+        #[cfg(feature = "fat16")]
+        fn fat16_valid_flags_kept() {
+            let mut bytes = [0x00, 0x00, 0xFF, 0x3F, 0, 0, 0, 0];
+            let entries = ReservedEntries::from_bytes(AllocationTableKind::Fat16, &bytes);
+
+            entries.repaired(0xF8).write(&mut bytes);
+
+            assert_eq!(bytes, [0xF8, 0xFF, 0xFF, 0x3F, 0, 0, 0, 0]);
+        }
+
+        #[test]
+        #[cfg(feature = "fat32")]
+        fn fat32_reserved_bits_preserved() {
+            let mut bytes = [0x00, 0x00, 0x00, 0xF0, 0x02, 0x00, 0x00, 0xF0];
+            let entries = ReservedEntries::from_bytes(AllocationTableKind::Fat32, &bytes);
+
+            entries.repaired(0xF8).write(&mut bytes);
+
+            assert_eq!(bytes, [0xF8, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+        }
+    }
+}
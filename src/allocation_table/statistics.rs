@@ -0,0 +1,142 @@
+use crate::allocation_table::AllocationTableEntry;
+use crate::units::ClusterNumber;
+
+/// Counts of the kinds of entries an allocation table holds for the data region's clusters,
+/// gathered in a single pass over the table by `FileSystem::allocation_table_statistics`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct AllocationTableStatistics {
+    free_cluster_count: u32,
+    allocated_cluster_count: u32,
+    bad_cluster_count: u32,
+    reserved_cluster_count: u32,
+
+    longest_free_run_start: u32,
+    longest_free_run_length: u32,
+    current_free_run_start: u32,
+    current_free_run_length: u32,
+}
+
+impl AllocationTableStatistics {
+    /// The number of clusters not allocated to any file or directory.
+    pub fn free_cluster_count(&self) -> u32 {
+        self.free_cluster_count
+    }
+
+    /// The number of clusters belonging to the chain of a file or directory.
+    pub fn allocated_cluster_count(&self) -> u32 {
+        self.allocated_cluster_count
+    }
+
+    /// The number of clusters marked as bad, which are never allocated.
+    pub fn bad_cluster_count(&self) -> u32 {
+        self.bad_cluster_count
+    }
+
+    /// The number of data region clusters which are never allocated despite not being bad, either
+    /// for holding the reserved entry value or for being reserved through
+    /// `FileSystemBuilder::with_reserved_clusters`.
+    pub fn reserved_cluster_count(&self) -> u32 {
+        self.reserved_cluster_count
+    }
+
+    /// The number of the first cluster of the longest run of consecutive free clusters, `None` if
+    /// no cluster is free.  The earliest run is returned when several are equally long.
+    pub fn longest_free_run_start(&self) -> Option<u32> {
+        (self.longest_free_run_length > 0).then_some(self.longest_free_run_start)
+    }
+
+    /// The number of clusters in the longest run of consecutive free clusters, which bounds the
+    /// largest file that can be written without fragmenting.
+    pub fn longest_free_run_length(&self) -> u32 {
+        self.longest_free_run_length
+    }
+
+    /// Accounts for the entry of the cluster, which must directly follow the previously recorded
+    /// one.
+    pub(crate) fn record(&mut self, cluster_number: ClusterNumber, entry: &AllocationTableEntry) {
+        if *entry != AllocationTableEntry::Free {
+            self.current_free_run_length = 0;
+        }
+
+        match entry {
+            AllocationTableEntry::Free => {
+                self.free_cluster_count += 1;
+
+                if self.current_free_run_length == 0 {
+                    self.current_free_run_start = cluster_number.value();
+                }
+                self.current_free_run_length += 1;
+
+                if self.current_free_run_length > self.longest_free_run_length {
+                    self.longest_free_run_start = self.current_free_run_start;
+                    self.longest_free_run_length = self.current_free_run_length;
+                }
+            }
+            AllocationTableEntry::NextClusterNumber(_) | AllocationTableEntry::EndOfFile => {
+                self.allocated_cluster_count += 1
+            }
+            AllocationTableEntry::BadSector => self.bad_cluster_count += 1,
+            AllocationTableEntry::Reserved => self.reserved_cluster_count += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statistics(entries: &[AllocationTableEntry]) -> AllocationTableStatistics {
+        let mut statistics = AllocationTableStatistics::default();
+
+        for (index, entry) in entries.iter().enumerate() {
+            statistics.record(ClusterNumber::new(index as u32 + 2), entry);
+        }
+
+        statistics
+    }
+
+    mod record {
+        use super::*;
+
+        #[test]
+        fn entries_counted_by_kind() {
+            let statistics = statistics(&[
+                AllocationTableEntry::NextClusterNumber(ClusterNumber::new(3)),
+                AllocationTableEntry::EndOfFile,
+                AllocationTableEntry::Free,
+                AllocationTableEntry::BadSector,
+                AllocationTableEntry::Reserved,
+                AllocationTableEntry::Free,
+            ]);
+
+            assert_eq!(statistics.free_cluster_count(), 2);
+            assert_eq!(statistics.allocated_cluster_count(), 2);
+            assert_eq!(statistics.bad_cluster_count(), 1);
+            assert_eq!(statistics.reserved_cluster_count(), 1);
+        }
+
+        #[test]
+        fn longest_free_run_found() {
+            let statistics = statistics(&[
+                AllocationTableEntry::Free,
+                AllocationTableEntry::EndOfFile,
+                AllocationTableEntry::Free,
+                AllocationTableEntry::Free,
+                AllocationTableEntry::BadSector,
+                AllocationTableEntry::Free,
+                AllocationTableEntry::Free,
+            ]);
+
+            assert_eq!(statistics.longest_free_run_start(), Some(4));
+            assert_eq!(statistics.longest_free_run_length(), 2);
+        }
+
+        #[test]
+        fn no_free_run_without_free_clusters() {
+            let statistics = statistics(&[AllocationTableEntry::EndOfFile]);
+
+            assert_eq!(statistics.longest_free_run_start(), None);
+            assert_eq!(statistics.longest_free_run_length(), 0);
+        }
+    }
+}
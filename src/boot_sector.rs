@@ -1,3 +1,9 @@
 mod bios_parameter_block;
+mod builder;
+mod error;
+mod fs_info;
 
 pub use bios_parameter_block::*;
+pub use builder::*;
+pub use error::*;
+pub use fs_info::*;
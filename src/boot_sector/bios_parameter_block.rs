@@ -4,28 +4,48 @@ pub use error::*;
 
 use crate::allocation_table::AllocationTableKind;
 use crate::directory_entry::DIRECTORY_ENTRY_SIZE;
+use crate::units::{ByteAddress, ClusterNumber, SectorIndex};
 use crate::utils::{read_le_u16, read_le_u32, write_le_u16, write_le_u32};
 use core::fmt::Display;
 
+const FAT_EXTENDED_BOOT_SIGNATURE_OFFSET: usize = 38;
+const FAT32_EXTENDED_BOOT_SIGNATURE_OFFSET: usize = 66;
+/// The number of sectors the boot record of a FAT32 volume spans, including boot code which
+/// doesn't fit into the boot sector.
+const FAT32_BOOT_RECORD_SECTOR_COUNT: u32 = 3;
+
 #[derive(Clone, Debug)]
 pub struct BiosParameterBlock {
     allocation_table_kind: AllocationTableKind,
     active_allocation_table_index: u8,
     allocation_table_mirroring_enabled: bool,
 
+    jump_code: [u8; 3],
+    oem_name: [u8; 8],
     bytes_per_sector: u16,
     sectors_per_cluster: u8,
     reserved_sector_count: u16,
-    fs_info_sector_index: Option<u16>,
+    media_type: u8,
+    sectors_per_track: u16,
+    head_count: u16,
+    hidden_sector_count: u32,
+    fs_info_sector_index: Option<SectorIndex>,
+    backup_boot_sector_index: Option<SectorIndex>,
     allocation_table_count: u8,
     root_directory_entry_count: u16,
-    root_directory_file_cluster_number: Option<u32>,
-    last_cluster_number: u32,
+    root_directory_file_cluster_number: Option<ClusterNumber>,
+    last_cluster_number: ClusterNumber,
     sectors_per_allocation_table: u32,
+    total_sector_count: u32,
+    volume_serial_number: Option<u32>,
 }
 
 impl BiosParameterBlock {
     pub fn from_boot_sector(bytes: &[u8; 512]) -> Result<Self, BiosParameterBlockError> {
+        let jump_code = [bytes[0], bytes[1], bytes[2]];
+        let mut oem_name = [0; 8];
+        oem_name.copy_from_slice(&bytes[3..11]);
+
         let bytes_per_sector = read_le_u16(bytes, 11);
         ensure!(
             matches!(bytes_per_sector, 512 | 1024 | 2048 | 4096),
@@ -52,12 +72,17 @@ impl BiosParameterBlock {
 
         let root_directory_entry_count = read_le_u16(bytes, 17);
         let total_sector_count_16bit = read_le_u16(bytes, 19);
+
+        let media_type = bytes[21];
         ensure!(
-            matches!(bytes[21], 0xF0 | 0xF8..=0xFF),
+            matches!(media_type, 0xF0 | 0xF8..=0xFF),
             BiosParameterBlockError::MediaTypeInvalid
         );
 
         let sectors_per_allocation_table_16bit = read_le_u16(bytes, 22);
+        let sectors_per_track = read_le_u16(bytes, 24);
+        let head_count = read_le_u16(bytes, 26);
+        let hidden_sector_count = read_le_u32(bytes, 28);
 
         let total_sector_count = if total_sector_count_16bit > 0 {
             total_sector_count_16bit as u32
@@ -86,20 +111,35 @@ impl BiosParameterBlock {
         let root_directory_sectors = (root_directory_entry_count as u32
             * DIRECTORY_ENTRY_SIZE as u32)
             .div_ceil(bytes_per_sector as u32);
-        let data_sectors_count = total_sector_count
-            - (reserved_sector_count as u32
-                + (allocation_table_count as u32 * sectors_per_allocation_table)
-                + root_directory_sectors);
+        let system_sectors_count = reserved_sector_count as u64
+            + (allocation_table_count as u64 * sectors_per_allocation_table as u64)
+            + root_directory_sectors as u64;
+        ensure!(
+            system_sectors_count < total_sector_count as u64,
+            BiosParameterBlockError::TotalSectorCountTooSmall
+        );
+
+        let data_sectors_count = total_sector_count - system_sectors_count as u32;
         let data_cluster_count = data_sectors_count / sectors_per_cluster as u32;
 
-        let allocation_table_kind = AllocationTableKind::new(data_cluster_count);
+        // Like common hosts, a FAT32 volume is recognized by its missing 16-bit allocation table
+        // size and root directory table whatever its cluster count, which lets volumes converted
+        // from FAT16 keep their clusters
+        let allocation_table_kind =
+            if sectors_per_allocation_table_16bit == 0 && root_directory_entry_count == 0 {
+                AllocationTableKind::fat32()
+            } else {
+                AllocationTableKind::new(data_cluster_count)
+            }
+            .ok_or(BiosParameterBlockError::AllocationTableKindUnsupported)?;
 
         let mut active_allocation_table_index = 0;
         let mut allocation_table_mirroring_enabled = true;
-        let mut root_directory_file_cluster_number: Option<u32> = None;
-        let mut fs_info_sector_index: Option<u16> = None;
+        let mut root_directory_file_cluster_number: Option<ClusterNumber> = None;
+        let mut fs_info_sector_index: Option<SectorIndex> = None;
+        let mut backup_boot_sector_index: Option<SectorIndex> = None;
 
-        if matches!(allocation_table_kind, AllocationTableKind::Fat32) {
+        if allocation_table_kind.has_root_directory_file() {
             ensure!(
                 root_directory_entry_count == 0,
                 BiosParameterBlockError::RootDirectoryEntryCountInvalid
@@ -123,9 +163,9 @@ impl BiosParameterBlock {
             );
 
             root_directory_file_cluster_number = Some({
-                let value = read_le_u32(bytes, 44);
+                let value = ClusterNumber::new(read_le_u32(bytes, 44));
                 ensure!(
-                    value >= 2,
+                    value.is_data_cluster(),
                     BiosParameterBlockError::RootDirectoryFileClusterNumberInvalid
                 );
 
@@ -139,8 +179,14 @@ impl BiosParameterBlock {
                     BiosParameterBlockError::FsInfoSectorNumberInvalid
                 );
 
-                value
+                SectorIndex::new(value as u32)
             });
+
+            // Zero or all bits set mean the volume keeps no backup of its boot record
+            backup_boot_sector_index = match read_le_u16(bytes, 50) {
+                0 | u16::MAX => None,
+                value => Some(SectorIndex::new(value as u32)),
+            };
         } else {
             ensure!(
                 sectors_per_allocation_table_16bit != 0,
@@ -153,10 +199,22 @@ impl BiosParameterBlock {
             );
         }
 
+        // The serial number follows the extended boot signature, which older formatters leave out
+        let extended_boot_signature_offset = if allocation_table_kind.has_root_directory_file() {
+            FAT32_EXTENDED_BOOT_SIGNATURE_OFFSET
+        } else {
+            FAT_EXTENDED_BOOT_SIGNATURE_OFFSET
+        };
+        let volume_serial_number = matches!(bytes[extended_boot_signature_offset], 0x28 | 0x29)
+            .then(|| read_le_u32(bytes, extended_boot_signature_offset + 1));
+
         let allocation_table_bytes = sectors_per_allocation_table as u64 * bytes_per_sector as u64;
         let allocation_table_entry_count = match allocation_table_kind {
+            #[cfg(feature = "fat12")]
             AllocationTableKind::Fat12 => (allocation_table_bytes * 3) / 2,
+            #[cfg(feature = "fat16")]
             AllocationTableKind::Fat16 => allocation_table_bytes / 2,
+            #[cfg(feature = "fat32")]
             AllocationTableKind::Fat32 => allocation_table_bytes / 4,
         };
 
@@ -168,19 +226,28 @@ impl BiosParameterBlock {
         Ok(Self {
             allocation_table_kind,
 
+            jump_code,
+            oem_name,
             bytes_per_sector,
             sectors_per_cluster,
 
             reserved_sector_count,
+            media_type,
+            sectors_per_track,
+            head_count,
+            hidden_sector_count,
             sectors_per_allocation_table,
             allocation_table_count,
             root_directory_entry_count,
             root_directory_file_cluster_number,
-            last_cluster_number: data_cluster_count + 1,
+            last_cluster_number: ClusterNumber::new(data_cluster_count + 1),
+            total_sector_count,
+            volume_serial_number,
 
             active_allocation_table_index,
             allocation_table_mirroring_enabled,
             fs_info_sector_index,
+            backup_boot_sector_index,
         })
     }
 
@@ -196,45 +263,169 @@ impl BiosParameterBlock {
         self.allocation_table_mirroring_enabled
     }
 
-    pub fn allocation_table_base_address(&self) -> u64 {
-        self.bytes_per_sector as u64 * self.reserved_sector_count as u64
+    pub fn allocation_table_base_address(&self) -> ByteAddress {
+        // The reserved sectors precede the first allocation table
+        SectorIndex::new(self.reserved_sector_count as u32).byte_address(self.bytes_per_sector)
     }
 
     pub fn allocation_table_count(&self) -> u8 {
         self.allocation_table_count
     }
 
+    pub fn bytes_per_allocation_table(&self) -> u64 {
+        self.bytes_per_sector as u64 * self.sectors_per_allocation_table as u64
+    }
+
+    pub fn bytes_per_sector(&self) -> u16 {
+        self.bytes_per_sector
+    }
+
+    pub fn sectors_per_cluster(&self) -> u8 {
+        self.sectors_per_cluster
+    }
+
+    /// The number of sectors preceding the first allocation table, including the boot sector.
+    pub fn reserved_sector_count(&self) -> u16 {
+        self.reserved_sector_count
+    }
+
+    pub fn total_sector_count(&self) -> u32 {
+        self.total_sector_count
+    }
+
     pub fn bytes_per_cluster(&self) -> u32 {
         self.bytes_per_sector as u32 * self.sectors_per_cluster as u32
     }
 
-    pub fn directory_table_base_address(&self) -> u64 {
+    pub fn directory_table_base_address(&self) -> ByteAddress {
         self.allocation_table_base_address()
-            + (self.bytes_per_sector as u64
-                * self.sectors_per_allocation_table as u64
-                * self.allocation_table_count as u64)
+            .offset_by(self.bytes_per_allocation_table() * self.allocation_table_count as u64)
     }
 
     pub fn directory_table_entry_count(&self) -> u16 {
         self.root_directory_entry_count
     }
 
-    pub fn data_region_base_address(&self) -> u64 {
+    pub fn data_region_base_address(&self) -> ByteAddress {
         self.directory_table_base_address()
-            + (self.root_directory_entry_count as u64 * DIRECTORY_ENTRY_SIZE as u64)
+            .offset_by(self.root_directory_entry_count as u64 * DIRECTORY_ENTRY_SIZE as u64)
+    }
+
+    /// Whether the other parameter block places the allocation tables, root directory and clusters
+    /// of the volume at the same addresses.
+    pub fn has_same_layout(&self, other: &BiosParameterBlock) -> bool {
+        self.allocation_table_kind == other.allocation_table_kind
+            && self.bytes_per_sector == other.bytes_per_sector
+            && self.sectors_per_cluster == other.sectors_per_cluster
+            && self.reserved_sector_count == other.reserved_sector_count
+            && self.allocation_table_count == other.allocation_table_count
+            && self.sectors_per_allocation_table == other.sectors_per_allocation_table
+            && self.root_directory_entry_count == other.root_directory_entry_count
+            && self.root_directory_file_cluster_number == other.root_directory_file_cluster_number
+            && self.total_sector_count == other.total_sector_count
+    }
+
+    /// The address following the last cluster of the data region.
+    pub fn data_region_end_address(&self) -> ByteAddress {
+        let data_cluster_count = (self.last_cluster_number.value()
+            - ClusterNumber::FIRST_DATA_CLUSTER.value()
+            + 1) as u64;
+
+        self.data_region_base_address()
+            .offset_by(data_cluster_count * self.bytes_per_cluster() as u64)
     }
 
-    pub fn fs_info_base_address(&self) -> Option<u64> {
-        Some(self.fs_info_sector_index? as u64 * self.bytes_per_sector as u64)
+    /// The address following the volume's last sector.
+    pub fn volume_end_address(&self) -> ByteAddress {
+        SectorIndex::new(self.total_sector_count).byte_address(self.bytes_per_sector)
     }
 
-    pub fn last_cluster_number(&self) -> u32 {
+    /// The address of the first reserved sector after the boot record and, on FAT32 volumes, the
+    /// FS information sector and the backup of the boot record.  The sectors from there up to the
+    /// first allocation table aren't used by the file system.
+    pub fn unused_reserved_sectors_base_address(&self) -> ByteAddress {
+        let mut first_unused_sector_index = 1;
+
+        if self.allocation_table_kind.has_root_directory_file() {
+            first_unused_sector_index = FAT32_BOOT_RECORD_SECTOR_COUNT;
+
+            if let Some(fs_info_sector_index) = self.fs_info_sector_index {
+                first_unused_sector_index =
+                    first_unused_sector_index.max(fs_info_sector_index.value() + 1);
+            }
+
+            if let Some(backup_boot_sector_index) = self.backup_boot_sector_index {
+                first_unused_sector_index = first_unused_sector_index
+                    .max(backup_boot_sector_index.value() + FAT32_BOOT_RECORD_SECTOR_COUNT);
+            }
+        }
+
+        SectorIndex::new(first_unused_sector_index.min(self.reserved_sector_count as u32))
+            .byte_address(self.bytes_per_sector)
+    }
+
+    /// The instruction jumping over the parameter block to the boot code, `EB xx 90` or `E9 xx xx`
+    /// on volumes created by most tools.
+    pub fn jump_code(&self) -> [u8; 3] {
+        self.jump_code
+    }
+
+    /// The name of the system which formatted the volume, padded with spaces.
+    pub fn oem_name(&self) -> [u8; 8] {
+        self.oem_name
+    }
+
+    /// The number of heads of the medium's geometry, only meaningful to media accessed through
+    /// interrupt 13h.
+    pub fn head_count(&self) -> u16 {
+        self.head_count
+    }
+
+    /// The number of sectors preceding the volume on its medium, zero for unpartitioned media.
+    pub fn hidden_sector_count(&self) -> u32 {
+        self.hidden_sector_count
+    }
+
+    /// The media descriptor, also stored in the low byte of the first allocation table entry.
+    pub fn media_type(&self) -> u8 {
+        self.media_type
+    }
+
+    /// The number of sectors per track of the medium's geometry, only meaningful to media
+    /// accessed through interrupt 13h.
+    pub fn sectors_per_track(&self) -> u16 {
+        self.sectors_per_track
+    }
+
+    pub fn fs_info_base_address(&self) -> Option<ByteAddress> {
+        Some(
+            self.fs_info_sector_index?
+                .byte_address(self.bytes_per_sector),
+        )
+    }
+
+    /// The address of the copy of the boot sector FAT32 volumes keep, `None` on other volumes or
+    /// when the boot sector doesn't record one.
+    pub fn backup_boot_sector_base_address(&self) -> Option<ByteAddress> {
+        Some(
+            self.backup_boot_sector_index?
+                .byte_address(self.bytes_per_sector),
+        )
+    }
+
+    pub fn last_cluster_number(&self) -> ClusterNumber {
         self.last_cluster_number
     }
 
-    pub fn root_directory_file_cluster_number(&self) -> Option<u32> {
+    pub fn root_directory_file_cluster_number(&self) -> Option<ClusterNumber> {
         self.root_directory_file_cluster_number
     }
+
+    /// The serial number given to the volume when it was formatted, `None` if the boot sector has
+    /// no extended boot signature.
+    pub fn volume_serial_number(&self) -> Option<u32> {
+        self.volume_serial_number
+    }
 }
 
 #[cfg(test)]
@@ -261,6 +452,7 @@ mod tests {
         mod bytes_per_sector {
             use super::*;
 
+            #[cfg(feature = "fat32")]
             #[test]
             fn valid_values_parse_successfully() {
                 let valid_values = [512, 1024, 2048, 4096];
@@ -302,6 +494,7 @@ mod tests {
         mod sectors_per_cluster {
             use super::*;
 
+            #[cfg(feature = "fat32")]
             #[test]
             fn valid_values_parse_successfully() {
                 let valid_values = [1, 2, 4, 8, 16, 32, 64, 128];
@@ -344,6 +537,7 @@ mod tests {
         mod reserved_sector_count {
             use super::*;
 
+            #[cfg(feature = "fat32")]
             #[test]
             fn valid_values_parse_successfully() {
                 let valid_values = [1, 2, 3, 16, 32, 128, 9999];
@@ -380,6 +574,7 @@ mod tests {
         mod allocation_table_count {
             use super::*;
 
+            #[cfg(feature = "fat32")]
             #[test]
             fn valid_values_parse_successfully() {
                 let valid_values = [1, 2, 3, 16, 32, 128];
@@ -418,6 +613,7 @@ mod tests {
         mod media_type {
             use super::*;
 
+            #[cfg(feature = "fat32")]
             #[test]
             fn valid_values_parse_successfully() {
                 let valid_values = [0xF0, 0xF8, 0xF9, 0xFA, 0xFB, 0xFC, 0xFD, 0xFF];
@@ -472,6 +668,20 @@ mod tests {
 
                 assert_eq!(result, BiosParameterBlockError::TotalSectorCountNotSet);
             }
+
+            #[test]
+            fn smaller_than_system_sectors_returns_err() {
+                let mut config = BiosParameterBlockConfig::fat32();
+                config.total_sector_count_32bit = config.reserved_sector_count as u32;
+
+                let mut bytes = [0x00; 512];
+                config.write(&mut bytes);
+
+                let result = BiosParameterBlock::from_boot_sector(&bytes)
+                    .expect_err("Err should be returned");
+
+                assert_eq!(result, BiosParameterBlockError::TotalSectorCountTooSmall);
+            }
         }
 
         mod sectors_per_allocation_table {
@@ -702,6 +912,7 @@ mod tests {
     mod allocation_table_kind {
         use super::*;
 
+        #[cfg(all(feature = "fat12", feature = "fat16", feature = "fat32"))]
         #[test]
         fn returns_correct_value() {
             let expected_values = [
@@ -731,6 +942,25 @@ mod tests {
                 );
             }
         }
+
+        #[cfg(feature = "fat32")]
+        #[test]
+        fn missing_16bit_sizes_mark_fat32_whatever_cluster_count() {
+            let mut config = BiosParameterBlockConfig::fat32();
+            config.total_sector_count_32bit = 8192;
+            config.sectors_per_allocation_table_32bit = 64;
+
+            let mut bytes = [0x00; 512];
+            config.write(&mut bytes);
+
+            let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+            assert_eq!(
+                bios_parameter_block.allocation_table_kind(),
+                AllocationTableKind::Fat32
+            );
+            assert_eq!(bios_parameter_block.last_cluster_number().value(), 8097);
+        }
     }
 
     mod allocation_table_mirroring_enabled {
@@ -787,7 +1017,10 @@ mod tests {
 
             let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
 
-            assert_eq!(bios_parameter_block.allocation_table_base_address(), 7168);
+            assert_eq!(
+                bios_parameter_block.allocation_table_base_address(),
+                ByteAddress::new(7168)
+            );
         }
     }
 
@@ -808,6 +1041,24 @@ mod tests {
         }
     }
 
+    mod bytes_per_allocation_table {
+        use super::*;
+
+        #[test]
+        fn derived_from_configurations_correctly() {
+            let mut config = BiosParameterBlockConfig::fat16();
+            config.bytes_per_sector = 1024;
+            config.sectors_per_allocation_table_16bit = 1024;
+
+            let mut bytes = [0x00; 512];
+            config.write(&mut bytes);
+
+            let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+            assert_eq!(bios_parameter_block.bytes_per_allocation_table(), 1_048_576);
+        }
+    }
+
     mod bytes_per_cluster {
         use super::*;
 
@@ -831,6 +1082,7 @@ mod tests {
     mod directory_table_base_address {
         use crate::boot_sector::BiosParameterBlock;
         use crate::boot_sector::bios_parameter_block::tests::BiosParameterBlockConfig;
+        use crate::units::ByteAddress;
 
         #[test]
         fn derived_from_configurations_correctly() {
@@ -847,7 +1099,7 @@ mod tests {
 
             assert_eq!(
                 bios_parameter_block.directory_table_base_address(),
-                7_168 + 3_145_728
+                ByteAddress::new(7_168 + 3_145_728)
             );
         }
     }
@@ -888,7 +1140,159 @@ mod tests {
 
             assert_eq!(
                 bios_parameter_block.data_region_base_address(),
-                7_168 + 3_145_728 + 42_784
+                ByteAddress::new(7_168 + 3_145_728 + 42_784)
+            );
+        }
+    }
+
+    mod has_same_layout {
+        use super::*;
+
+        #[test]
+        fn identification_ignored() {
+            let mut bytes = [0x00; 512];
+            BiosParameterBlockConfig::fat32().write(&mut bytes);
+            let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+            let mut config = BiosParameterBlockConfig::fat32();
+            config.oem_name = *b"OTHEROEM";
+            config.write(&mut bytes);
+            write_le_u32(&mut bytes, 67, 0x1234_ABCD);
+
+            assert!(
+                bios_parameter_block
+                    .has_same_layout(&BiosParameterBlock::from_boot_sector(&bytes).unwrap())
+            );
+        }
+
+        #[test]
+        fn geometry_compared() {
+            let mut bytes = [0x00; 512];
+            BiosParameterBlockConfig::fat32().write(&mut bytes);
+            let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+            let mut config = BiosParameterBlockConfig::fat32();
+            config.reserved_sector_count = 16;
+            config.write(&mut bytes);
+
+            assert!(
+                !bios_parameter_block
+                    .has_same_layout(&BiosParameterBlock::from_boot_sector(&bytes).unwrap())
+            );
+        }
+    }
+
+    mod data_region_end_address {
+        use super::*;
+
+        #[test]
+        fn follows_last_cluster() {
+            let mut config = BiosParameterBlockConfig::fat16();
+            config.total_sector_count_16bit = 32770;
+
+            let mut bytes = [0x00; 512];
+            config.write(&mut bytes);
+
+            let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+            assert_eq!(
+                bios_parameter_block.data_region_end_address(),
+                ByteAddress::new(32770 * 512)
+            );
+
+            config.sectors_per_cluster = 4;
+            config.write(&mut bytes);
+
+            let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+            assert_eq!(
+                bios_parameter_block.data_region_end_address(),
+                ByteAddress::new(32769 * 512),
+                "Sectors too few to form a cluster follow the data region"
+            );
+        }
+    }
+
+    mod volume_end_address {
+        use super::*;
+
+        #[test]
+        fn derived_from_total_sector_count() {
+            let mut config = BiosParameterBlockConfig::fat32();
+            config.bytes_per_sector = 1024;
+
+            let mut bytes = [0x00; 512];
+            config.write(&mut bytes);
+
+            let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+            assert_eq!(
+                bios_parameter_block.volume_end_address(),
+                ByteAddress::new(131_072 * 1024)
+            );
+        }
+    }
+
+    mod unused_reserved_sectors_base_address {
+        use super::*;
+
+        #[test]
+        fn non_fat32_follows_boot_sector() {
+            let mut config = BiosParameterBlockConfig::fat16();
+            config.reserved_sector_count = 4;
+
+            let mut bytes = [0x00; 512];
+            config.write(&mut bytes);
+
+            let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+            assert_eq!(
+                bios_parameter_block.unused_reserved_sectors_base_address(),
+                ByteAddress::new(512)
+            );
+        }
+
+        #[test]
+        fn fat32_follows_fs_info_and_backup_boot_record() {
+            let mut config = BiosParameterBlockConfig::fat32();
+            config.fs_info_sector_index = 1;
+
+            let mut bytes = [0x00; 512];
+            config.write(&mut bytes);
+
+            let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+            assert_eq!(
+                bios_parameter_block.unused_reserved_sectors_base_address(),
+                ByteAddress::new(3 * 512),
+                "The boot record spans three sectors"
+            );
+
+            config.backup_boot_sector_index = 6;
+            config.write(&mut bytes);
+
+            let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+            assert_eq!(
+                bios_parameter_block.unused_reserved_sectors_base_address(),
+                ByteAddress::new(9 * 512)
+            );
+        }
+
+        #[test]
+        fn limited_to_reserved_sectors() {
+            let mut config = BiosParameterBlockConfig::fat32();
+            config.reserved_sector_count = 8;
+            config.backup_boot_sector_index = 6;
+
+            let mut bytes = [0x00; 512];
+            config.write(&mut bytes);
+
+            let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+            assert_eq!(
+                bios_parameter_block.unused_reserved_sectors_base_address(),
+                bios_parameter_block.allocation_table_base_address()
             );
         }
     }
@@ -924,7 +1328,112 @@ mod tests {
 
             let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
 
-            assert_eq!(bios_parameter_block.fs_info_base_address(), Some(5120));
+            assert_eq!(
+                bios_parameter_block.fs_info_base_address(),
+                Some(ByteAddress::new(5120))
+            );
+        }
+    }
+
+    mod backup_boot_sector_base_address {
+        use super::*;
+
+        #[test]
+        fn non_fat32_returns_none() {
+            let configs = [
+                BiosParameterBlockConfig::fat12(),
+                BiosParameterBlockConfig::fat16(),
+            ];
+
+            for config in configs {
+                let mut bytes = [0; 512];
+                config.write(&mut bytes);
+
+                let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+                assert_eq!(bios_parameter_block.backup_boot_sector_base_address(), None);
+            }
+        }
+
+        #[test]
+        fn fat32_derived_from_configurations_correctly() {
+            let mut config = BiosParameterBlockConfig::fat32();
+            config.bytes_per_sector = 1024;
+            config.backup_boot_sector_index = 6;
+
+            let mut bytes = [0x00; 512];
+            config.write(&mut bytes);
+
+            let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+            assert_eq!(
+                bios_parameter_block.backup_boot_sector_base_address(),
+                Some(ByteAddress::new(6144))
+            );
+        }
+    }
+
+    mod boot_code {
+        use super::*;
+
+        #[test]
+        fn returns_configured_values() {
+            let mut config = BiosParameterBlockConfig::fat32();
+            config.jump_code = [0xE9, 0x12, 0x34];
+            config.oem_name = *b"MSWIN4.1";
+
+            let mut bytes = [0x00; 512];
+            config.write(&mut bytes);
+
+            let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+            assert_eq!(bios_parameter_block.jump_code(), [0xE9, 0x12, 0x34]);
+            assert_eq!(&bios_parameter_block.oem_name(), b"MSWIN4.1");
+        }
+    }
+
+    mod sizes {
+        use super::*;
+
+        #[test]
+        fn returns_configured_values() {
+            let mut config = BiosParameterBlockConfig::fat32();
+            config.bytes_per_sector = 1024;
+            config.sectors_per_cluster = 2;
+            config.reserved_sector_count = 8;
+
+            let mut bytes = [0x00; 512];
+            config.write(&mut bytes);
+
+            let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+            assert_eq!(bios_parameter_block.bytes_per_sector(), 1024);
+            assert_eq!(bios_parameter_block.sectors_per_cluster(), 2);
+            assert_eq!(bios_parameter_block.reserved_sector_count(), 8);
+            assert_eq!(bios_parameter_block.total_sector_count(), 131_072);
+        }
+    }
+
+    mod geometry {
+        use super::*;
+
+        #[test]
+        fn returns_configured_values() {
+            let mut config = BiosParameterBlockConfig::fat16();
+            config.media_type = 0xF8;
+            config.sectors_per_track = 63;
+            config.head_count = 255;
+            config.hidden_sector_count = 2048;
+
+            let mut bytes = [0x00; 512];
+            config.write(&mut bytes);
+
+            let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+            assert_eq!(bios_parameter_block.media_type(), 0xF8);
+            assert_eq!(bios_parameter_block.sectors_per_track(), 63);
+            assert_eq!(bios_parameter_block.head_count(), 255);
+            assert_eq!(bios_parameter_block.hidden_sector_count(), 2048);
         }
     }
 
@@ -947,7 +1456,10 @@ mod tests {
 
             let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
 
-            assert_eq!(bios_parameter_block.last_cluster_number(), 29_933);
+            assert_eq!(
+                bios_parameter_block.last_cluster_number(),
+                ClusterNumber::new(29_933)
+            );
         }
 
         #[test]
@@ -965,7 +1477,10 @@ mod tests {
 
             let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
 
-            assert_eq!(bios_parameter_block.last_cluster_number(), 129_533);
+            assert_eq!(
+                bios_parameter_block.last_cluster_number(),
+                ClusterNumber::new(129_533)
+            );
         }
     }
 
@@ -1004,12 +1519,52 @@ mod tests {
 
             assert_eq!(
                 bios_parameter_block.root_directory_file_cluster_number(),
-                Some(1337)
+                Some(ClusterNumber::new(1337))
             );
         }
     }
 
+    mod volume_serial_number {
+        use super::*;
+
+        #[test]
+        fn extended_boot_signature_present_returns_serial() {
+            let configs = [
+                (BiosParameterBlockConfig::fat12(), 38),
+                (BiosParameterBlockConfig::fat16(), 38),
+                (BiosParameterBlockConfig::fat32(), 66),
+            ];
+
+            for (config, signature_offset) in configs {
+                let mut bytes = [0; 512];
+                config.write(&mut bytes);
+                bytes[signature_offset] = 0x29;
+                write_le_u32(&mut bytes, signature_offset + 1, 0x1234_ABCD);
+
+                let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+                assert_eq!(
+                    bios_parameter_block.volume_serial_number(),
+                    Some(0x1234_ABCD)
+                );
+            }
+        }
+
+        #[test]
+        fn extended_boot_signature_missing_returns_none() {
+            let mut bytes = [0; 512];
+            BiosParameterBlockConfig::fat16().write(&mut bytes);
+            write_le_u32(&mut bytes, 39, 0x1234_ABCD);
+
+            let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+            assert_eq!(bios_parameter_block.volume_serial_number(), None);
+        }
+    }
+
     struct BiosParameterBlockConfig {
+        jump_code: [u8; 3],
+        oem_name: [u8; 8],
         bytes_per_sector: u16,
         sectors_per_cluster: u8,
         reserved_sector_count: u16,
@@ -1018,6 +1573,9 @@ mod tests {
         total_sector_count_16bit: u16,
         media_type: u8,
         sectors_per_allocation_table_16bit: u16,
+        sectors_per_track: u16,
+        head_count: u16,
+        hidden_sector_count: u32,
         total_sector_count_32bit: u32,
         sectors_per_allocation_table_32bit: u32,
         ext_flags: u16,
@@ -1025,11 +1583,14 @@ mod tests {
         filesystem_version_major: u8,
         root_directory_file_cluster_number: u32,
         fs_info_sector_index: u16,
+        backup_boot_sector_index: u16,
     }
 
     impl BiosParameterBlockConfig {
         fn fat12() -> BiosParameterBlockConfig {
             BiosParameterBlockConfig {
+                jump_code: [0xEB, 0x3C, 0x90],
+                oem_name: *b"MSWIN4.1",
                 bytes_per_sector: 512,
                 sectors_per_cluster: 4,
                 reserved_sector_count: 1,
@@ -1038,6 +1599,9 @@ mod tests {
                 total_sector_count_16bit: 8192,
                 media_type: 0xF0,
                 sectors_per_allocation_table_16bit: 3,
+                sectors_per_track: 0,
+                head_count: 0,
+                hidden_sector_count: 0,
                 total_sector_count_32bit: 0,
                 sectors_per_allocation_table_32bit: 0,
                 ext_flags: 0,
@@ -1045,11 +1609,14 @@ mod tests {
                 filesystem_version_major: 0,
                 root_directory_file_cluster_number: 0,
                 fs_info_sector_index: 0,
+                backup_boot_sector_index: 0,
             }
         }
 
         fn fat16() -> BiosParameterBlockConfig {
             BiosParameterBlockConfig {
+                jump_code: [0xEB, 0x3C, 0x90],
+                oem_name: *b"MSWIN4.1",
                 bytes_per_sector: 512,
                 sectors_per_cluster: 1,
                 reserved_sector_count: 1,
@@ -1058,6 +1625,9 @@ mod tests {
                 total_sector_count_16bit: 32768,
                 media_type: 0xF0,
                 sectors_per_allocation_table_16bit: 128,
+                sectors_per_track: 0,
+                head_count: 0,
+                hidden_sector_count: 0,
                 total_sector_count_32bit: 0,
                 sectors_per_allocation_table_32bit: 0,
                 ext_flags: 0,
@@ -1065,11 +1635,14 @@ mod tests {
                 filesystem_version_major: 0,
                 root_directory_file_cluster_number: 0,
                 fs_info_sector_index: 0,
+                backup_boot_sector_index: 0,
             }
         }
 
         fn fat32() -> BiosParameterBlockConfig {
             BiosParameterBlockConfig {
+                jump_code: [0xEB, 0x3C, 0x90],
+                oem_name: *b"MSWIN4.1",
                 bytes_per_sector: 512,
                 sectors_per_cluster: 1,
                 reserved_sector_count: 32,
@@ -1078,6 +1651,9 @@ mod tests {
                 total_sector_count_16bit: 0,
                 media_type: 0xF0,
                 sectors_per_allocation_table_16bit: 0,
+                sectors_per_track: 0,
+                head_count: 0,
+                hidden_sector_count: 0,
                 total_sector_count_32bit: 131_072,
                 sectors_per_allocation_table_32bit: 1024,
                 ext_flags: 0,
@@ -1085,10 +1661,13 @@ mod tests {
                 filesystem_version_major: 0,
                 root_directory_file_cluster_number: 2,
                 fs_info_sector_index: 6,
+                backup_boot_sector_index: 0,
             }
         }
 
         fn write(&self, bytes: &mut [u8; 512]) {
+            bytes[0..3].copy_from_slice(&self.jump_code);
+            bytes[3..11].copy_from_slice(&self.oem_name);
             write_le_u16(bytes, 11, self.bytes_per_sector);
             bytes[13] = self.sectors_per_cluster;
             write_le_u16(bytes, 14, self.reserved_sector_count);
@@ -1097,6 +1676,9 @@ mod tests {
             write_le_u16(bytes, 19, self.total_sector_count_16bit);
             bytes[21] = self.media_type;
             write_le_u16(bytes, 22, self.sectors_per_allocation_table_16bit);
+            write_le_u16(bytes, 24, self.sectors_per_track);
+            write_le_u16(bytes, 26, self.head_count);
+            write_le_u32(bytes, 28, self.hidden_sector_count);
             write_le_u32(bytes, 32, self.total_sector_count_32bit);
 
             write_le_u32(bytes, 36, self.sectors_per_allocation_table_32bit);
@@ -1105,6 +1687,7 @@ mod tests {
             bytes[43] = self.filesystem_version_major;
             write_le_u32(bytes, 44, self.root_directory_file_cluster_number);
             write_le_u16(bytes, 48, self.fs_info_sector_index);
+            write_le_u16(bytes, 50, self.backup_boot_sector_index);
         }
     }
 }
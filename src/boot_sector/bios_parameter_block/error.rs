@@ -5,6 +5,7 @@ use core::fmt::{Display, Formatter};
 #[cfg_attr(test, derive(strum::EnumIter))]
 pub enum BiosParameterBlockError {
     AllocationTableCountInvalid,
+    AllocationTableKindUnsupported,
     AllocationTableTooSmall,
     BytesPerSectorInvalid,
     FilesystemVersionUnsupported,
@@ -18,6 +19,7 @@ pub enum BiosParameterBlockError {
     SectorsPerAllocationTableNotSet,
     TotalSectorCount16BitInvalid,
     TotalSectorCountNotSet,
+    TotalSectorCountTooSmall,
 }
 
 impl Error for BiosParameterBlockError {}
@@ -28,6 +30,12 @@ impl Display for BiosParameterBlockError {
             BiosParameterBlockError::AllocationTableCountInvalid => {
                 write!(f, "BPB_NumFATs must not be zero")
             }
+            BiosParameterBlockError::AllocationTableKindUnsupported => {
+                write!(
+                    f,
+                    "The volume's FAT type was determined from its cluster count, but support for it isn't enabled"
+                )
+            }
             BiosParameterBlockError::AllocationTableTooSmall => {
                 write!(
                     f,
@@ -76,6 +84,12 @@ impl Display for BiosParameterBlockError {
             BiosParameterBlockError::TotalSectorCountNotSet => {
                 write!(f, "Either BPB_TotSec16 or BPB_TotSec32 must be non-zero")
             }
+            BiosParameterBlockError::TotalSectorCountTooSmall => {
+                write!(
+                    f,
+                    "BPB_TotSec16 or BPB_TotSec32 must be large enough to contain the reserved, allocation table, and root directory sectors"
+                )
+            }
         }
     }
 }
@@ -0,0 +1,366 @@
+use crate::AllocationTableKind;
+use crate::boot_sector::{BiosParameterBlock, BootSectorError};
+use crate::directory_entry::{DIRECTORY_ENTRY_SIZE, SHORT_NAME_CHARACTER_COUNT};
+use crate::utils::{write_le_u16, write_le_u32};
+use bon::Builder;
+
+const EXTENDED_BOOT_SIGNATURE: u8 = 0x29;
+const FAT32_FS_INFO_SECTOR_INDEX: u16 = 1;
+const FAT32_BACKUP_BOOT_SECTOR_INDEX: u16 = 6;
+
+/// The parameters of a volume's boot sector, turned into its bytes by `to_bytes` without
+/// formatting the rest of the volume, for tools which lay out volumes themselves.
+///
+/// Parameters left unset take the values common formatters use: two allocation tables, a root
+/// directory of 512 entries on FAT12 and FAT16 volumes, and 32 reserved sectors on FAT32 volumes,
+/// which hold the FS information sector in sector 1 and the backup of the boot record in sector 6.
+/// The size of the allocation tables is derived from the other parameters unless given.
+///
+/// Only the boot sector is produced, the allocation tables, root directory and on FAT32 volumes
+/// the FS information sector and backup boot record still need to be written for the volume to
+/// mount.
+#[derive(Builder, Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BootSector {
+    allocation_table_kind: AllocationTableKind,
+    total_sector_count: u32,
+    sectors_per_cluster: u8,
+
+    #[builder(default = 512)]
+    bytes_per_sector: u16,
+    reserved_sector_count: Option<u16>,
+    #[builder(default = 2)]
+    allocation_table_count: u8,
+    root_directory_entry_count: Option<u16>,
+    /// The number of sectors of each allocation table, for laying out volumes whose tables are
+    /// larger than needed or must end at a given sector.
+    sectors_per_allocation_table: Option<u32>,
+    /// The first cluster of the root directory on FAT32 volumes, ignored on other volumes.
+    #[builder(default = 2)]
+    root_directory_cluster_number: u32,
+
+    /// The media descriptor, `0xF8` for fixed media by default.
+    #[builder(default = 0xF8)]
+    media_type: u8,
+    #[builder(default)]
+    hidden_sector_count: u32,
+    #[builder(default)]
+    sectors_per_track: u16,
+    #[builder(default)]
+    head_count: u16,
+
+    #[builder(default)]
+    volume_serial_number: u32,
+    /// The space padded label, which hosts show in place of the root directory's volume label
+    /// entry when it has none.
+    #[builder(default = *b"NO NAME    ")]
+    volume_label: [u8; SHORT_NAME_CHARACTER_COUNT],
+    #[builder(default = *b"MSWIN4.1")]
+    oem_name: [u8; 8],
+}
+
+impl BootSector {
+    /// The number of reserved sectors preceding the first allocation table.
+    pub fn reserved_sector_count(&self) -> u16 {
+        self.reserved_sector_count.unwrap_or(
+            if self.allocation_table_kind.has_root_directory_file() {
+                32
+            } else {
+                1
+            },
+        )
+    }
+
+    /// The number of entries of the root directory table, always zero on FAT32 volumes whose root
+    /// directory is a cluster chain.
+    pub fn root_directory_entry_count(&self) -> u16 {
+        self.root_directory_entry_count.unwrap_or(
+            if self.allocation_table_kind.has_root_directory_file() {
+                0
+            } else {
+                512
+            },
+        )
+    }
+
+    /// The number of sectors of each allocation table, either as given or as needed to hold an
+    /// entry for every cluster left after the tables themselves.
+    pub fn sectors_per_allocation_table(&self) -> Result<u32, BootSectorError> {
+        if let Some(sectors_per_allocation_table) = self.sectors_per_allocation_table {
+            return Ok(sectors_per_allocation_table);
+        }
+
+        let entry_bit_count: u64 = match self.allocation_table_kind {
+            #[cfg(feature = "fat12")]
+            AllocationTableKind::Fat12 => 12,
+            #[cfg(feature = "fat16")]
+            AllocationTableKind::Fat16 => 16,
+            #[cfg(feature = "fat32")]
+            AllocationTableKind::Fat32 => 32,
+        };
+        let bytes_per_sector = u64::from(self.bytes_per_sector);
+        let root_directory_sectors = (u64::from(self.root_directory_entry_count())
+            * DIRECTORY_ENTRY_SIZE as u64)
+            .div_ceil(bytes_per_sector);
+        let fixed_sectors = u64::from(self.reserved_sector_count()) + root_directory_sectors;
+
+        // Larger tables leave fewer clusters to describe, so growing the tables until they fit
+        // settles after a few rounds
+        let mut sectors_per_allocation_table = 1;
+
+        loop {
+            let data_sectors = u64::from(self.total_sector_count)
+                .checked_sub(
+                    fixed_sectors
+                        + u64::from(self.allocation_table_count) * sectors_per_allocation_table,
+                )
+                .filter(|&data_sectors| data_sectors > 0)
+                .ok_or(BootSectorError::TotalSectorCountTooSmall)?;
+            let cluster_count = data_sectors / u64::from(self.sectors_per_cluster.max(1));
+            let required_sectors =
+                ((cluster_count + 2) * entry_bit_count).div_ceil(8 * bytes_per_sector);
+
+            if required_sectors <= sectors_per_allocation_table {
+                return Ok(sectors_per_allocation_table as u32);
+            }
+
+            sectors_per_allocation_table = required_sectors;
+        }
+    }
+
+    /// Lays out the boot sector, failing if the parameters don't describe a volume of the
+    /// requested kind which this crate could mount.
+    pub fn to_bytes(&self) -> Result<[u8; 512], BootSectorError> {
+        let sectors_per_allocation_table = self.sectors_per_allocation_table()?;
+        let is_fat32 = self.allocation_table_kind.has_root_directory_file();
+        let mut bytes = [0; 512];
+
+        bytes[0..3].copy_from_slice(if is_fat32 {
+            &[0xEB, 0x58, 0x90]
+        } else {
+            &[0xEB, 0x3C, 0x90]
+        });
+        bytes[3..11].copy_from_slice(&self.oem_name);
+        write_le_u16(&mut bytes, 11, self.bytes_per_sector);
+        bytes[13] = self.sectors_per_cluster;
+        write_le_u16(&mut bytes, 14, self.reserved_sector_count());
+        bytes[16] = self.allocation_table_count;
+        write_le_u16(&mut bytes, 17, self.root_directory_entry_count());
+        bytes[21] = self.media_type;
+        write_le_u16(&mut bytes, 24, self.sectors_per_track);
+        write_le_u16(&mut bytes, 26, self.head_count);
+        write_le_u32(&mut bytes, 28, self.hidden_sector_count);
+
+        match u16::try_from(self.total_sector_count) {
+            Ok(total_sector_count) if !is_fat32 => write_le_u16(&mut bytes, 19, total_sector_count),
+            _ => write_le_u32(&mut bytes, 32, self.total_sector_count),
+        }
+
+        let extended_boot_record_offset = if is_fat32 {
+            write_le_u32(&mut bytes, 36, sectors_per_allocation_table);
+            write_le_u32(&mut bytes, 44, self.root_directory_cluster_number);
+            write_le_u16(&mut bytes, 48, FAT32_FS_INFO_SECTOR_INDEX);
+
+            // The backup boot record spans three sectors, volumes without room for it keep none
+            if self.reserved_sector_count() >= FAT32_BACKUP_BOOT_SECTOR_INDEX + 3 {
+                write_le_u16(&mut bytes, 50, FAT32_BACKUP_BOOT_SECTOR_INDEX);
+            }
+
+            64
+        } else {
+            let sectors_per_allocation_table = u16::try_from(sectors_per_allocation_table)
+                .map_err(|_| BootSectorError::AllocationTableKindMismatch)?;
+            write_le_u16(&mut bytes, 22, sectors_per_allocation_table);
+
+            36
+        };
+
+        bytes[extended_boot_record_offset] = if self.media_type == 0xF8 { 0x80 } else { 0x00 };
+        bytes[extended_boot_record_offset + 2] = EXTENDED_BOOT_SIGNATURE;
+        write_le_u32(
+            &mut bytes,
+            extended_boot_record_offset + 3,
+            self.volume_serial_number,
+        );
+        bytes[extended_boot_record_offset + 7..extended_boot_record_offset + 18]
+            .copy_from_slice(&self.volume_label);
+        bytes[extended_boot_record_offset + 18..extended_boot_record_offset + 26]
+            .copy_from_slice(self.file_system_type());
+
+        bytes[510] = 0x55;
+        bytes[511] = 0xAA;
+
+        let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes)?;
+        ensure!(
+            bios_parameter_block.allocation_table_kind() == self.allocation_table_kind,
+            BootSectorError::AllocationTableKindMismatch
+        );
+
+        Ok(bytes)
+    }
+
+    /// The informational name of the file system type, which hosts don't rely on.
+    fn file_system_type(&self) -> &'static [u8; 8] {
+        match self.allocation_table_kind {
+            #[cfg(feature = "fat12")]
+            AllocationTableKind::Fat12 => b"FAT12   ",
+            #[cfg(feature = "fat16")]
+            AllocationTableKind::Fat16 => b"FAT16   ",
+            #[cfg(feature = "fat32")]
+            AllocationTableKind::Fat32 => b"FAT32   ",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod to_bytes {
+        use super::*;
+
+        #[test]
+        #[cfg(feature = "fat12")]
+        fn fat12_volume_described() {
+            let boot_sector = BootSector::builder()
+                .allocation_table_kind(AllocationTableKind::Fat12)
+                .total_sector_count(2880)
+                .sectors_per_cluster(1)
+                .media_type(0xF0)
+                .root_directory_entry_count(224)
+                .build();
+
+            let bytes = boot_sector.to_bytes().unwrap();
+            let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+            assert_eq!(boot_sector.sectors_per_allocation_table(), Ok(9));
+            assert_eq!(
+                bios_parameter_block.allocation_table_kind(),
+                AllocationTableKind::Fat12
+            );
+            assert_eq!(bios_parameter_block.media_type(), 0xF0);
+            assert_eq!(bios_parameter_block.directory_table_entry_count(), 224);
+            assert_eq!(
+                bios_parameter_block.volume_end_address().value(),
+                2880 * 512
+            );
+        }
+
+        #[test]
+        #[cfg(feature = "fat16")]
+        fn fat16_identification_written() {
+            let bytes = BootSector::builder()
+                .allocation_table_kind(AllocationTableKind::Fat16)
+                .total_sector_count(65536)
+                .sectors_per_cluster(4)
+                .volume_serial_number(0x1234_ABCD)
+                .volume_label(*b"DATA       ")
+                .oem_name(*b"EMBEDDED")
+                .build()
+                .to_bytes()
+                .unwrap();
+            let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+            assert_eq!(bios_parameter_block.oem_name(), *b"EMBEDDED");
+            assert_eq!(
+                bios_parameter_block.volume_serial_number(),
+                Some(0x1234_ABCD)
+            );
+            assert_eq!(&bytes[43..54], b"DATA       ");
+            assert_eq!(&bytes[54..62], b"FAT16   ");
+            assert_eq!(bios_parameter_block.allocation_table_count(), 2);
+        }
+
+        #[test]
+        #[cfg(feature = "fat32")]
+        fn fat32_reserved_sectors_laid_out() {
+            let bytes = BootSector::builder()
+                .allocation_table_kind(AllocationTableKind::Fat32)
+                .total_sector_count(131_072)
+                .sectors_per_cluster(1)
+                .build()
+                .to_bytes()
+                .unwrap();
+            let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+            assert_eq!(
+                bios_parameter_block.allocation_table_kind(),
+                AllocationTableKind::Fat32
+            );
+            assert_eq!(
+                bios_parameter_block.allocation_table_base_address().value(),
+                32 * 512
+            );
+            assert_eq!(
+                bios_parameter_block.fs_info_base_address().unwrap().value(),
+                512
+            );
+            assert_eq!(
+                bios_parameter_block
+                    .backup_boot_sector_base_address()
+                    .unwrap()
+                    .value(),
+                6 * 512
+            );
+            assert_eq!(&bytes[82..90], b"FAT32   ");
+        }
+
+        #[test]
+        #[cfg(feature = "fat32")]
+        fn fat32_given_layout_kept() {
+            let bytes = BootSector::builder()
+                .allocation_table_kind(AllocationTableKind::Fat32)
+                .total_sector_count(6144)
+                .sectors_per_cluster(1)
+                .reserved_sector_count(33)
+                .allocation_table_count(1)
+                .sectors_per_allocation_table(48)
+                .root_directory_cluster_number(9)
+                .build()
+                .to_bytes()
+                .unwrap();
+            let bios_parameter_block = BiosParameterBlock::from_boot_sector(&bytes).unwrap();
+
+            assert_eq!(
+                bios_parameter_block.allocation_table_kind(),
+                AllocationTableKind::Fat32
+            );
+            assert_eq!(
+                bios_parameter_block.data_region_base_address().value(),
+                81 * 512
+            );
+            assert_eq!(
+                bios_parameter_block
+                    .root_directory_file_cluster_number()
+                    .unwrap()
+                    .value(),
+                9
+            );
+        }
+
+        #[test]
+        #[cfg(all(feature = "fat12", feature = "fat16"))]
+        fn kind_mismatch_returns_err() {
+            let result = BootSector::builder()
+                .allocation_table_kind(AllocationTableKind::Fat16)
+                .total_sector_count(2880)
+                .sectors_per_cluster(1)
+                .build()
+                .to_bytes();
+
+            assert_eq!(result, Err(BootSectorError::AllocationTableKindMismatch));
+        }
+
+        #[test]
+        #[cfg(feature = "fat16")]
+        fn too_few_sectors_returns_err() {
+            let result = BootSector::builder()
+                .allocation_table_kind(AllocationTableKind::Fat16)
+                .total_sector_count(16)
+                .sectors_per_cluster(1)
+                .build()
+                .to_bytes();
+
+            assert_eq!(result, Err(BootSectorError::TotalSectorCountTooSmall));
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::BiosParameterBlockError;
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BootSectorError {
+    AllocationTableKindMismatch,
+    InvalidBiosParameterBlock(BiosParameterBlockError),
+    TotalSectorCountTooSmall,
+}
+
+impl Error for BootSectorError {}
+
+impl Display for BootSectorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BootSectorError::AllocationTableKindMismatch => write!(
+                f,
+                "the volume's cluster count doesn't fit the requested kind of allocation table"
+            ),
+            BootSectorError::InvalidBiosParameterBlock(e) => {
+                write!(f, "the resulting bios parameter block is invalid: {}", e)
+            }
+            BootSectorError::TotalSectorCountTooSmall => write!(
+                f,
+                "the volume has no sectors left for data after its reserved sectors, allocation tables and root directory"
+            ),
+        }
+    }
+}
+
+impl From<BiosParameterBlockError> for BootSectorError {
+    fn from(value: BiosParameterBlockError) -> Self {
+        BootSectorError::InvalidBiosParameterBlock(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn produces_non_empty_value() {
+            let values = [
+                BootSectorError::AllocationTableKindMismatch,
+                BootSectorError::InvalidBiosParameterBlock(
+                    BiosParameterBlockError::MediaTypeInvalid,
+                ),
+                BootSectorError::TotalSectorCountTooSmall,
+            ];
+
+            for value in values {
+                assert!(
+                    !value.to_string().is_empty(),
+                    "Display implementation should be non-empty"
+                );
+            }
+        }
+    }
+}
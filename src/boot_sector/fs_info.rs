@@ -0,0 +1,138 @@
+mod error;
+
+pub use error::*;
+
+use crate::utils::{read_le_u32, write_le_u32};
+
+pub(crate) const FS_INFO_LEAD_SIGNATURE: u32 = 0x4161_5252;
+pub(crate) const FS_INFO_STRUCTURE_SIGNATURE: u32 = 0x6141_7272;
+pub(crate) const FS_INFO_TRAIL_SIGNATURE: u32 = 0xAA55_0000;
+/// The value of the FS information sector's free count and next free hint when it isn't known.
+pub(crate) const FS_INFO_UNKNOWN_VALUE: u32 = u32::MAX;
+
+const FREE_CLUSTER_COUNT_OFFSET: usize = 488;
+const NEXT_FREE_CLUSTER_OFFSET: usize = 492;
+
+/// The FS information sector of a FAT32 volume, which records the number of free clusters and
+/// where to start looking for one so they needn't be found by scanning the allocation table.
+///
+/// Both values are only hints, hosts don't always keep them up to date and either may be unknown.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FsInfo {
+    free_cluster_count: Option<u32>,
+    next_free_cluster_hint: Option<u32>,
+}
+
+impl FsInfo {
+    pub fn from_sector(bytes: &[u8; 512]) -> Result<Self, FsInfoError> {
+        let lead_signature = read_le_u32(bytes, 0);
+        ensure!(
+            lead_signature == FS_INFO_LEAD_SIGNATURE,
+            FsInfoError::LeadSignatureInvalid(lead_signature)
+        );
+
+        let structure_signature = read_le_u32(bytes, 484);
+        ensure!(
+            structure_signature == FS_INFO_STRUCTURE_SIGNATURE,
+            FsInfoError::StructureSignatureInvalid(structure_signature)
+        );
+
+        let trail_signature = read_le_u32(bytes, 508);
+        ensure!(
+            trail_signature == FS_INFO_TRAIL_SIGNATURE,
+            FsInfoError::TrailSignatureInvalid(trail_signature)
+        );
+
+        Ok(Self {
+            free_cluster_count: known_value(read_le_u32(bytes, FREE_CLUSTER_COUNT_OFFSET)),
+            next_free_cluster_hint: known_value(read_le_u32(bytes, NEXT_FREE_CLUSTER_OFFSET)),
+        })
+    }
+
+    /// The last known number of free clusters on the volume, `None` if it isn't known.
+    pub fn free_cluster_count(&self) -> Option<u32> {
+        self.free_cluster_count
+    }
+
+    /// The cluster to start looking for a free cluster from, `None` if it isn't known.
+    pub fn next_free_cluster_hint(&self) -> Option<u32> {
+        self.next_free_cluster_hint
+    }
+}
+
+fn known_value(value: u32) -> Option<u32> {
+    (value != FS_INFO_UNKNOWN_VALUE).then_some(value)
+}
+
+/// Lays out the FS information sector of a FAT32 volume with the given number of free clusters,
+/// leaving the hint for the next free cluster unknown.
+pub(crate) fn fs_info_sector(free_cluster_count: u32) -> [u8; 512] {
+    let mut bytes = [0; 512];
+
+    write_le_u32(&mut bytes, 0, FS_INFO_LEAD_SIGNATURE);
+    write_le_u32(&mut bytes, 484, FS_INFO_STRUCTURE_SIGNATURE);
+    write_le_u32(&mut bytes, FREE_CLUSTER_COUNT_OFFSET, free_cluster_count);
+    write_le_u32(&mut bytes, NEXT_FREE_CLUSTER_OFFSET, FS_INFO_UNKNOWN_VALUE);
+    write_le_u32(&mut bytes, 508, FS_INFO_TRAIL_SIGNATURE);
+
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod from_sector {
+        use super::*;
+
+        #[test]
+        fn known_values_parsed() {
+            let mut bytes = fs_info_sector(100);
+            write_le_u32(&mut bytes, NEXT_FREE_CLUSTER_OFFSET, 5);
+
+            let fs_info = FsInfo::from_sector(&bytes).expect("Ok should be returned");
+
+            assert_eq!(fs_info.free_cluster_count(), Some(100));
+            assert_eq!(fs_info.next_free_cluster_hint(), Some(5));
+        }
+
+        #[test]
+        fn unknown_values_parsed_as_none() {
+            let fs_info = FsInfo::from_sector(&fs_info_sector(FS_INFO_UNKNOWN_VALUE))
+                .expect("Ok should be returned");
+
+            assert_eq!(fs_info.free_cluster_count(), None);
+            assert_eq!(fs_info.next_free_cluster_hint(), None);
+        }
+
+        #[test]
+        fn lead_signature_invalid_returns_err() {
+            let mut bytes = fs_info_sector(100);
+            write_le_u32(&mut bytes, 0, 0);
+
+            let result = FsInfo::from_sector(&bytes).expect_err("Err should be returned");
+
+            assert_eq!(result, FsInfoError::LeadSignatureInvalid(0));
+        }
+
+        #[test]
+        fn structure_signature_invalid_returns_err() {
+            let mut bytes = fs_info_sector(100);
+            write_le_u32(&mut bytes, 484, 0);
+
+            let result = FsInfo::from_sector(&bytes).expect_err("Err should be returned");
+
+            assert_eq!(result, FsInfoError::StructureSignatureInvalid(0));
+        }
+
+        #[test]
+        fn trail_signature_invalid_returns_err() {
+            let mut bytes = fs_info_sector(100);
+            write_le_u32(&mut bytes, 508, 0);
+
+            let result = FsInfo::from_sector(&bytes).expect_err("Err should be returned");
+
+            assert_eq!(result, FsInfoError::TrailSignatureInvalid(0));
+        }
+    }
+}
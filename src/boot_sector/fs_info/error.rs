@@ -0,0 +1,56 @@
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FsInfoError {
+    LeadSignatureInvalid(u32),
+    StructureSignatureInvalid(u32),
+    TrailSignatureInvalid(u32),
+}
+
+impl Error for FsInfoError {}
+
+impl Display for FsInfoError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FsInfoError::LeadSignatureInvalid(signature) => write!(
+                f,
+                "the FS information sector's lead signature is invalid: {signature:#010X}"
+            ),
+            FsInfoError::StructureSignatureInvalid(signature) => write!(
+                f,
+                "the FS information sector's structure signature is invalid: {signature:#010X}"
+            ),
+            FsInfoError::TrailSignatureInvalid(signature) => write!(
+                f,
+                "the FS information sector's trail signature is invalid: {signature:#010X}"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn produces_non_empty_value() {
+            let values = [
+                FsInfoError::LeadSignatureInvalid(0),
+                FsInfoError::StructureSignatureInvalid(0),
+                FsInfoError::TrailSignatureInvalid(0),
+            ];
+
+            for value in values {
+                assert!(
+                    !value.to_string().is_empty(),
+                    "Display implementation should be non-empty"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,275 @@
+mod error;
+
+pub use error::*;
+
+use crate::Lines;
+use core::error::Error;
+
+#[cfg(feature = "sync")]
+use embedded_io::Read;
+
+#[cfg(feature = "async")]
+use embedded_io_async::Read as AsyncRead;
+
+/// An item of a key-value config file read by `ConfigReader`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConfigItem<'a> {
+    /// A `[name]` header, the entries following it belong to the named section.
+    Section(&'a str),
+    /// A `key = value` line.
+    Entry { key: &'a str, value: &'a str },
+}
+
+/// Reads the sections and entries of an INI-style config file, typically a `File` such as
+/// `CONFIG.TXT`, as they're read from the reader without allocating.
+///
+/// Each line is either a `[section]` header, a `key = value` entry, a comment starting with `#` or
+/// `;`, or blank.  Whitespace around names, keys and values is ignored, values can be wrapped in
+/// double quotes to keep it.  Lines may be up to `N` bytes long.
+///
+/// Entries are returned in the order they're written, so a key written twice is returned twice.
+#[derive(Clone, Debug)]
+pub struct ConfigReader<R, const N: usize> {
+    lines: Lines<R, N>,
+    line_number: usize,
+}
+
+impl<R, const N: usize> ConfigReader<R, N> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: Lines::new(reader),
+            line_number: 0,
+        }
+    }
+
+    /// The number of the line the last returned item or error was read from, starting at 1.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    pub fn into_inner(self) -> R {
+        self.lines.into_inner()
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<R, const N: usize> ConfigReader<R, N>
+where
+    R: Read,
+{
+    /// Reads the next section header or entry, `None` once the reader's end is reached.
+    pub fn next_item(&mut self) -> Option<Result<ConfigItem<'_>, ConfigError<R::Error>>> {
+        loop {
+            self.line_number += 1;
+
+            match self.lines.next_line()? {
+                Ok(line) if is_ignored(line) => continue,
+                Ok(_) => return Some(parse_line(self.lines.last_line(), self.line_number)),
+                Err(e) => return Some(Err(ConfigError::from_lines_error(e, self.line_number))),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R, const N: usize> ConfigReader<R, N>
+where
+    R: AsyncRead,
+{
+    /// Reads the next section header or entry, `None` once the reader's end is reached.
+    pub async fn next_item_async(
+        &mut self,
+    ) -> Option<Result<ConfigItem<'_>, ConfigError<R::Error>>> {
+        loop {
+            self.line_number += 1;
+
+            match self.lines.next_line_async().await? {
+                Ok(line) if is_ignored(line) => continue,
+                Ok(_) => return Some(parse_line(self.lines.last_line(), self.line_number)),
+                Err(e) => return Some(Err(ConfigError::from_lines_error(e, self.line_number))),
+            }
+        }
+    }
+}
+
+/// Whether the line is blank or a comment.
+fn is_ignored(line: &str) -> bool {
+    let line = line.trim();
+
+    line.is_empty() || line.starts_with(['#', ';'])
+}
+
+/// Parses a line which isn't ignored into a section header or entry.
+fn parse_line<RE>(line: &str, line_number: usize) -> Result<ConfigItem<'_>, ConfigError<RE>>
+where
+    RE: Error,
+{
+    let line = line.trim();
+
+    if let Some(name) = line
+        .strip_prefix('[')
+        .and_then(|line| line.strip_suffix(']'))
+    {
+        return Ok(ConfigItem::Section(name.trim()));
+    }
+
+    let Some((key, value)) = line.split_once('=') else {
+        return Err(ConfigError::MalformedLine { line_number });
+    };
+
+    let key = key.trim();
+    let value = value.trim();
+
+    if key.is_empty() {
+        return Err(ConfigError::MalformedLine { line_number });
+    }
+
+    let value = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value);
+
+    Ok(ConfigItem::Entry { key, value })
+}
+
+#[cfg(all(test, feature = "sync"))]
+mod tests {
+    use super::*;
+    use crate::mock::DataStream;
+
+    fn reader(text: &str) -> ConfigReader<DataStream<&[u8]>, 32> {
+        ConfigReader::new(DataStream::from_bytes(text.as_bytes()))
+    }
+
+    mod next_item {
+        use super::*;
+
+        #[test]
+        fn sections_and_entries_returned() {
+            let mut reader = reader("[network]\r\nssid = home\r\n[ display ]\r\nbrightness=80");
+
+            assert_eq!(
+                reader
+                    .next_item()
+                    .expect("Some should be returned")
+                    .expect("Ok should be returned"),
+                ConfigItem::Section("network")
+            );
+            assert_eq!(
+                reader
+                    .next_item()
+                    .expect("Some should be returned")
+                    .expect("Ok should be returned"),
+                ConfigItem::Entry {
+                    key: "ssid",
+                    value: "home"
+                }
+            );
+            assert_eq!(
+                reader
+                    .next_item()
+                    .expect("Some should be returned")
+                    .expect("Ok should be returned"),
+                ConfigItem::Section("display")
+            );
+            assert_eq!(
+                reader
+                    .next_item()
+                    .expect("Some should be returned")
+                    .expect("Ok should be returned"),
+                ConfigItem::Entry {
+                    key: "brightness",
+                    value: "80"
+                }
+            );
+            assert!(reader.next_item().is_none());
+        }
+
+        #[test]
+        fn blank_and_comment_lines_skipped() {
+            let mut reader = reader("# comment\n\n   \n; comment\nkey = value\n");
+
+            assert_eq!(
+                reader
+                    .next_item()
+                    .expect("Some should be returned")
+                    .expect("Ok should be returned"),
+                ConfigItem::Entry {
+                    key: "key",
+                    value: "value"
+                }
+            );
+            assert_eq!(reader.line_number(), 5);
+            assert!(reader.next_item().is_none());
+        }
+
+        #[test]
+        fn quoted_values_keep_whitespace() {
+            let mut reader = reader("greeting = \" hi = there \"");
+
+            assert_eq!(
+                reader
+                    .next_item()
+                    .expect("Some should be returned")
+                    .expect("Ok should be returned"),
+                ConfigItem::Entry {
+                    key: "greeting",
+                    value: " hi = there "
+                }
+            );
+        }
+
+        #[test]
+        fn empty_values_returned() {
+            let mut reader = reader("key =");
+
+            assert_eq!(
+                reader
+                    .next_item()
+                    .expect("Some should be returned")
+                    .expect("Ok should be returned"),
+                ConfigItem::Entry {
+                    key: "key",
+                    value: ""
+                }
+            );
+        }
+
+        #[test]
+        fn malformed_lines_reported_with_line_number() {
+            let mut reader = reader("a = 1\njust text\n= value\nb = 2");
+
+            assert!(reader.next_item().expect("Some should be returned").is_ok());
+            assert!(matches!(
+                reader.next_item(),
+                Some(Err(ConfigError::MalformedLine { line_number: 2 }))
+            ));
+            assert!(matches!(
+                reader.next_item(),
+                Some(Err(ConfigError::MalformedLine { line_number: 3 }))
+            ));
+            assert_eq!(
+                reader
+                    .next_item()
+                    .expect("Some should be returned")
+                    .expect("Ok should be returned"),
+                ConfigItem::Entry {
+                    key: "b",
+                    value: "2"
+                }
+            );
+        }
+
+        #[test]
+        fn over_long_lines_reported_with_line_number() {
+            let mut reader = reader("a = 1\nkey = a value much too long for the buffer\nb = 2");
+
+            assert!(reader.next_item().expect("Some should be returned").is_ok());
+            assert!(matches!(
+                reader.next_item(),
+                Some(Err(ConfigError::LineTooLong { line_number: 2 }))
+            ));
+            assert!(reader.next_item().expect("Some should be returned").is_ok());
+        }
+    }
+}
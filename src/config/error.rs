@@ -0,0 +1,88 @@
+use crate::LinesError;
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+
+#[derive(Clone, Debug)]
+pub enum ConfigError<RE>
+where
+    RE: Error,
+{
+    /// The line isn't valid UTF-8, reading continues with the following line.
+    InvalidUtf8 {
+        line_number: usize,
+    },
+    /// The line doesn't fit the line buffer, reading continues with the following line.
+    LineTooLong {
+        line_number: usize,
+    },
+    /// The line is neither a section header, an entry, a comment nor blank, reading continues with
+    /// the following line.
+    MalformedLine {
+        line_number: usize,
+    },
+    ReadError(RE),
+}
+
+impl<RE> ConfigError<RE>
+where
+    RE: Error,
+{
+    pub(crate) fn from_lines_error(error: LinesError<RE>, line_number: usize) -> Self {
+        match error {
+            LinesError::InvalidUtf8(_) => ConfigError::InvalidUtf8 { line_number },
+            LinesError::LineTooLong => ConfigError::LineTooLong { line_number },
+            LinesError::ReadError(e) => ConfigError::ReadError(e),
+        }
+    }
+}
+
+impl<RE> Error for ConfigError<RE> where RE: Error {}
+
+impl<RE> Display for ConfigError<RE>
+where
+    RE: Error,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ConfigError::InvalidUtf8 { line_number } => {
+                write!(f, "line {line_number} is not valid UTF-8")
+            }
+            ConfigError::LineTooLong { line_number } => {
+                write!(f, "line {line_number} is longer than the line buffer")
+            }
+            ConfigError::MalformedLine { line_number } => write!(
+                f,
+                "line {line_number} is neither a section header nor a key-value entry"
+            ),
+            ConfigError::ReadError(e) => write!(f, "reading the config failed: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::CoreError;
+    use alloc::string::ToString;
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn produces_non_empty_value() {
+            let values = [
+                ConfigError::<CoreError>::InvalidUtf8 { line_number: 1 },
+                ConfigError::LineTooLong { line_number: 1 },
+                ConfigError::MalformedLine { line_number: 1 },
+                ConfigError::ReadError(CoreError),
+            ];
+
+            for value in values {
+                assert!(
+                    !value.to_string().is_empty(),
+                    "Display implementation should be non-empty"
+                );
+            }
+        }
+    }
+}
@@ -1,7 +1,15 @@
+#[cfg(target_has_atomic = "8")]
+mod shared;
 mod single_access;
+#[cfg(feature = "async")]
+mod timeout;
 
 use core::error::Error;
+#[cfg(target_has_atomic = "8")]
+pub use shared::*;
 pub use single_access::*;
+#[cfg(feature = "async")]
+pub use timeout::*;
 
 use core::fmt::Debug;
 use core::ops::DerefMut;
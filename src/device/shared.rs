@@ -0,0 +1,325 @@
+use crate::device::{Device, SingleAccessDeviceError};
+use core::cell::UnsafeCell;
+use core::fmt::{Debug, Formatter};
+use core::sync::atomic::{AtomicBool, Ordering};
+use embedded_io::ErrorType;
+
+#[cfg(feature = "sync")]
+use {
+    crate::{SyncDevice, SyncFlushableDevice},
+    embedded_io::Write,
+};
+
+#[cfg(feature = "async")]
+use {
+    crate::{AsyncDevice, AsyncFlushableDevice},
+    embedded_io_async::Write as AsyncWrite,
+};
+
+/// A single-stream device which can be shared between tasks and interrupt handlers.
+///
+/// Unlike [`SingleAccessDevice`](crate::SingleAccessDevice), `SharedDevice` is `Sync` whenever
+/// its stream is `Send`, so it can be placed in a `static` (see [`SharedDevice::new`]) and the
+/// resulting `&'static` handle moved freely between RTIC tasks. Access is arbitrated by a single
+/// atomic flag rather than a lock: claiming the stream never blocks or disables interrupts, and a
+/// task which preempts another while the stream is claimed receives
+/// [`SingleAccessDeviceError::StreamInUse`] instead of deadlocking.
+///
+/// Requires a target with atomic compare-and-swap on bytes.
+pub struct SharedDevice<S>
+where
+    S: ErrorType,
+{
+    in_use: AtomicBool,
+    stream: UnsafeCell<S>,
+}
+
+// SAFETY: the stream is only ever reachable through a `StreamClaim`, of which at most one exists at
+// a time, so sharing the device only ever moves exclusive access to the stream between contexts.
+unsafe impl<S> Sync for SharedDevice<S> where S: ErrorType + Send {}
+
+impl<S> SharedDevice<S>
+where
+    S: ErrorType,
+{
+    /// Creates a new shared device, usable in `static` initializers.
+    pub const fn new(stream: S) -> Self {
+        Self {
+            in_use: AtomicBool::new(false),
+            stream: UnsafeCell::new(stream),
+        }
+    }
+
+    /// Determines whether the stream is currently claimed by some operation.
+    pub fn is_in_use(&self) -> bool {
+        self.in_use.load(Ordering::Acquire)
+    }
+
+    pub fn into_inner(self) -> S {
+        self.stream.into_inner()
+    }
+
+    fn claim(&self) -> Result<StreamClaim<'_, S>, SingleAccessDeviceError<S::Error>> {
+        self.in_use
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .map_err(|_| SingleAccessDeviceError::StreamInUse)?;
+
+        Ok(StreamClaim { device: self })
+    }
+}
+
+impl<S> From<S> for SharedDevice<S>
+where
+    S: ErrorType,
+{
+    fn from(value: S) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<S> Debug for SharedDevice<S>
+where
+    S: ErrorType,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SharedDevice")
+            .field("in_use", &self.is_in_use())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S> Device for SharedDevice<S>
+where
+    S: ErrorType,
+{
+    type Stream = S;
+    type Error = SingleAccessDeviceError<S::Error>;
+}
+
+/// Exclusive access to a [`SharedDevice`]'s stream, released when dropped.
+struct StreamClaim<'a, S>
+where
+    S: ErrorType,
+{
+    device: &'a SharedDevice<S>,
+}
+
+impl<S> StreamClaim<'_, S>
+where
+    S: ErrorType,
+{
+    fn stream(&mut self) -> &mut S {
+        // SAFETY: holding the claim guarantees no other reference to the stream exists.
+        unsafe { &mut *self.device.stream.get() }
+    }
+}
+
+impl<S> Drop for StreamClaim<'_, S>
+where
+    S: ErrorType,
+{
+    fn drop(&mut self) {
+        self.device.in_use.store(false, Ordering::Release);
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<S> SyncDevice for SharedDevice<S>
+where
+    S: ErrorType,
+{
+    fn with_stream<F, R>(&self, f: F) -> Result<R, Self::Error>
+    where
+        F: FnOnce(&mut Self::Stream) -> R,
+    {
+        let mut claim = self.claim()?;
+
+        Ok(f(claim.stream()))
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<S> SyncFlushableDevice for SharedDevice<S>
+where
+    S: Write,
+{
+    fn flush(&self) -> Result<(), Self::Error> {
+        let mut claim = self.claim()?;
+
+        claim
+            .stream()
+            .flush()
+            .map_err(SingleAccessDeviceError::FlushFailed)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S> AsyncDevice for SharedDevice<S>
+where
+    S: ErrorType,
+{
+    async fn with_stream<F, R>(&self, f: F) -> Result<R, Self::Error>
+    where
+        F: AsyncFnOnce(&mut Self::Stream) -> R,
+    {
+        let mut claim = self.claim()?;
+
+        Ok(f(claim.stream()).await)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S> AsyncFlushableDevice for SharedDevice<S>
+where
+    S: AsyncWrite,
+{
+    async fn flush(&self) -> Result<(), Self::Error> {
+        let mut claim = self.claim()?;
+
+        claim
+            .stream()
+            .flush()
+            .await
+            .map_err(SingleAccessDeviceError::FlushFailed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{ErroringStream, ErroringStreamScenarios, IoError, VoidStream};
+
+    fn assert_sync<T: Sync>() {}
+
+    #[test]
+    fn shareable_across_contexts() {
+        assert_sync::<SharedDevice<VoidStream>>();
+    }
+
+    #[cfg(feature = "sync")]
+    mod sync_with_stream {
+        use super::*;
+
+        #[test]
+        fn basic_usage_works() {
+            let device = SharedDevice::new(VoidStream::new());
+
+            let result =
+                SyncDevice::with_stream(&device, |_| 5).expect("with_stream should be successful");
+
+            assert_eq!(result, 5, "Result should match expected value");
+            assert!(!device.is_in_use(), "Stream should be released");
+        }
+
+        #[test]
+        fn nested_usage_returns_err() {
+            let device = SharedDevice::new(VoidStream::new());
+
+            let result = SyncDevice::with_stream(&device, |_| {
+                SyncDevice::with_stream(&device, |_| unreachable!())
+                    .expect_err("Inner usage should fail")
+            })
+            .expect("Outer usage should succeed");
+
+            assert!(
+                matches!(result, SingleAccessDeviceError::StreamInUse),
+                "Result should be StreamInUse"
+            );
+        }
+
+        #[test]
+        fn panicking_operation_releases_stream() {
+            let device = SharedDevice::new(VoidStream::new());
+
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                SyncDevice::with_stream(&device, |_| panic!("operation failed"))
+            }));
+
+            assert!(result.is_err(), "Operation should have panicked");
+            assert!(!device.is_in_use(), "Stream should be released");
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    mod sync_flush {
+        use super::*;
+
+        #[test]
+        fn nested_usage_returns_err() {
+            let device = SharedDevice::new(VoidStream::new());
+
+            let result = SyncDevice::with_stream(&device, |_| {
+                SyncFlushableDevice::flush(&device).expect_err("Inner usage should fail")
+            })
+            .expect("Outer usage should succeed");
+
+            assert!(
+                matches!(result, SingleAccessDeviceError::StreamInUse),
+                "Result should be StreamInUse"
+            );
+        }
+
+        #[test]
+        fn stream_flush_failure_propagated() {
+            let device = SharedDevice::new(ErroringStream::new(
+                VoidStream::new(),
+                IoError::default(),
+                ErroringStreamScenarios::FLUSH,
+            ));
+
+            let result = SyncFlushableDevice::flush(&device).expect_err("Flush should fail");
+
+            assert!(
+                matches!(result, SingleAccessDeviceError::FlushFailed(IoError(_))),
+                "Err should be FlushFailed"
+            );
+        }
+    }
+
+    #[cfg(feature = "async")]
+    mod async_with_stream {
+        use super::*;
+
+        #[tokio::test]
+        async fn nested_usage_returns_err() {
+            let device = SharedDevice::new(VoidStream::new());
+
+            let result = AsyncDevice::with_stream(&device, async |_| {
+                AsyncDevice::with_stream(&device, async |_| unreachable!())
+                    .await
+                    .expect_err("Inner usage should fail")
+            })
+            .await
+            .expect("Outer usage should succeed");
+
+            assert!(
+                matches!(result, SingleAccessDeviceError::StreamInUse),
+                "Result should be StreamInUse"
+            );
+            assert!(!device.is_in_use(), "Stream should be released");
+        }
+    }
+
+    #[cfg(feature = "async")]
+    mod async_flush {
+        use super::*;
+
+        #[tokio::test]
+        async fn stream_flush_failure_propagated() {
+            let device = SharedDevice::new(ErroringStream::new(
+                VoidStream::new(),
+                IoError::default(),
+                ErroringStreamScenarios::FLUSH,
+            ));
+
+            let result = AsyncFlushableDevice::flush(&device)
+                .await
+                .expect_err("Flush should fail");
+
+            assert!(
+                matches!(result, SingleAccessDeviceError::FlushFailed(IoError(_))),
+                "Err should be FlushFailed"
+            );
+        }
+    }
+}
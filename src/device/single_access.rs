@@ -122,6 +122,7 @@ mod tests {
     use core::fmt::Debug;
     use embedded_io::ErrorType;
 
+    #[cfg(feature = "sync")]
     mod sync_with_stream {
         use super::*;
 
@@ -157,6 +158,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "sync")]
     mod sync_flush {
         use super::*;
 
@@ -203,6 +205,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "async")]
     mod async_with_stream {
         use super::*;
 
@@ -241,6 +244,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "async")]
     mod async_flush {
         use super::*;
 
@@ -0,0 +1,185 @@
+mod error;
+
+pub use error::*;
+
+use crate::device::Device;
+use crate::{AsyncDevice, AsyncFlushableDevice};
+use core::future::poll_fn;
+use core::pin::pin;
+use core::task::Poll;
+
+/// An asynchronous device decorator which bounds how long each operation may take.
+///
+/// Every `with_stream` and `flush` call is raced against a fresh future produced by the
+/// caller-supplied timer factory, such as `|| embassy_time::Timer::after_millis(250)`. If the
+/// timer completes first, the operation is dropped and [`TimeoutDeviceError::DeviceTimeout`] is
+/// returned.
+///
+/// An operation abandoned mid-transfer may leave the stream at an arbitrary position, so the
+/// `FileSystem` should be treated as inconsistent until it is reopened.
+#[derive(Clone, Debug)]
+pub struct TimeoutDevice<D, T> {
+    device: D,
+    timer: T,
+}
+
+impl<D, T> TimeoutDevice<D, T> {
+    pub fn new(device: D, timer: T) -> Self {
+        Self { device, timer }
+    }
+
+    pub fn into_inner(self) -> D {
+        self.device
+    }
+}
+
+impl<D, T> Device for TimeoutDevice<D, T>
+where
+    D: Device,
+{
+    type Stream = D::Stream;
+    type Error = TimeoutDeviceError<D::Error>;
+}
+
+impl<D, T, TF> AsyncDevice for TimeoutDevice<D, T>
+where
+    D: AsyncDevice,
+    T: Fn() -> TF,
+    TF: Future<Output = ()>,
+{
+    async fn with_stream<F, R>(&self, f: F) -> Result<R, Self::Error>
+    where
+        F: AsyncFnOnce(&mut Self::Stream) -> R,
+    {
+        race(self.device.with_stream(f), (self.timer)())
+            .await
+            .ok_or(TimeoutDeviceError::DeviceTimeout)?
+            .map_err(TimeoutDeviceError::Device)
+    }
+}
+
+impl<D, T, TF> AsyncFlushableDevice for TimeoutDevice<D, T>
+where
+    D: AsyncFlushableDevice,
+    T: Fn() -> TF,
+    TF: Future<Output = ()>,
+{
+    async fn flush(&self) -> Result<(), Self::Error> {
+        race(self.device.flush(), (self.timer)())
+            .await
+            .ok_or(TimeoutDeviceError::DeviceTimeout)?
+            .map_err(TimeoutDeviceError::Device)
+    }
+}
+
+/// Runs `operation` until it or `timer` completes, returning `None` if the timer completed first.
+///
+/// The operation is always polled first, so an operation completing in the same poll as the timer
+/// is not discarded.
+async fn race<O, T>(operation: O, timer: T) -> Option<O::Output>
+where
+    O: Future,
+    T: Future<Output = ()>,
+{
+    let mut operation = pin!(operation);
+    let mut timer = pin!(timer);
+
+    poll_fn(|cx| {
+        if let Poll::Ready(output) = operation.as_mut().poll(cx) {
+            return Poll::Ready(Some(output));
+        }
+
+        if timer.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(None);
+        }
+
+        Poll::Pending
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SingleAccessDevice;
+    use crate::SingleAccessDeviceError;
+    use crate::mock::{ErroringStream, ErroringStreamScenarios, IoError, VoidStream};
+    use core::future::{pending, ready};
+
+    mod with_stream {
+        use super::*;
+
+        #[tokio::test]
+        async fn completed_operation_returned() {
+            let device =
+                TimeoutDevice::new(SingleAccessDevice::new(VoidStream::new()), pending::<()>);
+
+            let result = device.with_stream(async |_| 5).await;
+
+            assert!(matches!(result, Ok(5)));
+        }
+
+        #[tokio::test]
+        async fn wedged_operation_times_out() {
+            let device =
+                TimeoutDevice::new(SingleAccessDevice::new(VoidStream::new()), || ready(()));
+
+            let result = device.with_stream(async |_| pending::<()>().await).await;
+
+            assert!(matches!(result, Err(TimeoutDeviceError::DeviceTimeout)));
+        }
+
+        #[tokio::test]
+        async fn operation_preferred_over_simultaneous_expiry() {
+            let device =
+                TimeoutDevice::new(SingleAccessDevice::new(VoidStream::new()), || ready(()));
+
+            let result = device.with_stream(async |_| 5).await;
+
+            assert!(matches!(result, Ok(5)));
+        }
+
+        #[tokio::test]
+        async fn device_error_propagated() {
+            let device =
+                TimeoutDevice::new(SingleAccessDevice::new(VoidStream::new()), pending::<()>);
+
+            let result = device
+                .with_stream(async |_| device.with_stream(async |_| ()).await)
+                .await
+                .expect("Outer usage should succeed");
+
+            assert!(matches!(
+                result,
+                Err(TimeoutDeviceError::Device(
+                    SingleAccessDeviceError::StreamInUse
+                ))
+            ));
+        }
+    }
+
+    mod flush {
+        use super::*;
+
+        #[tokio::test]
+        async fn device_error_propagated() {
+            let device = TimeoutDevice::new(
+                SingleAccessDevice::new(ErroringStream::new(
+                    VoidStream::new(),
+                    IoError::default(),
+                    ErroringStreamScenarios::FLUSH,
+                )),
+                pending::<()>,
+            );
+
+            let result = device.flush().await;
+
+            assert!(matches!(
+                result,
+                Err(TimeoutDeviceError::Device(
+                    SingleAccessDeviceError::FlushFailed(_)
+                ))
+            ));
+        }
+    }
+}
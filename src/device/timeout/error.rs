@@ -0,0 +1,62 @@
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+
+#[derive(Clone, Debug)]
+pub enum TimeoutDeviceError<E>
+where
+    E: Error,
+{
+    /// The timer expired before the device operation completed
+    DeviceTimeout,
+
+    /// The wrapped device reported an error
+    Device(E),
+}
+
+impl<E> Error for TimeoutDeviceError<E> where E: Error {}
+
+impl<E> Display for TimeoutDeviceError<E>
+where
+    E: Error,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TimeoutDeviceError::DeviceTimeout => {
+                write!(
+                    f,
+                    "the device operation did not complete before the timer expired"
+                )
+            }
+            TimeoutDeviceError::Device(e) => {
+                write!(f, "an error occurred while using the wrapped device: {}", e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SingleAccessDeviceError;
+    use crate::mock::IoError;
+    use alloc::string::ToString;
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn produces_non_empty_value() {
+            let values = [
+                TimeoutDeviceError::DeviceTimeout,
+                TimeoutDeviceError::Device(SingleAccessDeviceError::<IoError>::StreamInUse),
+            ];
+
+            for value in values {
+                assert!(
+                    !value.to_string().is_empty(),
+                    "Display implementation should be non-empty"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,323 @@
+mod cursor;
+mod filtered;
+
+pub use cursor::*;
+pub use filtered::*;
+
+use crate::directory::{Directory, LocatedDirectoryEntryIterator};
+use crate::directory_entry::{DEFAULT_BUFFER_SIZE, ShortNameDirectoryEntry};
+use crate::directory_item::{
+    DeviceDirectoryItemIterationError, DirectoryItem, DirectoryItemFilter,
+    DirectoryItemIterationError, DirectoryItemIterator, LongNamePolicy,
+};
+use crate::{Device, InvalidTimestampPolicy};
+
+#[cfg(feature = "sync")]
+use {
+    crate::SyncDevice,
+    embedded_io::{Read, Seek},
+};
+
+#[cfg(all(feature = "dangerous", feature = "sync"))]
+use {
+    crate::directory_entry::{DIRECTORY_ENTRY_SIZE, DirectoryEntryIteratorResult},
+    crate::directory_item::EntryLocation,
+    embedded_io::Write,
+};
+
+#[cfg(feature = "async")]
+use {
+    crate::AsyncDevice,
+    crate::yield_budget::YieldBudget,
+    embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek},
+};
+
+/// A listing of the items contained in a single directory.
+///
+/// Items are read from the device lazily as the listing is advanced.  Invalid items are returned
+/// as errors rather than ending the listing, subsequent calls continue with the following item.
+/// An item whose long name is corrupt is reported as an error once and then listed under its short
+/// name.
+#[derive(Clone, Debug)]
+pub struct Dir<'a, D, const BUFFER_SIZE: usize = DEFAULT_BUFFER_SIZE>
+where
+    D: Device,
+{
+    directory: Directory<'a, D, BUFFER_SIZE>,
+    item_iterator: DirectoryItemIterator<'a, D, BUFFER_SIZE>,
+    long_name_policy: LongNamePolicy,
+    skip_dot_items: bool,
+    yield_interval: u32,
+    listed_generation: Option<u32>,
+}
+
+impl<'a, D, const BUFFER_SIZE: usize> Dir<'a, D, BUFFER_SIZE>
+where
+    D: Device,
+{
+    pub(crate) fn new(directory: Directory<'a, D, BUFFER_SIZE>, yield_interval: u32) -> Self {
+        Self {
+            item_iterator: directory.items(),
+            directory,
+            long_name_policy: LongNamePolicy::default(),
+            skip_dot_items: false,
+            yield_interval,
+            listed_generation: None,
+        }
+    }
+
+    /// Sets how items with over-long or undecodable long names are listed, such items are
+    /// reported as errors by default.
+    pub fn with_long_name_policy(mut self, long_name_policy: LongNamePolicy) -> Self {
+        self.long_name_policy = long_name_policy;
+        self.item_iterator = self.item_iterator.with_long_name_policy(long_name_policy);
+        self
+    }
+
+    /// Sets whether the `.` and `..` items of subdirectories are left out of the listing, they are
+    /// listed by default.
+    pub fn with_dot_items_skipped(mut self, skip_dot_items: bool) -> Self {
+        self.skip_dot_items = skip_dot_items;
+        self.item_iterator = self.item_iterator.with_dot_items_skipped(skip_dot_items);
+        self
+    }
+
+    /// Sets whether the listing reports `ListingChanged` and ends once a directory of the volume
+    /// is written to while it's being listed, rather than listing items from before and after the
+    /// write.  Changes aren't detected by default.
+    ///
+    /// Any directory write is treated as a change, as the volume only counts writes as a whole.
+    /// Rewinding or resuming the listing starts watching for changes afresh.
+    pub fn with_change_detection(mut self, detect_changes: bool) -> Self {
+        self.listed_generation = detect_changes.then(|| self.directory.generation().get());
+        self
+    }
+
+    /// Leaves the items not matched by the filter out of the listing, continuing from the same
+    /// position.
+    pub fn with_filter<'f>(
+        self,
+        filter: DirectoryItemFilter<'f>,
+    ) -> FilteredDir<'a, 'f, D, BUFFER_SIZE> {
+        FilteredDir::new(self, filter)
+    }
+
+    /// Leaves out every item whose name doesn't end in the extension, given without its leading
+    /// `.` and compared ignoring case, see `with_filter`.
+    ///
+    /// Directories are matched as well as files, an empty extension matches items without one.
+    pub fn with_extension<'f>(self, extension: &'f str) -> FilteredDir<'a, 'f, D, BUFFER_SIZE> {
+        self.with_filter(DirectoryItemFilter::builder().extension(extension).build())
+    }
+
+    /// Restarts the listing from the first item of the directory.
+    pub fn rewind(&mut self) {
+        self.item_iterator = self.fresh_item_iterator();
+        self.restart_change_detection();
+    }
+
+    /// The position of the next item, which `resume` returns the listing to.
+    pub fn cursor(&self) -> DirCursor {
+        DirCursor::new(self.item_iterator.position())
+    }
+
+    /// Continues the listing from a cursor previously taken from a listing of the same directory.
+    pub fn resume(&mut self, cursor: DirCursor) {
+        self.item_iterator.set_position(cursor.position());
+        self.restart_change_detection();
+    }
+
+    /// Iterates the directory's raw entries along with their locations, independently of the
+    /// listing.
+    pub fn entries(&self) -> LocatedDirectoryEntryIterator<'a, D, BUFFER_SIZE> {
+        self.directory.located_entries()
+    }
+
+    fn restart_change_detection(&mut self) {
+        if self.listed_generation.is_some() {
+            self.listed_generation = Some(self.directory.generation().get());
+        }
+    }
+
+    /// Replaces an item read after the directory changed with `ListingChanged`, ending the
+    /// listing.
+    fn check_unchanged(
+        &mut self,
+        item: Option<Result<DirectoryItem, DeviceDirectoryItemIterationError<D>>>,
+    ) -> Option<Result<DirectoryItem, DeviceDirectoryItemIterationError<D>>> {
+        match self.listed_generation {
+            Some(generation) if generation != self.directory.generation().get() => {
+                self.item_iterator.set_position(None);
+                self.listed_generation = Some(self.directory.generation().get());
+
+                Some(Err(DirectoryItemIterationError::ListingChanged))
+            }
+            _ => item,
+        }
+    }
+
+    fn fresh_item_iterator(&self) -> DirectoryItemIterator<'a, D, BUFFER_SIZE> {
+        self.directory
+            .items()
+            .with_long_name_policy(self.long_name_policy)
+            .with_dot_items_skipped(self.skip_dot_items)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<D, S, const BUFFER_SIZE: usize> Iterator for Dir<'_, D, BUFFER_SIZE>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Seek,
+{
+    type Item = Result<DirectoryItem, DeviceDirectoryItemIterationError<D>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.item_iterator.next();
+
+        self.check_unchanged(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.item_iterator.size_hint()
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<D, S, const BUFFER_SIZE: usize> Dir<'_, D, BUFFER_SIZE>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Seek,
+{
+    /// Returns the item at the given index, counting from the directory's first item, and
+    /// continues the listing after it.
+    ///
+    /// Items before the index are skipped by their entries alone, without assembling their names.
+    pub fn item_at(
+        &mut self,
+        index: usize,
+    ) -> Option<Result<DirectoryItem, DeviceDirectoryItemIterationError<D>>> {
+        self.rewind();
+        propagate_iteration_error!(self.item_iterator.skip_items(index)?);
+
+        let item = self.item_iterator.next();
+
+        self.check_unchanged(item)
+    }
+
+    /// Counts every item of the directory, as listed, by scanning it once independently of the
+    /// listing.
+    ///
+    /// Items are counted by their short name entries alone, so items with invalid long names are
+    /// counted once even though the listing reports them as errors first.
+    pub fn entry_count(&self) -> Result<usize, DeviceDirectoryItemIterationError<D>> {
+        self.fresh_item_iterator().count_items()
+    }
+
+    /// Counts the items whose last write timestamp doesn't exist, whatever the directory's
+    /// invalid timestamp policy, by scanning it like `entry_count`.
+    pub(crate) fn invalid_timestamp_count(
+        &self,
+    ) -> Result<u32, DeviceDirectoryItemIterationError<D>> {
+        let mut invalid_timestamp_count = 0;
+
+        self.fresh_item_iterator().visit_items(|short_name_entry| {
+            if !is_timestamp_valid(short_name_entry) {
+                invalid_timestamp_count += 1;
+            }
+        })?;
+
+        Ok(invalid_timestamp_count)
+    }
+}
+
+#[cfg(all(feature = "dangerous", feature = "sync"))]
+impl<D, S, const BUFFER_SIZE: usize> Dir<'_, D, BUFFER_SIZE>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Write + Seek,
+{
+    /// Overwrites consecutive entries starting at a location returned by `entries`, bypassing the
+    /// checks made when items are written.
+    ///
+    /// Entries are written as given, so mismatched long name checksums or cluster numbers corrupt
+    /// the directory.
+    pub fn write_entries(
+        &self,
+        location: EntryLocation,
+        entries: &[[u8; DIRECTORY_ENTRY_SIZE]],
+    ) -> DirectoryEntryIteratorResult<(), D> {
+        self.directory.write_entries(location.position(), entries)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, S, const BUFFER_SIZE: usize> Dir<'_, D, BUFFER_SIZE>
+where
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncSeek,
+{
+    pub async fn next_async(
+        &mut self,
+    ) -> Option<Result<DirectoryItem, DeviceDirectoryItemIterationError<D>>> {
+        let item = self.item_iterator.next_async().await;
+
+        self.check_unchanged(item)
+    }
+
+    /// Returns the item at the given index, counting from the directory's first item, and
+    /// continues the listing after it.
+    ///
+    /// Items before the index are skipped by their entries alone, without assembling their names.
+    pub async fn item_at_async(
+        &mut self,
+        index: usize,
+    ) -> Option<Result<DirectoryItem, DeviceDirectoryItemIterationError<D>>> {
+        self.rewind();
+        propagate_iteration_error!(self.item_iterator.skip_items_async(index).await?);
+
+        let item = self.item_iterator.next_async().await;
+
+        self.check_unchanged(item)
+    }
+
+    /// Counts every item of the directory, as listed, by scanning it once independently of the
+    /// listing, see `entry_count`.
+    ///
+    /// The scan yields to the executor at the file system's yield interval, so dropping the
+    /// future cancels it part way through long directories.
+    pub async fn entry_count_async(&self) -> Result<usize, DeviceDirectoryItemIterationError<D>> {
+        self.fresh_item_iterator()
+            .count_items_async(&mut YieldBudget::new(self.yield_interval))
+            .await
+    }
+
+    /// Counts the items whose last write timestamp doesn't exist, see `invalid_timestamp_count`.
+    pub(crate) async fn invalid_timestamp_count_async(
+        &self,
+    ) -> Result<u32, DeviceDirectoryItemIterationError<D>> {
+        let mut invalid_timestamp_count = 0;
+
+        self.fresh_item_iterator()
+            .visit_items_async(
+                &mut YieldBudget::new(self.yield_interval),
+                |short_name_entry| {
+                    if !is_timestamp_valid(short_name_entry) {
+                        invalid_timestamp_count += 1;
+                    }
+                },
+            )
+            .await?;
+
+        Ok(invalid_timestamp_count)
+    }
+}
+
+fn is_timestamp_valid(short_name_entry: &ShortNameDirectoryEntry) -> bool {
+    InvalidTimestampPolicy::Reject
+        .unpack(
+            short_name_entry.last_write_date(),
+            short_name_entry.last_write_time(),
+        )
+        .is_ok()
+}
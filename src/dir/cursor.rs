@@ -0,0 +1,26 @@
+use crate::directory_entry::DirectoryEntryPosition;
+
+/// A saved position within a directory listing, used to resume the listing later without
+/// reading the items before it again.
+///
+/// Cursors are only meaningful for listings of the directory they were taken from, and only
+/// while its items aren't added, removed or renamed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DirCursor {
+    position: Option<DirectoryEntryPosition>,
+}
+
+impl DirCursor {
+    pub(crate) fn new(position: Option<DirectoryEntryPosition>) -> Self {
+        Self { position }
+    }
+
+    pub(crate) fn position(&self) -> Option<DirectoryEntryPosition> {
+        self.position
+    }
+
+    /// Whether the listing had no items left when the cursor was taken.
+    pub fn is_end(&self) -> bool {
+        self.position.is_none()
+    }
+}
@@ -0,0 +1,105 @@
+use crate::directory_entry::DEFAULT_BUFFER_SIZE;
+use crate::directory_item::{
+    DeviceDirectoryItemIterationError, DirectoryItem, DirectoryItemFilter,
+};
+use crate::{Device, Dir, DirCursor};
+
+#[cfg(feature = "sync")]
+use {
+    crate::SyncDevice,
+    embedded_io::{Read, Seek},
+};
+
+#[cfg(feature = "async")]
+use {
+    crate::AsyncDevice,
+    embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek},
+};
+
+/// A listing of a single directory which leaves out the items not matched by a filter, returned
+/// by `Dir::with_filter` and `Dir::with_extension`.
+///
+/// Errors are returned as by the underlying listing, as items which fail to be read can't be
+/// matched against the filter.
+#[derive(Clone, Debug)]
+pub struct FilteredDir<'a, 'f, D, const BUFFER_SIZE: usize = DEFAULT_BUFFER_SIZE>
+where
+    D: Device,
+{
+    dir: Dir<'a, D, BUFFER_SIZE>,
+    filter: DirectoryItemFilter<'f>,
+}
+
+impl<'a, 'f, D, const BUFFER_SIZE: usize> FilteredDir<'a, 'f, D, BUFFER_SIZE>
+where
+    D: Device,
+{
+    pub(crate) fn new(dir: Dir<'a, D, BUFFER_SIZE>, filter: DirectoryItemFilter<'f>) -> Self {
+        Self { dir, filter }
+    }
+
+    /// The filter items are matched against.
+    pub fn filter(&self) -> &DirectoryItemFilter<'f> {
+        &self.filter
+    }
+
+    /// Restarts the listing from the first item of the directory.
+    pub fn rewind(&mut self) {
+        self.dir.rewind();
+    }
+
+    /// The position of the next item to be matched, which `resume` returns the listing to.
+    pub fn cursor(&self) -> DirCursor {
+        self.dir.cursor()
+    }
+
+    /// Continues the listing from a cursor previously taken from a listing of the same directory.
+    pub fn resume(&mut self, cursor: DirCursor) {
+        self.dir.resume(cursor);
+    }
+
+    /// Returns the unfiltered listing, continuing from the same position.
+    pub fn into_inner(self) -> Dir<'a, D, BUFFER_SIZE> {
+        self.dir
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<D, S, const BUFFER_SIZE: usize> Iterator for FilteredDir<'_, '_, D, BUFFER_SIZE>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Seek,
+{
+    type Item = Result<DirectoryItem, DeviceDirectoryItemIterationError<D>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let filter = &self.filter;
+
+        self.dir.find(|item| match item {
+            Ok(item) => filter.matches(item),
+            Err(_) => true,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.dir.size_hint().1)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, S, const BUFFER_SIZE: usize> FilteredDir<'_, '_, D, BUFFER_SIZE>
+where
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncSeek,
+{
+    pub async fn next_async(
+        &mut self,
+    ) -> Option<Result<DirectoryItem, DeviceDirectoryItemIterationError<D>>> {
+        loop {
+            match self.dir.next_async().await? {
+                Ok(item) if !self.filter.matches(&item) => {}
+                result => return Some(result),
+            }
+        }
+    }
+}
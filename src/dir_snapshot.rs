@@ -0,0 +1,257 @@
+mod change;
+mod entry;
+mod error;
+
+pub use change::*;
+pub use entry::*;
+pub use error::*;
+
+use crate::{Device, Dir, DirectoryItem};
+
+#[cfg(feature = "sync")]
+use {
+    crate::SyncDevice,
+    embedded_io::{Read, Seek},
+};
+
+#[cfg(feature = "async")]
+use {
+    crate::AsyncDevice,
+    embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek},
+};
+
+/// The name, size and last write timestamp of every item in a directory at a point in time.
+///
+/// Comparing a snapshot against an earlier one of the same directory reveals items which were
+/// added, removed or rewritten in between, such as files copied onto the volume by a host over
+/// USB mass storage.  Snapshots hold up to `N` items without allocating.
+#[derive(Clone, Debug)]
+pub struct DirSnapshot<const N: usize> {
+    entries: [Option<DirSnapshotEntry>; N],
+    entry_count: usize,
+}
+
+impl<const N: usize> DirSnapshot<N> {
+    fn new() -> Self {
+        Self {
+            entries: [const { None }; N],
+            entry_count: 0,
+        }
+    }
+
+    /// The recorded items, in directory order.
+    pub fn entries(&self) -> impl Iterator<Item = &DirSnapshotEntry> {
+        self.entries[..self.entry_count].iter().flatten()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entry_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entry_count == 0
+    }
+
+    /// Lists the differences from an earlier snapshot of the same directory, reporting items which
+    /// are new or changed first, followed by items which no longer exist.
+    pub fn changes_since<'a, const M: usize>(
+        &'a self,
+        earlier: &'a DirSnapshot<M>,
+    ) -> impl Iterator<Item = DirChange<'a>> {
+        let added_or_modified =
+            self.entries()
+                .filter_map(|current| match earlier.find_entry(current) {
+                    None => Some(DirChange::Added(current)),
+                    Some(previous) if current.is_modified_from(previous) => {
+                        Some(DirChange::Modified { previous, current })
+                    }
+                    Some(_) => None,
+                });
+        let removed = earlier
+            .entries()
+            .filter(|previous| self.find_entry(previous).is_none())
+            .map(DirChange::Removed);
+
+        added_or_modified.chain(removed)
+    }
+
+    fn find_entry(&self, other: &DirSnapshotEntry) -> Option<&DirSnapshotEntry> {
+        self.entries()
+            .find(|entry| entry.short_name() == other.short_name())
+    }
+
+    fn push<DE, SE>(&mut self, item: &DirectoryItem) -> Result<(), DirSnapshotError<DE, SE>>
+    where
+        DE: core::error::Error,
+        SE: embedded_io::Error,
+    {
+        let slot = self
+            .entries
+            .get_mut(self.entry_count)
+            .ok_or(DirSnapshotError::CapacityExceeded { capacity: N })?;
+
+        *slot = Some(DirSnapshotEntry::new(item));
+        self.entry_count += 1;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<const N: usize> DirSnapshot<N> {
+    /// Records the items remaining in the listing.
+    pub fn capture<D, S, const BUFFER_SIZE: usize>(
+        dir: Dir<'_, D, BUFFER_SIZE>,
+    ) -> Result<Self, DeviceDirSnapshotError<D>>
+    where
+        D: SyncDevice<Stream = S>,
+        S: Read + Seek,
+    {
+        let mut snapshot = Self::new();
+
+        for item in dir {
+            snapshot.push(&item?)?;
+        }
+
+        Ok(snapshot)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<const N: usize> DirSnapshot<N> {
+    /// Records the items remaining in the listing.
+    pub async fn capture_async<D, S, const BUFFER_SIZE: usize>(
+        mut dir: Dir<'_, D, BUFFER_SIZE>,
+    ) -> Result<Self, DeviceDirSnapshotError<D>>
+    where
+        D: AsyncDevice<Stream = S>,
+        S: AsyncRead + AsyncSeek,
+    {
+        let mut snapshot = Self::new();
+
+        while let Some(item) = dir.next_async().await {
+            snapshot.push(&item?)?;
+        }
+
+        Ok(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::directory_entry::ShortNameDirectoryEntry;
+    use crate::mock::{CoreError, IoError};
+    use crate::{AsciiOnlyEncoder, DirectoryEntryAttributes, DirectoryItemEntries, ShortFileName};
+    use alloc::vec::Vec;
+
+    fn item(name: &str, file_size: u32, last_write_time: u16) -> DirectoryItem {
+        let item_entries = DirectoryItemEntries::builder()
+            .short_name(ShortFileName::from_str(&AsciiOnlyEncoder, name).unwrap())
+            .attributes(DirectoryEntryAttributes::Archive)
+            .last_write_time(last_write_time)
+            .first_cluster_number(2)
+            .file_size(file_size.into())
+            .build();
+
+        DirectoryItem::new(
+            ShortNameDirectoryEntry::from_bytes(&item_entries.entries()[0]).unwrap(),
+            None,
+        )
+    }
+
+    fn snapshot<const N: usize>(items: &[DirectoryItem]) -> DirSnapshot<N> {
+        let mut snapshot = DirSnapshot::new();
+
+        for item in items {
+            snapshot
+                .push::<CoreError, IoError>(item)
+                .expect("Ok should be returned");
+        }
+
+        snapshot
+    }
+
+    mod push {
+        use super::*;
+
+        #[test]
+        fn records_items_in_order() {
+            let snapshot = snapshot::<2>(&[item("A.TXT", 1, 0), item("B.TXT", 2, 0)]);
+
+            assert_eq!(snapshot.len(), 2);
+            assert_eq!(
+                snapshot
+                    .entries()
+                    .map(|entry| entry.file_size())
+                    .collect::<Vec<_>>(),
+                [1, 2]
+            );
+        }
+
+        #[test]
+        fn beyond_capacity_errors() {
+            let mut snapshot = snapshot::<1>(&[item("A.TXT", 1, 0)]);
+
+            let error = snapshot
+                .push::<CoreError, IoError>(&item("B.TXT", 2, 0))
+                .expect_err("Err should be returned");
+
+            assert!(matches!(
+                error,
+                DirSnapshotError::CapacityExceeded { capacity: 1 }
+            ));
+            assert_eq!(snapshot.len(), 1);
+        }
+    }
+
+    mod changes_since {
+        use super::*;
+
+        #[test]
+        fn reports_added_modified_and_removed_items() {
+            let earlier = snapshot::<4>(&[
+                item("KEPT.TXT", 10, 0),
+                item("WRITTEN.TXT", 10, 0),
+                item("TOUCHED.TXT", 10, 0),
+                item("REMOVED.TXT", 10, 0),
+            ]);
+            let later = snapshot::<4>(&[
+                item("KEPT.TXT", 10, 0),
+                item("WRITTEN.TXT", 20, 0),
+                item("TOUCHED.TXT", 10, 1),
+                item("ADDED.TXT", 10, 0),
+            ]);
+
+            let changes = later.changes_since(&earlier).collect::<Vec<_>>();
+
+            assert_eq!(changes.len(), 4);
+            assert!(matches!(
+                changes[0],
+                DirChange::Modified { previous, current }
+                    if previous.file_size() == 10 && current.file_size() == 20
+            ));
+            assert!(matches!(
+                changes[1],
+                DirChange::Modified { previous, current }
+                    if previous.last_write_time() == 0 && current.last_write_time() == 1
+            ));
+            assert!(matches!(
+                changes[2],
+                DirChange::Added(entry) if entry.short_name().base_name() == b"ADDED"
+            ));
+            assert!(matches!(
+                changes[3],
+                DirChange::Removed(entry) if entry.short_name().base_name() == b"REMOVED"
+            ));
+        }
+
+        #[test]
+        fn unchanged_directory_reports_nothing() {
+            let earlier = snapshot::<2>(&[item("A.TXT", 1, 0), item("B.TXT", 2, 0)]);
+            let later = snapshot::<3>(&[item("B.TXT", 2, 0), item("A.TXT", 1, 0)]);
+
+            assert_eq!(later.changes_since(&earlier).count(), 0);
+        }
+    }
+}
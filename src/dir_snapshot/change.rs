@@ -0,0 +1,12 @@
+use crate::DirSnapshotEntry;
+
+/// A difference between two snapshots of the same directory.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DirChange<'a> {
+    Added(&'a DirSnapshotEntry),
+    Modified {
+        previous: &'a DirSnapshotEntry,
+        current: &'a DirSnapshotEntry,
+    },
+    Removed(&'a DirSnapshotEntry),
+}
@@ -0,0 +1,53 @@
+use crate::{DirectoryItem, ShortFileName};
+
+/// The state of a single item recorded by a `DirSnapshot`.
+///
+/// Items are identified by their short name, which is unique within a directory and fits in a
+/// fixed amount of storage unlike long names.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DirSnapshotEntry {
+    short_name: ShortFileName,
+    is_directory: bool,
+    file_size: u32,
+    last_write_date: u16,
+    last_write_time: u16,
+}
+
+impl DirSnapshotEntry {
+    pub(crate) fn new(item: &DirectoryItem) -> Self {
+        Self {
+            short_name: item.short_name().clone(),
+            is_directory: item.is_directory(),
+            file_size: item.file_size(),
+            last_write_date: item.last_write_date(),
+            last_write_time: item.last_write_time(),
+        }
+    }
+
+    pub fn short_name(&self) -> &ShortFileName {
+        &self.short_name
+    }
+
+    pub fn is_directory(&self) -> bool {
+        self.is_directory
+    }
+
+    pub fn file_size(&self) -> u32 {
+        self.file_size
+    }
+
+    pub fn last_write_date(&self) -> u16 {
+        self.last_write_date
+    }
+
+    pub fn last_write_time(&self) -> u16 {
+        self.last_write_time
+    }
+
+    /// Whether the item's size or last write timestamp differs from the other entry's.
+    pub(crate) fn is_modified_from(&self, other: &DirSnapshotEntry) -> bool {
+        self.file_size != other.file_size
+            || self.last_write_date != other.last_write_date
+            || self.last_write_time != other.last_write_time
+    }
+}
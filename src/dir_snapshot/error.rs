@@ -0,0 +1,79 @@
+use crate::Device;
+use crate::directory_item::DirectoryItemIterationError;
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+use embedded_io::ErrorType;
+
+pub type DeviceDirSnapshotError<D> =
+    DirSnapshotError<<D as Device>::Error, <<D as Device>::Stream as ErrorType>::Error>;
+
+#[derive(Clone, Debug)]
+pub enum DirSnapshotError<DE, SE>
+where
+    DE: Error,
+    SE: embedded_io::Error,
+{
+    CapacityExceeded { capacity: usize },
+    ItemIterationError(DirectoryItemIterationError<DE, SE>),
+}
+
+impl<DE, SE> Error for DirSnapshotError<DE, SE>
+where
+    DE: Error,
+    SE: embedded_io::Error,
+{
+}
+
+impl<DE, SE> Display for DirSnapshotError<DE, SE>
+where
+    DE: Error,
+    SE: embedded_io::Error,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DirSnapshotError::CapacityExceeded { capacity } => write!(
+                f,
+                "the directory holds more than the snapshot's capacity of {capacity} items"
+            ),
+            DirSnapshotError::ItemIterationError(e) => {
+                write!(f, "reading the directory's items failed: {}", e)
+            }
+        }
+    }
+}
+
+impl<DE, SE> From<DirectoryItemIterationError<DE, SE>> for DirSnapshotError<DE, SE>
+where
+    DE: Error,
+    SE: embedded_io::Error,
+{
+    fn from(value: DirectoryItemIterationError<DE, SE>) -> Self {
+        Self::ItemIterationError(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{CoreError, IoError};
+    use alloc::string::ToString;
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn produces_non_empty_value() {
+            let values = [
+                DirSnapshotError::<CoreError, IoError>::CapacityExceeded { capacity: 8 },
+                DirSnapshotError::ItemIterationError(DirectoryItemIterationError::StreamEndReached),
+            ];
+
+            for value in values {
+                assert!(
+                    !value.to_string().is_empty(),
+                    "Display implementation should be non-empty"
+                );
+            }
+        }
+    }
+}
@@ -1,56 +1,553 @@
 mod entry_iteration_error;
 mod entry_iterator;
 mod file;
+#[cfg(any(feature = "fat12", feature = "fat16"))]
 mod table;
 
 pub use entry_iteration_error::*;
 pub use entry_iterator::*;
 pub use file::*;
+#[cfg(any(feature = "fat12", feature = "fat16"))]
 pub use table::*;
 
-use crate::Device;
-use crate::directory_entry::DirectoryEntryIterator;
+use crate::directory_entry::{
+    DEFAULT_BUFFER_SIZE, DIRECTORY_ENTRY_SIZE, DirectoryEntry, DirectoryEntryIterationError,
+    DirectoryEntryIterator, DirectoryEntryIteratorResult, DirectoryEntryPosition,
+    FreeDirectoryEntry,
+};
 use crate::directory_item::DirectoryItemIterator;
+use crate::{Device, InvalidTimestampPolicy};
+use core::cell::Cell;
+
+#[cfg(feature = "sync")]
+use {
+    crate::SyncDevice,
+    embedded_io::{Read, Seek, Write},
+};
+
+#[cfg(feature = "async")]
+use {
+    crate::AsyncDevice,
+    embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek, Write as AsyncWrite},
+};
 
 #[derive(Clone, Debug)]
-pub enum Directory<'a, D>
+pub enum Directory<'a, D, const BUFFER_SIZE: usize = DEFAULT_BUFFER_SIZE>
 where
     D: Device,
 {
-    Table(DirectoryTable<'a, D>),
-    File(DirectoryFile<'a, D>),
+    #[cfg(any(feature = "fat12", feature = "fat16"))]
+    Table(DirectoryTable<'a, D, BUFFER_SIZE>),
+    File(DirectoryFile<'a, D, BUFFER_SIZE>),
 }
 
-impl<'a, D> Directory<'a, D>
+impl<'a, D, const BUFFER_SIZE: usize> Directory<'a, D, BUFFER_SIZE>
 where
     D: Device,
 {
-    pub fn items(&'a self) -> DirectoryItemIterator<'a, D> {
+    pub fn items(&self) -> DirectoryItemIterator<'a, D, BUFFER_SIZE> {
         DirectoryItemIterator::new(self.entries())
+            .with_utc_offset(self.utc_offset_minutes())
+            .with_invalid_timestamp_policy(self.invalid_timestamp_policy())
     }
 
-    fn entries(&'a self) -> DirectoryEntryIterator<'a, D> {
+    pub fn entries(&self) -> DirectoryEntryIterator<'a, D, BUFFER_SIZE> {
         match self {
+            #[cfg(any(feature = "fat12", feature = "fat16"))]
             Directory::Table(table) => table.entries().into(),
             Directory::File(file) => file.entries().into(),
         }
     }
+
+    pub fn located_entries(&self) -> LocatedDirectoryEntryIterator<'a, D, BUFFER_SIZE> {
+        LocatedDirectoryEntryIterator::new(self.entries())
+    }
+
+    /// The counter of directory writes shared by every directory of the volume.
+    pub fn generation(&self) -> &'a Cell<u32> {
+        match self {
+            #[cfg(any(feature = "fat12", feature = "fat16"))]
+            Directory::Table(table) => table.generation(),
+            Directory::File(file) => file.generation(),
+        }
+    }
+
+    /// The offset of the volume's local time from UTC, in minutes.
+    pub fn utc_offset_minutes(&self) -> i16 {
+        match self {
+            #[cfg(any(feature = "fat12", feature = "fat16"))]
+            Directory::Table(table) => table.utc_offset_minutes(),
+            Directory::File(file) => file.utc_offset_minutes(),
+        }
+    }
+
+    /// How items listed read timestamps which don't exist.
+    pub fn invalid_timestamp_policy(&self) -> InvalidTimestampPolicy {
+        match self {
+            #[cfg(any(feature = "fat12", feature = "fat16"))]
+            Directory::Table(table) => table.invalid_timestamp_policy(),
+            Directory::File(file) => file.invalid_timestamp_policy(),
+        }
+    }
 }
 
-impl<'a, D> From<DirectoryTable<'a, D>> for Directory<'a, D>
+/// The body of `write_entries`, shared between the sync and async implementations.
+macro_rules! write_entries_body {
+    ($io:ident, $self:ident, $position:ident, $entries:ident) => {{
+        let generation = $self.generation();
+        generation.set(generation.get().wrapping_add(1));
+
+        let mut iterator = $self.entries();
+        iterator.set_position($position);
+
+        let mut entry_index = 0;
+        let mut is_end_overwritten = false;
+        let mut following_position = None;
+        while entry_index < $entries.len() {
+            let run_position = iterator
+                .position()
+                .ok_or(DirectoryEntryIterationError::StreamEndReached)?;
+            let mut run_length = 0;
+
+            while entry_index + run_length < $entries.len()
+                && iterator.position() == Some(run_position.offset_by(run_length as u32))
+            {
+                is_end_overwritten |= matches!(
+                    $io!(iterator.peek(); iterator.peek_async()),
+                    Some(Ok(DirectoryEntry::Free(FreeDirectoryEntry::AllFollowing)))
+                );
+                run_length += 1;
+
+                if !$io!(iterator.advance(); iterator.advance_async())? {
+                    break;
+                }
+            }
+
+            let next_position = iterator.position();
+            following_position = next_position;
+
+            iterator.set_position(run_position);
+            $io!(
+                iterator.write($entries[entry_index..entry_index + run_length].as_flattened());
+                iterator.write_async($entries[entry_index..entry_index + run_length].as_flattened())
+            )?;
+
+            entry_index += run_length;
+
+            if let Some(next_position) = next_position {
+                iterator.set_position(next_position);
+            } else {
+                ensure!(
+                    entry_index == $entries.len(),
+                    DirectoryEntryIterationError::StreamEndReached
+                );
+            }
+        }
+
+        // A directory filled to its last slot needs no marker
+        if is_end_overwritten && let Some(following_position) = following_position {
+            iterator.set_position(following_position);
+            $io!(
+                iterator.write(&[0; DIRECTORY_ENTRY_SIZE]);
+                iterator.write_async(&[0; DIRECTORY_ENTRY_SIZE])
+            )?;
+        }
+
+        Ok(())
+    }};
+}
+
+#[cfg(feature = "sync")]
+impl<D, S, const BUFFER_SIZE: usize> Directory<'_, D, BUFFER_SIZE>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Write + Seek,
+{
+    /// Finds the first run of `entry_count` consecutive free entries, returning the position of
+    /// its first entry.
+    ///
+    /// Every entry following an end-of-directory marker is considered free, regardless of its
+    /// contents.
+    pub fn find_free_entries(
+        &self,
+        entry_count: usize,
+    ) -> DirectoryEntryIteratorResult<Option<DirectoryEntryPosition>, D> {
+        let mut iterator = self.entries();
+        let mut run_start = None;
+        let mut run_length = 0;
+        let mut is_end_reached = false;
+
+        while let Some(position) = iterator.position() {
+            let is_free = is_end_reached
+                || match iterator.peek() {
+                    None => break,
+                    Some(Ok(DirectoryEntry::Free(free_entry))) => {
+                        is_end_reached = matches!(free_entry, FreeDirectoryEntry::AllFollowing);
+                        true
+                    }
+                    Some(Ok(_)) | Some(Err(DirectoryEntryIterationError::EntryInvalid { .. })) => {
+                        false
+                    }
+                    Some(Err(error)) => return Err(error),
+                };
+
+            if is_free {
+                run_start.get_or_insert(position);
+                run_length += 1;
+
+                if run_length == entry_count {
+                    return Ok(run_start);
+                }
+            } else {
+                run_start = None;
+                run_length = 0;
+            }
+
+            if !iterator.advance()? {
+                break;
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Writes the entries into consecutive slots starting at the given position.
+    ///
+    /// Entries which are stored contiguously are written with a single write, so a set of entries
+    /// within one cluster is replaced in one operation.  The volume's directory generation is
+    /// advanced before writing, so listings checking it notice even partially written entries.
+    ///
+    /// Overwriting the end-of-directory marker writes a new one following the entries, so
+    /// whatever the slots after the old marker held isn't listed.
+    pub fn write_entries(
+        &self,
+        position: DirectoryEntryPosition,
+        entries: &[[u8; DIRECTORY_ENTRY_SIZE]],
+    ) -> DirectoryEntryIteratorResult<(), D> {
+        write_entries_body!(sync_io, self, position, entries)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, S, const BUFFER_SIZE: usize> Directory<'_, D, BUFFER_SIZE>
+where
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncWrite + AsyncSeek,
+{
+    /// Writes the entries into consecutive slots starting at the given position, see
+    /// `write_entries`.
+    pub async fn write_entries_async(
+        &self,
+        position: DirectoryEntryPosition,
+        entries: &[[u8; DIRECTORY_ENTRY_SIZE]],
+    ) -> DirectoryEntryIteratorResult<(), D> {
+        write_entries_body!(async_io, self, position, entries)
+    }
+}
+
+#[cfg(any(feature = "fat12", feature = "fat16"))]
+impl<'a, D, const BUFFER_SIZE: usize> From<DirectoryTable<'a, D, BUFFER_SIZE>>
+    for Directory<'a, D, BUFFER_SIZE>
 where
     D: Device,
 {
-    fn from(value: DirectoryTable<'a, D>) -> Self {
+    fn from(value: DirectoryTable<'a, D, BUFFER_SIZE>) -> Self {
         Self::Table(value)
     }
 }
 
-impl<'a, D> From<DirectoryFile<'a, D>> for Directory<'a, D>
+impl<'a, D, const BUFFER_SIZE: usize> From<DirectoryFile<'a, D, BUFFER_SIZE>>
+    for Directory<'a, D, BUFFER_SIZE>
 where
     D: Device,
 {
-    fn from(value: DirectoryFile<'a, D>) -> Self {
+    fn from(value: DirectoryFile<'a, D, BUFFER_SIZE>) -> Self {
         Self::File(value)
     }
 }
+
+#[cfg(all(test, feature = "fat32"))]
+mod tests {
+    use super::*;
+    use crate::allocation_table::AllocationTable;
+    use crate::directory_item::EntryLocation;
+    use crate::mock::DataStream;
+    use crate::units::{ByteAddress, ClusterNumber};
+    use crate::utils::write_le_u32;
+    use crate::{AllocationTableKind, SingleAccessDevice};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[cfg(feature = "sync")]
+    mod find_free_entries {
+        use super::*;
+
+        #[test]
+        fn run_spanning_clusters_found() {
+            let test_instance = TestInstance::new([0x41, 0xE5, 0xE5, 0x41]);
+
+            let position = test_instance
+                .directory()
+                .find_free_entries(2)
+                .expect("Ok should be returned");
+
+            assert_eq!(
+                position,
+                Some(DirectoryEntryPosition::File {
+                    cluster_number: 2,
+                    cluster_offset: DIRECTORY_ENTRY_SIZE as u32,
+                })
+            );
+        }
+
+        #[test]
+        fn entries_after_end_marker_considered_free() {
+            let test_instance = TestInstance::new([0x41, 0x00, 0x41, 0x41]);
+
+            let position = test_instance
+                .directory()
+                .find_free_entries(3)
+                .expect("Ok should be returned");
+
+            assert_eq!(
+                position,
+                Some(DirectoryEntryPosition::File {
+                    cluster_number: 2,
+                    cluster_offset: DIRECTORY_ENTRY_SIZE as u32,
+                })
+            );
+        }
+
+        #[test]
+        fn insufficient_run_returns_none() {
+            let test_instance = TestInstance::new([0xE5, 0x41, 0xE5, 0x41]);
+
+            let position = test_instance
+                .directory()
+                .find_free_entries(2)
+                .expect("Ok should be returned");
+
+            assert_eq!(position, None);
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    mod located_entries {
+        use super::*;
+
+        #[test]
+        fn entries_paired_with_their_locations() {
+            let test_instance = TestInstance::new([0xE5, 0xE5, 0xE5, 0x00]);
+
+            let locations: Vec<_> = test_instance
+                .directory()
+                .located_entries()
+                .map(|entry| {
+                    let (location, entry) = entry.expect("Ok should be returned");
+                    assert!(matches!(entry, DirectoryEntry::Free(_)));
+
+                    location
+                })
+                .collect();
+
+            assert_eq!(
+                locations,
+                [
+                    (2, 0),
+                    (2, DIRECTORY_ENTRY_SIZE as u32),
+                    (3, 0),
+                    (3, DIRECTORY_ENTRY_SIZE as u32),
+                ]
+                .map(|(cluster_number, cluster_offset)| EntryLocation::new(
+                    DirectoryEntryPosition::File {
+                        cluster_number,
+                        cluster_offset,
+                    },
+                    1
+                ))
+            );
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    mod write_entries {
+        use super::*;
+
+        #[test]
+        fn entries_written_across_clusters() {
+            let test_instance = TestInstance::new([0x41, 0x41, 0x41, 0x41]);
+            let directory = test_instance.directory();
+
+            directory
+                .write_entries(
+                    DirectoryEntryPosition::File {
+                        cluster_number: 2,
+                        cluster_offset: DIRECTORY_ENTRY_SIZE as u32,
+                    },
+                    &[[0xE5; DIRECTORY_ENTRY_SIZE], [0x00; DIRECTORY_ENTRY_SIZE]],
+                )
+                .expect("Ok should be returned");
+
+            assert_eq!(
+                directory
+                    .find_free_entries(3)
+                    .expect("Ok should be returned"),
+                Some(DirectoryEntryPosition::File {
+                    cluster_number: 2,
+                    cluster_offset: DIRECTORY_ENTRY_SIZE as u32,
+                }),
+                "Written entries should be free"
+            );
+        }
+
+        #[test]
+        fn overwritten_end_marker_written_following_entries() {
+            let test_instance = TestInstance::new([0x41, 0x00, 0x41, 0x41]);
+            let directory = test_instance.directory();
+
+            directory
+                .write_entries(
+                    DirectoryEntryPosition::File {
+                        cluster_number: 2,
+                        cluster_offset: DIRECTORY_ENTRY_SIZE as u32,
+                    },
+                    &[[0xE5; DIRECTORY_ENTRY_SIZE]],
+                )
+                .expect("Ok should be returned");
+
+            let mut iterator = directory.entries();
+            iterator.set_position(DirectoryEntryPosition::File {
+                cluster_number: 3,
+                cluster_offset: 0,
+            });
+
+            assert!(
+                matches!(
+                    iterator.peek(),
+                    Some(Ok(DirectoryEntry::Free(FreeDirectoryEntry::AllFollowing)))
+                ),
+                "Entry following the written entries should end the directory"
+            );
+        }
+
+        #[test]
+        fn end_marker_kept_when_not_overwritten() {
+            let test_instance = TestInstance::new([0x41, 0x41, 0x00, 0x41]);
+            let directory = test_instance.directory();
+
+            directory
+                .write_entries(
+                    DirectoryEntryPosition::File {
+                        cluster_number: 2,
+                        cluster_offset: 0,
+                    },
+                    &[[0xE5; DIRECTORY_ENTRY_SIZE]],
+                )
+                .expect("Ok should be returned");
+
+            let mut iterator = directory.entries();
+            iterator.set_position(DirectoryEntryPosition::File {
+                cluster_number: 2,
+                cluster_offset: DIRECTORY_ENTRY_SIZE as u32,
+            });
+
+            assert!(
+                !matches!(
+                    iterator.peek(),
+                    Some(Ok(DirectoryEntry::Free(FreeDirectoryEntry::AllFollowing)))
+                ),
+                "Following entry should be left as is"
+            );
+        }
+
+        #[test]
+        fn generation_advanced() {
+            let test_instance = TestInstance::new([0x41, 0x41, 0x41, 0x41]);
+
+            test_instance
+                .directory()
+                .write_entries(
+                    DirectoryEntryPosition::File {
+                        cluster_number: 2,
+                        cluster_offset: 0,
+                    },
+                    &[[0xE5; DIRECTORY_ENTRY_SIZE]],
+                )
+                .expect("Ok should be returned");
+
+            assert_eq!(test_instance.generation.get(), 1);
+        }
+
+        #[test]
+        fn directory_end_reached_returns_err() {
+            let test_instance = TestInstance::new([0x41, 0x41, 0x41, 0x41]);
+
+            let error = test_instance
+                .directory()
+                .write_entries(
+                    DirectoryEntryPosition::File {
+                        cluster_number: 3,
+                        cluster_offset: DIRECTORY_ENTRY_SIZE as u32,
+                    },
+                    &[[0xE5; DIRECTORY_ENTRY_SIZE], [0xE5; DIRECTORY_ENTRY_SIZE]],
+                )
+                .expect_err("Err should be returned");
+
+            assert!(
+                matches!(error, DirectoryEntryIterationError::StreamEndReached),
+                "StreamEndReached should be returned"
+            );
+        }
+    }
+
+    type TestInstanceDevice = SingleAccessDevice<DataStream<Vec<u8>>>;
+
+    const DATA_REGION_BASE_ADDRESS: usize = 16;
+    const ENTRIES_PER_CLUSTER: usize = 2;
+
+    struct TestInstance {
+        device: TestInstanceDevice,
+        allocation_table: AllocationTable,
+        generation: Cell<u32>,
+    }
+
+    impl TestInstance {
+        /// Creates a directory file of two clusters, each holding two entries starting with the
+        /// provided bytes.
+        fn new(first_entry_bytes: [u8; 4]) -> Self {
+            let mut data = vec![0; DATA_REGION_BASE_ADDRESS + (4 * DIRECTORY_ENTRY_SIZE)];
+            write_le_u32(&mut data, 8, 3);
+            write_le_u32(
+                &mut data,
+                12,
+                AllocationTableKind::Fat32.end_of_chain_value(),
+            );
+
+            for (entry_index, first_entry_byte) in first_entry_bytes.into_iter().enumerate() {
+                data[DATA_REGION_BASE_ADDRESS + (entry_index * DIRECTORY_ENTRY_SIZE)] =
+                    first_entry_byte;
+            }
+
+            Self {
+                device: DataStream::from_bytes(data).into(),
+                allocation_table: AllocationTable::new(
+                    AllocationTableKind::Fat32,
+                    ByteAddress::new(0),
+                ),
+                generation: Cell::new(0),
+            }
+        }
+
+        fn directory(&self) -> Directory<'_, TestInstanceDevice> {
+            DirectoryFile::new(
+                &self.device,
+                &self.allocation_table,
+                ByteAddress::new(DATA_REGION_BASE_ADDRESS as u64),
+                (ENTRIES_PER_CLUSTER * DIRECTORY_ENTRY_SIZE) as u32,
+                ClusterNumber::new(2),
+                &self.generation,
+            )
+            .into()
+        }
+    }
+}
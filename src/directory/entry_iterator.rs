@@ -1 +1,81 @@
+use crate::Device;
+use crate::directory_entry::{
+    DEFAULT_BUFFER_SIZE, DirectoryEntry, DirectoryEntryIterator, DirectoryEntryIteratorResult,
+};
+use crate::directory_item::EntryLocation;
 
+#[cfg(feature = "sync")]
+use {
+    crate::SyncDevice,
+    embedded_io::{Read, Seek},
+};
+
+#[cfg(feature = "async")]
+use {
+    crate::AsyncDevice,
+    embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek},
+};
+
+/// Iterates the raw entries of a directory along with the location of each, so an entry can be
+/// rewritten after examining it without searching the directory for it again.
+///
+/// Every entry is returned on its own, including free entries and the long name entries making
+/// up an item's name, so each location spans a single entry.
+#[derive(Clone, Debug)]
+pub struct LocatedDirectoryEntryIterator<'a, D, const BUFFER_SIZE: usize = DEFAULT_BUFFER_SIZE>
+where
+    D: Device,
+{
+    entry_iterator: DirectoryEntryIterator<'a, D, BUFFER_SIZE>,
+}
+
+impl<'a, D, const BUFFER_SIZE: usize> LocatedDirectoryEntryIterator<'a, D, BUFFER_SIZE>
+where
+    D: Device,
+{
+    pub(crate) fn new(entry_iterator: DirectoryEntryIterator<'a, D, BUFFER_SIZE>) -> Self {
+        Self { entry_iterator }
+    }
+
+    /// The location of the entry returned next, `None` once the iterator is exhausted.
+    fn current_location(&self) -> Option<EntryLocation> {
+        self.entry_iterator
+            .position()
+            .map(|position| EntryLocation::new(position, 1))
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<D, S, const BUFFER_SIZE: usize> Iterator for LocatedDirectoryEntryIterator<'_, D, BUFFER_SIZE>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Seek,
+{
+    type Item = DirectoryEntryIteratorResult<(EntryLocation, DirectoryEntry), D>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let location = self.current_location()?;
+
+        Some(self.entry_iterator.next()?.map(|entry| (location, entry)))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, S, const BUFFER_SIZE: usize> LocatedDirectoryEntryIterator<'_, D, BUFFER_SIZE>
+where
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncSeek,
+{
+    pub async fn next_async(
+        &mut self,
+    ) -> Option<DirectoryEntryIteratorResult<(EntryLocation, DirectoryEntry), D>> {
+        let location = self.current_location()?;
+
+        Some(
+            self.entry_iterator
+                .next_async()
+                .await?
+                .map(|entry| (location, entry)),
+        )
+    }
+}
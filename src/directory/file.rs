@@ -1,31 +1,38 @@
+use crate::InvalidTimestampPolicy;
 use crate::allocation_table::AllocationTable;
 use crate::device::Device;
-use crate::directory_entry::DirectoryFileEntryIterator;
+use crate::directory_entry::{DEFAULT_BUFFER_SIZE, DirectoryFileEntryIterator};
+use crate::units::{ByteAddress, ClusterNumber};
+use core::cell::Cell;
 
 #[derive(Clone, Debug)]
-pub struct DirectoryFile<'a, D>
+pub struct DirectoryFile<'a, D, const BUFFER_SIZE: usize = DEFAULT_BUFFER_SIZE>
 where
     D: Device,
 {
     device: &'a D,
     allocation_table: &'a AllocationTable,
 
-    data_region_base_address: u64,
+    data_region_base_address: ByteAddress,
     bytes_per_cluster: u32,
 
-    start_cluster_number: u32,
+    start_cluster_number: ClusterNumber,
+    generation: &'a Cell<u32>,
+    utc_offset_minutes: i16,
+    invalid_timestamp_policy: InvalidTimestampPolicy,
 }
 
-impl<'a, D> DirectoryFile<'a, D>
+impl<'a, D, const BUFFER_SIZE: usize> DirectoryFile<'a, D, BUFFER_SIZE>
 where
     D: Device,
 {
     pub fn new(
         device: &'a D,
         allocation_table: &'a AllocationTable,
-        data_region_base_address: u64,
+        data_region_base_address: ByteAddress,
         bytes_per_cluster: u32,
-        start_cluster_number: u32,
+        start_cluster_number: ClusterNumber,
+        generation: &'a Cell<u32>,
     ) -> Self {
         Self {
             device,
@@ -35,10 +42,40 @@ where
             bytes_per_cluster,
 
             start_cluster_number,
+            generation,
+            utc_offset_minutes: 0,
+            invalid_timestamp_policy: InvalidTimestampPolicy::default(),
         }
     }
 
-    pub fn entries(&self) -> DirectoryFileEntryIterator<'_, D> {
+    /// Sets the offset of the volume's local time from UTC given to the items listed.
+    pub fn with_utc_offset(mut self, utc_offset_minutes: i16) -> Self {
+        self.utc_offset_minutes = utc_offset_minutes;
+        self
+    }
+
+    /// Sets how the items listed read timestamps which don't exist.
+    pub fn with_invalid_timestamp_policy(
+        mut self,
+        invalid_timestamp_policy: InvalidTimestampPolicy,
+    ) -> Self {
+        self.invalid_timestamp_policy = invalid_timestamp_policy;
+        self
+    }
+
+    pub fn generation(&self) -> &'a Cell<u32> {
+        self.generation
+    }
+
+    pub fn utc_offset_minutes(&self) -> i16 {
+        self.utc_offset_minutes
+    }
+
+    pub fn invalid_timestamp_policy(&self) -> InvalidTimestampPolicy {
+        self.invalid_timestamp_policy
+    }
+
+    pub fn entries(&self) -> DirectoryFileEntryIterator<'a, D, BUFFER_SIZE> {
         DirectoryFileEntryIterator::new(
             self.device,
             self.allocation_table,
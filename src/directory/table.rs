@@ -1,31 +1,72 @@
+use crate::InvalidTimestampPolicy;
 use crate::device::Device;
-use crate::directory_entry::DirectoryTableEntryIterator;
+use crate::directory_entry::{DEFAULT_BUFFER_SIZE, DirectoryTableEntryIterator};
+use crate::units::ByteAddress;
+use core::cell::Cell;
 
 #[derive(Clone, Debug)]
-pub struct DirectoryTable<'a, D>
+pub struct DirectoryTable<'a, D, const BUFFER_SIZE: usize = DEFAULT_BUFFER_SIZE>
 where
     D: Device,
 {
     device: &'a D,
 
-    start_address: u64,
+    start_address: ByteAddress,
     entry_count: u16,
+    generation: &'a Cell<u32>,
+    utc_offset_minutes: i16,
+    invalid_timestamp_policy: InvalidTimestampPolicy,
 }
 
-impl<'a, D> DirectoryTable<'a, D>
+impl<'a, D, const BUFFER_SIZE: usize> DirectoryTable<'a, D, BUFFER_SIZE>
 where
     D: Device,
 {
-    pub fn new(device: &'a D, start_address: u64, entry_count: u16) -> Self {
+    pub fn new(
+        device: &'a D,
+        start_address: ByteAddress,
+        entry_count: u16,
+        generation: &'a Cell<u32>,
+    ) -> Self {
         Self {
             device,
 
             start_address,
             entry_count,
+            generation,
+            utc_offset_minutes: 0,
+            invalid_timestamp_policy: InvalidTimestampPolicy::default(),
         }
     }
 
-    pub fn entries(&self) -> DirectoryTableEntryIterator<'_, D> {
+    /// Sets the offset of the volume's local time from UTC given to the items listed.
+    pub fn with_utc_offset(mut self, utc_offset_minutes: i16) -> Self {
+        self.utc_offset_minutes = utc_offset_minutes;
+        self
+    }
+
+    /// Sets how the items listed read timestamps which don't exist.
+    pub fn with_invalid_timestamp_policy(
+        mut self,
+        invalid_timestamp_policy: InvalidTimestampPolicy,
+    ) -> Self {
+        self.invalid_timestamp_policy = invalid_timestamp_policy;
+        self
+    }
+
+    pub fn generation(&self) -> &'a Cell<u32> {
+        self.generation
+    }
+
+    pub fn utc_offset_minutes(&self) -> i16 {
+        self.utc_offset_minutes
+    }
+
+    pub fn invalid_timestamp_policy(&self) -> InvalidTimestampPolicy {
+        self.invalid_timestamp_policy
+    }
+
+    pub fn entries(&self) -> DirectoryTableEntryIterator<'a, D, BUFFER_SIZE> {
         DirectoryTableEntryIterator::new(self.device, self.start_address, self.entry_count)
     }
 }
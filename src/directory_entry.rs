@@ -3,6 +3,7 @@ mod error;
 mod free;
 mod iterator;
 mod long_name;
+mod position;
 mod short_name;
 
 pub use attributes::*;
@@ -10,6 +11,7 @@ pub use error::*;
 pub use free::*;
 pub use iterator::*;
 pub use long_name::*;
+pub use position::*;
 pub use short_name::*;
 
 #[cfg(feature = "sync")]
@@ -20,6 +22,10 @@ use embedded_io_async::{Seek as AsyncSeek, Write as AsyncWrite};
 
 pub const DIRECTORY_ENTRY_SIZE: usize = 32;
 
+// Long name entries have exactly the attributes of `DirectoryEntryAttributes::LongName` set among
+// the low six bits, short name entries may have any subset of them
+const LONG_NAME_ATTRIBUTE_MASK: u8 = 0x3F;
+
 #[derive(Clone, Debug)]
 pub enum DirectoryEntry {
     Free(FreeDirectoryEntry),
@@ -35,7 +41,9 @@ impl DirectoryEntry {
             Ok(FreeDirectoryEntry::AllFollowing.into())
         } else if matches!(entry_bytes[0], 0xE5) {
             Ok(FreeDirectoryEntry::CurrentOnly.into())
-        } else if entry_bytes[11] & 0x0F > 0 {
+        } else if entry_bytes[11] & LONG_NAME_ATTRIBUTE_MASK
+            == DirectoryEntryAttributes::LongName.bits()
+        {
             Ok(LongNameDirectoryEntry::from_bytes(entry_bytes)?.into())
         } else {
             Ok(ShortNameDirectoryEntry::from_bytes(entry_bytes)?.into())
@@ -109,8 +117,7 @@ mod tests {
                 .file_size(0)
                 .build();
 
-            let mut data = [0x00; DIRECTORY_ENTRY_SIZE];
-            short_name_entry.write(&mut data);
+            let data = short_name_entry.to_bytes();
 
             let entry = DirectoryEntry::from_bytes(&data).expect("Ok should be returned");
 
@@ -120,6 +127,31 @@ mod tests {
             );
         }
 
+        #[test]
+        fn short_name_with_long_name_attribute_subset_parsed_correctly() {
+            for attributes in [
+                DirectoryEntryAttributes::ReadOnly,
+                DirectoryEntryAttributes::Hidden | DirectoryEntryAttributes::System,
+                DirectoryEntryAttributes::VolumeLabel,
+            ] {
+                let short_name_entry = ShortNameDirectoryEntry::builder()
+                    .name(ShortFileName::from_str(&AsciiOnlyEncoder, "A").unwrap())
+                    .attributes(attributes)
+                    .first_cluster_number(2)
+                    .file_size(0)
+                    .build();
+
+                let data = short_name_entry.to_bytes();
+
+                let entry = DirectoryEntry::from_bytes(&data).expect("Ok should be returned");
+
+                assert!(
+                    matches!(entry, DirectoryEntry::ShortName(_)),
+                    "ShortName entry should be returned"
+                );
+            }
+        }
+
         #[test]
         fn short_name_error_propagated() {
             let mut data = [0x00; DIRECTORY_ENTRY_SIZE];
@@ -146,8 +178,7 @@ mod tests {
                 .short_name_checksum(0x00)
                 .build();
 
-            let mut data = [0x00; DIRECTORY_ENTRY_SIZE];
-            long_name_entry.write(&mut data);
+            let data = long_name_entry.to_bytes();
 
             let entry = DirectoryEntry::from_bytes(&data).expect("Ok should be returned");
 
@@ -1,25 +1,30 @@
+mod buffer;
 mod error;
 mod file;
+#[cfg(any(feature = "fat12", feature = "fat16"))]
 mod table;
 
+pub(crate) use buffer::DEFAULT_BUFFER_SIZE;
 pub use error::*;
 pub use file::*;
+#[cfg(any(feature = "fat12", feature = "fat16"))]
 pub use table::*;
 
 use crate::Device;
-use crate::directory_entry::DirectoryEntry;
+use crate::directory_entry::{DirectoryEntry, DirectoryEntryPosition};
+use crate::units::ClusterNumber;
 use embedded_io::{ErrorType, SeekFrom};
 
 #[cfg(feature = "sync")]
 use {
     crate::device::SyncDevice,
-    embedded_io::{Read, Seek},
+    embedded_io::{Read, Seek, Write},
 };
 
 #[cfg(feature = "async")]
 use {
     crate::AsyncDevice,
-    embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek},
+    embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek, Write as AsyncWrite},
 };
 
 #[cfg(test)]
@@ -31,111 +36,247 @@ pub type DirectoryEntryIteratorResult<R, D> = Result<
 >;
 
 #[derive(Clone, Debug)]
-pub enum DirectoryEntryIterator<'a, D>
+// The scripted iterator dwarfs the file iterator when it's the only other variant, in tests only.
+#[cfg_attr(test, allow(clippy::large_enum_variant))]
+pub enum DirectoryEntryIterator<'a, D, const BUFFER_SIZE: usize = DEFAULT_BUFFER_SIZE>
 where
     D: Device,
 {
-    Table(DirectoryTableEntryIterator<'a, D>),
-    File(DirectoryFileEntryIterator<'a, D>),
+    #[cfg(any(feature = "fat12", feature = "fat16"))]
+    Table(DirectoryTableEntryIterator<'a, D, BUFFER_SIZE>),
+    File(DirectoryFileEntryIterator<'a, D, BUFFER_SIZE>),
 
     #[cfg(test)]
     Scripted(ScriptedDirectoryEntryIterator<'a, D>),
 }
 
-#[cfg(feature = "sync")]
-impl<D, S> DirectoryEntryIterator<'_, D>
+impl<D, const BUFFER_SIZE: usize> DirectoryEntryIterator<'_, D, BUFFER_SIZE>
 where
-    D: SyncDevice<Stream = S>,
-    S: Read + Seek,
+    D: Device,
 {
-    pub fn peek(&self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
+    /// The position of the current entry, `None` once the iterator is exhausted.
+    pub fn position(&self) -> Option<DirectoryEntryPosition> {
         match self {
-            DirectoryEntryIterator::Table(table_iterator) => table_iterator.peek(),
-            DirectoryEntryIterator::File(file_iterator) => file_iterator.peek(),
+            #[cfg(any(feature = "fat12", feature = "fat16"))]
+            DirectoryEntryIterator::Table(table_iterator) => table_iterator.position(),
+            DirectoryEntryIterator::File(file_iterator) => file_iterator.position(),
 
             #[cfg(test)]
-            DirectoryEntryIterator::Scripted(scripted_iterator) => scripted_iterator.peek(),
+            DirectoryEntryIterator::Scripted(scripted_iterator) => scripted_iterator.position(),
         }
     }
 
-    pub fn advance(&mut self) -> DirectoryEntryIteratorResult<bool, D> {
+    /// Moves the iterator to a position previously returned by `position` on an iterator over the
+    /// same directory.
+    pub fn set_position(&mut self, position: DirectoryEntryPosition) {
+        match (self, position) {
+            #[cfg(any(feature = "fat12", feature = "fat16"))]
+            (
+                DirectoryEntryIterator::Table(table_iterator),
+                DirectoryEntryPosition::Table { entry_index },
+            ) => table_iterator.set_position(entry_index),
+            (
+                DirectoryEntryIterator::File(file_iterator),
+                DirectoryEntryPosition::File {
+                    cluster_number,
+                    cluster_offset,
+                },
+            ) => file_iterator.set_position(ClusterNumber::new(cluster_number), cluster_offset),
+
+            // Positions from a different kind of directory can't be resumed
+            #[allow(unreachable_patterns)]
+            _ => {}
+        }
+    }
+
+    /// The number of entries from the current one to the end of the directory, `None` for
+    /// directories stored in cluster chains which would have to be followed to count them.
+    pub fn remaining_entry_count(&self) -> Option<usize> {
         match self {
-            DirectoryEntryIterator::Table(table_iterator) => Ok(table_iterator.advance()),
-            DirectoryEntryIterator::File(file_iterator) => file_iterator.advance(),
+            #[cfg(any(feature = "fat12", feature = "fat16"))]
+            DirectoryEntryIterator::Table(table_iterator) => {
+                Some(table_iterator.remaining_entry_count())
+            }
+            DirectoryEntryIterator::File(file_iterator) => match file_iterator.position() {
+                Some(_) => None,
+                None => Some(0),
+            },
 
             #[cfg(test)]
-            DirectoryEntryIterator::Scripted(scripted_iterator) => scripted_iterator.advance(),
+            DirectoryEntryIterator::Scripted(_) => None,
         }
     }
 
-    pub fn next(&mut self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
+    /// Moves the iterator past the last entry, as if it were exhausted.
+    pub fn finish(&mut self) {
         match self {
-            DirectoryEntryIterator::Table(table_iterator) => table_iterator.next(),
-            DirectoryEntryIterator::File(file_iterator) => file_iterator.next(),
+            #[cfg(any(feature = "fat12", feature = "fat16"))]
+            DirectoryEntryIterator::Table(table_iterator) => table_iterator.finish(),
+            DirectoryEntryIterator::File(file_iterator) => file_iterator.finish(),
 
             #[cfg(test)]
-            DirectoryEntryIterator::Scripted(scripted_iterator) => scripted_iterator.next(),
+            DirectoryEntryIterator::Scripted(scripted_iterator) => scripted_iterator.finish(),
         }
     }
 }
 
-#[cfg(feature = "async")]
-impl<D, S> DirectoryEntryIterator<'_, D>
-where
-    D: AsyncDevice<Stream = S>,
-    S: AsyncRead + AsyncSeek,
-{
-    pub async fn peek_async(&self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
-        match self {
-            DirectoryEntryIterator::Table(table_iterator) => table_iterator.peek_async().await,
-            DirectoryEntryIterator::File(file_iterator) => file_iterator.peek_async().await,
+/// The body of `peek`, shared between the sync and async implementations.
+macro_rules! peek_body {
+    ($io:ident, $self:ident) => {{
+        match $self {
+            #[cfg(any(feature = "fat12", feature = "fat16"))]
+            DirectoryEntryIterator::Table(table_iterator) => {
+                $io!(table_iterator.peek(); table_iterator.peek_async())
+            }
+            DirectoryEntryIterator::File(file_iterator) => {
+                $io!(file_iterator.peek(); file_iterator.peek_async())
+            }
 
             #[cfg(test)]
             DirectoryEntryIterator::Scripted(scripted_iterator) => {
-                scripted_iterator.peek_async().await
+                $io!(scripted_iterator.peek(); scripted_iterator.peek_async())
             }
         }
-    }
+    }};
+}
 
-    pub async fn advance_async(&mut self) -> DirectoryEntryIteratorResult<bool, D> {
-        match self {
+/// The body of `advance`, shared between the sync and async implementations.
+macro_rules! advance_body {
+    ($io:ident, $self:ident) => {{
+        match $self {
+            #[cfg(any(feature = "fat12", feature = "fat16"))]
             DirectoryEntryIterator::Table(table_iterator) => Ok(table_iterator.advance()),
-            DirectoryEntryIterator::File(file_iterator) => file_iterator.advance_async().await,
+            DirectoryEntryIterator::File(file_iterator) => {
+                $io!(file_iterator.advance(); file_iterator.advance_async())
+            }
 
             #[cfg(test)]
             DirectoryEntryIterator::Scripted(scripted_iterator) => {
-                scripted_iterator.advance_async().await
+                $io!(scripted_iterator.advance(); scripted_iterator.advance_async())
             }
         }
-    }
+    }};
+}
 
-    pub async fn next_async(&mut self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
-        match self {
-            DirectoryEntryIterator::Table(table_iterator) => table_iterator.next_async().await,
-            DirectoryEntryIterator::File(file_iterator) => file_iterator.next_async().await,
+/// The body of `next`, shared between the sync and async implementations.
+macro_rules! next_body {
+    ($io:ident, $self:ident) => {{
+        match $self {
+            #[cfg(any(feature = "fat12", feature = "fat16"))]
+            DirectoryEntryIterator::Table(table_iterator) => {
+                $io!(table_iterator.next(); table_iterator.next_async())
+            }
+            DirectoryEntryIterator::File(file_iterator) => {
+                $io!(file_iterator.next(); file_iterator.next_async())
+            }
 
             #[cfg(test)]
             DirectoryEntryIterator::Scripted(scripted_iterator) => {
-                scripted_iterator.next_async().await
+                $io!(scripted_iterator.next(); scripted_iterator.next_async())
             }
         }
+    }};
+}
+
+/// The body of `write`, shared between the sync and async implementations.
+macro_rules! write_body {
+    ($io:ident, $self:ident, $bytes:ident) => {{
+        match $self {
+            #[cfg(any(feature = "fat12", feature = "fat16"))]
+            DirectoryEntryIterator::Table(table_iterator) => {
+                $io!(table_iterator.write($bytes); table_iterator.write_async($bytes))
+            }
+            DirectoryEntryIterator::File(file_iterator) => {
+                $io!(file_iterator.write($bytes); file_iterator.write_async($bytes))
+            }
+
+            #[cfg(test)]
+            DirectoryEntryIterator::Scripted(scripted_iterator) => {
+                $io!(scripted_iterator.write($bytes); scripted_iterator.write_async($bytes))
+            }
+        }
+    }};
+}
+
+#[cfg(feature = "sync")]
+impl<D, S, const BUFFER_SIZE: usize> DirectoryEntryIterator<'_, D, BUFFER_SIZE>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Seek,
+{
+    pub fn peek(&self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
+        peek_body!(sync_io, self)
+    }
+
+    pub fn advance(&mut self) -> DirectoryEntryIteratorResult<bool, D> {
+        advance_body!(sync_io, self)
+    }
+
+    pub fn next(&mut self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
+        next_body!(sync_io, self)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<D, S, const BUFFER_SIZE: usize> DirectoryEntryIterator<'_, D, BUFFER_SIZE>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Write + Seek,
+{
+    /// Overwrites the start of the current entry with the provided bytes.
+    pub fn write(&self, bytes: &[u8]) -> DirectoryEntryIteratorResult<(), D> {
+        write_body!(sync_io, self, bytes)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, S, const BUFFER_SIZE: usize> DirectoryEntryIterator<'_, D, BUFFER_SIZE>
+where
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncSeek,
+{
+    pub async fn peek_async(&self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
+        peek_body!(async_io, self)
+    }
+
+    pub async fn advance_async(&mut self) -> DirectoryEntryIteratorResult<bool, D> {
+        advance_body!(async_io, self)
+    }
+
+    pub async fn next_async(&mut self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
+        next_body!(async_io, self)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, S, const BUFFER_SIZE: usize> DirectoryEntryIterator<'_, D, BUFFER_SIZE>
+where
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncWrite + AsyncSeek,
+{
+    /// Overwrites the start of the current entry with the provided bytes.
+    pub async fn write_async(&self, bytes: &[u8]) -> DirectoryEntryIteratorResult<(), D> {
+        write_body!(async_io, self, bytes)
     }
 }
 
-impl<'a, D> From<DirectoryTableEntryIterator<'a, D>> for DirectoryEntryIterator<'a, D>
+#[cfg(any(feature = "fat12", feature = "fat16"))]
+impl<'a, D, const BUFFER_SIZE: usize> From<DirectoryTableEntryIterator<'a, D, BUFFER_SIZE>>
+    for DirectoryEntryIterator<'a, D, BUFFER_SIZE>
 where
     D: Device,
 {
-    fn from(value: DirectoryTableEntryIterator<'a, D>) -> Self {
+    fn from(value: DirectoryTableEntryIterator<'a, D, BUFFER_SIZE>) -> Self {
         Self::Table(value)
     }
 }
 
-impl<'a, D> From<DirectoryFileEntryIterator<'a, D>> for DirectoryEntryIterator<'a, D>
+impl<'a, D, const BUFFER_SIZE: usize> From<DirectoryFileEntryIterator<'a, D, BUFFER_SIZE>>
+    for DirectoryEntryIterator<'a, D, BUFFER_SIZE>
 where
     D: Device,
 {
-    fn from(value: DirectoryFileEntryIterator<'a, D>) -> Self {
+    fn from(value: DirectoryFileEntryIterator<'a, D, BUFFER_SIZE>) -> Self {
         Self::File(value)
     }
 }
@@ -150,17 +291,19 @@ where
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "fat32", any(feature = "fat12", feature = "fat16")))]
 mod tests {
     use super::*;
     use crate::allocation_table::AllocationTable;
     use crate::directory_entry::{DIRECTORY_ENTRY_SIZE, FreeDirectoryEntry};
     use crate::mock::DataStream;
+    use crate::units::ByteAddress;
     use crate::utils::write_le_u32;
     use crate::{AllocationTableKind, SingleAccessDevice};
     use alloc::vec;
     use alloc::vec::Vec;
 
+    #[cfg(feature = "sync")]
     mod peek {
         use super::*;
 
@@ -198,6 +341,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "sync")]
     mod next {
         use super::*;
 
@@ -235,6 +379,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "sync")]
     mod advance {
         use super::*;
 
@@ -258,6 +403,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "async")]
     mod peek_async {
         use super::*;
 
@@ -297,6 +443,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "async")]
     mod advance_async {
         use super::*;
 
@@ -322,6 +469,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "async")]
     mod next_async {
         use super::*;
 
@@ -367,7 +515,7 @@ mod tests {
         device: TestInstanceDevice,
         allocation_table: AllocationTable,
 
-        data_region_base_address: u64,
+        data_region_base_address: ByteAddress,
         entry_count: u16,
     }
 
@@ -383,10 +531,10 @@ mod tests {
 
             Self {
                 device: SingleAccessDevice::new(DataStream::from_bytes(data)),
-                allocation_table: AllocationTable::new(allocation_table_kind, 0),
+                allocation_table: AllocationTable::new(allocation_table_kind, ByteAddress::new(0)),
 
                 entry_count,
-                data_region_base_address: 12,
+                data_region_base_address: ByteAddress::new(12),
             }
         }
 
@@ -396,7 +544,7 @@ mod tests {
                 &self.allocation_table,
                 self.data_region_base_address,
                 self.entry_count as u32 * DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             iterator.into()
@@ -0,0 +1,139 @@
+use crate::directory_entry::DIRECTORY_ENTRY_SIZE;
+use crate::units::ByteAddress;
+
+/// Number of bytes of the scratch buffers kept on the stack, such as the directory entries read
+/// per device access, unless configured otherwise with
+/// [`FileSystemBuilder::with_buffer_size`](crate::FileSystemBuilder::with_buffer_size).  Matches
+/// the smallest supported sector size.
+pub(crate) const DEFAULT_BUFFER_SIZE: usize = 512;
+
+/// An in-memory copy of a contiguous run of directory entries, allowing entries within the same
+/// window to be iterated without accessing the device again.
+///
+/// Only writes performed through the owning iterator are reflected, so the buffer must be
+/// invalidated whenever its iterator writes to the device.
+///
+/// `BUFFER_SIZE` must be a non-zero multiple of the directory entry size.
+#[derive(Clone, Debug)]
+pub(crate) struct DirectoryEntryBuffer<const BUFFER_SIZE: usize> {
+    bytes: [u8; BUFFER_SIZE],
+    start_address: ByteAddress,
+    length: usize,
+}
+
+impl<const BUFFER_SIZE: usize> Default for DirectoryEntryBuffer<BUFFER_SIZE> {
+    fn default() -> Self {
+        const {
+            assert!(
+                BUFFER_SIZE > 0 && BUFFER_SIZE.is_multiple_of(DIRECTORY_ENTRY_SIZE),
+                "the buffer size must be a non-zero multiple of the directory entry size"
+            );
+        }
+
+        Self {
+            bytes: [0; BUFFER_SIZE],
+            start_address: ByteAddress::new(0),
+            length: 0,
+        }
+    }
+}
+
+impl<const BUFFER_SIZE: usize> DirectoryEntryBuffer<BUFFER_SIZE> {
+    /// The bytes of the entry at `address`, if it is held by the buffer.
+    pub(crate) fn entry_bytes(&self, address: ByteAddress) -> Option<[u8; DIRECTORY_ENTRY_SIZE]> {
+        let offset = address.value().checked_sub(self.start_address.value())?;
+        let offset = usize::try_from(offset).ok()?;
+
+        self.bytes[..self.length]
+            .get(offset..offset + DIRECTORY_ENTRY_SIZE)?
+            .try_into()
+            .ok()
+    }
+
+    /// Invalidates the buffer, returning the region `length` bytes long to read the entries
+    /// starting at `start_address` into.
+    ///
+    /// The read entries must be committed with `commit` before they are returned by
+    /// `entry_bytes`.
+    pub(crate) fn begin_fill(&mut self, start_address: ByteAddress, length: usize) -> &mut [u8] {
+        self.start_address = start_address;
+        self.length = 0;
+
+        &mut self.bytes[..length]
+    }
+
+    /// Marks the `length` bytes read after `begin_fill` as valid.
+    pub(crate) fn commit(&mut self, length: usize) {
+        self.length = length;
+    }
+
+    pub(crate) fn invalidate(&mut self) {
+        self.length = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod entry_bytes {
+        use super::*;
+
+        #[test]
+        fn empty_buffer_returns_none() {
+            let buffer = DirectoryEntryBuffer::<DEFAULT_BUFFER_SIZE>::default();
+
+            assert_eq!(buffer.entry_bytes(ByteAddress::new(0)), None);
+        }
+
+        #[test]
+        fn committed_entry_returned() {
+            let mut buffer = DirectoryEntryBuffer::<DEFAULT_BUFFER_SIZE>::default();
+
+            let region = buffer.begin_fill(ByteAddress::new(0x100), 2 * DIRECTORY_ENTRY_SIZE);
+            region[DIRECTORY_ENTRY_SIZE] = 0x41;
+            buffer.commit(2 * DIRECTORY_ENTRY_SIZE);
+
+            let entry_bytes = buffer
+                .entry_bytes(ByteAddress::new(0x100 + DIRECTORY_ENTRY_SIZE as u64))
+                .expect("Some should be returned");
+
+            assert_eq!(entry_bytes[0], 0x41);
+        }
+
+        #[test]
+        fn uncommitted_fill_returns_none() {
+            let mut buffer = DirectoryEntryBuffer::<DEFAULT_BUFFER_SIZE>::default();
+
+            buffer.begin_fill(ByteAddress::new(0), DIRECTORY_ENTRY_SIZE);
+
+            assert_eq!(buffer.entry_bytes(ByteAddress::new(0)), None);
+        }
+
+        #[test]
+        fn address_outside_buffer_returns_none() {
+            let mut buffer = DirectoryEntryBuffer::<DEFAULT_BUFFER_SIZE>::default();
+
+            buffer.begin_fill(ByteAddress::new(0x100), DIRECTORY_ENTRY_SIZE);
+            buffer.commit(DIRECTORY_ENTRY_SIZE);
+
+            assert_eq!(buffer.entry_bytes(ByteAddress::new(0xE0)), None);
+            assert_eq!(buffer.entry_bytes(ByteAddress::new(0x120)), None);
+        }
+    }
+
+    mod invalidate {
+        use super::*;
+
+        #[test]
+        fn entries_no_longer_returned() {
+            let mut buffer = DirectoryEntryBuffer::<DEFAULT_BUFFER_SIZE>::default();
+
+            buffer.begin_fill(ByteAddress::new(0), DIRECTORY_ENTRY_SIZE);
+            buffer.commit(DIRECTORY_ENTRY_SIZE);
+            buffer.invalidate();
+
+            assert_eq!(buffer.entry_bytes(ByteAddress::new(0)), None);
+        }
+    }
+}
@@ -10,8 +10,16 @@ where
     DE: Error,
     SE: embedded_io::Error,
 {
-    AllocationTableEntryTypeUnexpected,
-    EntryInvalid(DirectoryEntryError),
+    AllocationTableEntryTypeUnexpected {
+        cluster_number: u32,
+    },
+    ClusterNumberInvalid {
+        cluster_number: u32,
+    },
+    EntryInvalid {
+        address: u64,
+        error: DirectoryEntryError,
+    },
     DeviceError(DE),
     StreamEndReached,
     StreamError(SE),
@@ -31,14 +39,23 @@ where
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
-            DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected => {
-                write!(f, "the allocation table entry was an unexpected type")
+            DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected { cluster_number } => {
+                write!(
+                    f,
+                    "the allocation table entry for cluster {cluster_number} was an unexpected type"
+                )
+            }
+            DirectoryEntryIterationError::ClusterNumberInvalid { cluster_number } => {
+                write!(
+                    f,
+                    "cluster {cluster_number} is not a valid data region cluster number"
+                )
             }
             DirectoryEntryIterationError::DeviceError(e) => {
                 write!(f, "device error occurred: {}", e)
             }
-            DirectoryEntryIterationError::EntryInvalid(e) => {
-                write!(f, "an entry was invalid: {}", e)
+            DirectoryEntryIterationError::EntryInvalid { address, error } => {
+                write!(f, "the entry at address 0x{address:X} was invalid: {error}")
             }
             DirectoryEntryIterationError::StreamEndReached => {
                 write!(f, "stream end was reached when not expected")
@@ -67,22 +84,15 @@ where
 {
     fn from(value: AllocationTableError<SE>) -> Self {
         match value {
+            AllocationTableError::EntryValueInvalid { cluster_number } => {
+                Self::ClusterNumberInvalid { cluster_number }
+            }
             AllocationTableError::StreamEndReached => Self::StreamEndReached,
             AllocationTableError::StreamError(device_error) => Self::StreamError(device_error),
         }
     }
 }
 
-impl<DE, SE> From<DirectoryEntryError> for DirectoryEntryIterationError<DE, SE>
-where
-    DE: Error,
-    SE: embedded_io::Error,
-{
-    fn from(value: DirectoryEntryError) -> Self {
-        Self::EntryInvalid(value)
-    }
-}
-
 impl<DE, SE> From<ReadExactError<SE>> for DirectoryEntryIterationError<DE, SE>
 where
     DE: Error,
@@ -110,9 +120,13 @@ mod tests {
         #[test]
         fn produces_non_empty_value() {
             let values = [
-                DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected,
-                DirectoryEntryIterationError::EntryInvalid(
-                    DirectoryEntryError::ShortNameEntryInvalid(
+                DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected {
+                    cluster_number: 2,
+                },
+                DirectoryEntryIterationError::ClusterNumberInvalid { cluster_number: 0 },
+                DirectoryEntryIterationError::EntryInvalid {
+                    address: 0,
+                    error: DirectoryEntryError::ShortNameEntryInvalid(
                         ShortNameDirectoryEntryError::NameInvalid(
                             ShortFileNameError::CharacterInvalid {
                                 character: 0x41,
@@ -120,7 +134,7 @@ mod tests {
                             },
                         ),
                     ),
-                ),
+                },
                 DirectoryEntryIterationError::DeviceError(IoError::default()),
                 DirectoryEntryIterationError::StreamEndReached,
                 DirectoryEntryIterationError::StreamError(IoError::default()),
@@ -1,49 +1,54 @@
 use crate::Device;
 use crate::allocation_table::{AllocationTable, AllocationTableEntry};
+use crate::directory_entry::iterator::buffer::{DEFAULT_BUFFER_SIZE, DirectoryEntryBuffer};
 use crate::directory_entry::{
     DIRECTORY_ENTRY_SIZE, DirectoryEntry, DirectoryEntryIterationError,
-    DirectoryEntryIteratorResult,
+    DirectoryEntryIteratorResult, DirectoryEntryPosition,
 };
+use crate::units::{ByteAddress, ClusterNumber};
+use core::cell::RefCell;
 use core::ops::DerefMut;
 use embedded_io::{ErrorType, SeekFrom};
 
 #[cfg(feature = "sync")]
 use {
     crate::SyncDevice,
-    embedded_io::{Read, Seek},
+    embedded_io::{Read, Seek, Write},
 };
 
 #[cfg(feature = "async")]
 use {
     crate::AsyncDevice,
-    embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek},
+    embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek, Write as AsyncWrite},
 };
 
 #[derive(Clone, Debug)]
-pub struct DirectoryFileEntryIterator<'a, D>
+pub struct DirectoryFileEntryIterator<'a, D, const BUFFER_SIZE: usize = DEFAULT_BUFFER_SIZE>
 where
     D: Device,
 {
     device: &'a D,
     allocation_table: &'a AllocationTable,
 
-    data_region_base_address: u64,
+    data_region_base_address: ByteAddress,
     bytes_per_cluster: u32,
 
-    current_cluster_number: u32,
+    current_cluster_number: ClusterNumber,
     current_cluster_offset: u32,
+
+    buffer: RefCell<DirectoryEntryBuffer<BUFFER_SIZE>>,
 }
 
-impl<'a, D> DirectoryFileEntryIterator<'a, D>
+impl<'a, D, const BUFFER_SIZE: usize> DirectoryFileEntryIterator<'a, D, BUFFER_SIZE>
 where
     D: Device,
 {
     pub fn new(
         device: &'a D,
         allocation_table: &'a AllocationTable,
-        data_region_base_address: u64,
+        data_region_base_address: ByteAddress,
         bytes_per_cluster: u32,
-        start_cluster_number: u32,
+        start_cluster_number: ClusterNumber,
     ) -> Self {
         Self {
             device,
@@ -54,13 +59,58 @@ where
 
             current_cluster_number: start_cluster_number,
             current_cluster_offset: 0,
+
+            buffer: RefCell::new(DirectoryEntryBuffer::default()),
         }
     }
 
-    fn current_address(&self) -> u64 {
-        self.data_region_base_address
-            + ((self.current_cluster_number - 2) as u64 * self.bytes_per_cluster as u64)
-            + self.current_cluster_offset as u64
+    pub fn position(&self) -> Option<DirectoryEntryPosition> {
+        if self.current_cluster_offset >= self.bytes_per_cluster {
+            return None;
+        }
+
+        Some(DirectoryEntryPosition::File {
+            cluster_number: self.current_cluster_number.value(),
+            cluster_offset: self.current_cluster_offset,
+        })
+    }
+
+    pub fn set_position(&mut self, cluster_number: ClusterNumber, cluster_offset: u32) {
+        self.current_cluster_number = cluster_number;
+        self.current_cluster_offset = cluster_offset;
+    }
+
+    /// Moves the iterator past the last entry.
+    pub fn finish(&mut self) {
+        self.current_cluster_offset = self.bytes_per_cluster;
+    }
+
+    fn current_cluster_address(&self) -> DirectoryEntryIteratorResult<ByteAddress, D> {
+        self.current_cluster_number
+            .byte_address(self.data_region_base_address, self.bytes_per_cluster)
+            .ok_or(DirectoryEntryIterationError::ClusterNumberInvalid {
+                cluster_number: self.current_cluster_number.value(),
+            })
+    }
+
+    fn current_address(&self) -> DirectoryEntryIteratorResult<ByteAddress, D> {
+        Ok(self
+            .current_cluster_address()?
+            .offset_by(self.current_cluster_offset as u64))
+    }
+
+    /// The address and length of the buffer-aligned run of entries containing the current entry,
+    /// limited to the current cluster.
+    fn buffer_window(&self) -> DirectoryEntryIteratorResult<(ByteAddress, usize), D> {
+        let window_offset =
+            self.current_cluster_offset - self.current_cluster_offset % BUFFER_SIZE as u32;
+        let window_length = (self.bytes_per_cluster - window_offset).min(BUFFER_SIZE as u32);
+
+        Ok((
+            self.current_cluster_address()?
+                .offset_by(window_offset as u64),
+            window_length as usize,
+        ))
     }
 
     fn advance_offset(&mut self) {
@@ -81,137 +131,182 @@ where
             AllocationTableEntry::EndOfFile => Ok(false),
             AllocationTableEntry::Free
             | AllocationTableEntry::BadSector
-            | AllocationTableEntry::Reserved => {
-                Err(DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected)
-            }
+            | AllocationTableEntry::Reserved => Err(
+                DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected {
+                    cluster_number: self.current_cluster_number.value(),
+                },
+            ),
         }
     }
 }
 
-#[cfg(feature = "sync")]
-impl<'a, D, S> DirectoryFileEntryIterator<'a, D>
-where
-    D: SyncDevice<Stream = S>,
-    S: Read + Seek,
-{
-    pub fn peek(&self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
-        if self.current_cluster_offset >= self.bytes_per_cluster {
+/// The body of `peek`, shared between the sync and async implementations.
+macro_rules! peek_body {
+    ($io:ident, $self:ident) => {{
+        if $self.current_cluster_offset >= $self.bytes_per_cluster {
             return None;
         }
 
-        let current_address = self.current_address();
+        let current_address = propagate_iteration_error!($self.current_address());
+        let mut buffer = $self.buffer.take();
 
-        let mut directory_entry_bytes = [0; DIRECTORY_ENTRY_SIZE];
+        if buffer.entry_bytes(current_address).is_none() {
+            let (window_address, window_length) = propagate_iteration_error!($self.buffer_window());
 
-        propagate_device_iteration_errors!(
-            self.device
-                .with_stream(|stream| -> DirectoryEntryIteratorResult<(), D> {
-                    stream.seek(SeekFrom::Start(current_address))?;
-                    stream.read_exact(&mut directory_entry_bytes)?;
+            propagate_device_iteration_errors!(
+                $io!(with_stream(
+                    $self.device,
+                    |stream| -> DirectoryEntryIteratorResult<(), D> {
+                        $io!(Seek::seek(stream, SeekFrom::Start(window_address.value())))?;
+                        $io!(Read::read_exact(
+                            stream,
+                            buffer.begin_fill(window_address, window_length)
+                        ))?;
+                        buffer.commit(window_length);
 
-                    Ok(())
-                })
+                        Ok(())
+                    }
+                ))
                 .map_err(DirectoryEntryIterationError::DeviceError)
-        );
+            );
+        }
 
-        Some(Ok(propagate_iteration_error!(DirectoryEntry::from_bytes(
-            &directory_entry_bytes
-        ))))
-    }
+        // The buffered window always contains the current entry
+        let directory_entry_bytes = buffer.entry_bytes(current_address)?;
+        $self.buffer.replace(buffer);
 
-    pub fn advance(&mut self) -> DirectoryEntryIteratorResult<bool, D> {
-        self.advance_offset();
+        Some(
+            DirectoryEntry::from_bytes(&directory_entry_bytes).map_err(|error| {
+                DirectoryEntryIterationError::EntryInvalid {
+                    address: current_address.value(),
+                    error,
+                }
+            }),
+        )
+    }};
+}
 
-        if self.current_cluster_offset < self.bytes_per_cluster {
+/// The body of `advance`, shared between the sync and async implementations.
+macro_rules! advance_body {
+    ($io:ident, $self:ident) => {{
+        $self.advance_offset();
+
+        if $self.current_cluster_offset < $self.bytes_per_cluster {
             return Ok(true);
         }
 
-        self.device
-            .with_stream(|stream| -> DirectoryEntryIteratorResult<bool, D> {
-                let allocation_table_entry = self
-                    .allocation_table
-                    .read_entry(stream, self.current_cluster_number)?;
+        $io!(with_stream($self.device, |stream| -> DirectoryEntryIteratorResult<bool, D> {
+            let allocation_table_entry = $io!(
+                $self.allocation_table.read_entry(stream, $self.current_cluster_number);
+                $self.allocation_table.read_entry_async(stream, $self.current_cluster_number)
+            )?;
 
-                self.try_advance_cluster(allocation_table_entry)
-            })
-            .map_err(DirectoryEntryIterationError::DeviceError)?
-    }
+            $self.try_advance_cluster(allocation_table_entry)
+        }))
+        .map_err(DirectoryEntryIterationError::DeviceError)?
+    }};
+}
 
-    pub fn next(&mut self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
-        let result = self.peek();
+/// The body of `next`, shared between the sync and async implementations.
+macro_rules! next_body {
+    ($io:ident, $self:ident) => {{
+        let result = $io!($self.peek(); $self.peek_async());
 
         if result.is_some() {
-            propagate_iteration_error!(self.advance());
+            propagate_iteration_error!($io!($self.advance(); $self.advance_async()));
         }
 
         result
-    }
+    }};
 }
 
-#[cfg(feature = "async")]
-impl<'a, D, S> DirectoryFileEntryIterator<'a, D>
-where
-    D: AsyncDevice<Stream = S>,
-    S: AsyncRead + AsyncSeek,
-{
-    pub async fn peek_async(&self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
-        if self.current_cluster_offset >= self.bytes_per_cluster {
-            return None;
-        }
+/// The body of `write`, shared between the sync and async implementations.
+macro_rules! write_body {
+    ($io:ident, $self:ident, $bytes:ident) => {{
+        ensure!(
+            $self.current_cluster_offset < $self.bytes_per_cluster,
+            DirectoryEntryIterationError::StreamEndReached
+        );
 
-        let current_address = self.current_address();
-        let mut directory_entry_bytes = [0; DIRECTORY_ENTRY_SIZE];
+        let current_address = $self.current_address()?;
+        $self.buffer.borrow_mut().invalidate();
 
-        propagate_device_iteration_errors!(
-            self.device
-                .with_stream(async |stream| -> DirectoryEntryIteratorResult<(), D> {
-                    stream.seek(SeekFrom::Start(current_address)).await?;
+        $io!(with_stream(
+            $self.device,
+            |stream| -> DirectoryEntryIteratorResult<(), D> {
+                $io!(Seek::seek(stream, SeekFrom::Start(current_address.value())))?;
+                $io!(Write::write_all(stream, $bytes))?;
 
-                    stream.read_exact(&mut directory_entry_bytes).await?;
+                Ok(())
+            }
+        ))
+        .map_err(DirectoryEntryIterationError::DeviceError)?
+    }};
+}
 
-                    Ok(())
-                })
-                .await
-                .map_err(DirectoryEntryIterationError::DeviceError)
-        );
+#[cfg(feature = "sync")]
+impl<'a, D, S, const BUFFER_SIZE: usize> DirectoryFileEntryIterator<'a, D, BUFFER_SIZE>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Seek,
+{
+    pub fn peek(&self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
+        peek_body!(sync_io, self)
+    }
 
-        Some(Ok(propagate_iteration_error!(DirectoryEntry::from_bytes(
-            &directory_entry_bytes
-        ))))
+    pub fn advance(&mut self) -> DirectoryEntryIteratorResult<bool, D> {
+        advance_body!(sync_io, self)
     }
 
-    pub async fn advance_async(&mut self) -> DirectoryEntryIteratorResult<bool, D> {
-        self.advance_offset();
+    pub fn next(&mut self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
+        next_body!(sync_io, self)
+    }
+}
 
-        if self.current_cluster_offset < self.bytes_per_cluster {
-            return Ok(true);
-        }
+#[cfg(feature = "sync")]
+impl<D, S, const BUFFER_SIZE: usize> DirectoryFileEntryIterator<'_, D, BUFFER_SIZE>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Write + Seek,
+{
+    /// Overwrites the start of the current entry with the provided bytes.
+    pub fn write(&self, bytes: &[u8]) -> DirectoryEntryIteratorResult<(), D> {
+        write_body!(sync_io, self, bytes)
+    }
+}
 
-        self.device
-            .with_stream(async |stream| -> DirectoryEntryIteratorResult<bool, D> {
-                let allocation_table_entry = self
-                    .allocation_table
-                    .read_entry_async(stream, self.current_cluster_number)
-                    .await?;
+#[cfg(feature = "async")]
+impl<'a, D, S, const BUFFER_SIZE: usize> DirectoryFileEntryIterator<'a, D, BUFFER_SIZE>
+where
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncSeek,
+{
+    pub async fn peek_async(&self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
+        peek_body!(async_io, self)
+    }
 
-                self.try_advance_cluster(allocation_table_entry)
-            })
-            .await
-            .map_err(DirectoryEntryIterationError::DeviceError)?
+    pub async fn advance_async(&mut self) -> DirectoryEntryIteratorResult<bool, D> {
+        advance_body!(async_io, self)
     }
 
     pub async fn next_async(&mut self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
-        let result = self.peek_async().await;
-
-        if result.is_some() {
-            propagate_iteration_error!(self.advance_async().await);
-        }
+        next_body!(async_io, self)
+    }
+}
 
-        result
+#[cfg(feature = "async")]
+impl<D, S, const BUFFER_SIZE: usize> DirectoryFileEntryIterator<'_, D, BUFFER_SIZE>
+where
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncWrite + AsyncSeek,
+{
+    /// Overwrites the start of the current entry with the provided bytes.
+    pub async fn write_async(&self, bytes: &[u8]) -> DirectoryEntryIteratorResult<(), D> {
+        write_body!(async_io, self, bytes)
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "fat32"))]
 mod tests {
     use super::*;
     use crate::directory_entry::FreeDirectoryEntry;
@@ -223,9 +318,197 @@ mod tests {
     use alloc::vec;
     use alloc::vec::Vec;
 
+    // Tests exercise the default buffer size unless they name another one
+    type DirectoryFileEntryIterator<'a, D> = super::DirectoryFileEntryIterator<'a, D>;
+
+    mod position {
+        use super::*;
+
+        #[test]
+        fn current_cluster_and_offset_returned() {
+            let test_instance = TestInstance::new(1, 2);
+            let mut iterator = test_instance.iterator();
+
+            iterator.advance_offset();
+
+            assert_eq!(
+                iterator.position(),
+                Some(DirectoryEntryPosition::File {
+                    cluster_number: 2,
+                    cluster_offset: DIRECTORY_ENTRY_SIZE as u32,
+                })
+            );
+        }
+
+        #[test]
+        fn end_of_cluster_returns_none() {
+            let test_instance = TestInstance::new(1, 1);
+            let mut iterator = test_instance.iterator();
+
+            iterator.advance_offset();
+
+            assert_eq!(iterator.position(), None);
+        }
+    }
+
+    mod finish {
+        use super::*;
+
+        #[test]
+        fn position_returns_none() {
+            let test_instance = TestInstance::new(1, 2);
+            let mut iterator = test_instance.iterator();
+
+            iterator.finish();
+
+            assert_eq!(iterator.position(), None);
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    mod set_position {
+        use super::*;
+
+        #[test]
+        fn iteration_resumed_at_position() {
+            let test_instance = TestInstance::new(2, 1);
+            let mut iterator = test_instance.iterator();
+
+            iterator.set_position(ClusterNumber::new(3), 0);
+
+            let result = iterator
+                .peek()
+                .expect("Some should be returned")
+                .expect("Ok should be returned");
+
+            assert!(
+                matches!(
+                    result,
+                    DirectoryEntry::Free(FreeDirectoryEntry::AllFollowing)
+                ),
+                "Entry of the second cluster should be returned"
+            );
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    mod write {
+        use super::*;
+
+        #[test]
+        fn buffered_entry_refreshed() {
+            let test_instance = TestInstance::new(1, 2);
+            let iterator = test_instance.iterator();
+
+            iterator
+                .peek()
+                .expect("Some should be returned")
+                .expect("Ok should be returned");
+            iterator.write(&[0x00]).expect("Ok should be returned");
+
+            let result = iterator
+                .peek()
+                .expect("Some should be returned")
+                .expect("Ok should be returned");
+
+            assert!(
+                matches!(
+                    result,
+                    DirectoryEntry::Free(FreeDirectoryEntry::AllFollowing)
+                ),
+                "Written entry should be returned"
+            );
+        }
+
+        #[test]
+        fn current_entry_overwritten() {
+            let test_instance = TestInstance::new(1, 2);
+            let iterator = test_instance.iterator();
+
+            iterator.write(&[0x00]).expect("Ok should be returned");
+
+            let result = iterator
+                .peek()
+                .expect("Some should be returned")
+                .expect("Ok should be returned");
+
+            assert!(
+                matches!(
+                    result,
+                    DirectoryEntry::Free(FreeDirectoryEntry::AllFollowing)
+                ),
+                "Written entry should be returned"
+            );
+        }
+
+        #[test]
+        fn end_of_cluster_returns_err() {
+            let test_instance = TestInstance::new(1, 1);
+            let mut iterator = test_instance.iterator();
+
+            iterator.advance_offset();
+
+            let error = iterator.write(&[0x00]).expect_err("Err should be returned");
+
+            assert!(
+                matches!(error, DirectoryEntryIterationError::StreamEndReached),
+                "StreamEndReached should be returned"
+            );
+        }
+    }
+
+    #[cfg(feature = "sync")]
     mod peek {
         use super::*;
 
+        #[test]
+        fn entry_in_buffered_sector_returned_without_device_access() {
+            let test_instance = TestInstance::new(1, 2);
+            let mut iterator = test_instance.iterator();
+
+            iterator
+                .peek()
+                .expect("Some should be returned")
+                .expect("Ok should be returned");
+            iterator.advance();
+
+            let result = SyncDevice::with_stream(&test_instance.device, |_| iterator.peek())
+                .expect("Device should not be in use");
+
+            assert!(
+                matches!(
+                    result,
+                    Some(Ok(DirectoryEntry::Free(FreeDirectoryEntry::AllFollowing)))
+                ),
+                "Buffered entry should be returned"
+            );
+        }
+
+        #[test]
+        fn invalid_cluster_number_returns_err() {
+            let test_instance = TestInstance::new(1, 1);
+            let iterator = DirectoryFileEntryIterator::new(
+                &test_instance.device,
+                &test_instance.allocation_table,
+                test_instance.data_region_base_address,
+                test_instance.bytes_per_cluster,
+                ClusterNumber::new(0),
+            );
+
+            let result = iterator
+                .peek()
+                .expect("Some should be returned")
+                .expect_err("Err should be returned");
+
+            assert!(
+                matches!(
+                    result,
+                    DirectoryEntryIterationError::ClusterNumberInvalid { cluster_number: 0 }
+                ),
+                "ClusterNumberInvalid error should be returned"
+            );
+        }
+
         #[test]
         fn initial_iteration_returns_first_entry() {
             let test_instance = TestInstance::new(1, 1);
@@ -317,14 +600,15 @@ mod tests {
                 IoError::default(),
                 ErroringStreamScenarios::SEEK,
             ));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -345,14 +629,15 @@ mod tests {
                 IoError::default(),
                 ErroringStreamScenarios::READ,
             ));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -369,14 +654,15 @@ mod tests {
         #[test]
         fn stream_end_reached_error_propagated() {
             let device = SingleAccessDevice::new(DataStream::from_bytes([]));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -392,14 +678,15 @@ mod tests {
 
         #[test]
         fn device_err_propagated() {
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let iterator = DirectoryFileEntryIterator::new(
                 &ErroringDevice,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -419,14 +706,15 @@ mod tests {
             data[0] = 0x20;
 
             let device = SingleAccessDevice::new(DataStream::from_bytes(data));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -435,12 +723,13 @@ mod tests {
                 .expect_err("Err should be returned");
 
             assert!(
-                matches!(error, DirectoryEntryIterationError::EntryInvalid(_)),
+                matches!(error, DirectoryEntryIterationError::EntryInvalid { .. }),
                 "EntryInvalid should be returned"
             );
         }
     }
 
+    #[cfg(feature = "sync")]
     mod advance {
         use super::*;
 
@@ -479,14 +768,15 @@ mod tests {
             write_le_u32(&mut data, 8, 0);
 
             let device = SingleAccessDevice::new(DataStream::from_bytes(data));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                12,
+                ByteAddress::new(12),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator.advance().expect_err("Err should be returned");
@@ -494,7 +784,9 @@ mod tests {
             assert!(
                 matches!(
                     error,
-                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected
+                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected {
+                        cluster_number: 2
+                    }
                 ),
                 "AllocationTableEntryTypeUnexpected should be returned"
             );
@@ -506,14 +798,15 @@ mod tests {
             write_le_u32(&mut data, 8, 1);
 
             let device = SingleAccessDevice::new(DataStream::from_bytes(data));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                12,
+                ByteAddress::new(12),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator.advance().expect_err("Err should be returned");
@@ -521,7 +814,9 @@ mod tests {
             assert!(
                 matches!(
                     error,
-                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected
+                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected {
+                        cluster_number: 2
+                    }
                 ),
                 "AllocationTableEntryTypeUnexpected should be returned"
             );
@@ -533,14 +828,15 @@ mod tests {
             write_le_u32(&mut data, 8, AllocationTableKind::Fat32.bad_sector_value());
 
             let device = SingleAccessDevice::new(DataStream::from_bytes(data));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                12,
+                ByteAddress::new(12),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator.advance().expect_err("Err should be returned");
@@ -548,7 +844,9 @@ mod tests {
             assert!(
                 matches!(
                     error,
-                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected
+                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected {
+                        cluster_number: 2
+                    }
                 ),
                 "AllocationTableEntryTypeUnexpected should be returned"
             );
@@ -561,14 +859,15 @@ mod tests {
                 IoError::default(),
                 ErroringStreamScenarios::SEEK,
             ));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator.advance().expect_err("Err should be returned");
@@ -586,14 +885,15 @@ mod tests {
                 IoError::default(),
                 ErroringStreamScenarios::READ,
             ));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator.advance().expect_err("Err should be returned");
@@ -607,14 +907,15 @@ mod tests {
         #[test]
         fn stream_end_reached_error_propagated() {
             let device = SingleAccessDevice::new(DataStream::from_bytes([]));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator.advance().expect_err("Err should be returned");
@@ -627,14 +928,15 @@ mod tests {
 
         #[test]
         fn device_error_propagated() {
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &ErroringDevice,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator.advance().expect_err("Err should be returned");
@@ -646,6 +948,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "sync")]
     mod next {
         use super::*;
 
@@ -718,14 +1021,15 @@ mod tests {
             write_le_u32(&mut data, 8, 0);
 
             let device = SingleAccessDevice::new(DataStream::from_bytes(data));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                12,
+                ByteAddress::new(12),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -736,7 +1040,9 @@ mod tests {
             assert!(
                 matches!(
                     error,
-                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected
+                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected {
+                        cluster_number: 2
+                    }
                 ),
                 "AllocationTableEntryTypeUnexpected should be returned"
             );
@@ -748,14 +1054,15 @@ mod tests {
             write_le_u32(&mut data, 8, 1);
 
             let device = SingleAccessDevice::new(DataStream::from_bytes(data));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                12,
+                ByteAddress::new(12),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -766,7 +1073,9 @@ mod tests {
             assert!(
                 matches!(
                     error,
-                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected
+                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected {
+                        cluster_number: 2
+                    }
                 ),
                 "AllocationTableEntryTypeUnexpected should be returned"
             );
@@ -778,14 +1087,15 @@ mod tests {
             write_le_u32(&mut data, 8, AllocationTableKind::Fat32.bad_sector_value());
 
             let device = SingleAccessDevice::new(DataStream::from_bytes(data));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                12,
+                ByteAddress::new(12),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -796,7 +1106,9 @@ mod tests {
             assert!(
                 matches!(
                     error,
-                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected
+                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected {
+                        cluster_number: 2
+                    }
                 ),
                 "AllocationTableEntryTypeUnexpected should be returned"
             );
@@ -809,14 +1121,15 @@ mod tests {
                 IoError::default(),
                 ErroringStreamScenarios::SEEK,
             ));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -837,14 +1150,15 @@ mod tests {
                 IoError::default(),
                 ErroringStreamScenarios::READ,
             ));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -861,14 +1175,15 @@ mod tests {
         #[test]
         fn stream_end_reached_error_propagated() {
             let device = SingleAccessDevice::new(DataStream::from_bytes([]));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -884,14 +1199,15 @@ mod tests {
 
         #[test]
         fn device_error_propagated() {
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &ErroringDevice,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -906,6 +1222,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "async")]
     mod peek_async {
         use super::*;
 
@@ -915,7 +1232,8 @@ mod tests {
             let iterator = test_instance.iterator();
 
             let result = iterator
-                .peek()
+                .peek_async()
+                .await
                 .expect("Some should be returned")
                 .expect("Ok should be returned");
 
@@ -933,10 +1251,11 @@ mod tests {
             let test_instance = TestInstance::new(1, 2);
             let mut iterator = test_instance.iterator();
 
-            iterator.advance();
+            iterator.advance_async().await;
 
             let result = iterator
-                .peek()
+                .peek_async()
+                .await
                 .expect("Some should be returned")
                 .expect("Ok should be returned");
 
@@ -988,7 +1307,7 @@ mod tests {
             let test_instance = TestInstance::new(1, 1);
             let mut iterator = test_instance.iterator();
 
-            iterator.advance();
+            iterator.advance_async().await;
 
             let result = iterator.peek_async().await;
 
@@ -1002,14 +1321,15 @@ mod tests {
                 IoError::default(),
                 ErroringStreamScenarios::SEEK,
             ));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -1031,14 +1351,15 @@ mod tests {
                 IoError::default(),
                 ErroringStreamScenarios::READ,
             ));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -1056,14 +1377,15 @@ mod tests {
         #[tokio::test]
         async fn stream_end_reached_error_propagated() {
             let device = SingleAccessDevice::new(DataStream::from_bytes([]));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -1080,14 +1402,15 @@ mod tests {
 
         #[tokio::test]
         async fn device_err_propagated() {
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let iterator = DirectoryFileEntryIterator::new(
                 &ErroringDevice,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -1108,14 +1431,15 @@ mod tests {
             data[0] = 0x20;
 
             let device = SingleAccessDevice::new(DataStream::from_bytes(data));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -1125,12 +1449,13 @@ mod tests {
                 .expect_err("Err should be returned");
 
             assert!(
-                matches!(error, DirectoryEntryIterationError::EntryInvalid(_)),
+                matches!(error, DirectoryEntryIterationError::EntryInvalid { .. }),
                 "EntryInvalid should be returned"
             );
         }
     }
 
+    #[cfg(feature = "async")]
     mod advance_async {
         use super::*;
 
@@ -1178,14 +1503,15 @@ mod tests {
             write_le_u32(&mut data, 8, 0);
 
             let device = SingleAccessDevice::new(DataStream::from_bytes(data));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                12,
+                ByteAddress::new(12),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -1196,7 +1522,9 @@ mod tests {
             assert!(
                 matches!(
                     error,
-                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected
+                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected {
+                        cluster_number: 2
+                    }
                 ),
                 "AllocationTableEntryTypeUnexpected should be returned"
             );
@@ -1208,14 +1536,15 @@ mod tests {
             write_le_u32(&mut data, 8, 1);
 
             let device = SingleAccessDevice::new(DataStream::from_bytes(data));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                12,
+                ByteAddress::new(12),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -1226,7 +1555,9 @@ mod tests {
             assert!(
                 matches!(
                     error,
-                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected
+                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected {
+                        cluster_number: 2
+                    }
                 ),
                 "AllocationTableEntryTypeUnexpected should be returned"
             );
@@ -1238,14 +1569,15 @@ mod tests {
             write_le_u32(&mut data, 8, AllocationTableKind::Fat32.bad_sector_value());
 
             let device = SingleAccessDevice::new(DataStream::from_bytes(data));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                12,
+                ByteAddress::new(12),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -1256,7 +1588,9 @@ mod tests {
             assert!(
                 matches!(
                     error,
-                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected
+                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected {
+                        cluster_number: 2
+                    }
                 ),
                 "AllocationTableEntryTypeUnexpected should be returned"
             );
@@ -1269,14 +1603,15 @@ mod tests {
                 IoError::default(),
                 ErroringStreamScenarios::SEEK,
             ));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -1297,14 +1632,15 @@ mod tests {
                 IoError::default(),
                 ErroringStreamScenarios::READ,
             ));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -1321,14 +1657,15 @@ mod tests {
         #[tokio::test]
         async fn stream_end_reached_error_propagated() {
             let device = SingleAccessDevice::new(DataStream::from_bytes([]));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -1344,14 +1681,15 @@ mod tests {
 
         #[tokio::test]
         async fn device_error_propagated() {
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &ErroringDevice,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -1366,6 +1704,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "async")]
     mod next_async {
         use super::*;
 
@@ -1428,7 +1767,7 @@ mod tests {
             let test_instance = TestInstance::new(1, 1);
             let mut iterator = test_instance.iterator();
 
-            iterator.advance();
+            iterator.advance_async().await;
 
             let result = iterator.next_async().await;
 
@@ -1441,14 +1780,15 @@ mod tests {
             write_le_u32(&mut data, 8, 0);
 
             let device = SingleAccessDevice::new(DataStream::from_bytes(data));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                12,
+                ByteAddress::new(12),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -1460,7 +1800,9 @@ mod tests {
             assert!(
                 matches!(
                     error,
-                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected
+                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected {
+                        cluster_number: 2
+                    }
                 ),
                 "AllocationTableEntryTypeUnexpected should be returned"
             );
@@ -1472,14 +1814,15 @@ mod tests {
             write_le_u32(&mut data, 8, 1);
 
             let device = SingleAccessDevice::new(DataStream::from_bytes(data));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                12,
+                ByteAddress::new(12),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -1491,7 +1834,9 @@ mod tests {
             assert!(
                 matches!(
                     error,
-                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected
+                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected {
+                        cluster_number: 2
+                    }
                 ),
                 "AllocationTableEntryTypeUnexpected should be returned"
             );
@@ -1503,14 +1848,15 @@ mod tests {
             write_le_u32(&mut data, 8, AllocationTableKind::Fat32.bad_sector_value());
 
             let device = SingleAccessDevice::new(DataStream::from_bytes(data));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                12,
+                ByteAddress::new(12),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -1522,7 +1868,9 @@ mod tests {
             assert!(
                 matches!(
                     error,
-                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected
+                    DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected {
+                        cluster_number: 2
+                    }
                 ),
                 "AllocationTableEntryTypeUnexpected should be returned"
             );
@@ -1535,14 +1883,15 @@ mod tests {
                 IoError::default(),
                 ErroringStreamScenarios::SEEK,
             ));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -1564,14 +1913,15 @@ mod tests {
                 IoError::default(),
                 ErroringStreamScenarios::READ,
             ));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -1589,14 +1939,15 @@ mod tests {
         #[tokio::test]
         async fn stream_end_reached_error_propagated() {
             let device = SingleAccessDevice::new(DataStream::from_bytes([]));
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &device,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -1613,14 +1964,15 @@ mod tests {
 
         #[tokio::test]
         async fn device_error_propagated() {
-            let allocation_table = AllocationTable::new(AllocationTableKind::Fat32, 0);
+            let allocation_table =
+                AllocationTable::new(AllocationTableKind::Fat32, ByteAddress::new(0));
 
             let mut iterator = DirectoryFileEntryIterator::new(
                 &ErroringDevice,
                 &allocation_table,
-                0,
+                ByteAddress::new(0),
                 DIRECTORY_ENTRY_SIZE as u32,
-                2,
+                ClusterNumber::new(2),
             );
 
             let error = iterator
@@ -1642,7 +1994,7 @@ mod tests {
         device: TestInstanceDevice,
         allocation_table: AllocationTable,
 
-        data_region_base_address: u64,
+        data_region_base_address: ByteAddress,
         bytes_per_cluster: u32,
     }
 
@@ -1684,9 +2036,12 @@ mod tests {
 
             Self {
                 device: DataStream::from_bytes(data).into(),
-                allocation_table: AllocationTable::new(AllocationTableKind::Fat32, 0),
+                allocation_table: AllocationTable::new(
+                    AllocationTableKind::Fat32,
+                    ByteAddress::new(0),
+                ),
 
-                data_region_base_address: data_region_base_address as u64,
+                data_region_base_address: ByteAddress::new(data_region_base_address as u64),
                 bytes_per_cluster: (entries_per_cluster * DIRECTORY_ENTRY_SIZE) as u32,
             }
         }
@@ -1697,7 +2052,7 @@ mod tests {
                 &self.allocation_table,
                 self.data_region_base_address,
                 self.bytes_per_cluster,
-                2,
+                ClusterNumber::new(2),
             )
         }
     }
@@ -1,40 +1,45 @@
 use crate::Device;
+use crate::directory_entry::iterator::buffer::{DEFAULT_BUFFER_SIZE, DirectoryEntryBuffer};
 use crate::directory_entry::{
     DIRECTORY_ENTRY_SIZE, DirectoryEntry, DirectoryEntryIterationError,
-    DirectoryEntryIteratorResult,
+    DirectoryEntryIteratorResult, DirectoryEntryPosition,
 };
+use crate::units::ByteAddress;
+use core::cell::RefCell;
 use embedded_io::{ErrorType, SeekFrom};
 
 #[cfg(feature = "sync")]
 use {
     crate::SyncDevice,
-    embedded_io::{Read, Seek},
+    embedded_io::{Read, Seek, Write},
 };
 
 #[cfg(feature = "async")]
 use {
     crate::AsyncDevice,
-    embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek},
+    embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek, Write as AsyncWrite},
 };
 
 #[derive(Clone, Debug)]
-pub struct DirectoryTableEntryIterator<'a, D>
+pub struct DirectoryTableEntryIterator<'a, D, const BUFFER_SIZE: usize = DEFAULT_BUFFER_SIZE>
 where
     D: Device,
 {
     device: &'a D,
 
-    start_address: u64,
+    start_address: ByteAddress,
     entry_count: u16,
 
     current_entry_index: Option<u16>,
+
+    buffer: RefCell<DirectoryEntryBuffer<BUFFER_SIZE>>,
 }
 
-impl<'a, D> DirectoryTableEntryIterator<'a, D>
+impl<'a, D, const BUFFER_SIZE: usize> DirectoryTableEntryIterator<'a, D, BUFFER_SIZE>
 where
     D: Device,
 {
-    pub fn new(device: &'a D, start_address: u64, entry_count: u16) -> Self {
+    pub fn new(device: &'a D, start_address: ByteAddress, entry_count: u16) -> Self {
         Self {
             device,
 
@@ -42,6 +47,8 @@ where
             entry_count,
 
             current_entry_index: if entry_count > 0 { Some(0) } else { None },
+
+            buffer: RefCell::new(DirectoryEntryBuffer::default()),
         }
     }
 
@@ -60,86 +67,182 @@ where
         self.current_entry_index.is_some()
     }
 
-    fn current_address(&self) -> Option<u64> {
+    pub fn position(&self) -> Option<DirectoryEntryPosition> {
+        self.current_entry_index
+            .map(|entry_index| DirectoryEntryPosition::Table { entry_index })
+    }
+
+    pub fn set_position(&mut self, entry_index: u16) {
+        self.current_entry_index = if entry_index < self.entry_count {
+            Some(entry_index)
+        } else {
+            None
+        };
+    }
+
+    /// The number of entries from the current one to the end of the table.
+    pub fn remaining_entry_count(&self) -> usize {
+        self.current_entry_index
+            .map_or(0, |entry_index| (self.entry_count - entry_index) as usize)
+    }
+
+    /// Moves the iterator past the last entry.
+    pub fn finish(&mut self) {
+        self.current_entry_index = None;
+    }
+
+    fn current_address(&self) -> Option<ByteAddress> {
         self.current_entry_index.map(|current_entry_index| {
-            self.start_address + (current_entry_index as u64 * DIRECTORY_ENTRY_SIZE as u64)
+            self.start_address
+                .offset_by(current_entry_index as u64 * DIRECTORY_ENTRY_SIZE as u64)
         })
     }
+
+    /// The address and length of the buffer-aligned run of entries containing the current entry,
+    /// limited to the end of the table.
+    fn buffer_window(&self) -> Option<(ByteAddress, usize)> {
+        let entries_per_window = (BUFFER_SIZE / DIRECTORY_ENTRY_SIZE) as u16;
+
+        let current_entry_index = self.current_entry_index?;
+        let window_entry_index = current_entry_index - current_entry_index % entries_per_window;
+        let window_entry_count = (self.entry_count - window_entry_index).min(entries_per_window);
+
+        Some((
+            self.start_address
+                .offset_by(window_entry_index as u64 * DIRECTORY_ENTRY_SIZE as u64),
+            window_entry_count as usize * DIRECTORY_ENTRY_SIZE,
+        ))
+    }
+}
+
+/// The body of `peek`, shared between the sync and async implementations.
+macro_rules! peek_body {
+    ($io:ident, $self:ident) => {{
+        let current_address = $self.current_address()?;
+        let mut buffer = $self.buffer.take();
+
+        if buffer.entry_bytes(current_address).is_none() {
+            let (window_address, window_length) = $self.buffer_window()?;
+
+            // Weird, but we need to unwrap two results
+            propagate_device_iteration_errors!(
+                $io!(with_stream(
+                    $self.device,
+                    |stream| -> DirectoryEntryIteratorResult<(), D> {
+                        $io!(Seek::seek(stream, SeekFrom::Start(window_address.value())))?;
+                        $io!(Read::read_exact(
+                            stream,
+                            buffer.begin_fill(window_address, window_length)
+                        ))?;
+                        buffer.commit(window_length);
+
+                        Ok(())
+                    }
+                ))
+                .map_err(DirectoryEntryIterationError::DeviceError)
+            );
+        }
+
+        // The buffered window always contains the current entry
+        let directory_entry_bytes = buffer.entry_bytes(current_address)?;
+        $self.buffer.replace(buffer);
+
+        Some(
+            DirectoryEntry::from_bytes(&directory_entry_bytes).map_err(|error| {
+                DirectoryEntryIterationError::EntryInvalid {
+                    address: current_address.value(),
+                    error,
+                }
+            }),
+        )
+    }};
+}
+
+/// The body of `next`, shared between the sync and async implementations.
+macro_rules! next_body {
+    ($io:ident, $self:ident) => {{
+        let result = $io!($self.peek(); $self.peek_async());
+
+        if result.is_some() {
+            $self.advance();
+        }
+
+        result
+    }};
+}
+
+/// The body of `write`, shared between the sync and async implementations.
+macro_rules! write_body {
+    ($io:ident, $self:ident, $bytes:ident) => {{
+        let current_address = $self
+            .current_address()
+            .ok_or(DirectoryEntryIterationError::StreamEndReached)?;
+        $self.buffer.borrow_mut().invalidate();
+
+        $io!(with_stream(
+            $self.device,
+            |stream| -> DirectoryEntryIteratorResult<(), D> {
+                $io!(Seek::seek(stream, SeekFrom::Start(current_address.value())))?;
+                $io!(Write::write_all(stream, $bytes))?;
+
+                Ok(())
+            }
+        ))
+        .map_err(DirectoryEntryIterationError::DeviceError)?
+    }};
 }
 
 #[cfg(feature = "sync")]
-impl<D, S> DirectoryTableEntryIterator<'_, D>
+impl<D, S, const BUFFER_SIZE: usize> DirectoryTableEntryIterator<'_, D, BUFFER_SIZE>
 where
     D: SyncDevice<Stream = S>,
     S: Read + Seek,
 {
     pub fn peek(&self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
-        let current_address = self.current_address()?;
-        let mut directory_entry_bytes = [0; DIRECTORY_ENTRY_SIZE];
-
-        propagate_device_iteration_errors!(
-            self.device
-                .with_stream(|stream| -> DirectoryEntryIteratorResult<(), D> {
-                    stream.seek(SeekFrom::Start(current_address))?;
-                    stream.read_exact(&mut directory_entry_bytes)?;
-
-                    Ok(())
-                })
-                .map_err(DirectoryEntryIterationError::DeviceError)
-        );
-
-        Some(Ok(propagate_iteration_error!(DirectoryEntry::from_bytes(
-            &directory_entry_bytes
-        ))))
+        peek_body!(sync_io, self)
     }
 
     pub fn next(&mut self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
-        let result = self.peek();
-
-        if result.is_some() {
-            self.advance();
-        }
+        next_body!(sync_io, self)
+    }
+}
 
-        result
+#[cfg(feature = "sync")]
+impl<D, S, const BUFFER_SIZE: usize> DirectoryTableEntryIterator<'_, D, BUFFER_SIZE>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Write + Seek,
+{
+    /// Overwrites the start of the current entry with the provided bytes.
+    pub fn write(&self, bytes: &[u8]) -> DirectoryEntryIteratorResult<(), D> {
+        write_body!(sync_io, self, bytes)
     }
 }
 
 #[cfg(feature = "async")]
-impl<D, S> DirectoryTableEntryIterator<'_, D>
+impl<D, S, const BUFFER_SIZE: usize> DirectoryTableEntryIterator<'_, D, BUFFER_SIZE>
 where
     D: AsyncDevice<Stream = S>,
     S: AsyncRead + AsyncSeek,
 {
     pub async fn peek_async(&self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
-        let current_address = self.current_address()?;
-        let mut directory_entry_bytes = [0; DIRECTORY_ENTRY_SIZE];
-
-        // Weird, but we need to unwrap two results
-        propagate_device_iteration_errors!(
-            self.device
-                .with_stream(async |stream| -> DirectoryEntryIteratorResult<(), D> {
-                    stream.seek(SeekFrom::Start(current_address)).await?;
-                    stream.read_exact(&mut directory_entry_bytes).await?;
-
-                    Ok(())
-                })
-                .await
-                .map_err(DirectoryEntryIterationError::DeviceError)
-        );
-
-        Some(Ok(propagate_iteration_error!(DirectoryEntry::from_bytes(
-            &directory_entry_bytes
-        ))))
+        peek_body!(async_io, self)
     }
 
     pub async fn next_async(&mut self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
-        let result = self.peek_async().await;
-
-        if result.is_some() {
-            self.advance();
-        }
+        next_body!(async_io, self)
+    }
+}
 
-        result
+#[cfg(feature = "async")]
+impl<D, S, const BUFFER_SIZE: usize> DirectoryTableEntryIterator<'_, D, BUFFER_SIZE>
+where
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncWrite + AsyncSeek,
+{
+    /// Overwrites the start of the current entry with the provided bytes.
+    pub async fn write_async(&self, bytes: &[u8]) -> DirectoryEntryIteratorResult<(), D> {
+        write_body!(async_io, self, bytes)
     }
 }
 
@@ -154,13 +257,16 @@ mod tests {
     use alloc::vec;
     use alloc::vec::Vec;
 
+    // Tests exercise the default buffer size unless they name another one
+    type DirectoryTableEntryIterator<'a, D> = super::DirectoryTableEntryIterator<'a, D>;
+
     mod advance {
         use super::*;
 
         #[test]
         fn next_entry_exists_returns_true() {
             let device = SingleAccessDevice::new(VoidStream::new());
-            let mut iterator = DirectoryTableEntryIterator::new(&device, 0, 2);
+            let mut iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 2);
 
             let result = iterator.advance();
 
@@ -170,7 +276,7 @@ mod tests {
         #[test]
         fn no_next_entry_returns_false() {
             let device = SingleAccessDevice::new(VoidStream::new());
-            let mut iterator = DirectoryTableEntryIterator::new(&device, 0, 1);
+            let mut iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 1);
 
             let result = iterator.advance();
             assert_eq!(result, false, "False should be returned");
@@ -179,16 +285,147 @@ mod tests {
         #[test]
         fn no_current_entry_returns_false() {
             let device = SingleAccessDevice::new(VoidStream::new());
-            let mut iterator = DirectoryTableEntryIterator::new(&device, 0, 0);
+            let mut iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 0);
 
             let result = iterator.advance();
             assert_eq!(result, false, "False should be returned");
         }
     }
 
+    mod position {
+        use super::*;
+
+        #[test]
+        fn current_entry_index_returned() {
+            let device = SingleAccessDevice::new(VoidStream::new());
+            let mut iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 2);
+
+            iterator.advance();
+
+            assert_eq!(
+                iterator.position(),
+                Some(DirectoryEntryPosition::Table { entry_index: 1 })
+            );
+        }
+
+        #[test]
+        fn no_current_entry_returns_none() {
+            let device = SingleAccessDevice::new(VoidStream::new());
+            let iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 0);
+
+            assert_eq!(iterator.position(), None);
+        }
+    }
+
+    mod finish {
+        use super::*;
+
+        #[test]
+        fn position_returns_none() {
+            let device = SingleAccessDevice::new(VoidStream::new());
+            let mut iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 3);
+
+            iterator.finish();
+
+            assert_eq!(iterator.position(), None);
+        }
+    }
+
+    mod set_position {
+        use super::*;
+
+        #[test]
+        fn position_restored() {
+            let device = SingleAccessDevice::new(VoidStream::new());
+            let mut iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 3);
+
+            iterator.set_position(2);
+
+            assert_eq!(
+                iterator.position(),
+                Some(DirectoryEntryPosition::Table { entry_index: 2 })
+            );
+        }
+
+        #[test]
+        fn out_of_range_position_exhausts_iterator() {
+            let device = SingleAccessDevice::new(VoidStream::new());
+            let mut iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 3);
+
+            iterator.set_position(3);
+
+            assert_eq!(iterator.position(), None);
+        }
+    }
+
+    #[cfg(feature = "sync")]
     mod peek {
         use super::*;
 
+        #[test]
+        fn entry_in_buffered_sector_returned_without_device_access() {
+            let test_instance = TestInstance::new(2);
+            let mut iterator = test_instance.iterator();
+
+            iterator
+                .peek()
+                .expect("Some should be returned")
+                .expect("Ok should be returned");
+            iterator.advance();
+
+            let result = SyncDevice::with_stream(&test_instance.device, |_| iterator.peek())
+                .expect("Device should not be in use");
+
+            assert!(
+                matches!(
+                    result,
+                    Some(Ok(DirectoryEntry::Free(FreeDirectoryEntry::AllFollowing)))
+                ),
+                "Buffered entry should be returned"
+            );
+        }
+
+        #[test]
+        fn entry_past_buffer_size_read_from_device() {
+            let test_instance = TestInstance::new(2);
+            let mut iterator =
+                super::super::DirectoryTableEntryIterator::<_, DIRECTORY_ENTRY_SIZE>::new(
+                    &test_instance.device,
+                    ByteAddress::new(0),
+                    2,
+                );
+
+            iterator
+                .peek()
+                .expect("Some should be returned")
+                .expect("Ok should be returned");
+            iterator.advance();
+
+            let result = SyncDevice::with_stream(&test_instance.device, |_| iterator.peek())
+                .expect("Device should not be in use");
+
+            assert!(
+                matches!(
+                    result,
+                    Some(Err(DirectoryEntryIterationError::DeviceError(_)))
+                ),
+                "DeviceError should be returned"
+            );
+
+            let result = iterator
+                .peek()
+                .expect("Some should be returned")
+                .expect("Ok should be returned");
+
+            assert!(
+                matches!(
+                    result,
+                    DirectoryEntry::Free(FreeDirectoryEntry::AllFollowing)
+                ),
+                "Second entry should be returned"
+            );
+        }
+
         #[test]
         fn initial_iteration_returns_first_entry() {
             let test_instance = TestInstance::new(1);
@@ -279,7 +516,7 @@ mod tests {
                 ErroringStreamScenarios::SEEK,
             ));
 
-            let iterator = DirectoryTableEntryIterator::new(&device, 1, 1);
+            let iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(1), 1);
 
             let error = iterator
                 .peek()
@@ -300,7 +537,7 @@ mod tests {
                 ErroringStreamScenarios::READ,
             ));
 
-            let iterator = DirectoryTableEntryIterator::new(&device, 0, 1);
+            let iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 1);
 
             let error = iterator
                 .peek()
@@ -317,7 +554,7 @@ mod tests {
         fn stream_end_reached_error_propagated() {
             let device = SingleAccessDevice::new(DataStream::from_bytes([]));
 
-            let iterator = DirectoryTableEntryIterator::new(&device, 0, 1);
+            let iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 1);
 
             let error = iterator
                 .peek()
@@ -332,7 +569,8 @@ mod tests {
 
         #[test]
         fn device_err_propagated() {
-            let iterator = DirectoryTableEntryIterator::new(&ErroringDevice, 0, 1);
+            let iterator =
+                DirectoryTableEntryIterator::new(&ErroringDevice, ByteAddress::new(0), 1);
 
             let error = iterator
                 .peek()
@@ -352,7 +590,7 @@ mod tests {
 
             let device = SingleAccessDevice::new(DataStream::from_bytes(data));
 
-            let iterator = DirectoryTableEntryIterator::new(&device, 0, 1);
+            let iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 1);
 
             let error = iterator
                 .peek()
@@ -360,12 +598,38 @@ mod tests {
                 .expect_err("Err should be returned");
 
             assert!(
-                matches!(error, DirectoryEntryIterationError::EntryInvalid(_)),
+                matches!(error, DirectoryEntryIterationError::EntryInvalid { .. }),
                 "EntryInvalid should be returned"
             );
         }
+
+        #[test]
+        fn invalid_directory_entry_error_includes_address() {
+            let mut data = [0; 2 * DIRECTORY_ENTRY_SIZE];
+            data[0] = 0xE5;
+            data[DIRECTORY_ENTRY_SIZE] = 0x20;
+
+            let device = SingleAccessDevice::new(DataStream::from_bytes(data));
+
+            let mut iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 2);
+            iterator.advance();
+
+            let error = iterator
+                .peek()
+                .expect("Some should be returned")
+                .expect_err("Err should be returned");
+
+            assert!(
+                matches!(
+                    error,
+                    DirectoryEntryIterationError::EntryInvalid { address: 0x20, .. }
+                ),
+                "EntryInvalid should be returned with the entry's address"
+            );
+        }
     }
 
+    #[cfg(feature = "sync")]
     mod next {
         use super::*;
 
@@ -429,7 +693,7 @@ mod tests {
                 ErroringStreamScenarios::SEEK,
             ));
 
-            let mut iterator = DirectoryTableEntryIterator::new(&device, 1, 1);
+            let mut iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(1), 1);
 
             let error = iterator
                 .next()
@@ -450,7 +714,7 @@ mod tests {
                 ErroringStreamScenarios::READ,
             ));
 
-            let mut iterator = DirectoryTableEntryIterator::new(&device, 0, 1);
+            let mut iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 1);
 
             let error = iterator
                 .next()
@@ -467,7 +731,7 @@ mod tests {
         fn stream_end_reached_error_propagated() {
             let device = SingleAccessDevice::new(DataStream::from_bytes([]));
 
-            let mut iterator = DirectoryTableEntryIterator::new(&device, 0, 1);
+            let mut iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 1);
 
             let error = iterator
                 .next()
@@ -482,7 +746,8 @@ mod tests {
 
         #[test]
         fn device_err_propagated() {
-            let mut iterator = DirectoryTableEntryIterator::new(&ErroringDevice, 0, 1);
+            let mut iterator =
+                DirectoryTableEntryIterator::new(&ErroringDevice, ByteAddress::new(0), 1);
 
             let error = iterator
                 .next()
@@ -502,7 +767,7 @@ mod tests {
 
             let device = SingleAccessDevice::new(DataStream::from_bytes(data));
 
-            let mut iterator = DirectoryTableEntryIterator::new(&device, 0, 1);
+            let mut iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 1);
 
             let error = iterator
                 .next()
@@ -510,12 +775,77 @@ mod tests {
                 .expect_err("Err should be returned");
 
             assert!(
-                matches!(error, DirectoryEntryIterationError::EntryInvalid(_)),
+                matches!(error, DirectoryEntryIterationError::EntryInvalid { .. }),
                 "EntryInvalid should be returned"
             );
         }
     }
 
+    #[cfg(feature = "sync")]
+    mod write {
+        use super::*;
+
+        #[test]
+        fn buffered_entry_refreshed() {
+            let test_instance = TestInstance::new(2);
+            let iterator = test_instance.iterator();
+
+            iterator
+                .peek()
+                .expect("Some should be returned")
+                .expect("Ok should be returned");
+            iterator.write(&[0x00]).expect("Ok should be returned");
+
+            let result = iterator
+                .peek()
+                .expect("Some should be returned")
+                .expect("Ok should be returned");
+
+            assert!(
+                matches!(
+                    result,
+                    DirectoryEntry::Free(FreeDirectoryEntry::AllFollowing)
+                ),
+                "Written entry should be returned"
+            );
+        }
+
+        #[test]
+        fn current_entry_overwritten() {
+            let test_instance = TestInstance::new(2);
+            let mut iterator = test_instance.iterator();
+
+            iterator.write(&[0x00]).expect("Ok should be returned");
+
+            let result = iterator
+                .peek()
+                .expect("Some should be returned")
+                .expect("Ok should be returned");
+
+            assert!(
+                matches!(
+                    result,
+                    DirectoryEntry::Free(FreeDirectoryEntry::AllFollowing)
+                ),
+                "Written entry should be returned"
+            );
+        }
+
+        #[test]
+        fn no_current_entry_returns_err() {
+            let test_instance = TestInstance::new(0);
+            let iterator = test_instance.iterator();
+
+            let error = iterator.write(&[0x00]).expect_err("Err should be returned");
+
+            assert!(
+                matches!(error, DirectoryEntryIterationError::StreamEndReached),
+                "StreamEndReached should be returned"
+            );
+        }
+    }
+
+    #[cfg(feature = "async")]
     mod peek_async {
         use super::*;
 
@@ -615,7 +945,7 @@ mod tests {
                 ErroringStreamScenarios::SEEK,
             ));
 
-            let iterator = DirectoryTableEntryIterator::new(&device, 1, 1);
+            let iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(1), 1);
 
             let error = iterator
                 .peek_async()
@@ -637,7 +967,7 @@ mod tests {
                 ErroringStreamScenarios::READ,
             ));
 
-            let iterator = DirectoryTableEntryIterator::new(&device, 0, 1);
+            let iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 1);
 
             let error = iterator
                 .peek_async()
@@ -655,7 +985,7 @@ mod tests {
         async fn stream_end_reached_error_propagated() {
             let device = SingleAccessDevice::new(DataStream::from_bytes([]));
 
-            let iterator = DirectoryTableEntryIterator::new(&device, 0, 1);
+            let iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 1);
 
             let error = iterator
                 .peek_async()
@@ -671,7 +1001,8 @@ mod tests {
 
         #[tokio::test]
         async fn device_err_propagated() {
-            let iterator = DirectoryTableEntryIterator::new(&ErroringDevice, 0, 1);
+            let iterator =
+                DirectoryTableEntryIterator::new(&ErroringDevice, ByteAddress::new(0), 1);
 
             let error = iterator
                 .peek_async()
@@ -692,7 +1023,7 @@ mod tests {
 
             let device = SingleAccessDevice::new(DataStream::from_bytes(data));
 
-            let iterator = DirectoryTableEntryIterator::new(&device, 0, 1);
+            let iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 1);
 
             let error = iterator
                 .peek_async()
@@ -701,12 +1032,13 @@ mod tests {
                 .expect_err("Err should be returned");
 
             assert!(
-                matches!(error, DirectoryEntryIterationError::EntryInvalid(_)),
+                matches!(error, DirectoryEntryIterationError::EntryInvalid { .. }),
                 "EntryInvalid should be returned"
             );
         }
     }
 
+    #[cfg(feature = "async")]
     mod next_async {
         use super::*;
 
@@ -772,7 +1104,7 @@ mod tests {
                 ErroringStreamScenarios::SEEK,
             ));
 
-            let mut iterator = DirectoryTableEntryIterator::new(&device, 1, 1);
+            let mut iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(1), 1);
 
             let error = iterator
                 .next_async()
@@ -794,7 +1126,7 @@ mod tests {
                 ErroringStreamScenarios::READ,
             ));
 
-            let mut iterator = DirectoryTableEntryIterator::new(&device, 0, 1);
+            let mut iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 1);
 
             let error = iterator
                 .next_async()
@@ -812,7 +1144,7 @@ mod tests {
         async fn stream_end_reached_error_propagated() {
             let device = SingleAccessDevice::new(DataStream::from_bytes([]));
 
-            let mut iterator = DirectoryTableEntryIterator::new(&device, 0, 1);
+            let mut iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 1);
 
             let error = iterator
                 .next_async()
@@ -828,7 +1160,8 @@ mod tests {
 
         #[tokio::test]
         async fn device_err_propagated() {
-            let mut iterator = DirectoryTableEntryIterator::new(&ErroringDevice, 0, 1);
+            let mut iterator =
+                DirectoryTableEntryIterator::new(&ErroringDevice, ByteAddress::new(0), 1);
 
             let error = iterator
                 .next_async()
@@ -849,7 +1182,7 @@ mod tests {
 
             let device = SingleAccessDevice::new(DataStream::from_bytes(data));
 
-            let mut iterator = DirectoryTableEntryIterator::new(&device, 0, 1);
+            let mut iterator = DirectoryTableEntryIterator::new(&device, ByteAddress::new(0), 1);
 
             let error = iterator
                 .next_async()
@@ -858,7 +1191,7 @@ mod tests {
                 .expect_err("Err should be returned");
 
             assert!(
-                matches!(error, DirectoryEntryIterationError::EntryInvalid(_)),
+                matches!(error, DirectoryEntryIterationError::EntryInvalid { .. }),
                 "EntryInvalid should be returned"
             );
         }
@@ -890,7 +1223,7 @@ mod tests {
         }
 
         fn iterator(&self) -> DirectoryTableEntryIterator<'_, TestInstanceDevice> {
-            DirectoryTableEntryIterator::new(&self.device, 0, self.entry_count)
+            DirectoryTableEntryIterator::new(&self.device, ByteAddress::new(0), self.entry_count)
         }
     }
 }
@@ -9,18 +9,61 @@ use crate::file_name::LONG_NAME_MAX_LENGTH;
 use crate::utils::{read_le_u16, write_le_u16};
 
 pub const LONG_NAME_CHARACTERS_PER_ENTRY: usize = 13;
+/// The value filling unused character slots following a long name's null terminator.
+pub const LONG_NAME_PADDING_CHARACTER: Ucs2Character = Ucs2Character::from_u16(0xFFFF).unwrap();
 pub const LONG_NAME_MAX_ENTRY_COUNT: u8 =
     LONG_NAME_MAX_LENGTH.div_ceil(LONG_NAME_CHARACTERS_PER_ENTRY) as u8;
 
+const SUBSTITUTE_CHARACTER: Ucs2Character = Ucs2Character::from_char('?').unwrap();
+
 #[derive(Builder, Clone, Debug)]
 pub struct LongNameDirectoryEntry {
     order_byte: u8,
 
     ucs2_characters: [Ucs2Character; LONG_NAME_CHARACTERS_PER_ENTRY],
     short_name_checksum: u8,
+
+    #[builder(default)]
+    has_substituted_characters: bool,
 }
 
 impl LongNameDirectoryEntry {
+    /// Creates the entry holding the given portion of a long name.  Portions shorter than
+    /// `LONG_NAME_CHARACTERS_PER_ENTRY` are followed by a null terminator and padded with 0xFFFF.
+    pub fn from_name_part(
+        order_byte: u8,
+        name_part: &[Ucs2Character],
+        short_name_checksum: u8,
+    ) -> Result<Self, LongNameDirectoryEntryError> {
+        ensure!(
+            matches!(order_byte & 0x3F, 1..=LONG_NAME_MAX_ENTRY_COUNT),
+            LongNameDirectoryEntryError::EntryNumberInvalid
+        );
+        ensure!(
+            !name_part.is_empty() && name_part.len() <= LONG_NAME_CHARACTERS_PER_ENTRY,
+            LongNameDirectoryEntryError::NamePartLengthInvalid(name_part.len())
+        );
+
+        let mut ucs2_characters = [LONG_NAME_PADDING_CHARACTER; LONG_NAME_CHARACTERS_PER_ENTRY];
+
+        ucs2_characters[..name_part.len()].copy_from_slice(name_part);
+
+        if name_part.len() < LONG_NAME_CHARACTERS_PER_ENTRY {
+            ucs2_characters[name_part.len()] = Ucs2Character::null();
+        }
+
+        Ok(Self {
+            order_byte,
+
+            ucs2_characters,
+            short_name_checksum,
+
+            has_substituted_characters: false,
+        })
+    }
+
+    /// Parses the entry, substituting `?` for characters which aren't valid UCS-2 so the rest of
+    /// the name is kept.
     pub fn from_bytes(
         bytes: &[u8; DIRECTORY_ENTRY_SIZE],
     ) -> Result<LongNameDirectoryEntry, LongNameDirectoryEntryError> {
@@ -30,21 +73,17 @@ impl LongNameDirectoryEntry {
         );
 
         let mut ucs2_characters = [Ucs2Character::null(); LONG_NAME_CHARACTERS_PER_ENTRY];
+        let mut has_substituted_characters = false;
+
         for (character_index, ucs2_character) in ucs2_characters.iter_mut().enumerate() {
-            let byte_index = match character_index {
-                0..5 => (character_index * 2) + 1,
-                5..11 => ((character_index - 5) * 2) + 14,
-                _ => ((character_index - 11) * 2) + 28,
-            };
-
-            let ucs2_character_codepoint = read_le_u16(bytes, byte_index);
-
-            *ucs2_character = Ucs2Character::from_u16(ucs2_character_codepoint).ok_or(
-                LongNameDirectoryEntryError::NameCharacterInvalid {
-                    character: ucs2_character_codepoint,
-                    offset: character_index as u8,
-                },
-            )?;
+            let ucs2_character_codepoint =
+                read_le_u16(bytes, Self::character_byte_offset(character_index));
+
+            *ucs2_character =
+                Ucs2Character::from_u16(ucs2_character_codepoint).unwrap_or_else(|| {
+                    has_substituted_characters = true;
+                    SUBSTITUTE_CHARACTER
+                });
         }
 
         Ok(Self {
@@ -52,6 +91,8 @@ impl LongNameDirectoryEntry {
 
             ucs2_characters,
             short_name_checksum: bytes[13],
+
+            has_substituted_characters,
         })
     }
 
@@ -71,34 +112,36 @@ impl LongNameDirectoryEntry {
         &self.ucs2_characters
     }
 
-    pub fn write(&self, mut bytes: &mut [u8; DIRECTORY_ENTRY_SIZE]) {
-        bytes[0] = self.order_byte;
+    /// Whether characters which couldn't be decoded were replaced while parsing the entry.
+    pub fn has_substituted_characters(&self) -> bool {
+        self.has_substituted_characters
+    }
 
-        for ucs2_character_index in 0..5 {
-            write_le_u16(
-                bytes,
-                1 + (2 * ucs2_character_index),
-                self.ucs2_characters[ucs2_character_index].to_u16(),
-            );
-        }
+    pub fn to_bytes(&self) -> [u8; DIRECTORY_ENTRY_SIZE] {
+        let mut bytes = [0; DIRECTORY_ENTRY_SIZE];
 
-        bytes[11] |= DirectoryEntryAttributes::LongName.bits();
+        bytes[0] = self.order_byte;
+        bytes[11] = DirectoryEntryAttributes::LongName.bits();
         bytes[13] = self.short_name_checksum;
 
-        for ucs2_character_index in 5..11 {
+        for (character_index, ucs2_character) in self.ucs2_characters.iter().enumerate() {
             write_le_u16(
-                bytes,
-                14 + (2 * (ucs2_character_index - 5)),
-                self.ucs2_characters[ucs2_character_index].to_u16(),
+                &mut bytes,
+                Self::character_byte_offset(character_index),
+                ucs2_character.to_u16(),
             );
         }
 
-        for ucs2_character_index in 11..13 {
-            write_le_u16(
-                bytes,
-                28 + (2 * (ucs2_character_index - 11)),
-                self.ucs2_characters[ucs2_character_index].to_u16(),
-            );
+        bytes
+    }
+
+    /// Offset of the given character within the entry, characters are split across three
+    /// non-contiguous name fields.
+    fn character_byte_offset(character_index: usize) -> usize {
+        match character_index {
+            0..5 => (character_index * 2) + 1,
+            5..11 => ((character_index - 5) * 2) + 14,
+            _ => ((character_index - 11) * 2) + 28,
         }
     }
 }
@@ -169,25 +212,102 @@ mod tests {
         }
 
         #[test]
-        fn character_invalid_returns_err() {
-            let mut data = TestData::valid().bytes;
-            data[3] = 0x00;
-            data[4] = 0xD8;
+        fn valid_characters_not_substituted() {
+            let entry = LongNameDirectoryEntry::from_bytes(&TestData::valid().bytes)
+                .expect("Ok should be returned");
 
-            let error =
-                LongNameDirectoryEntry::from_bytes(&mut data).expect_err("Err should be returned");
+            assert!(!entry.has_substituted_characters());
+        }
+
+        #[test]
+        fn character_invalid_substituted() {
+            let mut test_data = TestData::valid();
+            test_data.bytes[3] = 0x00;
+            test_data.bytes[4] = 0xD8;
+
+            let entry = LongNameDirectoryEntry::from_bytes(&test_data.bytes)
+                .expect("Ok should be returned");
+
+            let mut expected_characters = test_data.name_characters;
+            expected_characters[1] = Ucs2Character::from_char('?').unwrap();
+
+            assert!(entry.has_substituted_characters());
+            assert_eq!(entry.ucs2_characters(), &expected_characters);
+        }
+    }
+
+    mod from_name_part {
+        use super::*;
+
+        #[test]
+        fn short_part_terminated_and_padded() {
+            let test_data = TestData::valid();
+
+            let entry = LongNameDirectoryEntry::from_name_part(
+                0x41,
+                &test_data.name_characters[..11],
+                0x12,
+            )
+            .expect("Ok should be returned");
+
+            assert_eq!(
+                entry.to_bytes(),
+                test_data.bytes,
+                "Entry should match the on-disk representation"
+            );
+        }
+
+        #[test]
+        fn full_part_not_terminated() {
+            let name_part =
+                [Ucs2Character::from_char('a').unwrap(); LONG_NAME_CHARACTERS_PER_ENTRY];
+
+            let entry = LongNameDirectoryEntry::from_name_part(0x01, &name_part, 0x12)
+                .expect("Ok should be returned");
+
+            assert_eq!(entry.ucs2_characters(), &name_part);
+        }
+
+        #[test]
+        fn empty_part_returns_err() {
+            let error = LongNameDirectoryEntry::from_name_part(0x01, &[], 0x12)
+                .expect_err("Err should be returned");
+
+            assert!(matches!(
+                error,
+                LongNameDirectoryEntryError::NamePartLengthInvalid(0)
+            ));
+        }
+
+        #[test]
+        fn long_part_returns_err() {
+            let name_part =
+                [Ucs2Character::from_char('a').unwrap(); LONG_NAME_CHARACTERS_PER_ENTRY + 1];
+
+            let error = LongNameDirectoryEntry::from_name_part(0x01, &name_part, 0x12)
+                .expect_err("Err should be returned");
+
+            assert!(matches!(
+                error,
+                LongNameDirectoryEntryError::NamePartLengthInvalid(14)
+            ));
+        }
+
+        #[test]
+        fn entry_number_invalid_returns_err() {
+            let name_part = [Ucs2Character::from_char('a').unwrap()];
+
+            let error = LongNameDirectoryEntry::from_name_part(0x40, &name_part, 0x12)
+                .expect_err("Err should be returned");
 
             assert!(matches!(
                 error,
-                LongNameDirectoryEntryError::NameCharacterInvalid {
-                    character: 0xD800,
-                    offset: 1
-                }
+                LongNameDirectoryEntryError::EntryNumberInvalid
             ));
         }
     }
 
-    mod write {
+    mod to_bytes {
         use super::*;
 
         #[test]
@@ -195,8 +315,7 @@ mod tests {
             let data = TestData::valid().bytes;
             let entry = LongNameDirectoryEntry::from_bytes(&data).expect("Ok should be returned");
 
-            let mut result = [0x00; DIRECTORY_ENTRY_SIZE];
-            entry.write(&mut result);
+            let result = entry.to_bytes();
 
             assert_eq!(result, data, "Input and output bytes should match exactly");
         }
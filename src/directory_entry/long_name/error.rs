@@ -1,11 +1,11 @@
-use crate::directory_entry::LONG_NAME_MAX_ENTRY_COUNT;
+use crate::directory_entry::{LONG_NAME_CHARACTERS_PER_ENTRY, LONG_NAME_MAX_ENTRY_COUNT};
 use core::error::Error;
 use core::fmt::{Display, Formatter};
 
 #[derive(Clone, Debug)]
 pub enum LongNameDirectoryEntryError {
     EntryNumberInvalid,
-    NameCharacterInvalid { character: u16, offset: u8 },
+    NamePartLengthInvalid(usize),
 }
 
 impl Display for LongNameDirectoryEntryError {
@@ -17,10 +17,10 @@ impl Display for LongNameDirectoryEntryError {
                     "entry number must be between 1 and {LONG_NAME_MAX_ENTRY_COUNT}"
                 )
             }
-            LongNameDirectoryEntryError::NameCharacterInvalid { character, offset } => {
+            LongNameDirectoryEntryError::NamePartLengthInvalid(length) => {
                 write!(
                     f,
-                    "the long name directory entry's name has the invalid character 0x{character:04X} at offset {offset}"
+                    "a long name directory entry holds between 1 and {LONG_NAME_CHARACTERS_PER_ENTRY} characters, {length} were provided"
                 )
             }
         }
@@ -41,10 +41,7 @@ mod tests {
         fn produces_non_empty_value() {
             let values = [
                 LongNameDirectoryEntryError::EntryNumberInvalid,
-                LongNameDirectoryEntryError::NameCharacterInvalid {
-                    character: 0x0000,
-                    offset: 0,
-                },
+                LongNameDirectoryEntryError::NamePartLengthInvalid(0),
             ];
 
             for value in values {
@@ -0,0 +1,70 @@
+use crate::directory_entry::DIRECTORY_ENTRY_SIZE;
+
+/// The position of an entry within its directory, allowing iteration to resume at that entry.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DirectoryEntryPosition {
+    /// An entry of the FAT12/16 root directory table, indexed from the table's first entry.
+    #[cfg(any(feature = "fat12", feature = "fat16"))]
+    Table { entry_index: u16 },
+    /// An entry of a directory stored in clusters, at a byte offset within the given cluster.
+    File {
+        cluster_number: u32,
+        cluster_offset: u32,
+    },
+}
+
+impl DirectoryEntryPosition {
+    /// The position `entry_count` entries later, assuming those entries are stored contiguously.
+    pub(crate) fn offset_by(&self, entry_count: u32) -> Self {
+        match *self {
+            #[cfg(any(feature = "fat12", feature = "fat16"))]
+            DirectoryEntryPosition::Table { entry_index } => DirectoryEntryPosition::Table {
+                entry_index: entry_index.saturating_add(entry_count as u16),
+            },
+            DirectoryEntryPosition::File {
+                cluster_number,
+                cluster_offset,
+            } => DirectoryEntryPosition::File {
+                cluster_number,
+                cluster_offset: cluster_offset
+                    .saturating_add(entry_count * DIRECTORY_ENTRY_SIZE as u32),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod offset_by {
+        use super::*;
+
+        #[test]
+        fn file_position_offset_within_cluster() {
+            let position = DirectoryEntryPosition::File {
+                cluster_number: 5,
+                cluster_offset: 32,
+            };
+
+            assert_eq!(
+                position.offset_by(2),
+                DirectoryEntryPosition::File {
+                    cluster_number: 5,
+                    cluster_offset: 96,
+                }
+            );
+        }
+
+        #[test]
+        #[cfg(any(feature = "fat12", feature = "fat16"))]
+        fn table_position_offset_by_entry_count() {
+            let position = DirectoryEntryPosition::Table { entry_index: 3 };
+
+            assert_eq!(
+                position.offset_by(2),
+                DirectoryEntryPosition::Table { entry_index: 5 }
+            );
+        }
+    }
+}
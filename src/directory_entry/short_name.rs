@@ -1,5 +1,7 @@
+mod case;
 mod error;
 
+pub use case::*;
 pub use error::*;
 
 use crate::AllocationTableKind;
@@ -10,6 +12,20 @@ use bon::Builder;
 
 pub const SHORT_NAME_CHARACTER_COUNT: usize = 11;
 
+/// The largest file size an entry can record, FAT+ extends the 32-bit size field with 6 bits kept
+/// in the reserved byte.
+#[cfg(not(feature = "fat-plus"))]
+pub const MAX_FILE_SIZE: u64 = u32::MAX as u64;
+#[cfg(feature = "fat-plus")]
+pub const MAX_FILE_SIZE: u64 = (1 << 38) - 1;
+
+/// Bits of the reserved byte holding bits 32 to 34 of a FAT+ file size, bits 35 to 37 are held
+/// by the top three bits.  The bits in between record the short name's case.
+#[cfg(feature = "fat-plus")]
+const FAT_PLUS_LOW_SIZE_BITS: u8 = 0b0000_0111;
+#[cfg(feature = "fat-plus")]
+const FAT_PLUS_HIGH_SIZE_BITS: u8 = 0b1110_0000;
+
 #[derive(Builder, Clone, Debug)]
 #[cfg_attr(test, derive(Eq, PartialEq))]
 pub struct ShortNameDirectoryEntry {
@@ -17,6 +33,22 @@ pub struct ShortNameDirectoryEntry {
 
     attributes: DirectoryEntryAttributes,
 
+    #[builder(default)]
+    reserved: u8,
+
+    #[builder(default)]
+    creation_time_tenths: u8,
+    #[builder(default)]
+    creation_time: u16,
+    #[builder(default)]
+    creation_date: u16,
+    #[builder(default)]
+    last_access_date: u16,
+    #[builder(default)]
+    last_write_time: u16,
+    #[builder(default)]
+    last_write_date: u16,
+
     first_cluster_number: u32,
     file_size: u32,
 }
@@ -36,8 +68,9 @@ impl ShortNameDirectoryEntry {
             (read_le_u16(bytes, 20) as u32) << 16 | read_le_u16(bytes, 26) as u32;
         let file_size = read_le_u32(bytes, 28);
 
+        // Empty files and `..` entries referring to the root directory have no cluster allocated
         ensure!(
-            file_size > 0 || first_cluster_number != 0,
+            file_size == 0 || first_cluster_number != 0,
             ShortNameDirectoryEntryError::FirstClusterNumberInvalid
         );
         ensure!(
@@ -49,6 +82,15 @@ impl ShortNameDirectoryEntry {
             name: ShortFileName::new(name_bytes)?,
             attributes: DirectoryEntryAttributes::from_bits_retain(bytes[11]),
 
+            reserved: bytes[12],
+
+            creation_time_tenths: bytes[13],
+            creation_time: read_le_u16(bytes, 14),
+            creation_date: read_le_u16(bytes, 16),
+            last_access_date: read_le_u16(bytes, 18),
+            last_write_time: read_le_u16(bytes, 22),
+            last_write_date: read_le_u16(bytes, 24),
+
             first_cluster_number,
             file_size,
         })
@@ -63,6 +105,40 @@ impl ShortNameDirectoryEntry {
             .contains(DirectoryEntryAttributes::Subdirectory)
     }
 
+    pub fn attributes(&self) -> DirectoryEntryAttributes {
+        self.attributes
+    }
+
+    /// Hundredths of a second to add to `creation_time`, ranging from 0 to 199.
+    pub fn creation_time_tenths(&self) -> u8 {
+        self.creation_time_tenths
+    }
+
+    /// Packed FAT time the entry was created.
+    pub fn creation_time(&self) -> u16 {
+        self.creation_time
+    }
+
+    /// Packed FAT date the entry was created.
+    pub fn creation_date(&self) -> u16 {
+        self.creation_date
+    }
+
+    /// Packed FAT date the entry was last read or written.
+    pub fn last_access_date(&self) -> u16 {
+        self.last_access_date
+    }
+
+    /// Packed FAT time the entry was last written.
+    pub fn last_write_time(&self) -> u16 {
+        self.last_write_time
+    }
+
+    /// Packed FAT date the entry was last written.
+    pub fn last_write_date(&self) -> u16 {
+        self.last_write_date
+    }
+
     pub fn first_cluster_number(&self) -> u32 {
         self.first_cluster_number
     }
@@ -71,17 +147,74 @@ impl ShortNameDirectoryEntry {
         self.file_size
     }
 
-    pub fn write(&self, mut bytes: &mut [u8; DIRECTORY_ENTRY_SIZE]) {
-        bytes[0..11].copy_from_slice(self.name.bytes());
+    /// The file size including the bits FAT+ keeps in the reserved byte, which are only read when
+    /// the `fat-plus` feature is enabled.
+    pub fn extended_file_size(&self) -> u64 {
+        #[cfg(feature = "fat-plus")]
+        {
+            let high_bits = (self.reserved & FAT_PLUS_LOW_SIZE_BITS)
+                | ((self.reserved & FAT_PLUS_HIGH_SIZE_BITS) >> 2);
+
+            ((high_bits as u64) << 32) | self.file_size as u64
+        }
+
+        #[cfg(not(feature = "fat-plus"))]
+        {
+            self.file_size as u64
+        }
+    }
+
+    pub(crate) fn reserved(&self) -> u8 {
+        self.reserved
+    }
+
+    /// The parts of the name displayed in lower case.
+    pub fn name_case(&self) -> ShortNameCase {
+        ShortNameCase::from_bits_truncate(self.reserved)
+    }
+
+    /// Splits a file size into the 32-bit size field and the reserved byte bits FAT+ keeps the
+    /// rest in, to be combined with the short name's case bits.  Sizes above `MAX_FILE_SIZE` are
+    /// truncated.
+    pub(crate) fn split_file_size(file_size: u64) -> (u32, u8) {
+        #[cfg(feature = "fat-plus")]
+        {
+            let high_bits = (file_size >> 32) as u8;
+            let reserved_bits =
+                (high_bits & FAT_PLUS_LOW_SIZE_BITS) | ((high_bits << 2) & FAT_PLUS_HIGH_SIZE_BITS);
+
+            (file_size as u32, reserved_bits)
+        }
+
+        #[cfg(not(feature = "fat-plus"))]
+        {
+            (file_size as u32, 0)
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; DIRECTORY_ENTRY_SIZE] {
+        let mut bytes = [0; DIRECTORY_ENTRY_SIZE];
+
+        bytes[0..SHORT_NAME_CHARACTER_COUNT].copy_from_slice(self.name.bytes());
 
         if bytes[0] == 0xE5 {
             bytes[0] = 0x05;
         }
 
         bytes[11] = self.attributes.bits();
-        write_le_u16(bytes, 20, (self.first_cluster_number >> 16) as u16);
-        write_le_u16(bytes, 26, self.first_cluster_number as u16);
-        write_le_u32(bytes, 28, self.file_size);
+        bytes[12] = self.reserved;
+
+        bytes[13] = self.creation_time_tenths;
+        write_le_u16(&mut bytes, 14, self.creation_time);
+        write_le_u16(&mut bytes, 16, self.creation_date);
+        write_le_u16(&mut bytes, 18, self.last_access_date);
+        write_le_u16(&mut bytes, 20, (self.first_cluster_number >> 16) as u16);
+        write_le_u16(&mut bytes, 22, self.last_write_time);
+        write_le_u16(&mut bytes, 24, self.last_write_date);
+        write_le_u16(&mut bytes, 26, self.first_cluster_number as u16);
+        write_le_u32(&mut bytes, 28, self.file_size);
+
+        bytes
     }
 }
 
@@ -106,6 +239,36 @@ mod tests {
                 test_data.is_directory,
                 "is_directory should be parsed correctly"
             );
+            assert_eq!(
+                entry.creation_time_tenths(),
+                test_data.creation_time_tenths,
+                "creation_time_tenths should be parsed correctly"
+            );
+            assert_eq!(
+                entry.creation_time(),
+                test_data.creation_time,
+                "creation_time should be parsed correctly"
+            );
+            assert_eq!(
+                entry.creation_date(),
+                test_data.creation_date,
+                "creation_date should be parsed correctly"
+            );
+            assert_eq!(
+                entry.last_access_date(),
+                test_data.last_access_date,
+                "last_access_date should be parsed correctly"
+            );
+            assert_eq!(
+                entry.last_write_time(),
+                test_data.last_write_time,
+                "last_write_time should be parsed correctly"
+            );
+            assert_eq!(
+                entry.last_write_date(),
+                test_data.last_write_date,
+                "last_write_date should be parsed correctly"
+            );
             assert_eq!(
                 entry.first_cluster_number(),
                 test_data.first_cluster_number,
@@ -118,6 +281,33 @@ mod tests {
             );
         }
 
+        #[test]
+        fn empty_without_cluster_parsed_correctly() {
+            let mut data = TestData::valid().data;
+            data[20..22].fill(0x00);
+            data[26..32].fill(0x00);
+
+            let entry = ShortNameDirectoryEntry::from_bytes(&data).expect("Ok should be returned");
+
+            assert_eq!(entry.first_cluster_number(), 0);
+            assert_eq!(entry.file_size(), 0);
+        }
+
+        #[test]
+        fn non_empty_without_cluster_returns_err() {
+            let mut data = TestData::valid().data;
+            data[20..22].fill(0x00);
+            data[26..28].fill(0x00);
+
+            let error =
+                ShortNameDirectoryEntry::from_bytes(&data).expect_err("Err should be returned");
+
+            assert!(matches!(
+                error,
+                ShortNameDirectoryEntryError::FirstClusterNumberInvalid
+            ));
+        }
+
         #[test]
         fn initial_byte_05_parsed_correctly() {
             let mut data = TestData::valid().data;
@@ -134,7 +324,7 @@ mod tests {
         }
     }
 
-    mod write {
+    mod to_bytes {
         use super::*;
 
         #[test]
@@ -142,8 +332,7 @@ mod tests {
             let data = TestData::valid().data;
             let entry = ShortNameDirectoryEntry::from_bytes(&data).expect("Ok should be returned");
 
-            let mut result = [0x00; DIRECTORY_ENTRY_SIZE];
-            entry.write(&mut result);
+            let result = entry.to_bytes();
 
             assert_eq!(result, data, "Input and output bytes should match exactly");
         }
@@ -155,18 +344,83 @@ mod tests {
 
             let entry = ShortNameDirectoryEntry::from_bytes(&data).expect("Ok should be returned");
 
-            let mut result = [0x00; DIRECTORY_ENTRY_SIZE];
-            entry.write(&mut result);
+            let result = entry.to_bytes();
 
             assert_eq!(result, data, "Input and output bytes should match exactly");
         }
     }
 
+    mod extended_file_size {
+        use super::*;
+
+        fn entry(reserved: u8) -> ShortNameDirectoryEntry {
+            ShortNameDirectoryEntry::builder()
+                .name(ShortFileName::from_str(&AsciiOnlyEncoder, "foo.bin").unwrap())
+                .attributes(DirectoryEntryAttributes::empty())
+                .reserved(reserved)
+                .first_cluster_number(2)
+                .file_size(0x1234_5678)
+                .build()
+        }
+
+        #[test]
+        #[cfg(not(feature = "fat-plus"))]
+        fn reserved_bits_ignored() {
+            assert_eq!(entry(0xFF).extended_file_size(), 0x1234_5678);
+        }
+
+        #[test]
+        #[cfg(feature = "fat-plus")]
+        fn reserved_bits_combined() {
+            assert_eq!(entry(0b1010_0101).extended_file_size(), 0x2D_1234_5678);
+        }
+
+        #[test]
+        #[cfg(feature = "fat-plus")]
+        fn case_bits_ignored() {
+            assert_eq!(entry(0b0001_1000).extended_file_size(), 0x1234_5678);
+        }
+    }
+
+    mod split_file_size {
+        use super::*;
+
+        #[test]
+        fn small_sizes_leave_reserved_bits_clear() {
+            assert_eq!(
+                ShortNameDirectoryEntry::split_file_size(0x1234_5678),
+                (0x1234_5678, 0)
+            );
+        }
+
+        #[test]
+        #[cfg(feature = "fat-plus")]
+        fn roundtrips_through_extended_file_size() {
+            let (file_size, reserved) = ShortNameDirectoryEntry::split_file_size(MAX_FILE_SIZE);
+            let entry = ShortNameDirectoryEntry::builder()
+                .name(ShortFileName::from_str(&AsciiOnlyEncoder, "foo.bin").unwrap())
+                .attributes(DirectoryEntryAttributes::empty())
+                .reserved(reserved)
+                .first_cluster_number(2)
+                .file_size(file_size)
+                .build();
+
+            assert_eq!(reserved & 0b0001_1000, 0, "Case bits should be left clear");
+            assert_eq!(entry.extended_file_size(), MAX_FILE_SIZE);
+        }
+    }
+
     struct TestData {
         data: [u8; DIRECTORY_ENTRY_SIZE],
 
         name: ShortFileName,
         is_directory: bool,
+        creation_time_tenths: u8,
+        creation_time: u16,
+        creation_date: u16,
+        last_access_date: u16,
+        last_write_time: u16,
+        last_write_date: u16,
         first_cluster_number: u32,
         file_size: u32,
     }
@@ -184,20 +438,22 @@ mod tests {
                     DirectoryEntryAttributes::Subdirectory.bits(),
 
                     // Reserved
-                    0x00,
+                    0x18,
+
+                    // Creation time tenths, time, and date
+                    0x64,
+                    0x2B, 0x6B,
+                    0x4F, 0x5A,
 
-                    // Unparsed timestamps
-                    0x00,
-                    0x00, 0x00,
-                    0x00, 0x00,
-                    0x00, 0x00,
+                    // Last access date
+                    0x50, 0x5A,
 
                     // First cluster high
                     0x34, 0x12,
 
-                    // Unparsed timestamps
-                    0x00, 0x00,
-                    0x00, 0x00,
+                    // Last write time and date
+                    0x6A, 0x7C,
+                    0x51, 0x5A,
 
                     // First cluster low
                     0x78, 0x56,
@@ -208,6 +464,12 @@ mod tests {
 
                 name: ShortFileName::from_str(&AsciiOnlyEncoder, "foobar.txt").unwrap(),
                 is_directory: true,
+                creation_time_tenths: 0x64,
+                creation_time: 0x6B2B,
+                creation_date: 0x5A4F,
+                last_access_date: 0x5A50,
+                last_write_time: 0x7C6A,
+                last_write_date: 0x5A51,
                 first_cluster_number: 0x12345678,
                 file_size: 0x9ABCDEF1,
             }
@@ -0,0 +1,11 @@
+use bitflags::bitflags;
+
+bitflags! {
+    /// The parts of a short name displayed in lower case, recorded in the reserved byte of the
+    /// entry by Windows NT and later instead of a long name.
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+    pub struct ShortNameCase: u8 {
+        const LowerCaseBaseName  = 1 << 3;
+        const LowerCaseExtension = 1 << 4;
+    }
+}
@@ -1,16 +1,48 @@
 mod builder;
+mod entries;
 mod error;
+mod filter;
 mod iteration_error;
 mod iterator;
+mod kind;
+mod location;
+mod long_name_policy;
+mod name_pattern;
+mod name_query;
 
 pub use builder::*;
+pub use entries::*;
 pub use error::*;
+pub use filter::*;
 pub use iteration_error::*;
 pub use iterator::*;
+pub use kind::*;
+pub use location::*;
+pub use long_name_policy::*;
+pub(crate) use name_pattern::*;
+pub(crate) use name_query::*;
 
-use crate::directory_entry::ShortNameDirectoryEntry;
+use crate::directory_entry::{SHORT_NAME_CHARACTER_COUNT, ShortNameCase, ShortNameDirectoryEntry};
+use crate::encoding::Ucs2Character;
 use crate::file_name::{LongFileName, ShortFileName};
-use crate::{AllocationTableKind, CodePageEncoder};
+use crate::timestamp::shift_minutes;
+use crate::{
+    AllocationTableKind, CodePageEncoder, DeviceOperationError, FatDate, FatTime, FileSystem,
+    InvalidDirectoryEntryHandler, InvalidTimestampPolicy, MutationEvent, OperationError,
+    TimestampError,
+};
+
+#[cfg(feature = "sync")]
+use {
+    crate::SyncDevice,
+    embedded_io::{Read, Seek},
+};
+
+#[cfg(feature = "async")]
+use {
+    crate::AsyncDevice,
+    embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek},
+};
 
 pub const DIRECTORY_ENTITY_LONG_NAME_MAX_LENGTH: usize = 255;
 
@@ -18,6 +50,10 @@ pub const DIRECTORY_ENTITY_LONG_NAME_MAX_LENGTH: usize = 255;
 pub struct DirectoryItem {
     short_directory_entry: ShortNameDirectoryEntry,
     long_name: Option<LongFileName>,
+
+    location: Option<EntryLocation>,
+    utc_offset_minutes: i16,
+    invalid_timestamp_policy: InvalidTimestampPolicy,
 }
 
 impl DirectoryItem {
@@ -28,15 +64,83 @@ impl DirectoryItem {
         Self {
             short_directory_entry,
             long_name,
+
+            location: None,
+            utc_offset_minutes: 0,
+            invalid_timestamp_policy: InvalidTimestampPolicy::default(),
         }
     }
 
+    pub(crate) fn with_location(mut self, location: EntryLocation) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    pub(crate) fn with_utc_offset(mut self, utc_offset_minutes: i16) -> Self {
+        self.utc_offset_minutes = utc_offset_minutes;
+        self
+    }
+
+    pub(crate) fn with_invalid_timestamp_policy(
+        mut self,
+        invalid_timestamp_policy: InvalidTimestampPolicy,
+    ) -> Self {
+        self.invalid_timestamp_policy = invalid_timestamp_policy;
+        self
+    }
+
+    /// Where the item's entries are stored within its directory, `None` for items which weren't
+    /// read from a directory.
+    pub fn location(&self) -> Option<EntryLocation> {
+        self.location
+    }
+
+    pub(crate) fn short_directory_entry(&self) -> &ShortNameDirectoryEntry {
+        &self.short_directory_entry
+    }
+
+    fn replace_short_directory_entry<DE, SE>(
+        &mut self,
+        short_directory_entry: ShortNameDirectoryEntry,
+    ) -> Result<(), OperationError<DE, SE>>
+    where
+        DE: core::error::Error,
+        SE: embedded_io::Error,
+    {
+        ensure!(
+            short_directory_entry.name() == self.short_name(),
+            OperationError::NotFound
+        );
+
+        self.short_directory_entry = short_directory_entry;
+
+        Ok(())
+    }
+
+    pub fn kind(&self) -> DirectoryItemKind {
+        DirectoryItemKind::from_attributes(self.short_directory_entry.attributes())
+    }
+
     pub fn is_directory(&self) -> bool {
-        self.short_directory_entry.is_directory()
+        self.kind() == DirectoryItemKind::Directory
     }
 
     pub fn is_file(&self) -> bool {
-        !self.is_directory()
+        self.kind() == DirectoryItemKind::File
+    }
+
+    pub fn is_volume_label(&self) -> bool {
+        self.kind() == DirectoryItemKind::VolumeLabel
+    }
+
+    /// Whether this is a directory's `.` item, which refers to the directory itself.
+    pub fn is_dot(&self) -> bool {
+        self.short_name().is_dot()
+    }
+
+    /// Whether this is a directory's `..` item, which refers to its parent.
+    pub fn is_dotdot(&self) -> bool {
+        self.short_name().is_dotdot()
     }
 
     pub fn first_cluster_number(&self) -> u32 {
@@ -47,23 +151,296 @@ impl DirectoryItem {
         self.short_directory_entry.file_size()
     }
 
+    /// The file size including the bits the FAT+ extension records beyond 4 GiB, which are only
+    /// read when the `fat-plus` feature is enabled.
+    pub fn extended_file_size(&self) -> u64 {
+        self.short_directory_entry.extended_file_size()
+    }
+
+    pub fn last_write_date(&self) -> u16 {
+        self.short_directory_entry.last_write_date()
+    }
+
+    pub fn last_write_time(&self) -> u16 {
+        self.short_directory_entry.last_write_time()
+    }
+
+    /// The last write date and time.  Entries holding values which don't exist, such as the zeroed
+    /// timestamps some hosts write, are treated as set by
+    /// `FileSystemBuilder::with_invalid_timestamp_policy` and fail by default.
+    pub fn last_write_timestamp(&self) -> Result<(FatDate, FatTime), TimestampError> {
+        self.invalid_timestamp_policy
+            .unpack(self.last_write_date(), self.last_write_time())
+    }
+
+    /// Whether the entry's last write date and time exist, whatever the invalid timestamp policy
+    /// makes of them.
+    pub fn is_last_write_timestamp_valid(&self) -> bool {
+        InvalidTimestampPolicy::Reject
+            .unpack(self.last_write_date(), self.last_write_time())
+            .is_ok()
+    }
+
+    /// The last write date and time converted to UTC with the offset set by
+    /// `FileSystemBuilder::with_utc_offset`, local times are taken to be UTC without one.
+    pub fn last_write_timestamp_utc(&self) -> Result<(FatDate, FatTime), TimestampError> {
+        let (date, time) = self.last_write_timestamp()?;
+
+        shift_minutes(date, time, -i32::from(self.utc_offset_minutes))
+    }
+
+    pub fn short_name(&self) -> &ShortFileName {
+        self.short_directory_entry.name()
+    }
+
+    /// The parts of the short name displayed in lower case when the item has no long name.
+    pub fn short_name_case(&self) -> ShortNameCase {
+        self.short_directory_entry.name_case()
+    }
+
+    pub fn long_name(&self) -> Option<&LongFileName> {
+        self.long_name.as_ref()
+    }
+
+    /// The characters of the item's long name where present, of its short name otherwise.
+    pub(crate) fn name_chars(&self) -> impl Iterator<Item = char> + '_ {
+        let short_name_chars = self
+            .long_name
+            .is_none()
+            .then(|| self.short_name().chars_with_case(self.short_name_case()));
+
+        self.long_name
+            .iter()
+            .flat_map(LongFileName::chars)
+            .chain(short_name_chars.into_iter().flatten())
+    }
+
+    /// Whether the item's name, as displayed, starts with the prefix ignoring case.
+    ///
+    /// The long name is compared where present and the short name otherwise, characters are
+    /// folded as when names are looked up.
+    pub fn name_starts_with(&self, prefix: &str) -> bool {
+        self.with_name_characters(|name| starts_with_ignore_case(name, prefix))
+    }
+
+    /// Whether the item's name, as displayed, ends with the suffix ignoring case, see
+    /// `name_starts_with`.
+    pub fn name_ends_with(&self, suffix: &str) -> bool {
+        self.with_name_characters(|name| {
+            name.len()
+                .checked_sub(suffix.chars().count())
+                .is_some_and(|start_index| starts_with_ignore_case(&name[start_index..], suffix))
+        })
+    }
+
+    /// Whether the item's name, as displayed, contains the value ignoring case, see
+    /// `name_starts_with`.
+    pub fn name_contains(&self, value: &str) -> bool {
+        self.with_name_characters(|name| {
+            let last_index = name.len().saturating_sub(value.chars().count());
+
+            (0..=last_index).any(|start_index| starts_with_ignore_case(&name[start_index..], value))
+        })
+    }
+
+    /// Calls `f` with the characters of the name as displayed, short names are decoded onto the
+    /// stack.
+    fn with_name_characters<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&[Ucs2Character]) -> R,
+    {
+        if let Some(long_name) = &self.long_name {
+            return f(long_name.ucs2_characters());
+        }
+
+        // Short names have one more character than their bytes for the extension separator
+        let mut short_name = [Ucs2Character::null(); SHORT_NAME_CHARACTER_COUNT + 1];
+        let mut short_name_length = 0;
+
+        for character in self.short_name().chars_with_case(self.short_name_case()) {
+            if let Some(ucs2_character) = Ucs2Character::from_char(character) {
+                short_name[short_name_length] = ucs2_character;
+                short_name_length += 1;
+            }
+        }
+
+        f(&short_name[..short_name_length])
+    }
+
     pub fn is_match<CPE>(&self, code_page_encoder: &CPE, file_name: &str) -> bool
     where
         CPE: CodePageEncoder,
     {
-        if let Some(item_long_name) = self.long_name.as_ref()
-            && let Ok(input_long_name) = LongFileName::from_str(file_name)
-            && item_long_name == &input_long_name
-        {
-            return true;
+        NameQuery::new(code_page_encoder, file_name).matches(self)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl DirectoryItem {
+    /// Reads the item's size, attributes and timestamps again from its entries, which is cheaper
+    /// than looking it up by path when watching a file another task is writing to.
+    ///
+    /// Fails with `NotFound` if the item's entries no longer hold it, such as after it was deleted
+    /// or renamed.
+    pub fn refresh<D, S, CPE, IDE, ME, const BUFFER_SIZE: usize>(
+        &mut self,
+        file_system: &FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>,
+    ) -> Result<(), DeviceOperationError<D>>
+    where
+        D: SyncDevice<Stream = S>,
+        S: Read + Seek,
+        CPE: CodePageEncoder,
+        IDE: InvalidDirectoryEntryHandler<D>,
+        ME: Fn(MutationEvent<'_>),
+    {
+        let location = self.location.ok_or(OperationError::NotFound)?;
+
+        self.replace_short_directory_entry(file_system.read_short_directory_entry(location)?)
+    }
+}
+
+#[cfg(feature = "async")]
+impl DirectoryItem {
+    /// Reads the item's size, attributes and timestamps again from its entries, which is cheaper
+    /// than looking it up by path when watching a file another task is writing to.
+    ///
+    /// Fails with `NotFound` if the item's entries no longer hold it, such as after it was deleted
+    /// or renamed.
+    pub async fn refresh_async<D, S, CPE, IDE, ME, const BUFFER_SIZE: usize>(
+        &mut self,
+        file_system: &FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>,
+    ) -> Result<(), DeviceOperationError<D>>
+    where
+        D: AsyncDevice<Stream = S>,
+        S: AsyncRead + AsyncSeek,
+        CPE: CodePageEncoder,
+        IDE: InvalidDirectoryEntryHandler<D>,
+        ME: Fn(MutationEvent<'_>),
+    {
+        let location = self.location.ok_or(OperationError::NotFound)?;
+
+        self.replace_short_directory_entry(
+            file_system
+                .read_short_directory_entry_async(location)
+                .await?,
+        )
+    }
+}
+
+/// Whether the name starts with the value, folding the characters of both.  Values holding
+/// characters no name can never match.
+fn starts_with_ignore_case(name: &[Ucs2Character], value: &str) -> bool {
+    let mut name = name.iter();
+
+    value.chars().all(
+        |character| match (name.next(), Ucs2Character::from_char(character)) {
+            (Some(name_character), Some(character)) => name_character.eq_ignore_case(&character),
+            _ => false,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AsciiOnlyEncoder;
+    use crate::directory_entry::DirectoryEntryAttributes;
+    use core::str::FromStr;
+
+    fn item(short_name: &str, long_name: Option<&str>) -> DirectoryItem {
+        DirectoryItem::new(
+            ShortNameDirectoryEntry::builder()
+                .name(ShortFileName::from_str(&AsciiOnlyEncoder, short_name).unwrap())
+                .attributes(DirectoryEntryAttributes::Archive)
+                .first_cluster_number(2)
+                .file_size(1)
+                .build(),
+            long_name.map(|long_name| LongFileName::from_str(long_name).unwrap()),
+        )
+    }
+
+    mod name_starts_with {
+        use super::*;
+
+        #[test]
+        fn long_name_compared_ignoring_case() {
+            let item = item("SENSOR~1.CSV", Some("Sensor A.csv"));
+
+            assert!(item.name_starts_with("sensor a"));
+            assert!(item.name_starts_with(""));
+            assert!(!item.name_starts_with("SENSOR~1"));
+            assert!(!item.name_starts_with("Sensor A.csv2"));
         }
 
-        if let Ok(short_name) = ShortFileName::from_str(code_page_encoder, file_name)
-            && *self.short_directory_entry.name() == short_name
-        {
-            return true;
+        #[test]
+        fn short_name_compared_without_long_name() {
+            let item = item("LOG2024.CSV", None);
+
+            assert!(item.name_starts_with("log"));
+            assert!(item.name_starts_with("log2024.csv"));
+            assert!(!item.name_starts_with("og"));
         }
 
-        false
+        #[test]
+        #[cfg(feature = "unicode-case-folding")]
+        fn non_ascii_characters_folded() {
+            let item = item("SENSOR~1.CSV", Some("Größe Ä.csv"));
+
+            assert!(item.name_starts_with("GRÖ"));
+            assert!(item.name_ends_with("ä.CSV"));
+        }
+
+        #[test]
+        fn unrepresentable_characters_never_match() {
+            let item = item("LOG.CSV", Some("Log.csv"));
+
+            assert!(!item.name_starts_with("Log😀"));
+            assert!(!item.name_contains("😀"));
+        }
+    }
+
+    mod name_ends_with {
+        use super::*;
+
+        #[test]
+        fn long_name_compared_ignoring_case() {
+            let item = item("SENSOR~1.CSV", Some("Sensor A.csv"));
+
+            assert!(item.name_ends_with("a.CSV"));
+            assert!(item.name_ends_with(""));
+            assert!(!item.name_ends_with("~1.CSV"));
+            assert!(!item.name_ends_with("a sensor A.csv"));
+        }
+
+        #[test]
+        fn short_name_compared_without_long_name() {
+            let item = item("LOG2024.CSV", None);
+
+            assert!(item.name_ends_with(".csv"));
+            assert!(!item.name_ends_with("CSV.LOG2024"));
+        }
+    }
+
+    mod name_contains {
+        use super::*;
+
+        #[test]
+        fn long_name_compared_ignoring_case() {
+            let item = item("SENSOR~1.CSV", Some("Sensor A.csv"));
+
+            assert!(item.name_contains("OR a"));
+            assert!(item.name_contains("sensor a.csv"));
+            assert!(item.name_contains(""));
+            assert!(!item.name_contains("~1"));
+            assert!(!item.name_contains("Sensor A.csv "));
+        }
+
+        #[test]
+        fn short_name_compared_without_long_name() {
+            let item = item("LOG2024.CSV", None);
+
+            assert!(item.name_contains("2024.c"));
+            assert!(!item.name_contains("20245"));
+        }
     }
 }
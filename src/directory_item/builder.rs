@@ -1,11 +1,11 @@
 use crate::directory_entry::{
-    LONG_NAME_CHARACTERS_PER_ENTRY, LongNameDirectoryEntry, ShortNameDirectoryEntry,
+    LONG_NAME_CHARACTERS_PER_ENTRY, LONG_NAME_PADDING_CHARACTER, LongNameDirectoryEntry,
+    ShortNameDirectoryEntry,
 };
-use crate::directory_item::{DirectoryItem, DirectoryItemError};
+use crate::directory_item::{DirectoryItem, DirectoryItemError, LongNamePolicy};
 use crate::encoding::Ucs2Character;
 use crate::file_name::{LONG_NAME_MAX_LENGTH, LongFileName};
 
-const LONG_NAME_PADDING_CHARACTER: Ucs2Character = Ucs2Character::from_u16(0xFFFF).unwrap();
 const LONG_NAME_MAX_ENTRY_COUNT: u8 =
     LONG_NAME_MAX_LENGTH.div_ceil(LONG_NAME_CHARACTERS_PER_ENTRY) as u8;
 
@@ -15,6 +15,9 @@ pub struct DirectoryItemBuilder {
 
     long_name: [Ucs2Character; LONG_NAME_MAX_LENGTH],
     long_name_state: Option<LongNameState>,
+
+    long_name_policy: LongNamePolicy,
+    is_long_name_repaired: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -33,15 +36,23 @@ impl LongNameState {
 }
 
 impl DirectoryItemBuilder {
-    pub fn new() -> DirectoryItemBuilder {
+    pub fn new(long_name_policy: LongNamePolicy) -> DirectoryItemBuilder {
         Self {
             current_entry_index: 0,
 
             long_name: [Ucs2Character::null(); LONG_NAME_MAX_LENGTH],
             long_name_state: None,
+
+            long_name_policy,
+            is_long_name_repaired: false,
         }
     }
 
+    /// Whether the item should be left out of listings because its long name needed repairs.
+    pub fn is_skipped(&self) -> bool {
+        self.is_long_name_repaired && self.long_name_policy == LongNamePolicy::Skip
+    }
+
     pub fn add_long_name_entry(
         mut self,
         entry: LongNameDirectoryEntry,
@@ -69,6 +80,10 @@ impl DirectoryItemBuilder {
             DirectoryItemError::LongNameShortNameChecksumInconsistent
         );
 
+        if entry.has_substituted_characters() {
+            self.repair_long_name(DirectoryItemError::LongNameCharacterInvalid)?;
+        }
+
         let long_name_offset = (entry.entry_number() - 1) as usize * LONG_NAME_CHARACTERS_PER_ENTRY;
         let mut null_encountered = false;
 
@@ -94,10 +109,10 @@ impl DirectoryItemBuilder {
 
             // NOTE: value at long_name_index == LONG_NAME_MAX_LENGTH may validly be
             //   0x0000 or 0xFFFF, but both cases are handled above in their respective situations.
-            ensure!(
-                long_name_index < LONG_NAME_MAX_LENGTH,
-                DirectoryItemError::LongNameTooLong
-            );
+            if long_name_index >= LONG_NAME_MAX_LENGTH {
+                self.repair_long_name(DirectoryItemError::LongNameTooLong)?;
+                continue;
+            }
 
             self.long_name[long_name_index] = *character;
         }
@@ -107,6 +122,16 @@ impl DirectoryItemBuilder {
         Ok(self)
     }
 
+    /// Notes that the long name needs repairing, failing with the error if the policy rejects such
+    /// names.
+    fn repair_long_name(&mut self, error: DirectoryItemError) -> Result<(), DirectoryItemError> {
+        ensure!(self.long_name_policy != LongNamePolicy::Reject, error);
+
+        self.is_long_name_repaired = true;
+
+        Ok(())
+    }
+
     pub fn build(
         self,
         entry: ShortNameDirectoryEntry,
@@ -131,3 +156,137 @@ impl DirectoryItemBuilder {
         Ok(DirectoryItem::new(entry, long_name))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::directory_entry::DirectoryEntryAttributes;
+    use crate::{AsciiOnlyEncoder, ShortFileName};
+    use alloc::string::String;
+
+    fn short_name_entry() -> ShortNameDirectoryEntry {
+        ShortNameDirectoryEntry::builder()
+            .name(ShortFileName::from_str(&AsciiOnlyEncoder, "LONG~1.TXT").unwrap())
+            .attributes(DirectoryEntryAttributes::empty())
+            .first_cluster_number(2)
+            .file_size(1)
+            .build()
+    }
+
+    fn build(
+        long_name_policy: LongNamePolicy,
+        long_name_entries: impl IntoIterator<Item = LongNameDirectoryEntry>,
+    ) -> Result<(DirectoryItem, bool), DirectoryItemError> {
+        let mut builder = DirectoryItemBuilder::new(long_name_policy);
+
+        for long_name_entry in long_name_entries {
+            builder = builder.add_long_name_entry(long_name_entry)?;
+        }
+
+        let is_skipped = builder.is_skipped();
+
+        Ok((builder.build(short_name_entry())?, is_skipped))
+    }
+
+    /// The entries of a name of `LONG_NAME_MAX_ENTRY_COUNT` full entries, which is longer than
+    /// `LONG_NAME_MAX_LENGTH`.
+    fn over_long_name_entries() -> impl Iterator<Item = LongNameDirectoryEntry> {
+        let checksum = short_name_entry().name().checksum();
+        let name_part = [Ucs2Character::from_char('a').unwrap(); LONG_NAME_CHARACTERS_PER_ENTRY];
+
+        (1..=LONG_NAME_MAX_ENTRY_COUNT)
+            .rev()
+            .map(move |entry_number| {
+                let order_byte = match entry_number {
+                    LONG_NAME_MAX_ENTRY_COUNT => 0x40 | entry_number,
+                    _ => entry_number,
+                };
+
+                LongNameDirectoryEntry::from_name_part(order_byte, &name_part, checksum).unwrap()
+            })
+    }
+
+    /// The entry of the name "a?c", whose second character couldn't be decoded.
+    fn substituted_name_entries() -> impl Iterator<Item = LongNameDirectoryEntry> {
+        let name_part = [
+            Ucs2Character::from_char('a').unwrap(),
+            Ucs2Character::from_char('b').unwrap(),
+            Ucs2Character::from_char('c').unwrap(),
+        ];
+        let mut bytes = LongNameDirectoryEntry::from_name_part(
+            0x41,
+            &name_part,
+            short_name_entry().name().checksum(),
+        )
+        .unwrap()
+        .to_bytes();
+
+        bytes[3..5].copy_from_slice(&0xD800_u16.to_le_bytes());
+
+        [LongNameDirectoryEntry::from_bytes(&bytes).unwrap()].into_iter()
+    }
+
+    fn long_name(item: &DirectoryItem) -> String {
+        item.long_name()
+            .expect("Long name should be present")
+            .chars()
+            .collect()
+    }
+
+    mod add_long_name_entry {
+        use super::*;
+
+        #[test]
+        fn over_long_name_rejected() {
+            let error = build(LongNamePolicy::Reject, over_long_name_entries())
+                .expect_err("Err should be returned");
+
+            assert!(matches!(error, DirectoryItemError::LongNameTooLong));
+        }
+
+        #[test]
+        fn over_long_name_truncated() {
+            let (item, is_skipped) = build(LongNamePolicy::Repair, over_long_name_entries())
+                .expect("Ok should be returned");
+
+            assert_eq!(long_name(&item), "a".repeat(LONG_NAME_MAX_LENGTH));
+            assert!(!is_skipped);
+        }
+
+        #[test]
+        fn over_long_name_skipped() {
+            let (_, is_skipped) = build(LongNamePolicy::Skip, over_long_name_entries())
+                .expect("Ok should be returned");
+
+            assert!(is_skipped);
+        }
+
+        #[test]
+        fn substituted_characters_rejected() {
+            let error = build(LongNamePolicy::Reject, substituted_name_entries())
+                .expect_err("Err should be returned");
+
+            assert!(matches!(
+                error,
+                DirectoryItemError::LongNameCharacterInvalid
+            ));
+        }
+
+        #[test]
+        fn substituted_characters_kept() {
+            let (item, is_skipped) = build(LongNamePolicy::Repair, substituted_name_entries())
+                .expect("Ok should be returned");
+
+            assert_eq!(long_name(&item), "a?c");
+            assert!(!is_skipped);
+        }
+
+        #[test]
+        fn substituted_characters_skipped() {
+            let (_, is_skipped) = build(LongNamePolicy::Skip, substituted_name_entries())
+                .expect("Ok should be returned");
+
+            assert!(is_skipped);
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use crate::directory_entry::{
+    DIRECTORY_ENTRY_SIZE, DirectoryEntryAttributes, LONG_NAME_CHARACTERS_PER_ENTRY,
+    LONG_NAME_MAX_ENTRY_COUNT, LongNameDirectoryEntry, ShortNameCase, ShortNameDirectoryEntry,
+};
+use crate::file_name::{LongFileName, ShortFileName};
+use bon::bon;
+
+const LAST_LONG_NAME_ENTRY_FLAG: u8 = 0x40;
+const MAX_ENTRY_COUNT: usize = LONG_NAME_MAX_ENTRY_COUNT as usize + 1;
+
+/// The on-disk entries representing a single directory item, ready to be written into consecutive
+/// directory entry slots.
+///
+/// Long name entries, if any, are stored in on-disk order followed by the short name entry.
+#[derive(Clone, Debug)]
+pub struct DirectoryItemEntries {
+    entries: [[u8; DIRECTORY_ENTRY_SIZE]; MAX_ENTRY_COUNT],
+    entry_count: usize,
+}
+
+#[bon]
+impl DirectoryItemEntries {
+    /// Timestamps are packed FAT dates and times, see `ShortNameDirectoryEntry` for details.  File
+    /// sizes above 4 GiB are only recorded with the `fat-plus` feature enabled.
+    #[builder]
+    pub fn new(
+        long_name: Option<LongFileName>,
+        short_name: ShortFileName,
+        #[builder(default)] short_name_case: ShortNameCase,
+        attributes: DirectoryEntryAttributes,
+
+        #[builder(default)] creation_time_tenths: u8,
+        #[builder(default)] creation_time: u16,
+        #[builder(default)] creation_date: u16,
+        #[builder(default)] last_access_date: u16,
+        #[builder(default)] last_write_time: u16,
+        #[builder(default)] last_write_date: u16,
+
+        #[builder(default)] first_cluster_number: u32,
+        #[builder(default)] file_size: u64,
+    ) -> Self {
+        let mut entries = [[0; DIRECTORY_ENTRY_SIZE]; MAX_ENTRY_COUNT];
+        let mut entry_count = 0;
+
+        if let Some(long_name) = long_name {
+            let short_name_checksum = short_name.checksum();
+            let name_parts = long_name
+                .ucs2_characters()
+                .chunks(LONG_NAME_CHARACTERS_PER_ENTRY);
+            let long_name_entry_count = name_parts.len();
+
+            for (part_index, name_part) in name_parts.enumerate().rev() {
+                let entry_number = part_index as u8 + 1;
+                let order_byte = if part_index + 1 == long_name_entry_count {
+                    entry_number | LAST_LONG_NAME_ENTRY_FLAG
+                } else {
+                    entry_number
+                };
+
+                let long_name_entry = LongNameDirectoryEntry::from_name_part(
+                    order_byte,
+                    name_part,
+                    short_name_checksum,
+                )
+                .expect("long file names always split into valid name parts");
+
+                entries[entry_count] = long_name_entry.to_bytes();
+                entry_count += 1;
+            }
+        }
+
+        let (file_size, file_size_reserved_bits) =
+            ShortNameDirectoryEntry::split_file_size(file_size);
+
+        entries[entry_count] = ShortNameDirectoryEntry::builder()
+            .name(short_name)
+            .attributes(attributes)
+            .reserved(file_size_reserved_bits | short_name_case.bits())
+            .creation_time_tenths(creation_time_tenths)
+            .creation_time(creation_time)
+            .creation_date(creation_date)
+            .last_access_date(last_access_date)
+            .last_write_time(last_write_time)
+            .last_write_date(last_write_date)
+            .first_cluster_number(first_cluster_number)
+            .file_size(file_size)
+            .build()
+            .to_bytes();
+        entry_count += 1;
+
+        Self {
+            entries,
+            entry_count,
+        }
+    }
+
+    /// The entries in the order they must be written to the directory.
+    pub fn entries(&self) -> &[[u8; DIRECTORY_ENTRY_SIZE]] {
+        &self.entries[..self.entry_count]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AsciiOnlyEncoder;
+    use crate::directory_entry::DirectoryEntry;
+    use crate::directory_item::{DirectoryItemBuilder, LongNamePolicy};
+    use core::str::FromStr;
+
+    mod new {
+        use super::*;
+
+        #[test]
+        fn short_name_only_produces_single_entry() {
+            let item_entries = DirectoryItemEntries::builder()
+                .short_name(ShortFileName::from_str(&AsciiOnlyEncoder, "foo.txt").unwrap())
+                .attributes(DirectoryEntryAttributes::Archive)
+                .first_cluster_number(5)
+                .file_size(10)
+                .build();
+
+            let entries = item_entries.entries();
+            assert_eq!(entries.len(), 1);
+
+            let DirectoryEntry::ShortName(entry) = DirectoryEntry::from_bytes(&entries[0]).unwrap()
+            else {
+                panic!("ShortName entry should be produced");
+            };
+
+            assert_eq!(entry.attributes(), DirectoryEntryAttributes::Archive);
+            assert_eq!(entry.first_cluster_number(), 5);
+            assert_eq!(entry.file_size(), 10);
+        }
+
+        #[test]
+        fn long_name_entries_precede_short_name_entry() {
+            let item_entries = DirectoryItemEntries::builder()
+                .long_name(LongFileName::from_str("a rather long file name.txt").unwrap())
+                .short_name(ShortFileName::from_str(&AsciiOnlyEncoder, "ARATHE~1.TXT").unwrap())
+                .attributes(DirectoryEntryAttributes::Archive)
+                .first_cluster_number(5)
+                .file_size(10)
+                .build();
+
+            let entries = item_entries.entries();
+            assert_eq!(entries.len(), 4);
+            assert_eq!(
+                entries[0][0], 0x43,
+                "First entry should be the last long name part"
+            );
+            assert_eq!(entries[1][0], 0x02);
+            assert_eq!(entries[2][0], 0x01);
+        }
+
+        #[test]
+        fn short_name_case_recorded() {
+            let item_entries = DirectoryItemEntries::builder()
+                .short_name(ShortFileName::from_str(&AsciiOnlyEncoder, "foo.txt").unwrap())
+                .short_name_case(ShortNameCase::LowerCaseBaseName)
+                .attributes(DirectoryEntryAttributes::Archive)
+                .first_cluster_number(5)
+                .file_size(u64::from(u32::MAX))
+                .build();
+
+            let DirectoryEntry::ShortName(entry) =
+                DirectoryEntry::from_bytes(&item_entries.entries()[0]).unwrap()
+            else {
+                panic!("ShortName entry should be produced");
+            };
+
+            assert_eq!(entry.name_case(), ShortNameCase::LowerCaseBaseName);
+            assert_eq!(entry.extended_file_size(), u64::from(u32::MAX));
+        }
+
+        #[test]
+        fn entries_parse_into_equivalent_item() {
+            let long_name = LongFileName::from_str("a rather long file name.txt").unwrap();
+            let short_name = ShortFileName::from_str(&AsciiOnlyEncoder, "ARATHE~1.TXT").unwrap();
+
+            let item_entries = DirectoryItemEntries::builder()
+                .long_name(long_name.clone())
+                .short_name(short_name.clone())
+                .attributes(DirectoryEntryAttributes::Archive)
+                .first_cluster_number(5)
+                .file_size(10)
+                .build();
+
+            let mut builder = DirectoryItemBuilder::new(LongNamePolicy::Reject);
+            let mut item = None;
+
+            for entry_bytes in item_entries.entries() {
+                match DirectoryEntry::from_bytes(entry_bytes).unwrap() {
+                    DirectoryEntry::LongName(entry) => {
+                        builder = builder
+                            .add_long_name_entry(entry)
+                            .expect("Long name entry should be accepted");
+                    }
+                    DirectoryEntry::ShortName(entry) => {
+                        item = Some(builder.clone().build(entry).expect("Item should be built"));
+                    }
+                    DirectoryEntry::Free(_) => panic!("Free entry should not be produced"),
+                }
+            }
+
+            let item = item.expect("Short name entry should be produced");
+            assert_eq!(item.long_name(), Some(&long_name));
+            assert_eq!(item.short_name(), &short_name);
+            assert_eq!(item.first_cluster_number(), 5);
+            assert_eq!(item.file_size(), 10);
+        }
+    }
+}
@@ -4,6 +4,7 @@ use core::fmt::{Display, Formatter};
 #[derive(Clone, Debug)]
 #[cfg_attr(test, derive(strum::EnumIter))]
 pub enum DirectoryItemError {
+    LongNameCharacterInvalid,
     LongNameCorrupted,
     LongNameEntryNumberWrong,
     LongNameEmpty,
@@ -17,6 +18,12 @@ pub enum DirectoryItemError {
 impl Display for DirectoryItemError {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
+            DirectoryItemError::LongNameCharacterInvalid => {
+                write!(
+                    f,
+                    "the long name directory entry chain contains characters which can't be decoded"
+                )
+            }
             DirectoryItemError::LongNameCorrupted => {
                 write!(
                     f,
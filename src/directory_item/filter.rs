@@ -0,0 +1,209 @@
+use crate::directory_item::DirectoryItem;
+use crate::encoding::Ucs2Character;
+use bon::Builder;
+
+/// Metadata conditions an item must meet to be returned by
+/// [`FileSystem::find`](crate::FileSystem::find), unset conditions accept every item.
+///
+/// Timestamps are packed FAT dates and times, which order chronologically when compared
+/// numerically.
+#[derive(Builder, Clone, Debug, Default, Eq, PartialEq)]
+pub struct DirectoryItemFilter<'a> {
+    /// Extension without its leading `.`, compared ignoring case against the long name where
+    /// present and the short name otherwise.
+    extension: Option<&'a str>,
+
+    /// Whether only files, rather than directories, are accepted.
+    #[builder(default)]
+    files_only: bool,
+
+    min_size: Option<u32>,
+    max_size: Option<u32>,
+
+    /// Earliest last write timestamp accepted, as a date and time.
+    #[builder(with = |date: u16, time: u16| (date, time))]
+    modified_since: Option<(u16, u16)>,
+}
+
+impl DirectoryItemFilter<'_> {
+    pub fn matches(&self, item: &DirectoryItem) -> bool {
+        if self.files_only && !item.is_file() {
+            return false;
+        }
+
+        if let Some(extension) = self.extension
+            && !Self::has_extension(item, extension)
+        {
+            return false;
+        }
+
+        if self
+            .min_size
+            .is_some_and(|min_size| item.file_size() < min_size)
+            || self
+                .max_size
+                .is_some_and(|max_size| item.file_size() > max_size)
+        {
+            return false;
+        }
+
+        if let Some(modified_since) = self.modified_since
+            && (item.last_write_date(), item.last_write_time()) < modified_since
+        {
+            return false;
+        }
+
+        true
+    }
+
+    fn has_extension(item: &DirectoryItem, extension: &str) -> bool {
+        let Some(long_name) = item.long_name() else {
+            return item
+                .short_name()
+                .extension()
+                .eq_ignore_ascii_case(extension.as_bytes());
+        };
+
+        let ucs2_characters = long_name.ucs2_characters();
+        let Some(separator_index) = ucs2_characters
+            .iter()
+            .rposition(|character| character.to_char() == '.')
+        else {
+            return extension.is_empty();
+        };
+
+        let mut item_extension = ucs2_characters[separator_index + 1..].iter();
+        for character in extension.chars() {
+            match (item_extension.next(), Ucs2Character::from_char(character)) {
+                (Some(item_character), Some(character))
+                    if item_character.eq_ignore_case(&character) => {}
+                _ => return false,
+            }
+        }
+
+        item_extension.next().is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AsciiOnlyEncoder;
+    use crate::directory_entry::{DirectoryEntryAttributes, ShortNameDirectoryEntry};
+    use crate::file_name::{LongFileName, ShortFileName};
+    use core::str::FromStr;
+
+    fn item(short_name: &str, long_name: Option<&str>) -> DirectoryItem {
+        DirectoryItem::new(
+            ShortNameDirectoryEntry::builder()
+                .name(ShortFileName::from_str(&AsciiOnlyEncoder, short_name).unwrap())
+                .attributes(DirectoryEntryAttributes::empty())
+                .last_write_date(100)
+                .last_write_time(200)
+                .first_cluster_number(2)
+                .file_size(1_000)
+                .build(),
+            long_name.map(|long_name| LongFileName::from_str(long_name).unwrap()),
+        )
+    }
+
+    fn directory() -> DirectoryItem {
+        DirectoryItem::new(
+            ShortNameDirectoryEntry::builder()
+                .name(ShortFileName::from_str(&AsciiOnlyEncoder, "foo").unwrap())
+                .attributes(DirectoryEntryAttributes::Subdirectory)
+                .first_cluster_number(2)
+                .file_size(0)
+                .build(),
+            None,
+        )
+    }
+
+    mod matches {
+        use super::*;
+
+        #[test]
+        fn default_matches_everything() {
+            let filter = DirectoryItemFilter::default();
+
+            assert!(filter.matches(&item("foo.upd", None)));
+            assert!(filter.matches(&directory()));
+        }
+
+        #[test]
+        fn files_only_excludes_directories() {
+            let filter = DirectoryItemFilter::builder().files_only(true).build();
+
+            assert!(filter.matches(&item("foo.upd", None)));
+            assert!(!filter.matches(&directory()));
+        }
+
+        #[test]
+        fn extension_compared_against_short_name() {
+            let filter = DirectoryItemFilter::builder().extension("upd").build();
+
+            assert!(filter.matches(&item("foo.upd", None)));
+            assert!(!filter.matches(&item("foo.bin", None)));
+            assert!(!filter.matches(&item("foo", None)));
+        }
+
+        #[test]
+        fn extension_compared_against_long_name() {
+            let filter = DirectoryItemFilter::builder().extension("jpeg").build();
+
+            assert!(filter.matches(&item("photo~1.jpe", Some("Photo.v2.JPEG"))));
+            assert!(!filter.matches(&item("photo~1.jpe", Some("Photo.jpe"))));
+            assert!(!filter.matches(&item("photo~1.jpe", Some("Photo.jpegs"))));
+            assert!(!filter.matches(&item("photo~1", Some("Photo"))));
+        }
+
+        #[test]
+        fn size_bounds_inclusive() {
+            let item = item("foo.upd", None);
+
+            assert!(
+                DirectoryItemFilter::builder()
+                    .min_size(1_000)
+                    .max_size(1_000)
+                    .build()
+                    .matches(&item)
+            );
+            assert!(
+                !DirectoryItemFilter::builder()
+                    .min_size(1_001)
+                    .build()
+                    .matches(&item)
+            );
+            assert!(
+                !DirectoryItemFilter::builder()
+                    .max_size(999)
+                    .build()
+                    .matches(&item)
+            );
+        }
+
+        #[test]
+        fn modified_since_inclusive() {
+            let item = item("foo.upd", None);
+
+            assert!(
+                DirectoryItemFilter::builder()
+                    .modified_since(100, 200)
+                    .build()
+                    .matches(&item)
+            );
+            assert!(
+                DirectoryItemFilter::builder()
+                    .modified_since(99, 300)
+                    .build()
+                    .matches(&item)
+            );
+            assert!(
+                !DirectoryItemFilter::builder()
+                    .modified_since(100, 201)
+                    .build()
+                    .matches(&item)
+            );
+        }
+    }
+}
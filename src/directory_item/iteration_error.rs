@@ -14,10 +14,19 @@ where
     DE: Error,
     SE: embedded_io::Error,
 {
-    AllocationTableEntryTypeUnexpected,
+    AllocationTableEntryTypeUnexpected {
+        cluster_number: u32,
+    },
+    ClusterNumberInvalid {
+        cluster_number: u32,
+    },
     DeviceError(DE),
-    EntryInvalid(DirectoryEntryError),
+    EntryInvalid {
+        address: u64,
+        error: DirectoryEntryError,
+    },
     ItemError(DirectoryItemError),
+    ListingChanged,
     StreamEndReached,
     StreamError(SE),
 }
@@ -29,18 +38,33 @@ where
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
-            DirectoryItemIterationError::AllocationTableEntryTypeUnexpected => {
-                write!(f, "the allocation table entry was an unexpected type")
+            DirectoryItemIterationError::AllocationTableEntryTypeUnexpected { cluster_number } => {
+                write!(
+                    f,
+                    "the allocation table entry for cluster {cluster_number} was an unexpected type"
+                )
+            }
+            DirectoryItemIterationError::ClusterNumberInvalid { cluster_number } => {
+                write!(
+                    f,
+                    "cluster {cluster_number} is not a valid data region cluster number"
+                )
             }
             DirectoryItemIterationError::DeviceError(e) => {
                 write!(f, "device error occurred: {}", e)
             }
-            DirectoryItemIterationError::EntryInvalid(e) => {
-                write!(f, "an invalid entry was encountered: {}", e)
+            DirectoryItemIterationError::EntryInvalid { address, error } => {
+                write!(
+                    f,
+                    "an invalid entry was encountered at address 0x{address:X}: {error}"
+                )
             }
             DirectoryItemIterationError::ItemError(e) => {
                 write!(f, "an invalid item was encountered: {}", e)
             }
+            DirectoryItemIterationError::ListingChanged => {
+                write!(f, "the directory was written to while it was being listed")
+            }
             DirectoryItemIterationError::StreamEndReached => {
                 write!(f, "stream end was reached when not expected")
             }
@@ -65,11 +89,16 @@ where
 {
     fn from(value: DirectoryEntryIterationError<DE, SE>) -> Self {
         match value {
-            DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected => {
-                Self::AllocationTableEntryTypeUnexpected
+            DirectoryEntryIterationError::AllocationTableEntryTypeUnexpected { cluster_number } => {
+                Self::AllocationTableEntryTypeUnexpected { cluster_number }
+            }
+            DirectoryEntryIterationError::ClusterNumberInvalid { cluster_number } => {
+                Self::ClusterNumberInvalid { cluster_number }
             }
             DirectoryEntryIterationError::DeviceError(e) => Self::DeviceError(e),
-            DirectoryEntryIterationError::EntryInvalid(e) => Self::EntryInvalid(e),
+            DirectoryEntryIterationError::EntryInvalid { address, error } => {
+                Self::EntryInvalid { address, error }
+            }
             DirectoryEntryIterationError::StreamEndReached => Self::StreamEndReached,
             DirectoryEntryIterationError::StreamError(e) => Self::StreamError(e),
         }
@@ -100,10 +129,14 @@ mod tests {
         #[test]
         fn produces_non_empty_value() {
             let values = [
-                DirectoryItemIterationError::AllocationTableEntryTypeUnexpected,
+                DirectoryItemIterationError::AllocationTableEntryTypeUnexpected {
+                    cluster_number: 2,
+                },
+                DirectoryItemIterationError::ClusterNumberInvalid { cluster_number: 0 },
                 DirectoryItemIterationError::DeviceError(IoError::default()),
-                DirectoryItemIterationError::EntryInvalid(
-                    DirectoryEntryError::ShortNameEntryInvalid(
+                DirectoryItemIterationError::EntryInvalid {
+                    address: 0,
+                    error: DirectoryEntryError::ShortNameEntryInvalid(
                         ShortNameDirectoryEntryError::NameInvalid(
                             ShortFileNameError::CharacterInvalid {
                                 character: 0,
@@ -111,8 +144,9 @@ mod tests {
                             },
                         ),
                     ),
-                ),
+                },
                 DirectoryItemIterationError::ItemError(DirectoryItemError::LongNameCorrupted),
+                DirectoryItemIterationError::ListingChanged,
                 DirectoryItemIterationError::StreamEndReached,
                 DirectoryItemIterationError::StreamError(IoError::default()),
             ];
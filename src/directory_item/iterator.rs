@@ -1,11 +1,13 @@
-use crate::Device;
 use crate::directory_entry::{
-    DirectoryEntry, DirectoryEntryIterator, FreeDirectoryEntry, LONG_NAME_CHARACTERS_PER_ENTRY,
+    DEFAULT_BUFFER_SIZE, DirectoryEntry, DirectoryEntryIterator, DirectoryEntryPosition,
+    FreeDirectoryEntry, LONG_NAME_CHARACTERS_PER_ENTRY, ShortNameDirectoryEntry,
 };
 use crate::directory_item::{
     DIRECTORY_ENTITY_LONG_NAME_MAX_LENGTH, DeviceDirectoryItemIterationError, DirectoryItem,
-    DirectoryItemBuilder, DirectoryItemError,
+    DirectoryItemBuilder, DirectoryItemError, EntryLocation, LongNamePolicy,
 };
+use crate::file_name::ShortFileName;
+use crate::{Device, InvalidTimestampPolicy};
 use embedded_io::{ErrorType, SeekFrom};
 
 #[cfg(feature = "sync")]
@@ -17,6 +19,7 @@ use {
 #[cfg(feature = "async")]
 use {
     crate::AsyncDevice,
+    crate::yield_budget::YieldBudget,
     embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek},
 };
 
@@ -24,19 +27,100 @@ const MAX_ENTRY_COUNT: usize =
     DIRECTORY_ENTITY_LONG_NAME_MAX_LENGTH.div_ceil(LONG_NAME_CHARACTERS_PER_ENTRY) + 1;
 
 #[derive(Clone, Debug)]
-pub struct DirectoryItemIterator<'a, D>
+pub struct DirectoryItemIterator<'a, D, const BUFFER_SIZE: usize = DEFAULT_BUFFER_SIZE>
 where
     D: Device,
 {
-    entry_iterator: DirectoryEntryIterator<'a, D>,
+    entry_iterator: DirectoryEntryIterator<'a, D, BUFFER_SIZE>,
+    long_name_policy: LongNamePolicy,
+    skip_dot_items: bool,
+    is_recovering: bool,
+    utc_offset_minutes: i16,
+    invalid_timestamp_policy: InvalidTimestampPolicy,
 }
 
-impl<'a, D> DirectoryItemIterator<'a, D>
+impl<'a, D, const BUFFER_SIZE: usize> DirectoryItemIterator<'a, D, BUFFER_SIZE>
 where
     D: Device,
 {
-    pub fn new(entry_iterator: DirectoryEntryIterator<'a, D>) -> Self {
-        Self { entry_iterator }
+    pub fn new(entry_iterator: DirectoryEntryIterator<'a, D, BUFFER_SIZE>) -> Self {
+        Self {
+            entry_iterator,
+            long_name_policy: LongNamePolicy::default(),
+            skip_dot_items: false,
+            is_recovering: false,
+            utc_offset_minutes: 0,
+            invalid_timestamp_policy: InvalidTimestampPolicy::default(),
+        }
+    }
+
+    pub fn with_long_name_policy(mut self, long_name_policy: LongNamePolicy) -> Self {
+        self.long_name_policy = long_name_policy;
+        self
+    }
+
+    /// Sets whether the `.` and `..` items are left out, they are returned by default.
+    pub fn with_dot_items_skipped(mut self, skip_dot_items: bool) -> Self {
+        self.skip_dot_items = skip_dot_items;
+        self
+    }
+
+    /// Sets the offset of the volume's local time from UTC given to the returned items.
+    pub fn with_utc_offset(mut self, utc_offset_minutes: i16) -> Self {
+        self.utc_offset_minutes = utc_offset_minutes;
+        self
+    }
+
+    /// Sets how the returned items read timestamps which don't exist.
+    pub fn with_invalid_timestamp_policy(
+        mut self,
+        invalid_timestamp_policy: InvalidTimestampPolicy,
+    ) -> Self {
+        self.invalid_timestamp_policy = invalid_timestamp_policy;
+        self
+    }
+
+    /// The position of the entries following the last returned item, `None` once the iterator is
+    /// exhausted.
+    pub fn position(&self) -> Option<DirectoryEntryPosition> {
+        self.entry_iterator.position()
+    }
+
+    /// Bounds on the number of results left, which is at most the number of entries left since
+    /// each result consumes at least one entry.
+    pub fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.entry_iterator.remaining_entry_count())
+    }
+
+    /// Moves the iterator to a position previously returned by `position` on an iterator over the
+    /// same directory.
+    pub fn set_position(&mut self, position: Option<DirectoryEntryPosition>) {
+        self.is_recovering = false;
+
+        match position {
+            Some(position) => self.entry_iterator.set_position(position),
+            None => self.entry_iterator.finish(),
+        }
+    }
+
+    /// Whether the entry is a leftover fragment of a long name which was already reported as
+    /// invalid, such fragments are passed over so the item is returned under its short name.
+    fn is_recovered_fragment(&mut self, entry: &DirectoryEntry) -> bool {
+        if !self.is_recovering {
+            return false;
+        }
+
+        match entry {
+            DirectoryEntry::LongName(long_name_entry) if !long_name_entry.is_last_entry() => true,
+            _ => {
+                self.is_recovering = false;
+                false
+            }
+        }
+    }
+
+    fn is_skipped_short_name(&self, short_name: &ShortFileName) -> bool {
+        self.skip_dot_items && (short_name.is_dot() || short_name.is_dotdot())
     }
 
     fn should_skip_advancing_iterator(&self, directory_item_error: &DirectoryItemError) -> bool {
@@ -45,14 +129,16 @@ where
 }
 
 #[cfg(feature = "sync")]
-impl<D, S> DirectoryItemIterator<'_, D>
+impl<D, S, const BUFFER_SIZE: usize> DirectoryItemIterator<'_, D, BUFFER_SIZE>
 where
     D: SyncDevice<Stream = S>,
     S: Read + Seek,
 {
     pub fn next(&mut self) -> Option<Result<DirectoryItem, DeviceDirectoryItemIterationError<D>>> {
         let mut is_first_entry = true;
-        let mut builder = DirectoryItemBuilder::new();
+        let mut builder = DirectoryItemBuilder::new(self.long_name_policy);
+        let mut first_entry_position = None;
+        let mut entry_count = 0;
 
         loop {
             let entry = match self.entry_iterator.peek() {
@@ -72,26 +158,46 @@ where
                 }
             };
 
+            if self.is_recovered_fragment(&entry) {
+                propagate_iteration_error!(self.entry_iterator.advance());
+                continue;
+            }
+
+            if is_first_entry && !matches!(entry, DirectoryEntry::Free(_)) {
+                first_entry_position = self.entry_iterator.position();
+            }
+
             match entry {
                 DirectoryEntry::Free(free_entry) => {
                     propagate_iteration_error!(self.entry_iterator.advance());
 
+                    // Nothing after the marker is in use, so the listing ends here even when a
+                    // long name is left without its short name entry
+                    let is_end_reached = matches!(free_entry, FreeDirectoryEntry::AllFollowing);
+                    if is_end_reached {
+                        self.entry_iterator.finish();
+                    }
+
                     if !is_first_entry {
                         return Some(Err(DirectoryItemError::LongNameOrphaned.into()));
                     }
 
-                    match free_entry {
-                        FreeDirectoryEntry::CurrentOnly => continue,
-                        FreeDirectoryEntry::AllFollowing => return None,
+                    if is_end_reached {
+                        return None;
                     }
+
+                    continue;
                 }
                 DirectoryEntry::LongName(long_name_entry) => {
                     builder = match builder.add_long_name_entry(long_name_entry) {
                         Ok(builder) => {
                             propagate_iteration_error!(self.entry_iterator.advance());
+                            entry_count += 1;
                             builder
                         }
                         Err(directory_item_error) => {
+                            self.is_recovering = true;
+
                             if !self.should_skip_advancing_iterator(&directory_item_error) {
                                 propagate_iteration_error!(self.entry_iterator.advance());
                             }
@@ -101,9 +207,26 @@ where
                     };
                 }
                 DirectoryEntry::ShortName(short_name_entry) => {
-                    let item = propagate_iteration_error!(builder.build(short_name_entry));
+                    let is_skipped = builder.is_skipped();
+                    let mut item = propagate_iteration_error!(builder.build(short_name_entry))
+                        .with_utc_offset(self.utc_offset_minutes)
+                        .with_invalid_timestamp_policy(self.invalid_timestamp_policy);
+
+                    if let Some(position) = first_entry_position {
+                        item = item.with_location(EntryLocation::new(position, entry_count + 1));
+                    }
+
                     propagate_iteration_error!(self.entry_iterator.advance());
 
+                    if is_skipped || self.is_skipped_short_name(item.short_name()) {
+                        is_first_entry = true;
+                        builder = DirectoryItemBuilder::new(self.long_name_policy);
+                        first_entry_position = None;
+                        entry_count = 0;
+
+                        continue;
+                    }
+
                     return Some(Ok(item));
                 }
             }
@@ -111,10 +234,75 @@ where
             is_first_entry = false;
         }
     }
+
+    /// Moves past the given number of items by counting their short name entries, which avoids
+    /// assembling their long names.  Returns `None` if the directory ends first.
+    pub fn skip_items(
+        &mut self,
+        item_count: usize,
+    ) -> Option<Result<(), DeviceDirectoryItemIterationError<D>>> {
+        let mut remaining_item_count = item_count;
+
+        while remaining_item_count > 0 {
+            match propagate_iteration_error!(self.entry_iterator.peek()?) {
+                DirectoryEntry::Free(FreeDirectoryEntry::AllFollowing) => {
+                    self.entry_iterator.finish();
+                    return None;
+                }
+                DirectoryEntry::ShortName(short_name_entry)
+                    if !self.is_skipped_short_name(short_name_entry.name()) =>
+                {
+                    remaining_item_count -= 1
+                }
+                _ => {}
+            }
+
+            propagate_iteration_error!(self.entry_iterator.advance());
+        }
+
+        Some(Ok(()))
+    }
+
+    /// Counts the items from the current position to the end of the directory by their short
+    /// name entries, which avoids assembling their long names.  The iterator is left exhausted.
+    pub fn count_items(&mut self) -> Result<usize, DeviceDirectoryItemIterationError<D>> {
+        self.visit_items(|_| {})
+    }
+
+    /// Counts the items from the current position to the end of the directory like
+    /// `count_items`, handing each item's short name entry to `visit`.
+    pub(crate) fn visit_items<V>(
+        &mut self,
+        mut visit: V,
+    ) -> Result<usize, DeviceDirectoryItemIterationError<D>>
+    where
+        V: FnMut(&ShortNameDirectoryEntry),
+    {
+        let mut item_count = 0;
+
+        while let Some(entry) = self.entry_iterator.peek() {
+            match entry? {
+                DirectoryEntry::Free(FreeDirectoryEntry::AllFollowing) => break,
+                DirectoryEntry::ShortName(short_name_entry)
+                    if !self.is_skipped_short_name(short_name_entry.name()) =>
+                {
+                    visit(&short_name_entry);
+                    item_count += 1
+                }
+                _ => {}
+            }
+
+            self.entry_iterator.advance()?;
+        }
+
+        self.entry_iterator.finish();
+
+        Ok(item_count)
+    }
 }
 
 #[cfg(feature = "async")]
-impl<D, S> DirectoryItemIterator<'_, D>
+impl<D, S, const BUFFER_SIZE: usize> DirectoryItemIterator<'_, D, BUFFER_SIZE>
 where
     D: AsyncDevice<Stream = S>,
     S: AsyncRead + AsyncSeek,
@@ -123,7 +311,9 @@ where
         &mut self,
     ) -> Option<Result<DirectoryItem, DeviceDirectoryItemIterationError<D>>> {
         let mut is_first_entry = true;
-        let mut builder = DirectoryItemBuilder::new();
+        let mut builder = DirectoryItemBuilder::new(self.long_name_policy);
+        let mut first_entry_position = None;
+        let mut entry_count = 0;
 
         loop {
             let entry = match self.entry_iterator.peek_async().await {
@@ -137,26 +327,46 @@ where
                 }
             };
 
+            if self.is_recovered_fragment(&entry) {
+                propagate_iteration_error!(self.entry_iterator.advance_async().await);
+                continue;
+            }
+
+            if is_first_entry && !matches!(entry, DirectoryEntry::Free(_)) {
+                first_entry_position = self.entry_iterator.position();
+            }
+
             match entry {
                 DirectoryEntry::Free(free_entry) => {
                     propagate_iteration_error!(self.entry_iterator.advance_async().await);
 
+                    // Nothing after the marker is in use, so the listing ends here even when a
+                    // long name is left without its short name entry
+                    let is_end_reached = matches!(free_entry, FreeDirectoryEntry::AllFollowing);
+                    if is_end_reached {
+                        self.entry_iterator.finish();
+                    }
+
                     if !is_first_entry {
                         return Some(Err(DirectoryItemError::LongNameOrphaned.into()));
                     }
 
-                    match free_entry {
-                        FreeDirectoryEntry::CurrentOnly => continue,
-                        FreeDirectoryEntry::AllFollowing => return None,
+                    if is_end_reached {
+                        return None;
                     }
+
+                    continue;
                 }
                 DirectoryEntry::LongName(long_name_entry) => {
                     builder = match builder.add_long_name_entry(long_name_entry) {
                         Ok(builder) => {
                             propagate_iteration_error!(self.entry_iterator.advance_async().await);
+                            entry_count += 1;
                             builder
                         }
                         Err(directory_item_error) => {
+                            self.is_recovering = true;
+
                             if !self.should_skip_advancing_iterator(&directory_item_error) {
                                 propagate_iteration_error!(
                                     self.entry_iterator.advance_async().await
@@ -168,9 +378,26 @@ where
                     };
                 }
                 DirectoryEntry::ShortName(short_name_entry) => {
-                    let item = propagate_iteration_error!(builder.build(short_name_entry));
+                    let is_skipped = builder.is_skipped();
+                    let mut item = propagate_iteration_error!(builder.build(short_name_entry))
+                        .with_utc_offset(self.utc_offset_minutes)
+                        .with_invalid_timestamp_policy(self.invalid_timestamp_policy);
+
+                    if let Some(position) = first_entry_position {
+                        item = item.with_location(EntryLocation::new(position, entry_count + 1));
+                    }
+
                     propagate_iteration_error!(self.entry_iterator.advance_async().await);
 
+                    if is_skipped || self.is_skipped_short_name(item.short_name()) {
+                        is_first_entry = true;
+                        builder = DirectoryItemBuilder::new(self.long_name_policy);
+                        first_entry_position = None;
+                        entry_count = 0;
+
+                        continue;
+                    }
+
                     return Some(Ok(item));
                 }
             }
@@ -178,12 +405,85 @@ where
             is_first_entry = false;
         }
     }
+
+    /// Moves past the given number of items by counting their short name entries, which avoids
+    /// assembling their long names.  Returns `None` if the directory ends first.
+    pub async fn skip_items_async(
+        &mut self,
+        item_count: usize,
+    ) -> Option<Result<(), DeviceDirectoryItemIterationError<D>>> {
+        let mut remaining_item_count = item_count;
+
+        while remaining_item_count > 0 {
+            match propagate_iteration_error!(self.entry_iterator.peek_async().await?) {
+                DirectoryEntry::Free(FreeDirectoryEntry::AllFollowing) => {
+                    self.entry_iterator.finish();
+                    return None;
+                }
+                DirectoryEntry::ShortName(short_name_entry)
+                    if !self.is_skipped_short_name(short_name_entry.name()) =>
+                {
+                    remaining_item_count -= 1
+                }
+                _ => {}
+            }
+
+            propagate_iteration_error!(self.entry_iterator.advance_async().await);
+        }
+
+        Some(Ok(()))
+    }
+
+    /// Counts the items from the current position to the end of the directory, see
+    /// `count_items`.  Yields to the executor as the budget runs out, so long directories don't
+    /// hold up other tasks and the count can be cancelled by dropping it.
+    pub(crate) async fn count_items_async(
+        &mut self,
+        yield_budget: &mut YieldBudget,
+    ) -> Result<usize, DeviceDirectoryItemIterationError<D>> {
+        self.visit_items_async(yield_budget, |_| {}).await
+    }
+
+    /// Counts the items from the current position to the end of the directory like
+    /// `count_items_async`, handing each item's short name entry to `visit`.
+    pub(crate) async fn visit_items_async<V>(
+        &mut self,
+        yield_budget: &mut YieldBudget,
+        mut visit: V,
+    ) -> Result<usize, DeviceDirectoryItemIterationError<D>>
+    where
+        V: FnMut(&ShortNameDirectoryEntry),
+    {
+        let mut item_count = 0;
+
+        while let Some(entry) = self.entry_iterator.peek_async().await {
+            match entry? {
+                DirectoryEntry::Free(FreeDirectoryEntry::AllFollowing) => break,
+                DirectoryEntry::ShortName(short_name_entry)
+                    if !self.is_skipped_short_name(short_name_entry.name()) =>
+                {
+                    visit(&short_name_entry);
+                    item_count += 1
+                }
+                _ => {}
+            }
+
+            self.entry_iterator.advance_async().await?;
+            yield_budget.tick().await;
+        }
+
+        self.entry_iterator.finish();
+
+        Ok(item_count)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::directory_entry::{DirectoryEntryAttributes, ShortNameDirectoryEntry};
+    use crate::directory_entry::{DirectoryEntryAttributes, LongNameDirectoryEntry};
+    use crate::directory_item::{DirectoryItemIterationError, DirectoryItemKind};
+    use crate::encoding::Ucs2Character;
     use crate::file_name::ShortFileName;
     use crate::mock::{ScriptedDirectoryEntryIterator, VoidStream};
     use crate::{AsciiOnlyEncoder, SingleAccessDevice};
@@ -219,5 +519,347 @@ mod tests {
             assert_eq!(result.short_directory_entry, expected_short_directory_entry);
             assert_eq!(result.long_name, None);
         }
+
+        #[test]
+        fn volume_label_classified() {
+            let expected_short_directory_entry = ShortNameDirectoryEntry::builder()
+                .name(ShortFileName::new(*b"MY VOLUME  ").unwrap())
+                .attributes(DirectoryEntryAttributes::VolumeLabel)
+                .first_cluster_number(0)
+                .file_size(0)
+                .build();
+
+            let scripted_entry_iterator =
+                ScriptedDirectoryEntryIterator::<SingleAccessDevice<VoidStream>>::new()
+                    .with_peek(|index| match index {
+                        0 => Some(Ok(expected_short_directory_entry.clone().into())),
+                        _ => panic!("Shouldn't be reached"),
+                    })
+                    .with_advance(|index| Ok(index == 0));
+
+            let mut item_iterator = DirectoryItemIterator::new(scripted_entry_iterator.into());
+
+            let result = item_iterator
+                .next()
+                .expect("Some should be returned")
+                .expect("Ok should be returned");
+
+            assert_eq!(result.kind(), DirectoryItemKind::VolumeLabel);
+            assert!(!result.is_file());
+            assert!(!result.is_directory());
+        }
+
+        #[test]
+        fn dot_items_skipped() {
+            let dot_entry = |name: &[u8; 11]| -> DirectoryEntry {
+                ShortNameDirectoryEntry::builder()
+                    .name(ShortFileName::new(*name).unwrap())
+                    .attributes(DirectoryEntryAttributes::Subdirectory)
+                    .first_cluster_number(0)
+                    .file_size(0)
+                    .build()
+                    .into()
+            };
+            let expected_short_directory_entry = ShortNameDirectoryEntry::builder()
+                .name(ShortFileName::from_str(&AsciiOnlyEncoder, "foo.txt").unwrap())
+                .attributes(DirectoryEntryAttributes::empty())
+                .first_cluster_number(2)
+                .file_size(1)
+                .build();
+
+            let scripted_entry_iterator =
+                ScriptedDirectoryEntryIterator::<SingleAccessDevice<VoidStream>>::new()
+                    .with_peek(move |index| match index {
+                        0 => Some(Ok(dot_entry(b".          "))),
+                        1 => Some(Ok(dot_entry(b"..         "))),
+                        2 => Some(Ok(expected_short_directory_entry.clone().into())),
+                        _ => panic!("Shouldn't be reached"),
+                    })
+                    .with_advance(|index| Ok(index < 2));
+
+            let mut item_iterator = DirectoryItemIterator::new(scripted_entry_iterator.into())
+                .with_dot_items_skipped(true);
+
+            let result = item_iterator
+                .next()
+                .expect("Some should be returned")
+                .expect("Ok should be returned");
+
+            assert_eq!(
+                result.short_name(),
+                &ShortFileName::from_str(&AsciiOnlyEncoder, "foo.txt").unwrap()
+            );
+        }
+
+        #[test]
+        fn corrupt_long_name_item_returned_under_short_name() {
+            let expected_short_directory_entry = ShortNameDirectoryEntry::builder()
+                .name(ShortFileName::from_str(&AsciiOnlyEncoder, "foo.txt").unwrap())
+                .attributes(DirectoryEntryAttributes::empty())
+                .first_cluster_number(2)
+                .file_size(1)
+                .build();
+            let checksum = expected_short_directory_entry.name().checksum();
+            let long_name_entry = move |order_byte: u8| -> DirectoryEntry {
+                LongNameDirectoryEntry::from_name_part(
+                    order_byte,
+                    &[Ucs2Character::from_char('a').unwrap(); 13],
+                    checksum,
+                )
+                .unwrap()
+                .into()
+            };
+
+            // The fragment numbered 2 is out of order, so the name can't be assembled
+            let scripted_entry_iterator =
+                ScriptedDirectoryEntryIterator::<SingleAccessDevice<VoidStream>>::new()
+                    .with_peek(move |index| match index {
+                        0 => Some(Ok(long_name_entry(0x43))),
+                        1 => Some(Ok(long_name_entry(0x01))),
+                        2 => Some(Ok(long_name_entry(0x02))),
+                        3 => Some(Ok(expected_short_directory_entry.clone().into())),
+                        _ => None,
+                    })
+                    .with_advance(|index| Ok(index < 4));
+
+            let mut item_iterator = DirectoryItemIterator::new(scripted_entry_iterator.into());
+
+            let warning = item_iterator.next().expect("Some should be returned");
+            assert!(matches!(
+                warning,
+                Err(DirectoryItemIterationError::ItemError(
+                    DirectoryItemError::LongNameEntryNumberWrong
+                ))
+            ));
+
+            let result = item_iterator
+                .next()
+                .expect("Some should be returned")
+                .expect("Ok should be returned");
+            assert_eq!(
+                result.short_name(),
+                &ShortFileName::from_str(&AsciiOnlyEncoder, "foo.txt").unwrap()
+            );
+            assert_eq!(result.long_name, None);
+
+            assert!(item_iterator.next().is_none());
+        }
+
+        #[test]
+        fn entries_after_end_marker_ignored() {
+            let scripted_entry_iterator =
+                ScriptedDirectoryEntryIterator::<SingleAccessDevice<VoidStream>>::new()
+                    .with_peek(|index| match index {
+                        0 => Some(Ok(FreeDirectoryEntry::AllFollowing.into())),
+                        _ => panic!("Shouldn't be reached"),
+                    })
+                    .with_advance(|_| Ok(true));
+
+            let mut item_iterator = DirectoryItemIterator::new(scripted_entry_iterator.into());
+
+            assert!(item_iterator.next().is_none());
+            assert!(item_iterator.next().is_none());
+        }
+
+        #[test]
+        fn orphaned_long_name_before_end_marker_ends_listing() {
+            let long_name_entry = LongNameDirectoryEntry::from_name_part(
+                0x41,
+                &[Ucs2Character::from_char('a').unwrap()],
+                0,
+            )
+            .unwrap();
+
+            let scripted_entry_iterator =
+                ScriptedDirectoryEntryIterator::<SingleAccessDevice<VoidStream>>::new()
+                    .with_peek(move |index| match index {
+                        0 => Some(Ok(long_name_entry.clone().into())),
+                        1 => Some(Ok(FreeDirectoryEntry::AllFollowing.into())),
+                        _ => panic!("Shouldn't be reached"),
+                    })
+                    .with_advance(|_| Ok(true));
+
+            let mut item_iterator = DirectoryItemIterator::new(scripted_entry_iterator.into());
+
+            assert!(matches!(
+                item_iterator.next(),
+                Some(Err(DirectoryItemIterationError::ItemError(
+                    DirectoryItemError::LongNameOrphaned
+                )))
+            ));
+            assert!(item_iterator.next().is_none());
+        }
+
+        #[test]
+        fn skipped_item_left_out() {
+            let skipped_short_directory_entry = ShortNameDirectoryEntry::builder()
+                .name(ShortFileName::from_str(&AsciiOnlyEncoder, "skip.txt").unwrap())
+                .attributes(DirectoryEntryAttributes::empty())
+                .first_cluster_number(2)
+                .file_size(1)
+                .build();
+            let expected_short_directory_entry = ShortNameDirectoryEntry::builder()
+                .name(ShortFileName::from_str(&AsciiOnlyEncoder, "foo.txt").unwrap())
+                .attributes(DirectoryEntryAttributes::empty())
+                .first_cluster_number(3)
+                .file_size(1)
+                .build();
+
+            // A long name whose only character couldn't be decoded
+            let mut long_name_entry_bytes = LongNameDirectoryEntry::from_name_part(
+                0x41,
+                &[Ucs2Character::from_char('a').unwrap()],
+                skipped_short_directory_entry.name().checksum(),
+            )
+            .unwrap()
+            .to_bytes();
+            long_name_entry_bytes[1..3].copy_from_slice(&0xDC00_u16.to_le_bytes());
+            let long_name_entry = LongNameDirectoryEntry::from_bytes(&long_name_entry_bytes)
+                .expect("Ok should be returned");
+
+            let scripted_entry_iterator =
+                ScriptedDirectoryEntryIterator::<SingleAccessDevice<VoidStream>>::new()
+                    .with_peek(|index| match index {
+                        0 => Some(Ok(long_name_entry.clone().into())),
+                        1 => Some(Ok(skipped_short_directory_entry.clone().into())),
+                        2 => Some(Ok(expected_short_directory_entry.clone().into())),
+                        _ => panic!("Shouldn't be reached"),
+                    })
+                    .with_advance(|index| Ok(index < 2));
+
+            let mut item_iterator = DirectoryItemIterator::new(scripted_entry_iterator.into())
+                .with_long_name_policy(LongNamePolicy::Skip);
+
+            let result = item_iterator
+                .next()
+                .expect("Some should be returned")
+                .expect("Ok should be returned");
+
+            assert_eq!(result.short_directory_entry, expected_short_directory_entry);
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    mod skip_items {
+        use super::*;
+
+        fn short_directory_entry(name: &str) -> ShortNameDirectoryEntry {
+            ShortNameDirectoryEntry::builder()
+                .name(ShortFileName::from_str(&AsciiOnlyEncoder, name).unwrap())
+                .attributes(DirectoryEntryAttributes::empty())
+                .first_cluster_number(2)
+                .file_size(1)
+                .build()
+        }
+
+        #[test]
+        fn following_item_returned_by_next() {
+            let scripted_entry_iterator =
+                ScriptedDirectoryEntryIterator::<SingleAccessDevice<VoidStream>>::new()
+                    .with_peek(|index| match index {
+                        0 => Some(Ok(short_directory_entry("first.txt").into())),
+                        1 => Some(Ok(FreeDirectoryEntry::CurrentOnly.into())),
+                        2 => Some(Ok(short_directory_entry("second.txt").into())),
+                        _ => panic!("Shouldn't be reached"),
+                    })
+                    .with_advance(|index| Ok(index < 2));
+
+            let mut item_iterator = DirectoryItemIterator::new(scripted_entry_iterator.into());
+
+            item_iterator
+                .skip_items(1)
+                .expect("Some should be returned")
+                .expect("Ok should be returned");
+
+            let result = item_iterator
+                .next()
+                .expect("Some should be returned")
+                .expect("Ok should be returned");
+
+            assert_eq!(
+                result.short_directory_entry,
+                short_directory_entry("second.txt")
+            );
+        }
+
+        #[test]
+        fn end_of_directory_returns_none() {
+            let scripted_entry_iterator =
+                ScriptedDirectoryEntryIterator::<SingleAccessDevice<VoidStream>>::new()
+                    .with_peek(|index| match index {
+                        0 => Some(Ok(short_directory_entry("first.txt").into())),
+                        1 => Some(Ok(FreeDirectoryEntry::AllFollowing.into())),
+                        _ => panic!("Shouldn't be reached"),
+                    })
+                    .with_advance(|index| Ok(index < 1));
+
+            let mut item_iterator = DirectoryItemIterator::new(scripted_entry_iterator.into());
+
+            assert!(item_iterator.skip_items(2).is_none());
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    mod count_items {
+        use super::*;
+
+        fn short_directory_entry(name: &str) -> ShortNameDirectoryEntry {
+            ShortNameDirectoryEntry::builder()
+                .name(ShortFileName::from_str(&AsciiOnlyEncoder, name).unwrap())
+                .attributes(DirectoryEntryAttributes::empty())
+                .first_cluster_number(2)
+                .file_size(1)
+                .build()
+        }
+
+        #[test]
+        fn short_name_entries_counted() {
+            let scripted_entry_iterator =
+                ScriptedDirectoryEntryIterator::<SingleAccessDevice<VoidStream>>::new()
+                    .with_peek(|index| match index {
+                        0 => Some(Ok(short_directory_entry("first.txt").into())),
+                        1 => Some(Ok(FreeDirectoryEntry::CurrentOnly.into())),
+                        2 => Some(Ok(short_directory_entry("second.txt").into())),
+                        3 => Some(Ok(FreeDirectoryEntry::AllFollowing.into())),
+                        _ => panic!("Shouldn't be reached"),
+                    })
+                    .with_advance(|index| Ok(index < 3));
+
+            let mut item_iterator = DirectoryItemIterator::new(scripted_entry_iterator.into());
+
+            let item_count = item_iterator.count_items().expect("Ok should be returned");
+
+            assert_eq!(item_count, 2);
+        }
+
+        #[test]
+        fn skipped_dot_items_not_counted() {
+            let dot_entry = |name: &[u8; 11]| -> DirectoryEntry {
+                ShortNameDirectoryEntry::builder()
+                    .name(ShortFileName::new(*name).unwrap())
+                    .attributes(DirectoryEntryAttributes::Subdirectory)
+                    .first_cluster_number(0)
+                    .file_size(0)
+                    .build()
+                    .into()
+            };
+
+            let scripted_entry_iterator =
+                ScriptedDirectoryEntryIterator::<SingleAccessDevice<VoidStream>>::new()
+                    .with_peek(move |index| match index {
+                        0 => Some(Ok(dot_entry(b".          "))),
+                        1 => Some(Ok(dot_entry(b"..         "))),
+                        2 => Some(Ok(short_directory_entry("first.txt").into())),
+                        _ => None,
+                    })
+                    .with_advance(|index| Ok(index < 2));
+
+            let mut item_iterator = DirectoryItemIterator::new(scripted_entry_iterator.into())
+                .with_dot_items_skipped(true);
+
+            let item_count = item_iterator.count_items().expect("Ok should be returned");
+
+            assert_eq!(item_count, 1);
+        }
     }
 }
@@ -0,0 +1,65 @@
+use crate::directory_entry::DirectoryEntryAttributes;
+
+/// What a directory item refers to, as classified by its attributes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DirectoryItemKind {
+    Directory,
+    File,
+    /// The volume's label, which only the root directory holds and which has no contents.
+    VolumeLabel,
+}
+
+impl DirectoryItemKind {
+    pub(crate) fn from_attributes(attributes: DirectoryEntryAttributes) -> Self {
+        if attributes.contains(DirectoryEntryAttributes::VolumeLabel) {
+            DirectoryItemKind::VolumeLabel
+        } else if attributes.contains(DirectoryEntryAttributes::Subdirectory) {
+            DirectoryItemKind::Directory
+        } else {
+            DirectoryItemKind::File
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod from_attributes {
+        use super::*;
+
+        #[test]
+        fn no_type_attributes_is_file() {
+            let kind = DirectoryItemKind::from_attributes(
+                DirectoryEntryAttributes::Archive | DirectoryEntryAttributes::ReadOnly,
+            );
+
+            assert_eq!(kind, DirectoryItemKind::File);
+        }
+
+        #[test]
+        fn subdirectory_is_directory() {
+            let kind = DirectoryItemKind::from_attributes(DirectoryEntryAttributes::Subdirectory);
+
+            assert_eq!(kind, DirectoryItemKind::Directory);
+        }
+
+        #[test]
+        fn volume_label_is_volume_label() {
+            let kind = DirectoryItemKind::from_attributes(
+                DirectoryEntryAttributes::VolumeLabel | DirectoryEntryAttributes::Archive,
+            );
+
+            assert_eq!(kind, DirectoryItemKind::VolumeLabel);
+        }
+
+        #[test]
+        fn volume_label_takes_precedence() {
+            let kind = DirectoryItemKind::from_attributes(
+                DirectoryEntryAttributes::VolumeLabel | DirectoryEntryAttributes::Subdirectory,
+            );
+
+            assert_eq!(kind, DirectoryItemKind::VolumeLabel);
+        }
+    }
+}
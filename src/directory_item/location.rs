@@ -0,0 +1,28 @@
+use crate::directory_entry::DirectoryEntryPosition;
+
+/// Where the entries making up an item are stored within its directory.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EntryLocation {
+    position: DirectoryEntryPosition,
+    entry_count: u8,
+}
+
+impl EntryLocation {
+    pub(crate) fn new(position: DirectoryEntryPosition, entry_count: u8) -> Self {
+        Self {
+            position,
+            entry_count,
+        }
+    }
+
+    /// The position of the item's first entry, which is its first long name entry if it has a
+    /// long name.
+    pub fn position(&self) -> DirectoryEntryPosition {
+        self.position
+    }
+
+    /// The number of consecutive entries making up the item, including its short name entry.
+    pub fn entry_count(&self) -> u8 {
+        self.entry_count
+    }
+}
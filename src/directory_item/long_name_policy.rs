@@ -0,0 +1,13 @@
+/// How listings treat items whose long names are longer than 255 characters or hold characters
+/// which can't be decoded.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LongNamePolicy {
+    /// Reports such items as iteration errors.
+    #[default]
+    Reject,
+    /// Keeps the first 255 characters of over-long names and substitutes `?` for characters which
+    /// can't be decoded.
+    Repair,
+    /// Leaves such items out of the listing.
+    Skip,
+}
@@ -0,0 +1,177 @@
+use crate::directory_entry::SHORT_NAME_CHARACTER_COUNT;
+use crate::directory_item::DirectoryItem;
+use crate::encoding::Ucs2Character;
+use crate::file_name::LONG_NAME_MAX_LENGTH;
+
+/// A name with `*` and `?` wildcards, parsed once so it can be matched against every item of a
+/// directory.
+///
+/// `*` matches any run of characters, including none, and `?` matches any single character.  Other
+/// characters are compared ignoring case.  Neither wildcard is valid within a name, so no escaping
+/// is needed.
+#[derive(Clone, Debug)]
+pub(crate) struct NamePattern {
+    characters: [Ucs2Character; LONG_NAME_MAX_LENGTH],
+    length: usize,
+}
+
+impl NamePattern {
+    /// Parses the pattern, `None` if it's longer than a name or holds characters no name can.
+    pub(crate) fn new(pattern: &str) -> Option<Self> {
+        let mut characters = [Ucs2Character::null(); LONG_NAME_MAX_LENGTH];
+        let mut length = 0;
+
+        for character in pattern.chars() {
+            *characters.get_mut(length)? = Ucs2Character::from_char(character)?;
+            length += 1;
+        }
+
+        Some(Self { characters, length })
+    }
+
+    /// Whether the item's long name or short name matches the pattern.
+    pub(crate) fn matches(&self, item: &DirectoryItem) -> bool {
+        if let Some(long_name) = item.long_name()
+            && self.matches_characters(long_name.ucs2_characters())
+        {
+            return true;
+        }
+
+        // Short names have one more character than their bytes for the extension separator
+        let mut short_name = [Ucs2Character::null(); SHORT_NAME_CHARACTER_COUNT + 1];
+        let mut short_name_length = 0;
+
+        for character in item.short_name().chars() {
+            if let Some(ucs2_character) = Ucs2Character::from_char(character) {
+                short_name[short_name_length] = ucs2_character;
+                short_name_length += 1;
+            }
+        }
+
+        self.matches_characters(&short_name[..short_name_length])
+    }
+
+    /// Matches the name against the pattern, returning to the last `*` seen and letting it match
+    /// one more character whenever the characters after it stop matching.
+    fn matches_characters(&self, name: &[Ucs2Character]) -> bool {
+        let pattern = &self.characters[..self.length];
+        let mut pattern_index = 0;
+        let mut name_index = 0;
+        let mut last_any_characters = None;
+
+        while name_index < name.len() {
+            match pattern
+                .get(pattern_index)
+                .map(|character| character.to_char())
+            {
+                Some('*') => {
+                    last_any_characters = Some((pattern_index, name_index));
+                    pattern_index += 1;
+
+                    continue;
+                }
+                Some('?') => {
+                    pattern_index += 1;
+                    name_index += 1;
+
+                    continue;
+                }
+                Some(_) if pattern[pattern_index].eq_ignore_case(&name[name_index]) => {
+                    pattern_index += 1;
+                    name_index += 1;
+
+                    continue;
+                }
+                _ => {}
+            }
+
+            let Some((any_characters_index, matched_name_index)) = last_any_characters else {
+                return false;
+            };
+
+            pattern_index = any_characters_index + 1;
+            name_index = matched_name_index + 1;
+            last_any_characters = Some((any_characters_index, name_index));
+        }
+
+        pattern[pattern_index..]
+            .iter()
+            .all(|character| character.to_char() == '*')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AsciiOnlyEncoder;
+    use crate::directory_entry::{DirectoryEntryAttributes, ShortNameDirectoryEntry};
+    use crate::file_name::{LongFileName, ShortFileName};
+    use core::str::FromStr;
+
+    fn item(short_name: &str, long_name: Option<&str>) -> DirectoryItem {
+        DirectoryItem::new(
+            ShortNameDirectoryEntry::builder()
+                .name(ShortFileName::from_str(&AsciiOnlyEncoder, short_name).unwrap())
+                .attributes(DirectoryEntryAttributes::Archive)
+                .first_cluster_number(2)
+                .file_size(1)
+                .build(),
+            long_name.map(|long_name| LongFileName::from_str(long_name).unwrap()),
+        )
+    }
+
+    fn matches(pattern: &str, item: &DirectoryItem) -> bool {
+        NamePattern::new(pattern).unwrap().matches(item)
+    }
+
+    mod new {
+        use super::*;
+
+        #[test]
+        fn over_long_pattern_rejected() {
+            assert!(NamePattern::new(&"*".repeat(LONG_NAME_MAX_LENGTH + 1)).is_none());
+        }
+    }
+
+    mod matches {
+        use super::*;
+
+        #[test]
+        fn wildcards_matched() {
+            let item = item("fw12.bin", None);
+
+            assert!(matches("FW*.BIN", &item));
+            assert!(matches("fw??.bin", &item));
+            assert!(matches("*", &item));
+            assert!(matches("*1*2*", &item));
+            assert!(!matches("FW?.BIN", &item));
+            assert!(!matches("FW*.HEX", &item));
+        }
+
+        #[test]
+        fn backtracking_matched() {
+            let item = item("aabab~1.txt", Some("aabab.bin"));
+
+            assert!(matches("*ab.bin", &item));
+            assert!(matches("a*b*.bin", &item));
+            assert!(!matches("*abb*", &item));
+        }
+
+        #[test]
+        fn long_and_short_names_matched() {
+            let item = item("firmwa~1.bin", Some("firmware v2.bin"));
+
+            assert!(matches("firmware*", &item));
+            assert!(matches("FIRMWA~?.BIN", &item));
+            assert!(!matches("firmware", &item));
+        }
+
+        #[test]
+        fn short_names_without_extension_matched() {
+            let item = item("readme", None);
+
+            assert!(matches("READ*", &item));
+            assert!(!matches("*.*", &item));
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use crate::CodePageEncoder;
+use crate::directory_item::DirectoryItem;
+use crate::file_name::{LongFileName, ShortFileName};
+use core::str::FromStr;
+
+/// A name looked up within a directory, parsed once so it can be compared against every item.
+///
+/// Names are compared against an item's long name first, encoding the name as a short name is
+/// only a fallback since it fails for characters the code page can't represent.
+#[derive(Clone, Debug)]
+pub(crate) struct NameQuery {
+    long_name: Option<LongFileName>,
+    short_name: Option<ShortFileName>,
+}
+
+impl NameQuery {
+    pub(crate) fn new<CPE>(code_page_encoder: &CPE, name: &str) -> Self
+    where
+        CPE: CodePageEncoder,
+    {
+        Self {
+            long_name: LongFileName::from_str(name).ok(),
+            short_name: ShortFileName::from_str(code_page_encoder, name).ok(),
+        }
+    }
+
+    pub(crate) fn matches(&self, item: &DirectoryItem) -> bool {
+        if let Some(item_long_name) = item.long_name()
+            && let Some(long_name) = &self.long_name
+            && item_long_name == long_name
+        {
+            return true;
+        }
+
+        self.short_name
+            .as_ref()
+            .is_some_and(|short_name| item.short_name() == short_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AsciiOnlyEncoder;
+    use crate::directory_entry::{DirectoryEntryAttributes, ShortNameDirectoryEntry};
+
+    fn item(short_name: &str, long_name: Option<&str>) -> DirectoryItem {
+        DirectoryItem::new(
+            ShortNameDirectoryEntry::builder()
+                .name(
+                    ShortFileName::from_str(&AsciiOnlyEncoder, short_name)
+                        .expect("Ok should be returned"),
+                )
+                .attributes(DirectoryEntryAttributes::empty())
+                .first_cluster_number(2)
+                .file_size(1)
+                .build(),
+            long_name
+                .map(|long_name| LongFileName::from_str(long_name).expect("Ok should be returned")),
+        )
+    }
+
+    mod matches {
+        use super::*;
+
+        #[test]
+        fn long_name_matched_ignoring_case() {
+            let query = NameQuery::new(&AsciiOnlyEncoder, "long-file.NAME.txt");
+
+            assert!(query.matches(&item("long-f~1.txt", Some("Long-File.name.txt"))));
+        }
+
+        #[cfg(feature = "unicode-case-folding")]
+        #[test]
+        fn long_name_unencodable_in_code_page_matched() {
+            let query = NameQuery::new(&AsciiOnlyEncoder, "ñu.txt");
+
+            assert!(query.matches(&item("_u~1.txt", Some("Ñu.txt"))));
+        }
+
+        #[cfg(feature = "unicode-normalization")]
+        #[test]
+        fn decomposed_long_name_matched() {
+            let query = NameQuery::new(&AsciiOnlyEncoder, "ñu.txt");
+
+            assert!(query.matches(&item("_u~1.txt", Some("N\u{303}u.txt"))));
+        }
+
+        #[test]
+        fn short_name_of_item_with_long_name_matched() {
+            let query = NameQuery::new(&AsciiOnlyEncoder, "long-f~1.txt");
+
+            assert!(query.matches(&item("long-f~1.txt", Some("Long-File.name.txt"))));
+        }
+
+        #[test]
+        fn short_name_only_item_matched() {
+            let query = NameQuery::new(&AsciiOnlyEncoder, "test.txt");
+
+            assert!(query.matches(&item("TEST.TXT", None)));
+        }
+
+        #[test]
+        fn other_names_not_matched() {
+            let query = NameQuery::new(&AsciiOnlyEncoder, "ñu.txt");
+
+            assert!(!query.matches(&item("_u~1.txt", Some("Ña.txt"))));
+            assert!(!query.matches(&item("test.txt", None)));
+        }
+    }
+}
@@ -1,7 +1,19 @@
 mod ascii_only_encoder;
 mod code_page_encoder;
+#[cfg(feature = "unicode-normalization")]
+mod composed_characters;
+#[cfg(feature = "code-page-437")]
+mod cp437_encoder;
+#[cfg(feature = "code-page-850")]
+mod cp850_encoder;
 mod ucs2_character;
 
 pub use ascii_only_encoder::*;
 pub use code_page_encoder::*;
+#[cfg(feature = "unicode-normalization")]
+pub(crate) use composed_characters::*;
+#[cfg(feature = "code-page-437")]
+pub use cp437_encoder::*;
+#[cfg(feature = "code-page-850")]
+pub use cp850_encoder::*;
 pub use ucs2_character::*;
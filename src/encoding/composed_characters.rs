@@ -0,0 +1,170 @@
+use crate::encoding::Ucs2Character;
+
+/// The most characters held from a single combining sequence, matching the limit of Unicode's
+/// stream-safe text format.  Characters of longer sequences are returned without composing them.
+const MAX_SEQUENCE_LENGTH: usize = 32;
+
+/// Composes decomposed characters into their NFC form while iterating them, so a name written
+/// decomposed, as macOS does, compares equal to the same name written composed.
+///
+/// Only the composition step of NFC is applied, characters are expected to already be in
+/// canonical order as every decomposed name written by a conforming implementation is.
+#[derive(Clone, Debug)]
+pub(crate) struct ComposedCharacters<I> {
+    characters: I,
+    next_character: Option<Ucs2Character>,
+
+    sequence: [Ucs2Character; MAX_SEQUENCE_LENGTH],
+    sequence_length: usize,
+    sequence_index: usize,
+}
+
+impl<I> ComposedCharacters<I>
+where
+    I: Iterator<Item = Ucs2Character>,
+{
+    pub(crate) fn new(characters: I) -> Self {
+        Self {
+            characters,
+            next_character: None,
+
+            sequence: [Ucs2Character::null(); MAX_SEQUENCE_LENGTH],
+            sequence_length: 0,
+            sequence_index: 0,
+        }
+    }
+
+    /// Reads the next combining sequence, composing each following character into its first
+    /// character unless blocked by an uncomposed character of the same or higher combining class.
+    fn read_sequence(&mut self) {
+        self.sequence_length = 0;
+        self.sequence_index = 0;
+
+        let Some(first_character) = self
+            .next_character
+            .take()
+            .or_else(|| self.characters.next())
+        else {
+            return;
+        };
+
+        let is_starter = first_character.combining_class() == 0;
+        let mut last_combining_class = None;
+
+        self.sequence[0] = first_character;
+        self.sequence_length = 1;
+
+        for character in self.characters.by_ref() {
+            let combining_class = character.combining_class();
+            let is_blocked =
+                last_combining_class.is_some_and(|last_class| last_class >= combining_class);
+
+            if is_starter
+                && !is_blocked
+                && let Some(composite) = self.sequence[0].compose(character)
+            {
+                self.sequence[0] = composite;
+
+                continue;
+            }
+
+            if combining_class == 0 || self.sequence_length == MAX_SEQUENCE_LENGTH {
+                self.next_character = Some(character);
+
+                return;
+            }
+
+            self.sequence[self.sequence_length] = character;
+            self.sequence_length += 1;
+            last_combining_class = Some(combining_class);
+        }
+    }
+}
+
+impl<I> Iterator for ComposedCharacters<I>
+where
+    I: Iterator<Item = Ucs2Character>,
+{
+    type Item = Ucs2Character;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.sequence_index == self.sequence_length {
+            self.read_sequence();
+        }
+
+        let character = *self.sequence[..self.sequence_length].get(self.sequence_index)?;
+        self.sequence_index += 1;
+
+        Some(character)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    fn compose(value: &str) -> String {
+        ComposedCharacters::new(
+            value
+                .chars()
+                .map(|character| Ucs2Character::from_char(character).unwrap()),
+        )
+        .map(Ucs2Character::to_char)
+        .collect()
+    }
+
+    mod next {
+        use super::*;
+
+        #[test]
+        fn composed_characters_unchanged() {
+            assert_eq!(compose("café.txt"), "café.txt");
+        }
+
+        #[test]
+        fn decomposed_characters_composed() {
+            assert_eq!(compose("cafe\u{301}.txt"), "café.txt");
+        }
+
+        #[test]
+        fn multiple_marks_composed() {
+            // Dot below has a lower combining class than circumflex, so both compose in order
+            assert_eq!(compose("e\u{323}\u{302}"), "ệ");
+        }
+
+        #[test]
+        fn blocked_marks_not_composed() {
+            // ó has no composite with a further acute accent, which is returned as-is
+            assert_eq!(compose("o\u{301}\u{301}"), "ó\u{301}");
+        }
+
+        #[test]
+        fn leading_marks_not_composed() {
+            assert_eq!(compose("\u{301}e"), "\u{301}e");
+        }
+
+        #[test]
+        fn hangul_jamo_composed() {
+            assert_eq!(
+                compose("\u{1112}\u{1161}\u{11AB}\u{1100}\u{1173}\u{11AF}"),
+                "한글"
+            );
+        }
+
+        #[test]
+        fn excluded_compositions_not_composed() {
+            // Devanagari qa is excluded from composition
+            assert_eq!(compose("\u{915}\u{93C}"), "\u{915}\u{93C}");
+        }
+
+        #[test]
+        fn over_long_sequences_returned_entirely() {
+            let value: String = core::iter::once('a')
+                .chain(core::iter::repeat_n('\u{301}', MAX_SEQUENCE_LENGTH + 1))
+                .collect();
+
+            assert_eq!(compose(&value).chars().count(), MAX_SEQUENCE_LENGTH + 1);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+mod code_page;
+
+use crate::CodePageEncoder;
+use crate::encoding::Ucs2Character;
+use code_page::*;
+
+/// Encodes short names using code page 437, the original IBM PC character set and the default OEM
+/// code page of US English installations of Windows.
+///
+/// Characters are uppercased the way Windows does before being stored, so `é` is stored as `É`
+/// while characters such as `à`, whose uppercase form isn't part of the code page, are kept as-is.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Cp437Encoder;
+
+impl CodePageEncoder for Cp437Encoder {
+    fn encode(&self, character: char) -> Option<u8> {
+        encode_character(Ucs2Character::from_char(character)?.to_u16())
+    }
+
+    fn uppercase(&self, character: char) -> char {
+        match Ucs2Character::from_char(character) {
+            None => character,
+            Some(character) => Ucs2Character::from_u16(uppercase_character(character.to_u16()))
+                .expect("uppercase mappings only produce valid characters")
+                .to_char(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod encode {
+        use super::*;
+
+        #[test]
+        fn ascii_characters_encodable() {
+            for codepoint in 0x00..=0x7F {
+                let character = char::from_u32(codepoint as u32).unwrap();
+
+                assert_eq!(Cp437Encoder.encode(character), Some(codepoint));
+            }
+        }
+
+        #[test]
+        fn code_page_characters_encodable() {
+            assert_eq!(Cp437Encoder.encode('É'), Some(0x90));
+            assert_eq!(Cp437Encoder.encode('Σ'), Some(0xE4));
+        }
+
+        #[test]
+        fn other_characters_not_encodable() {
+            for character in ['À', '귒', '😀'] {
+                assert!(Cp437Encoder.encode(character).is_none());
+            }
+        }
+    }
+
+    mod uppercase {
+        use super::*;
+
+        #[test]
+        fn ascii_characters_uppercased() {
+            assert_eq!(Cp437Encoder.uppercase('a'), 'A');
+        }
+
+        #[test]
+        fn characters_with_encodable_uppercase_uppercased() {
+            assert_eq!(Cp437Encoder.uppercase('é'), 'É');
+            assert_eq!(Cp437Encoder.uppercase('σ'), 'Σ');
+        }
+
+        #[test]
+        fn characters_without_encodable_uppercase_not_modified() {
+            assert_eq!(Cp437Encoder.uppercase('à'), 'à');
+            assert_eq!(Cp437Encoder.uppercase('ß'), 'ß');
+        }
+    }
+}
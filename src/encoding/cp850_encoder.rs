@@ -0,0 +1,80 @@
+mod code_page;
+
+use crate::CodePageEncoder;
+use crate::encoding::Ucs2Character;
+use code_page::*;
+
+/// Encodes short names using code page 850, the multilingual Latin-1 character set and the
+/// default OEM code page of most Western European installations of Windows.
+///
+/// Characters are uppercased the way Windows does before being stored, so `à` is stored as `À`
+/// while characters such as `ÿ`, whose uppercase form isn't part of the code page, are kept as-is.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Cp850Encoder;
+
+impl CodePageEncoder for Cp850Encoder {
+    fn encode(&self, character: char) -> Option<u8> {
+        encode_character(Ucs2Character::from_char(character)?.to_u16())
+    }
+
+    fn uppercase(&self, character: char) -> char {
+        match Ucs2Character::from_char(character) {
+            None => character,
+            Some(character) => Ucs2Character::from_u16(uppercase_character(character.to_u16()))
+                .expect("uppercase mappings only produce valid characters")
+                .to_char(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod encode {
+        use super::*;
+
+        #[test]
+        fn ascii_characters_encodable() {
+            for codepoint in 0x00..=0x7F {
+                let character = char::from_u32(codepoint as u32).unwrap();
+
+                assert_eq!(Cp850Encoder.encode(character), Some(codepoint));
+            }
+        }
+
+        #[test]
+        fn code_page_characters_encodable() {
+            assert_eq!(Cp850Encoder.encode('À'), Some(0xB7));
+            assert_eq!(Cp850Encoder.encode('Ø'), Some(0x9D));
+        }
+
+        #[test]
+        fn other_characters_not_encodable() {
+            for character in ['Σ', '귒', '😀'] {
+                assert!(Cp850Encoder.encode(character).is_none());
+            }
+        }
+    }
+
+    mod uppercase {
+        use super::*;
+
+        #[test]
+        fn ascii_characters_uppercased() {
+            assert_eq!(Cp850Encoder.uppercase('a'), 'A');
+        }
+
+        #[test]
+        fn characters_with_encodable_uppercase_uppercased() {
+            assert_eq!(Cp850Encoder.uppercase('à'), 'À');
+            assert_eq!(Cp850Encoder.uppercase('ø'), 'Ø');
+        }
+
+        #[test]
+        fn characters_without_encodable_uppercase_not_modified() {
+            assert_eq!(Cp850Encoder.uppercase('ÿ'), 'ÿ');
+            assert_eq!(Cp850Encoder.uppercase('ß'), 'ß');
+        }
+    }
+}
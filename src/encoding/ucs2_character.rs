@@ -4,7 +4,15 @@ mod case_folding;
 #[cfg(feature = "unicode-case-folding")]
 use case_folding::*;
 
+#[cfg(feature = "unicode-normalization")]
+mod normalization;
+
+#[cfg(feature = "unicode-normalization")]
+use normalization::*;
+
 use core::fmt::{Display, Formatter};
+#[cfg(feature = "unicode-normalization")]
+use core::ops::Range;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Ucs2Character(u16);
@@ -50,6 +58,40 @@ impl Ucs2Character {
 
         fold_character(self.0) == fold_character(other.0)
     }
+
+    /// The canonical combining class of the character, zero for characters which start a new
+    /// combining sequence.
+    #[cfg(feature = "unicode-normalization")]
+    pub(crate) fn combining_class(self) -> u8 {
+        combining_class(self.0)
+    }
+
+    /// The primary composite of the two characters, `None` if NFC doesn't compose them.
+    #[cfg(feature = "unicode-normalization")]
+    pub(crate) fn compose(self, other: Ucs2Character) -> Option<Ucs2Character> {
+        const SYLLABLES: Range<u16> = 0xAC00..0xD7A4;
+        const LEADING_JAMO: Range<u16> = 0x1100..0x1113;
+        const VOWEL_JAMO: Range<u16> = 0x1161..0x1176;
+        const TRAILING_JAMO: Range<u16> = 0x11A8..0x11C3;
+        const VOWEL_COUNT: u16 = VOWEL_JAMO.end - VOWEL_JAMO.start;
+        const TRAILING_COUNT: u16 = TRAILING_JAMO.end - TRAILING_JAMO.start + 1;
+
+        // Hangul syllables are composed algorithmically from their jamo
+        let composite = if LEADING_JAMO.contains(&self.0) && VOWEL_JAMO.contains(&other.0) {
+            SYLLABLES.start
+                + (self.0 - LEADING_JAMO.start) * VOWEL_COUNT * TRAILING_COUNT
+                + (other.0 - VOWEL_JAMO.start) * TRAILING_COUNT
+        } else if SYLLABLES.contains(&self.0)
+            && (self.0 - SYLLABLES.start).is_multiple_of(TRAILING_COUNT)
+            && TRAILING_JAMO.contains(&other.0)
+        {
+            self.0 + (other.0 - TRAILING_JAMO.start + 1)
+        } else {
+            compose_characters(self.0, other.0)?
+        };
+
+        Self::from_u16(composite)
+    }
 }
 
 impl Display for Ucs2Character {
@@ -0,0 +1,154 @@
+use crate::units::ByteAddress;
+use embedded_io::{ErrorType, Read, ReadExactError, Seek, SeekFrom, Write};
+
+/// A stream operation failed, the error itself is held by the [`ErasingStream`] it was raised
+/// through.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct StreamFailed;
+
+/// Reads a stream without naming its type, so logic shared by every kind of stream is only
+/// compiled once.
+pub(crate) trait ErasedRead {
+    fn seek_to(&mut self, address: ByteAddress) -> Result<(), StreamFailed>;
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), StreamFailed>;
+}
+
+/// Writes a stream without naming its type, see [`ErasedRead`].
+pub(crate) trait ErasedWrite: ErasedRead {
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), StreamFailed>;
+}
+
+/// Adapts a stream to the erased traits, keeping the error which ended the operation so it can be
+/// returned with its original type.
+pub(crate) struct ErasingStream<'s, S>
+where
+    S: ErrorType,
+{
+    stream: &'s mut S,
+    error: Option<ReadExactError<S::Error>>,
+}
+
+impl<'s, S> ErasingStream<'s, S>
+where
+    S: ErrorType,
+{
+    /// Runs the non-generic operation over the stream, converting a failure back into the
+    /// stream's own error.
+    pub(crate) fn run<T, E, F>(stream: &'s mut S, operation: F) -> Result<T, E>
+    where
+        F: FnOnce(&mut Self) -> Result<T, StreamFailed>,
+        E: From<ReadExactError<S::Error>>,
+    {
+        let mut erasing_stream = Self {
+            stream,
+            error: None,
+        };
+
+        operation(&mut erasing_stream).map_err(|StreamFailed| {
+            erasing_stream
+                .error
+                .take()
+                .expect("failures are only reported after the error is recorded")
+                .into()
+        })
+    }
+
+    fn record<T>(
+        &mut self,
+        result: Result<T, ReadExactError<S::Error>>,
+    ) -> Result<T, StreamFailed> {
+        result.map_err(|error| {
+            self.error = Some(error);
+
+            StreamFailed
+        })
+    }
+}
+
+impl<S> ErasedRead for ErasingStream<'_, S>
+where
+    S: Read + Seek,
+{
+    fn seek_to(&mut self, address: ByteAddress) -> Result<(), StreamFailed> {
+        let result = self
+            .stream
+            .seek(SeekFrom::Start(address.value()))
+            .map(|_| ())
+            .map_err(ReadExactError::Other);
+
+        self.record(result)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), StreamFailed> {
+        let result = self.stream.read_exact(buf);
+
+        self.record(result)
+    }
+}
+
+impl<S> ErasedWrite for ErasingStream<'_, S>
+where
+    S: Read + Write + Seek,
+{
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), StreamFailed> {
+        let result = self.stream.write_all(buf).map_err(ReadExactError::Other);
+
+        self.record(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{DataStream, ErroringStream, ErroringStreamScenarios, IoError};
+    use embedded_io::ErrorKind;
+
+    mod run {
+        use super::*;
+
+        #[test]
+        fn operation_result_returned() {
+            let mut stream = DataStream::from_bytes([1, 2, 3, 4]);
+
+            let result: Result<[u8; 2], ReadExactError<IoError>> =
+                ErasingStream::run(&mut stream, |stream| {
+                    let mut buf = [0; 2];
+
+                    stream.seek_to(ByteAddress::new(2))?;
+                    stream.read_exact(&mut buf)?;
+
+                    Ok(buf)
+                });
+
+            assert_eq!(result.expect("Operation should succeed"), [3, 4]);
+        }
+
+        #[test]
+        fn stream_error_returned() {
+            let mut stream = ErroringStream::new(
+                DataStream::from_bytes([0; 4]),
+                IoError(ErrorKind::TimedOut),
+                ErroringStreamScenarios::WRITE,
+            );
+
+            let result: Result<(), ReadExactError<IoError>> =
+                ErasingStream::run(&mut stream, |stream| stream.write_all(&[1]));
+
+            assert!(matches!(
+                result,
+                Err(ReadExactError::Other(IoError(ErrorKind::TimedOut)))
+            ));
+        }
+
+        #[test]
+        fn end_of_stream_returned() {
+            let mut stream = DataStream::from_bytes([0; 2]);
+
+            let result: Result<(), ReadExactError<IoError>> =
+                ErasingStream::run(&mut stream, |stream| stream.read_exact(&mut [0; 4]));
+
+            assert!(matches!(result, Err(ReadExactError::UnexpectedEof)));
+        }
+    }
+}
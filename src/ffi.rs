@@ -0,0 +1,544 @@
+//! C bindings for mounting a volume, reading and writing its files, and listing its directories.
+//!
+//! The library never allocates, so every handle lives in storage owned by the caller.  Handles are
+//! opaque fixed-size structs which must be initialized by the matching `embedded_fat_mount`,
+//! `embedded_fat_open`, `embedded_fat_open_writer`, or `embedded_fat_opendir` call before use and
+//! released by the matching `embedded_fat_unmount`, `embedded_fat_close`, or
+//! `embedded_fat_closedir` call.  File and
+//! directory handles borrow the file system handle they were opened from, which must therefore
+//! remain mounted and must not be moved until they are released.  Files opened for writing also
+//! keep a copy of their path within their own handle, so they must not be moved until they are
+//! closed either.
+//!
+//! Functions return `EMBEDDED_FAT_OK` or a negative `EMBEDDED_FAT_ERROR_*` code unless stated
+//! otherwise.
+
+mod io;
+
+pub use io::*;
+
+use crate::{
+    AsciiOnlyEncoder, Dir, DirectoryItem, DirectoryItemIterationError, File, FileError, FileSystem,
+    FileSystemBuilder, FileSystemError, FileWriteGuard, IgnoreInvalidEntries, MutationEvent,
+    OperationError, SingleAccessDevice,
+};
+use core::ffi::{CStr, c_char, c_int};
+use core::mem::{MaybeUninit, align_of, size_of};
+use embedded_io::{Read, Write};
+
+pub const EMBEDDED_FAT_OK: c_int = 0;
+pub const EMBEDDED_FAT_ERROR_IO: c_int = -1;
+pub const EMBEDDED_FAT_ERROR_NOT_FOUND: c_int = -2;
+pub const EMBEDDED_FAT_ERROR_INVALID_ARGUMENT: c_int = -3;
+pub const EMBEDDED_FAT_ERROR_UNSUPPORTED: c_int = -4;
+pub const EMBEDDED_FAT_ERROR_CORRUPTED: c_int = -5;
+
+pub const EMBEDDED_FAT_FILE_SYSTEM_SIZE: usize = 256;
+pub const EMBEDDED_FAT_FILE_SIZE: usize = 1080;
+pub const EMBEDDED_FAT_DIR_SIZE: usize = 672;
+
+pub const EMBEDDED_FAT_SHORT_NAME_SIZE: usize = 13;
+pub const EMBEDDED_FAT_LONG_NAME_SIZE: usize = 766;
+
+pub const EMBEDDED_FAT_PATH_SIZE: usize = 256;
+
+type FfiDevice = SingleAccessDevice<FfiStream>;
+type FfiFileSystem = FileSystem<FfiDevice, AsciiOnlyEncoder>;
+type FfiFileWriteGuard = FileWriteGuard<
+    'static,
+    FfiDevice,
+    AsciiOnlyEncoder,
+    IgnoreInvalidEntries,
+    fn(MutationEvent<'_>),
+>;
+
+/// A file opened by `embedded_fat_open` or `embedded_fat_open_writer`.
+// Handle storage is sized for the largest variant either way
+#[allow(clippy::large_enum_variant)]
+enum FfiFile {
+    Reader(File<'static, FfiDevice>),
+    Writer(FfiFileWriter),
+}
+
+/// A file opened by `embedded_fat_open_writer`, along with the copy of the path its write guard
+/// borrows.
+struct FfiFileWriter {
+    path: [u8; EMBEDDED_FAT_PATH_SIZE],
+    /// Initialized once the file is opened from `path`, which it borrows for as long as it's open.
+    guard: MaybeUninit<FfiFileWriteGuard>,
+}
+type FfiDir = Dir<'static, FfiDevice>;
+
+/// Storage for a mounted file system.
+#[repr(C, align(8))]
+pub struct EmbeddedFatFileSystem {
+    storage: [MaybeUninit<u8>; EMBEDDED_FAT_FILE_SYSTEM_SIZE],
+}
+
+/// Storage for an open file.
+#[repr(C, align(8))]
+pub struct EmbeddedFatFile {
+    storage: [MaybeUninit<u8>; EMBEDDED_FAT_FILE_SIZE],
+}
+
+/// Storage for an open directory listing.
+#[repr(C, align(8))]
+pub struct EmbeddedFatDir {
+    storage: [MaybeUninit<u8>; EMBEDDED_FAT_DIR_SIZE],
+}
+
+const _: () = {
+    assert!(size_of::<FfiFileSystem>() <= EMBEDDED_FAT_FILE_SYSTEM_SIZE);
+    assert!(align_of::<FfiFileSystem>() <= align_of::<EmbeddedFatFileSystem>());
+    assert!(size_of::<FfiFile>() <= EMBEDDED_FAT_FILE_SIZE);
+    assert!(align_of::<FfiFile>() <= align_of::<EmbeddedFatFile>());
+    assert!(size_of::<FfiDir>() <= EMBEDDED_FAT_DIR_SIZE);
+    assert!(align_of::<FfiDir>() <= align_of::<EmbeddedFatDir>());
+};
+
+/// A single item produced by `embedded_fat_readdir`.
+///
+/// `short_name` holds the `NAME.EXT` form of the short name and `long_name` holds the UTF-8
+/// encoded long name, or an empty string if the item has none.  Both are NUL-terminated.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct EmbeddedFatDirEntry {
+    pub short_name: [u8; EMBEDDED_FAT_SHORT_NAME_SIZE],
+    pub long_name: [u8; EMBEDDED_FAT_LONG_NAME_SIZE],
+    pub file_size: u32,
+    pub is_directory: bool,
+    pub is_volume_label: bool,
+}
+
+impl EmbeddedFatDirEntry {
+    fn from_item(item: &DirectoryItem) -> Self {
+        let mut entry = Self {
+            short_name: [0; EMBEDDED_FAT_SHORT_NAME_SIZE],
+            long_name: [0; EMBEDDED_FAT_LONG_NAME_SIZE],
+            file_size: item.file_size(),
+            is_directory: item.is_directory(),
+            is_volume_label: item.is_volume_label(),
+        };
+
+        let short_name = item.short_name();
+        let base_name = short_name.base_name();
+        let extension = short_name.extension();
+
+        entry.short_name[..base_name.len()].copy_from_slice(base_name);
+
+        if !extension.is_empty() {
+            entry.short_name[base_name.len()] = b'.';
+            entry.short_name[base_name.len() + 1..][..extension.len()].copy_from_slice(extension);
+        }
+
+        if let Some(long_name) = item.long_name() {
+            let mut offset = 0;
+
+            for character in long_name.chars() {
+                offset += character.encode_utf8(&mut entry.long_name[offset..]).len();
+            }
+        }
+
+        entry
+    }
+}
+
+/// Mounts the volume accessed through `io` into `fs`.
+///
+/// # Safety
+/// `fs` must point to writable storage which is not currently mounted and `io` must point to a
+/// valid `EmbeddedFatIo` whose callbacks remain valid until `embedded_fat_unmount` is called.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn embedded_fat_mount(
+    fs: *mut EmbeddedFatFileSystem,
+    io: *const EmbeddedFatIo,
+) -> c_int {
+    if fs.is_null() || io.is_null() {
+        return EMBEDDED_FAT_ERROR_INVALID_ARGUMENT;
+    }
+
+    // SAFETY: `io` is non-null and the caller guarantees it points to a valid value.
+    let io = unsafe { *io };
+
+    if !io.is_valid() {
+        return EMBEDDED_FAT_ERROR_INVALID_ARGUMENT;
+    }
+
+    match FileSystemBuilder::from_stream(FfiStream::new(io)).build() {
+        Ok(file_system) => {
+            // SAFETY: the storage is large enough and aligned for the file system as asserted
+            //   above.
+            unsafe { fs.cast::<FfiFileSystem>().write(file_system) };
+
+            EMBEDDED_FAT_OK
+        }
+        Err(FileSystemError::DeviceError(_) | FileSystemError::StreamError(_)) => {
+            EMBEDDED_FAT_ERROR_IO
+        }
+        Err(_) => EMBEDDED_FAT_ERROR_CORRUPTED,
+    }
+}
+
+/// Releases a file system mounted by `embedded_fat_mount`.
+///
+/// # Safety
+/// `fs` must point to a mounted file system with no open files or directory listings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn embedded_fat_unmount(fs: *mut EmbeddedFatFileSystem) -> c_int {
+    if fs.is_null() {
+        return EMBEDDED_FAT_ERROR_INVALID_ARGUMENT;
+    }
+
+    // SAFETY: the caller guarantees `fs` holds a mounted file system.
+    unsafe { fs.cast::<FfiFileSystem>().drop_in_place() };
+
+    EMBEDDED_FAT_OK
+}
+
+/// Opens the file at the NUL-terminated, `/` separated `path` into `file`.
+///
+/// # Safety
+/// `fs` must point to a mounted file system, `path` must point to a NUL-terminated string, and
+/// `file` must point to writable storage which is not currently open.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn embedded_fat_open(
+    fs: *const EmbeddedFatFileSystem,
+    path: *const c_char,
+    file: *mut EmbeddedFatFile,
+) -> c_int {
+    if file.is_null() {
+        return EMBEDDED_FAT_ERROR_INVALID_ARGUMENT;
+    }
+
+    // SAFETY: forwarded from the caller.
+    let Some((file_system, path)) = (unsafe { file_system_and_path(fs, path) }) else {
+        return EMBEDDED_FAT_ERROR_INVALID_ARGUMENT;
+    };
+
+    let Some(opened_file) = file_system.open(path) else {
+        return EMBEDDED_FAT_ERROR_NOT_FOUND;
+    };
+
+    // SAFETY: the storage is large enough and aligned for the file as asserted above.
+    unsafe { file.cast::<FfiFile>().write(FfiFile::Reader(opened_file)) };
+
+    EMBEDDED_FAT_OK
+}
+
+/// Opens the existing file at the NUL-terminated, `/` separated `path` for writing into `file`.
+///
+/// The written size is recorded in the file's directory entry when the file is closed by
+/// `embedded_fat_close`, which reports any error doing so.  The path is copied into `file`, paths
+/// longer than `EMBEDDED_FAT_PATH_SIZE` bytes return `EMBEDDED_FAT_ERROR_INVALID_ARGUMENT`.
+///
+/// # Safety
+/// `fs` must point to a mounted file system, `path` must point to a NUL-terminated string, and
+/// `file` must point to writable storage which is not currently open.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn embedded_fat_open_writer(
+    fs: *const EmbeddedFatFileSystem,
+    path: *const c_char,
+    file: *mut EmbeddedFatFile,
+) -> c_int {
+    if file.is_null() {
+        return EMBEDDED_FAT_ERROR_INVALID_ARGUMENT;
+    }
+
+    // SAFETY: forwarded from the caller.
+    let Some((file_system, path)) = (unsafe { file_system_and_path(fs, path) }) else {
+        return EMBEDDED_FAT_ERROR_INVALID_ARGUMENT;
+    };
+
+    if path.len() > EMBEDDED_FAT_PATH_SIZE {
+        return EMBEDDED_FAT_ERROR_INVALID_ARGUMENT;
+    }
+
+    // SAFETY: the storage is large enough and aligned for the file as asserted above, the guard is
+    //   only initialized once opened.
+    let writer = unsafe {
+        let file = file.cast::<FfiFile>();
+
+        file.write(FfiFile::Writer(FfiFileWriter {
+            path: [0; EMBEDDED_FAT_PATH_SIZE],
+            guard: MaybeUninit::uninit(),
+        }));
+
+        let FfiFile::Writer(writer) = &mut *file else {
+            unreachable!()
+        };
+
+        writer
+    };
+
+    writer.path[..path.len()].copy_from_slice(path.as_bytes());
+
+    // SAFETY: the copy is valid UTF-8 as it was copied from `path`, and stays in place until the
+    //   guard borrowing it is closed as the caller mustn't move the file until then.
+    let path = unsafe {
+        core::str::from_utf8_unchecked(core::slice::from_raw_parts(
+            writer.path.as_ptr(),
+            path.len(),
+        ))
+    };
+
+    let guard = match file_system.open_writer(path) {
+        Ok(guard) => guard,
+        Err(OperationError::NotFound | OperationError::NotAFile) => {
+            return EMBEDDED_FAT_ERROR_NOT_FOUND;
+        }
+        Err(OperationError::DeviceError(_) | OperationError::StreamError(_)) => {
+            return EMBEDDED_FAT_ERROR_IO;
+        }
+        Err(_) => return EMBEDDED_FAT_ERROR_CORRUPTED,
+    };
+
+    writer.guard.write(guard);
+
+    EMBEDDED_FAT_OK
+}
+
+/// Reads up to `length` bytes from `file` into `buffer`.
+///
+/// Returns the number of bytes read, which is only less than `length` once the end of the file is
+/// reached, or a negative error code.
+///
+/// # Safety
+/// `file` must point to an open file and `buffer` must be valid for writes of `length` bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn embedded_fat_read(
+    file: *mut EmbeddedFatFile,
+    buffer: *mut u8,
+    length: usize,
+) -> isize {
+    if file.is_null() || (buffer.is_null() && length > 0) || length > isize::MAX as usize {
+        return EMBEDDED_FAT_ERROR_INVALID_ARGUMENT as isize;
+    }
+
+    if length == 0 {
+        return 0;
+    }
+
+    // SAFETY: the caller guarantees `file` holds an open file and `buffer` is valid for `length`
+    //   bytes.
+    let (file, buffer) = unsafe {
+        (
+            &mut *file.cast::<FfiFile>(),
+            core::slice::from_raw_parts_mut(buffer, length),
+        )
+    };
+
+    let mut total_read = 0;
+
+    while total_read < buffer.len() {
+        let result = match file {
+            FfiFile::Reader(file) => file.read(&mut buffer[total_read..]),
+            // SAFETY: the guard is initialized for as long as the file is open.
+            FfiFile::Writer(writer) => {
+                unsafe { writer.guard.assume_init_mut() }.read(&mut buffer[total_read..])
+            }
+        };
+
+        match result {
+            Ok(0) => break,
+            Ok(read) => total_read += read,
+            Err(error) => return file_error_code(&error) as isize,
+        }
+    }
+
+    total_read as isize
+}
+
+/// Writes `length` bytes from `buffer` to `file` at its current position.
+///
+/// Returns the number of bytes written, which is only less than `length` once the file reaches
+/// its maximum size, or a negative error code.  Files opened by `embedded_fat_open` aren't open for
+/// writing, writing to them returns `EMBEDDED_FAT_ERROR_INVALID_ARGUMENT`.
+///
+/// # Safety
+/// `file` must point to an open file and `buffer` must be valid for reads of `length` bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn embedded_fat_write(
+    file: *mut EmbeddedFatFile,
+    buffer: *const u8,
+    length: usize,
+) -> isize {
+    if file.is_null() || (buffer.is_null() && length > 0) || length > isize::MAX as usize {
+        return EMBEDDED_FAT_ERROR_INVALID_ARGUMENT as isize;
+    }
+
+    // SAFETY: the caller guarantees `file` holds an open file.
+    let FfiFile::Writer(writer) = (unsafe { &mut *file.cast::<FfiFile>() }) else {
+        return EMBEDDED_FAT_ERROR_INVALID_ARGUMENT as isize;
+    };
+
+    if length == 0 {
+        return 0;
+    }
+
+    // SAFETY: the guard is initialized for as long as the file is open and the caller guarantees
+    //   `buffer` is valid for `length` bytes.
+    let (writer, buffer) = unsafe {
+        (
+            writer.guard.assume_init_mut(),
+            core::slice::from_raw_parts(buffer, length),
+        )
+    };
+
+    let mut total_written = 0;
+
+    while total_written < buffer.len() {
+        match writer.write(&buffer[total_written..]) {
+            Ok(0) => break,
+            Ok(written) => total_written += written,
+            Err(error) => return file_error_code(&error) as isize,
+        }
+    }
+
+    total_written as isize
+}
+
+/// Releases a file opened by `embedded_fat_open` or `embedded_fat_open_writer`, recording the size
+/// of written files in their directory entry.  The file is released even if an error is returned.
+///
+/// # Safety
+/// `file` must point to an open file.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn embedded_fat_close(file: *mut EmbeddedFatFile) -> c_int {
+    if file.is_null() {
+        return EMBEDDED_FAT_ERROR_INVALID_ARGUMENT;
+    }
+
+    // SAFETY: the caller guarantees `file` holds an open file.
+    let result = match unsafe { &mut *file.cast::<FfiFile>() } {
+        FfiFile::Reader(_) => EMBEDDED_FAT_OK,
+        // SAFETY: the guard is initialized for as long as the file is open and no longer used once
+        //   read.  It's closed while the path it borrows is still in place.
+        FfiFile::Writer(writer) => match unsafe { writer.guard.assume_init_read() }.close() {
+            Ok(()) => EMBEDDED_FAT_OK,
+            Err(error) => file_error_code(&error),
+        },
+    };
+
+    // SAFETY: the file is no longer used once released, the guard was already moved out above.
+    unsafe { file.cast::<FfiFile>().drop_in_place() };
+
+    result
+}
+
+/// Opens a listing of the directory at the NUL-terminated, `/` separated `path` into `dir`, an
+/// empty path refers to the root directory.
+///
+/// # Safety
+/// `fs` must point to a mounted file system, `path` must point to a NUL-terminated string, and
+/// `dir` must point to writable storage which is not currently open.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn embedded_fat_opendir(
+    fs: *const EmbeddedFatFileSystem,
+    path: *const c_char,
+    dir: *mut EmbeddedFatDir,
+) -> c_int {
+    if dir.is_null() {
+        return EMBEDDED_FAT_ERROR_INVALID_ARGUMENT;
+    }
+
+    // SAFETY: forwarded from the caller.
+    let Some((file_system, path)) = (unsafe { file_system_and_path(fs, path) }) else {
+        return EMBEDDED_FAT_ERROR_INVALID_ARGUMENT;
+    };
+
+    let Some(opened_dir) = file_system.read_dir(path) else {
+        return EMBEDDED_FAT_ERROR_NOT_FOUND;
+    };
+
+    // SAFETY: the storage is large enough and aligned for the listing as asserted above.
+    unsafe { dir.cast::<FfiDir>().write(opened_dir) };
+
+    EMBEDDED_FAT_OK
+}
+
+/// Reads the next item of `dir` into `entry`.
+///
+/// Returns `1` if an item was read, `0` once the listing is exhausted, or a negative error code.
+/// The listing may be continued after `EMBEDDED_FAT_ERROR_CORRUPTED` is returned.
+///
+/// # Safety
+/// `dir` must point to an open directory listing and `entry` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn embedded_fat_readdir(
+    dir: *mut EmbeddedFatDir,
+    entry: *mut EmbeddedFatDirEntry,
+) -> c_int {
+    if dir.is_null() || entry.is_null() {
+        return EMBEDDED_FAT_ERROR_INVALID_ARGUMENT;
+    }
+
+    // SAFETY: the caller guarantees `dir` holds an open directory listing.
+    let dir = unsafe { &mut *dir.cast::<FfiDir>() };
+
+    match dir.next() {
+        Some(Ok(item)) => {
+            // SAFETY: the caller guarantees `entry` is valid for writes.
+            unsafe { entry.write(EmbeddedFatDirEntry::from_item(&item)) };
+
+            1
+        }
+        Some(Err(
+            DirectoryItemIterationError::DeviceError(_)
+            | DirectoryItemIterationError::StreamError(_),
+        )) => EMBEDDED_FAT_ERROR_IO,
+        Some(Err(_)) => EMBEDDED_FAT_ERROR_CORRUPTED,
+        None => 0,
+    }
+}
+
+/// Releases a directory listing opened by `embedded_fat_opendir`.
+///
+/// # Safety
+/// `dir` must point to an open directory listing.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn embedded_fat_closedir(dir: *mut EmbeddedFatDir) -> c_int {
+    if dir.is_null() {
+        return EMBEDDED_FAT_ERROR_INVALID_ARGUMENT;
+    }
+
+    // SAFETY: the caller guarantees `dir` holds an open directory listing.
+    unsafe { dir.cast::<FfiDir>().drop_in_place() };
+
+    EMBEDDED_FAT_OK
+}
+
+/// # Safety
+/// `fs` must be null or point to a mounted file system which outlives every handle derived from
+/// the returned reference, `path` must be null or point to a NUL-terminated string.
+unsafe fn file_system_and_path<'a>(
+    fs: *const EmbeddedFatFileSystem,
+    path: *const c_char,
+) -> Option<(&'static FfiFileSystem, &'a str)> {
+    if fs.is_null() || path.is_null() {
+        return None;
+    }
+
+    // SAFETY: the caller guarantees both pointers are valid, the file system reference is only
+    //   held by handles which the caller must release before unmounting.
+    unsafe {
+        Some((
+            &*fs.cast::<FfiFileSystem>(),
+            CStr::from_ptr(path).to_str().ok()?,
+        ))
+    }
+}
+
+fn file_error_code<DE, SE>(error: &FileError<DE, SE>) -> c_int
+where
+    DE: core::error::Error,
+    SE: embedded_io::Error,
+{
+    match error {
+        FileError::DeviceError(_) | FileError::StreamError(_) => EMBEDDED_FAT_ERROR_IO,
+        FileError::FileSizeBeyondLimits(_)
+        | FileError::NotOpenForWriting
+        | FileError::QuotaExceeded
+        | FileError::SeekPositionBeyondEnd(_) => EMBEDDED_FAT_ERROR_INVALID_ARGUMENT,
+        _ => EMBEDDED_FAT_ERROR_CORRUPTED,
+    }
+}
@@ -0,0 +1,138 @@
+use core::error::Error;
+use core::ffi::{c_int, c_void};
+use core::fmt::{Display, Formatter};
+use embedded_io::{ErrorKind, ErrorType, Read, Seek, SeekFrom, Write};
+
+pub const EMBEDDED_FAT_SEEK_SET: c_int = 0;
+pub const EMBEDDED_FAT_SEEK_CUR: c_int = 1;
+pub const EMBEDDED_FAT_SEEK_END: c_int = 2;
+
+/// Callbacks used to access the bytes of the underlying volume.
+///
+/// Every callback receives `context` as its first argument.  `read` and `write` return the number
+/// of bytes transferred or a negative value on failure, `seek` follows `lseek` semantics using the
+/// `EMBEDDED_FAT_SEEK_*` constants and returns the new position or a negative value on failure.
+/// `flush` returns zero on success.
+///
+/// `read` and `seek` are required, `write` and `flush` may be null for read-only volumes.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct EmbeddedFatIo {
+    pub context: *mut c_void,
+
+    pub read:
+        Option<unsafe extern "C" fn(context: *mut c_void, buffer: *mut u8, length: usize) -> isize>,
+    pub write: Option<
+        unsafe extern "C" fn(context: *mut c_void, buffer: *const u8, length: usize) -> isize,
+    >,
+    pub seek: Option<unsafe extern "C" fn(context: *mut c_void, offset: i64, whence: c_int) -> i64>,
+    pub flush: Option<unsafe extern "C" fn(context: *mut c_void) -> c_int>,
+}
+
+impl EmbeddedFatIo {
+    pub(crate) fn is_valid(&self) -> bool {
+        self.read.is_some() && self.seek.is_some()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FfiIoError {
+    CallbackFailed(i64),
+    CallbackMissing,
+}
+
+impl Error for FfiIoError {}
+
+impl Display for FfiIoError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FfiIoError::CallbackFailed(code) => write!(f, "io callback failed with code {code}"),
+            FfiIoError::CallbackMissing => write!(f, "io callback required but not provided"),
+        }
+    }
+}
+
+impl embedded_io::Error for FfiIoError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            FfiIoError::CallbackFailed(_) => ErrorKind::Other,
+            FfiIoError::CallbackMissing => ErrorKind::Unsupported,
+        }
+    }
+}
+
+/// Adapts the C callbacks of an `EmbeddedFatIo` to the embedded-io traits.
+#[derive(Debug)]
+pub struct FfiStream {
+    io: EmbeddedFatIo,
+}
+
+impl FfiStream {
+    pub fn new(io: EmbeddedFatIo) -> Self {
+        Self { io }
+    }
+
+    fn transferred_length(result: isize) -> Result<usize, FfiIoError> {
+        usize::try_from(result).map_err(|_| FfiIoError::CallbackFailed(result as i64))
+    }
+}
+
+impl ErrorType for FfiStream {
+    type Error = FfiIoError;
+}
+
+impl Read for FfiStream {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let read = self.io.read.ok_or(FfiIoError::CallbackMissing)?;
+
+        // SAFETY: the mount contract requires the callback to be valid for `context` and to write
+        //   at most `length` bytes to `buffer`.
+        let result = unsafe { read(self.io.context, buf.as_mut_ptr(), buf.len()) };
+
+        Self::transferred_length(result)
+    }
+}
+
+impl Write for FfiStream {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let write = self.io.write.ok_or(FfiIoError::CallbackMissing)?;
+
+        // SAFETY: the mount contract requires the callback to be valid for `context` and to read
+        //   at most `length` bytes from `buffer`.
+        let result = unsafe { write(self.io.context, buf.as_ptr(), buf.len()) };
+
+        Self::transferred_length(result)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        let Some(flush) = self.io.flush else {
+            return Ok(());
+        };
+
+        // SAFETY: the mount contract requires the callback to be valid for `context`.
+        match unsafe { flush(self.io.context) } {
+            0 => Ok(()),
+            code => Err(FfiIoError::CallbackFailed(code as i64)),
+        }
+    }
+}
+
+impl Seek for FfiStream {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        let seek = self.io.seek.ok_or(FfiIoError::CallbackMissing)?;
+
+        let (offset, whence) = match pos {
+            SeekFrom::Start(offset) => (
+                i64::try_from(offset).map_err(|_| FfiIoError::CallbackFailed(-1))?,
+                EMBEDDED_FAT_SEEK_SET,
+            ),
+            SeekFrom::Current(offset) => (offset, EMBEDDED_FAT_SEEK_CUR),
+            SeekFrom::End(offset) => (offset, EMBEDDED_FAT_SEEK_END),
+        };
+
+        // SAFETY: the mount contract requires the callback to be valid for `context`.
+        let result = unsafe { seek(self.io.context, offset, whence) };
+
+        u64::try_from(result).map_err(|_| FfiIoError::CallbackFailed(result))
+    }
+}
@@ -1,12 +1,23 @@
+mod concatenated_file;
 mod error;
+mod multi_file_reader;
+mod open_options;
+mod slice;
 
+pub use concatenated_file::*;
 pub use error::*;
+pub use multi_file_reader::*;
+pub use open_options::*;
+pub use slice::*;
 
 use crate::Device;
 use crate::allocation_table::{AllocationTable, AllocationTableEntry};
-use core::cmp::min;
+use crate::directory_entry::MAX_FILE_SIZE;
+use crate::units::{ByteAddress, ClusterNumber};
+use core::cell::Cell;
+use core::cmp::{max, min};
 use core::ops::DerefMut;
-use embedded_io::{ErrorType, SeekFrom};
+use embedded_io::{ErrorType, ReadExactError, ReadReady, SeekFrom, WriteReady};
 
 #[cfg(feature = "sync")]
 use {
@@ -20,6 +31,9 @@ use {
     embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek, Write as AsyncWrite},
 };
 
+/// Zeroes written over the gap between the end of a file and a write past it.
+const ZERO_FILL: [u8; 512] = [0; 512];
+
 #[derive(Clone, Debug)]
 pub struct File<'a, D>
 where
@@ -28,16 +42,22 @@ where
     device: &'a D,
     allocation_table: &'a AllocationTable,
 
-    data_region_base_address: u64,
+    data_region_base_address: ByteAddress,
     bytes_per_cluster: u32,
+    volume_last_cluster_number: ClusterNumber,
+
+    first_cluster_number: ClusterNumber,
+    file_size: u64,
 
-    first_cluster_number: u32,
-    file_size: u32,
+    open_options: OpenOptions,
 
-    current_position: u32,
+    current_position: u64,
 
-    current_cluster_number: u32,
+    current_cluster_number: ClusterNumber,
     current_cluster_offset: u32,
+
+    indexed_clusters: &'a [ClusterNumber],
+    media_changed: Option<&'a Cell<bool>>,
 }
 
 impl<'a, D> File<'a, D>
@@ -47,10 +67,11 @@ where
     pub fn new(
         device: &'a D,
         allocation_table: &'a AllocationTable,
-        data_region_base_address: u64,
+        data_region_base_address: ByteAddress,
         bytes_per_cluster: u32,
-        first_cluster_number: u32,
-        file_size: u32,
+        volume_last_cluster_number: ClusterNumber,
+        first_cluster_number: ClusterNumber,
+        file_size: u64,
     ) -> Self {
         Self {
             device,
@@ -58,34 +79,169 @@ where
 
             data_region_base_address,
             bytes_per_cluster,
+            volume_last_cluster_number,
 
             first_cluster_number,
             file_size,
 
+            open_options: OpenOptions::default(),
+
             current_position: 0,
 
             current_cluster_number: first_cluster_number,
             current_cluster_offset: 0,
+
+            indexed_clusters: &[],
+            media_changed: None,
+        }
+    }
+
+    pub(crate) fn with_open_options(self, open_options: OpenOptions) -> Self {
+        Self {
+            open_options,
+            ..self
+        }
+    }
+
+    /// Seeks through the leading clusters of the file's chain without reading the allocation
+    /// table, the clusters must be in chain order starting at the first cluster.
+    pub(crate) fn with_indexed_clusters(self, indexed_clusters: &'a [ClusterNumber]) -> Self {
+        Self {
+            indexed_clusters,
+            ..self
+        }
+    }
+
+    /// Fails every later access once the flag is set, as it is when the medium is found to have
+    /// changed.
+    pub(crate) fn with_media_changed_flag(self, media_changed: &'a Cell<bool>) -> Self {
+        Self {
+            media_changed: Some(media_changed),
+            ..self
         }
     }
 
-    fn current_address(&self) -> u64 {
-        self.data_region_base_address
-            + ((self.current_cluster_number - 2) as u64 * self.bytes_per_cluster as u64)
-            + self.current_cluster_offset as u64
+    /// The number of bytes the file holds.
+    pub fn size(&self) -> u64 {
+        self.file_size
+    }
+
+    /// The offset from the start of the file the next read begins at.
+    pub fn position(&self) -> u64 {
+        self.current_position
+    }
+
+    /// The number of bytes left to read before the end of the file is reached.
+    pub fn remaining(&self) -> u64 {
+        self.file_size.saturating_sub(self.current_position)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.file_size == 0
+    }
+
+    /// The cluster the file's contents start in, zero for an empty file.
+    pub fn first_cluster_number(&self) -> u32 {
+        self.first_cluster_number.value()
+    }
+
+    /// A view of `len` bytes of the file starting at `offset`, which reads and seeks as if the
+    /// region were the whole file, so a parser of one section can't read past it.
+    ///
+    /// The file is seeked to the region by the view's first read, and left wherever the view's
+    /// last read ended.
+    pub fn slice(
+        &mut self,
+        offset: u64,
+        len: u64,
+    ) -> Result<FileSlice<'_, 'a, D>, <Self as ErrorType>::Error> {
+        let end = offset.saturating_add(len);
+
+        ensure!(end <= self.file_size, FileError::SeekPositionBeyondEnd(end));
+
+        Ok(FileSlice::new(self, offset, len))
+    }
+
+    fn ensure_media_unchanged(&self) -> Result<(), <Self as ErrorType>::Error> {
+        ensure!(
+            !self.media_changed.is_some_and(Cell::get),
+            FileError::MediaChanged
+        );
+
+        Ok(())
+    }
+
+    fn current_address(&self) -> Result<ByteAddress, <Self as ErrorType>::Error> {
+        let cluster_address = self
+            .current_cluster_number
+            .byte_address(self.data_region_base_address, self.bytes_per_cluster)
+            .ok_or(FileError::ClusterNumberInvalid {
+                cluster_number: self.current_cluster_number.value(),
+            })?;
+
+        Ok(cluster_address.offset_by(self.current_cluster_offset as u64))
     }
 
     fn resolve_max_read_size(&self, target_buffer_length: usize) -> usize {
         min(
             min(
-                target_buffer_length.try_into().unwrap_or(u32::MAX),
-                self.file_size - self.current_position,
+                target_buffer_length.try_into().unwrap_or(u64::MAX),
+                self.file_size.saturating_sub(self.current_position),
             ),
-            self.bytes_per_cluster - self.current_cluster_offset,
+            self.bytes_per_cluster
+                .saturating_sub(self.current_cluster_offset)
+                .into(),
         ) as usize
     }
 
-    fn resolve_desired_position(&self, pos: SeekFrom) -> Result<u32, <Self as ErrorType>::Error> {
+    fn resolve_max_write_size(
+        &self,
+        source_buffer_length: usize,
+    ) -> Result<usize, <Self as ErrorType>::Error> {
+        let available_size = MAX_FILE_SIZE - self.current_position;
+
+        ensure!(
+            available_size > 0 || source_buffer_length == 0,
+            FileError::FileSizeBeyondLimits(
+                self.current_position
+                    .saturating_add(source_buffer_length as u64)
+            )
+        );
+
+        Ok(min(
+            source_buffer_length.try_into().unwrap_or(u64::MAX),
+            available_size,
+        ) as usize)
+    }
+
+    /// The position within the file the current cluster and offset refer to, which stops at the end
+    /// of the file when seeked past it.
+    fn cluster_position(&self) -> u64 {
+        min(self.current_position, self.file_size)
+    }
+
+    /// The indexed cluster closest to the target position along with the target's offset from its
+    /// start, unless walking forward from the current cluster reaches the target sooner.
+    fn indexed_cluster_for(&self, target_position: u64) -> Option<(ClusterNumber, i64)> {
+        let last_index = self.indexed_clusters.len().checked_sub(1)?;
+        let index = min(
+            target_position / self.bytes_per_cluster as u64,
+            last_index as u64,
+        );
+
+        let current_cluster_start = self.cluster_position() - self.current_cluster_offset as u64;
+        let index_start = index * self.bytes_per_cluster as u64;
+        if target_position >= current_cluster_start && index_start <= current_cluster_start {
+            return None;
+        }
+
+        Some((
+            self.indexed_clusters[index as usize],
+            (target_position - index_start) as i64,
+        ))
+    }
+
+    fn resolve_desired_position(&self, pos: SeekFrom) -> Result<u64, <Self as ErrorType>::Error> {
         let desired_address: u64 = match pos {
             SeekFrom::Start(desired_address) => desired_address,
             SeekFrom::Current(offset) => {
@@ -104,12 +260,56 @@ where
             }
         };
 
-        desired_address
-            .try_into()
-            .map_err(|_| FileError::SeekPositionBeyondLimits(desired_address))
+        ensure!(
+            desired_address <= MAX_FILE_SIZE,
+            FileError::SeekPositionBeyondLimits(desired_address)
+        );
+        ensure!(
+            desired_address <= self.file_size || self.open_options.is_write(),
+            FileError::SeekPositionBeyondEnd(desired_address)
+        );
+
+        Ok(desired_address)
     }
 }
 
+/// Resolves a seek within a read-only stream of the given size, which can't be seeked past its
+/// end.
+fn resolve_bounded_position<DE, SE>(
+    pos: SeekFrom,
+    current_position: u64,
+    size: u64,
+) -> Result<u64, FileError<DE, SE>>
+where
+    DE: core::error::Error,
+    SE: embedded_io::Error,
+{
+    let desired_address: u64 = match pos {
+        SeekFrom::Start(desired_address) => desired_address,
+        SeekFrom::Current(offset) => {
+            let desired_address: i64 = current_position as i64 + offset;
+
+            desired_address
+                .try_into()
+                .map_err(|_| FileError::SeekPositionImpossible(desired_address))?
+        }
+        SeekFrom::End(end_offset) => {
+            let desired_address: i64 = size as i64 + end_offset;
+
+            desired_address
+                .try_into()
+                .map_err(|_| FileError::SeekPositionImpossible(desired_address))?
+        }
+    };
+
+    ensure!(
+        desired_address <= size,
+        FileError::SeekPositionBeyondEnd(desired_address)
+    );
+
+    Ok(desired_address)
+}
+
 impl<D> ErrorType for File<'_, D>
 where
     D: Device,
@@ -117,32 +317,108 @@ where
     type Error = FileError<D::Error, <D::Stream as ErrorType>::Error>;
 }
 
-#[cfg(feature = "sync")]
-impl<D, S> Read for File<'_, D>
+/// Reads complete as soon as the device answers, so a file is always ready to be read.
+impl<D> ReadReady for File<'_, D>
 where
-    D: SyncDevice<Stream = S>,
-    S: Read + Seek,
+    D: Device,
 {
-    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+}
+
+/// Writes complete as soon as the device answers, so a file is always ready to be written.  Files
+/// not opened for writing still reject the write itself.
+impl<D> WriteReady for File<'_, D>
+where
+    D: Device,
+{
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+}
+
+/// The body of `read_exact_or_eof`, shared between the sync and async implementations.
+macro_rules! read_exact_or_eof_body {
+    ($io:ident, $self:ident, $buf:ident) => {{
+        let mut is_anything_read = false;
+
+        while !$buf.is_empty() {
+            match $io!(Read::read($self, $buf))? {
+                0 if is_anything_read => return Err(ReadExactError::UnexpectedEof),
+                0 => return Ok(false),
+                read => {
+                    is_anything_read = true;
+                    $buf = &mut $buf[read..];
+                }
+            }
+        }
+
+        Ok(true)
+    }};
+}
+
+/// The body of `read`, shared between the sync and async implementations.
+macro_rules! read_body {
+    ($io:ident, $self:ident, $buf:ident) => {{
+        $self.ensure_media_unchanged()?;
+
         // Limit to either the end of the file or the end of the current cluster
-        let target_read_size = self.resolve_max_read_size(buf.len());
+        let target_read_size = $self.resolve_max_read_size($buf.len());
 
         if target_read_size == 0 {
             return Ok(0);
         }
 
-        let actual_read_size = self
-            .device
-            .with_stream(|stream| -> Result<usize, Self::Error> {
-                stream.seek(SeekFrom::Start(self.current_address()))?;
+        let current_address = $self.current_address()?;
+        let actual_read_size = $io!(with_stream(
+            $self.device,
+            |stream| -> Result<usize, Self::Error> {
+                $io!(Seek::seek(stream, SeekFrom::Start(current_address.value())))?;
 
-                Ok(stream.read(&mut buf[0..target_read_size])?)
-            })
-            .map_err(FileError::DeviceError)??;
+                Ok($io!(Read::read(stream, &mut $buf[0..target_read_size]))?)
+            }
+        ))
+        .map_err(FileError::DeviceError)??;
 
-        self.seek(SeekFrom::Current(actual_read_size as i64))?;
+        $io!(Seek::seek(
+            $self,
+            SeekFrom::Current(actual_read_size as i64)
+        ))?;
 
         Ok(actual_read_size)
+    }};
+}
+
+#[cfg(feature = "sync")]
+impl<D, S> Read for File<'_, D>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        read_body!(sync_io, self, buf)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<D, S> File<'_, D>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Seek,
+{
+    /// Fills the buffer as `read_exact` does, but tells a file which was already at its end apart
+    /// from one which ended partway through the buffer.
+    ///
+    /// Returns `false` if the file was at its end before anything was read, leaving the buffer
+    /// untouched.  Fails with `ReadExactError::UnexpectedEof` if the file ended after only part of
+    /// the buffer was filled, while device and stream errors are returned as
+    /// `ReadExactError::Other`.
+    pub fn read_exact_or_eof(
+        &mut self,
+        mut buf: &mut [u8],
+    ) -> Result<bool, ReadExactError<<Self as ErrorType>::Error>> {
+        read_exact_or_eof_body!(sync_io, self, buf)
     }
 }
 
@@ -153,90 +429,107 @@ where
     S: AsyncRead + AsyncSeek,
 {
     async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
-        let target_read_size = self.resolve_max_read_size(buf.len());
-
-        if target_read_size == 0 {
-            return Ok(0);
-        }
-
-        let actual_read_size = self
-            .device
-            .with_stream(async |stream| -> Result<usize, Self::Error> {
-                stream.seek(SeekFrom::Start(self.current_address())).await?;
-
-                Ok(stream.read(&mut buf[0..target_read_size]).await?)
-            })
-            .await
-            .map_err(FileError::DeviceError)??;
-
-        self.seek(SeekFrom::Current(actual_read_size as i64))
-            .await?;
-
-        Ok(actual_read_size)
+        read_body!(async_io, self, buf)
     }
 }
 
-#[cfg(feature = "sync")]
-impl<D, S> Seek for File<'_, D>
+#[cfg(feature = "async")]
+impl<D, S> File<'_, D>
 where
-    D: SyncDevice<Stream = S>,
-    S: Read + Seek,
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncSeek,
 {
-    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
-        let desired_position = self.resolve_desired_position(pos)?;
-        let relative_position_change = desired_position as i64 - self.current_position as i64;
+    /// Fills the buffer as `read_exact` does, but tells a file which was already at its end apart
+    /// from one which ended partway through the buffer, as `read_exact_or_eof` does.
+    pub async fn read_exact_or_eof_async(
+        &mut self,
+        mut buf: &mut [u8],
+    ) -> Result<bool, ReadExactError<<Self as ErrorType>::Error>> {
+        read_exact_or_eof_body!(async_io, self, buf)
+    }
+}
+
+/// The body of `seek`, shared between the sync and async implementations.
+macro_rules! seek_body {
+    ($io:ident, $self:ident, $pos:ident) => {{
+        $self.ensure_media_unchanged()?;
+
+        let desired_position = $self.resolve_desired_position($pos)?;
+
+        // Positions past the end share the cluster position of the end itself
+        let target_position = min(desired_position, $self.file_size);
+        let relative_position_change = target_position as i64 - $self.cluster_position() as i64;
 
         if relative_position_change == 0 {
-            return Ok(self.current_position.into());
+            $self.current_position = desired_position;
+
+            return Ok(desired_position);
         }
 
-        let mut new_cluster_number = self.current_cluster_number;
+        let mut new_cluster_number = $self.current_cluster_number;
         let mut new_cluster_offset: i64 =
-            self.current_cluster_offset as i64 + relative_position_change;
+            $self.current_cluster_offset as i64 + relative_position_change;
         let is_inside_current_cluster =
-            new_cluster_offset >= 0 && new_cluster_offset < self.bytes_per_cluster as i64;
+            new_cluster_offset >= 0 && new_cluster_offset < $self.bytes_per_cluster as i64;
 
         if !is_inside_current_cluster {
-            if relative_position_change < 0 {
+            if let Some((indexed_cluster_number, indexed_cluster_offset)) =
+                $self.indexed_cluster_for(target_position)
+            {
+                new_cluster_number = indexed_cluster_number;
+                new_cluster_offset = indexed_cluster_offset;
+            } else if relative_position_change < 0 {
                 // Rewind back to the start
-                new_cluster_number = self.first_cluster_number;
-                new_cluster_offset = desired_position as i64;
+                new_cluster_number = $self.first_cluster_number;
+                new_cluster_offset = target_position as i64;
             }
 
-            self.device
-                .with_stream(|stream| -> Result<(), Self::Error> {
-                    // Navigate forward until we get to the correct cluster or reach EOF
-                    while new_cluster_offset > self.bytes_per_cluster as i64 {
-                        match self
-                            .allocation_table
-                            .read_entry(stream, new_cluster_number)?
-                        {
-                            AllocationTableEntry::NextClusterNumber(next_cluster_number) => {
-                                new_cluster_number = next_cluster_number;
-                                new_cluster_offset -= self.bytes_per_cluster as i64;
-                            }
-                            AllocationTableEntry::EndOfFile => break,
-                            AllocationTableEntry::Free
-                            | AllocationTableEntry::BadSector
-                            | AllocationTableEntry::Reserved => {
-                                return Err(FileError::UnexpectedAllocationTableEntryEncountered);
-                            }
+            $io!(with_stream($self.device, |stream| -> Result<(), Self::Error> {
+                // Navigate forward until we get to the correct cluster or reach EOF
+                while new_cluster_offset >= $self.bytes_per_cluster as i64 {
+                    match $io!(
+                        $self.allocation_table.read_entry(stream, new_cluster_number);
+                        $self.allocation_table.read_entry_async(stream, new_cluster_number)
+                    )? {
+                        AllocationTableEntry::NextClusterNumber(next_cluster_number) => {
+                            new_cluster_number = next_cluster_number;
+                            new_cluster_offset -= $self.bytes_per_cluster as i64;
+                        }
+                        AllocationTableEntry::EndOfFile => break,
+                        AllocationTableEntry::Free
+                        | AllocationTableEntry::BadSector
+                        | AllocationTableEntry::Reserved => {
+                            return Err(FileError::UnexpectedAllocationTableEntryEncountered {
+                                cluster_number: new_cluster_number.value(),
+                            });
                         }
                     }
+                }
 
-                    Ok(())
-                })
-                .map_err(FileError::DeviceError)??;
+                Ok(())
+            }))
+            .map_err(FileError::DeviceError)??;
 
             // Clamp to the end of the cluster if the offset is beyond the cluster's end still
-            new_cluster_offset = min(new_cluster_offset, self.bytes_per_cluster as i64);
+            new_cluster_offset = min(new_cluster_offset, $self.bytes_per_cluster as i64);
         }
 
-        self.current_cluster_number = new_cluster_number;
-        self.current_cluster_offset = new_cluster_offset as u32;
-        self.current_position = desired_position;
+        $self.current_cluster_number = new_cluster_number;
+        $self.current_cluster_offset = new_cluster_offset as u32;
+        $self.current_position = desired_position;
 
-        Ok(desired_position.into())
+        Ok(desired_position)
+    }};
+}
+
+#[cfg(feature = "sync")]
+impl<D, S> Seek for File<'_, D>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Seek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        seek_body!(sync_io, self, pos)
     }
 }
 
@@ -247,63 +540,181 @@ where
     S: AsyncRead + AsyncSeek,
 {
     async fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
-        let desired_position: u32 = self.resolve_desired_position(pos)?;
-        let relative_position_change = desired_position as i64 - self.current_position as i64;
+        seek_body!(async_io, self, pos)
+    }
+}
 
-        if relative_position_change == 0 {
-            return Ok(self.current_position.into());
+/// The body of `write`, shared between the sync and async implementations.
+macro_rules! write_body {
+    ($io:ident, $self:ident, $buf:ident) => {{
+        $self.ensure_media_unchanged()?;
+        ensure!($self.open_options.is_write(), FileError::NotOpenForWriting);
+
+        let target_write_size = $self.resolve_max_write_size($buf.len())?;
+
+        if target_write_size == 0 {
+            return Ok(0);
         }
 
-        let mut new_cluster_number = self.current_cluster_number;
-        let mut new_cluster_offset: i64 =
-            self.current_cluster_offset as i64 + relative_position_change;
-        let is_inside_current_cluster =
-            new_cluster_offset >= 0 && new_cluster_offset < self.bytes_per_cluster as i64;
+        $io!(with_stream($self.device, |stream| -> Result<(), Self::Error> {
+            // Fill the gap left by seeking past the end first, so clusters recycled from
+            // deleted files never expose their previous contents
+            while $self.file_size < $self.current_position {
+                let fill_size = min(
+                    $self.current_position - $self.file_size,
+                    ZERO_FILL.len() as u64,
+                ) as usize;
+
+                $self.file_size += $io!(
+                    $self.write_at_cluster_position(stream, &ZERO_FILL[..fill_size]);
+                    $self.write_at_cluster_position_async(stream, &ZERO_FILL[..fill_size])
+                )? as u64;
+            }
 
-        if !is_inside_current_cluster {
-            if relative_position_change < 0 {
-                // Rewind back to the start
-                new_cluster_number = self.first_cluster_number;
-                new_cluster_offset = desired_position as i64;
+            let mut written_size = 0;
+
+            while written_size < target_write_size {
+                written_size += $io!(
+                    $self.write_at_cluster_position(stream, &$buf[written_size..target_write_size]);
+                    $self.write_at_cluster_position_async(
+                        stream,
+                        &$buf[written_size..target_write_size]
+                    )
+                )?;
             }
 
-            self.device
-                .with_stream(async |stream| -> Result<(), Self::Error> {
-                    // Navigate forward until we get to the correct cluster or reach EOF
-                    while new_cluster_offset >= self.bytes_per_cluster as i64 {
-                        match self
-                            .allocation_table
-                            .read_entry_async(stream, new_cluster_number)
-                            .await?
-                        {
-                            AllocationTableEntry::NextClusterNumber(next_cluster_number) => {
-                                new_cluster_number = next_cluster_number;
-                                new_cluster_offset -= self.bytes_per_cluster as i64;
-                            }
-                            AllocationTableEntry::EndOfFile => break,
-                            AllocationTableEntry::Free
-                            | AllocationTableEntry::BadSector
-                            | AllocationTableEntry::Reserved => {
-                                return Err(FileError::UnexpectedAllocationTableEntryEncountered);
-                            }
-                        }
-                    }
+            Ok(())
+        }))
+        .map_err(FileError::DeviceError)??;
 
-                    Ok(())
-                })
-                .await
-                .map_err(FileError::DeviceError)??;
+        $self.current_position += target_write_size as u64;
+        $self.file_size = max($self.file_size, $self.current_position);
 
-            // Clamp to the end of the cluster if the offset is beyond the cluster's end still
-            new_cluster_offset = min(new_cluster_offset, self.bytes_per_cluster as i64);
+        Ok(target_write_size)
+    }};
+}
+
+/// The body of `flush`, shared between the sync and async implementations.
+macro_rules! flush_body {
+    ($io:ident, $self:ident) => {{
+        $self.ensure_media_unchanged()?;
+
+        $io!($self.device.flush()).map_err(FileError::DeviceError)
+    }};
+}
+
+/// The body of `write_at_cluster_position`, shared between the sync and async implementations.
+macro_rules! write_at_cluster_position_body {
+    ($io:ident, $self:ident, $stream:ident, $bytes:ident) => {{
+        if $self.current_cluster_number == ClusterNumber::NONE {
+            let cluster_number = $io!(
+                $self.allocate_cluster($stream, None);
+                $self.allocate_cluster_async($stream, None)
+            )?;
+
+            $self.first_cluster_number = cluster_number;
+            $self.current_cluster_number = cluster_number;
+            $self.current_cluster_offset = 0;
+        } else if $self.current_cluster_offset >= $self.bytes_per_cluster {
+            $self.current_cluster_number = match $io!(
+                $self.allocation_table.read_entry($stream, $self.current_cluster_number);
+                $self.allocation_table.read_entry_async($stream, $self.current_cluster_number)
+            )? {
+                AllocationTableEntry::NextClusterNumber(next_cluster_number) => next_cluster_number,
+                AllocationTableEntry::EndOfFile => $io!(
+                    $self.allocate_cluster($stream, Some($self.current_cluster_number));
+                    $self.allocate_cluster_async($stream, Some($self.current_cluster_number))
+                )?,
+                AllocationTableEntry::Free
+                | AllocationTableEntry::BadSector
+                | AllocationTableEntry::Reserved => {
+                    return Err(FileError::UnexpectedAllocationTableEntryEncountered {
+                        cluster_number: $self.current_cluster_number.value(),
+                    });
+                }
+            };
+            $self.current_cluster_offset = 0;
         }
 
-        self.current_cluster_number = new_cluster_number;
-        self.current_cluster_offset = new_cluster_offset as u32;
-        self.current_position = desired_position;
+        let write_size = min(
+            $bytes.len(),
+            ($self.bytes_per_cluster - $self.current_cluster_offset) as usize,
+        );
 
-        Ok(desired_position.into())
-    }
+        $io!(Seek::seek($stream, SeekFrom::Start($self.current_address()?.value())))?;
+        $io!(Write::write_all($stream, &$bytes[..write_size]))?;
+
+        $self.current_cluster_offset += write_size as u32;
+
+        Ok(write_size)
+    }};
+}
+
+/// The body of `allocate_cluster`, shared between the sync and async implementations.
+macro_rules! allocate_cluster_body {
+    ($io:ident, $self:ident, $stream:ident, $previous_cluster_number:ident) => {{
+        let start_cluster_number =
+            $previous_cluster_number.map_or(ClusterNumber::FIRST_DATA_CLUSTER, ClusterNumber::next);
+
+        // Clusters before the previous one are only searched once the rest of the volume is full
+        let cluster_number = match $io!(
+            $self.allocation_table.find_free_cluster(
+                $stream,
+                start_cluster_number,
+                $self.volume_last_cluster_number,
+            );
+            $self.allocation_table.find_free_cluster_async(
+                $stream,
+                start_cluster_number,
+                $self.volume_last_cluster_number,
+            )
+        )? {
+            Some(cluster_number) => Some(cluster_number),
+            None => $io!(
+                $self.allocation_table.find_free_cluster(
+                    $stream,
+                    ClusterNumber::FIRST_DATA_CLUSTER,
+                    $self.volume_last_cluster_number,
+                );
+                $self.allocation_table.find_free_cluster_async(
+                    $stream,
+                    ClusterNumber::FIRST_DATA_CLUSTER,
+                    $self.volume_last_cluster_number,
+                )
+            )?,
+        }
+        .ok_or(FileError::VolumeFull)?;
+
+        $io!(
+            $self.allocation_table.write_entry(
+                $stream,
+                cluster_number,
+                AllocationTableEntry::EndOfFile,
+            );
+            $self.allocation_table.write_entry_async(
+                $stream,
+                cluster_number,
+                AllocationTableEntry::EndOfFile,
+            )
+        )?;
+
+        if let Some(previous_cluster_number) = $previous_cluster_number {
+            $io!(
+                $self.allocation_table.write_entry(
+                    $stream,
+                    previous_cluster_number,
+                    AllocationTableEntry::NextClusterNumber(cluster_number),
+                );
+                $self.allocation_table.write_entry_async(
+                    $stream,
+                    previous_cluster_number,
+                    AllocationTableEntry::NextClusterNumber(cluster_number),
+                )
+            )?;
+        }
+
+        Ok(cluster_number)
+    }};
 }
 
 #[cfg(feature = "sync")]
@@ -312,12 +723,39 @@ where
     D: SyncFlushableDevice<Stream = S>,
     S: Read + Seek + Write,
 {
-    fn write(&mut self, _buf: &[u8]) -> Result<usize, Self::Error> {
-        todo!()
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        write_body!(sync_io, self, buf)
     }
 
     fn flush(&mut self) -> Result<(), Self::Error> {
-        self.device.flush().map_err(FileError::DeviceError)
+        flush_body!(sync_io, self)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<D, S> File<'_, D>
+where
+    D: SyncFlushableDevice<Stream = S>,
+    S: Read + Seek + Write,
+{
+    /// Writes as much of the bytes as fits in the cluster holding the cluster position, moving to
+    /// the next cluster of the chain or allocating one first if the current cluster is full.
+    /// Returns the number of bytes written, which the cluster position has been advanced by.
+    fn write_at_cluster_position(
+        &mut self,
+        stream: &mut S,
+        bytes: &[u8],
+    ) -> Result<usize, <Self as ErrorType>::Error> {
+        write_at_cluster_position_body!(sync_io, self, stream, bytes)
+    }
+
+    /// Allocates a free cluster ending the chain, linking it after the previous cluster if given.
+    fn allocate_cluster(
+        &self,
+        stream: &mut S,
+        previous_cluster_number: Option<ClusterNumber>,
+    ) -> Result<ClusterNumber, <Self as ErrorType>::Error> {
+        allocate_cluster_body!(sync_io, self, stream, previous_cluster_number)
     }
 }
 
@@ -327,11 +765,37 @@ where
     D: AsyncFlushableDevice<Stream = S>,
     S: AsyncRead + AsyncSeek + AsyncWrite,
 {
-    async fn write(&mut self, _buf: &[u8]) -> Result<usize, Self::Error> {
-        todo!()
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        write_body!(async_io, self, buf)
     }
 
     async fn flush(&mut self) -> Result<(), Self::Error> {
-        self.device.flush().await.map_err(FileError::DeviceError)
+        flush_body!(async_io, self)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, S> File<'_, D>
+where
+    D: AsyncFlushableDevice<Stream = S>,
+    S: AsyncRead + AsyncSeek + AsyncWrite,
+{
+    /// Writes as much of the bytes as fits in the cluster holding the cluster position, see
+    /// `write_at_cluster_position`.
+    async fn write_at_cluster_position_async(
+        &mut self,
+        stream: &mut S,
+        bytes: &[u8],
+    ) -> Result<usize, <Self as ErrorType>::Error> {
+        write_at_cluster_position_body!(async_io, self, stream, bytes)
+    }
+
+    /// Allocates a free cluster ending the chain, see `allocate_cluster`.
+    async fn allocate_cluster_async(
+        &self,
+        stream: &mut S,
+        previous_cluster_number: Option<ClusterNumber>,
+    ) -> Result<ClusterNumber, <Self as ErrorType>::Error> {
+        allocate_cluster_body!(async_io, self, stream, previous_cluster_number)
     }
 }
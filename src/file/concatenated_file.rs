@@ -0,0 +1,150 @@
+use crate::file::resolve_bounded_position;
+use crate::{Device, File, FileError};
+use embedded_io::{ErrorType, SeekFrom};
+
+#[cfg(feature = "sync")]
+use {
+    crate::SyncDevice,
+    embedded_io::{Read, Seek},
+};
+
+#[cfg(feature = "async")]
+use {
+    crate::AsyncDevice,
+    embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek},
+};
+
+/// Presents several files as one continuous stream of their contents in order, such as a
+/// firmware image split into chunks `FW.001` through `FW.999`.
+///
+/// The size of the stream is the sum of the files' sizes.  Seeking only moves the stream's
+/// position, the file holding it is seeked to its part of the position by the following read, so
+/// reading straight through never seeks any file.  Empty files take up no part of the stream.
+#[derive(Clone, Debug)]
+pub struct ConcatenatedFile<'a, D, const N: usize>
+where
+    D: Device,
+{
+    files: [File<'a, D>; N],
+    position: u64,
+}
+
+impl<'a, D, const N: usize> ConcatenatedFile<'a, D, N>
+where
+    D: Device,
+{
+    /// Starts the stream at the start of the first file, regardless of the files' positions.
+    pub fn new(files: [File<'a, D>; N]) -> Self {
+        Self { files, position: 0 }
+    }
+
+    /// The number of bytes all of the files hold together.
+    pub fn size(&self) -> u64 {
+        self.files.iter().map(File::size).sum()
+    }
+
+    /// The offset from the start of the first file the next read begins at.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// The number of bytes left to read before the end of the last file is reached.
+    pub fn remaining(&self) -> u64 {
+        self.size().saturating_sub(self.position)
+    }
+
+    pub fn into_inner(self) -> [File<'a, D>; N] {
+        self.files
+    }
+
+    /// The index of the file holding the byte at the position along with the position's offset
+    /// within that file, `None` at or past the end of the stream.
+    fn locate(&self, position: u64) -> Option<(usize, u64)> {
+        let mut file_start = 0;
+
+        for (index, file) in self.files.iter().enumerate() {
+            if position < file_start + file.size() {
+                return Some((index, position - file_start));
+            }
+
+            file_start += file.size();
+        }
+
+        None
+    }
+}
+
+impl<D, const N: usize> ErrorType for ConcatenatedFile<'_, D, N>
+where
+    D: Device,
+{
+    type Error = FileError<D::Error, <D::Stream as ErrorType>::Error>;
+}
+
+/// The body of `read`, shared between the sync and async implementations.
+macro_rules! read_body {
+    ($io:ident, $self:ident, $buf:ident) => {{
+        let Some((index, offset)) = $self.locate($self.position) else {
+            return Ok(0);
+        };
+
+        let file = &mut $self.files[index];
+
+        if file.position() != offset {
+            $io!(Seek::seek(file, SeekFrom::Start(offset)))?;
+        }
+
+        let read_size = $io!(Read::read(file, $buf))?;
+        $self.position += read_size as u64;
+
+        Ok(read_size)
+    }};
+}
+
+#[cfg(feature = "sync")]
+impl<D, S, const N: usize> Read for ConcatenatedFile<'_, D, N>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        read_body!(sync_io, self, buf)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<D, S, const N: usize> Seek for ConcatenatedFile<'_, D, N>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Seek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        self.position = resolve_bounded_position(pos, self.position, self.size())?;
+
+        Ok(self.position)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, S, const N: usize> AsyncRead for ConcatenatedFile<'_, D, N>
+where
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncSeek,
+{
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        read_body!(async_io, self, buf)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, S, const N: usize> AsyncSeek for ConcatenatedFile<'_, D, N>
+where
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncSeek,
+{
+    async fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        self.position = resolve_bounded_position(pos, self.position, self.size())?;
+
+        Ok(self.position)
+    }
+}
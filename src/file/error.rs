@@ -1,4 +1,5 @@
 use crate::allocation_table::AllocationTableError;
+use crate::directory_entry::DirectoryEntryIterationError;
 use core::error::Error;
 use core::fmt::{Display, Formatter};
 use embedded_io::{ErrorKind, ReadExactError};
@@ -9,12 +10,20 @@ where
     DE: Error,
     SE: embedded_io::Error,
 {
+    ClusterNumberInvalid { cluster_number: u32 },
     DeviceError(DE),
+    EntryIterationError(DirectoryEntryIterationError<DE, SE>),
+    FileSizeBeyondLimits(u64),
+    MediaChanged,
+    NotOpenForWriting,
+    QuotaExceeded,
+    SeekPositionBeyondEnd(u64),
     SeekPositionBeyondLimits(u64),
     SeekPositionImpossible(i64),
     StreamEndReached,
     StreamError(SE),
-    UnexpectedAllocationTableEntryEncountered,
+    UnexpectedAllocationTableEntryEncountered { cluster_number: u32 },
+    VolumeFull,
 }
 
 impl<DE, SE> Error for FileError<DE, SE>
@@ -31,7 +40,36 @@ where
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
+            FileError::ClusterNumberInvalid { cluster_number } => write!(
+                f,
+                "cluster {cluster_number} is not a valid data region cluster number"
+            ),
             FileError::DeviceError(e) => write!(f, "device error occurred: {}", e),
+            FileError::EntryIterationError(e) => {
+                write!(
+                    f,
+                    "error occurred updating the file's directory entry: {}",
+                    e
+                )
+            }
+            FileError::FileSizeBeyondLimits(file_size) => write!(
+                f,
+                "write results in a file size beyond allowed limits: {}",
+                file_size
+            ),
+            FileError::MediaChanged => write!(
+                f,
+                "the medium was changed since the file's volume was mounted"
+            ),
+            FileError::NotOpenForWriting => write!(f, "file was not opened for writing"),
+            FileError::QuotaExceeded => {
+                write!(f, "write would exceed the quota of the file's directory")
+            }
+            FileError::SeekPositionBeyondEnd(desired_address) => write!(
+                f,
+                "seek position {} is beyond the end of a file not opened for writing",
+                desired_address
+            ),
             FileError::SeekPositionBeyondLimits(desired_address) => write!(
                 f,
                 "seek position provided results in address beyond allowed limits: {}",
@@ -44,10 +82,11 @@ where
             ),
             FileError::StreamEndReached => write!(f, "stream end was reached when not expected"),
             FileError::StreamError(e) => write!(f, "stream error occurred: {}", e),
-            FileError::UnexpectedAllocationTableEntryEncountered => write!(
+            FileError::UnexpectedAllocationTableEntryEncountered { cluster_number } => write!(
                 f,
-                "an unexpected allocation table entry type was encountered"
+                "an unexpected allocation table entry type was encountered for cluster {cluster_number}"
             ),
+            FileError::VolumeFull => write!(f, "the volume has no free clusters left"),
         }
     }
 }
@@ -88,6 +127,16 @@ where
     }
 }
 
+impl<DE, SE> From<DirectoryEntryIterationError<DE, SE>> for FileError<DE, SE>
+where
+    DE: Error,
+    SE: embedded_io::Error,
+{
+    fn from(value: DirectoryEntryIterationError<DE, SE>) -> Self {
+        FileError::EntryIterationError(value)
+    }
+}
+
 impl<DE, SE> From<AllocationTableError<SE>> for FileError<DE, SE>
 where
     DE: Error,
@@ -95,6 +144,9 @@ where
 {
     fn from(value: AllocationTableError<SE>) -> Self {
         match value {
+            AllocationTableError::EntryValueInvalid { cluster_number } => {
+                FileError::ClusterNumberInvalid { cluster_number }
+            }
             AllocationTableError::StreamEndReached => FileError::StreamEndReached,
             AllocationTableError::StreamError(stream_error) => stream_error.into(),
         }
@@ -117,12 +169,20 @@ mod tests {
         #[test]
         fn produces_non_empty_value() {
             let values = [
+                FileError::ClusterNumberInvalid { cluster_number: 0 },
                 FileError::DeviceError(IoError::default()),
+                FileError::EntryIterationError(DirectoryEntryIterationError::StreamEndReached),
+                FileError::FileSizeBeyondLimits(0),
+                FileError::MediaChanged,
+                FileError::NotOpenForWriting,
+                FileError::QuotaExceeded,
+                FileError::SeekPositionBeyondEnd(6),
                 FileError::SeekPositionBeyondLimits(0),
                 FileError::SeekPositionImpossible(0),
                 FileError::StreamEndReached,
                 FileError::StreamError(IoError::default()),
-                FileError::UnexpectedAllocationTableEntryEncountered,
+                FileError::UnexpectedAllocationTableEntryEncountered { cluster_number: 2 },
+                FileError::VolumeFull,
             ];
 
             for value in values {
@@ -0,0 +1,153 @@
+use crate::{Device, File, FileError};
+use embedded_io::ErrorType;
+
+#[cfg(feature = "sync")]
+use {
+    crate::SyncDevice,
+    embedded_io::{Read, Seek},
+};
+
+#[cfg(feature = "async")]
+use {
+    crate::AsyncDevice,
+    embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek},
+};
+
+/// Reads several files of the same volume in turns of one cluster each, so their contents can be
+/// merged into a single stream, such as an upload of log segments, without seeking back and forth
+/// through any one file's chain.
+///
+/// Each turn reads from the file's position up to the end of the cluster holding it, so only the
+/// first turn of a file seeked into a cluster is shorter.  Files which reach their end drop out of
+/// the rotation, and reading ends once every file has.
+#[derive(Clone, Debug)]
+pub struct MultiFileReader<'a, D, const N: usize>
+where
+    D: Device,
+{
+    files: [File<'a, D>; N],
+    current_index: usize,
+}
+
+impl<'a, D, const N: usize> MultiFileReader<'a, D, N>
+where
+    D: Device,
+{
+    /// Starts reading with the first file, each file from its current position.
+    pub fn new(files: [File<'a, D>; N]) -> Self {
+        Self {
+            files,
+            current_index: 0,
+        }
+    }
+
+    /// The index of the file the next read is taken from, `None` once every file was read to its
+    /// end.
+    pub fn current_index(&self) -> Option<usize> {
+        (0..N)
+            .map(|offset| (self.current_index + offset) % N)
+            .find(|index| self.files[*index].remaining() > 0)
+    }
+
+    pub fn into_inner(self) -> [File<'a, D>; N] {
+        self.files
+    }
+
+    /// Moves the rotation on to the following file once the read from the file at the index
+    /// finished its cluster or its contents.
+    fn advance_after_read(&mut self, index: usize, read_size: usize) {
+        let file = &self.files[index];
+
+        self.current_index = if read_size > 0
+            && (file.remaining() == 0
+                || file
+                    .position()
+                    .is_multiple_of(u64::from(file.bytes_per_cluster)))
+        {
+            (index + 1) % N
+        } else {
+            index
+        };
+    }
+}
+
+impl<D, const N: usize> ErrorType for MultiFileReader<'_, D, N>
+where
+    D: Device,
+{
+    type Error = FileError<D::Error, <D::Stream as ErrorType>::Error>;
+}
+
+/// The body of `read_chunk`, shared between the sync and async implementations.
+macro_rules! read_chunk_body {
+    ($io:ident, $self:ident, $buf:ident) => {{
+        let Some(index) = $self.current_index() else {
+            return Ok(None);
+        };
+
+        let read_size = $io!(Read::read(&mut $self.files[index], $buf))?;
+        $self.advance_after_read(index, read_size);
+
+        Ok(Some((index, read_size)))
+    }};
+}
+
+#[cfg(feature = "sync")]
+impl<D, S, const N: usize> MultiFileReader<'_, D, N>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Seek,
+{
+    /// Reads the next part of the current file's turn into the buffer, returning the index of the
+    /// file read from along with the number of bytes read.  Returns `None` once every file was read
+    /// to its end.
+    ///
+    /// A turn continues over several calls when the buffer is smaller than the rest of the
+    /// cluster.
+    pub fn read_chunk(
+        &mut self,
+        buf: &mut [u8],
+    ) -> Result<Option<(usize, usize)>, <Self as ErrorType>::Error> {
+        read_chunk_body!(sync_io, self, buf)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<D, S, const N: usize> Read for MultiFileReader<'_, D, N>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(self.read_chunk(buf)?.map_or(0, |(_, read_size)| read_size))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, S, const N: usize> MultiFileReader<'_, D, N>
+where
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncSeek,
+{
+    /// Reads the next part of the current file's turn into the buffer, see `read_chunk`.
+    pub async fn read_chunk_async(
+        &mut self,
+        buf: &mut [u8],
+    ) -> Result<Option<(usize, usize)>, <Self as ErrorType>::Error> {
+        read_chunk_body!(async_io, self, buf)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, S, const N: usize> AsyncRead for MultiFileReader<'_, D, N>
+where
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncSeek,
+{
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(self
+            .read_chunk_async(buf)
+            .await?
+            .map_or(0, |(_, read_size)| read_size))
+    }
+}
@@ -0,0 +1,19 @@
+use bon::Builder;
+
+/// How a file is opened.  Files are only opened for writing through a write guard, which records
+/// the written size in the file's directory entry.
+#[derive(Builder, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) struct OpenOptions {
+    /// Whether the file is opened for writing.
+    ///
+    /// Files opened for writing may be seeked past their end, the gap being filled with zeros by
+    /// the next write.  Seeking past the end of a file opened only for reading is an error.
+    #[builder(default)]
+    write: bool,
+}
+
+impl OpenOptions {
+    pub fn is_write(&self) -> bool {
+        self.write
+    }
+}
@@ -0,0 +1,159 @@
+use crate::file::resolve_bounded_position;
+use crate::{Device, File, FileError};
+use core::cmp::min;
+use embedded_io::{ErrorType, SeekFrom};
+
+#[cfg(feature = "sync")]
+use {
+    crate::SyncDevice,
+    embedded_io::{Read, Seek},
+};
+
+#[cfg(feature = "async")]
+use {
+    crate::AsyncDevice,
+    embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek},
+};
+
+/// A region of a file which reads and seeks as if it were the whole file, returned by
+/// `File::slice`.
+///
+/// Positions are relative to the start of the region and reads end at its end, so a parser handed
+/// a slice, such as one for the data chunk of a WAV file, can't stray into the rest of the file.
+#[derive(Debug)]
+pub struct FileSlice<'f, 'a, D>
+where
+    D: Device,
+{
+    file: &'f mut File<'a, D>,
+    offset: u64,
+    len: u64,
+    position: u64,
+}
+
+impl<'f, 'a, D> FileSlice<'f, 'a, D>
+where
+    D: Device,
+{
+    pub(crate) fn new(file: &'f mut File<'a, D>, offset: u64, len: u64) -> Self {
+        Self {
+            file,
+            offset,
+            len,
+            position: 0,
+        }
+    }
+
+    /// The offset of the region's start within the file.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// The number of bytes the region holds.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The offset from the start of the region the next read begins at.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// The number of bytes left to read before the end of the region is reached.
+    pub fn remaining(&self) -> u64 {
+        self.len - self.position
+    }
+
+    /// The position within the file the next read begins at.
+    fn file_position(&self) -> u64 {
+        self.offset + self.position
+    }
+
+    fn resolve_max_read_size(&self, target_buffer_length: usize) -> usize {
+        min(
+            target_buffer_length.try_into().unwrap_or(u64::MAX),
+            self.remaining(),
+        ) as usize
+    }
+}
+
+impl<D> ErrorType for FileSlice<'_, '_, D>
+where
+    D: Device,
+{
+    type Error = FileError<D::Error, <D::Stream as ErrorType>::Error>;
+}
+
+/// The body of `read`, shared between the sync and async implementations.
+macro_rules! read_body {
+    ($io:ident, $self:ident, $buf:ident) => {{
+        let target_read_size = $self.resolve_max_read_size($buf.len());
+
+        if target_read_size == 0 {
+            return Ok(0);
+        }
+
+        let file_position = $self.file_position();
+
+        if $self.file.position() != file_position {
+            $io!(Seek::seek($self.file, SeekFrom::Start(file_position)))?;
+        }
+
+        let read_size = $io!(Read::read($self.file, &mut $buf[..target_read_size]))?;
+        $self.position += read_size as u64;
+
+        Ok(read_size)
+    }};
+}
+
+#[cfg(feature = "sync")]
+impl<D, S> Read for FileSlice<'_, '_, D>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        read_body!(sync_io, self, buf)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<D, S> Seek for FileSlice<'_, '_, D>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Seek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        self.position = resolve_bounded_position(pos, self.position, self.len)?;
+
+        Ok(self.position)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, S> AsyncRead for FileSlice<'_, '_, D>
+where
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncSeek,
+{
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        read_body!(async_io, self, buf)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, S> AsyncSeek for FileSlice<'_, '_, D>
+where
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncSeek,
+{
+    async fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        self.position = resolve_bounded_position(pos, self.position, self.len)?;
+
+        Ok(self.position)
+    }
+}
@@ -1,4 +1,9 @@
+#[cfg(feature = "unicode-normalization")]
+use crate::encoding::ComposedCharacters;
 use crate::encoding::Ucs2Character;
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
 
 pub const LONG_NAME_MAX_LENGTH: usize = 255;
 
@@ -12,7 +17,40 @@ impl LongFileName {
         LongFileName { ucs2_characters }
     }
 
-    pub fn from_str(name: &str) -> Result<LongFileName, LongFileNameError> {
+    pub fn is_empty(&self) -> bool {
+        self.ucs2_characters[0] == Ucs2Character::null()
+    }
+
+    /// The UCS-2 characters of the name, excluding the null terminator and anything following it.
+    pub(crate) fn ucs2_characters(&self) -> &[Ucs2Character] {
+        let length = self
+            .ucs2_characters
+            .iter()
+            .position(|character| *character == Ucs2Character::null())
+            .unwrap_or(LONG_NAME_MAX_LENGTH);
+
+        &self.ucs2_characters[..length]
+    }
+
+    /// The characters of the name, excluding the null terminator and anything following it.
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.ucs2_characters()
+            .iter()
+            .map(|character| character.to_char())
+    }
+
+    fn is_valid_character(character: char) -> bool {
+        !matches!(
+            character,
+            '\0'..='\x1F' | '"' | '*' | '/' | ':' | '<' | '>' | '?' | '\\' | '|' | '\u{FFFF}'
+        )
+    }
+}
+
+impl FromStr for LongFileName {
+    type Err = LongFileNameError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
         ensure!(!name.is_empty(), LongFileNameError::InputEmpty);
 
         let mut ucs2_characters = [Ucs2Character::null(); LONG_NAME_MAX_LENGTH];
@@ -39,20 +77,10 @@ impl LongFileName {
 
         Ok(Self::new(ucs2_characters))
     }
-
-    pub fn is_empty(&self) -> bool {
-        self.ucs2_characters[0] == Ucs2Character::null()
-    }
-
-    fn is_valid_character(character: char) -> bool {
-        !matches!(
-            character,
-            '\0'..='\x1F' | '"' | '*' | '/' | ':' | '<' | '>' | '?' | '\\' | '|' | '\u{FFFF}'
-        )
-    }
 }
 
 impl PartialEq for LongFileName {
+    #[cfg(not(feature = "unicode-normalization"))]
     fn eq(&self, other: &Self) -> bool {
         let mut left_chars = self.ucs2_characters.iter();
         let mut right_chars = other.ucs2_characters.iter();
@@ -76,6 +104,21 @@ impl PartialEq for LongFileName {
             }
         }
     }
+
+    #[cfg(feature = "unicode-normalization")]
+    fn eq(&self, other: &Self) -> bool {
+        // Names are composed before being folded so decomposed names match their composed form
+        let mut left_chars = ComposedCharacters::new(self.ucs2_characters().iter().copied());
+        let mut right_chars = ComposedCharacters::new(other.ucs2_characters().iter().copied());
+
+        loop {
+            match (left_chars.next(), right_chars.next()) {
+                (None, None) => return true,
+                (Some(l), Some(r)) if l.eq_ignore_case(&r) => {}
+                _ => return false,
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -85,11 +128,57 @@ pub enum LongFileNameError {
     InputTooLong,
 }
 
+impl Error for LongFileNameError {}
+
+impl Display for LongFileNameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LongFileNameError::CharacterInvalid { character, offset } => {
+                write!(
+                    f,
+                    "the character {character:?} at offset {offset} is not valid in a long name"
+                )
+            }
+            LongFileNameError::InputEmpty => write!(f, "the name is empty"),
+            LongFileNameError::InputTooLong => {
+                write!(
+                    f,
+                    "the name is longer than {LONG_NAME_MAX_LENGTH} characters"
+                )
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::string::ToString;
     use core::array::from_fn;
 
+    mod display {
+        use super::*;
+
+        #[test]
+        fn produces_non_empty_value() {
+            let values = [
+                LongFileNameError::CharacterInvalid {
+                    character: '/',
+                    offset: 0,
+                },
+                LongFileNameError::InputEmpty,
+                LongFileNameError::InputTooLong,
+            ];
+
+            for value in values {
+                assert!(
+                    !value.to_string().is_empty(),
+                    "Display implementation should be non-empty"
+                );
+            }
+        }
+    }
+
     mod from_str {
         use super::*;
 
@@ -218,6 +307,48 @@ mod tests {
         }
     }
 
+    mod ucs2_characters {
+        use super::*;
+
+        #[test]
+        fn characters_returned_until_terminator() {
+            let long_file_name =
+                LongFileName::from_str("foo").expect("Provided string should be valid");
+
+            assert_eq!(
+                long_file_name.ucs2_characters(),
+                &[
+                    Ucs2Character::from_char('f').unwrap(),
+                    Ucs2Character::from_char('o').unwrap(),
+                    Ucs2Character::from_char('o').unwrap(),
+                ]
+            );
+        }
+
+        #[test]
+        fn maximum_length_returned_entirely() {
+            let long_file_name = LongFileName::from_str(&"a".repeat(LONG_NAME_MAX_LENGTH))
+                .expect("Provided string should be valid");
+
+            assert_eq!(long_file_name.ucs2_characters().len(), LONG_NAME_MAX_LENGTH);
+        }
+    }
+
+    mod chars {
+        use super::*;
+        use alloc::string::String;
+
+        #[test]
+        fn characters_returned_until_terminator() {
+            let long_file_name = LongFileName::from_str("long-File.name.txt")
+                .expect("Provided string should be valid");
+
+            let result: String = long_file_name.chars().collect();
+
+            assert_eq!(result, "long-File.name.txt");
+        }
+    }
+
     mod eq {
         use super::*;
 
@@ -256,6 +387,18 @@ mod tests {
             assert_eq!(name_2, name_1, "Values should be equal");
         }
 
+        #[cfg(feature = "unicode-normalization")]
+        #[test]
+        fn decomposed_characters_returns_true() {
+            let name_1 =
+                LongFileName::from_str("Cafe\u{301}.txt").expect("Provided string should be valid");
+            let name_2 =
+                LongFileName::from_str("café.TXT").expect("Provided string should be valid");
+
+            assert_eq!(name_1, name_2, "Values should be equal");
+            assert_eq!(name_2, name_1, "Values should be equal");
+        }
+
         #[test]
         fn different_values_returns_false() {
             let name_1 = LongFileName::from_str("a").expect("Provided string should be valid");
@@ -5,7 +5,10 @@ pub use error::*;
 pub use parse_error::*;
 
 use crate::CodePageEncoder;
-use crate::directory_entry::SHORT_NAME_CHARACTER_COUNT;
+use crate::directory_entry::{SHORT_NAME_CHARACTER_COUNT, ShortNameCase};
+
+const DOT_NAME: [u8; SHORT_NAME_CHARACTER_COUNT] = *b".          ";
+const DOTDOT_NAME: [u8; SHORT_NAME_CHARACTER_COUNT] = *b"..         ";
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ShortFileName {
@@ -37,33 +40,47 @@ impl ShortFileName {
     where
         CPE: CodePageEncoder,
     {
-        ensure!(!value.is_empty(), ShortFileNameParseError::InputEmpty);
-
-        let (name, extension) = match value.split_once(".") {
-            None => (value, ""),
-            Some((name, extension)) => (name, extension),
-        };
+        Self::from_chars(encoder, value.chars())
+    }
 
-        ensure!(!name.is_empty(), ShortFileNameParseError::NameEmpty);
+    /// Parses the characters as `from_str` does, for names which aren't held as a `str` such as
+    /// long names.
+    pub(crate) fn from_chars<CPE>(
+        encoder: &CPE,
+        characters: impl Iterator<Item = char>,
+    ) -> Result<Self, ShortFileNameParseError>
+    where
+        CPE: CodePageEncoder,
+    {
+        let mut characters = characters.peekable();
+        ensure!(
+            characters.peek().is_some(),
+            ShortFileNameParseError::InputEmpty
+        );
 
         let mut bytes = [0x20; SHORT_NAME_CHARACTER_COUNT];
 
         let mut name_len = 0;
-        for (index, character) in name.chars().enumerate() {
-            // Using index here instead of str.len() because this counts characters instead of bytes
-            ensure!(index < 8, ShortFileNameParseError::NameTooLong);
+        for character in characters.by_ref() {
+            if character == '.' {
+                break;
+            }
 
-            let mut encoded_character = Self::encode_character(encoder, character, index as u8)?;
+            ensure!(name_len < 8, ShortFileNameParseError::NameTooLong);
+
+            let encoded_character = Self::encode_character(encoder, character, name_len)?;
             ensure!(
-                index != 0 || encoded_character != 0x20,
+                name_len != 0 || encoded_character != 0x20,
                 ShortFileNameParseError::NameStartsWithSpace
             );
 
-            bytes[index] = encoded_character;
+            bytes[name_len as usize] = encoded_character;
             name_len += 1;
         }
 
-        for (index, character) in extension.chars().enumerate() {
+        ensure!(name_len != 0, ShortFileNameParseError::NameEmpty);
+
+        for (index, character) in characters.enumerate() {
             // Using index here instead of str.len() because this counts characters instead of bytes
             ensure!(index < 3, ShortFileNameParseError::ExtensionTooLong);
 
@@ -74,18 +91,205 @@ impl ShortFileName {
         Ok(Self { bytes })
     }
 
+    /// Parses the value only if the resulting short name represents it exactly, meaning no long name
+    /// is needed to preserve its casing or characters.
+    pub(crate) fn from_str_exact<CPE>(encoder: &CPE, value: &str) -> Option<Self>
+    where
+        CPE: CodePageEncoder,
+    {
+        Self::from_str_with_case(encoder, value)
+            .filter(|(_, case)| case.is_empty())
+            .map(|(short_name, _)| short_name)
+    }
+
+    /// Parses the value only if the resulting short name represents it exactly once displayed in
+    /// the returned case, so names such as `readme.txt` or `README.txt` need no long name.  Parts
+    /// mixing upper and lower case, such as `ReadMe`, can't be represented.
+    pub(crate) fn from_str_with_case<CPE>(
+        encoder: &CPE,
+        value: &str,
+    ) -> Option<(Self, ShortNameCase)>
+    where
+        CPE: CodePageEncoder,
+    {
+        let short_name = Self::from_str(encoder, value).ok()?;
+        let (name, extension) = match value.split_once(".") {
+            None => (value, ""),
+            Some((name, extension)) => (name, extension),
+        };
+
+        let part_case = |part: &str, bytes: &[u8], lower_case: ShortNameCase| {
+            let is_exact = part
+                .chars()
+                .map(|character| encoder.encode(character.to_ascii_uppercase()))
+                .eq(bytes.iter().map(|byte| Some(*byte)));
+            let is_lower_case = part.chars().any(|character| character.is_ascii_lowercase());
+            let is_upper_case = part.chars().any(|character| character.is_ascii_uppercase());
+
+            match (is_exact, is_lower_case, is_upper_case) {
+                (false, _, _) | (true, true, true) => None,
+                (true, true, false) => Some(lower_case),
+                (true, false, _) => Some(ShortNameCase::empty()),
+            }
+        };
+
+        if value.ends_with(".") {
+            return None;
+        }
+
+        let name_case = part_case(
+            name,
+            short_name.base_name(),
+            ShortNameCase::LowerCaseBaseName,
+        )?;
+        let extension_case = part_case(
+            extension,
+            short_name.extension(),
+            ShortNameCase::LowerCaseExtension,
+        )?;
+
+        Some((short_name, name_case | extension_case))
+    }
+
+    /// Generates the short name alias for a long name.  A `~N` numeric tail is included when
+    /// `tail_number` is non-zero, truncating the name portion as needed to fit it.
+    pub(crate) fn alias_for<CPE>(encoder: &CPE, long_name: &str, tail_number: u32) -> Self
+    where
+        CPE: CodePageEncoder,
+    {
+        let long_name = long_name.trim_start_matches(['.', ' ']);
+        let (name, extension) = match long_name.rsplit_once(".") {
+            None => (long_name, ""),
+            Some((name, extension)) => (name, extension),
+        };
+
+        let mut bytes = [0x20; SHORT_NAME_CHARACTER_COUNT];
+        let mut name_length = Self::write_alias_part(encoder, name, &mut bytes[0..8]);
+        Self::write_alias_part(encoder, extension, &mut bytes[8..]);
+
+        if name_length == 0 {
+            bytes[0] = b'_';
+            name_length = 1;
+        }
+
+        Self::write_tail(&mut bytes, name_length, tail_number);
+
+        Self { bytes }
+    }
+
+    /// Generates the short name alias used once the `alias_for` aliases of a long name are taken,
+    /// replacing all but the first two characters of the name portion with a hash of the long
+    /// name so long names sharing a prefix rarely share these aliases.
+    pub(crate) fn hashed_alias_for<CPE>(encoder: &CPE, long_name: &str, tail_number: u32) -> Self
+    where
+        CPE: CodePageEncoder,
+    {
+        let Self { mut bytes } = Self::alias_for(encoder, long_name, 0);
+        let prefix_length = Self::trim_padding(&bytes[0..8]).len().min(2);
+
+        let hash = long_name.chars().fold(0x811C_9DC5_u32, |hash, character| {
+            (hash ^ character as u32).wrapping_mul(0x0100_0193)
+        });
+        let hash = (hash ^ (hash >> 16)) as u16;
+
+        for (index, byte) in bytes[prefix_length..prefix_length + 4]
+            .iter_mut()
+            .enumerate()
+        {
+            *byte = b"0123456789ABCDEF"[usize::from((hash >> (12 - (index * 4))) & 0xF)];
+        }
+        bytes[prefix_length + 4..8].fill(0x20);
+
+        Self::write_tail(&mut bytes, prefix_length + 4, tail_number);
+
+        Self { bytes }
+    }
+
+    /// The number of the `~N` numeric tail ending the name portion, if it has one.
+    pub(crate) fn tail_number(&self) -> Option<u32> {
+        let base_name = self.base_name();
+        let tilde_index = base_name.iter().rposition(|byte| *byte == b'~')?;
+        let digits = &base_name[tilde_index + 1..];
+
+        if !digits
+            .first()
+            .is_some_and(|digit| matches!(digit, b'1'..=b'9'))
+        {
+            return None;
+        }
+
+        digits.iter().try_fold(0, |tail_number: u32, digit| {
+            digit
+                .is_ascii_digit()
+                .then(|| tail_number * 10 + u32::from(digit - b'0'))
+        })
+    }
+
     pub fn bytes(&self) -> &[u8; SHORT_NAME_CHARACTER_COUNT] {
         &self.bytes
     }
 
-    pub fn checksum(&self) -> u8 {
-        let mut checksum: u8 = 0;
+    /// The code page encoded name portion, without its trailing space padding.
+    pub fn base_name(&self) -> &[u8] {
+        Self::trim_padding(&self.bytes[0..8])
+    }
 
-        for character in self.bytes.iter() {
-            checksum = checksum.rotate_right(1).wrapping_add(*character);
-        }
+    /// The code page encoded extension portion, without its trailing space padding.
+    pub fn extension(&self) -> &[u8] {
+        Self::trim_padding(&self.bytes[8..])
+    }
 
-        checksum
+    /// The name as it's displayed, `NAME.EXT`, with bytes outside of ASCII replaced since they
+    /// can't be decoded without the code page.
+    pub(crate) fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.chars_with_case(ShortNameCase::empty())
+    }
+
+    /// The name as it's displayed by `chars`, with the parts recorded as lower case lowered.
+    pub(crate) fn chars_with_case(&self, case: ShortNameCase) -> impl Iterator<Item = char> + '_ {
+        let extension = self.extension();
+        let separator = (!extension.is_empty()).then_some(b'.');
+
+        let lowered = |is_lower_case: bool| {
+            move |byte: &u8| {
+                if is_lower_case {
+                    byte.to_ascii_lowercase()
+                } else {
+                    *byte
+                }
+            }
+        };
+
+        self.base_name()
+            .iter()
+            .map(lowered(case.contains(ShortNameCase::LowerCaseBaseName)))
+            .chain(separator)
+            .chain(
+                extension
+                    .iter()
+                    .map(lowered(case.contains(ShortNameCase::LowerCaseExtension))),
+            )
+            .map(|byte| {
+                if byte.is_ascii() {
+                    byte as char
+                } else {
+                    char::REPLACEMENT_CHARACTER
+                }
+            })
+    }
+
+    /// Whether this is the name of a directory's `.` entry, which refers to the directory itself.
+    pub fn is_dot(&self) -> bool {
+        self.bytes == DOT_NAME
+    }
+
+    /// Whether this is the name of a directory's `..` entry, which refers to its parent.
+    pub fn is_dotdot(&self) -> bool {
+        self.bytes == DOTDOT_NAME
+    }
+
+    pub fn checksum(&self) -> u8 {
+        crate::raw::short_name_checksum(&self.bytes)
     }
 
     fn encode_character<CPE>(
@@ -117,6 +321,62 @@ impl ShortFileName {
         Ok(encoded_character)
     }
 
+    /// Writes the characters of a long name part which are valid in a short name, replacing those
+    /// that aren't with underscores.  Spaces and periods are skipped.
+    fn write_alias_part<CPE>(encoder: &CPE, part: &str, output: &mut [u8]) -> usize
+    where
+        CPE: CodePageEncoder,
+    {
+        let characters = part
+            .chars()
+            .filter(|character| !matches!(character, ' ' | '.'));
+
+        let mut length = 0;
+        for (output_byte, character) in output.iter_mut().zip(characters) {
+            *output_byte = Self::encode_character(encoder, character, 0).unwrap_or(b'_');
+            length += 1;
+        }
+
+        length
+    }
+
+    /// Writes a `~N` numeric tail following the name portion's first `name_length` characters,
+    /// truncating them as needed to fit it, unless `tail_number` is zero.
+    fn write_tail(
+        bytes: &mut [u8; SHORT_NAME_CHARACTER_COUNT],
+        name_length: usize,
+        tail_number: u32,
+    ) {
+        if tail_number == 0 {
+            return;
+        }
+
+        let mut tail = [0; 8];
+        let mut tail_length = 0;
+        let mut remaining = tail_number;
+
+        while remaining > 0 && tail_length < 7 {
+            tail[7 - tail_length] = b'0' + (remaining % 10) as u8;
+            remaining /= 10;
+            tail_length += 1;
+        }
+
+        tail[7 - tail_length] = b'~';
+        tail_length += 1;
+
+        let tail_start = name_length.min(8 - tail_length);
+        bytes[tail_start..tail_start + tail_length].copy_from_slice(&tail[8 - tail_length..]);
+    }
+
+    fn trim_padding(bytes: &[u8]) -> &[u8] {
+        let length = bytes
+            .iter()
+            .rposition(|byte| *byte != 0x20)
+            .map_or(0, |index| index + 1);
+
+        &bytes[..length]
+    }
+
     fn is_valid_character(character: char) -> bool {
         !matches!(character, '\0'..='\x1F' | '"' | '*'..=',' | '.' | '/' | ':'..='?' | '['..=']' | '|')
     }
@@ -134,6 +394,173 @@ mod tests {
     use crate::mock::ScriptedCodePageEncoder;
     use alloc::string::String;
 
+    mod from_str_exact {
+        use super::*;
+
+        #[test]
+        fn exact_values_returned() {
+            for value in ["FOO.BAR", "FOO", "PRETTYBG.BIG", "A~1.TXT"] {
+                assert!(
+                    ShortFileName::from_str_exact(&AsciiOnlyEncoder, value).is_some(),
+                    "{value} should be represented exactly"
+                );
+            }
+        }
+
+        #[test]
+        fn inexact_values_return_none() {
+            for value in [
+                "foo.bar",
+                "Foo",
+                "FOO.",
+                "LONGER NAME.TXT",
+                "FOO.TEXT",
+                "FOO+.TXT",
+            ] {
+                assert!(
+                    ShortFileName::from_str_exact(&AsciiOnlyEncoder, value).is_none(),
+                    "{value} should not be represented exactly"
+                );
+            }
+        }
+    }
+
+    mod from_str_with_case {
+        use super::*;
+
+        #[test]
+        fn uniformly_cased_parts_represented() {
+            let test_values = [
+                ("FOO.BAR", ShortNameCase::empty()),
+                ("foo.bar", ShortNameCase::all()),
+                ("foo.BAR", ShortNameCase::LowerCaseBaseName),
+                ("FOO.bar", ShortNameCase::LowerCaseExtension),
+                ("log_1", ShortNameCase::LowerCaseBaseName),
+                ("a~1.txt", ShortNameCase::all()),
+            ];
+
+            for (value, expected_case) in test_values {
+                let (short_name, case) =
+                    ShortFileName::from_str_with_case(&AsciiOnlyEncoder, value)
+                        .unwrap_or_else(|| panic!("{value} should be represented"));
+
+                assert_eq!(case, expected_case, "{value} should have the expected case");
+                assert!(
+                    short_name.chars_with_case(case).eq(value.chars()),
+                    "{value} should be displayed unchanged"
+                );
+            }
+        }
+
+        #[test]
+        fn mixed_case_parts_return_none() {
+            for value in ["Foo.txt", "foo.Txt", "foo.", "longer name.txt"] {
+                assert!(
+                    ShortFileName::from_str_with_case(&AsciiOnlyEncoder, value).is_none(),
+                    "{value} should not be represented"
+                );
+            }
+        }
+    }
+
+    mod alias_for {
+        use super::*;
+
+        #[test]
+        fn values_converted_correctly() {
+            #[rustfmt::skip]
+            let test_values = [
+                ("The quick brown.fox",         0,      "THEQUICKFOX".as_bytes()),
+                ("a rather long file name.txt", 1,      "ARATHE~1TXT".as_bytes()),
+                ("foo.bar",                     12,     "FOO~12  BAR".as_bytes()),
+                (".bashrc",                     1,      "BASHRC~1   ".as_bytes()),
+                ("a+b,c.tar.gz",                1,      "A_B_CT~1GZ ".as_bytes()),
+                ("...",                         1,      "_~1        ".as_bytes()),
+                ("foo",                         999999, "F~999999   ".as_bytes()),
+            ];
+
+            for (long_name, tail_number, expected) in test_values {
+                let short_name =
+                    ShortFileName::alias_for(&AsciiOnlyEncoder, long_name, tail_number);
+
+                assert_eq!(
+                    short_name.bytes(),
+                    expected,
+                    "{long_name} with tail {tail_number} should convert correctly"
+                );
+            }
+        }
+    }
+
+    mod hashed_alias_for {
+        use super::*;
+
+        #[test]
+        fn values_converted_correctly() {
+            #[rustfmt::skip]
+            let test_values = [
+                ("a rather long file name.txt", 1, "ARFF5F~1TXT".as_bytes()),
+                ("a rather long file name.md",  2, "AR826B~2MD ".as_bytes()),
+                ("...",                         1, "_16DA~1    ".as_bytes()),
+            ];
+
+            for (long_name, tail_number, expected) in test_values {
+                let short_name =
+                    ShortFileName::hashed_alias_for(&AsciiOnlyEncoder, long_name, tail_number);
+
+                assert_eq!(
+                    short_name.bytes(),
+                    expected,
+                    "{long_name} with tail {tail_number} should convert correctly"
+                );
+            }
+        }
+
+        #[test]
+        fn long_names_sharing_prefix_hashed_differently() {
+            let short_name =
+                ShortFileName::hashed_alias_for(&AsciiOnlyEncoder, "a rather long file.txt", 1);
+            let other_short_name =
+                ShortFileName::hashed_alias_for(&AsciiOnlyEncoder, "a rather long name.txt", 1);
+
+            assert_ne!(short_name, other_short_name);
+        }
+    }
+
+    mod tail_number {
+        use super::*;
+
+        #[test]
+        fn values_parsed_correctly() {
+            #[rustfmt::skip]
+            let test_values = [
+                ("ARATHE~1TXT", Some(1)),
+                ("FOO~12  BAR", Some(12)),
+                ("F~999999   ", Some(999_999)),
+                ("FOO     BAR", None),
+                ("FOO~    BAR", None),
+                ("FOO~01  BAR", None),
+                ("FOO~1A  BAR", None),
+            ];
+
+            for (bytes, expected) in test_values {
+                let short_name = ShortFileName::new(
+                    bytes
+                        .as_bytes()
+                        .try_into()
+                        .expect("Name should be 11 bytes"),
+                )
+                .expect("Ok should be returned");
+
+                assert_eq!(
+                    short_name.tail_number(),
+                    expected,
+                    "{bytes} should parse correctly"
+                );
+            }
+        }
+    }
+
     mod from_str {
         use super::*;
 
@@ -166,6 +593,15 @@ mod tests {
             }
         }
 
+        #[test]
+        #[cfg(feature = "code-page-437")]
+        fn code_page_characters_uppercased() {
+            let result = ShortFileName::from_str(&crate::Cp437Encoder, "café.à")
+                .expect("Parsing should succeed");
+
+            assert_eq!(*result.bytes(), *b"CAF\x90    \x85  ");
+        }
+
         #[test]
         fn valid_characters_allowed() {
             for byte_value in 0..=0xFF {
@@ -442,6 +878,80 @@ mod tests {
         }
     }
 
+    mod base_name {
+        use super::*;
+
+        #[test]
+        fn padding_removed() {
+            let short_file_name = ShortFileName::from_str(&AsciiOnlyEncoder, "foo.ba")
+                .expect("Parsing should succeed");
+
+            assert_eq!(short_file_name.base_name(), b"FOO");
+        }
+    }
+
+    mod extension {
+        use super::*;
+
+        #[test]
+        fn padding_removed() {
+            let short_file_name = ShortFileName::from_str(&AsciiOnlyEncoder, "foo.ba")
+                .expect("Parsing should succeed");
+
+            assert_eq!(short_file_name.extension(), b"BA");
+        }
+
+        #[test]
+        fn missing_extension_returns_empty() {
+            let short_file_name =
+                ShortFileName::from_str(&AsciiOnlyEncoder, "foo").expect("Parsing should succeed");
+
+            assert_eq!(short_file_name.extension(), b"");
+        }
+    }
+
+    mod is_dot {
+        use super::*;
+
+        #[test]
+        fn dot_name_matches() {
+            let short_file_name = ShortFileName::new(*b".          ").unwrap();
+
+            assert!(short_file_name.is_dot());
+            assert!(!short_file_name.is_dotdot());
+        }
+
+        #[test]
+        fn other_names_do_not_match() {
+            for name in [b"..         ", b"FOO        ", b".FOO       "] {
+                let short_file_name = ShortFileName::new(*name).unwrap();
+
+                assert!(!short_file_name.is_dot());
+            }
+        }
+    }
+
+    mod is_dotdot {
+        use super::*;
+
+        #[test]
+        fn dotdot_name_matches() {
+            let short_file_name = ShortFileName::new(*b"..         ").unwrap();
+
+            assert!(short_file_name.is_dotdot());
+            assert!(!short_file_name.is_dot());
+        }
+
+        #[test]
+        fn other_names_do_not_match() {
+            for name in [b".          ", b"FOO        ", b"...        "] {
+                let short_file_name = ShortFileName::new(*name).unwrap();
+
+                assert!(!short_file_name.is_dotdot());
+            }
+        }
+    }
+
     mod checksum {
         use super::*;
 
@@ -1,108 +1,632 @@
 mod builder;
+mod canonicalize_error;
+mod conversion_error;
+mod copy_error;
+mod directory_quota;
 mod error;
+mod find;
+mod fixed_file_policy;
+mod hash_error;
+mod health_summary;
+mod invalid_directory_entry_handler;
+mod item_path;
+mod manifest_error;
+mod manifest_mismatch;
+mod metadata_error;
+mod mutation_event;
+mod name_conflict;
+mod operation_error;
+mod root_directory_location;
+mod search_error;
+mod suspended_file_system;
+mod tree_line;
+mod walk;
 
 pub use builder::*;
+pub use canonicalize_error::*;
+pub use conversion_error::*;
+pub use copy_error::*;
 use core::error::Error;
+pub use directory_quota::*;
 pub use error::*;
+pub use find::*;
+pub use fixed_file_policy::*;
+pub use hash_error::*;
+pub use health_summary::*;
+pub use invalid_directory_entry_handler::*;
+pub use manifest_error::*;
+pub use manifest_mismatch::*;
+pub use metadata_error::*;
+pub use mutation_event::*;
+pub use name_conflict::*;
+pub use operation_error::*;
+pub use search_error::*;
+pub use suspended_file_system::*;
 
 use crate::Device;
-use crate::allocation_table::AllocationTable;
-use crate::boot_sector::BiosParameterBlock;
-use crate::directory::{Directory, DirectoryFile, DirectoryTable};
-use crate::directory_item::{DeviceDirectoryItemIterationError, DirectoryItem};
-use crate::{AllocationTableKind, CodePageEncoder, File};
+use crate::allocation_table::{
+    AllocationTable, AllocationTableEntry, AllocationTableStatistics, RESERVED_ENTRIES_SIZE,
+    ReservedEntries,
+};
+use crate::boot_sector::{BiosParameterBlock, BootSector, FsInfo, fs_info_sector};
+use crate::directory::{Directory, DirectoryFile};
+use crate::directory_entry::{
+    DEFAULT_BUFFER_SIZE, DIRECTORY_ENTRY_SIZE, DirectoryEntry, DirectoryEntryAttributes,
+    DirectoryEntryPosition, LONG_NAME_MAX_ENTRY_COUNT, MAX_FILE_SIZE, ShortNameCase,
+    ShortNameDirectoryEntry,
+};
+use crate::directory_item::{
+    DeviceDirectoryItemIterationError, DirectoryItem, DirectoryItemEntries, DirectoryItemFilter,
+    EntryLocation, NamePattern, NameQuery,
+};
+use crate::file::OpenOptions;
+use crate::file_name::LONG_NAME_MAX_LENGTH;
+use crate::hash::Hasher;
+use crate::path_cache::MAX_CACHED_PATH_LENGTH;
+use crate::units::{ByteAddress, ClusterNumber};
+use crate::utils::{read_le_u16, write_le_u32};
+use crate::yield_budget::YieldBudget;
+use crate::{
+    AllocationTableKind, AsciiOnlyEncoder, CodePageEncoder, Crc32, Dir, File, FileError,
+    InvalidTimestampPolicy, LongFileName, LongFileNameError, PathCache, PathCacheEntry, PinnedFile,
+    PrefetchingStream, ShortFileName, SingleAccessDevice, SingleAccessDeviceError, SliceStream,
+    SliceStreamError, VolumeLabel,
+};
+use core::cell::{Cell, RefCell};
+use core::cmp::{Ordering, min};
+use core::ops::{ControlFlow, Range, RangeInclusive};
+use core::str::FromStr;
 use embedded_io::{ErrorType, SeekFrom};
+use item_path::ItemPath;
+use root_directory_location::RootDirectoryLocation;
+use tree_line::write_tree_line;
+use walk::Walk;
+
+#[cfg(any(feature = "fat12", feature = "fat16"))]
+use crate::directory::DirectoryTable;
+
+#[cfg(feature = "alloc")]
+use {
+    crate::{Inventory, InventoryItem},
+    alloc::string::String,
+};
 
 #[cfg(feature = "sync")]
 use {
-    crate::SyncDevice,
-    embedded_io::{Read, Seek, Write},
+    crate::{FileWriteGuard, SyncDevice, SyncFlushableDevice, TempFile},
+    embedded_io::{Read, ReadExactError, Seek, Write},
 };
 
 #[cfg(feature = "async")]
 use {
-    crate::AsyncDevice,
+    crate::{AsyncDevice, AsyncFileWriteGuard, AsyncFlushableDevice},
     embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek, Write as AsyncWrite},
 };
 
+const MAX_ITEM_ENTRY_COUNT: usize = LONG_NAME_MAX_ENTRY_COUNT as usize + 1;
+// Each UCS-2 character of a long name encodes to at most three UTF-8 bytes
+const MAX_NAME_BYTE_LENGTH: usize = LONG_NAME_MAX_LENGTH * 3;
+/// The number of `~N` aliases of a long name tried before falling back to hashed aliases.
+const MAX_SHORT_NAME_TAIL_NUMBER: u32 = 256;
+const MAX_HASHED_SHORT_NAME_TAIL_NUMBER: u32 = 9;
+const DELETED_ENTRY_MARKER: u8 = 0xE5;
+const TEMPORARY_FILE_NAME: &str = "~replace.tmp";
+const STAGED_FILE_NAME: &str = "~staged.tmp";
+const DEFAULT_YIELD_INTERVAL: u32 = 32;
+/// The boot sector and the FS information sector.
+const FAT32_MINIMUM_RESERVED_SECTOR_COUNT: u64 = 2;
+
 #[derive(Clone, Debug)]
-pub struct FileSystem<D, CPE, IDE>
-where
+pub struct FileSystem<
+    D,
+    CPE,
+    IDE = IgnoreInvalidEntries,
+    ME = fn(MutationEvent<'_>),
+    const BUFFER_SIZE: usize = DEFAULT_BUFFER_SIZE,
+> where
     D: Device,
     CPE: CodePageEncoder,
-    IDE: Fn(DeviceDirectoryItemIterationError<D>),
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
 {
     device: D,
     code_page_encoder: CPE,
 
     allocation_table: AllocationTable,
     bios_parameter_block: BiosParameterBlock,
+    root_directory_location: RootDirectoryLocation,
+    data_region_base_address: ByteAddress,
 
-    on_invalid_directory_entry: IDE,
+    on_invalid_directory_entry: RefCell<IDE>,
+    on_mutation: ME,
+    quota_policy: Option<QuotaPolicy>,
+    fixed_file_policy: Option<FixedFilePolicy>,
+    use_short_name_case: bool,
+    utc_offset_minutes: i16,
+    invalid_timestamp_policy: InvalidTimestampPolicy,
+    health_summary: Option<HealthSummary>,
+
+    yield_interval: u32,
+    is_remounted: bool,
+    is_media_changed: Cell<bool>,
+    directory_generation: Cell<u32>,
 }
 
-impl<D, CPE, IDE> FileSystem<D, CPE, IDE>
+impl<D, CPE, IDE, ME, const BUFFER_SIZE: usize> FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>
 where
     D: Device,
     CPE: CodePageEncoder,
-    IDE: Fn(DeviceDirectoryItemIterationError<D>),
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
 {
+    /// Assembles a file system over the volume described by the already validated boot sector.
+    #[allow(clippy::too_many_arguments)]
+    fn from_parts(
+        device: D,
+        code_page_encoder: CPE,
+        on_invalid_directory_entry: IDE,
+        on_mutation: ME,
+        quota_policy: Option<QuotaPolicy>,
+        fixed_file_policy: Option<FixedFilePolicy>,
+        use_short_name_case: bool,
+        utc_offset_minutes: i16,
+        invalid_timestamp_policy: InvalidTimestampPolicy,
+        reserved_cluster_ranges: &'static [RangeInclusive<u32>],
+        bios_parameter_block: BiosParameterBlock,
+        yield_interval: u32,
+        is_remounted: bool,
+    ) -> Self {
+        let allocation_table = Self::allocation_table_for(&bios_parameter_block)
+            .with_reserved_clusters(reserved_cluster_ranges);
+        let root_directory_location = RootDirectoryLocation::resolve(&bios_parameter_block);
+        let data_region_base_address = bios_parameter_block.data_region_base_address();
+
+        Self {
+            device,
+            code_page_encoder,
+
+            allocation_table,
+            bios_parameter_block,
+            root_directory_location,
+            data_region_base_address,
+
+            on_invalid_directory_entry: RefCell::new(on_invalid_directory_entry),
+            on_mutation,
+            quota_policy,
+            fixed_file_policy,
+            use_short_name_case,
+            utc_offset_minutes,
+            invalid_timestamp_policy,
+            health_summary: None,
+
+            yield_interval,
+            is_remounted,
+            is_media_changed: Cell::new(false),
+            directory_generation: Cell::new(0),
+        }
+    }
+
+    /// Takes the file system apart so it can be rebuilt by `SuspendedFileSystem::resume`.
+    fn into_suspended(self) -> SuspendedFileSystem<D, CPE, IDE, ME, BUFFER_SIZE> {
+        SuspendedFileSystem::new(
+            self.device,
+            self.code_page_encoder,
+            self.on_invalid_directory_entry.into_inner(),
+            self.on_mutation,
+            self.quota_policy,
+            self.fixed_file_policy,
+            self.use_short_name_case,
+            self.utc_offset_minutes,
+            self.invalid_timestamp_policy,
+            self.allocation_table.reserved_cluster_ranges(),
+            self.yield_interval,
+        )
+    }
+
+    /// Whether the volume was mounted again by `SuspendedFileSystem::resume`, in which case
+    /// anything derived from it before suspending, such as a `PathCache` or `DirSnapshot`, may no
+    /// longer match the volume.
+    pub fn is_remounted(&self) -> bool {
+        self.is_remounted
+    }
+
+    /// Whether `revalidate` found the medium was changed, after which files opened from the file
+    /// system fail with `FileError::MediaChanged`.
+    pub fn is_media_changed(&self) -> bool {
+        self.is_media_changed.get()
+    }
+
+    /// The outcome of the sanity pass run at mount when enabled by
+    /// `FileSystemBuilder::with_self_test`, `None` if it wasn't run or the volume was mounted again
+    /// by `SuspendedFileSystem::resume` since.
+    pub fn health_summary(&self) -> Option<HealthSummary> {
+        self.health_summary
+    }
+
+    /// The offset of the volume's local time from UTC set by `FileSystemBuilder::with_utc_offset`,
+    /// in minutes.
+    pub fn utc_offset_minutes(&self) -> i16 {
+        self.utc_offset_minutes
+    }
+
+    /// How invalid directory entry timestamps are read, set by
+    /// `FileSystemBuilder::with_invalid_timestamp_policy`.
+    pub fn invalid_timestamp_policy(&self) -> InvalidTimestampPolicy {
+        self.invalid_timestamp_policy
+    }
+
     /// The type of FAT filesystem the loaded instance is
     pub fn allocation_table_kind(&self) -> AllocationTableKind {
         self.allocation_table.kind()
     }
 
-    fn root_directory(&self) -> Directory<'_, D> {
-        match self
+    /// The jump instruction at the start of the boot sector.
+    pub fn jump_code(&self) -> [u8; 3] {
+        self.bios_parameter_block.jump_code()
+    }
+
+    /// The space padded name of the system which formatted the volume, such as `MSWIN4.1`.
+    pub fn oem_name(&self) -> [u8; 8] {
+        self.bios_parameter_block.oem_name()
+    }
+
+    /// The serial number given to the volume when it was formatted, `None` if the boot sector
+    /// doesn't record one.
+    pub fn volume_serial_number(&self) -> Option<u32> {
+        self.bios_parameter_block.volume_serial_number()
+    }
+
+    /// The media descriptor recorded in the boot sector, `0xF8` for fixed media and `0xF0` for
+    /// most removable media.
+    pub fn media_type(&self) -> u8 {
+        self.bios_parameter_block.media_type()
+    }
+
+    /// The sectors per track recorded in the boot sector, as reported to hosts which still
+    /// address the volume by cylinder, head and sector.
+    pub fn sectors_per_track(&self) -> u16 {
+        self.bios_parameter_block.sectors_per_track()
+    }
+
+    /// The head count recorded in the boot sector, see `sectors_per_track`.
+    pub fn head_count(&self) -> u16 {
+        self.bios_parameter_block.head_count()
+    }
+
+    /// The number of sectors preceding the volume on its medium as recorded in the boot sector.
+    pub fn hidden_sector_count(&self) -> u32 {
+        self.bios_parameter_block.hidden_sector_count()
+    }
+
+    /// The number of copies of the allocation table the volume keeps, see
+    /// `allocation_table_statistics`.
+    pub fn allocation_table_count(&self) -> u8 {
+        self.bios_parameter_block.allocation_table_count()
+    }
+
+    /// The number of bytes `backup_metadata` writes: the reserved sectors, holding the boot sector
+    /// and on FAT32 volumes the FS information sector, followed by every copy of the allocation
+    /// table.
+    pub fn metadata_size(&self) -> u64 {
+        self.bios_parameter_block
+            .directory_table_base_address()
+            .value()
+    }
+
+    /// The byte ranges of the volume the file system never reads or writes, where firmware may
+    /// keep data of its own without corrupting the volume.  Empty ranges are left out.
+    ///
+    /// These are the reserved sectors following the boot record, and on FAT32 volumes the FS
+    /// information sector and backup boot record, along with the sectors following the last
+    /// cluster, which are too few to form a cluster of their own.  Formatting the volume again
+    /// or resizing it may overwrite either region.
+    pub fn unused_regions(&self) -> impl Iterator<Item = Range<u64>> {
+        let reserved_sectors = self
             .bios_parameter_block
-            .root_directory_file_cluster_number()
-        {
-            Some(root_directory_file_cluster_number) => DirectoryFile::new(
-                &self.device,
-                &self.allocation_table,
-                self.bios_parameter_block.data_region_base_address(),
-                self.bios_parameter_block.bytes_per_cluster(),
-                root_directory_file_cluster_number,
-            )
-            .into(),
-            None => DirectoryTable::new(
+            .unused_reserved_sectors_base_address()
+            .value()
+            ..self
+                .bios_parameter_block
+                .allocation_table_base_address()
+                .value();
+        let trailing_sectors = self.bios_parameter_block.data_region_end_address().value()
+            ..self.bios_parameter_block.volume_end_address().value();
+
+        [reserved_sectors, trailing_sectors]
+            .into_iter()
+            .filter(|region| !region.is_empty())
+    }
+
+    /// The number of bytes each cluster of the data region holds.
+    #[cfg(feature = "dangerous")]
+    pub fn bytes_per_cluster(&self) -> u32 {
+        self.bios_parameter_block.bytes_per_cluster()
+    }
+
+    /// The highest cluster number of the data region, which starts at cluster 2.
+    #[cfg(feature = "dangerous")]
+    pub fn last_cluster_number(&self) -> u32 {
+        self.bios_parameter_block.last_cluster_number().value()
+    }
+
+    /// Returns the address of the cluster after checking that it lies within the data region and
+    /// that the buffer holds exactly one cluster.
+    #[cfg(feature = "dangerous")]
+    fn raw_cluster_address(
+        &self,
+        cluster_number: u32,
+        buffer_length: usize,
+    ) -> Result<ByteAddress, DeviceOperationError<D>> {
+        ensure!(
+            (2..=self.last_cluster_number()).contains(&cluster_number),
+            OperationError::ClusterNumberInvalid { cluster_number }
+        );
+
+        let bytes_per_cluster = self.bytes_per_cluster() as usize;
+        ensure!(
+            buffer_length == bytes_per_cluster,
+            OperationError::BufferSizeInvalid {
+                expected: bytes_per_cluster
+            }
+        );
+
+        self.cluster_address(ClusterNumber::new(cluster_number))
+    }
+
+    /// Returns the items of the volume the filter accepts, searching every directory up to
+    /// `MAX_DEPTH` levels of them with the root as the first.  Items are read as the iterator
+    /// advances, so no results are collected up front.
+    pub fn find<'a, const MAX_DEPTH: usize>(
+        &'a self,
+        filter: DirectoryItemFilter<'a>,
+    ) -> Find<'a, D, CPE, IDE, ME, MAX_DEPTH, BUFFER_SIZE> {
+        Find::new(
+            self,
+            Walk::new(self.root_directory(), self.yield_interval),
+            filter,
+        )
+    }
+
+    /// Opens a file pinned by `pin`, seeking through its indexed clusters without reading the
+    /// allocation table.
+    pub fn open_pinned<'a, const N: usize>(
+        &'a self,
+        pinned_file: &'a PinnedFile<N>,
+    ) -> File<'a, D> {
+        self.file_at(
+            ClusterNumber::new(pinned_file.first_cluster_number()),
+            pinned_file.size(),
+        )
+        .with_indexed_clusters(pinned_file.indexed_clusters())
+    }
+
+    fn root_directory(&self) -> Directory<'_, D, BUFFER_SIZE> {
+        match self.root_directory_location {
+            #[cfg(any(feature = "fat12", feature = "fat16"))]
+            RootDirectoryLocation::Table {
+                start_address,
+                entry_count,
+            } => DirectoryTable::new(
                 &self.device,
-                self.bios_parameter_block.directory_table_base_address(),
-                self.bios_parameter_block.directory_table_entry_count(),
+                start_address,
+                entry_count,
+                &self.directory_generation,
             )
+            .with_utc_offset(self.utc_offset_minutes)
+            .with_invalid_timestamp_policy(self.invalid_timestamp_policy)
             .into(),
+            RootDirectoryLocation::File {
+                start_cluster_number,
+            } => self.directory_file_at(start_cluster_number),
         }
     }
 
-    fn directory_for(&'_ self, item: &DirectoryItem) -> Option<DirectoryFile<'_, D>> {
-        if item.is_directory() {
-            Some(DirectoryFile::new(
-                &self.device,
-                &self.allocation_table,
-                self.bios_parameter_block.data_region_base_address(),
-                self.bios_parameter_block.bytes_per_cluster(),
-                item.first_cluster_number(),
+    fn directory_file_at(
+        &self,
+        start_cluster_number: ClusterNumber,
+    ) -> Directory<'_, D, BUFFER_SIZE> {
+        DirectoryFile::new(
+            &self.device,
+            &self.allocation_table,
+            self.data_region_base_address,
+            self.bios_parameter_block.bytes_per_cluster(),
+            start_cluster_number,
+            &self.directory_generation,
+        )
+        .with_utc_offset(self.utc_offset_minutes)
+        .with_invalid_timestamp_policy(self.invalid_timestamp_policy)
+        .into()
+    }
+
+    /// Whether a record of the manifest lists the path, ignoring ASCII case.
+    fn is_listed<'m, M>(manifest: M, path: &str) -> bool
+    where
+        M: IntoIterator<Item = (&'m str, u64, u32)>,
+    {
+        manifest
+            .into_iter()
+            .any(|(listed_path, _, _)| listed_path.eq_ignore_ascii_case(path))
+    }
+
+    fn directory_for(&'_ self, item: &DirectoryItem) -> Option<Directory<'_, D, BUFFER_SIZE>> {
+        if !item.is_directory() {
+            return None;
+        }
+
+        // `..` entries which refer to the root directory use cluster 0 regardless of FAT type
+        if item.first_cluster_number() == 0 {
+            return Some(self.root_directory());
+        }
+
+        Some(self.directory_file_at(ClusterNumber::new(item.first_cluster_number())))
+    }
+
+    /// The directory whose entries include the given position, positions within a directory file
+    /// are resolved against a directory starting at their cluster.
+    fn directory_containing(
+        &self,
+        position: DirectoryEntryPosition,
+    ) -> Directory<'_, D, BUFFER_SIZE> {
+        match position {
+            #[cfg(any(feature = "fat12", feature = "fat16"))]
+            DirectoryEntryPosition::Table { .. } => self.root_directory(),
+            DirectoryEntryPosition::File { cluster_number, .. } => {
+                self.directory_file_at(ClusterNumber::new(cluster_number))
+            }
+        }
+    }
+
+    fn file_for(&'_ self, item: &DirectoryItem) -> Option<File<'_, D>> {
+        if item.is_file() {
+            Some(self.file_at(
+                ClusterNumber::new(item.first_cluster_number()),
+                item.extended_file_size(),
             ))
         } else {
             None
         }
     }
 
-    fn file_for(&'_ self, item: &DirectoryItem) -> Option<File<'_, D>> {
-        if item.is_file() {
-            Some(File::new(
-                &self.device,
-                &self.allocation_table,
-                self.bios_parameter_block.data_region_base_address(),
-                self.bios_parameter_block.bytes_per_cluster(),
-                item.first_cluster_number(),
-                item.file_size(),
+    fn file_for_cached(&'_ self, entry: &PathCacheEntry) -> Option<File<'_, D>> {
+        if entry.is_file() {
+            Some(self.file_at(
+                ClusterNumber::new(entry.first_cluster_number()),
+                entry.file_size(),
             ))
         } else {
             None
         }
     }
 
+    fn file_at(&'_ self, first_cluster_number: ClusterNumber, file_size: u64) -> File<'_, D> {
+        File::new(
+            &self.device,
+            &self.allocation_table,
+            self.data_region_base_address,
+            self.bios_parameter_block.bytes_per_cluster(),
+            self.bios_parameter_block.last_cluster_number(),
+            first_cluster_number,
+            file_size,
+        )
+        .with_media_changed_flag(&self.is_media_changed)
+    }
+
+    /// Marks the medium as changed unless the boot sector read again still has a valid signature
+    /// and the volume serial number the file system was mounted with.
+    fn check_boot_sector_unchanged(
+        &self,
+        boot_sector_bytes: &[u8; 512],
+    ) -> Result<(), DeviceOperationError<D>> {
+        let is_signature_valid = boot_sector_bytes[510] == 0x55 && boot_sector_bytes[511] == 0xAA;
+        let is_unchanged = is_signature_valid
+            && BiosParameterBlock::from_boot_sector(boot_sector_bytes).is_ok_and(
+                |bios_parameter_block| {
+                    bios_parameter_block.volume_serial_number()
+                        == self.bios_parameter_block.volume_serial_number()
+                },
+            );
+
+        if !is_unchanged {
+            self.is_media_changed.set(true);
+        }
+
+        ensure!(is_unchanged, OperationError::MediaChanged);
+
+        Ok(())
+    }
+
+    fn allocation_table_for(bios_parameter_block: &BiosParameterBlock) -> AllocationTable {
+        // Every copy is kept up to date, which is also valid when only one copy is active
+        AllocationTable::new(
+            bios_parameter_block.allocation_table_kind(),
+            bios_parameter_block.allocation_table_base_address(),
+        )
+        .with_mirrors(
+            bios_parameter_block
+                .allocation_table_count()
+                .saturating_sub(1),
+            bios_parameter_block.bytes_per_allocation_table(),
+        )
+    }
+
+    /// The address of the copy of the allocation table at the given index.
+    fn allocation_table_copy_address(
+        bios_parameter_block: &BiosParameterBlock,
+        table_index: u8,
+    ) -> u64 {
+        bios_parameter_block.allocation_table_base_address().value()
+            + u64::from(table_index) * bios_parameter_block.bytes_per_allocation_table()
+    }
+
+    /// The FAT32 entry standing for the FAT16 entry at the given index, with the reserved entries
+    /// marking the volume as cleanly unmounted and free of disk errors.
+    #[cfg(all(feature = "fat16", feature = "fat32"))]
+    fn fat32_entry_for(entry_index: u64, entry: u16, media_type: u8) -> u32 {
+        match (entry_index, entry) {
+            (0, _) => 0x0FFF_FF00 | u32::from(media_type),
+            (1, _) => 0x0FFF_FFFF,
+            // Reserved values, bad cluster and end of chain markers keep their low bits
+            (_, 0xFFF0..) => 0x0FFF_0000 | u32::from(entry),
+            _ => u32::from(entry),
+        }
+    }
+
+    /// Whether the item was written after the other item, or at the same time with a name ordering
+    /// after the other item's name ignoring ASCII case.
+    fn is_written_after(item: &DirectoryItem, other_item: &DirectoryItem) -> bool {
+        let timestamp = (item.last_write_date(), item.last_write_time());
+        let other_timestamp = (other_item.last_write_date(), other_item.last_write_time());
+
+        timestamp.cmp(&other_timestamp).then_with(|| {
+            item.name_chars()
+                .map(|character| character.to_ascii_uppercase())
+                .cmp(
+                    other_item
+                        .name_chars()
+                        .map(|character| character.to_ascii_uppercase()),
+                )
+        }) == Ordering::Greater
+    }
+
+    /// The number of clusters a file of the given size spans.
+    fn cluster_count_for(&self, file_size: u64) -> u64 {
+        file_size.div_ceil(self.bios_parameter_block.bytes_per_cluster() as u64)
+    }
+
+    /// Returns the address of the cluster, failing for cluster numbers below the data region such
+    /// as those read from a corrupt allocation table.
+    pub(crate) fn cluster_address(
+        &self,
+        cluster_number: ClusterNumber,
+    ) -> Result<ByteAddress, DeviceOperationError<D>> {
+        cluster_number
+            .byte_address(
+                self.data_region_base_address,
+                self.bios_parameter_block.bytes_per_cluster(),
+            )
+            .ok_or(OperationError::ClusterNumberInvalid {
+                cluster_number: cluster_number.value(),
+            })
+    }
+
+    /// Checks that the media descriptor repeated in the first allocation table entry matches the
+    /// boot sector, which fails on volumes left partly rewritten.
+    fn validate_allocation_table_media_type<DE, SE>(
+        bios_parameter_block: &BiosParameterBlock,
+        allocation_table_media_type: u8,
+    ) -> Result<(), FileSystemError<DE, SE>>
+    where
+        DE: Error,
+        SE: embedded_io::Error,
+    {
+        ensure!(
+            allocation_table_media_type == bios_parameter_block.media_type(),
+            FileSystemError::AllocationTableMediaTypeMismatch
+        );
+
+        Ok(())
+    }
+
     fn validate_boot_sector_signature<DE, SE>(
         boot_sector_bytes: &[u8; 512],
     ) -> Result<(), FileSystemError<DE, SE>>
@@ -117,169 +641,3284 @@ where
 
         Ok(())
     }
+
+    /// Passes the error to the invalid directory entry callback, returning whether the operation
+    /// reading the entry should go on.
+    ///
+    /// Errors raised while the callback is already running, such as by the callback searching the
+    /// file system itself, are skipped without being reported.
+    pub(crate) fn report_invalid_directory_entry(
+        &self,
+        error: DeviceDirectoryItemIterationError<D>,
+    ) -> ControlFlow<()> {
+        match self.on_invalid_directory_entry.try_borrow_mut() {
+            Ok(mut on_invalid_directory_entry) => on_invalid_directory_entry.handle(error),
+            Err(_) => ControlFlow::Continue(()),
+        }
+    }
+
+    pub(crate) fn notify_mutation(&self, event: MutationEvent<'_>) {
+        (self.on_mutation)(event)
+    }
+}
+
+type SuspendResult<D, CPE, IDE, ME, const BUFFER_SIZE: usize> = Result<
+    SuspendedFileSystem<D, CPE, IDE, ME, BUFFER_SIZE>,
+    (
+        FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>,
+        <D as Device>::Error,
+    ),
+>;
+
+type ConversionResult<D, CPE, IDE, ME, const BUFFER_SIZE: usize> = Result<
+    SuspendedFileSystem<D, CPE, IDE, ME, BUFFER_SIZE>,
+    (
+        FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>,
+        DeviceConversionError<D>,
+    ),
+>;
+
+type ImageDevice<'a> = SingleAccessDevice<SliceStream<'a>>;
+type ImageFileSystemResult<'a> = Result<
+    FileSystem<ImageDevice<'a>, AsciiOnlyEncoder>,
+    FileSystemError<SingleAccessDeviceError<SliceStreamError>, SliceStreamError>,
+>;
+
+impl<'a> FileSystem<ImageDevice<'a>, AsciiOnlyEncoder> {
+    /// Mounts a volume image held in memory using the default `FileSystemBuilder` options.
+    #[cfg(feature = "sync")]
+    pub fn from_image(image: &'a [u8]) -> ImageFileSystemResult<'a> {
+        FileSystemBuilder::from_stream(SliceStream::new(image)).build()
+    }
+
+    /// Mounts a volume image held in memory using the default `FileSystemBuilder` options.
+    #[cfg(feature = "async")]
+    pub async fn from_image_async(image: &'a [u8]) -> ImageFileSystemResult<'a> {
+        FileSystemBuilder::from_stream(SliceStream::new(image))
+            .build_async()
+            .await
+    }
 }
 
 #[cfg(feature = "sync")]
-impl<D, S, CPE, IDE> FileSystem<D, CPE, IDE>
+impl<D, S, CPE> FileSystem<D, CPE>
 where
     D: SyncDevice<Stream = S>,
     S: Read + Seek,
     CPE: CodePageEncoder,
-    IDE: Fn(DeviceDirectoryItemIterationError<D>),
 {
-    pub fn new(
-        mut device: D,
+    /// Mounts the volume on the device, skipping invalid directory entries and reporting no
+    /// mutations.
+    pub fn from_device(
+        device: D,
         code_page_encoder: CPE,
-        on_invalid_directory_entry: IDE,
     ) -> Result<Self, FileSystemError<D::Error, S::Error>> {
-        let mut boot_sector_bytes = [0; 512];
-
-        device
-            .with_stream(
-                |stream| -> Result<(), FileSystemError<D::Error, S::Error>> {
-                    stream.seek(SeekFrom::Start(0))?;
-
-                    stream.read_exact(&mut boot_sector_bytes)?;
-
-                    Ok(())
-                },
-            )
-            .map_err(FileSystemError::DeviceError)?;
-
-        Self::validate_boot_sector_signature(&boot_sector_bytes)?;
-
-        let bios_parameter_block = BiosParameterBlock::from_boot_sector(&boot_sector_bytes)?;
-        let allocation_table = AllocationTable::new(
-            bios_parameter_block.allocation_table_kind(),
-            bios_parameter_block.allocation_table_base_address(),
-        );
-
-        Ok(Self {
-            device,
-            code_page_encoder,
-
-            allocation_table,
-            bios_parameter_block,
-
-            on_invalid_directory_entry,
-        })
-    }
-
-    pub fn open(&self, file_path: &str) -> Option<File<'_, D>> {
-        self.file_for(&self.find_item(file_path)?)
+        Self::new(device, code_page_encoder, IgnoreInvalidEntries, |_| {})
     }
+}
 
-    fn find_item(&self, file_path: &str) -> Option<DirectoryItem> {
-        let mut current_directory = self.root_directory();
-        let mut file_path_part_iterator = file_path.split("/");
-        let mut file_path_part = file_path_part_iterator.next()?;
+/// The body of `find_parent_directory`, shared between the sync and async implementations.
+macro_rules! find_parent_directory_body {
+    ($io:ident, $self:ident, $path:ident) => {{
+        match $path.rsplit_once("/") {
+            None => Some(($self.root_directory(), "", $path)),
+            Some((directory_path, name)) => Some((
+                $self.directory_for(&$io!(
+                    $self.find_item(directory_path);
+                    $self.find_item_async(directory_path)
+                )?)?,
+                directory_path,
+                name,
+            )),
+        }
+    }};
+}
 
-        loop {
-            let iterator_directory = current_directory;
-            let mut item_iterator = iterator_directory.items();
-
-            loop {
-                let item = match item_iterator.next()? {
-                    Ok(item) => item,
-                    Err(error) => {
-                        (self.on_invalid_directory_entry)(error);
-                        continue;
-                    }
-                };
+/// The body of `directory_quota_usage`, shared between the sync and async implementations.
+macro_rules! directory_quota_usage_body {
+    ($io:ident, $self:ident, $directory:ident, $directory_path:ident) => {{
+        let quota = $self
+            .quota_policy
+            .and_then(|quota_policy| quota_policy($directory_path))?;
 
-                if item.is_match(&self.code_page_encoder, file_path_part) {
-                    file_path_part = match file_path_part_iterator.next() {
-                        Some(next_file_path_part) => next_file_path_part,
-                        None => return Some(item),
-                    };
+        let mut file_count = 0;
+        let mut byte_count = 0_u64;
+        let mut item_iterator = $directory.items();
 
-                    current_directory = self.directory_for(&item)?.into();
-                    break;
+        while let Some(item) = $io!(item_iterator.next(); item_iterator.next_async()) {
+            match item {
+                Ok(item) if item.is_file() => {
+                    file_count += 1;
+                    byte_count = byte_count.saturating_add(u64::from(item.file_size()));
+                }
+                Ok(_) => {}
+                Err(error) => {
+                    if $self.report_invalid_directory_entry(error).is_break() {
+                        break;
+                    }
                 }
             }
         }
-    }
+
+        Some(DirectoryQuotaUsage::new(quota, file_count, byte_count))
+    }};
 }
 
-#[cfg(feature = "async")]
-impl<D, S, CPE, IDE> FileSystem<D, CPE, IDE>
-where
-    D: AsyncDevice<Stream = S>,
-    S: AsyncRead + AsyncSeek,
-    CPE: CodePageEncoder,
-    IDE: Fn(DeviceDirectoryItemIterationError<D>),
-{
-    pub async fn new_async(
-        mut device: D,
-        code_page_encoder: CPE,
-        on_invalid_directory_entry: IDE,
-    ) -> Result<Self, FileSystemError<D::Error, S::Error>> {
+/// The body of `revalidate`, shared between the sync and async implementations.
+macro_rules! revalidate_body {
+    ($io:ident, $self:ident) => {{
+        ensure!(!$self.is_media_changed.get(), OperationError::MediaChanged);
+
         let mut boot_sector_bytes = [0; 512];
 
-        device
-            .with_stream(
-                async |stream| -> Result<(), FileSystemError<D::Error, S::Error>> {
-                    stream.seek(SeekFrom::Start(0)).await?;
+        $io!(with_stream(
+            $self.device,
+            |stream| -> Result<(), DeviceOperationError<D>> {
+                $io!(Seek::seek(stream, SeekFrom::Start(0)))?;
+                $io!(Read::read_exact(stream, &mut boot_sector_bytes))?;
 
-                    stream.read_exact(&mut boot_sector_bytes).await?;
+                Ok(())
+            }
+        ))
+        .map_err(OperationError::DeviceError)??;
 
-                    Ok(())
-                },
-            )
-            .await
-            .map_err(FileSystemError::DeviceError)?;
+        $self.check_boot_sector_unchanged(&boot_sector_bytes)
+    }};
+}
 
-        Self::validate_boot_sector_signature(&boot_sector_bytes)?;
+/// The body of `pin`, shared between the sync and async implementations.
+macro_rules! pin_body {
+    ($io:ident, $self:ident, $file_path:ident) => {{
+        let item = $io!($self.find_item($file_path); $self.find_item_async($file_path))
+            .ok_or(OperationError::NotFound)?;
+        ensure!(item.is_file(), OperationError::NotAFile);
 
-        let bios_parameter_block = BiosParameterBlock::from_boot_sector(&boot_sector_bytes)?;
-        let allocation_table = AllocationTable::new(
-            bios_parameter_block.allocation_table_kind(),
-            bios_parameter_block.allocation_table_base_address(),
+        let mut pinned_file = PinnedFile::new(
+            ClusterNumber::new(item.first_cluster_number()),
+            item.extended_file_size(),
         );
+        let cluster_count = $self.cluster_count_for(pinned_file.size());
 
-        Ok(Self {
-            device,
-            code_page_encoder,
+        if pinned_file.is_fully_indexed() {
+            return Ok(pinned_file);
+        }
 
-            allocation_table,
-            bios_parameter_block,
+        $io!(with_stream($self.device, |stream| -> Result<(), DeviceOperationError<D>> {
+            let mut yield_budget = YieldBudget::new($self.yield_interval);
+            let mut cluster_number = ClusterNumber::new(pinned_file.first_cluster_number());
 
-            on_invalid_directory_entry,
-        })
-    }
+            while pinned_file.push(cluster_number) {
+                if pinned_file.indexed_cluster_count() as u64 == cluster_count {
+                    pinned_file.mark_fully_indexed();
 
-    pub async fn open_async(&self, file_path: &str) -> Option<File<'_, D>> {
-        self.file_for(&self.find_item_async(file_path).await?)
-    }
+                    return Ok(());
+                }
 
-    async fn find_item_async(&self, file_path: &str) -> Option<DirectoryItem> {
-        let mut current_directory = self.root_directory();
-        let mut file_path_part_iterator = file_path.split("/");
-        let mut file_path_part = file_path_part_iterator.next()?;
+                cluster_number = match $io!(
+                    $self.allocation_table.read_entry(stream, cluster_number);
+                    $self.allocation_table.read_entry_async(stream, cluster_number)
+                )? {
+                    AllocationTableEntry::NextClusterNumber(next_cluster_number) => {
+                        next_cluster_number
+                    }
+                    // Chains shorter than the file's size are read as far as they go
+                    AllocationTableEntry::EndOfFile => {
+                        pinned_file.mark_fully_indexed();
 
-        loop {
-            let iterator_directory = current_directory;
-            let mut item_iterator = iterator_directory.items();
-
-            loop {
-                let item = match item_iterator.next_async().await? {
-                    Ok(item) => item,
-                    Err(error) => {
-                        (self.on_invalid_directory_entry)(error);
-                        continue;
+                        return Ok(());
+                    }
+                    AllocationTableEntry::Free
+                    | AllocationTableEntry::BadSector
+                    | AllocationTableEntry::Reserved => {
+                        return Err(OperationError::ClusterNumberInvalid {
+                            cluster_number: cluster_number.value(),
+                        });
                     }
                 };
 
-                if item.is_match(&self.code_page_encoder, file_path) {
-                    file_path_part = match file_path_part_iterator.next() {
-                        Some(next_file_path_part) => next_file_path_part,
-                        None => return Some(item),
-                    };
+                $io!(; yield_budget.tick());
+            }
+
+            Ok(())
+        }))
+        .map_err(OperationError::DeviceError)??;
+
+        Ok(pinned_file)
+    }};
+}
 
-                    current_directory = self.directory_for(&item)?.into();
-                    break;
+/// The body of `inventory`, shared between the sync and async implementations.
+macro_rules! inventory_body {
+    ($io:ident, $self:ident, $include_hashes:ident) => {{
+        let mut inventory = Inventory::new();
+        let mut walk =
+            Walk::<'_, D, MAX_DEPTH, BUFFER_SIZE>::new($self.root_directory(), $self.yield_interval);
+        let mut path = String::new();
+        let mut path_lengths = [0; MAX_DEPTH];
+
+        while let Some(result) = $io!(walk.next(); walk.next_async()) {
+            let item = match result {
+                Ok(item) => item,
+                Err(error) => {
+                    if $self.report_invalid_directory_entry(error).is_break() {
+                        break;
+                    }
+
+                    continue;
                 }
+            };
+
+            path.truncate(path_lengths[walk.depth() - 1]);
+            if !path.is_empty() {
+                path.push('/');
             }
+            path.extend(item.name_chars());
+
+            let crc32 = match $self.file_for(&item) {
+                Some(mut file) if $include_hashes => {
+                    let mut crc32 = Crc32::new();
+                    $io!(
+                        $self.hash_file(&mut file, &mut crc32);
+                        $self.hash_file_async(&mut file, &mut crc32)
+                    )
+                    .map_err(HashError::FileError)?;
+
+                    Some(crc32.finalize())
+                }
+                _ => None,
+            };
+
+            inventory.push(InventoryItem::new(path.clone(), &item, crc32));
+
+            if walk.can_descend()
+                && let Some(directory) = $self.directory_for(&item)
+            {
+                path_lengths[walk.depth()] = path.len();
+                walk.descend(directory);
+            }
+        }
+
+        Ok(inventory)
+    }};
+}
+
+/// The body of `dump_tree`, shared between the sync and async implementations.
+macro_rules! dump_tree_body {
+    ($io:ident, $self:ident, $writer:ident) => {{
+        let mut walk =
+            Walk::<'_, D, MAX_DEPTH, BUFFER_SIZE>::new($self.root_directory(), $self.yield_interval);
+
+        while let Some(result) = $io!(walk.next(); walk.next_async()) {
+            let item = match result {
+                Ok(item) => item,
+                Err(error) => {
+                    if $self.report_invalid_directory_entry(error).is_break() {
+                        return Ok(());
+                    }
+
+                    continue;
+                }
+            };
+
+            write_tree_line($writer, walk.depth() - 1, &item)?;
+
+            if walk.can_descend()
+                && let Some(directory) = $self.directory_for(&item)
+            {
+                walk.descend(directory);
+            }
+        }
+
+        Ok(())
+    }};
+}
+
+/// The body of `find_all`, shared between the sync and async implementations.
+macro_rules! find_all_body {
+    ($io:ident, $self:ident, $path_buffer:ident, $predicate:ident, $on_match:ident) => {{
+        let mut path = ItemPath::new($path_buffer);
+        let mut walk =
+            Walk::<'_, D, MAX_DEPTH, BUFFER_SIZE>::new($self.root_directory(), $self.yield_interval);
+        let mut path_lengths = [0; MAX_DEPTH];
+
+        while let Some(result) = $io!(walk.next(); walk.next_async()) {
+            let item = match result {
+                Ok(item) => item,
+                Err(error) => {
+                    if $self.report_invalid_directory_entry(error).is_break() {
+                        return Ok(());
+                    }
+
+                    continue;
+                }
+            };
+
+            let is_match = $predicate(&item);
+            let directory = if walk.can_descend() {
+                $self.directory_for(&item)
+            } else {
+                None
+            };
+
+            // Only paths which are reported or searched below need to fit the buffer
+            if !is_match && directory.is_none() {
+                continue;
+            }
+
+            path.truncate(path_lengths[walk.depth() - 1]);
+            path.push(&item)?;
+
+            if is_match && $on_match(path.as_str(), &item).is_break() {
+                return Ok(());
+            }
+
+            if let Some(directory) = directory {
+                path_lengths[walk.depth()] = path.len();
+                walk.descend(directory);
+            }
+        }
+
+        Ok(())
+    }};
+}
+
+/// The body of `find_name_conflicts`, shared between the sync and async implementations.
+macro_rules! find_name_conflicts_body {
+    ($io:ident, $self:ident, $directory_path:ident, $on_conflict:ident) => {{
+        let directory = $io!(
+            $self.find_directory($directory_path);
+            $self.find_directory_async($directory_path)
+        )
+        .ok_or(OperationError::NotFound)?;
+        let mut yield_budget = YieldBudget::new($self.yield_interval);
+        let mut item_iterator = directory.items().with_dot_items_skipped(true);
+
+        while let Some(result) = $io!(item_iterator.next(); item_iterator.next_async()) {
+            let item = match result {
+                Ok(item) if item.is_volume_label() => continue,
+                Ok(item) => item,
+                Err(error) => {
+                    if $self.report_invalid_directory_entry(error).is_break() {
+                        return Ok(());
+                    }
+
+                    continue;
+                }
+            };
+
+            // Invalid entries were already reported when the outer scan passed them
+            let mut later_item_iterator = directory.items().with_dot_items_skipped(true);
+            later_item_iterator.set_position(item_iterator.position());
+            while let Some(result) =
+                $io!(later_item_iterator.next(); later_item_iterator.next_async())
+            {
+                $io!(; yield_budget.tick());
+
+                let Ok(later_item) = result else {
+                    continue;
+                };
+
+                if !later_item.is_volume_label()
+                    && let Some(conflict) =
+                        NameConflict::between(&$self.code_page_encoder, &item, &later_item)
+                    && $on_conflict(conflict).is_break()
+                {
+                    return Ok(());
+                }
+            }
+        }
+
+        Ok(())
+    }};
+}
+
+/// The body of `latest`, shared between the sync and async implementations.
+macro_rules! latest_body {
+    ($io:ident, $self:ident, $path_glob:ident) => {{
+        let (directory_path, name_glob) = $path_glob.rsplit_once("/").unwrap_or(("", $path_glob));
+        let name_pattern = NamePattern::new(name_glob)?;
+        let mut item_iterator = $io!(
+            $self.find_directory(directory_path);
+            $self.find_directory_async(directory_path)
+        )?
+        .items()
+        .with_dot_items_skipped(true);
+        let mut latest_item: Option<DirectoryItem> = None;
+
+        while let Some(result) = $io!(item_iterator.next(); item_iterator.next_async()) {
+            let item = match result {
+                Ok(item) => item,
+                Err(error) => {
+                    if $self.report_invalid_directory_entry(error).is_break() {
+                        return None;
+                    }
+
+                    continue;
+                }
+            };
+
+            if item.is_file()
+                && name_pattern.matches(&item)
+                && latest_item
+                    .as_ref()
+                    .is_none_or(|latest_item| Self::is_written_after(&item, latest_item))
+            {
+                latest_item = Some(item);
+            }
+        }
+
+        latest_item
+    }};
+}
+
+/// The body of `hash`, shared between the sync and async implementations.
+macro_rules! hash_body {
+    ($io:ident, $self:ident, $file_path:ident, $hasher:ident) => {{
+        let item = $io!($self.find_item($file_path); $self.find_item_async($file_path))
+            .ok_or(HashError::NotFound)?;
+        let mut file = $self.file_for(&item).ok_or(HashError::NotAFile)?;
+
+        $io!($self.hash_file(&mut file, $hasher); $self.hash_file_async(&mut file, $hasher))
+            .map_err(HashError::FileError)
+    }};
+}
+
+/// The body of `verify_manifest`, shared between the sync and async implementations.
+macro_rules! verify_manifest_body {
+    ($io:ident, $self:ident, $manifest:ident, $path_buffer:ident, $on_mismatch:ident) => {{
+        for (path, expected_size, expected_crc32) in $manifest.clone() {
+            let mismatch = match $io!($self.find_item(path); $self.find_item_async(path))
+                .filter(DirectoryItem::is_file)
+            {
+                None => Some(ManifestMismatch::Missing { path }),
+                Some(item) if item.extended_file_size() != expected_size => {
+                    Some(ManifestMismatch::SizeMismatch {
+                        path,
+                        expected: expected_size,
+                        actual: item.extended_file_size(),
+                    })
+                }
+                Some(item) => {
+                    let mut file = $self.file_at(
+                        ClusterNumber::new(item.first_cluster_number()),
+                        item.extended_file_size(),
+                    );
+                    let mut crc32 = Crc32::new();
+                    $io!(
+                        $self.hash_file(&mut file, &mut crc32);
+                        $self.hash_file_async(&mut file, &mut crc32)
+                    )
+                    .map_err(ManifestError::FileError)?;
+
+                    let actual = crc32.finalize();
+                    (actual != expected_crc32).then_some(ManifestMismatch::Crc32Mismatch {
+                        path,
+                        expected: expected_crc32,
+                        actual,
+                    })
+                }
+            };
+
+            if let Some(mismatch) = mismatch
+                && $on_mismatch(mismatch).is_break()
+            {
+                return Ok(());
+            }
+        }
+
+        let mut path = ItemPath::new($path_buffer);
+        let mut walk =
+            Walk::<'_, D, MAX_DEPTH, BUFFER_SIZE>::new($self.root_directory(), $self.yield_interval);
+        let mut path_lengths = [0; MAX_DEPTH];
+
+        while let Some(result) = $io!(walk.next(); walk.next_async()) {
+            let item = match result {
+                Ok(item) => item,
+                Err(error) => {
+                    if $self.report_invalid_directory_entry(error).is_break() {
+                        return Ok(());
+                    }
+
+                    continue;
+                }
+            };
+
+            let directory = if walk.can_descend() {
+                $self.directory_for(&item)
+            } else {
+                None
+            };
+
+            if !item.is_file() && directory.is_none() {
+                continue;
+            }
+
+            path.truncate(path_lengths[walk.depth() - 1]);
+            path.push(&item)?;
+
+            if item.is_file()
+                && !Self::is_listed($manifest.clone(), path.as_str())
+                && $on_mismatch(ManifestMismatch::Extra {
+                    path: path.as_str(),
+                })
+                .is_break()
+            {
+                return Ok(());
+            }
+
+            if let Some(directory) = directory {
+                path_lengths[walk.depth()] = path.len();
+                walk.descend(directory);
+            }
+        }
+
+        Ok(())
+    }};
+}
+
+/// The body of `backup_metadata`, shared between the sync and async implementations.
+macro_rules! backup_metadata_body {
+    ($io:ident, $self:ident, $writer:ident) => {{
+        let metadata_size = $self.metadata_size();
+        let mut buffer = [0; BUFFER_SIZE];
+        let mut address = 0;
+        let mut yield_budget = YieldBudget::new($self.yield_interval);
+
+        while address < metadata_size {
+            $io!(; yield_budget.tick());
+
+            let chunk = &mut buffer[..min(metadata_size - address, BUFFER_SIZE as u64) as usize];
+
+            $io!($self.read_volume(address, chunk); $self.read_volume_async(address, chunk))
+                .map_err(MetadataError::VolumeError)?;
+            $io!(Write::write_all($writer, chunk)).map_err(MetadataError::BackupError)?;
+
+            address += chunk.len() as u64;
+        }
+
+        Ok(())
+    }};
+}
+
+/// The body of `hash_file`, shared between the sync and async implementations.
+macro_rules! hash_file_body {
+    ($io:ident, $self:ident, $file:ident, $hasher:ident) => {{
+        let mut buffer = [0; BUFFER_SIZE];
+        let mut yield_budget = YieldBudget::new($self.yield_interval);
+
+        loop {
+            $io!(; yield_budget.tick());
+
+            let read = $io!($file.read(&mut buffer))?;
+            if read == 0 {
+                return Ok(());
+            }
+
+            $hasher.update(&buffer[..read]);
+        }
+    }};
+}
+
+/// The body of `find_directory`, shared between the sync and async implementations.
+macro_rules! find_directory_body {
+    ($io:ident, $self:ident, $directory_path:ident) => {{
+        if $directory_path.is_empty() {
+            return Some($self.root_directory());
+        }
+
+        $self.directory_for(&$io!(
+            $self.find_item($directory_path);
+            $self.find_item_async($directory_path)
+        )?)
+    }};
+}
+
+#[cfg(feature = "sync")]
+impl<D, S, CPE, IDE, ME, const BUFFER_SIZE: usize> FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Seek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    pub fn new(
+        device: D,
+        code_page_encoder: CPE,
+        on_invalid_directory_entry: IDE,
+        on_mutation: ME,
+    ) -> Result<Self, FileSystemError<D::Error, S::Error>> {
+        let bios_parameter_block = Self::read_bios_parameter_block(&device)?;
+
+        Ok(Self::from_parts(
+            device,
+            code_page_encoder,
+            on_invalid_directory_entry,
+            on_mutation,
+            None,
+            None,
+            true,
+            0,
+            InvalidTimestampPolicy::default(),
+            &[],
+            bios_parameter_block,
+            DEFAULT_YIELD_INTERVAL,
+            false,
+        ))
+    }
+
+    fn read_bios_parameter_block(
+        device: &D,
+    ) -> Result<BiosParameterBlock, FileSystemError<D::Error, S::Error>> {
+        let mut boot_sector_bytes = [0; 512];
+
+        device
+            .with_stream(
+                |stream| -> Result<(), FileSystemError<D::Error, S::Error>> {
+                    stream.seek(SeekFrom::Start(0))?;
+
+                    stream.read_exact(&mut boot_sector_bytes)?;
+
+                    Ok(())
+                },
+            )
+            .map_err(FileSystemError::DeviceError)??;
+
+        Self::validate_boot_sector_signature(&boot_sector_bytes)?;
+
+        Ok(BiosParameterBlock::from_boot_sector(&boot_sector_bytes)?)
+    }
+
+    /// Reads the volume's boot sector and allocation table again after the device was handed to
+    /// another host, failing if they no longer describe a supported volume.
+    fn remount_bios_parameter_block(
+        device: &D,
+    ) -> Result<BiosParameterBlock, FileSystemError<D::Error, S::Error>> {
+        let bios_parameter_block = Self::read_bios_parameter_block(device)?;
+        let mut allocation_table_media_type = [0; 1];
+
+        device
+            .with_stream(
+                |stream| -> Result<(), FileSystemError<D::Error, S::Error>> {
+                    stream.seek(SeekFrom::Start(
+                        bios_parameter_block.allocation_table_base_address().value(),
+                    ))?;
+
+                    stream.read_exact(&mut allocation_table_media_type)?;
+
+                    Ok(())
+                },
+            )
+            .map_err(FileSystemError::DeviceError)??;
+
+        Self::validate_allocation_table_media_type(
+            &bios_parameter_block,
+            allocation_table_media_type[0],
+        )?;
+
+        Ok(bios_parameter_block)
+    }
+
+    /// Runs the sanity pass of `FileSystemBuilder::with_self_test`, reading the structures
+    /// `HealthSummary` describes.
+    fn self_test(&self) -> Result<HealthSummary, FileSystemError<D::Error, S::Error>> {
+        let invalid_timestamp_count =
+            Dir::new(self.root_directory(), self.yield_interval).invalid_timestamp_count();
+        let bios_parameter_block = &self.bios_parameter_block;
+        let mut health_summary = HealthSummary::new(
+            invalid_timestamp_count.is_ok(),
+            invalid_timestamp_count.unwrap_or(0),
+        );
+        let mut sector_bytes = [0; 512];
+        let mut reserved_entries_bytes = [0; RESERVED_ENTRIES_SIZE];
+
+        self.device
+            .with_stream(
+                |stream| -> Result<(), FileSystemError<D::Error, S::Error>> {
+                    if let Some(address) = bios_parameter_block.backup_boot_sector_base_address() {
+                        stream.seek(SeekFrom::Start(address.value()))?;
+                        stream.read_exact(&mut sector_bytes)?;
+
+                        health_summary
+                            .check_backup_boot_sector(bios_parameter_block, &sector_bytes);
+                    }
+
+                    if let Some(address) = bios_parameter_block.fs_info_base_address() {
+                        stream.seek(SeekFrom::Start(address.value()))?;
+                        stream.read_exact(&mut sector_bytes)?;
+
+                        health_summary.check_fs_info(bios_parameter_block, &sector_bytes);
+                    }
+
+                    for table_index in 0..bios_parameter_block.allocation_table_count() {
+                        stream.seek(SeekFrom::Start(Self::allocation_table_copy_address(
+                            bios_parameter_block,
+                            table_index,
+                        )))?;
+                        stream.read_exact(&mut reserved_entries_bytes)?;
+
+                        health_summary.check_reserved_entries(
+                            bios_parameter_block,
+                            &ReservedEntries::from_bytes(
+                                bios_parameter_block.allocation_table_kind(),
+                                &reserved_entries_bytes,
+                            ),
+                            table_index == bios_parameter_block.active_allocation_table_index(),
+                        );
+                    }
+
+                    Ok(())
+                },
+            )
+            .map_err(FileSystemError::DeviceError)??;
+
+        Ok(health_summary)
+    }
+
+    /// Checks that the medium the file system was mounted from is still in place by reading its
+    /// boot sector again, which is cheap enough to call before each batch of work on removable
+    /// media.
+    ///
+    /// Fails with `MediaChanged` once the boot sector's signature or volume serial number no
+    /// longer match, from then on files opened from the file system fail with
+    /// `FileError::MediaChanged` rather than touching the new medium.  Suspending and resuming the
+    /// file system mounts the new medium, a `PathCache` or `DirSnapshot` built before should be
+    /// cleared.
+    pub fn revalidate(&self) -> Result<(), DeviceOperationError<D>> {
+        revalidate_body!(sync_io, self)
+    }
+
+    /// Opens the file at the given path for reading, files are written through `open_writer`.
+    pub fn open(&self, file_path: &str) -> Option<File<'_, D>> {
+        self.file_for(&self.find_item(file_path)?)
+    }
+
+    /// Lists the items of the directory at the given path, an empty path refers to the root
+    /// directory.
+    pub fn read_dir(&self, directory_path: &str) -> Option<Dir<'_, D, BUFFER_SIZE>> {
+        Some(Dir::new(
+            self.find_directory(directory_path)?,
+            self.yield_interval,
+        ))
+    }
+
+    /// Resolves the path to the names the items along it are stored under, built in the buffer
+    /// with components separated by `/`.  An empty path refers to the root directory.
+    ///
+    /// Names are matched as by `open` and written as by `find_all`, so differently cased paths to
+    /// the same item canonicalize to the same path.  Empty and `.` components are skipped, `..`
+    /// components step back to the parent directory and leave the root directory unchanged.
+    pub fn canonicalize<'b>(
+        &self,
+        path: &str,
+        path_buffer: &'b mut [u8],
+    ) -> Result<&'b str, CanonicalizeError> {
+        let mut canonical_path = ItemPath::new(path_buffer);
+        self.resolve(path, &mut canonical_path)?;
+
+        Ok(canonical_path.into_str())
+    }
+
+    /// Opens the file at the given path, looking it up in the cache before walking the directory
+    /// tree and recording it in the cache when it was walked.
+    ///
+    /// Entries aren't updated as the volume changes, see [`PathCache`] for invalidating them.
+    pub fn open_cached<const N: usize>(
+        &self,
+        cache: &mut PathCache<N>,
+        file_path: &str,
+    ) -> Option<File<'_, D>> {
+        if let Some(entry) = cache.get(file_path) {
+            return self.file_for_cached(entry);
+        }
+
+        let mut path_buffer = [0; MAX_CACHED_PATH_LENGTH];
+        let mut canonical_path = ItemPath::new(&mut path_buffer);
+
+        match self.resolve(file_path, &mut canonical_path) {
+            Ok(item) => {
+                let item = item?;
+                cache.insert(canonical_path.as_str(), &item);
+
+                self.file_for(&item)
+            }
+            // Paths too long to be cached are still opened
+            Err(CanonicalizeError::PathBufferTooSmall) => self.open(file_path),
+            Err(_) => None,
+        }
+    }
+
+    /// Indexes the cluster chain of the file at the given path into memory, up to `N` clusters of
+    /// it, for opening through `open_pinned`.
+    pub fn pin<const N: usize>(
+        &self,
+        file_path: &str,
+    ) -> Result<PinnedFile<N>, DeviceOperationError<D>> {
+        pin_body!(sync_io, self, file_path)
+    }
+
+    /// Walks the path, building its canonical form and returning the item its last component
+    /// refers to.  No item is returned for paths ending at the root directory or a `..` component.
+    fn resolve(
+        &self,
+        path: &str,
+        canonical_path: &mut ItemPath<'_>,
+    ) -> Result<Option<DirectoryItem>, CanonicalizeError> {
+        let mut current_directory = Some(self.root_directory());
+        let mut current_item = None;
+
+        let mut next_component_offset = 0;
+
+        for component in path.split("/") {
+            let component_offset = next_component_offset;
+            next_component_offset += component.len() + 1;
+
+            if component.is_empty() || component == "." {
+                continue;
+            }
+
+            let directory = current_directory
+                .as_ref()
+                .ok_or(CanonicalizeError::NotADirectory { component_offset })?;
+
+            if component == ".." {
+                // The root directory is its own parent and has no `..` item to follow
+                if canonical_path.len() > 0 {
+                    let parent_item = self
+                        .find_item_in(directory, DirectoryItem::is_dotdot)
+                        .ok_or(CanonicalizeError::NotFound { component_offset })?;
+
+                    current_directory = self.directory_for(&parent_item);
+                    canonical_path.pop();
+                }
+
+                current_item = None;
+                continue;
+            }
+
+            let item = self
+                .find_item_named(directory, component)
+                .ok_or(CanonicalizeError::NotFound { component_offset })?;
+
+            canonical_path.push(&item)?;
+            current_directory = self.directory_for(&item);
+            current_item = Some(item);
+        }
+
+        Ok(current_item)
+    }
+
+    /// Records every item of the volume, up to `MAX_DEPTH` levels of directories with the root as
+    /// the first, along with the CRC-32 of each file's contents if `include_hashes` is set.
+    ///
+    /// Invalid entries are reported to the invalid directory entry callback and skipped, the walk
+    /// ends early if the callback returns `ControlFlow::Break`.
+    #[cfg(feature = "alloc")]
+    pub fn inventory<const MAX_DEPTH: usize>(
+        &self,
+        include_hashes: bool,
+    ) -> Result<Inventory, DeviceHashError<D>> {
+        inventory_body!(sync_io, self, include_hashes)
+    }
+
+    /// Writes an indented listing of every item of the volume, up to `MAX_DEPTH` levels of
+    /// directories with the root as the first, for debug consoles and support dumps.
+    ///
+    /// Each item takes a line indented by two spaces per level, with directories suffixed by `/`
+    /// and files followed by their size in bytes, then the item's last write timestamp.  Invalid
+    /// entries are reported to the invalid directory entry callback and skipped, the listing ends
+    /// early if the callback returns `ControlFlow::Break`.
+    pub fn dump_tree<const MAX_DEPTH: usize, W>(&self, writer: &mut W) -> core::fmt::Result
+    where
+        W: core::fmt::Write,
+    {
+        dump_tree_body!(sync_io, self, writer)
+    }
+
+    /// Walks every directory of the volume, up to `MAX_DEPTH` levels of them with the root as the
+    /// first, and calls `on_match` with the path and item of each item the predicate accepts.
+    /// Returning `ControlFlow::Break` from `on_match` ends the search early.
+    ///
+    /// Paths are built in the buffer from long names where present, separated by `/`, so they can
+    /// be passed back to `open`.  Invalid entries are reported to the invalid directory entry
+    /// callback and skipped, the search ends early if the callback returns `ControlFlow::Break`.
+    pub fn find_all<const MAX_DEPTH: usize, P, F>(
+        &self,
+        path_buffer: &mut [u8],
+        mut predicate: P,
+        mut on_match: F,
+    ) -> Result<(), SearchError>
+    where
+        P: FnMut(&DirectoryItem) -> bool,
+        F: FnMut(&str, &DirectoryItem) -> ControlFlow<()>,
+    {
+        find_all_body!(sync_io, self, path_buffer, predicate, on_match)
+    }
+
+    /// Compares every item of the directory at the given path against every other, calling
+    /// `on_conflict` with each pair which a lookup by name can't tell apart.  Returning
+    /// `ControlFlow::Break` from `on_conflict` ends the scan early.
+    ///
+    /// Each item is compared against the items following it, so the directory is read once per
+    /// item rather than held in memory.  Invalid entries are reported to the invalid directory
+    /// entry callback and skipped, the scan ends early if the callback returns
+    /// `ControlFlow::Break`.
+    pub fn find_name_conflicts<F>(
+        &self,
+        directory_path: &str,
+        mut on_conflict: F,
+    ) -> Result<(), DeviceOperationError<D>>
+    where
+        F: FnMut(NameConflict<'_>) -> ControlFlow<()>,
+    {
+        find_name_conflicts_body!(sync_io, self, directory_path, on_conflict)
+    }
+
+    /// Finds the file in the directory whose name matches the pattern and which was written last,
+    /// such as the newest firmware image matching `update/FW*.BIN`.
+    ///
+    /// Only the last path component may hold wildcards, `*` matching any run of characters and `?`
+    /// any single character, and is matched against both long and short names ignoring case.
+    /// Files written at the same time are told apart by the name ordering last ignoring ASCII
+    /// case.  Returns `None` if the directory doesn't exist or no file matches.
+    pub fn latest(&self, path_glob: &str) -> Option<DirectoryItem> {
+        latest_body!(sync_io, self, path_glob)
+    }
+
+    /// The label of the volume held by its root directory, `None` if the volume has no label.
+    ///
+    /// The label kept in the boot sector isn't used, since hosts only update the root directory's
+    /// copy when relabelling a volume.
+    pub fn volume_label(&self) -> Option<VolumeLabel> {
+        let item = self.find_item_in(&self.root_directory(), DirectoryItem::is_volume_label)?;
+
+        Some(VolumeLabel::new(*item.short_name().bytes()))
+    }
+
+    /// Gathers the free, allocated, bad and reserved cluster counts and the longest run of free
+    /// clusters recorded by the copy of the allocation table at the given index, the first being
+    /// the one in use.
+    ///
+    /// The table is read in a single pass, so comparing the statistics of each copy is a cheap
+    /// check of whether they still agree.
+    pub fn allocation_table_statistics(
+        &self,
+        table_index: u8,
+    ) -> Result<AllocationTableStatistics, DeviceOperationError<D>> {
+        let allocation_table = self
+            .allocation_table
+            .copy(table_index)
+            .ok_or(OperationError::AllocationTableIndexInvalid { table_index })?;
+        let last_cluster_number = self.bios_parameter_block.last_cluster_number();
+
+        let statistics = self
+            .device
+            .with_stream(|stream| allocation_table.statistics(stream, last_cluster_number))
+            .map_err(OperationError::DeviceError)??;
+
+        Ok(statistics)
+    }
+
+    /// Reads the FS information sector of a FAT32 volume, `None` on other volumes or when the boot
+    /// sector doesn't record one.
+    ///
+    /// Its free cluster count answers free space queries without scanning the allocation table,
+    /// though hosts don't always keep it up to date, so `allocation_table_statistics` remains the
+    /// authoritative count.
+    pub fn fs_info(&self) -> Result<Option<FsInfo>, DeviceOperationError<D>> {
+        let Some(address) = self.bios_parameter_block.fs_info_base_address() else {
+            return Ok(None);
+        };
+
+        let mut sector_bytes = [0; 512];
+
+        self.device
+            .with_stream(|stream| -> Result<(), DeviceOperationError<D>> {
+                stream.seek(SeekFrom::Start(address.value()))?;
+                stream.read_exact(&mut sector_bytes)?;
+
+                Ok(())
+            })
+            .map_err(OperationError::DeviceError)??;
+
+        Ok(Some(FsInfo::from_sector(&sector_bytes)?))
+    }
+
+    /// Reads a cluster of the data region into the buffer, which must be exactly
+    /// [`bytes_per_cluster`](Self::bytes_per_cluster) long, bypassing the file layer.
+    #[cfg(feature = "dangerous")]
+    pub fn read_cluster(
+        &self,
+        cluster_number: u32,
+        buffer: &mut [u8],
+    ) -> Result<(), DeviceOperationError<D>> {
+        let address = self.raw_cluster_address(cluster_number, buffer.len())?;
+
+        self.device
+            .with_stream(|stream| -> Result<(), DeviceOperationError<D>> {
+                stream.seek(SeekFrom::Start(address.value()))?;
+                stream.read_exact(buffer)?;
+
+                Ok(())
+            })
+            .map_err(OperationError::DeviceError)?
+    }
+
+    /// Streams the contents of the file at the given path through the hasher, a chunk at a time,
+    /// so files of any size can be verified without holding them in memory.
+    pub fn hash<H>(&self, file_path: &str, hasher: &mut H) -> Result<(), DeviceHashError<D>>
+    where
+        H: Hasher,
+    {
+        hash_body!(sync_io, self, file_path, hasher)
+    }
+
+    /// Verifies the volume against a manifest of `(path, size, crc32)` records, calling
+    /// `on_mismatch` with each difference found.  Returning `ControlFlow::Break` from `on_mismatch`
+    /// ends the verification early.
+    ///
+    /// Listed files are looked up as `open` does, their contents are only hashed if their size
+    /// matches.  Files on the volume which no record lists are reported as `Extra`, searching up to
+    /// `MAX_DEPTH` levels of directories with the root as the first.  Their paths are built in the
+    /// buffer from long names where present and compared against the listed paths ignoring ASCII
+    /// case.  The manifest is iterated again for each file on the volume rather than held in
+    /// memory.
+    pub fn verify_manifest<'m, const MAX_DEPTH: usize, M, F>(
+        &self,
+        manifest: M,
+        path_buffer: &mut [u8],
+        mut on_mismatch: F,
+    ) -> Result<(), DeviceManifestError<D>>
+    where
+        M: IntoIterator<Item = (&'m str, u64, u32)> + Clone,
+        F: FnMut(ManifestMismatch<'_>) -> ControlFlow<()>,
+    {
+        verify_manifest_body!(sync_io, self, manifest, path_buffer, on_mismatch)
+    }
+
+    /// Writes a copy of the volume's metadata, the `metadata_size` bytes holding the boot sector,
+    /// FS information sector and allocation tables, so they can be put back by `restore_metadata`
+    /// should a risky operation leave them damaged.
+    ///
+    /// Directories and file contents aren't part of the backup, restoring an old backup loses
+    /// track of clusters allocated since and frees clusters released since.
+    pub fn backup_metadata<W>(&self, writer: &mut W) -> Result<(), DeviceMetadataError<D, W::Error>>
+    where
+        W: Write,
+    {
+        backup_metadata_body!(sync_io, self, writer)
+    }
+
+    fn read_volume(&self, address: u64, buffer: &mut [u8]) -> Result<(), DeviceOperationError<D>> {
+        self.device
+            .with_stream(|stream| -> Result<(), DeviceOperationError<D>> {
+                stream.seek(SeekFrom::Start(address))?;
+                stream.read_exact(buffer)?;
+
+                Ok(())
+            })
+            .map_err(OperationError::DeviceError)?
+    }
+
+    fn hash_file<H>(
+        &self,
+        file: &mut File<'_, D>,
+        hasher: &mut H,
+    ) -> Result<(), FileError<D::Error, S::Error>>
+    where
+        H: Hasher,
+    {
+        hash_file_body!(sync_io, self, file, hasher)
+    }
+
+    fn find_directory(&self, directory_path: &str) -> Option<Directory<'_, D, BUFFER_SIZE>> {
+        find_directory_body!(sync_io, self, directory_path)
+    }
+
+    fn find_item(&self, file_path: &str) -> Option<DirectoryItem> {
+        let mut current_directory = self.root_directory();
+        let mut file_path_part_iterator = file_path.split("/");
+        let mut file_path_part = file_path_part_iterator.next()?;
+
+        loop {
+            let query = NameQuery::new(&self.code_page_encoder, file_path_part);
+            let item = self.find_item_in(&current_directory, |item| query.matches(item))?;
+
+            file_path_part = match file_path_part_iterator.next() {
+                Some(next_file_path_part) => next_file_path_part,
+                None => return Some(item),
+            };
+
+            current_directory = self.directory_for(&item)?;
+        }
+    }
+
+    /// Finds the directory holding the item at the given path, returning it along with its path
+    /// and the item's name.
+    fn find_parent_directory<'p>(
+        &self,
+        path: &'p str,
+    ) -> Option<(Directory<'_, D, BUFFER_SIZE>, &'p str, &'p str)> {
+        find_parent_directory_body!(sync_io, self, path)
+    }
+
+    pub(crate) fn find_item_named(
+        &self,
+        directory: &Directory<'_, D, BUFFER_SIZE>,
+        name: &str,
+    ) -> Option<DirectoryItem> {
+        let query = NameQuery::new(&self.code_page_encoder, name);
+
+        self.find_item_in(directory, |item| query.matches(item))
+    }
+
+    pub(crate) fn find_item_in(
+        &self,
+        directory: &Directory<'_, D, BUFFER_SIZE>,
+        predicate: impl Fn(&DirectoryItem) -> bool,
+    ) -> Option<DirectoryItem> {
+        match self.search_items_in(directory, predicate) {
+            ControlFlow::Continue(item) => item,
+            ControlFlow::Break(()) => None,
+        }
+    }
+
+    /// Whether any of the directory's items match the predicate.  A search stopped by the invalid
+    /// directory entry callback counts as a match, so names in use aren't handed out again.
+    pub(crate) fn contains_item_in(
+        &self,
+        directory: &Directory<'_, D, BUFFER_SIZE>,
+        predicate: impl Fn(&DirectoryItem) -> bool,
+    ) -> bool {
+        match self.search_items_in(directory, predicate) {
+            ControlFlow::Continue(item) => item.is_some(),
+            ControlFlow::Break(()) => true,
+        }
+    }
+
+    fn search_items_in(
+        &self,
+        directory: &Directory<'_, D, BUFFER_SIZE>,
+        predicate: impl Fn(&DirectoryItem) -> bool,
+    ) -> ControlFlow<(), Option<DirectoryItem>> {
+        let mut item_iterator = directory.items();
+
+        loop {
+            match item_iterator.next() {
+                None => return ControlFlow::Continue(None),
+                Some(Ok(item)) if predicate(&item) => return ControlFlow::Continue(Some(item)),
+                Some(Ok(_)) => {}
+                Some(Err(error)) => self.report_invalid_directory_entry(error)?,
+            }
+        }
+    }
+
+    /// Whether the directory at the given path stays within the quota its policy sets once the
+    /// files and bytes are added to it.
+    pub(crate) fn is_within_quota(
+        &self,
+        directory: &Directory<'_, D, BUFFER_SIZE>,
+        directory_path: &str,
+        added_file_count: u64,
+        added_byte_count: u64,
+    ) -> bool {
+        if added_file_count == 0 && added_byte_count == 0 {
+            return true;
+        }
+
+        self.directory_quota_usage(directory, directory_path)
+            .is_none_or(|usage| usage.allows(added_file_count, added_byte_count))
+    }
+
+    /// Measures the files and bytes held in the directory at the given path for checking against
+    /// the quota its policy sets, `None` when it sets none.  Files stopped from being counted by
+    /// the invalid directory entry callback aren't held against the quota.
+    pub(crate) fn directory_quota_usage(
+        &self,
+        directory: &Directory<'_, D, BUFFER_SIZE>,
+        directory_path: &str,
+    ) -> Option<DirectoryQuotaUsage> {
+        directory_quota_usage_body!(sync_io, self, directory, directory_path)
+    }
+
+    /// Reads the short name entry ending the item at the given location again.
+    pub(crate) fn read_short_directory_entry(
+        &self,
+        location: EntryLocation,
+    ) -> Result<ShortNameDirectoryEntry, DeviceOperationError<D>> {
+        let mut iterator = self.directory_containing(location.position()).entries();
+        iterator.set_position(location.position());
+
+        for _ in 1..location.entry_count() {
+            ensure!(iterator.advance()?, OperationError::NotFound);
+        }
+
+        match iterator.peek().ok_or(OperationError::NotFound)?? {
+            DirectoryEntry::ShortName(short_directory_entry) => Ok(short_directory_entry),
+            _ => Err(OperationError::NotFound),
+        }
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<D, S, CPE, IDE, ME, const BUFFER_SIZE: usize> FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Write + Seek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    /// Overwrites a cluster of the data region with the buffer, which must be exactly
+    /// [`bytes_per_cluster`](Self::bytes_per_cluster) long, bypassing the file layer.
+    ///
+    /// The allocation table and directory entries are left untouched, so writing a cluster that
+    /// belongs to a directory or to another file's chain corrupts them.
+    #[cfg(feature = "dangerous")]
+    pub fn write_cluster(
+        &self,
+        cluster_number: u32,
+        buffer: &[u8],
+    ) -> Result<(), DeviceOperationError<D>> {
+        let address = self.raw_cluster_address(cluster_number, buffer.len())?;
+
+        self.device
+            .with_stream(|stream| -> Result<(), DeviceOperationError<D>> {
+                stream.seek(SeekFrom::Start(address.value()))?;
+                stream.write_all(buffer)?;
+
+                Ok(())
+            })
+            .map_err(OperationError::DeviceError)?
+    }
+
+    /// Writes the metadata backed up by `backup_metadata` back to the volume, replacing its boot
+    /// sector, FS information sector and allocation tables.
+    ///
+    /// Fails with `BackupMismatch` before writing anything if the backup's boot sector lays the
+    /// volume out differently.  A backup ending early or failing to be read leaves the metadata
+    /// partially restored, which may be retried with the same backup.  Files and directories
+    /// opened before restoring should be opened again, as their clusters may have changed.
+    pub fn restore_metadata<R>(
+        &self,
+        reader: &mut R,
+    ) -> Result<(), DeviceMetadataError<D, R::Error>>
+    where
+        R: Read,
+    {
+        let metadata_size = self.metadata_size();
+        let mut boot_sector_bytes = [0; 512];
+
+        Self::read_backup(reader, &mut boot_sector_bytes)?;
+        let backup_bios_parameter_block = BiosParameterBlock::from_boot_sector(&boot_sector_bytes)
+            .map_err(|_| MetadataError::BackupMismatch)?;
+        ensure!(
+            self.bios_parameter_block
+                .has_same_layout(&backup_bios_parameter_block),
+            MetadataError::BackupMismatch
+        );
+
+        self.directory_generation
+            .set(self.directory_generation.get().wrapping_add(1));
+
+        self.write_volume(0, &boot_sector_bytes)
+            .map_err(MetadataError::VolumeError)?;
+
+        let mut buffer = [0; BUFFER_SIZE];
+        let mut address = boot_sector_bytes.len() as u64;
+
+        while address < metadata_size {
+            let chunk = &mut buffer[..min(metadata_size - address, BUFFER_SIZE as u64) as usize];
+
+            Self::read_backup(reader, chunk)?;
+            self.write_volume(address, chunk)
+                .map_err(MetadataError::VolumeError)?;
+
+            address += chunk.len() as u64;
+        }
+
+        Ok(())
+    }
+
+    /// Rewrites the reserved entries at the start of each copy of the allocation table which don't
+    /// echo the boot sector's media descriptor or hold an end of chain marker, the damage
+    /// `HealthSummary` reports.  Returns whether any copy was rewritten.
+    ///
+    /// Valid entries are left as they are, along with the volume's flags they hold.  A rewritten
+    /// second entry marks the volume as cleanly unmounted and free of disk errors.
+    pub fn repair_reserved_entries(&self) -> Result<bool, DeviceOperationError<D>> {
+        let kind = self.allocation_table_kind();
+        let media_type = self.media_type();
+        let mut bytes = [0; RESERVED_ENTRIES_SIZE];
+        let mut is_repaired = false;
+
+        for table_index in 0..self.allocation_table_count() {
+            let address =
+                Self::allocation_table_copy_address(&self.bios_parameter_block, table_index);
+
+            self.read_volume(address, &mut bytes)?;
+
+            let reserved_entries = ReservedEntries::from_bytes(kind, &bytes);
+            let repaired_entries = reserved_entries.repaired(media_type);
+
+            if repaired_entries != reserved_entries {
+                repaired_entries.write(&mut bytes);
+                self.write_volume(address, &bytes)?;
+
+                is_repaired = true;
+            }
+        }
+
+        Ok(is_repaired)
+    }
+
+    /// Rewrites the FAT16 volume's allocation tables and root directory in the FAT32 layout for
+    /// `convert_to_fat32`, keeping the data region where it is.
+    #[cfg(all(feature = "fat16", feature = "fat32"))]
+    fn rewrite_as_fat32(&self) -> Result<(), DeviceConversionError<D>> {
+        let bios_parameter_block = &self.bios_parameter_block;
+        ensure!(
+            bios_parameter_block.allocation_table_kind() == AllocationTableKind::Fat16,
+            ConversionError::AllocationTableKindUnsupported
+        );
+
+        let bytes_per_sector = u64::from(bios_parameter_block.bytes_per_sector());
+        let data_region_base_address = self.data_region_base_address.value();
+        ensure!(
+            data_region_base_address.is_multiple_of(bytes_per_sector),
+            ConversionError::LayoutUnsupported
+        );
+
+        let entry_count = u64::from(bios_parameter_block.last_cluster_number().value()) + 1;
+        let sectors_per_allocation_table = (entry_count * 4).div_ceil(bytes_per_sector);
+        let system_sector_count = data_region_base_address / bytes_per_sector;
+
+        // Keeping the reserved sectors at least as long as before places every entry of the new
+        // table after the entry it replaces, so converting from the last entry to the first never
+        // overwrites an entry before it is read
+        let minimum_reserved_sector_count = u64::from(bios_parameter_block.reserved_sector_count())
+            .max(FAT32_MINIMUM_RESERVED_SECTOR_COUNT);
+        let (allocation_table_count, reserved_sector_count) =
+            [bios_parameter_block.allocation_table_count(), 1]
+                .into_iter()
+                .find_map(|allocation_table_count| {
+                    let reserved_sector_count = system_sector_count
+                        .checked_sub(
+                            u64::from(allocation_table_count) * sectors_per_allocation_table,
+                        )
+                        .filter(|&count| count >= minimum_reserved_sector_count)?;
+
+                    Some((
+                        allocation_table_count,
+                        u16::try_from(reserved_sector_count).ok()?,
+                    ))
+                })
+                .ok_or(ConversionError::SpaceInsufficient)?;
+
+        let mut old_boot_sector_bytes = [0; 512];
+        self.read_volume(0, &mut old_boot_sector_bytes)?;
+
+        // Only boot sectors with this extended boot signature hold a volume label
+        let volume_label = (old_boot_sector_bytes[38] == 0x29).then(|| {
+            let mut volume_label = [0; 11];
+            volume_label.copy_from_slice(&old_boot_sector_bytes[43..54]);
+
+            volume_label
+        });
+
+        let root_directory_cluster_number = self.copy_root_directory_table()?;
+        let boot_sector = BootSector::builder()
+            .allocation_table_kind(AllocationTableKind::Fat32)
+            .total_sector_count(bios_parameter_block.total_sector_count())
+            .sectors_per_cluster(bios_parameter_block.sectors_per_cluster())
+            .bytes_per_sector(bios_parameter_block.bytes_per_sector())
+            .reserved_sector_count(reserved_sector_count)
+            .allocation_table_count(allocation_table_count)
+            .sectors_per_allocation_table(sectors_per_allocation_table as u32)
+            .root_directory_cluster_number(root_directory_cluster_number.value())
+            .media_type(bios_parameter_block.media_type())
+            .hidden_sector_count(bios_parameter_block.hidden_sector_count())
+            .sectors_per_track(bios_parameter_block.sectors_per_track())
+            .head_count(bios_parameter_block.head_count())
+            .maybe_volume_serial_number(bios_parameter_block.volume_serial_number())
+            .maybe_volume_label(volume_label)
+            .oem_name(bios_parameter_block.oem_name())
+            .build();
+        let Some((boot_sector_bytes, converted_bios_parameter_block)) = boot_sector
+            .to_bytes()
+            .ok()
+            .and_then(|bytes| Some((bytes, BiosParameterBlock::from_boot_sector(&bytes).ok()?)))
+        else {
+            self.release_cluster_chain(root_directory_cluster_number)?;
+
+            return Err(ConversionError::LayoutUnsupported);
+        };
+        let free_cluster_count = self.count_free_clusters()?;
+
+        let old_table_address = bios_parameter_block.allocation_table_base_address().value();
+        let new_table_address = u64::from(reserved_sector_count) * bytes_per_sector;
+        let table_size = sectors_per_allocation_table * bytes_per_sector;
+        let media_type = bios_parameter_block.media_type();
+
+        self.device
+            .with_stream(|stream| -> Result<(), DeviceOperationError<D>> {
+                let mut entries = [0; BUFFER_SIZE];
+                let chunk_max_entry_count = (BUFFER_SIZE / 4) as u64;
+
+                // The unused end of the new table only overlaps the end of the old tables and the
+                // root directory table, which was already copied
+                let mut address = new_table_address + entry_count * 4;
+                stream.seek(SeekFrom::Start(address))?;
+
+                while address < new_table_address + table_size {
+                    let length = min(
+                        new_table_address + table_size - address,
+                        entries.len() as u64,
+                    );
+                    stream.write_all(&entries[..length as usize])?;
+                    address += length;
+                }
+
+                let mut chunk_end = entry_count;
+
+                while chunk_end > 0 {
+                    let chunk_start =
+                        (chunk_end - 1) / chunk_max_entry_count * chunk_max_entry_count;
+                    let chunk_entry_count = (chunk_end - chunk_start) as usize;
+
+                    stream.seek(SeekFrom::Start(old_table_address + chunk_start * 2))?;
+                    stream.read_exact(&mut entries[..chunk_entry_count * 2])?;
+
+                    // Widening the entries in place from the last one on reads each old entry
+                    // before a new entry overwrites it
+                    for index in (0..chunk_entry_count).rev() {
+                        let entry = Self::fat32_entry_for(
+                            chunk_start + index as u64,
+                            read_le_u16(&entries, index * 2),
+                            media_type,
+                        );
+
+                        write_le_u32(&mut entries, index * 4, entry);
+                    }
+
+                    stream.seek(SeekFrom::Start(new_table_address + chunk_start * 4))?;
+                    stream.write_all(&entries[..chunk_entry_count * 4])?;
+
+                    chunk_end = chunk_start;
+                }
+
+                for table_index in 1..u64::from(allocation_table_count) {
+                    let mut offset = 0;
+
+                    while offset < table_size {
+                        let length = min(table_size - offset, entries.len() as u64) as usize;
+
+                        stream.seek(SeekFrom::Start(new_table_address + offset))?;
+                        stream.read_exact(&mut entries[..length])?;
+                        stream.seek(SeekFrom::Start(
+                            new_table_address + table_index * table_size + offset,
+                        ))?;
+                        stream.write_all(&entries[..length])?;
+
+                        offset += length as u64;
+                    }
+                }
+
+                // The reserved sectors now cover the start of the old tables, which must not be
+                // mistaken for the FS information sector or boot code
+                entries.fill(0);
+                let mut address = bytes_per_sector;
+                stream.seek(SeekFrom::Start(address))?;
+
+                while address < new_table_address {
+                    let length = min(new_table_address - address, entries.len() as u64);
+                    stream.write_all(&entries[..length as usize])?;
+                    address += length;
+                }
+
+                Ok(())
+            })
+            .map_err(OperationError::DeviceError)??;
+
+        let fs_info_bytes = fs_info_sector(free_cluster_count);
+
+        if let Some(fs_info_base_address) = converted_bios_parameter_block.fs_info_base_address() {
+            self.write_volume(fs_info_base_address.value(), &fs_info_bytes)?;
+        }
+
+        if let Some(backup_base_address) =
+            converted_bios_parameter_block.backup_boot_sector_base_address()
+        {
+            self.write_volume(backup_base_address.value(), &boot_sector_bytes)?;
+            self.write_volume(
+                backup_base_address.value() + bytes_per_sector,
+                &fs_info_bytes,
+            )?;
+        }
+
+        // The boot sector is written last, so the volume only turns into a FAT32 volume once
+        // everything it describes is in place
+        self.write_volume(0, &boot_sector_bytes)?;
+
+        Ok(())
+    }
+
+    /// Copies the entries of the FAT16 root directory table preceding its end of directory marker
+    /// into newly allocated clusters, returning the first cluster of the copy.
+    #[cfg(all(feature = "fat16", feature = "fat32"))]
+    fn copy_root_directory_table(&self) -> Result<ClusterNumber, DeviceOperationError<D>> {
+        let table_address = self
+            .bios_parameter_block
+            .directory_table_base_address()
+            .value();
+        let table_size = u64::from(self.bios_parameter_block.directory_table_entry_count())
+            * DIRECTORY_ENTRY_SIZE as u64;
+        let mut buffer = [0; BUFFER_SIZE];
+        let mut used_size = 0;
+
+        'search: while used_size < table_size {
+            let chunk_length = min(table_size - used_size, BUFFER_SIZE as u64) as usize;
+            self.read_volume(table_address + used_size, &mut buffer[..chunk_length])?;
+
+            for entry in buffer[..chunk_length].chunks_exact(DIRECTORY_ENTRY_SIZE) {
+                if entry[0] == 0x00 {
+                    break 'search;
+                }
+
+                used_size += DIRECTORY_ENTRY_SIZE as u64;
+            }
+        }
+
+        // Padding the copy with zeros to whole clusters ends the directory after the copied
+        // entries
+        let bytes_per_cluster = u64::from(self.bios_parameter_block.bytes_per_cluster());
+        let copy_size = used_size.div_ceil(bytes_per_cluster).max(1) * bytes_per_cluster;
+        let mut first_cluster_number = ClusterNumber::NONE;
+        let mut last_cluster_number = ClusterNumber::NONE;
+        let mut copied_size = 0;
+
+        while copied_size < copy_size {
+            let chunk_length = min(copy_size - copied_size, BUFFER_SIZE as u64) as usize;
+            let read_length =
+                min(used_size.saturating_sub(copied_size), chunk_length as u64) as usize;
+
+            let result = self
+                .read_volume(table_address + copied_size, &mut buffer[..read_length])
+                .and_then(|()| {
+                    buffer[read_length..chunk_length].fill(0);
+
+                    self.append_to_cluster_chain(
+                        first_cluster_number,
+                        last_cluster_number,
+                        copied_size,
+                        &buffer[..chunk_length],
+                    )
+                });
+
+            match result {
+                Ok(cluster_numbers) => {
+                    (first_cluster_number, last_cluster_number) = cluster_numbers
+                }
+                Err(error) => {
+                    if first_cluster_number != ClusterNumber::NONE {
+                        self.release_cluster_chain(first_cluster_number)?;
+                    }
+
+                    return Err(error);
+                }
+            }
+
+            copied_size += chunk_length as u64;
+        }
+
+        Ok(first_cluster_number)
+    }
+
+    fn read_backup<R>(
+        reader: &mut R,
+        buffer: &mut [u8],
+    ) -> Result<(), DeviceMetadataError<D, R::Error>>
+    where
+        R: Read,
+    {
+        reader.read_exact(buffer).map_err(|e| match e {
+            ReadExactError::UnexpectedEof => MetadataError::BackupEndReached,
+            ReadExactError::Other(e) => MetadataError::BackupError(e),
+        })
+    }
+
+    fn write_volume(&self, address: u64, buffer: &[u8]) -> Result<(), DeviceOperationError<D>> {
+        self.device
+            .with_stream(|stream| -> Result<(), DeviceOperationError<D>> {
+                stream.seek(SeekFrom::Start(address))?;
+                stream.write_all(buffer)?;
+
+                Ok(())
+            })
+            .map_err(OperationError::DeviceError)?
+    }
+
+    /// Renames the item at the given path, keeping it within the same directory.
+    ///
+    /// The item's entries are rewritten in place when the new name needs no more entries than the
+    /// old one, otherwise the new entries are written to free slots before the old ones are
+    /// released.  The item's clusters are never released, so an interrupted rename can't lose its
+    /// contents.
+    pub fn rename(&self, path: &str, new_name: &str) -> Result<(), DeviceOperationError<D>> {
+        let (directory, directory_path, name) = self
+            .find_parent_directory(path)
+            .ok_or(OperationError::NotFound)?;
+        let item = self
+            .find_item_named(&directory, name)
+            .ok_or(OperationError::NotFound)?;
+
+        self.rename_item(&directory, &item, new_name)?;
+
+        self.notify_mutation(MutationEvent::Renamed {
+            directory_path,
+            name,
+            new_name,
+            first_cluster_number: item.first_cluster_number(),
+        });
+
+        Ok(())
+    }
+
+    /// Deletes the oldest files directly within the directory at the given path, an empty path
+    /// refers to the root directory, until the volume has room for the given number of bytes.
+    /// Returns the number of files deleted.
+    ///
+    /// Files are deleted by last write timestamp and then name, the reverse of the order `latest`
+    /// prefers them in, so logs spread across files behave like a ring buffer.  Subdirectories are
+    /// kept, as are files the `FixedFilePolicy` set through
+    /// `FileSystemBuilder::with_fixed_file_policy` fixes in place.  Fails with `VolumeFull` once no
+    /// files are left to delete, files deleted until then stay deleted.
+    pub fn evict_until_free(
+        &self,
+        directory_path: &str,
+        bytes_needed: u64,
+    ) -> Result<u32, DeviceOperationError<D>> {
+        let directory = self
+            .find_directory(directory_path)
+            .ok_or(OperationError::NotFound)?;
+        let needed_cluster_count = self.cluster_count_for(bytes_needed);
+        let mut free_cluster_count = u64::from(self.count_free_clusters()?);
+        let mut evicted_count = 0;
+
+        while free_cluster_count < needed_cluster_count {
+            let oldest_item = self
+                .oldest_file_in(&directory, directory_path)
+                .ok_or(OperationError::VolumeFull)?;
+
+            self.delete_item(&directory, &oldest_item)?;
+            free_cluster_count += self.cluster_count_for(oldest_item.extended_file_size());
+            evicted_count += 1;
+
+            let mut name_buffer = [0; MAX_NAME_BYTE_LENGTH];
+            let mut name = ItemPath::new(&mut name_buffer);
+            // Every name fits the buffer, the event is only missing a name if one doesn't
+            let _ = name.push(&oldest_item);
+
+            self.notify_mutation(MutationEvent::Deleted {
+                directory_path,
+                name: name.as_str(),
+                first_cluster_number: oldest_item.first_cluster_number(),
+            });
+        }
+
+        Ok(evicted_count)
+    }
+
+    /// Deletes every file directly within the directory at the given path, an empty path refers
+    /// to the root directory, which the predicate accepts.  Returns the number of files deleted.
+    ///
+    /// The directory is read once, each file being deleted as soon as it's accepted, so the
+    /// predicate can't rely on files it was shown earlier still existing.  Subdirectories are
+    /// kept, as are files the `FixedFilePolicy` set through
+    /// `FileSystemBuilder::with_fixed_file_policy` fixes in place.  The pass ends early if the
+    /// invalid directory entry callback stops it, files deleted until then stay deleted.
+    pub fn remove_where<P>(
+        &self,
+        directory_path: &str,
+        mut predicate: P,
+    ) -> Result<u32, DeviceOperationError<D>>
+    where
+        P: FnMut(&DirectoryItem) -> bool,
+    {
+        let directory = self
+            .find_directory(directory_path)
+            .ok_or(OperationError::NotFound)?;
+        let mut item_iterator = directory.items().with_dot_items_skipped(true);
+        let mut removed_count = 0;
+
+        while let Some(result) = item_iterator.next() {
+            let item = match result {
+                Ok(item) => item,
+                Err(error) => {
+                    if self.report_invalid_directory_entry(error).is_break() {
+                        break;
+                    }
+
+                    continue;
+                }
+            };
+
+            if !item.is_file() || self.is_fixed(directory_path, &item) || !predicate(&item) {
+                continue;
+            }
+
+            self.delete_item(&directory, &item)?;
+            removed_count += 1;
+
+            let mut name_buffer = [0; MAX_NAME_BYTE_LENGTH];
+            let mut name = ItemPath::new(&mut name_buffer);
+            // Every name fits the buffer, the event is only missing a name if one doesn't
+            let _ = name.push(&item);
+
+            self.notify_mutation(MutationEvent::Deleted {
+                directory_path,
+                name: name.as_str(),
+                first_cluster_number: item.first_cluster_number(),
+            });
+        }
+
+        Ok(removed_count)
+    }
+
+    /// The file in the directory with the earliest last write timestamp which isn't fixed in place,
+    /// `None` if there are no such files or the invalid directory entry callback stopped the
+    /// search.
+    fn oldest_file_in(
+        &self,
+        directory: &Directory<'_, D, BUFFER_SIZE>,
+        directory_path: &str,
+    ) -> Option<DirectoryItem> {
+        let mut item_iterator = directory.items().with_dot_items_skipped(true);
+        let mut oldest_item: Option<DirectoryItem> = None;
+
+        while let Some(result) = item_iterator.next() {
+            let item = match result {
+                Ok(item) => item,
+                Err(error) => {
+                    if self.report_invalid_directory_entry(error).is_break() {
+                        return None;
+                    }
+
+                    continue;
+                }
+            };
+
+            if item.is_file()
+                && oldest_item
+                    .as_ref()
+                    .is_none_or(|oldest_item| Self::is_written_after(oldest_item, &item))
+                && !self.is_fixed(directory_path, &item)
+            {
+                oldest_item = Some(item);
+            }
+        }
+
+        oldest_item
+    }
+
+    /// Whether the `FixedFilePolicy` keeps the item in the directory at the given path from being
+    /// deleted or moved by helpers.  Items whose name doesn't fit the buffer are never fixed, as
+    /// no name the policy could match is that long.
+    pub(crate) fn is_fixed(&self, directory_path: &str, item: &DirectoryItem) -> bool {
+        let Some(fixed_file_policy) = self.fixed_file_policy else {
+            return false;
+        };
+
+        let mut name_buffer = [0; MAX_NAME_BYTE_LENGTH];
+        let mut name = ItemPath::new(&mut name_buffer);
+
+        name.push(item).is_ok() && fixed_file_policy(directory_path, name.as_str())
+    }
+
+    /// Releases the item's entries and the clusters holding its contents.
+    pub(crate) fn delete_item(
+        &self,
+        directory: &Directory<'_, D, BUFFER_SIZE>,
+        item: &DirectoryItem,
+    ) -> Result<(), DeviceOperationError<D>> {
+        let location = item.location().ok_or(OperationError::NotFound)?;
+
+        directory.write_entries(
+            location.position(),
+            &[Self::deleted_entry(); MAX_ITEM_ENTRY_COUNT][..location.entry_count() as usize],
+        )?;
+
+        if item.first_cluster_number() != 0 {
+            self.release_cluster_chain(ClusterNumber::new(item.first_cluster_number()))?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn rename_item(
+        &self,
+        directory: &Directory<'_, D, BUFFER_SIZE>,
+        item: &DirectoryItem,
+        new_name: &str,
+    ) -> Result<(), DeviceOperationError<D>> {
+        let location = item.location().ok_or(OperationError::NotFound)?;
+
+        ensure!(
+            new_name != "." && new_name != "..",
+            OperationError::NameInvalid(LongFileNameError::CharacterInvalid {
+                character: '.',
+                offset: 0
+            })
+        );
+
+        let long_name = LongFileName::from_str(new_name)?;
+        let exact_short_name = ShortFileName::from_str_exact(&self.code_page_encoder, new_name);
+
+        let conflicting_item = self.find_item_in(directory, |other_item| {
+            other_item.location() != Some(location)
+                && (other_item.is_match(&self.code_page_encoder, new_name)
+                    || exact_short_name.as_ref() == Some(other_item.short_name()))
+        });
+
+        if let Some(conflicting_item) = conflicting_item {
+            // Names given as short names report the item holding them, since the collision may
+            // be with an alias the caller never saw
+            return Err(match exact_short_name {
+                Some(_) => OperationError::NameCollision {
+                    existing: conflicting_item.short_directory_entry().clone(),
+                },
+                None => OperationError::AlreadyExists,
+            });
+        }
+
+        let short_directory_entry = item.short_directory_entry();
+        let cased_short_name = self
+            .use_short_name_case
+            .then(|| ShortFileName::from_str_with_case(&self.code_page_encoder, new_name))
+            .flatten();
+        let (long_name, short_name, short_name_case) = match (exact_short_name, cased_short_name) {
+            (Some(short_name), _) => (None, short_name, ShortNameCase::empty()),
+            (None, Some((short_name, short_name_case))) => (None, short_name, short_name_case),
+            (None, None) => (
+                Some(long_name),
+                self.short_name_alias_for(directory, new_name, Some(location))?,
+                ShortNameCase::empty(),
+            ),
+        };
+
+        let item_entries = DirectoryItemEntries::builder()
+            .maybe_long_name(long_name)
+            .short_name(short_name)
+            .short_name_case(short_name_case)
+            .attributes(short_directory_entry.attributes())
+            .creation_time_tenths(short_directory_entry.creation_time_tenths())
+            .creation_time(short_directory_entry.creation_time())
+            .creation_date(short_directory_entry.creation_date())
+            .last_access_date(short_directory_entry.last_access_date())
+            .last_write_time(short_directory_entry.last_write_time())
+            .last_write_date(short_directory_entry.last_write_date())
+            .first_cluster_number(short_directory_entry.first_cluster_number())
+            .file_size(short_directory_entry.extended_file_size())
+            .build();
+
+        let new_entries = item_entries.entries();
+        let old_entry_count = location.entry_count() as usize;
+
+        if new_entries.len() <= old_entry_count {
+            // Unused leading slots are released within the same write as the new entries, which
+            // end in the slot of the existing short name entry.
+            let mut entries = [Self::deleted_entry(); MAX_ITEM_ENTRY_COUNT];
+            let unused_entry_count = old_entry_count - new_entries.len();
+
+            entries[unused_entry_count..old_entry_count].copy_from_slice(new_entries);
+            directory.write_entries(location.position(), &entries[..old_entry_count])?;
+        } else {
+            let position = directory
+                .find_free_entries(new_entries.len())?
+                .ok_or(OperationError::DirectoryFull)?;
+
+            directory.write_entries(position, new_entries)?;
+            directory.write_entries(
+                location.position(),
+                &[Self::deleted_entry(); MAX_ITEM_ENTRY_COUNT][..old_entry_count],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Moves the contents and last write timestamp of the temporary item over the target, then
+    /// deletes the temporary item's entry and releases the target's previous clusters.
+    pub(crate) fn replace_item(
+        &self,
+        directory: &Directory<'_, D, BUFFER_SIZE>,
+        target: &DirectoryItem,
+        temporary_item: &DirectoryItem,
+    ) -> Result<(), DeviceOperationError<D>> {
+        let temporary_location = temporary_item.location().ok_or(OperationError::NotFound)?;
+
+        self.replace_item_contents(directory, target, temporary_item)?;
+        directory.write_entries(
+            temporary_location.position(),
+            &[Self::deleted_entry(); MAX_ITEM_ENTRY_COUNT]
+                [..temporary_location.entry_count() as usize],
+        )?;
+
+        if target.first_cluster_number() != 0 {
+            self.release_cluster_chain(ClusterNumber::new(target.first_cluster_number()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Points the item at the replacement's contents by rewriting its entries in a single write,
+    /// keeping its names and metadata other than the last write timestamp.
+    fn replace_item_contents(
+        &self,
+        directory: &Directory<'_, D, BUFFER_SIZE>,
+        item: &DirectoryItem,
+        replacement: &DirectoryItem,
+    ) -> Result<(), DeviceOperationError<D>> {
+        let location = item.location().ok_or(OperationError::NotFound)?;
+        let short_directory_entry = item.short_directory_entry();
+
+        let item_entries = DirectoryItemEntries::builder()
+            .maybe_long_name(item.long_name().cloned())
+            .short_name(short_directory_entry.name().clone())
+            .short_name_case(short_directory_entry.name_case())
+            .attributes(short_directory_entry.attributes())
+            .creation_time_tenths(short_directory_entry.creation_time_tenths())
+            .creation_time(short_directory_entry.creation_time())
+            .creation_date(short_directory_entry.creation_date())
+            .last_access_date(short_directory_entry.last_access_date())
+            .last_write_time(replacement.last_write_time())
+            .last_write_date(replacement.last_write_date())
+            .first_cluster_number(replacement.first_cluster_number())
+            .file_size(replacement.extended_file_size())
+            .build();
+
+        // The names were read from these entries, so the rebuilt entries occupy the same slots
+        directory.write_entries(location.position(), item_entries.entries())?;
+
+        Ok(())
+    }
+
+    /// Finds an unused short name for the given long name, preferring the name without a numeric
+    /// tail when it only differs from the long name by case.
+    ///
+    /// The directory is scanned once, noting which of the candidate names are taken.  Once the
+    /// first `MAX_SHORT_NAME_TAIL_NUMBER` numeric tails are taken, aliases with a hash of the long
+    /// name in place of most of the name are used instead, like Windows does.
+    pub(crate) fn short_name_alias_for(
+        &self,
+        directory: &Directory<'_, D, BUFFER_SIZE>,
+        long_name: &str,
+        excluded_location: Option<EntryLocation>,
+    ) -> Result<ShortFileName, DeviceOperationError<D>> {
+        let plain_short_name = ShortFileName::from_str(&self.code_page_encoder, long_name).ok();
+        let is_plain_short_name_used = Cell::new(false);
+        let used_tail_numbers = RefCell::new([false; MAX_SHORT_NAME_TAIL_NUMBER as usize]);
+        let used_hashed_tail_numbers =
+            RefCell::new([false; MAX_HASHED_SHORT_NAME_TAIL_NUMBER as usize]);
+
+        let search = self.search_items_in(directory, |item| {
+            if excluded_location.is_some() && item.location() == excluded_location {
+                return false;
+            }
+
+            let short_name = item.short_name();
+            if plain_short_name.as_ref() == Some(short_name) {
+                is_plain_short_name_used.set(true);
+            }
+
+            // An alias can only be taken by a name with the same numeric tail
+            if let Some(tail_number) = short_name.tail_number() {
+                let tail_index = tail_number as usize - 1;
+
+                if tail_number <= MAX_SHORT_NAME_TAIL_NUMBER
+                    && ShortFileName::alias_for(&self.code_page_encoder, long_name, tail_number)
+                        == *short_name
+                {
+                    used_tail_numbers.borrow_mut()[tail_index] = true;
+                }
+
+                if tail_number <= MAX_HASHED_SHORT_NAME_TAIL_NUMBER
+                    && ShortFileName::hashed_alias_for(
+                        &self.code_page_encoder,
+                        long_name,
+                        tail_number,
+                    ) == *short_name
+                {
+                    used_hashed_tail_numbers.borrow_mut()[tail_index] = true;
+                }
+            }
+
+            false
+        });
+
+        // A search stopped by the invalid directory entry callback leaves every name in doubt
+        ensure!(
+            matches!(search, ControlFlow::Continue(_)),
+            OperationError::ShortNameAliasesExhausted
+        );
+
+        if let Some(short_name) = plain_short_name
+            && !is_plain_short_name_used.get()
+        {
+            return Ok(short_name);
+        }
+
+        let first_unused_tail_number = |used_tail_numbers: &[bool]| {
+            used_tail_numbers
+                .iter()
+                .position(|is_used| !is_used)
+                .map(|tail_index| tail_index as u32 + 1)
+        };
+
+        if let Some(tail_number) = first_unused_tail_number(&*used_tail_numbers.borrow()) {
+            return Ok(ShortFileName::alias_for(
+                &self.code_page_encoder,
+                long_name,
+                tail_number,
+            ));
+        }
+
+        first_unused_tail_number(&*used_hashed_tail_numbers.borrow())
+            .map(|tail_number| {
+                ShortFileName::hashed_alias_for(&self.code_page_encoder, long_name, tail_number)
+            })
+            .ok_or(OperationError::ShortNameAliasesExhausted)
+    }
+
+    /// Creates an empty temporary file in the directory at the given path, an empty path refers to
+    /// the root directory.
+    ///
+    /// The file is deleted when the returned guard is dropped unless it is persisted under a new
+    /// name first.
+    pub fn tempfile<'a>(
+        &'a self,
+        directory_path: &'a str,
+    ) -> Result<TempFile<'a, D, CPE, IDE, ME, BUFFER_SIZE>, DeviceOperationError<D>> {
+        let directory = self
+            .find_directory(directory_path)
+            .ok_or(OperationError::NotFound)?;
+
+        ensure!(
+            self.is_within_quota(&directory, directory_path, 1, 0),
+            OperationError::QuotaExceeded
+        );
+
+        let short_name = self.short_name_alias_for(&directory, STAGED_FILE_NAME, None)?;
+        let position = directory
+            .find_free_entries(1)?
+            .ok_or(OperationError::DirectoryFull)?;
+
+        let item_entries = DirectoryItemEntries::builder()
+            .short_name(short_name.clone())
+            .attributes(DirectoryEntryAttributes::Archive)
+            .build();
+        directory.write_entries(position, item_entries.entries())?;
+
+        Ok(TempFile::new(
+            self,
+            directory,
+            directory_path,
+            EntryLocation::new(position, 1),
+            short_name,
+        ))
+    }
+
+    /// Stores the contents in newly allocated clusters, returning the first and last cluster numbers
+    /// of the chain or `ClusterNumber::NONE` for both if the contents are empty.  Clusters are
+    /// released again if the contents don't fit.
+    pub(crate) fn write_cluster_chain(
+        &self,
+        contents: &[u8],
+    ) -> Result<(ClusterNumber, ClusterNumber), DeviceOperationError<D>> {
+        let bytes_per_cluster = self.bios_parameter_block.bytes_per_cluster() as usize;
+        let volume_last_cluster_number = self.bios_parameter_block.last_cluster_number();
+
+        let mut first_cluster_number = ClusterNumber::NONE;
+        let mut last_cluster_number = ClusterNumber::NONE;
+
+        let result = self
+            .device
+            .with_stream(|stream| -> Result<(), DeviceOperationError<D>> {
+                let mut previous_cluster_number = None;
+
+                for cluster_contents in contents.chunks(bytes_per_cluster) {
+                    let cluster_number = self
+                        .allocation_table
+                        .find_free_cluster(
+                            stream,
+                            previous_cluster_number
+                                .map_or(ClusterNumber::FIRST_DATA_CLUSTER, ClusterNumber::next),
+                            volume_last_cluster_number,
+                        )?
+                        .ok_or(OperationError::VolumeFull)?;
+
+                    stream.seek(SeekFrom::Start(
+                        self.cluster_address(cluster_number)?.value(),
+                    ))?;
+                    stream.write_all(cluster_contents)?;
+
+                    // Clusters are only linked once they hold their contents and end the chain
+                    self.allocation_table.write_entry(
+                        stream,
+                        cluster_number,
+                        AllocationTableEntry::EndOfFile,
+                    )?;
+
+                    match previous_cluster_number {
+                        Some(previous_cluster_number) => self.allocation_table.write_entry(
+                            stream,
+                            previous_cluster_number,
+                            AllocationTableEntry::NextClusterNumber(cluster_number),
+                        )?,
+                        None => first_cluster_number = cluster_number,
+                    }
+
+                    previous_cluster_number = Some(cluster_number);
+                    last_cluster_number = cluster_number;
+                }
+
+                Ok(())
+            })
+            .map_err(OperationError::DeviceError)
+            .flatten();
+
+        if let Err(error) = result {
+            if first_cluster_number != ClusterNumber::NONE {
+                self.release_cluster_chain(first_cluster_number)?;
+            }
+
+            return Err(error);
+        }
+
+        Ok((first_cluster_number, last_cluster_number))
+    }
+
+    /// Appends the contents to a chain holding `file_size` bytes, filling the unused end of its last
+    /// cluster before allocating new clusters.  Returns the first and last cluster numbers of the
+    /// extended chain, an empty chain is given as `ClusterNumber::NONE` for both.
+    pub(crate) fn append_to_cluster_chain(
+        &self,
+        first_cluster_number: ClusterNumber,
+        last_cluster_number: ClusterNumber,
+        file_size: u64,
+        contents: &[u8],
+    ) -> Result<(ClusterNumber, ClusterNumber), DeviceOperationError<D>> {
+        let bytes_per_cluster = self.bios_parameter_block.bytes_per_cluster();
+        let last_cluster_offset = (file_size % bytes_per_cluster as u64) as u32;
+
+        let mut remaining_contents = contents;
+        if last_cluster_number != ClusterNumber::NONE && last_cluster_offset != 0 {
+            let tail_length = remaining_contents
+                .len()
+                .min((bytes_per_cluster - last_cluster_offset) as usize);
+            let tail_address = self
+                .cluster_address(last_cluster_number)?
+                .offset_by(last_cluster_offset as u64);
+
+            self.device
+                .with_stream(|stream| -> Result<(), DeviceOperationError<D>> {
+                    stream.seek(SeekFrom::Start(tail_address.value()))?;
+                    stream.write_all(&remaining_contents[..tail_length])?;
+
+                    Ok(())
+                })
+                .map_err(OperationError::DeviceError)??;
+
+            remaining_contents = &remaining_contents[tail_length..];
+        }
+
+        let (appended_first_cluster_number, appended_last_cluster_number) =
+            self.write_cluster_chain(remaining_contents)?;
+
+        if appended_first_cluster_number == ClusterNumber::NONE {
+            return Ok((first_cluster_number, last_cluster_number));
+        }
+
+        if last_cluster_number == ClusterNumber::NONE {
+            return Ok((appended_first_cluster_number, appended_last_cluster_number));
+        }
+
+        self.device
+            .with_stream(|stream| {
+                self.allocation_table.write_entry(
+                    stream,
+                    last_cluster_number,
+                    AllocationTableEntry::NextClusterNumber(appended_first_cluster_number),
+                )
+            })
+            .map_err(OperationError::DeviceError)??;
+
+        Ok((first_cluster_number, appended_last_cluster_number))
+    }
+
+    /// Marks every cluster of the chain starting at the given cluster number as free.
+    pub(crate) fn release_cluster_chain(
+        &self,
+        first_cluster_number: ClusterNumber,
+    ) -> Result<(), DeviceOperationError<D>> {
+        let last_cluster_number = self.bios_parameter_block.last_cluster_number();
+
+        self.device
+            .with_stream(|stream| {
+                self.allocation_table.release_chain(
+                    stream,
+                    first_cluster_number,
+                    last_cluster_number,
+                )
+            })
+            .map_err(OperationError::DeviceError)??;
+
+        Ok(())
+    }
+
+    /// The number of clusters of the data region not allocated to any file or directory.
+    pub(crate) fn count_free_clusters(&self) -> Result<u32, DeviceOperationError<D>> {
+        let last_cluster_number = self.bios_parameter_block.last_cluster_number();
+
+        let free_cluster_count = self
+            .device
+            .with_stream(|stream| {
+                self.allocation_table
+                    .count_free_clusters(stream, last_cluster_number)
+            })
+            .map_err(OperationError::DeviceError)??;
+
+        Ok(free_cluster_count)
+    }
+
+    pub(crate) fn deleted_entry() -> [u8; DIRECTORY_ENTRY_SIZE] {
+        let mut entry = [0; DIRECTORY_ENTRY_SIZE];
+        entry[0] = DELETED_ENTRY_MARKER;
+
+        entry
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<D, S, CPE, IDE, ME, const N: usize, const BUFFER_SIZE: usize>
+    FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: SyncDevice<Stream = PrefetchingStream<S, N>>,
+    S: Read + Seek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    /// Holds the first allocation table in memory, as much of it as fits into the stream's
+    /// capacity, returning how many bytes are held.
+    ///
+    /// Meant to be called once after mounting, so that cluster chain walks and seeks within files
+    /// no longer read from the device.
+    pub fn prefetch_allocation_table(&self) -> Result<usize, DeviceOperationError<D>> {
+        let base_address = self.bios_parameter_block.allocation_table_base_address();
+        let length = self.bios_parameter_block.bytes_per_allocation_table();
+
+        self.device
+            .with_stream(|stream| -> Result<usize, DeviceOperationError<D>> {
+                Ok(stream.prefetch(base_address.value(), length)?)
+            })
+            .map_err(OperationError::DeviceError)?
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<D, S, CPE, IDE, ME, const BUFFER_SIZE: usize> FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: SyncFlushableDevice<Stream = S>,
+    S: Read + Write + Seek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    /// Flushes the device and releases the volume so another host, such as a USB mass storage
+    /// host, can use the medium until the file system is resumed.
+    ///
+    /// Taking the file system by value ensures no files or directories remain open.  The file
+    /// system is returned along with the error if flushing fails.
+    #[allow(clippy::result_large_err)]
+    pub fn suspend(self) -> SuspendResult<D, CPE, IDE, ME, BUFFER_SIZE> {
+        match self.device.flush() {
+            Ok(()) => Ok(self.into_suspended()),
+            Err(error) => Err((self, error)),
+        }
+    }
+
+    /// Converts the FAT16 volume to FAT32 in place, for upgrading volumes in the field, and
+    /// releases it like `suspend` so resuming mounts the converted volume.
+    ///
+    /// Clusters stay where they are, so no file or directory is moved: the root directory table is
+    /// copied into newly allocated clusters and the allocation table is rewritten with 32-bit
+    /// entries into the sectors preceding the data region.  Two copies of the table are kept when
+    /// they fit there, otherwise one, failing with `SpaceInsufficient` if not even one does.  Hosts
+    /// recognize the converted volume as FAT32 by its boot sector, even though it has fewer
+    /// clusters than volumes formatted as FAT32.
+    ///
+    /// Volumes which can't be converted are left untouched.  The conversion isn't safe against
+    /// power loss, an interruption while the allocation table is rewritten leaves a volume which no
+    /// longer mounts, so the volume should be backed up beforehand.  The file system is returned
+    /// along with the error if converting fails.
+    #[cfg(all(feature = "fat16", feature = "fat32"))]
+    #[allow(clippy::result_large_err)]
+    pub fn convert_to_fat32(self) -> ConversionResult<D, CPE, IDE, ME, BUFFER_SIZE> {
+        if let Err(error) = self.rewrite_as_fat32() {
+            return Err((self, error));
+        }
+
+        match self.device.flush() {
+            Ok(()) => Ok(self.into_suspended()),
+            Err(error) => Err((self, OperationError::DeviceError(error).into())),
+        }
+    }
+
+    /// Opens the file at the given path for writing through a guard which records the file's new
+    /// size in its directory entry when flushed, closed or dropped.
+    pub fn open_writer<'a>(
+        &'a self,
+        path: &'a str,
+    ) -> Result<FileWriteGuard<'a, D, CPE, IDE, ME, BUFFER_SIZE>, DeviceOperationError<D>> {
+        let (directory, directory_path, name) = self
+            .find_parent_directory(path)
+            .ok_or(OperationError::NotFound)?;
+        let item = self
+            .find_item_named(&directory, name)
+            .ok_or(OperationError::NotFound)?;
+        let location = item.location().ok_or(OperationError::NotFound)?;
+        let file = self
+            .file_for(&item)
+            .ok_or(OperationError::NotAFile)?
+            .with_open_options(OpenOptions::builder().write(true).build());
+
+        Ok(FileWriteGuard::new(
+            self,
+            directory,
+            directory_path,
+            name,
+            item,
+            location,
+            file,
+        ))
+    }
+
+    /// Replaces the contents of the file at the given path, creating it if it doesn't exist.
+    ///
+    /// The contents are written to a temporary file in the same directory and flushed before being
+    /// renamed over the target, so an interrupted replace leaves the target with either its
+    /// previous or its new contents.
+    pub fn write_replace(
+        &self,
+        path: &str,
+        contents: &[u8],
+    ) -> Result<(), DeviceOperationError<D>> {
+        let file_size = u64::try_from(contents.len())
+            .ok()
+            .filter(|file_size| *file_size <= MAX_FILE_SIZE)
+            .ok_or(OperationError::ContentsTooLarge)?;
+        let (directory, directory_path, name) = self
+            .find_parent_directory(path)
+            .ok_or(OperationError::NotFound)?;
+        let target = self.find_item_named(&directory, name);
+
+        if let Some(target) = &target {
+            ensure!(target.is_file(), OperationError::NotAFile);
+        } else {
+            LongFileName::from_str(name)?;
+        }
+
+        let replaced_file_size = target
+            .as_ref()
+            .map_or(0, |target| u64::from(target.file_size()));
+        ensure!(
+            self.is_within_quota(
+                &directory,
+                directory_path,
+                u64::from(target.is_none()),
+                file_size.saturating_sub(replaced_file_size),
+            ),
+            OperationError::QuotaExceeded
+        );
+
+        let temporary_short_name =
+            self.short_name_alias_for(&directory, TEMPORARY_FILE_NAME, None)?;
+        let temporary_position = directory
+            .find_free_entries(1)?
+            .ok_or(OperationError::DirectoryFull)?;
+
+        let (first_cluster_number, _) = self.write_cluster_chain(contents)?;
+        let temporary_entries = DirectoryItemEntries::builder()
+            .short_name(temporary_short_name.clone())
+            .attributes(DirectoryEntryAttributes::Archive)
+            .maybe_last_write_time(target.as_ref().map(DirectoryItem::last_write_time))
+            .maybe_last_write_date(target.as_ref().map(DirectoryItem::last_write_date))
+            .first_cluster_number(first_cluster_number.value())
+            .file_size(file_size)
+            .build();
+
+        if let Err(error) = directory.write_entries(temporary_position, temporary_entries.entries())
+        {
+            if first_cluster_number != ClusterNumber::NONE {
+                self.release_cluster_chain(first_cluster_number)?;
+            }
+
+            return Err(error.into());
+        }
+
+        self.device.flush().map_err(OperationError::DeviceError)?;
+
+        let temporary_item = self
+            .find_item_in(&directory, |item| {
+                item.short_name() == &temporary_short_name
+            })
+            .ok_or(OperationError::NotFound)?;
+
+        match &target {
+            Some(target) => self.replace_item(&directory, target, &temporary_item)?,
+            None => self.rename_item(&directory, &temporary_item, name)?,
+        }
+
+        self.device.flush().map_err(OperationError::DeviceError)?;
+
+        if target.is_none() {
+            self.notify_mutation(MutationEvent::Created {
+                directory_path,
+                name,
+                first_cluster_number: first_cluster_number.value(),
+            });
+        }
+
+        self.notify_mutation(MutationEvent::WriteCompleted {
+            directory_path,
+            name,
+            first_cluster_number: first_cluster_number.value(),
+            file_size,
+        });
+
+        Ok(())
+    }
+
+    /// Copies the file at the source path of another volume to the destination path, unless the
+    /// destination already holds a file with a later last write timestamp, or the same timestamp
+    /// and size.  Returns whether the file was copied.
+    ///
+    /// The copy takes on the source's last write timestamp, so mirroring a directory by calling
+    /// this for each of its files only copies those changed since the last run.  Contents are
+    /// staged in a temporary file through the buffer and flushed before replacing the destination,
+    /// so an interrupted copy leaves the destination with either its previous or its new contents.
+    pub fn copy_if_newer<SD, SS, SCPE, SIDE, SME>(
+        &self,
+        source: &FileSystem<SD, SCPE, SIDE, SME, BUFFER_SIZE>,
+        source_path: &str,
+        destination_path: &str,
+        buffer: &mut [u8],
+    ) -> Result<bool, DeviceCopyError<SD, D>>
+    where
+        SD: SyncDevice<Stream = SS>,
+        SS: Read + Seek,
+        SCPE: CodePageEncoder,
+        SIDE: InvalidDirectoryEntryHandler<SD>,
+        SME: Fn(MutationEvent<'_>),
+    {
+        ensure!(!buffer.is_empty(), CopyError::BufferEmpty);
+
+        let source_item = source
+            .find_item(source_path)
+            .ok_or(CopyError::SourceNotFound)?;
+        let mut source_file = source
+            .file_for(&source_item)
+            .ok_or(CopyError::SourceNotAFile)?;
+        // FAT dates and times order chronologically when compared numerically
+        let source_last_write = (source_item.last_write_date(), source_item.last_write_time());
+
+        let (directory, directory_path, name) = self
+            .find_parent_directory(destination_path)
+            .ok_or(CopyError::DestinationError(OperationError::NotFound))?;
+
+        if let Some(target) = self.find_item_named(&directory, name) {
+            ensure!(
+                target.is_file(),
+                CopyError::DestinationError(OperationError::NotAFile)
+            );
+
+            let target_last_write = (target.last_write_date(), target.last_write_time());
+            if target_last_write > source_last_write
+                || (target_last_write == source_last_write
+                    && target.extended_file_size() == source_item.extended_file_size())
+            {
+                return Ok(false);
+            }
+        }
+
+        let mut temp_file = self
+            .tempfile(directory_path)
+            .map_err(CopyError::DestinationError)?;
+
+        loop {
+            let read = source_file.read(buffer).map_err(CopyError::SourceError)?;
+            if read == 0 {
+                break;
+            }
+
+            temp_file
+                .append(&buffer[..read])
+                .map_err(CopyError::DestinationError)?;
+        }
+
+        temp_file
+            .set_last_write(source_last_write.0, source_last_write.1)
+            .map_err(CopyError::DestinationError)?;
+        self.device
+            .flush()
+            .map_err(|error| CopyError::DestinationError(OperationError::DeviceError(error)))?;
+
+        temp_file
+            .persist_replacing(name)
+            .map_err(|(_, error)| CopyError::DestinationError(error))?;
+        self.device
+            .flush()
+            .map_err(|error| CopyError::DestinationError(OperationError::DeviceError(error)))?;
+
+        Ok(true)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, S, CPE> FileSystem<D, CPE>
+where
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncSeek,
+    CPE: CodePageEncoder,
+{
+    /// Mounts the volume on the device, skipping invalid directory entries and reporting no
+    /// mutations.
+    pub async fn from_device_async(
+        device: D,
+        code_page_encoder: CPE,
+    ) -> Result<Self, FileSystemError<D::Error, S::Error>> {
+        Self::new_async(device, code_page_encoder, IgnoreInvalidEntries, |_| {}).await
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, S, CPE, IDE, ME, const BUFFER_SIZE: usize> FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncSeek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    pub async fn new_async(
+        device: D,
+        code_page_encoder: CPE,
+        on_invalid_directory_entry: IDE,
+        on_mutation: ME,
+    ) -> Result<Self, FileSystemError<D::Error, S::Error>> {
+        let bios_parameter_block = Self::read_bios_parameter_block_async(&device).await?;
+
+        Ok(Self::from_parts(
+            device,
+            code_page_encoder,
+            on_invalid_directory_entry,
+            on_mutation,
+            None,
+            None,
+            true,
+            0,
+            InvalidTimestampPolicy::default(),
+            &[],
+            bios_parameter_block,
+            DEFAULT_YIELD_INTERVAL,
+            false,
+        ))
+    }
+
+    async fn read_bios_parameter_block_async(
+        device: &D,
+    ) -> Result<BiosParameterBlock, FileSystemError<D::Error, S::Error>> {
+        let mut boot_sector_bytes = [0; 512];
+
+        device
+            .with_stream(
+                async |stream| -> Result<(), FileSystemError<D::Error, S::Error>> {
+                    stream.seek(SeekFrom::Start(0)).await?;
+
+                    stream.read_exact(&mut boot_sector_bytes).await?;
+
+                    Ok(())
+                },
+            )
+            .await
+            .map_err(FileSystemError::DeviceError)??;
+
+        Self::validate_boot_sector_signature(&boot_sector_bytes)?;
+
+        Ok(BiosParameterBlock::from_boot_sector(&boot_sector_bytes)?)
+    }
+
+    /// Reads the volume's boot sector and allocation table again after the device was handed to
+    /// another host, failing if they no longer describe a supported volume.
+    async fn remount_bios_parameter_block_async(
+        device: &D,
+    ) -> Result<BiosParameterBlock, FileSystemError<D::Error, S::Error>> {
+        let bios_parameter_block = Self::read_bios_parameter_block_async(device).await?;
+        let mut allocation_table_media_type = [0; 1];
+
+        device
+            .with_stream(
+                async |stream| -> Result<(), FileSystemError<D::Error, S::Error>> {
+                    stream
+                        .seek(SeekFrom::Start(
+                            bios_parameter_block.allocation_table_base_address().value(),
+                        ))
+                        .await?;
+
+                    stream.read_exact(&mut allocation_table_media_type).await?;
+
+                    Ok(())
+                },
+            )
+            .await
+            .map_err(FileSystemError::DeviceError)??;
+
+        Self::validate_allocation_table_media_type(
+            &bios_parameter_block,
+            allocation_table_media_type[0],
+        )?;
+
+        Ok(bios_parameter_block)
+    }
+
+    /// Runs the sanity pass of `FileSystemBuilder::with_self_test`, reading the structures
+    /// `HealthSummary` describes.
+    async fn self_test_async(&self) -> Result<HealthSummary, FileSystemError<D::Error, S::Error>> {
+        let invalid_timestamp_count = Dir::new(self.root_directory(), self.yield_interval)
+            .invalid_timestamp_count_async()
+            .await;
+        let bios_parameter_block = &self.bios_parameter_block;
+        let mut health_summary = HealthSummary::new(
+            invalid_timestamp_count.is_ok(),
+            invalid_timestamp_count.unwrap_or(0),
+        );
+        let mut sector_bytes = [0; 512];
+        let mut reserved_entries_bytes = [0; RESERVED_ENTRIES_SIZE];
+
+        self.device
+            .with_stream(
+                async |stream| -> Result<(), FileSystemError<D::Error, S::Error>> {
+                    if let Some(address) = bios_parameter_block.backup_boot_sector_base_address() {
+                        stream.seek(SeekFrom::Start(address.value())).await?;
+                        stream.read_exact(&mut sector_bytes).await?;
+
+                        health_summary
+                            .check_backup_boot_sector(bios_parameter_block, &sector_bytes);
+                    }
+
+                    if let Some(address) = bios_parameter_block.fs_info_base_address() {
+                        stream.seek(SeekFrom::Start(address.value())).await?;
+                        stream.read_exact(&mut sector_bytes).await?;
+
+                        health_summary.check_fs_info(bios_parameter_block, &sector_bytes);
+                    }
+
+                    for table_index in 0..bios_parameter_block.allocation_table_count() {
+                        stream
+                            .seek(SeekFrom::Start(Self::allocation_table_copy_address(
+                                bios_parameter_block,
+                                table_index,
+                            )))
+                            .await?;
+                        stream.read_exact(&mut reserved_entries_bytes).await?;
+
+                        health_summary.check_reserved_entries(
+                            bios_parameter_block,
+                            &ReservedEntries::from_bytes(
+                                bios_parameter_block.allocation_table_kind(),
+                                &reserved_entries_bytes,
+                            ),
+                            table_index == bios_parameter_block.active_allocation_table_index(),
+                        );
+                    }
+
+                    Ok(())
+                },
+            )
+            .await
+            .map_err(FileSystemError::DeviceError)??;
+
+        Ok(health_summary)
+    }
+
+    /// Checks that the medium the file system was mounted from is still in place by reading its
+    /// boot sector again, which is cheap enough to call before each batch of work on removable
+    /// media.
+    ///
+    /// Fails with `MediaChanged` once the boot sector's signature or volume serial number no
+    /// longer match, from then on files opened from the file system fail with
+    /// `FileError::MediaChanged` rather than touching the new medium.  Suspending and resuming the
+    /// file system mounts the new medium, a `PathCache` or `DirSnapshot` built before should be
+    /// cleared.
+    pub async fn revalidate_async(&self) -> Result<(), DeviceOperationError<D>> {
+        revalidate_body!(async_io, self)
+    }
+
+    /// Opens the file at the given path for reading, files are written through
+    /// `open_writer_async`.
+    pub async fn open_async(&self, file_path: &str) -> Option<File<'_, D>> {
+        self.file_for(&self.find_item_async(file_path).await?)
+    }
+
+    /// Lists the items of the directory at the given path, an empty path refers to the root
+    /// directory.
+    pub async fn read_dir_async(&self, directory_path: &str) -> Option<Dir<'_, D, BUFFER_SIZE>> {
+        Some(Dir::new(
+            self.find_directory_async(directory_path).await?,
+            self.yield_interval,
+        ))
+    }
+
+    /// Resolves the path to the names the items along it are stored under, built in the buffer
+    /// with components separated by `/`.  An empty path refers to the root directory.
+    ///
+    /// Names are matched as by `open` and written as by `find_all`, so differently cased paths to
+    /// the same item canonicalize to the same path.  Empty and `.` components are skipped, `..`
+    /// components step back to the parent directory and leave the root directory unchanged.
+    pub async fn canonicalize_async<'b>(
+        &self,
+        path: &str,
+        path_buffer: &'b mut [u8],
+    ) -> Result<&'b str, CanonicalizeError> {
+        let mut canonical_path = ItemPath::new(path_buffer);
+        self.resolve_async(path, &mut canonical_path).await?;
+
+        Ok(canonical_path.into_str())
+    }
+
+    /// Indexes the cluster chain of the file at the given path into memory, up to `N` clusters of
+    /// it, for opening through `open_pinned`.
+    pub async fn pin_async<const N: usize>(
+        &self,
+        file_path: &str,
+    ) -> Result<PinnedFile<N>, DeviceOperationError<D>> {
+        pin_body!(async_io, self, file_path)
+    }
+
+    /// Opens the file at the given path, looking it up in the cache before walking the directory
+    /// tree and recording it in the cache when it was walked.
+    ///
+    /// Entries aren't updated as the volume changes, see [`PathCache`] for invalidating them.
+    pub async fn open_cached_async<const N: usize>(
+        &self,
+        cache: &mut PathCache<N>,
+        file_path: &str,
+    ) -> Option<File<'_, D>> {
+        if let Some(entry) = cache.get(file_path) {
+            return self.file_for_cached(entry);
+        }
+
+        let mut path_buffer = [0; MAX_CACHED_PATH_LENGTH];
+        let mut canonical_path = ItemPath::new(&mut path_buffer);
+
+        match self.resolve_async(file_path, &mut canonical_path).await {
+            Ok(item) => {
+                let item = item?;
+                cache.insert(canonical_path.as_str(), &item);
+
+                self.file_for(&item)
+            }
+            // Paths too long to be cached are still opened
+            Err(CanonicalizeError::PathBufferTooSmall) => self.open_async(file_path).await,
+            Err(_) => None,
+        }
+    }
+
+    /// Walks the path, building its canonical form and returning the item its last component
+    /// refers to.  No item is returned for paths ending at the root directory or a `..` component.
+    async fn resolve_async(
+        &self,
+        path: &str,
+        canonical_path: &mut ItemPath<'_>,
+    ) -> Result<Option<DirectoryItem>, CanonicalizeError> {
+        let mut yield_budget = YieldBudget::new(self.yield_interval);
+        let mut current_directory = Some(self.root_directory());
+        let mut current_item = None;
+
+        let mut next_component_offset = 0;
+
+        for component in path.split("/") {
+            let component_offset = next_component_offset;
+            next_component_offset += component.len() + 1;
+
+            if component.is_empty() || component == "." {
+                continue;
+            }
+
+            let directory = current_directory
+                .as_ref()
+                .ok_or(CanonicalizeError::NotADirectory { component_offset })?;
+
+            if component == ".." {
+                // The root directory is its own parent and has no `..` item to follow
+                if canonical_path.len() > 0 {
+                    let parent_item = self
+                        .find_item_in_async(directory, DirectoryItem::is_dotdot, &mut yield_budget)
+                        .await
+                        .ok_or(CanonicalizeError::NotFound { component_offset })?;
+
+                    current_directory = self.directory_for(&parent_item);
+                    canonical_path.pop();
+                }
+
+                current_item = None;
+                continue;
+            }
+
+            let query = NameQuery::new(&self.code_page_encoder, component);
+            let item = self
+                .find_item_in_async(directory, |item| query.matches(item), &mut yield_budget)
+                .await
+                .ok_or(CanonicalizeError::NotFound { component_offset })?;
+
+            canonical_path.push(&item)?;
+            current_directory = self.directory_for(&item);
+            current_item = Some(item);
+        }
+
+        Ok(current_item)
+    }
+
+    /// Records every item of the volume, up to `MAX_DEPTH` levels of directories with the root as
+    /// the first, along with the CRC-32 of each file's contents if `include_hashes` is set.
+    ///
+    /// Invalid entries are reported to the invalid directory entry callback and skipped, the walk
+    /// ends early if the callback returns `ControlFlow::Break`.
+    #[cfg(feature = "alloc")]
+    pub async fn inventory_async<const MAX_DEPTH: usize>(
+        &self,
+        include_hashes: bool,
+    ) -> Result<Inventory, DeviceHashError<D>> {
+        inventory_body!(async_io, self, include_hashes)
+    }
+
+    /// Writes an indented listing of every item of the volume, up to `MAX_DEPTH` levels of
+    /// directories with the root as the first, for debug consoles and support dumps.
+    ///
+    /// Each item takes a line indented by two spaces per level, with directories suffixed by `/`
+    /// and files followed by their size in bytes, then the item's last write timestamp.  Invalid
+    /// entries are reported to the invalid directory entry callback and skipped, the listing ends
+    /// early if the callback returns `ControlFlow::Break`.
+    pub async fn dump_tree_async<const MAX_DEPTH: usize, W>(
+        &self,
+        writer: &mut W,
+    ) -> core::fmt::Result
+    where
+        W: core::fmt::Write,
+    {
+        dump_tree_body!(async_io, self, writer)
+    }
+
+    /// Walks every directory of the volume, up to `MAX_DEPTH` levels of them with the root as the
+    /// first, and calls `on_match` with the path and item of each item the predicate accepts.
+    /// Returning `ControlFlow::Break` from `on_match` ends the search early.
+    ///
+    /// Paths are built in the buffer from long names where present, separated by `/`, so they can
+    /// be passed back to `open`.  Invalid entries are reported to the invalid directory entry
+    /// callback and skipped, the search ends early if the callback returns `ControlFlow::Break`.
+    pub async fn find_all_async<const MAX_DEPTH: usize, P, F>(
+        &self,
+        path_buffer: &mut [u8],
+        mut predicate: P,
+        mut on_match: F,
+    ) -> Result<(), SearchError>
+    where
+        P: FnMut(&DirectoryItem) -> bool,
+        F: FnMut(&str, &DirectoryItem) -> ControlFlow<()>,
+    {
+        find_all_body!(async_io, self, path_buffer, predicate, on_match)
+    }
+
+    /// Compares every item of the directory at the given path against every other, calling
+    /// `on_conflict` with each pair which a lookup by name can't tell apart.  Returning
+    /// `ControlFlow::Break` from `on_conflict` ends the scan early.
+    ///
+    /// Each item is compared against the items following it, so the directory is read once per
+    /// item rather than held in memory.  Invalid entries are reported to the invalid directory
+    /// entry callback and skipped, the scan ends early if the callback returns
+    /// `ControlFlow::Break`.
+    pub async fn find_name_conflicts_async<F>(
+        &self,
+        directory_path: &str,
+        mut on_conflict: F,
+    ) -> Result<(), DeviceOperationError<D>>
+    where
+        F: FnMut(NameConflict<'_>) -> ControlFlow<()>,
+    {
+        find_name_conflicts_body!(async_io, self, directory_path, on_conflict)
+    }
+
+    /// Finds the file in the directory whose name matches the pattern and which was written last,
+    /// as `latest` does.
+    pub async fn latest_async(&self, path_glob: &str) -> Option<DirectoryItem> {
+        latest_body!(async_io, self, path_glob)
+    }
+
+    /// The label of the volume held by its root directory, `None` if the volume has no label.
+    ///
+    /// The label kept in the boot sector isn't used, since hosts only update the root directory's
+    /// copy when relabelling a volume.
+    pub async fn volume_label_async(&self) -> Option<VolumeLabel> {
+        let mut item_iterator = self.root_directory().items();
+
+        while let Some(result) = item_iterator.next_async().await {
+            match result {
+                Ok(item) if item.is_volume_label() => {
+                    return Some(VolumeLabel::new(*item.short_name().bytes()));
+                }
+                Ok(_) => {}
+                Err(error) => {
+                    if self.report_invalid_directory_entry(error).is_break() {
+                        return None;
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Gathers the free, allocated, bad and reserved cluster counts and the longest run of free
+    /// clusters recorded by the copy of the allocation table at the given index, the first being
+    /// the one in use.
+    ///
+    /// The table is read in a single pass, so comparing the statistics of each copy is a cheap
+    /// check of whether they still agree.
+    pub async fn allocation_table_statistics_async(
+        &self,
+        table_index: u8,
+    ) -> Result<AllocationTableStatistics, DeviceOperationError<D>> {
+        let allocation_table = self
+            .allocation_table
+            .copy(table_index)
+            .ok_or(OperationError::AllocationTableIndexInvalid { table_index })?;
+        let last_cluster_number = self.bios_parameter_block.last_cluster_number();
+
+        self.device
+            .with_stream(
+                async |stream| -> Result<AllocationTableStatistics, DeviceOperationError<D>> {
+                    Ok(allocation_table
+                        .statistics_async(stream, last_cluster_number)
+                        .await?)
+                },
+            )
+            .await
+            .map_err(OperationError::DeviceError)?
+    }
+
+    /// Reads the FS information sector of a FAT32 volume, see `fs_info`.
+    pub async fn fs_info_async(&self) -> Result<Option<FsInfo>, DeviceOperationError<D>> {
+        let Some(address) = self.bios_parameter_block.fs_info_base_address() else {
+            return Ok(None);
+        };
+
+        let mut sector_bytes = [0; 512];
+
+        self.device
+            .with_stream(async |stream| -> Result<(), DeviceOperationError<D>> {
+                stream.seek(SeekFrom::Start(address.value())).await?;
+                stream.read_exact(&mut sector_bytes).await?;
+
+                Ok(())
+            })
+            .await
+            .map_err(OperationError::DeviceError)??;
+
+        Ok(Some(FsInfo::from_sector(&sector_bytes)?))
+    }
+
+    /// Reads a cluster of the data region into the buffer, which must be exactly
+    /// [`bytes_per_cluster`](Self::bytes_per_cluster) long, bypassing the file layer.
+    #[cfg(feature = "dangerous")]
+    pub async fn read_cluster_async(
+        &self,
+        cluster_number: u32,
+        buffer: &mut [u8],
+    ) -> Result<(), DeviceOperationError<D>> {
+        let address = self.raw_cluster_address(cluster_number, buffer.len())?;
+
+        self.device
+            .with_stream(async |stream| -> Result<(), DeviceOperationError<D>> {
+                stream.seek(SeekFrom::Start(address.value())).await?;
+                stream.read_exact(buffer).await?;
+
+                Ok(())
+            })
+            .await
+            .map_err(OperationError::DeviceError)?
+    }
+
+    /// Streams the contents of the file at the given path through the hasher, a chunk at a time,
+    /// so files of any size can be verified without holding them in memory.
+    pub async fn hash_async<H>(
+        &self,
+        file_path: &str,
+        hasher: &mut H,
+    ) -> Result<(), DeviceHashError<D>>
+    where
+        H: Hasher,
+    {
+        hash_body!(async_io, self, file_path, hasher)
+    }
+
+    /// Verifies the volume against a manifest of `(path, size, crc32)` records, calling
+    /// `on_mismatch` with each difference found.  Returning `ControlFlow::Break` from `on_mismatch`
+    /// ends the verification early.
+    ///
+    /// Listed files are looked up as `open` does, their contents are only hashed if their size
+    /// matches.  Files on the volume which no record lists are reported as `Extra`, searching up to
+    /// `MAX_DEPTH` levels of directories with the root as the first.  Their paths are built in the
+    /// buffer from long names where present and compared against the listed paths ignoring ASCII
+    /// case.  The manifest is iterated again for each file on the volume rather than held in
+    /// memory.
+    pub async fn verify_manifest_async<'m, const MAX_DEPTH: usize, M, F>(
+        &self,
+        manifest: M,
+        path_buffer: &mut [u8],
+        mut on_mismatch: F,
+    ) -> Result<(), DeviceManifestError<D>>
+    where
+        M: IntoIterator<Item = (&'m str, u64, u32)> + Clone,
+        F: FnMut(ManifestMismatch<'_>) -> ControlFlow<()>,
+    {
+        verify_manifest_body!(async_io, self, manifest, path_buffer, on_mismatch)
+    }
+
+    /// Writes a copy of the volume's metadata, the `metadata_size` bytes holding the boot sector,
+    /// FS information sector and allocation tables, so they can be put back by `restore_metadata`
+    /// should a risky operation leave them damaged.
+    ///
+    /// Directories and file contents aren't part of the backup, restoring an old backup loses
+    /// track of clusters allocated since and frees clusters released since.
+    pub async fn backup_metadata_async<W>(
+        &self,
+        writer: &mut W,
+    ) -> Result<(), DeviceMetadataError<D, W::Error>>
+    where
+        W: AsyncWrite,
+    {
+        backup_metadata_body!(async_io, self, writer)
+    }
+
+    async fn read_volume_async(
+        &self,
+        address: u64,
+        buffer: &mut [u8],
+    ) -> Result<(), DeviceOperationError<D>> {
+        self.device
+            .with_stream(async |stream| -> Result<(), DeviceOperationError<D>> {
+                stream.seek(SeekFrom::Start(address)).await?;
+                stream.read_exact(buffer).await?;
+
+                Ok(())
+            })
+            .await
+            .map_err(OperationError::DeviceError)?
+    }
+
+    async fn hash_file_async<H>(
+        &self,
+        file: &mut File<'_, D>,
+        hasher: &mut H,
+    ) -> Result<(), FileError<D::Error, S::Error>>
+    where
+        H: Hasher,
+    {
+        hash_file_body!(async_io, self, file, hasher)
+    }
+
+    /// Reads the short name entry ending the item at the given location again.
+    pub(crate) async fn read_short_directory_entry_async(
+        &self,
+        location: EntryLocation,
+    ) -> Result<ShortNameDirectoryEntry, DeviceOperationError<D>> {
+        let mut iterator = self.directory_containing(location.position()).entries();
+        iterator.set_position(location.position());
+
+        for _ in 1..location.entry_count() {
+            ensure!(iterator.advance_async().await?, OperationError::NotFound);
+        }
+
+        match iterator
+            .peek_async()
+            .await
+            .ok_or(OperationError::NotFound)??
+        {
+            DirectoryEntry::ShortName(short_directory_entry) => Ok(short_directory_entry),
+            _ => Err(OperationError::NotFound),
+        }
+    }
+
+    async fn find_directory_async(
+        &self,
+        directory_path: &str,
+    ) -> Option<Directory<'_, D, BUFFER_SIZE>> {
+        find_directory_body!(async_io, self, directory_path)
+    }
+
+    async fn find_item_async(&self, file_path: &str) -> Option<DirectoryItem> {
+        let mut yield_budget = YieldBudget::new(self.yield_interval);
+        let mut current_directory = self.root_directory();
+        let mut file_path_part_iterator = file_path.split("/");
+        let mut file_path_part = file_path_part_iterator.next()?;
+
+        loop {
+            let query = NameQuery::new(&self.code_page_encoder, file_path_part);
+            let item = self
+                .find_item_in_async(
+                    &current_directory,
+                    |item| query.matches(item),
+                    &mut yield_budget,
+                )
+                .await?;
+
+            file_path_part = match file_path_part_iterator.next() {
+                Some(next_file_path_part) => next_file_path_part,
+                None => return Some(item),
+            };
+
+            current_directory = self.directory_for(&item)?;
+        }
+    }
+
+    /// Finds the directory holding the item at the given path, see `find_parent_directory`.
+    async fn find_parent_directory_async<'p>(
+        &self,
+        path: &'p str,
+    ) -> Option<(Directory<'_, D, BUFFER_SIZE>, &'p str, &'p str)> {
+        find_parent_directory_body!(async_io, self, path)
+    }
+
+    async fn find_item_named_async(
+        &self,
+        directory: &Directory<'_, D, BUFFER_SIZE>,
+        name: &str,
+    ) -> Option<DirectoryItem> {
+        let query = NameQuery::new(&self.code_page_encoder, name);
+        let mut yield_budget = YieldBudget::new(self.yield_interval);
+
+        self.find_item_in_async(directory, |item| query.matches(item), &mut yield_budget)
+            .await
+    }
+
+    /// Measures the files and bytes held in the directory at the given path, see
+    /// `directory_quota_usage`.
+    pub(crate) async fn directory_quota_usage_async(
+        &self,
+        directory: &Directory<'_, D, BUFFER_SIZE>,
+        directory_path: &str,
+    ) -> Option<DirectoryQuotaUsage> {
+        directory_quota_usage_body!(async_io, self, directory, directory_path)
+    }
+
+    /// Finds the first of the directory's items matching the predicate, yielding to the executor
+    /// as the budget runs out.
+    async fn find_item_in_async(
+        &self,
+        directory: &Directory<'_, D, BUFFER_SIZE>,
+        predicate: impl Fn(&DirectoryItem) -> bool,
+        yield_budget: &mut YieldBudget,
+    ) -> Option<DirectoryItem> {
+        let mut item_iterator = directory.items();
+
+        loop {
+            yield_budget.tick().await;
+
+            match item_iterator.next_async().await? {
+                Ok(item) if predicate(&item) => return Some(item),
+                Ok(_) => {}
+                Err(error) => {
+                    if self.report_invalid_directory_entry(error).is_break() {
+                        return None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, S, CPE, IDE, ME, const N: usize, const BUFFER_SIZE: usize>
+    FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: AsyncDevice<Stream = PrefetchingStream<S, N>>,
+    S: AsyncRead + AsyncSeek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    /// Holds the first allocation table in memory, as much of it as fits into the stream's
+    /// capacity, returning how many bytes are held.
+    pub async fn prefetch_allocation_table_async(&self) -> Result<usize, DeviceOperationError<D>> {
+        let base_address = self.bios_parameter_block.allocation_table_base_address();
+        let length = self.bios_parameter_block.bytes_per_allocation_table();
+
+        self.device
+            .with_stream(async |stream| -> Result<usize, DeviceOperationError<D>> {
+                Ok(stream.prefetch_async(base_address.value(), length).await?)
+            })
+            .await
+            .map_err(OperationError::DeviceError)?
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, S, CPE, IDE, ME, const BUFFER_SIZE: usize> FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: AsyncFlushableDevice<Stream = S>,
+    S: AsyncRead + AsyncWrite + AsyncSeek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    /// Flushes the device and releases the volume so another host, such as a USB mass storage
+    /// host, can use the medium until the file system is resumed.
+    ///
+    /// Taking the file system by value ensures no files or directories remain open.  The file
+    /// system is returned along with the error if flushing fails.
+    #[allow(clippy::result_large_err)]
+    pub async fn suspend_async(self) -> SuspendResult<D, CPE, IDE, ME, BUFFER_SIZE> {
+        match self.device.flush().await {
+            Ok(()) => Ok(self.into_suspended()),
+            Err(error) => Err((self, error)),
         }
     }
+
+    /// Opens the file at the given path for writing through a guard which records the file's new
+    /// size in its directory entry when flushed or closed.
+    pub async fn open_writer_async<'a>(
+        &'a self,
+        path: &'a str,
+    ) -> Result<AsyncFileWriteGuard<'a, D, CPE, IDE, ME, BUFFER_SIZE>, DeviceOperationError<D>>
+    {
+        let (directory, directory_path, name) = self
+            .find_parent_directory_async(path)
+            .await
+            .ok_or(OperationError::NotFound)?;
+        let item = self
+            .find_item_named_async(&directory, name)
+            .await
+            .ok_or(OperationError::NotFound)?;
+        let location = item.location().ok_or(OperationError::NotFound)?;
+        let file = self
+            .file_for(&item)
+            .ok_or(OperationError::NotAFile)?
+            .with_open_options(OpenOptions::builder().write(true).build());
+
+        Ok(
+            AsyncFileWriteGuard::new(self, directory, directory_path, name, item, location, file)
+                .await,
+        )
+    }
 }
@@ -1,7 +1,13 @@
-use crate::directory_item::DeviceDirectoryItemIterationError;
+use crate::directory_entry::{DEFAULT_BUFFER_SIZE, DIRECTORY_ENTRY_SIZE};
+use crate::file_system::{
+    DEFAULT_YIELD_INTERVAL, FixedFilePolicy, IgnoreInvalidEntries, InvalidDirectoryEntryHandler,
+    QuotaPolicy,
+};
 use crate::{
-    AsciiOnlyEncoder, CodePageEncoder, Device, FileSystem, FileSystemError, SingleAccessDevice,
+    AsciiOnlyEncoder, CodePageEncoder, Device, FileSystem, FileSystemError, InvalidTimestampPolicy,
+    MutationEvent, SingleAccessDevice,
 };
+use core::ops::RangeInclusive;
 use embedded_io::{ErrorType, SeekFrom};
 
 #[cfg(feature = "sync")]
@@ -16,24 +22,34 @@ use {
     embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek},
 };
 
-type FileSystemBuilderResult<D, CPE, IDE> = Result<
-    FileSystem<D, CPE, IDE>,
+type FileSystemBuilderResult<D, CPE, IDE, ME, const BUFFER_SIZE: usize> = Result<
+    FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>,
     FileSystemError<<D as Device>::Error, <<D as Device>::Stream as ErrorType>::Error>,
 >;
 
 #[derive(Clone, Debug)]
-pub struct FileSystemBuilder<D, CPE, IDE>
+pub struct FileSystemBuilder<D, CPE, IDE, ME, const BUFFER_SIZE: usize = DEFAULT_BUFFER_SIZE>
 where
     D: Device,
     CPE: CodePageEncoder,
-    IDE: Fn(DeviceDirectoryItemIterationError<D>),
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
 {
     device: D,
     code_page_encoder: CPE,
     on_invalid_directory_entry: IDE,
+    on_mutation: ME,
+    quota_policy: Option<QuotaPolicy>,
+    fixed_file_policy: Option<FixedFilePolicy>,
+    use_short_name_case: bool,
+    utc_offset_minutes: i16,
+    invalid_timestamp_policy: InvalidTimestampPolicy,
+    reserved_cluster_ranges: &'static [RangeInclusive<u32>],
+    run_self_test: bool,
+    yield_interval: u32,
 }
 
-impl<D> FileSystemBuilder<D, AsciiOnlyEncoder, fn(DeviceDirectoryItemIterationError<D>)>
+impl<D> FileSystemBuilder<D, AsciiOnlyEncoder, IgnoreInvalidEntries, fn(MutationEvent<'_>)>
 where
     D: Device,
 {
@@ -41,7 +57,16 @@ where
         Self {
             device,
             code_page_encoder: AsciiOnlyEncoder,
-            on_invalid_directory_entry: |_| {},
+            on_invalid_directory_entry: IgnoreInvalidEntries,
+            on_mutation: |_| {},
+            quota_policy: None,
+            fixed_file_policy: None,
+            use_short_name_case: true,
+            utc_offset_minutes: 0,
+            invalid_timestamp_policy: InvalidTimestampPolicy::default(),
+            reserved_cluster_ranges: &[],
+            run_self_test: false,
+            yield_interval: DEFAULT_YIELD_INTERVAL,
         }
     }
 }
@@ -50,7 +75,8 @@ impl<S>
     FileSystemBuilder<
         SingleAccessDevice<S>,
         AsciiOnlyEncoder,
-        fn(DeviceDirectoryItemIterationError<SingleAccessDevice<S>>),
+        IgnoreInvalidEntries,
+        fn(MutationEvent<'_>),
     >
 where
     S: ErrorType,
@@ -59,21 +85,31 @@ where
         Self {
             device: SingleAccessDevice::new(stream),
             code_page_encoder: AsciiOnlyEncoder,
-            on_invalid_directory_entry: |_| {},
+            on_invalid_directory_entry: IgnoreInvalidEntries,
+            on_mutation: |_| {},
+            quota_policy: None,
+            fixed_file_policy: None,
+            use_short_name_case: true,
+            utc_offset_minutes: 0,
+            invalid_timestamp_policy: InvalidTimestampPolicy::default(),
+            reserved_cluster_ranges: &[],
+            run_self_test: false,
+            yield_interval: DEFAULT_YIELD_INTERVAL,
         }
     }
 }
 
-impl<D, CPE, IDE> FileSystemBuilder<D, CPE, IDE>
+impl<D, CPE, IDE, ME, const BUFFER_SIZE: usize> FileSystemBuilder<D, CPE, IDE, ME, BUFFER_SIZE>
 where
     D: Device,
     CPE: CodePageEncoder,
-    IDE: Fn(DeviceDirectoryItemIterationError<D>),
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
 {
     pub fn with_code_page_encoder<CPE2>(
         self,
         code_page_encoder: CPE2,
-    ) -> FileSystemBuilder<D, CPE2, IDE>
+    ) -> FileSystemBuilder<D, CPE2, IDE, ME, BUFFER_SIZE>
     where
         CPE2: CodePageEncoder,
     {
@@ -81,55 +117,249 @@ where
             device: self.device,
             code_page_encoder,
             on_invalid_directory_entry: self.on_invalid_directory_entry,
+            on_mutation: self.on_mutation,
+            quota_policy: self.quota_policy,
+            fixed_file_policy: self.fixed_file_policy,
+            use_short_name_case: self.use_short_name_case,
+            utc_offset_minutes: self.utc_offset_minutes,
+            invalid_timestamp_policy: self.invalid_timestamp_policy,
+            reserved_cluster_ranges: self.reserved_cluster_ranges,
+            run_self_test: self.run_self_test,
+            yield_interval: self.yield_interval,
         }
     }
 
+    /// Sets the handler invoked with each invalid directory entry encountered while searching the
+    /// volume.  Returning `ControlFlow::Break` stops the search, which then finds nothing more.
     pub fn on_invalid_directory_entry<IDE2>(
         self,
         on_invalid_directory_entry: IDE2,
-    ) -> FileSystemBuilder<D, CPE, IDE2>
+    ) -> FileSystemBuilder<D, CPE, IDE2, ME, BUFFER_SIZE>
     where
-        IDE2: Fn(DeviceDirectoryItemIterationError<D>),
+        IDE2: InvalidDirectoryEntryHandler<D>,
     {
         FileSystemBuilder {
             device: self.device,
             code_page_encoder: self.code_page_encoder,
             on_invalid_directory_entry,
+            on_mutation: self.on_mutation,
+            quota_policy: self.quota_policy,
+            fixed_file_policy: self.fixed_file_policy,
+            use_short_name_case: self.use_short_name_case,
+            utc_offset_minutes: self.utc_offset_minutes,
+            invalid_timestamp_policy: self.invalid_timestamp_policy,
+            reserved_cluster_ranges: self.reserved_cluster_ranges,
+            run_self_test: self.run_self_test,
+            yield_interval: self.yield_interval,
+        }
+    }
+
+    /// Sets the handler invoked after each change the file system makes to the volume.
+    pub fn on_mutation<ME2>(
+        self,
+        on_mutation: ME2,
+    ) -> FileSystemBuilder<D, CPE, IDE, ME2, BUFFER_SIZE>
+    where
+        ME2: Fn(MutationEvent<'_>),
+    {
+        FileSystemBuilder {
+            device: self.device,
+            code_page_encoder: self.code_page_encoder,
+            on_invalid_directory_entry: self.on_invalid_directory_entry,
+            on_mutation,
+            quota_policy: self.quota_policy,
+            fixed_file_policy: self.fixed_file_policy,
+            use_short_name_case: self.use_short_name_case,
+            utc_offset_minutes: self.utc_offset_minutes,
+            invalid_timestamp_policy: self.invalid_timestamp_policy,
+            reserved_cluster_ranges: self.reserved_cluster_ranges,
+            run_self_test: self.run_self_test,
+            yield_interval: self.yield_interval,
         }
     }
+
+    /// Sets the size in bytes of the buffers kept on the stack while listing directories, hashing
+    /// files and copying or converting metadata, 512 by default.  It must be a non-zero multiple
+    /// of the 32 byte directory entry size.
+    ///
+    /// Smaller buffers suit targets with little stack to spare at the cost of more device reads,
+    /// larger ones cut the number of reads on devices with a high cost per access.
+    pub fn with_buffer_size<const BUFFER_SIZE2: usize>(
+        self,
+    ) -> FileSystemBuilder<D, CPE, IDE, ME, BUFFER_SIZE2> {
+        const {
+            assert!(
+                BUFFER_SIZE2 > 0 && BUFFER_SIZE2.is_multiple_of(DIRECTORY_ENTRY_SIZE),
+                "the buffer size must be a non-zero multiple of the directory entry size"
+            )
+        };
+
+        FileSystemBuilder {
+            device: self.device,
+            code_page_encoder: self.code_page_encoder,
+            on_invalid_directory_entry: self.on_invalid_directory_entry,
+            on_mutation: self.on_mutation,
+            quota_policy: self.quota_policy,
+            fixed_file_policy: self.fixed_file_policy,
+            use_short_name_case: self.use_short_name_case,
+            utc_offset_minutes: self.utc_offset_minutes,
+            invalid_timestamp_policy: self.invalid_timestamp_policy,
+            reserved_cluster_ranges: self.reserved_cluster_ranges,
+            run_self_test: self.run_self_test,
+            yield_interval: self.yield_interval,
+        }
+    }
+
+    /// Sets the policy giving the quota of each directory, checked before files are created in or
+    /// written past their end in it.
+    pub fn with_quota_policy(mut self, quota_policy: QuotaPolicy) -> Self {
+        self.quota_policy = Some(quota_policy);
+        self
+    }
+
+    /// Sets the policy deciding which files are fixed in place, which helpers freeing or
+    /// rearranging space never delete or move.
+    pub fn with_fixed_file_policy(mut self, fixed_file_policy: FixedFilePolicy) -> Self {
+        self.fixed_file_policy = Some(fixed_file_policy);
+        self
+    }
+
+    /// Sets whether names which fit a short name but for being in lower case, such as `readme.txt`
+    /// or `LOG.txt`, are created as short names with their case recorded in the entry rather than
+    /// with a long name, enabled by default.  This keeps such items to a single entry, but hosts
+    /// predating Windows NT list them in upper case.
+    pub fn with_short_name_case(mut self, use_short_name_case: bool) -> Self {
+        self.use_short_name_case = use_short_name_case;
+        self
+    }
+
+    /// Sets the offset of the volume's local time from UTC in minutes, such as `60` for Central
+    /// European Time, for volumes shared across time zones.
+    ///
+    /// FAT timestamps are stored in local time, the offset converts them to UTC through
+    /// `DirectoryItem::last_write_timestamp_utc` and back through
+    /// `TempFile::set_last_write_timestamp_utc`.  Without an offset local time is taken to be UTC.
+    pub fn with_utc_offset(mut self, utc_offset_minutes: i16) -> Self {
+        self.utc_offset_minutes = utc_offset_minutes;
+        self
+    }
+
+    /// Sets how timestamps which don't exist, such as the zeroed or garbage values written by
+    /// devices without a working clock, are read through `DirectoryItem::last_write_timestamp`,
+    /// rejected by default.
+    ///
+    /// Such timestamps are still flagged whatever the policy, by
+    /// `DirectoryItem::is_last_write_timestamp_valid` and, for the root directory, by the self test
+    /// enabled through `with_self_test`.
+    pub fn with_invalid_timestamp_policy(
+        mut self,
+        invalid_timestamp_policy: InvalidTimestampPolicy,
+    ) -> Self {
+        self.invalid_timestamp_policy = invalid_timestamp_policy;
+        self
+    }
+
+    /// Keeps the clusters numbered within the given ranges from ever being allocated, for media
+    /// which hold data outside of the file system in the data region, such as the image of a
+    /// bootloader.
+    ///
+    /// Reserved clusters are left as they are in the allocation table, so hosts see them as free,
+    /// and don't count towards the volume's free space.  Clusters already allocated to a file are
+    /// unaffected.
+    pub fn with_reserved_clusters(
+        mut self,
+        reserved_cluster_ranges: &'static [RangeInclusive<u32>],
+    ) -> Self {
+        self.reserved_cluster_ranges = reserved_cluster_ranges;
+        self
+    }
+
+    /// Sets whether a bounded sanity pass over the volume's structures is run at mount, reported
+    /// through `FileSystem::health_summary`, disabled by default.
+    ///
+    /// The pass reads a few sectors and the root directory, which is far cheaper than checking the
+    /// whole volume, so devices can decide at boot whether a full check or reformat is warranted.
+    /// Problems it finds don't keep the volume from being mounted, only failing to read the
+    /// device does.
+    pub fn with_self_test(mut self, run_self_test: bool) -> Self {
+        self.run_self_test = run_self_test;
+        self
+    }
+
+    /// Sets how many items or chunks long running async operations, such as searches and hashing,
+    /// process before yielding to the executor so other tasks stay responsive.  Zero never yields.
+    pub fn with_yield_interval(mut self, yield_interval: u32) -> Self {
+        self.yield_interval = yield_interval;
+        self
+    }
 }
 
 #[cfg(feature = "sync")]
-impl<D, S, CPE, IDE> FileSystemBuilder<D, CPE, IDE>
+impl<D, S, CPE, IDE, ME, const BUFFER_SIZE: usize> FileSystemBuilder<D, CPE, IDE, ME, BUFFER_SIZE>
 where
     D: SyncDevice<Stream = S>,
     S: Read + Seek,
     CPE: CodePageEncoder,
-    IDE: Fn(DeviceDirectoryItemIterationError<D>),
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
 {
-    pub fn build(self) -> FileSystemBuilderResult<D, CPE, IDE> {
-        FileSystem::new(
+    pub fn build(self) -> FileSystemBuilderResult<D, CPE, IDE, ME, BUFFER_SIZE> {
+        let mut file_system = FileSystem::new(
             self.device,
             self.code_page_encoder,
             self.on_invalid_directory_entry,
-        )
+            self.on_mutation,
+        )?;
+
+        file_system.quota_policy = self.quota_policy;
+        file_system.fixed_file_policy = self.fixed_file_policy;
+        file_system.use_short_name_case = self.use_short_name_case;
+        file_system.utc_offset_minutes = self.utc_offset_minutes;
+        file_system.invalid_timestamp_policy = self.invalid_timestamp_policy;
+        file_system.allocation_table = file_system
+            .allocation_table
+            .with_reserved_clusters(self.reserved_cluster_ranges);
+
+        if self.run_self_test {
+            file_system.health_summary = Some(file_system.self_test()?);
+        }
+
+        Ok(file_system)
     }
 }
 
 #[cfg(feature = "async")]
-impl<D, S, CPE, IDE> FileSystemBuilder<D, CPE, IDE>
+impl<D, S, CPE, IDE, ME, const BUFFER_SIZE: usize> FileSystemBuilder<D, CPE, IDE, ME, BUFFER_SIZE>
 where
     D: AsyncDevice<Stream = S>,
     S: AsyncRead + AsyncSeek,
     CPE: CodePageEncoder,
-    IDE: Fn(DeviceDirectoryItemIterationError<D>),
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
 {
-    pub async fn build_async(self) -> FileSystemBuilderResult<D, CPE, IDE> {
-        FileSystem::new_async(
+    pub async fn build_async(self) -> FileSystemBuilderResult<D, CPE, IDE, ME, BUFFER_SIZE> {
+        let mut file_system = FileSystem::new_async(
             self.device,
             self.code_page_encoder,
             self.on_invalid_directory_entry,
+            self.on_mutation,
         )
-        .await
+        .await?;
+
+        file_system.quota_policy = self.quota_policy;
+        file_system.fixed_file_policy = self.fixed_file_policy;
+        file_system.use_short_name_case = self.use_short_name_case;
+        file_system.utc_offset_minutes = self.utc_offset_minutes;
+        file_system.invalid_timestamp_policy = self.invalid_timestamp_policy;
+        file_system.allocation_table = file_system
+            .allocation_table
+            .with_reserved_clusters(self.reserved_cluster_ranges);
+        file_system.yield_interval = self.yield_interval;
+
+        if self.run_self_test {
+            file_system.health_summary = Some(file_system.self_test_async().await?);
+        }
+
+        Ok(file_system)
     }
 }
@@ -0,0 +1,75 @@
+use crate::SearchError;
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CanonicalizeError {
+    /// The component starting at the given byte offset of the path follows one which is not a
+    /// directory.
+    NotADirectory {
+        component_offset: usize,
+    },
+    /// No item is named by the component starting at the given byte offset of the path.
+    NotFound {
+        component_offset: usize,
+    },
+    PathBufferTooSmall,
+}
+
+impl Error for CanonicalizeError {}
+
+impl Display for CanonicalizeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CanonicalizeError::NotADirectory { component_offset } => write!(
+                f,
+                "the component before byte {component_offset} of the path is not a directory"
+            ),
+            CanonicalizeError::NotFound { component_offset } => write!(
+                f,
+                "the component at byte {component_offset} of the path was not found"
+            ),
+            CanonicalizeError::PathBufferTooSmall => {
+                write!(f, "the path buffer has no room for the canonical path")
+            }
+        }
+    }
+}
+
+impl From<SearchError> for CanonicalizeError {
+    fn from(value: SearchError) -> Self {
+        match value {
+            SearchError::PathBufferTooSmall => CanonicalizeError::PathBufferTooSmall,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn produces_non_empty_value() {
+            let values = [
+                CanonicalizeError::NotADirectory {
+                    component_offset: 4,
+                },
+                CanonicalizeError::NotFound {
+                    component_offset: 4,
+                },
+                CanonicalizeError::PathBufferTooSmall,
+            ];
+
+            for value in values {
+                assert!(
+                    !value.to_string().is_empty(),
+                    "Display implementation should be non-empty"
+                );
+            }
+        }
+    }
+}
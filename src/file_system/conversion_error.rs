@@ -0,0 +1,83 @@
+use crate::{Device, DeviceOperationError};
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+
+pub type DeviceConversionError<D> = ConversionError<DeviceOperationError<D>>;
+
+/// An error converting a volume to FAT32 through `FileSystem::convert_to_fat32`.
+#[derive(Clone, Debug)]
+pub enum ConversionError<VE>
+where
+    VE: Error,
+{
+    /// Only FAT16 volumes can be converted.
+    AllocationTableKindUnsupported,
+    /// The volume's data region doesn't start on a sector boundary, so it can't be described by a
+    /// FAT32 boot sector without moving every cluster.
+    LayoutUnsupported,
+    /// The sectors preceding the data region can't hold a FAT32 allocation table along with the
+    /// boot sector and FS information sector.
+    SpaceInsufficient,
+    VolumeError(VE),
+}
+
+impl<VE> Error for ConversionError<VE> where VE: Error {}
+
+impl<VE> Display for ConversionError<VE>
+where
+    VE: Error,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ConversionError::AllocationTableKindUnsupported => {
+                write!(f, "only FAT16 volumes can be converted to FAT32")
+            }
+            ConversionError::LayoutUnsupported => write!(
+                f,
+                "the volume's data region doesn't start on a sector boundary"
+            ),
+            ConversionError::SpaceInsufficient => write!(
+                f,
+                "the sectors preceding the data region can't hold a FAT32 allocation table"
+            ),
+            ConversionError::VolumeError(e) => write!(f, "accessing the volume failed: {}", e),
+        }
+    }
+}
+
+impl<VE> From<VE> for ConversionError<VE>
+where
+    VE: Error,
+{
+    fn from(value: VE) -> Self {
+        ConversionError::VolumeError(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::CoreError;
+    use alloc::string::ToString;
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn produces_non_empty_value() {
+            let values = [
+                ConversionError::<CoreError>::AllocationTableKindUnsupported,
+                ConversionError::LayoutUnsupported,
+                ConversionError::SpaceInsufficient,
+                ConversionError::VolumeError(CoreError),
+            ];
+
+            for value in values {
+                assert!(
+                    !value.to_string().is_empty(),
+                    "Display implementation should be non-empty"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{Device, DeviceOperationError, FileError};
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+use embedded_io::ErrorType;
+
+pub type DeviceCopyError<SD, D> = CopyError<
+    FileError<<SD as Device>::Error, <<SD as Device>::Stream as ErrorType>::Error>,
+    DeviceOperationError<D>,
+>;
+
+#[derive(Clone, Debug)]
+pub enum CopyError<SE, DE>
+where
+    SE: Error,
+    DE: Error,
+{
+    BufferEmpty,
+    DestinationError(DE),
+    SourceError(SE),
+    SourceNotAFile,
+    SourceNotFound,
+}
+
+impl<SE, DE> Error for CopyError<SE, DE>
+where
+    SE: Error,
+    DE: Error,
+{
+}
+
+impl<SE, DE> Display for CopyError<SE, DE>
+where
+    SE: Error,
+    DE: Error,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CopyError::BufferEmpty => write!(f, "the copy buffer has no room for any bytes"),
+            CopyError::DestinationError(e) => {
+                write!(f, "writing the destination failed: {}", e)
+            }
+            CopyError::SourceError(e) => write!(f, "reading the source failed: {}", e),
+            CopyError::SourceNotAFile => write!(f, "the source item is not a file"),
+            CopyError::SourceNotFound => write!(f, "the source item was not found"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::CoreError;
+    use alloc::string::ToString;
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn produces_non_empty_value() {
+            let values = [
+                CopyError::<CoreError, CoreError>::BufferEmpty,
+                CopyError::DestinationError(CoreError),
+                CopyError::SourceError(CoreError),
+                CopyError::SourceNotAFile,
+                CopyError::SourceNotFound,
+            ];
+
+            for value in values {
+                assert!(
+                    !value.to_string().is_empty(),
+                    "Display implementation should be non-empty"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+/// Looks up the quota of the directory at the given path, which is empty for the root directory.
+/// Directories without a quota may grow until the volume is full.
+pub type QuotaPolicy = fn(&str) -> Option<DirectoryQuota>;
+
+/// Limits on the files held directly in a directory, set through
+/// `FileSystemBuilder::with_quota_policy`.
+///
+/// Creating a file or writing past the end of one fails with `QuotaExceeded` once it would take the
+/// directory beyond either limit, leaving the volume unchanged.  Loggers can handle this by
+/// deleting old files and retrying rather than filling the volume.  Subdirectories and their
+/// contents don't count towards the limits.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DirectoryQuota {
+    max_bytes: Option<u64>,
+    max_files: Option<u32>,
+}
+
+impl DirectoryQuota {
+    /// A quota without limits, restricted further by `with_max_bytes` and `with_max_files`.
+    pub const fn new() -> Self {
+        Self {
+            max_bytes: None,
+            max_files: None,
+        }
+    }
+
+    /// Limits the sum of the sizes of the directory's files.
+    pub const fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Limits the number of files in the directory.
+    pub const fn with_max_files(mut self, max_files: u32) -> Self {
+        self.max_files = Some(max_files);
+        self
+    }
+
+    pub fn max_bytes(&self) -> Option<u64> {
+        self.max_bytes
+    }
+
+    pub fn max_files(&self) -> Option<u32> {
+        self.max_files
+    }
+
+    /// Whether a directory holding the given number of files and bytes is within the quota.
+    pub(crate) fn allows(&self, file_count: u64, byte_count: u64) -> bool {
+        self.max_files
+            .is_none_or(|max_files| file_count <= u64::from(max_files))
+            && self
+                .max_bytes
+                .is_none_or(|max_bytes| byte_count <= max_bytes)
+    }
+}
+
+/// A directory's quota along with the files and bytes the directory held when it was measured, so
+/// repeated checks against it don't scan the directory again.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct DirectoryQuotaUsage {
+    quota: DirectoryQuota,
+    file_count: u64,
+    byte_count: u64,
+}
+
+impl DirectoryQuotaUsage {
+    pub(crate) fn new(quota: DirectoryQuota, file_count: u64, byte_count: u64) -> Self {
+        Self {
+            quota,
+            file_count,
+            byte_count,
+        }
+    }
+
+    /// Whether the directory is within the quota once the files and bytes are added to it.
+    pub(crate) fn allows(&self, added_file_count: u64, added_byte_count: u64) -> bool {
+        self.quota.allows(
+            self.file_count.saturating_add(added_file_count),
+            self.byte_count.saturating_add(added_byte_count),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod allows {
+        use super::*;
+
+        #[test]
+        fn unlimited_quota_allows_anything() {
+            assert!(DirectoryQuota::new().allows(u64::MAX, u64::MAX));
+        }
+
+        #[test]
+        fn limits_inclusive() {
+            let quota = DirectoryQuota::new().with_max_bytes(100).with_max_files(2);
+
+            assert!(quota.allows(2, 100));
+            assert!(!quota.allows(3, 100));
+            assert!(!quota.allows(2, 101));
+        }
+
+        #[test]
+        fn unset_limits_ignored() {
+            assert!(DirectoryQuota::new().with_max_files(1).allows(1, u64::MAX));
+            assert!(DirectoryQuota::new().with_max_bytes(1).allows(u64::MAX, 1));
+        }
+    }
+
+    mod usage_allows {
+        use super::*;
+
+        #[test]
+        fn additions_counted_with_measured_usage() {
+            let usage = DirectoryQuotaUsage::new(DirectoryQuota::new().with_max_bytes(100), 1, 60);
+
+            assert!(usage.allows(0, 40));
+            assert!(!usage.allows(0, 41));
+        }
+    }
+}
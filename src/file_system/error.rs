@@ -9,6 +9,7 @@ where
     DE: Error,
     SE: embedded_io::Error,
 {
+    AllocationTableMediaTypeMismatch,
     DeviceError(DE),
     InvalidBiosParameterBlock(BiosParameterBlockError),
     InvalidFatSignature,
@@ -30,6 +31,10 @@ where
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
+            FileSystemError::AllocationTableMediaTypeMismatch => write!(
+                f,
+                "the media type in the allocation table does not match the boot sector"
+            ),
             FileSystemError::DeviceError(e) => write!(f, "device error occurred: {}", e),
             FileSystemError::InvalidBiosParameterBlock(e) => {
                 write!(f, "the bios parameter block is invalid: {}", e)
@@ -93,6 +98,7 @@ mod tests {
         #[test]
         fn produces_non_empty_value() {
             let values = [
+                FileSystemError::AllocationTableMediaTypeMismatch,
                 FileSystemError::DeviceError(IoError::default()),
                 FileSystemError::InvalidFatSignature,
                 FileSystemError::InvalidBiosParameterBlock(
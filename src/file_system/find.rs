@@ -0,0 +1,139 @@
+use crate::directory_entry::DEFAULT_BUFFER_SIZE;
+use crate::directory_item::{
+    DeviceDirectoryItemIterationError, DirectoryItem, DirectoryItemFilter,
+};
+use crate::file_system::walk::Walk;
+use crate::{CodePageEncoder, Device, FileSystem, InvalidDirectoryEntryHandler, MutationEvent};
+
+#[cfg(feature = "sync")]
+use {
+    crate::SyncDevice,
+    embedded_io::{Read, Seek},
+};
+
+#[cfg(feature = "async")]
+use {
+    crate::AsyncDevice,
+    embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek},
+};
+
+/// The items of a volume matching a filter, see [`FileSystem::find`].
+///
+/// Every directory is searched, up to `MAX_DEPTH` levels of them with the root as the first.
+/// Invalid entries are reported to the file system's invalid directory entry callback and skipped,
+/// unless the callback stops the search.
+#[derive(Clone, Debug)]
+pub struct Find<
+    'a,
+    D,
+    CPE,
+    IDE,
+    ME,
+    const MAX_DEPTH: usize,
+    const BUFFER_SIZE: usize = DEFAULT_BUFFER_SIZE,
+> where
+    D: Device,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    file_system: &'a FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>,
+    walk: Walk<'a, D, MAX_DEPTH, BUFFER_SIZE>,
+    filter: DirectoryItemFilter<'a>,
+}
+
+impl<'a, D, CPE, IDE, ME, const MAX_DEPTH: usize, const BUFFER_SIZE: usize>
+    Find<'a, D, CPE, IDE, ME, MAX_DEPTH, BUFFER_SIZE>
+where
+    D: Device,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    pub(crate) fn new(
+        file_system: &'a FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>,
+        walk: Walk<'a, D, MAX_DEPTH, BUFFER_SIZE>,
+        filter: DirectoryItemFilter<'a>,
+    ) -> Self {
+        Self {
+            file_system,
+            walk,
+            filter,
+        }
+    }
+
+    /// Queues the item's directory to be searched, returning the item if the filter accepts it.
+    ///
+    /// The search ends when the invalid directory entry callback asks to stop.
+    fn visit(
+        &mut self,
+        result: Result<DirectoryItem, DeviceDirectoryItemIterationError<D>>,
+    ) -> Option<DirectoryItem> {
+        let item = match result {
+            Ok(item) => item,
+            Err(error) => {
+                if self
+                    .file_system
+                    .report_invalid_directory_entry(error)
+                    .is_break()
+                {
+                    self.walk.stop();
+                }
+
+                return None;
+            }
+        };
+
+        if self.walk.can_descend()
+            && let Some(directory) = self.file_system.directory_for(&item)
+        {
+            self.walk.descend(directory);
+        }
+
+        self.filter.matches(&item).then_some(item)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<D, S, CPE, IDE, ME, const MAX_DEPTH: usize, const BUFFER_SIZE: usize> Iterator
+    for Find<'_, D, CPE, IDE, ME, MAX_DEPTH, BUFFER_SIZE>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Seek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    type Item = DirectoryItem;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let result = self.walk.next()?;
+
+            if let Some(item) = self.visit(result) {
+                return Some(item);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, S, CPE, IDE, ME, const MAX_DEPTH: usize, const BUFFER_SIZE: usize>
+    Find<'_, D, CPE, IDE, ME, MAX_DEPTH, BUFFER_SIZE>
+where
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncSeek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    pub async fn next_async(&mut self) -> Option<DirectoryItem> {
+        loop {
+            let result = self.walk.next_async().await?;
+
+            if let Some(item) = self.visit(result) {
+                return Some(item);
+            }
+        }
+    }
+}
@@ -0,0 +1,9 @@
+/// Decides whether the file with the given name, directly within the directory at the given path
+/// which is empty for the root directory, is fixed in place.  Names are passed as long names where
+/// the file has one, so policies should compare them ignoring ASCII case.
+///
+/// Helpers which free or rearrange space on their own, such as `FileSystem::evict_until_free` and
+/// `FileSystem::remove_where`, never delete or move fixed files.  This suits files whose location is recorded outside of the
+/// file system, such as a `BOOT.BIN` a bootloader loads from a raw offset.  Files are still
+/// deleted or rewritten when asked to directly.
+pub type FixedFilePolicy = fn(&str, &str) -> bool;
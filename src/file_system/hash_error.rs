@@ -0,0 +1,59 @@
+use crate::{Device, FileError};
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+use embedded_io::ErrorType;
+
+pub type DeviceHashError<D> =
+    HashError<FileError<<D as Device>::Error, <<D as Device>::Stream as ErrorType>::Error>>;
+
+#[derive(Clone, Debug)]
+pub enum HashError<FE>
+where
+    FE: Error,
+{
+    FileError(FE),
+    NotAFile,
+    NotFound,
+}
+
+impl<FE> Error for HashError<FE> where FE: Error {}
+
+impl<FE> Display for HashError<FE>
+where
+    FE: Error,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            HashError::FileError(e) => write!(f, "reading the file failed: {}", e),
+            HashError::NotAFile => write!(f, "the item is not a file"),
+            HashError::NotFound => write!(f, "the item was not found"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::CoreError;
+    use alloc::string::ToString;
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn produces_non_empty_value() {
+            let values = [
+                HashError::<CoreError>::FileError(CoreError),
+                HashError::NotAFile,
+                HashError::NotFound,
+            ];
+
+            for value in values {
+                assert!(
+                    !value.to_string().is_empty(),
+                    "Display implementation should be non-empty"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use crate::allocation_table::ReservedEntries;
+use crate::boot_sector::{BiosParameterBlock, FsInfo};
+
+/// The outcome of the sanity pass run at mount when enabled by `FileSystemBuilder::with_self_test`.
+///
+/// The pass only reads the volume's structures which are cheap to check: the backup boot sector
+/// and FS information sector of FAT32 volumes, the two reserved entries at the start of each copy
+/// of the allocation table, and the root directory.  Passing it doesn't mean the volume is free of
+/// damage, cluster chains and other directories are never followed.  Checks which don't apply to
+/// the volume are `None`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HealthSummary {
+    is_backup_boot_sector_valid: Option<bool>,
+    is_fs_info_valid: Option<bool>,
+    are_media_entries_valid: bool,
+    are_end_of_chain_entries_valid: bool,
+    was_cleanly_unmounted: Option<bool>,
+    is_free_of_hard_errors: Option<bool>,
+    is_root_directory_readable: bool,
+    invalid_timestamp_count: u32,
+}
+
+impl HealthSummary {
+    pub(crate) fn new(is_root_directory_readable: bool, invalid_timestamp_count: u32) -> Self {
+        Self {
+            is_backup_boot_sector_valid: None,
+            is_fs_info_valid: None,
+            are_media_entries_valid: true,
+            are_end_of_chain_entries_valid: true,
+            was_cleanly_unmounted: None,
+            is_free_of_hard_errors: None,
+            is_root_directory_readable,
+            invalid_timestamp_count,
+        }
+    }
+
+    /// Whether none of the checks found a problem.
+    pub fn is_healthy(&self) -> bool {
+        self.is_backup_boot_sector_valid != Some(false)
+            && self.is_fs_info_valid != Some(false)
+            && self.are_media_entries_valid
+            && self.are_end_of_chain_entries_valid
+            && self.was_cleanly_unmounted != Some(false)
+            && self.is_free_of_hard_errors != Some(false)
+            && self.is_root_directory_readable
+    }
+
+    /// Whether the backup of the boot sector has a valid signature and describes the same layout
+    /// as the boot sector, `None` if the volume keeps no backup.
+    pub fn is_backup_boot_sector_valid(&self) -> Option<bool> {
+        self.is_backup_boot_sector_valid
+    }
+
+    /// Whether the FS information sector has valid signatures and its free cluster count fits the
+    /// volume, `None` if the volume has no FS information sector.
+    pub fn is_fs_info_valid(&self) -> Option<bool> {
+        self.is_fs_info_valid
+    }
+
+    /// Whether the first entry of every copy of the allocation table holds the boot sector's media
+    /// descriptor.
+    pub fn are_media_entries_valid(&self) -> bool {
+        self.are_media_entries_valid
+    }
+
+    /// Whether the second entry of every copy of the allocation table holds an end of chain
+    /// marker.
+    pub fn are_end_of_chain_entries_valid(&self) -> bool {
+        self.are_end_of_chain_entries_valid
+    }
+
+    /// Whether the active allocation table records that the volume was unmounted cleanly, `None`
+    /// for FAT12 volumes which don't record it.  Hosts clear the flag while the volume is mounted,
+    /// so a cleared flag means it was removed without being ejected.
+    pub fn was_cleanly_unmounted(&self) -> Option<bool> {
+        self.was_cleanly_unmounted
+    }
+
+    /// Whether the active allocation table records that no disk errors were encountered, `None`
+    /// for FAT12 volumes which don't record it.
+    pub fn is_free_of_hard_errors(&self) -> Option<bool> {
+        self.is_free_of_hard_errors
+    }
+
+    /// Whether every entry of the root directory could be read.
+    pub fn is_root_directory_readable(&self) -> bool {
+        self.is_root_directory_readable
+    }
+
+    /// The number of root directory items whose last write timestamp doesn't exist, such as the
+    /// zeroed timestamps written by devices without a working clock.  Items are counted whatever
+    /// `FileSystemBuilder::with_invalid_timestamp_policy` makes of them, and don't make the
+    /// volume unhealthy.
+    pub fn invalid_timestamp_count(&self) -> u32 {
+        self.invalid_timestamp_count
+    }
+
+    pub(crate) fn check_backup_boot_sector(
+        &mut self,
+        bios_parameter_block: &BiosParameterBlock,
+        bytes: &[u8; 512],
+    ) {
+        let is_signature_valid = bytes[510] == 0x55 && bytes[511] == 0xAA;
+
+        self.is_backup_boot_sector_valid = Some(
+            is_signature_valid
+                && BiosParameterBlock::from_boot_sector(bytes).is_ok_and(
+                    |backup_bios_parameter_block| {
+                        backup_bios_parameter_block.has_same_layout(bios_parameter_block)
+                    },
+                ),
+        );
+    }
+
+    pub(crate) fn check_fs_info(
+        &mut self,
+        bios_parameter_block: &BiosParameterBlock,
+        bytes: &[u8; 512],
+    ) {
+        let data_cluster_count = bios_parameter_block.last_cluster_number().value() - 1;
+
+        self.is_fs_info_valid = Some(FsInfo::from_sector(bytes).is_ok_and(|fs_info| {
+            fs_info
+                .free_cluster_count()
+                .is_none_or(|free_cluster_count| free_cluster_count <= data_cluster_count)
+        }));
+    }
+
+    /// Checks the reserved entries at the start of a copy of the allocation table, the volume's
+    /// flags are only taken from the active copy.
+    pub(crate) fn check_reserved_entries(
+        &mut self,
+        bios_parameter_block: &BiosParameterBlock,
+        reserved_entries: &ReservedEntries,
+        is_active_table: bool,
+    ) {
+        self.are_media_entries_valid &=
+            reserved_entries.is_media_entry_valid(bios_parameter_block.media_type());
+        self.are_end_of_chain_entries_valid &= reserved_entries.is_end_of_chain_entry_valid();
+
+        if is_active_table
+            && let Some((is_clean, is_free_of_errors)) = reserved_entries.volume_flags()
+        {
+            self.was_cleanly_unmounted = Some(is_clean);
+            self.is_free_of_hard_errors = Some(is_free_of_errors);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "fat32"))]
+mod tests {
+    use super::*;
+    use crate::AllocationTableKind;
+    use crate::allocation_table::RESERVED_ENTRIES_SIZE;
+    use crate::boot_sector::{FS_INFO_UNKNOWN_VALUE, fs_info_sector};
+    use crate::utils::write_le_u32;
+
+    fn fat32_bios_parameter_block() -> BiosParameterBlock {
+        let image = std::fs::read("disks/fat32.img").expect("Ok should be returned");
+
+        BiosParameterBlock::from_boot_sector(
+            image[..512]
+                .try_into()
+                .expect("Boot sector should be 512 bytes"),
+        )
+        .expect("Ok should be returned")
+    }
+
+    fn reserved_entries(media_entry: u32, end_of_chain_entry: u32) -> ReservedEntries {
+        let mut bytes = [0; RESERVED_ENTRIES_SIZE];
+
+        write_le_u32(&mut bytes, 0, media_entry);
+        write_le_u32(&mut bytes, 4, end_of_chain_entry);
+
+        ReservedEntries::from_bytes(AllocationTableKind::Fat32, &bytes)
+    }
+
+    mod is_healthy {
+        use super::*;
+
+        #[test]
+        fn unreadable_root_directory_unhealthy() {
+            assert!(HealthSummary::new(true, 0).is_healthy());
+            assert!(!HealthSummary::new(false, 0).is_healthy());
+        }
+    }
+
+    mod check_fs_info {
+        use super::*;
+
+        #[test]
+        fn valid_sector_accepted() {
+            let mut health_summary = HealthSummary::new(true, 0);
+
+            health_summary.check_fs_info(&fat32_bios_parameter_block(), &fs_info_sector(100));
+
+            assert_eq!(health_summary.is_fs_info_valid(), Some(true));
+        }
+
+        #[test]
+        fn unknown_free_count_accepted() {
+            let mut health_summary = HealthSummary::new(true, 0);
+
+            health_summary.check_fs_info(
+                &fat32_bios_parameter_block(),
+                &fs_info_sector(FS_INFO_UNKNOWN_VALUE),
+            );
+
+            assert_eq!(health_summary.is_fs_info_valid(), Some(true));
+        }
+
+        #[test]
+        fn oversized_free_count_rejected() {
+            let mut health_summary = HealthSummary::new(true, 0);
+
+            health_summary.check_fs_info(
+                &fat32_bios_parameter_block(),
+                &fs_info_sector(FS_INFO_UNKNOWN_VALUE - 1),
+            );
+
+            assert_eq!(health_summary.is_fs_info_valid(), Some(false));
+            assert!(!health_summary.is_healthy());
+        }
+
+        #[test]
+        fn missing_signature_rejected() {
+            let mut health_summary = HealthSummary::new(true, 0);
+            let mut bytes = fs_info_sector(100);
+            bytes[0] = 0;
+
+            health_summary.check_fs_info(&fat32_bios_parameter_block(), &bytes);
+
+            assert_eq!(health_summary.is_fs_info_valid(), Some(false));
+        }
+    }
+
+    mod check_reserved_entries {
+        use super::*;
+
+        #[test]
+        fn valid_entries_accepted() {
+            let mut health_summary = HealthSummary::new(true, 0);
+
+            health_summary.check_reserved_entries(
+                &fat32_bios_parameter_block(),
+                &reserved_entries(0x0FFF_FFF8, 0x0FFF_FFFF),
+                true,
+            );
+
+            assert!(health_summary.are_media_entries_valid());
+            assert!(health_summary.are_end_of_chain_entries_valid());
+            assert_eq!(health_summary.was_cleanly_unmounted(), Some(true));
+            assert_eq!(health_summary.is_free_of_hard_errors(), Some(true));
+            assert!(health_summary.is_healthy());
+        }
+
+        #[test]
+        fn volume_flags_read() {
+            let mut health_summary = HealthSummary::new(true, 0);
+
+            health_summary.check_reserved_entries(
+                &fat32_bios_parameter_block(),
+                &reserved_entries(0x0FFF_FFF8, 0x03FF_FFFF),
+                true,
+            );
+
+            assert!(health_summary.are_end_of_chain_entries_valid());
+            assert_eq!(health_summary.was_cleanly_unmounted(), Some(false));
+            assert_eq!(health_summary.is_free_of_hard_errors(), Some(false));
+        }
+
+        #[test]
+        fn inactive_table_flags_ignored() {
+            let mut health_summary = HealthSummary::new(true, 0);
+
+            health_summary.check_reserved_entries(
+                &fat32_bios_parameter_block(),
+                &reserved_entries(0x0FFF_FFF8, 0x03FF_FFFF),
+                false,
+            );
+
+            assert_eq!(health_summary.was_cleanly_unmounted(), None);
+            assert_eq!(health_summary.is_free_of_hard_errors(), None);
+        }
+
+        #[test]
+        fn invalid_entries_rejected() {
+            let mut health_summary = HealthSummary::new(true, 0);
+
+            health_summary.check_reserved_entries(
+                &fat32_bios_parameter_block(),
+                &reserved_entries(0x0FFF_FFF8, 0x0FFF_FFFF),
+                true,
+            );
+            health_summary.check_reserved_entries(
+                &fat32_bios_parameter_block(),
+                &reserved_entries(0x0FFF_FFF0, 0x0000_0002),
+                false,
+            );
+
+            assert!(!health_summary.are_media_entries_valid());
+            assert!(!health_summary.are_end_of_chain_entries_valid());
+            assert!(!health_summary.is_healthy());
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use crate::Device;
+use crate::directory_item::DeviceDirectoryItemIterationError;
+use core::ops::ControlFlow;
+
+/// Receives the invalid directory entries encountered while searching a volume.
+///
+/// Implemented for closures taking the error, so a handler can count or log entries and stop the
+/// search by returning `ControlFlow::Break`.
+pub trait InvalidDirectoryEntryHandler<D>
+where
+    D: Device,
+{
+    fn handle(&mut self, error: DeviceDirectoryItemIterationError<D>) -> ControlFlow<()>;
+}
+
+/// Skips every invalid directory entry without reporting it.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IgnoreInvalidEntries;
+
+impl<D> InvalidDirectoryEntryHandler<D> for IgnoreInvalidEntries
+where
+    D: Device,
+{
+    fn handle(&mut self, error: DeviceDirectoryItemIterationError<D>) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+}
+
+impl<D, F> InvalidDirectoryEntryHandler<D> for F
+where
+    D: Device,
+    F: FnMut(DeviceDirectoryItemIterationError<D>) -> ControlFlow<()>,
+{
+    fn handle(&mut self, error: DeviceDirectoryItemIterationError<D>) -> ControlFlow<()> {
+        self(error)
+    }
+}
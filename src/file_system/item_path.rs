@@ -0,0 +1,227 @@
+use crate::SearchError;
+use crate::directory_entry::ShortNameCase;
+use crate::directory_item::DirectoryItem;
+
+const PATH_SEPARATOR: u8 = b'/';
+const UNDECODABLE_CHARACTER: char = '?';
+
+/// Path of the item visited by a volume-wide search, built in a caller-provided buffer from the
+/// names of the directories above it.
+#[derive(Debug)]
+pub(crate) struct ItemPath<'a> {
+    buffer: &'a mut [u8],
+    length: usize,
+}
+
+impl<'a> ItemPath<'a> {
+    pub(crate) fn new(buffer: &'a mut [u8]) -> Self {
+        Self { buffer, length: 0 }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Drops the trailing components so the path is again `length` bytes long, which must be a
+    /// length the path had before.
+    pub(crate) fn truncate(&mut self, length: usize) {
+        self.length = length.min(self.length);
+    }
+
+    /// Appends the item's name as a new component, preferring its long name.  Short names are
+    /// written as `BASE.EXT` in their recorded case, with bytes outside of ASCII replaced by `?`.
+    pub(crate) fn push(&mut self, item: &DirectoryItem) -> Result<(), SearchError> {
+        let previous_length = self.length;
+
+        let result = self.push_name(item);
+        if result.is_err() {
+            self.length = previous_length;
+        }
+
+        result
+    }
+
+    /// Drops the last component, leaving an empty path unchanged.
+    pub(crate) fn pop(&mut self) {
+        self.length = self.buffer[..self.length]
+            .iter()
+            .rposition(|&byte| byte == PATH_SEPARATOR)
+            .unwrap_or(0);
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        // Only whole characters are ever written, so the bytes are always valid UTF-8
+        core::str::from_utf8(&self.buffer[..self.length]).unwrap_or_default()
+    }
+
+    pub(crate) fn into_str(self) -> &'a str {
+        core::str::from_utf8(&self.buffer[..self.length]).unwrap_or_default()
+    }
+
+    fn push_name(&mut self, item: &DirectoryItem) -> Result<(), SearchError> {
+        if self.length > 0 {
+            self.push_char(PATH_SEPARATOR as char)?;
+        }
+
+        if let Some(long_name) = item.long_name() {
+            for character in long_name.chars() {
+                self.push_char(character)?;
+            }
+
+            return Ok(());
+        }
+
+        let short_name = item.short_name();
+        let name_case = item.short_name_case();
+
+        for &byte in short_name.base_name() {
+            self.push_byte(Self::cased(
+                byte,
+                name_case.contains(ShortNameCase::LowerCaseBaseName),
+            ))?;
+        }
+
+        if !short_name.extension().is_empty() {
+            self.push_char('.')?;
+
+            for &byte in short_name.extension() {
+                self.push_byte(Self::cased(
+                    byte,
+                    name_case.contains(ShortNameCase::LowerCaseExtension),
+                ))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn cased(byte: u8, is_lower_case: bool) -> u8 {
+        if is_lower_case {
+            byte.to_ascii_lowercase()
+        } else {
+            byte
+        }
+    }
+
+    fn push_byte(&mut self, byte: u8) -> Result<(), SearchError> {
+        if byte.is_ascii() {
+            self.push_char(byte as char)
+        } else {
+            self.push_char(UNDECODABLE_CHARACTER)
+        }
+    }
+
+    fn push_char(&mut self, character: char) -> Result<(), SearchError> {
+        let end = self.length + character.len_utf8();
+        ensure!(end <= self.buffer.len(), SearchError::PathBufferTooSmall);
+
+        character.encode_utf8(&mut self.buffer[self.length..end]);
+        self.length = end;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AsciiOnlyEncoder;
+    use crate::directory_entry::{DirectoryEntryAttributes, ShortNameDirectoryEntry};
+    use crate::file_name::{LongFileName, ShortFileName};
+    use core::str::FromStr;
+
+    fn item(short_name: &str, long_name: Option<&str>) -> DirectoryItem {
+        DirectoryItem::new(
+            ShortNameDirectoryEntry::builder()
+                .name(ShortFileName::from_str(&AsciiOnlyEncoder, short_name).unwrap())
+                .attributes(DirectoryEntryAttributes::empty())
+                .first_cluster_number(2)
+                .file_size(1)
+                .build(),
+            long_name.map(|long_name| LongFileName::from_str(long_name).unwrap()),
+        )
+    }
+
+    mod push {
+        use super::*;
+
+        #[test]
+        fn components_separated() {
+            let mut buffer = [0; 64];
+            let mut path = ItemPath::new(&mut buffer);
+
+            path.push(&item("foo", None))
+                .expect("Ok should be returned");
+            path.push(&item("bar.txt", None))
+                .expect("Ok should be returned");
+
+            assert_eq!(path.as_str(), "FOO/BAR.TXT");
+        }
+
+        #[test]
+        fn long_name_preferred() {
+            let mut buffer = [0; 64];
+            let mut path = ItemPath::new(&mut buffer);
+
+            path.push(&item("longfi~1.txt", Some("Long File.txt")))
+                .expect("Ok should be returned");
+
+            assert_eq!(path.as_str(), "Long File.txt");
+        }
+
+        #[test]
+        fn buffer_too_small_leaves_path_unchanged() {
+            let mut buffer = [0; 8];
+            let mut path = ItemPath::new(&mut buffer);
+
+            path.push(&item("foo", None))
+                .expect("Ok should be returned");
+            let result = path.push(&item("bar.txt", None));
+
+            assert_eq!(result, Err(SearchError::PathBufferTooSmall));
+            assert_eq!(path.as_str(), "FOO");
+        }
+    }
+
+    mod truncate {
+        use super::*;
+
+        #[test]
+        fn drops_trailing_components() {
+            let mut buffer = [0; 64];
+            let mut path = ItemPath::new(&mut buffer);
+
+            path.push(&item("foo", None))
+                .expect("Ok should be returned");
+            let length = path.len();
+            path.push(&item("bar.txt", None))
+                .expect("Ok should be returned");
+            path.truncate(length);
+
+            assert_eq!(path.as_str(), "FOO");
+        }
+    }
+
+    mod pop {
+        use super::*;
+
+        #[test]
+        fn drops_last_component() {
+            let mut buffer = [0; 64];
+            let mut path = ItemPath::new(&mut buffer);
+
+            path.push(&item("foo", None))
+                .expect("Ok should be returned");
+            path.push(&item("bar.txt", None))
+                .expect("Ok should be returned");
+            path.pop();
+
+            assert_eq!(path.as_str(), "FOO");
+
+            path.pop();
+            path.pop();
+
+            assert_eq!(path.as_str(), "");
+        }
+    }
+}
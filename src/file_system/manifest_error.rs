@@ -0,0 +1,69 @@
+use crate::{Device, FileError, SearchError};
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+use embedded_io::ErrorType;
+
+pub type DeviceManifestError<D> =
+    ManifestError<FileError<<D as Device>::Error, <<D as Device>::Stream as ErrorType>::Error>>;
+
+#[derive(Clone, Debug)]
+pub enum ManifestError<FE>
+where
+    FE: Error,
+{
+    FileError(FE),
+    PathBufferTooSmall,
+}
+
+impl<FE> Error for ManifestError<FE> where FE: Error {}
+
+impl<FE> Display for ManifestError<FE>
+where
+    FE: Error,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ManifestError::FileError(e) => write!(f, "reading a file failed: {}", e),
+            ManifestError::PathBufferTooSmall => {
+                write!(f, "the path buffer has no room for a file's path")
+            }
+        }
+    }
+}
+
+impl<FE> From<SearchError> for ManifestError<FE>
+where
+    FE: Error,
+{
+    fn from(value: SearchError) -> Self {
+        match value {
+            SearchError::PathBufferTooSmall => ManifestError::PathBufferTooSmall,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::CoreError;
+    use alloc::string::ToString;
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn produces_non_empty_value() {
+            let values = [
+                ManifestError::<CoreError>::FileError(CoreError),
+                ManifestError::PathBufferTooSmall,
+            ];
+
+            for value in values {
+                assert!(
+                    !value.to_string().is_empty(),
+                    "Display implementation should be non-empty"
+                );
+            }
+        }
+    }
+}
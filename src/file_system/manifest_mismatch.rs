@@ -0,0 +1,32 @@
+/// A difference between the volume and a manifest, reported by `FileSystem::verify_manifest`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ManifestMismatch<'a> {
+    /// The manifest lists a file which doesn't exist on the volume, or is a directory there.
+    Missing { path: &'a str },
+    /// A file on the volume isn't listed by the manifest.
+    Extra { path: &'a str },
+    /// The file's size differs from the one listed, its contents aren't hashed.
+    SizeMismatch {
+        path: &'a str,
+        expected: u64,
+        actual: u64,
+    },
+    /// The CRC-32 of the file's contents differs from the one listed.
+    Crc32Mismatch {
+        path: &'a str,
+        expected: u32,
+        actual: u32,
+    },
+}
+
+impl<'a> ManifestMismatch<'a> {
+    /// The path of the file, as given by the manifest for every mismatch but `Extra`.
+    pub fn path(&self) -> &'a str {
+        match self {
+            ManifestMismatch::Missing { path }
+            | ManifestMismatch::Extra { path }
+            | ManifestMismatch::SizeMismatch { path, .. }
+            | ManifestMismatch::Crc32Mismatch { path, .. } => path,
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{Device, DeviceOperationError};
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+
+pub type DeviceMetadataError<D, BE> = MetadataError<DeviceOperationError<D>, BE>;
+
+/// An error backing up or restoring the volume's metadata through
+/// `FileSystem::backup_metadata` and `FileSystem::restore_metadata`.
+#[derive(Clone, Debug)]
+pub enum MetadataError<VE, BE>
+where
+    VE: Error,
+    BE: embedded_io::Error,
+{
+    /// The backup ended before holding all of the volume's metadata.
+    BackupEndReached,
+    BackupError(BE),
+    /// The backup's boot sector describes a volume laid out differently than the mounted one, so
+    /// restoring it would corrupt the volume.
+    BackupMismatch,
+    VolumeError(VE),
+}
+
+impl<VE, BE> Error for MetadataError<VE, BE>
+where
+    VE: Error,
+    BE: embedded_io::Error,
+{
+}
+
+impl<VE, BE> Display for MetadataError<VE, BE>
+where
+    VE: Error,
+    BE: embedded_io::Error,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MetadataError::BackupEndReached => {
+                write!(f, "the backup ended before holding all of the metadata")
+            }
+            MetadataError::BackupError(e) => write!(f, "accessing the backup failed: {}", e),
+            MetadataError::BackupMismatch => {
+                write!(f, "the backup belongs to a volume with a different layout")
+            }
+            MetadataError::VolumeError(e) => write!(f, "accessing the volume failed: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{CoreError, IoError};
+    use alloc::string::ToString;
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn produces_non_empty_value() {
+            let values = [
+                MetadataError::<CoreError, IoError>::BackupEndReached,
+                MetadataError::BackupError(IoError::default()),
+                MetadataError::BackupMismatch,
+                MetadataError::VolumeError(CoreError),
+            ];
+
+            for value in values {
+                assert!(
+                    !value.to_string().is_empty(),
+                    "Display implementation should be non-empty"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,30 @@
+/// A change made to the volume, reported to the handler registered through
+/// `FileSystemBuilder::on_mutation` once the change has been written.
+///
+/// Items are identified by the path of their directory, which is empty for the root directory,
+/// and their name.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MutationEvent<'a> {
+    Created {
+        directory_path: &'a str,
+        name: &'a str,
+        first_cluster_number: u32,
+    },
+    Deleted {
+        directory_path: &'a str,
+        name: &'a str,
+        first_cluster_number: u32,
+    },
+    Renamed {
+        directory_path: &'a str,
+        name: &'a str,
+        new_name: &'a str,
+        first_cluster_number: u32,
+    },
+    WriteCompleted {
+        directory_path: &'a str,
+        name: &'a str,
+        first_cluster_number: u32,
+        file_size: u64,
+    },
+}
@@ -0,0 +1,149 @@
+use crate::file_name::ShortFileName;
+use crate::{CodePageEncoder, DirectoryItem};
+
+/// A pair of items in the same directory which a lookup by name can't tell apart, reported by
+/// `FileSystem::find_name_conflicts`.
+///
+/// Writers which skip the collision checks made when creating or renaming items can leave these
+/// behind, after which opening either name may reach the other item.  Items are given in the order
+/// they're stored in the directory.
+#[derive(Clone, Copy, Debug)]
+pub enum NameConflict<'a> {
+    /// Both items are stored under the same short name.
+    ShortNames {
+        first: &'a DirectoryItem,
+        second: &'a DirectoryItem,
+    },
+    /// Both items have long names which are equal ignoring case.
+    LongNames {
+        first: &'a DirectoryItem,
+        second: &'a DirectoryItem,
+    },
+    /// The long name of one item parses to the short name of the other, which every item can also
+    /// be opened by.
+    LongAndShortNames {
+        long_named: &'a DirectoryItem,
+        short_named: &'a DirectoryItem,
+    },
+}
+
+impl<'a> NameConflict<'a> {
+    /// The conflict between the two items, if any, reporting short names over long names since a
+    /// shared short name breaks lookups through either name.
+    pub(crate) fn between<CPE>(
+        code_page_encoder: &CPE,
+        first: &'a DirectoryItem,
+        second: &'a DirectoryItem,
+    ) -> Option<Self>
+    where
+        CPE: CodePageEncoder,
+    {
+        if first.short_name() == second.short_name() {
+            return Some(Self::ShortNames { first, second });
+        }
+
+        if let (Some(first_long_name), Some(second_long_name)) =
+            (first.long_name(), second.long_name())
+            && first_long_name == second_long_name
+        {
+            return Some(Self::LongNames { first, second });
+        }
+
+        let is_long_name_of = |long_named: &DirectoryItem, short_named: &DirectoryItem| {
+            long_named.long_name().is_some_and(|long_name| {
+                ShortFileName::from_chars(code_page_encoder, long_name.chars())
+                    .is_ok_and(|short_name| short_name == *short_named.short_name())
+            })
+        };
+
+        if is_long_name_of(first, second) {
+            Some(Self::LongAndShortNames {
+                long_named: first,
+                short_named: second,
+            })
+        } else if is_long_name_of(second, first) {
+            Some(Self::LongAndShortNames {
+                long_named: second,
+                short_named: first,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AsciiOnlyEncoder;
+    use crate::directory_entry::{DirectoryEntryAttributes, ShortNameDirectoryEntry};
+    use crate::file_name::LongFileName;
+    use core::str::FromStr;
+
+    fn item(short_name: &str, long_name: Option<&str>) -> DirectoryItem {
+        DirectoryItem::new(
+            ShortNameDirectoryEntry::builder()
+                .name(ShortFileName::from_str(&AsciiOnlyEncoder, short_name).unwrap())
+                .attributes(DirectoryEntryAttributes::Archive)
+                .first_cluster_number(2)
+                .file_size(1)
+                .build(),
+            long_name.map(|long_name| LongFileName::from_str(long_name).unwrap()),
+        )
+    }
+
+    mod between {
+        use super::*;
+
+        #[test]
+        fn same_short_names_conflict() {
+            let first = item("foo.txt", Some("foo long.txt"));
+            let second = item("FOO.TXT", None);
+
+            let conflict = NameConflict::between(&AsciiOnlyEncoder, &first, &second);
+
+            assert!(matches!(
+                conflict,
+                Some(NameConflict::ShortNames { first: f, second: s })
+                    if core::ptr::eq(f, &first) && core::ptr::eq(s, &second)
+            ));
+        }
+
+        #[test]
+        fn long_names_differing_in_case_conflict() {
+            let first = item("foolon~1.txt", Some("Foo long.txt"));
+            let second = item("foolon~2.txt", Some("FOO LONG.TXT"));
+
+            let conflict = NameConflict::between(&AsciiOnlyEncoder, &first, &second);
+
+            assert!(matches!(conflict, Some(NameConflict::LongNames { .. })));
+        }
+
+        #[test]
+        fn long_name_parsing_to_short_name_conflicts() {
+            let first = item("bar.txt", None);
+            let second = item("_bar~1.txt", Some("bar.TXT"));
+
+            let conflict = NameConflict::between(&AsciiOnlyEncoder, &first, &second);
+
+            assert!(matches!(
+                conflict,
+                Some(NameConflict::LongAndShortNames {
+                    long_named,
+                    short_named,
+                }) if core::ptr::eq(long_named, &second) && core::ptr::eq(short_named, &first)
+            ));
+        }
+
+        #[test]
+        fn distinct_names_do_not_conflict() {
+            let first = item("foolon~1.txt", Some("Foo long.txt"));
+            let second = item("foolon~2.txt", Some("Foo longer.txt"));
+            let third = item("foo.txt", None);
+
+            assert!(NameConflict::between(&AsciiOnlyEncoder, &first, &second).is_none());
+            assert!(NameConflict::between(&AsciiOnlyEncoder, &first, &third).is_none());
+            assert!(NameConflict::between(&AsciiOnlyEncoder, &third, &second).is_none());
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use crate::Device;
+use crate::FsInfoError;
+use crate::LongFileNameError;
+use crate::TimestampError;
+use crate::allocation_table::AllocationTableError;
+use crate::directory_entry::{DirectoryEntryIterationError, ShortNameDirectoryEntry};
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+use embedded_io::{ErrorType, ReadExactError};
+
+pub type DeviceOperationError<D> =
+    OperationError<<D as Device>::Error, <<D as Device>::Stream as ErrorType>::Error>;
+
+#[derive(Clone, Debug)]
+pub enum OperationError<DE, SE>
+where
+    DE: Error,
+    SE: embedded_io::Error,
+{
+    AllocationTableIndexInvalid {
+        table_index: u8,
+    },
+    AlreadyExists,
+    BufferSizeInvalid {
+        expected: usize,
+    },
+    ClusterNumberInvalid {
+        cluster_number: u32,
+    },
+    ContentsTooLarge,
+    DeviceError(DE),
+    DirectoryFull,
+    EntryIterationError(DirectoryEntryIterationError<DE, SE>),
+    FsInfoInvalid(FsInfoError),
+    /// The medium was replaced since the file system was mounted, as detected by
+    /// `FileSystem::revalidate`.
+    MediaChanged,
+    NameInvalid(LongFileNameError),
+    /// The requested name is a short name already taken by another item in the directory, either
+    /// as its name or as the alias of its long name.
+    NameCollision {
+        existing: ShortNameDirectoryEntry,
+    },
+    NotAFile,
+    NotFound,
+    /// The change would take a directory beyond the quota set for it by the registered
+    /// `QuotaPolicy`.
+    QuotaExceeded,
+    ShortNameAliasesExhausted,
+    StreamEndReached,
+    StreamError(SE),
+    /// The timestamp can't be stored, such as a UTC timestamp which falls outside of the dates FAT
+    /// can store once converted to local time.
+    TimestampInvalid(TimestampError),
+    VolumeFull,
+}
+
+impl<DE, SE> Error for OperationError<DE, SE>
+where
+    DE: Error,
+    SE: embedded_io::Error,
+{
+}
+
+impl<DE, SE> Display for OperationError<DE, SE>
+where
+    DE: Error,
+    SE: embedded_io::Error,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            OperationError::AllocationTableIndexInvalid { table_index } => write!(
+                f,
+                "the volume has no allocation table at index {table_index}"
+            ),
+            OperationError::AlreadyExists => {
+                write!(f, "an item with the requested name already exists")
+            }
+            OperationError::BufferSizeInvalid { expected } => {
+                write!(f, "the buffer must be exactly {expected} bytes long")
+            }
+            OperationError::ClusterNumberInvalid { cluster_number } => write!(
+                f,
+                "cluster {cluster_number} is not a valid data region cluster number"
+            ),
+            OperationError::ContentsTooLarge => {
+                write!(f, "the contents are larger than a file can hold")
+            }
+            OperationError::DeviceError(e) => write!(f, "device error occurred: {}", e),
+            OperationError::DirectoryFull => {
+                write!(f, "the directory has no room for the item's entries")
+            }
+            OperationError::EntryIterationError(e) => {
+                write!(f, "accessing the directory's entries failed: {}", e)
+            }
+            OperationError::FsInfoInvalid(e) => {
+                write!(f, "the FS information sector is invalid: {}", e)
+            }
+            OperationError::MediaChanged => {
+                write!(f, "the medium was changed since the volume was mounted")
+            }
+            OperationError::NameInvalid(e) => write!(f, "the requested name is invalid: {}", e),
+            OperationError::NameCollision { .. } => {
+                write!(f, "another item already uses the requested short name")
+            }
+            OperationError::NotAFile => write!(f, "the item is not a file"),
+            OperationError::NotFound => write!(f, "the item was not found"),
+            OperationError::QuotaExceeded => {
+                write!(f, "the change would exceed the directory's quota")
+            }
+            OperationError::ShortNameAliasesExhausted => {
+                write!(f, "every short name alias for the requested name is in use")
+            }
+            OperationError::StreamEndReached => {
+                write!(f, "stream end was reached when not expected")
+            }
+            OperationError::StreamError(e) => write!(f, "stream error occurred: {}", e),
+            OperationError::TimestampInvalid(e) => write!(f, "the timestamp is invalid: {}", e),
+            OperationError::VolumeFull => write!(f, "the volume has no free clusters left"),
+        }
+    }
+}
+
+impl<DE, SE> From<DirectoryEntryIterationError<DE, SE>> for OperationError<DE, SE>
+where
+    DE: Error,
+    SE: embedded_io::Error,
+{
+    fn from(value: DirectoryEntryIterationError<DE, SE>) -> Self {
+        OperationError::EntryIterationError(value)
+    }
+}
+
+impl<DE, SE> From<AllocationTableError<SE>> for OperationError<DE, SE>
+where
+    DE: Error,
+    SE: embedded_io::Error,
+{
+    fn from(value: AllocationTableError<SE>) -> Self {
+        match value {
+            AllocationTableError::EntryValueInvalid { cluster_number } => {
+                OperationError::ClusterNumberInvalid { cluster_number }
+            }
+            AllocationTableError::StreamEndReached => OperationError::StreamEndReached,
+            AllocationTableError::StreamError(stream_error) => {
+                OperationError::StreamError(stream_error)
+            }
+        }
+    }
+}
+
+impl<DE, SE> From<FsInfoError> for OperationError<DE, SE>
+where
+    DE: Error,
+    SE: embedded_io::Error,
+{
+    fn from(value: FsInfoError) -> Self {
+        OperationError::FsInfoInvalid(value)
+    }
+}
+
+impl<DE, SE> From<LongFileNameError> for OperationError<DE, SE>
+where
+    DE: Error,
+    SE: embedded_io::Error,
+{
+    fn from(value: LongFileNameError) -> Self {
+        OperationError::NameInvalid(value)
+    }
+}
+
+impl<DE, SE> From<SE> for OperationError<DE, SE>
+where
+    DE: Error,
+    SE: embedded_io::Error,
+{
+    fn from(value: SE) -> Self {
+        OperationError::StreamError(value)
+    }
+}
+
+impl<DE, SE> From<ReadExactError<SE>> for OperationError<DE, SE>
+where
+    DE: Error,
+    SE: embedded_io::Error,
+{
+    fn from(value: ReadExactError<SE>) -> Self {
+        match value {
+            ReadExactError::Other(stream_error) => stream_error.into(),
+            ReadExactError::UnexpectedEof => OperationError::StreamEndReached,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    mod display {
+        use super::*;
+        use crate::AsciiOnlyEncoder;
+        use crate::directory_entry::DirectoryEntryAttributes;
+        use crate::file_name::ShortFileName;
+        use crate::mock::IoError;
+
+        #[test]
+        fn produces_non_empty_value() {
+            let values: [OperationError<IoError, IoError>; 20] = [
+                OperationError::AllocationTableIndexInvalid { table_index: 2 },
+                OperationError::AlreadyExists,
+                OperationError::BufferSizeInvalid { expected: 512 },
+                OperationError::ClusterNumberInvalid { cluster_number: 1 },
+                OperationError::ContentsTooLarge,
+                OperationError::DeviceError(IoError::default()),
+                OperationError::DirectoryFull,
+                OperationError::EntryIterationError(DirectoryEntryIterationError::StreamEndReached),
+                OperationError::FsInfoInvalid(FsInfoError::LeadSignatureInvalid(0)),
+                OperationError::MediaChanged,
+                OperationError::NameInvalid(LongFileNameError::InputEmpty),
+                OperationError::NameCollision {
+                    existing: ShortNameDirectoryEntry::builder()
+                        .name(
+                            ShortFileName::from_str(&AsciiOnlyEncoder, "FOO.TXT")
+                                .expect("Name is valid"),
+                        )
+                        .attributes(DirectoryEntryAttributes::Archive)
+                        .first_cluster_number(2)
+                        .file_size(1)
+                        .build(),
+                },
+                OperationError::NotAFile,
+                OperationError::NotFound,
+                OperationError::QuotaExceeded,
+                OperationError::ShortNameAliasesExhausted,
+                OperationError::StreamEndReached,
+                OperationError::StreamError(IoError::default()),
+                OperationError::TimestampInvalid(TimestampError::MonthInvalid { month: 0 }),
+                OperationError::VolumeFull,
+            ];
+
+            for value in values {
+                assert!(
+                    !value.to_string().is_empty(),
+                    "Display implementation should be non-empty"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use crate::boot_sector::BiosParameterBlock;
+use crate::units::{ByteAddress, ClusterNumber};
+
+/// Where the entries of the root directory are stored, resolved from the boot sector once when the
+/// file system is assembled so lookups starting at the root don't repeat the address arithmetic.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum RootDirectoryLocation {
+    /// The fixed-size table following the allocation tables of FAT12 and FAT16 volumes.
+    #[cfg(any(feature = "fat12", feature = "fat16"))]
+    Table {
+        start_address: ByteAddress,
+        entry_count: u16,
+    },
+    /// The cluster chain of a FAT32 volume's root directory, starting at the given cluster.
+    File { start_cluster_number: ClusterNumber },
+}
+
+impl RootDirectoryLocation {
+    pub(crate) fn resolve(bios_parameter_block: &BiosParameterBlock) -> Self {
+        #[cfg(any(feature = "fat12", feature = "fat16"))]
+        if !bios_parameter_block
+            .allocation_table_kind()
+            .has_root_directory_file()
+        {
+            return Self::Table {
+                start_address: bios_parameter_block.directory_table_base_address(),
+                entry_count: bios_parameter_block.directory_table_entry_count(),
+            };
+        }
+
+        // NOTE: the boot sector is validated to have a root directory cluster for these volumes,
+        //   falling back to an invalid cluster number surfaces an error instead of panicking.
+        Self::File {
+            start_cluster_number: bios_parameter_block
+                .root_directory_file_cluster_number()
+                .unwrap_or(ClusterNumber::NONE),
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SearchError {
+    PathBufferTooSmall,
+}
+
+impl Error for SearchError {}
+
+impl Display for SearchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SearchError::PathBufferTooSmall => {
+                write!(f, "the path buffer has no room for a matching item's path")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn produces_non_empty_value() {
+            let values = [SearchError::PathBufferTooSmall];
+
+            for value in values {
+                assert!(
+                    !value.to_string().is_empty(),
+                    "Display implementation should be non-empty"
+                );
+            }
+        }
+    }
+}
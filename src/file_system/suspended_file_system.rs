@@ -0,0 +1,167 @@
+use crate::directory_entry::DEFAULT_BUFFER_SIZE;
+use crate::file_system::{FixedFilePolicy, InvalidDirectoryEntryHandler, QuotaPolicy};
+use crate::{
+    CodePageEncoder, Device, FileSystem, FileSystemError, InvalidTimestampPolicy, MutationEvent,
+};
+use core::ops::RangeInclusive;
+use embedded_io::ErrorType;
+
+#[cfg(feature = "sync")]
+use {
+    crate::SyncDevice,
+    embedded_io::{Read, Seek},
+};
+
+#[cfg(feature = "async")]
+use {
+    crate::AsyncDevice,
+    embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek},
+};
+
+type ResumeResult<D, CPE, IDE, ME, const BUFFER_SIZE: usize> = Result<
+    FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>,
+    (
+        SuspendedFileSystem<D, CPE, IDE, ME, BUFFER_SIZE>,
+        FileSystemError<<D as Device>::Error, <<D as Device>::Stream as ErrorType>::Error>,
+    ),
+>;
+
+/// A file system which released its volume, keeping what is needed to mount it again once the
+/// medium is handed back.
+///
+/// While suspended the device may be used directly, such as by a USB mass storage class driver
+/// exposing the medium to a host.  The volume may change in any way in the meantime, so resuming
+/// reads the boot sector and allocation table again rather than trusting what was known before.
+#[derive(Clone, Debug)]
+pub struct SuspendedFileSystem<D, CPE, IDE, ME, const BUFFER_SIZE: usize = DEFAULT_BUFFER_SIZE>
+where
+    D: Device,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    device: D,
+    code_page_encoder: CPE,
+    on_invalid_directory_entry: IDE,
+    on_mutation: ME,
+    quota_policy: Option<QuotaPolicy>,
+    fixed_file_policy: Option<FixedFilePolicy>,
+    use_short_name_case: bool,
+    utc_offset_minutes: i16,
+    invalid_timestamp_policy: InvalidTimestampPolicy,
+    reserved_cluster_ranges: &'static [RangeInclusive<u32>],
+    yield_interval: u32,
+}
+
+impl<D, CPE, IDE, ME, const BUFFER_SIZE: usize> SuspendedFileSystem<D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: Device,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        device: D,
+        code_page_encoder: CPE,
+        on_invalid_directory_entry: IDE,
+        on_mutation: ME,
+        quota_policy: Option<QuotaPolicy>,
+        fixed_file_policy: Option<FixedFilePolicy>,
+        use_short_name_case: bool,
+        utc_offset_minutes: i16,
+        invalid_timestamp_policy: InvalidTimestampPolicy,
+        reserved_cluster_ranges: &'static [RangeInclusive<u32>],
+        yield_interval: u32,
+    ) -> Self {
+        Self {
+            device,
+            code_page_encoder,
+            on_invalid_directory_entry,
+            on_mutation,
+            quota_policy,
+            fixed_file_policy,
+            use_short_name_case,
+            utc_offset_minutes,
+            invalid_timestamp_policy,
+            reserved_cluster_ranges,
+            yield_interval,
+        }
+    }
+
+    /// The device the volume is stored on, for handing the medium to another host.
+    pub fn device(&self) -> &D {
+        &self.device
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<D, S, CPE, IDE, ME, const BUFFER_SIZE: usize> SuspendedFileSystem<D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Seek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    /// Mounts the volume again, marking the file system as remounted.  The suspended file system
+    /// is returned along with the error if the volume is no longer valid.
+    pub fn resume(self) -> ResumeResult<D, CPE, IDE, ME, BUFFER_SIZE> {
+        match FileSystem::<D, CPE, IDE, ME, BUFFER_SIZE>::remount_bios_parameter_block(&self.device)
+        {
+            Ok(bios_parameter_block) => Ok(FileSystem::from_parts(
+                self.device,
+                self.code_page_encoder,
+                self.on_invalid_directory_entry,
+                self.on_mutation,
+                self.quota_policy,
+                self.fixed_file_policy,
+                self.use_short_name_case,
+                self.utc_offset_minutes,
+                self.invalid_timestamp_policy,
+                self.reserved_cluster_ranges,
+                bios_parameter_block,
+                self.yield_interval,
+                true,
+            )),
+            Err(error) => Err((self, error)),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, S, CPE, IDE, ME, const BUFFER_SIZE: usize> SuspendedFileSystem<D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncSeek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    /// Mounts the volume again, marking the file system as remounted.  The suspended file system
+    /// is returned along with the error if the volume is no longer valid.
+    pub async fn resume_async(self) -> ResumeResult<D, CPE, IDE, ME, BUFFER_SIZE> {
+        match FileSystem::<D, CPE, IDE, ME, BUFFER_SIZE>::remount_bios_parameter_block_async(
+            &self.device,
+        )
+        .await
+        {
+            Ok(bios_parameter_block) => Ok(FileSystem::from_parts(
+                self.device,
+                self.code_page_encoder,
+                self.on_invalid_directory_entry,
+                self.on_mutation,
+                self.quota_policy,
+                self.fixed_file_policy,
+                self.use_short_name_case,
+                self.utc_offset_minutes,
+                self.invalid_timestamp_policy,
+                self.reserved_cluster_ranges,
+                bios_parameter_block,
+                self.yield_interval,
+                true,
+            )),
+            Err(error) => Err((self, error)),
+        }
+    }
+}
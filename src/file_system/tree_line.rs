@@ -0,0 +1,125 @@
+use crate::directory_item::DirectoryItem;
+use core::fmt::{Result, Write};
+
+const INDENT: &str = "  ";
+const COLUMN_SEPARATOR: &str = "  ";
+
+/// Writes the line describing an item in a tree dump, indented by its depth below the root.
+///
+/// Directories are suffixed with `/`, files are followed by their size in bytes, and both by their
+/// last write timestamp or `-` if it doesn't hold a date and time which exist.
+pub(crate) fn write_tree_line<W>(writer: &mut W, depth: usize, item: &DirectoryItem) -> Result
+where
+    W: Write,
+{
+    for _ in 0..depth {
+        writer.write_str(INDENT)?;
+    }
+
+    for character in item.name_chars() {
+        writer.write_char(character)?;
+    }
+
+    if item.is_directory() {
+        writer.write_char('/')?;
+    } else {
+        write!(writer, "{COLUMN_SEPARATOR}{}", item.extended_file_size())?;
+    }
+
+    writer.write_str(COLUMN_SEPARATOR)?;
+
+    match item.last_write_timestamp() {
+        Ok((date, time)) => write!(
+            writer,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            date.year(),
+            date.month(),
+            date.day(),
+            time.hour(),
+            time.minute(),
+            time.second()
+        )?,
+        Err(_) => writer.write_char('-')?,
+    }
+
+    writer.write_char('\n')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AsciiOnlyEncoder;
+    use crate::directory_entry::{DirectoryEntryAttributes, ShortNameDirectoryEntry};
+    use crate::file_name::{LongFileName, ShortFileName};
+    use alloc::string::String;
+    use core::str::FromStr;
+
+    fn item(
+        short_name: &str,
+        long_name: Option<&str>,
+        attributes: DirectoryEntryAttributes,
+        file_size: u32,
+        last_write_date: u16,
+    ) -> DirectoryItem {
+        DirectoryItem::new(
+            ShortNameDirectoryEntry::builder()
+                .name(
+                    ShortFileName::from_str(&AsciiOnlyEncoder, short_name).expect("Name is valid"),
+                )
+                .attributes(attributes)
+                .first_cluster_number(5)
+                .file_size(file_size)
+                .last_write_date(last_write_date)
+                .last_write_time(0x6000)
+                .build(),
+            long_name.map(|long_name| LongFileName::from_str(long_name).expect("Name is valid")),
+        )
+    }
+
+    fn line(depth: usize, item: &DirectoryItem) -> String {
+        let mut line = String::new();
+        write_tree_line(&mut line, depth, item).expect("Writing to a string works");
+
+        line
+    }
+
+    mod write_tree_line {
+        use super::*;
+
+        #[test]
+        fn files_listed_with_size_and_timestamp() {
+            let item = item(
+                "LOG.TXT",
+                Some("Event log.txt"),
+                DirectoryEntryAttributes::Archive,
+                1234,
+                0x58A1,
+            );
+
+            assert_eq!(
+                line(2, &item),
+                "    Event log.txt  1234  2024-05-01 12:00:00\n"
+            );
+        }
+
+        #[test]
+        fn directories_suffixed() {
+            let item = item(
+                "DATA",
+                None,
+                DirectoryEntryAttributes::Subdirectory,
+                0,
+                0x58A1,
+            );
+
+            assert_eq!(line(0, &item), "DATA/  2024-05-01 12:00:00\n");
+        }
+
+        #[test]
+        fn invalid_timestamps_replaced() {
+            let item = item("A.BIN", None, DirectoryEntryAttributes::Archive, 0, 0);
+
+            assert_eq!(line(1, &item), "  A.BIN  0  -\n");
+        }
+    }
+}
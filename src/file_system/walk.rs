@@ -0,0 +1,136 @@
+use crate::Device;
+use crate::directory::Directory;
+use crate::directory_entry::DEFAULT_BUFFER_SIZE;
+use crate::directory_item::{
+    DeviceDirectoryItemIterationError, DirectoryItem, DirectoryItemIterator,
+};
+use crate::yield_budget::YieldBudget;
+
+#[cfg(feature = "sync")]
+use {
+    crate::SyncDevice,
+    embedded_io::{Read, Seek},
+};
+
+#[cfg(feature = "async")]
+use {
+    crate::AsyncDevice,
+    embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek},
+};
+
+/// Depth-first walk over the items of a directory tree, holding one item iterator for each of up
+/// to `MAX_DEPTH` directory levels.
+///
+/// The walk only descends into the directories it is told to, so callers decide which items are
+/// searched below.  The `.` and `..` entries and the volume label are left out.
+#[derive(Clone, Debug)]
+pub(crate) struct Walk<
+    'a,
+    D,
+    const MAX_DEPTH: usize,
+    const BUFFER_SIZE: usize = DEFAULT_BUFFER_SIZE,
+> where
+    D: Device,
+{
+    levels: [Option<DirectoryItemIterator<'a, D, BUFFER_SIZE>>; MAX_DEPTH],
+    depth: usize,
+    yield_budget: YieldBudget,
+}
+
+impl<'a, D, const MAX_DEPTH: usize, const BUFFER_SIZE: usize> Walk<'a, D, MAX_DEPTH, BUFFER_SIZE>
+where
+    D: Device,
+{
+    /// Starts a walk at the root directory, yielding to the executor every `yield_interval` items
+    /// when walked asynchronously.
+    pub(crate) fn new(root_directory: Directory<'a, D, BUFFER_SIZE>, yield_interval: u32) -> Self {
+        let mut walk = Self {
+            levels: [const { None }; MAX_DEPTH],
+            depth: 0,
+            yield_budget: YieldBudget::new(yield_interval),
+        };
+
+        walk.descend(root_directory);
+        walk
+    }
+
+    /// The number of directory levels currently being walked, the most recently returned item
+    /// belongs to the last of them.
+    pub(crate) fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Walks the directory's items before continuing with the rest of the current one, ignored
+    /// once `MAX_DEPTH` levels are being walked.
+    pub(crate) fn descend(&mut self, directory: Directory<'a, D, BUFFER_SIZE>) {
+        if self.depth < MAX_DEPTH {
+            self.levels[self.depth] = Some(directory.items().with_dot_items_skipped(true));
+            self.depth += 1;
+        }
+    }
+
+    /// Ends the walk, no more items are returned afterwards.
+    pub(crate) fn stop(&mut self) {
+        while self.depth > 0 {
+            self.ascend();
+        }
+    }
+
+    pub(crate) fn can_descend(&self) -> bool {
+        self.depth < MAX_DEPTH
+    }
+
+    fn current_level(&mut self) -> Option<&mut DirectoryItemIterator<'a, D, BUFFER_SIZE>> {
+        self.levels[self.depth.checked_sub(1)?].as_mut()
+    }
+
+    fn ascend(&mut self) {
+        self.depth -= 1;
+        self.levels[self.depth] = None;
+    }
+
+    fn is_walked(item: &DirectoryItem) -> bool {
+        !item.is_volume_label()
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<D, S, const MAX_DEPTH: usize, const BUFFER_SIZE: usize> Iterator
+    for Walk<'_, D, MAX_DEPTH, BUFFER_SIZE>
+where
+    D: SyncDevice<Stream = S>,
+    S: Read + Seek,
+{
+    type Item = Result<DirectoryItem, DeviceDirectoryItemIterationError<D>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.current_level()?.next() {
+                Some(Ok(item)) if !Self::is_walked(&item) => continue,
+                Some(result) => return Some(result),
+                None => self.ascend(),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, S, const MAX_DEPTH: usize, const BUFFER_SIZE: usize> Walk<'_, D, MAX_DEPTH, BUFFER_SIZE>
+where
+    D: AsyncDevice<Stream = S>,
+    S: AsyncRead + AsyncSeek,
+{
+    pub(crate) async fn next_async(
+        &mut self,
+    ) -> Option<Result<DirectoryItem, DeviceDirectoryItemIterationError<D>>> {
+        loop {
+            self.yield_budget.tick().await;
+
+            match self.current_level()?.next_async().await {
+                Some(Ok(item)) if !Self::is_walked(&item) => continue,
+                Some(result) => return Some(result),
+                None => self.ascend(),
+            }
+        }
+    }
+}
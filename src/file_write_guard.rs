@@ -0,0 +1,468 @@
+use crate::directory::Directory;
+use crate::directory_entry::{DEFAULT_BUFFER_SIZE, DirectoryEntryAttributes};
+use crate::directory_item::{DirectoryItemEntries, EntryLocation};
+use crate::file_system::DirectoryQuotaUsage;
+use crate::{
+    CodePageEncoder, Device, DirectoryItem, File, FileError, FileSystem,
+    InvalidDirectoryEntryHandler, MutationEvent,
+};
+use embedded_io::{ErrorType, SeekFrom};
+
+#[cfg(feature = "sync")]
+use {
+    crate::SyncFlushableDevice,
+    embedded_io::{Read, Seek, Write},
+};
+
+#[cfg(feature = "async")]
+use {
+    crate::AsyncFlushableDevice,
+    embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek, Write as AsyncWrite},
+};
+
+/// The item's entries pointing at the file's current first cluster and size, marked as changed
+/// since the file was last archived.
+fn written_item_entries<D>(item: &DirectoryItem, file: &File<'_, D>) -> DirectoryItemEntries
+where
+    D: Device,
+{
+    let short_directory_entry = item.short_directory_entry();
+
+    DirectoryItemEntries::builder()
+        .maybe_long_name(item.long_name().cloned())
+        .short_name(short_directory_entry.name().clone())
+        .short_name_case(short_directory_entry.name_case())
+        .attributes(short_directory_entry.attributes() | DirectoryEntryAttributes::Archive)
+        .creation_time_tenths(short_directory_entry.creation_time_tenths())
+        .creation_time(short_directory_entry.creation_time())
+        .creation_date(short_directory_entry.creation_date())
+        .last_access_date(short_directory_entry.last_access_date())
+        .last_write_time(short_directory_entry.last_write_time())
+        .last_write_date(short_directory_entry.last_write_date())
+        .first_cluster_number(file.first_cluster_number())
+        .file_size(file.size())
+        .build()
+}
+
+/// Whether writing the bytes at the file's position keeps its directory within the quota.
+/// Growth is measured against the size the quota usage counted the file at.
+fn is_write_within_quota<D>(
+    quota_usage: Option<DirectoryQuotaUsage>,
+    measured_file_size: u64,
+    file: &File<'_, D>,
+    write_size: usize,
+) -> bool
+where
+    D: Device,
+{
+    let written_end = file.position().saturating_add(write_size as u64);
+    let added_byte_count = written_end
+        .max(file.size())
+        .saturating_sub(measured_file_size);
+
+    added_byte_count == 0
+        || quota_usage.is_none_or(|quota_usage| quota_usage.allows(0, added_byte_count))
+}
+
+/// A file opened for writing which records its new size in its directory entry and flushes the
+/// device once written to, at the latest when dropped.
+///
+/// Writes through a bare [`File`] only reach the data region, so a file written without updating
+/// its entry loses the written bytes on the next mount.  The guard tracks whether it was written
+/// to since it was last flushed, so dropping it without calling `close` or `flush` still leaves
+/// the volume consistent.
+#[cfg(feature = "sync")]
+#[derive(Debug)]
+pub struct FileWriteGuard<'a, D, CPE, IDE, ME, const BUFFER_SIZE: usize = DEFAULT_BUFFER_SIZE>
+where
+    D: SyncFlushableDevice,
+    D::Stream: Read + Write + Seek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    file_system: &'a FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>,
+    directory: Directory<'a, D, BUFFER_SIZE>,
+    directory_path: &'a str,
+    name: &'a str,
+
+    item: DirectoryItem,
+    location: EntryLocation,
+    file: File<'a, D>,
+
+    /// The directory's quota usage measured when the guard was created, when it has a quota,
+    /// which counted the file at `measured_file_size` bytes.
+    quota_usage: Option<DirectoryQuotaUsage>,
+    measured_file_size: u64,
+    is_dirty: bool,
+}
+
+#[cfg(feature = "sync")]
+impl<'a, D, CPE, IDE, ME, const BUFFER_SIZE: usize> FileWriteGuard<'a, D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: SyncFlushableDevice,
+    D::Stream: Read + Write + Seek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        file_system: &'a FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>,
+        directory: Directory<'a, D, BUFFER_SIZE>,
+        directory_path: &'a str,
+        name: &'a str,
+        item: DirectoryItem,
+        location: EntryLocation,
+        file: File<'a, D>,
+    ) -> Self {
+        let quota_usage = file_system.directory_quota_usage(&directory, directory_path);
+        let measured_file_size = u64::from(item.file_size());
+
+        Self {
+            file_system,
+            directory,
+            directory_path,
+            name,
+
+            item,
+            location,
+            file,
+
+            quota_usage,
+            measured_file_size,
+            is_dirty: false,
+        }
+    }
+
+    /// The file being written, for inspecting its size and position.
+    pub fn file(&self) -> &File<'a, D> {
+        &self.file
+    }
+
+    /// Whether bytes were written since the guard was last flushed.
+    pub fn is_dirty(&self) -> bool {
+        self.is_dirty
+    }
+
+    /// Flushes the file, reporting errors which would otherwise be ignored when the guard is
+    /// dropped.
+    pub fn close(mut self) -> Result<(), <Self as ErrorType>::Error> {
+        self.flush()
+    }
+
+    /// Rewrites the item's entries in a single write, see `written_item_entries`.
+    fn write_entry(&self) -> Result<(), <Self as ErrorType>::Error> {
+        self.directory.write_entries(
+            self.location.position(),
+            written_item_entries(&self.item, &self.file).entries(),
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<D, CPE, IDE, ME, const BUFFER_SIZE: usize> ErrorType
+    for FileWriteGuard<'_, D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: SyncFlushableDevice,
+    D::Stream: Read + Write + Seek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    type Error = FileError<D::Error, <D::Stream as ErrorType>::Error>;
+}
+
+#[cfg(feature = "sync")]
+impl<D, CPE, IDE, ME, const BUFFER_SIZE: usize> Read
+    for FileWriteGuard<'_, D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: SyncFlushableDevice,
+    D::Stream: Read + Write + Seek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.file.read(buf)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<D, CPE, IDE, ME, const BUFFER_SIZE: usize> Seek
+    for FileWriteGuard<'_, D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: SyncFlushableDevice,
+    D::Stream: Read + Write + Seek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        self.file.seek(pos)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<D, CPE, IDE, ME, const BUFFER_SIZE: usize> Write
+    for FileWriteGuard<'_, D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: SyncFlushableDevice,
+    D::Stream: Read + Write + Seek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        ensure!(
+            is_write_within_quota(
+                self.quota_usage,
+                self.measured_file_size,
+                &self.file,
+                buf.len()
+            ),
+            FileError::QuotaExceeded
+        );
+
+        // A failed write may still have allocated clusters or zero-filled part of a gap
+        if !buf.is_empty() {
+            self.is_dirty = true;
+        }
+
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        if !self.is_dirty {
+            return self.file.flush();
+        }
+
+        self.write_entry()?;
+        self.file.flush()?;
+        self.is_dirty = false;
+
+        self.file_system
+            .notify_mutation(MutationEvent::WriteCompleted {
+                directory_path: self.directory_path,
+                name: self.name,
+                first_cluster_number: self.file.first_cluster_number(),
+                file_size: self.file.size(),
+            });
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<D, CPE, IDE, ME, const BUFFER_SIZE: usize> Drop
+    for FileWriteGuard<'_, D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: SyncFlushableDevice,
+    D::Stream: Read + Write + Seek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    fn drop(&mut self) {
+        if self.is_dirty {
+            // Errors can't be reported from drop, `close` reports them instead
+            let _ = self.flush();
+        }
+    }
+}
+
+/// A file opened for writing which records its new size in its directory entry and flushes the
+/// device when flushed or closed, the async counterpart of [`FileWriteGuard`].
+///
+/// Flushing can't be awaited when dropped, so the guard must be closed or flushed after writing:
+/// bytes written since the last flush are otherwise lost on the next mount.
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct AsyncFileWriteGuard<'a, D, CPE, IDE, ME, const BUFFER_SIZE: usize = DEFAULT_BUFFER_SIZE>
+where
+    D: AsyncFlushableDevice,
+    D::Stream: AsyncRead + AsyncWrite + AsyncSeek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    file_system: &'a FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>,
+    directory: Directory<'a, D, BUFFER_SIZE>,
+    directory_path: &'a str,
+    name: &'a str,
+
+    item: DirectoryItem,
+    location: EntryLocation,
+    file: File<'a, D>,
+
+    /// The directory's quota usage measured when the guard was created, when it has a quota,
+    /// which counted the file at `measured_file_size` bytes.
+    quota_usage: Option<DirectoryQuotaUsage>,
+    measured_file_size: u64,
+    is_dirty: bool,
+}
+
+#[cfg(feature = "async")]
+impl<'a, D, CPE, IDE, ME, const BUFFER_SIZE: usize>
+    AsyncFileWriteGuard<'a, D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: AsyncFlushableDevice,
+    D::Stream: AsyncRead + AsyncWrite + AsyncSeek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn new(
+        file_system: &'a FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>,
+        directory: Directory<'a, D, BUFFER_SIZE>,
+        directory_path: &'a str,
+        name: &'a str,
+        item: DirectoryItem,
+        location: EntryLocation,
+        file: File<'a, D>,
+    ) -> Self {
+        let quota_usage = file_system
+            .directory_quota_usage_async(&directory, directory_path)
+            .await;
+        let measured_file_size = u64::from(item.file_size());
+
+        Self {
+            file_system,
+            directory,
+            directory_path,
+            name,
+
+            item,
+            location,
+            file,
+
+            quota_usage,
+            measured_file_size,
+            is_dirty: false,
+        }
+    }
+
+    /// The file being written, for inspecting its size and position.
+    pub fn file(&self) -> &File<'a, D> {
+        &self.file
+    }
+
+    /// Whether bytes were written since the guard was last flushed.
+    pub fn is_dirty(&self) -> bool {
+        self.is_dirty
+    }
+
+    /// Flushes the file, which must happen before the guard is dropped for the written bytes to
+    /// persist.
+    pub async fn close(mut self) -> Result<(), <Self as ErrorType>::Error> {
+        self.flush().await
+    }
+
+    /// Rewrites the item's entries in a single write, see `written_item_entries`.
+    async fn write_entry(&self) -> Result<(), <Self as ErrorType>::Error> {
+        self.directory
+            .write_entries_async(
+                self.location.position(),
+                written_item_entries(&self.item, &self.file).entries(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, CPE, IDE, ME, const BUFFER_SIZE: usize> ErrorType
+    for AsyncFileWriteGuard<'_, D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: AsyncFlushableDevice,
+    D::Stream: AsyncRead + AsyncWrite + AsyncSeek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    type Error = FileError<D::Error, <D::Stream as ErrorType>::Error>;
+}
+
+#[cfg(feature = "async")]
+impl<D, CPE, IDE, ME, const BUFFER_SIZE: usize> AsyncRead
+    for AsyncFileWriteGuard<'_, D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: AsyncFlushableDevice,
+    D::Stream: AsyncRead + AsyncWrite + AsyncSeek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.file.read(buf).await
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, CPE, IDE, ME, const BUFFER_SIZE: usize> AsyncSeek
+    for AsyncFileWriteGuard<'_, D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: AsyncFlushableDevice,
+    D::Stream: AsyncRead + AsyncWrite + AsyncSeek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    async fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        self.file.seek(pos).await
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D, CPE, IDE, ME, const BUFFER_SIZE: usize> AsyncWrite
+    for AsyncFileWriteGuard<'_, D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: AsyncFlushableDevice,
+    D::Stream: AsyncRead + AsyncWrite + AsyncSeek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        ensure!(
+            is_write_within_quota(
+                self.quota_usage,
+                self.measured_file_size,
+                &self.file,
+                buf.len()
+            ),
+            FileError::QuotaExceeded
+        );
+
+        // A failed write may still have allocated clusters or zero-filled part of a gap
+        if !buf.is_empty() {
+            self.is_dirty = true;
+        }
+
+        self.file.write(buf).await
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        if !self.is_dirty {
+            return self.file.flush().await;
+        }
+
+        self.write_entry().await?;
+        self.file.flush().await?;
+        self.is_dirty = false;
+
+        self.file_system
+            .notify_mutation(MutationEvent::WriteCompleted {
+                directory_path: self.directory_path,
+                name: self.name,
+                first_cluster_number: self.file.first_cluster_number(),
+                file_size: self.file.size(),
+            });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,12 @@
+mod crc32;
+
+pub use crc32::*;
+
+/// Digest fed the contents of a file as they are streamed from the device, see
+/// [`FileSystem::hash`](crate::FileSystem::hash).
+///
+/// Implement this for any digest algorithm, such as SHA-256 from an external crate, by forwarding
+/// to its update method.
+pub trait Hasher {
+    fn update(&mut self, bytes: &[u8]);
+}
@@ -0,0 +1,72 @@
+use crate::hash::Hasher;
+
+/// Reversed form of the CRC-32 (IEEE 802.3) polynomial.
+const POLYNOMIAL: u32 = 0xEDB8_8320;
+
+/// CRC-32 as used by zip, gzip and Ethernet, computed bitwise to keep its footprint small.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Crc32 {
+    state: u32,
+}
+
+impl Crc32 {
+    pub fn new() -> Self {
+        Self { state: u32::MAX }
+    }
+
+    /// Returns the checksum of the bytes added so far.
+    pub fn finalize(&self) -> u32 {
+        !self.state
+    }
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for Crc32 {
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.state ^= byte as u32;
+
+            for _ in 0..8 {
+                let mask = (self.state & 1).wrapping_neg();
+                self.state = (self.state >> 1) ^ (POLYNOMIAL & mask);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod finalize {
+        use super::*;
+
+        #[test]
+        fn empty_returns_zero() {
+            assert_eq!(Crc32::new().finalize(), 0);
+        }
+
+        #[test]
+        fn check_value_matches() {
+            let mut crc32 = Crc32::new();
+            crc32.update(b"123456789");
+
+            assert_eq!(crc32.finalize(), 0xCBF4_3926);
+        }
+
+        #[test]
+        fn split_updates_match_single_update() {
+            let mut crc32 = Crc32::new();
+            crc32.update(b"1234");
+            crc32.update(b"");
+            crc32.update(b"56789");
+
+            assert_eq!(crc32.finalize(), 0xCBF4_3926);
+        }
+    }
+}
@@ -0,0 +1,37 @@
+mod item;
+
+pub use item::*;
+
+use alloc::vec::Vec;
+
+/// Every item of a volume with its path, size and last write timestamp, and optionally the CRC-32
+/// of each file's contents, see [`FileSystem::inventory`](crate::FileSystem::inventory).
+///
+/// Host tools can compare an inventory against a manifest to find the files which differ on a
+/// device.  With the `serde` feature enabled inventories can be serialized, such as to JSON.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Inventory {
+    items: Vec<InventoryItem>,
+}
+
+impl Inventory {
+    pub(crate) fn new() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    /// The items of the volume in the order they were walked, each directory followed by its
+    /// contents.
+    pub fn items(&self) -> &[InventoryItem] {
+        &self.items
+    }
+
+    /// The item at the given path, which is matched exactly.
+    pub fn find(&self, path: &str) -> Option<&InventoryItem> {
+        self.items.iter().find(|item| item.path() == path)
+    }
+
+    pub(crate) fn push(&mut self, item: InventoryItem) {
+        self.items.push(item);
+    }
+}
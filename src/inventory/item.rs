@@ -0,0 +1,57 @@
+use crate::DirectoryItem;
+use alloc::string::String;
+
+/// A single item recorded by an `Inventory`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct InventoryItem {
+    path: String,
+    is_directory: bool,
+    file_size: u64,
+    last_write_date: u16,
+    last_write_time: u16,
+    crc32: Option<u32>,
+}
+
+impl InventoryItem {
+    pub(crate) fn new(path: String, item: &DirectoryItem, crc32: Option<u32>) -> Self {
+        Self {
+            path,
+            is_directory: item.is_directory(),
+            file_size: item.extended_file_size(),
+            last_write_date: item.last_write_date(),
+            last_write_time: item.last_write_time(),
+            crc32,
+        }
+    }
+
+    /// The path from the root directory built from long names where present, separated by `/`, so
+    /// it can be passed back to `open`.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn is_directory(&self) -> bool {
+        self.is_directory
+    }
+
+    pub fn file_size(&self) -> u64 {
+        self.file_size
+    }
+
+    /// Packed FAT date the item was last written.
+    pub fn last_write_date(&self) -> u16 {
+        self.last_write_date
+    }
+
+    /// Packed FAT time the item was last written.
+    pub fn last_write_time(&self) -> u16 {
+        self.last_write_time
+    }
+
+    /// The CRC-32 of the file's contents, `None` for directories and when the inventory was taken
+    /// without hashes.
+    pub fn crc32(&self) -> Option<u32> {
+        self.crc32
+    }
+}
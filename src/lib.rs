@@ -1,39 +1,138 @@
 #![cfg_attr(not(test), no_std)]
 #![allow(dead_code, unused)]
+#![cfg_attr(
+    test,
+    allow(
+        clippy::bool_assert_comparison,
+        clippy::iter_skip_next,
+        clippy::needless_borrow,
+        clippy::redundant_pattern_matching,
+        clippy::unit_arg,
+        clippy::unnecessary_mut_passed
+    )
+)]
 
-#[cfg(test)]
+#[cfg(any(test, feature = "alloc"))]
 extern crate alloc;
 
+#[cfg(not(any(feature = "fat12", feature = "fat16", feature = "fat32")))]
+compile_error!("At least one of the `fat12`, `fat16`, or `fat32` features must be enabled");
+
 #[macro_use]
 mod utils;
 
 mod allocation_table;
 mod boot_sector;
+#[cfg(feature = "config")]
+mod config;
 mod device;
+mod dir;
+mod dir_snapshot;
 mod directory;
 mod directory_entry;
 mod directory_item;
 mod encoding;
+#[cfg(feature = "sync")]
+mod erased_stream;
 mod file;
 mod file_name;
 mod file_system;
+#[cfg(any(feature = "sync", feature = "async"))]
+mod file_write_guard;
+mod hash;
+#[cfg(feature = "alloc")]
+mod inventory;
+mod lines;
+mod path_cache;
+mod pinned_file;
+#[cfg(feature = "sd-card")]
+mod sd_card;
+mod stream;
+#[cfg(feature = "sync")]
+mod temp_file;
+mod timestamp;
+mod units;
+mod volume_label;
+mod yield_budget;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod raw;
 
 #[cfg(test)]
 mod mock;
 
-pub use allocation_table::AllocationTableKind;
-pub use boot_sector::BiosParameterBlockError;
+pub use allocation_table::{AllocationTableKind, AllocationTableStatistics};
+pub use boot_sector::{BiosParameterBlockError, BootSector, BootSectorError, FsInfo, FsInfoError};
 pub use device::{Device, SingleAccessDevice, SingleAccessDeviceError};
+pub use dir::{Dir, DirCursor, FilteredDir};
+pub use dir_snapshot::{
+    DeviceDirSnapshotError, DirChange, DirSnapshot, DirSnapshotEntry, DirSnapshotError,
+};
+pub use directory::LocatedDirectoryEntryIterator;
 pub use directory_entry::{
-    DirectoryEntryError, LongNameDirectoryEntryError, ShortNameDirectoryEntryError,
+    DIRECTORY_ENTRY_SIZE, DirectoryEntry, DirectoryEntryAttributes, DirectoryEntryError,
+    DirectoryEntryIterationError, DirectoryEntryPosition, FreeDirectoryEntry,
+    LongNameDirectoryEntry, LongNameDirectoryEntryError, ShortNameCase, ShortNameDirectoryEntry,
+    ShortNameDirectoryEntryError,
+};
+pub use directory_item::{
+    DeviceDirectoryItemIterationError, DirectoryItem, DirectoryItemEntries, DirectoryItemError,
+    DirectoryItemFilter, DirectoryItemIterationError, DirectoryItemKind, EntryLocation,
+    LongNamePolicy,
 };
-pub use directory_item::{DirectoryItemError, DirectoryItemIterationError};
 pub use encoding::{AsciiOnlyEncoder, CodePageEncoder};
-pub use file::{File, FileError};
-pub use file_system::{FileSystem, FileSystemBuilder, FileSystemError};
+pub use file::{ConcatenatedFile, File, FileError, FileSlice, MultiFileReader};
+pub use file_name::{LongFileName, LongFileNameError, ShortFileName, ShortFileNameParseError};
+pub use file_system::{
+    CanonicalizeError, ConversionError, CopyError, DeviceConversionError, DeviceCopyError,
+    DeviceHashError, DeviceManifestError, DeviceMetadataError, DeviceOperationError,
+    DirectoryQuota, FileSystem, FileSystemBuilder, FileSystemError, Find, FixedFilePolicy,
+    HashError, HealthSummary, IgnoreInvalidEntries, InvalidDirectoryEntryHandler, ManifestError,
+    ManifestMismatch, MetadataError, MutationEvent, NameConflict, OperationError, QuotaPolicy,
+    SearchError, SuspendedFileSystem,
+};
+pub use hash::{Crc32, Hasher};
+pub use lines::{Lines, LinesError};
+pub use path_cache::{PathCache, PathCacheEntry};
+pub use pinned_file::PinnedFile;
+pub use stream::{
+    Backoff, BounceBufferedStream, BufferRequirements, NoBackoff, PrefetchingStream, RetryMetrics,
+    RetryingStream, SectorSplittingStream, SliceStream, SliceStreamError, SparseStream,
+};
+pub use timestamp::{FatDate, FatTime, InvalidTimestampPolicy, TimestampError};
+pub use volume_label::VolumeLabel;
+
+#[cfg(target_has_atomic = "8")]
+pub use device::SharedDevice;
+
+#[cfg(feature = "code-page-437")]
+pub use encoding::Cp437Encoder;
+#[cfg(feature = "code-page-850")]
+pub use encoding::Cp850Encoder;
+
+#[cfg(feature = "alloc")]
+pub use inventory::{Inventory, InventoryItem};
+
+#[cfg(feature = "config")]
+pub use config::{ConfigError, ConfigItem, ConfigReader};
+
+#[cfg(feature = "dyn-device")]
+pub use stream::{DynDevice, DynStream};
+
+#[cfg(feature = "sd-card")]
+pub use sd_card::{SD_CARD_BLOCK_SIZE, SdCard, SdCardDevice, SdCardError, SdCardStream};
 
 #[cfg(feature = "sync")]
 pub use device::{SyncDevice, SyncFlushableDevice};
+#[cfg(feature = "sync")]
+pub use file_write_guard::FileWriteGuard;
+#[cfg(feature = "sync")]
+pub use temp_file::TempFile;
 
 #[cfg(feature = "async")]
-pub use device::{AsyncDevice, AsyncFlushableDevice};
+pub use device::{AsyncDevice, AsyncFlushableDevice, TimeoutDevice, TimeoutDeviceError};
+#[cfg(feature = "async")]
+pub use file_write_guard::AsyncFileWriteGuard;
+#[cfg(feature = "async")]
+pub use stream::AsyncBackoff;
@@ -0,0 +1,293 @@
+mod error;
+
+pub use error::*;
+
+use core::error::Error;
+use core::ops::Range;
+
+#[cfg(feature = "sync")]
+use embedded_io::Read;
+
+#[cfg(feature = "async")]
+use embedded_io_async::Read as AsyncRead;
+
+/// Splits the text read from a reader, typically a `File`, into lines of up to `N` bytes without
+/// allocating.
+///
+/// Lines end at a line feed, a carriage return or both of them in that order, the line ending
+/// isn't part of the returned line.  The last line doesn't need to be ended.  Lines which are too
+/// long or not valid UTF-8 are returned as errors, subsequent calls continue with the following
+/// line.
+#[derive(Clone, Debug)]
+pub struct Lines<R, const N: usize> {
+    reader: R,
+
+    buffer: [u8; N],
+    buffer_start: usize,
+    buffer_end: usize,
+    last_line: Range<usize>,
+
+    is_after_carriage_return: bool,
+    is_skipping_line: bool,
+    is_end_reached: bool,
+}
+
+/// The outcome of looking for a line within the buffered bytes.
+enum BufferedLine {
+    Line(Range<usize>),
+    TooLong,
+    Incomplete,
+}
+
+impl<R, const N: usize> Lines<R, N> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+
+            buffer: [0; N],
+            buffer_start: 0,
+            buffer_end: 0,
+            last_line: 0..0,
+
+            is_after_carriage_return: false,
+            is_skipping_line: false,
+            is_end_reached: false,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Takes the next complete line from the buffer, moving the remaining bytes to its start once
+    /// no complete line is left.
+    fn take_buffered_line(&mut self) -> BufferedLine {
+        loop {
+            // The line feed of a line ended by a carriage return may only arrive with the next read
+            if self.is_after_carriage_return && self.buffer_start < self.buffer_end {
+                if self.buffer[self.buffer_start] == b'\n' {
+                    self.buffer_start += 1;
+                }
+
+                self.is_after_carriage_return = false;
+            }
+
+            let buffered_bytes = &self.buffer[self.buffer_start..self.buffer_end];
+
+            let Some(line_length) = buffered_bytes
+                .iter()
+                .position(|byte| *byte == b'\n' || *byte == b'\r')
+            else {
+                if self.is_skipping_line {
+                    self.buffer_start = self.buffer_end;
+                }
+
+                self.buffer
+                    .copy_within(self.buffer_start..self.buffer_end, 0);
+                self.buffer_end -= self.buffer_start;
+                self.buffer_start = 0;
+
+                if self.buffer_end == N {
+                    self.buffer_end = 0;
+                    self.is_skipping_line = true;
+
+                    return BufferedLine::TooLong;
+                }
+
+                return BufferedLine::Incomplete;
+            };
+
+            let line = self.buffer_start..self.buffer_start + line_length;
+            self.is_after_carriage_return = buffered_bytes[line_length] == b'\r';
+            self.buffer_start = line.end + 1;
+
+            if self.is_skipping_line {
+                self.is_skipping_line = false;
+
+                continue;
+            }
+
+            return BufferedLine::Line(line);
+        }
+    }
+
+    /// Takes the unended last line once the reader reached its end.
+    fn take_last_line(&mut self) -> Option<Range<usize>> {
+        let line = self.buffer_start..self.buffer_end;
+        self.buffer_start = self.buffer_end;
+
+        (!line.is_empty() && !self.is_skipping_line).then_some(line)
+    }
+
+    /// The last line returned, valid until the next line is read.
+    pub(crate) fn last_line(&self) -> &str {
+        core::str::from_utf8(&self.buffer[self.last_line.clone()]).unwrap_or_default()
+    }
+
+    fn decode<RE>(&mut self, line: Range<usize>) -> Result<&str, LinesError<RE>>
+    where
+        RE: Error,
+    {
+        let line = core::str::from_utf8(&self.buffer[line.clone()])
+            .map(|_| line)
+            .map_err(LinesError::InvalidUtf8)?;
+        self.last_line = line;
+
+        Ok(self.last_line())
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<R, const N: usize> Lines<R, N>
+where
+    R: Read,
+{
+    /// Reads the next line, `None` once the reader's end is reached.
+    pub fn next_line(&mut self) -> Option<Result<&str, LinesError<R::Error>>> {
+        loop {
+            match self.take_buffered_line() {
+                BufferedLine::Line(line) => return Some(self.decode(line)),
+                BufferedLine::TooLong => return Some(Err(LinesError::LineTooLong)),
+                BufferedLine::Incomplete => {}
+            }
+
+            if self.is_end_reached {
+                return None;
+            }
+
+            match self.reader.read(&mut self.buffer[self.buffer_end..]) {
+                Ok(0) => {
+                    self.is_end_reached = true;
+
+                    let line = self.take_last_line()?;
+
+                    return Some(self.decode(line));
+                }
+                Ok(read) => self.buffer_end += read,
+                Err(e) => return Some(Err(LinesError::ReadError(e))),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R, const N: usize> Lines<R, N>
+where
+    R: AsyncRead,
+{
+    /// Reads the next line, `None` once the reader's end is reached.
+    pub async fn next_line_async(&mut self) -> Option<Result<&str, LinesError<R::Error>>> {
+        loop {
+            match self.take_buffered_line() {
+                BufferedLine::Line(line) => return Some(self.decode(line)),
+                BufferedLine::TooLong => return Some(Err(LinesError::LineTooLong)),
+                BufferedLine::Incomplete => {}
+            }
+
+            if self.is_end_reached {
+                return None;
+            }
+
+            match self.reader.read(&mut self.buffer[self.buffer_end..]).await {
+                Ok(0) => {
+                    self.is_end_reached = true;
+
+                    let line = self.take_last_line()?;
+
+                    return Some(self.decode(line));
+                }
+                Ok(read) => self.buffer_end += read,
+                Err(e) => return Some(Err(LinesError::ReadError(e))),
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "sync"))]
+mod tests {
+    use super::*;
+    use crate::mock::{DataStream, ErroringStream, ErroringStreamScenarios, IoError};
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+    use embedded_io::ErrorKind;
+
+    fn lines<const N: usize>(text: &[u8]) -> Vec<Result<String, ()>> {
+        let mut lines = Lines::<_, N>::new(DataStream::from_bytes(text));
+        let mut results = Vec::new();
+
+        while let Some(line) = lines.next_line() {
+            results.push(line.map(ToString::to_string).map_err(|_| ()));
+        }
+
+        results
+    }
+
+    mod next_line {
+        use super::*;
+
+        #[test]
+        fn line_endings_split_lines() {
+            assert_eq!(
+                lines::<16>(b"one\ntwo\r\nthree\rfour"),
+                [Ok("one"), Ok("two"), Ok("three"), Ok("four")].map(|line| line.map(String::from))
+            );
+        }
+
+        #[test]
+        fn empty_lines_returned() {
+            assert_eq!(
+                lines::<16>(b"\n\r\n\r\rend\n"),
+                [Ok(""), Ok(""), Ok(""), Ok(""), Ok("end")].map(|line| line.map(String::from))
+            );
+        }
+
+        #[test]
+        fn line_endings_split_across_reads_ended_once() {
+            // The buffer fills right after the carriage return, so the line feed arrives later
+            assert_eq!(
+                lines::<4>(b"abc\r\ndef"),
+                [Ok("abc"), Ok("def")].map(|line| line.map(String::from))
+            );
+        }
+
+        #[test]
+        fn over_long_lines_skipped() {
+            assert_eq!(
+                lines::<4>(b"a\nlong line\nb\nlonger line"),
+                [Ok("a"), Err(()), Ok("b"), Err(())].map(|line| line.map(String::from))
+            );
+        }
+
+        #[test]
+        fn invalid_utf8_lines_skipped() {
+            let mut lines = Lines::<_, 16>::new(DataStream::from_bytes(&b"\xFF\xFE\nok"[..]));
+
+            assert!(matches!(
+                lines.next_line(),
+                Some(Err(LinesError::InvalidUtf8(_)))
+            ));
+            assert_eq!(
+                lines
+                    .next_line()
+                    .expect("Some should be returned")
+                    .expect("Ok should be returned"),
+                "ok"
+            );
+            assert!(lines.next_line().is_none());
+        }
+
+        #[test]
+        fn read_errors_returned() {
+            let mut lines = Lines::<_, 16>::new(ErroringStream::new(
+                DataStream::from_bytes(&b"text"[..]),
+                IoError(ErrorKind::TimedOut),
+                ErroringStreamScenarios::READ,
+            ));
+
+            assert!(matches!(
+                lines.next_line(),
+                Some(Err(LinesError::ReadError(IoError(ErrorKind::TimedOut))))
+            ));
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+use core::str::Utf8Error;
+
+#[derive(Clone, Debug)]
+pub enum LinesError<RE>
+where
+    RE: Error,
+{
+    /// The line isn't valid UTF-8, reading continues with the following line.
+    InvalidUtf8(Utf8Error),
+    /// The line doesn't fit the line buffer, reading continues with the following line.
+    LineTooLong,
+    ReadError(RE),
+}
+
+impl<RE> Error for LinesError<RE> where RE: Error {}
+
+impl<RE> Display for LinesError<RE>
+where
+    RE: Error,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LinesError::InvalidUtf8(e) => write!(f, "the line is not valid UTF-8: {}", e),
+            LinesError::LineTooLong => write!(f, "the line is longer than the line buffer"),
+            LinesError::ReadError(e) => write!(f, "reading the line failed: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::CoreError;
+    use alloc::string::ToString;
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn produces_non_empty_value() {
+            let values = [
+                LinesError::<CoreError>::InvalidUtf8(
+                    core::str::from_utf8(&"é".as_bytes()[..1]).unwrap_err(),
+                ),
+                LinesError::LineTooLong,
+                LinesError::ReadError(CoreError),
+            ];
+
+            for value in values {
+                assert!(
+                    !value.to_string().is_empty(),
+                    "Display implementation should be non-empty"
+                );
+            }
+        }
+    }
+}
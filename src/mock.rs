@@ -2,6 +2,8 @@ mod core_error;
 mod data_stream;
 mod erroring_device;
 mod erroring_stream;
+#[cfg(feature = "sd-card")]
+mod fake_sd_card;
 mod io_error;
 mod scripted_code_page_encoder;
 mod scripted_directory_entry_iterator;
@@ -11,6 +13,8 @@ pub use core_error::*;
 pub use data_stream::*;
 pub use erroring_device::*;
 pub use erroring_stream::*;
+#[cfg(feature = "sd-card")]
+pub use fake_sd_card::*;
 pub use io_error::*;
 pub use scripted_code_page_encoder::*;
 pub use scripted_directory_entry_iterator::*;
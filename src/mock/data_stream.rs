@@ -1,14 +1,14 @@
 use crate::Device;
 use crate::mock::IoError;
-use core::borrow::Borrow;
+use core::borrow::{Borrow, BorrowMut};
 use core::cmp::min;
 use embedded_io::{ErrorType, SeekFrom};
 
 #[cfg(feature = "sync")]
-use embedded_io::{Read, Seek};
+use embedded_io::{Read, Seek, Write};
 
 #[cfg(feature = "async")]
-use embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek};
+use embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek, Write as AsyncWrite};
 
 #[derive(Clone, Debug)]
 pub struct DataStream<B>
@@ -47,6 +47,25 @@ where
         Ok(bytes_read)
     }
 
+    fn write_internal(&mut self, buf: &[u8]) -> Result<usize, IoError>
+    where
+        B: BorrowMut<[u8]>,
+    {
+        let bytes = self.bytes.borrow_mut();
+
+        let start = min(self.position, bytes.len());
+        let end = min(start + buf.len(), bytes.len());
+
+        let bytes_written = end - start;
+
+        if bytes_written > 0 {
+            bytes[start..end].copy_from_slice(&buf[0..bytes_written]);
+            self.position += bytes_written;
+        }
+
+        Ok(bytes_written)
+    }
+
     fn seek_internal(&mut self, pos: SeekFrom) -> Result<u64, IoError> {
         self.position = match pos {
             SeekFrom::Start(value) => value as usize,
@@ -65,6 +84,7 @@ where
     type Error = IoError;
 }
 
+#[cfg(feature = "sync")]
 impl<D> Read for DataStream<D>
 where
     D: Borrow<[u8]>,
@@ -74,6 +94,7 @@ where
     }
 }
 
+#[cfg(feature = "async")]
 impl<D> AsyncRead for DataStream<D>
 where
     D: Borrow<[u8]>,
@@ -83,6 +104,35 @@ where
     }
 }
 
+#[cfg(feature = "sync")]
+impl<D> Write for DataStream<D>
+where
+    D: Borrow<[u8]> + BorrowMut<[u8]>,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.write_internal(buf)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<D> AsyncWrite for DataStream<D>
+where
+    D: Borrow<[u8]> + BorrowMut<[u8]>,
+{
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.write_internal(buf)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sync")]
 impl<D> Seek for DataStream<D>
 where
     D: Borrow<[u8]>,
@@ -92,6 +142,7 @@ where
     }
 }
 
+#[cfg(feature = "async")]
 impl<D> AsyncSeek for DataStream<D>
 where
     D: Borrow<[u8]>,
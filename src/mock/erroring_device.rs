@@ -1,6 +1,11 @@
-use crate::device::SyncDevice;
+use crate::Device;
 use crate::mock::{IoError, VoidStream};
-use crate::{AsyncDevice, AsyncFlushableDevice, Device, SyncFlushableDevice};
+
+#[cfg(feature = "sync")]
+use crate::{SyncDevice, SyncFlushableDevice};
+
+#[cfg(feature = "async")]
+use crate::{AsyncDevice, AsyncFlushableDevice};
 
 #[derive(Clone, Copy, Debug)]
 pub struct ErroringDevice;
@@ -20,6 +25,7 @@ impl SyncDevice for ErroringDevice {
     }
 }
 
+#[cfg(feature = "sync")]
 impl SyncFlushableDevice for ErroringDevice {
     fn flush(&self) -> Result<(), Self::Error> {
         Err(IoError::default())
@@ -36,6 +42,7 @@ impl AsyncDevice for ErroringDevice {
     }
 }
 
+#[cfg(feature = "async")]
 impl AsyncFlushableDevice for ErroringDevice {
     async fn flush(&self) -> Result<(), Self::Error> {
         Err(IoError::default())
@@ -58,6 +58,7 @@ where
     type Error = E;
 }
 
+#[cfg(feature = "sync")]
 impl<S, E> Read for ErroringStream<S, E>
 where
     S: ErrorType<Error = E> + Read,
@@ -72,6 +73,7 @@ where
     }
 }
 
+#[cfg(feature = "async")]
 impl<S, E> AsyncRead for ErroringStream<S, E>
 where
     S: ErrorType<Error = E> + AsyncRead,
@@ -86,6 +88,7 @@ where
     }
 }
 
+#[cfg(feature = "sync")]
 impl<S, E> Seek for ErroringStream<S, E>
 where
     S: ErrorType<Error = E> + Seek,
@@ -100,6 +103,7 @@ where
     }
 }
 
+#[cfg(feature = "async")]
 impl<S, E> AsyncSeek for ErroringStream<S, E>
 where
     S: ErrorType<Error = E> + AsyncSeek,
@@ -114,6 +118,7 @@ where
     }
 }
 
+#[cfg(feature = "sync")]
 impl<S, E> Write for ErroringStream<S, E>
 where
     S: ErrorType<Error = E> + Write,
@@ -142,6 +147,7 @@ where
     }
 }
 
+#[cfg(feature = "async")]
 impl<S, E> AsyncWrite for ErroringStream<S, E>
 where
     S: ErrorType<Error = E> + AsyncWrite,
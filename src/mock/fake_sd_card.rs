@@ -0,0 +1,301 @@
+use crate::sd_card::{SD_CARD_BLOCK_SIZE, crc7};
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+use embedded_hal::spi::SpiBus;
+
+const COMMAND_LENGTH: usize = 6;
+const WRITE_PAYLOAD_LENGTH: usize = SD_CARD_BLOCK_SIZE + 2;
+
+/// An in-memory emulation of an SD card's SPI-mode protocol.
+#[derive(Debug)]
+pub struct FakeSdCard {
+    storage: Vec<u8>,
+    is_present: bool,
+    is_high_capacity: bool,
+    is_idle: bool,
+    is_app_command: bool,
+    op_cond_attempts: usize,
+    command: Vec<u8>,
+    responses: VecDeque<u8>,
+    write_state: WriteState,
+}
+
+#[derive(Debug)]
+enum WriteState {
+    None,
+    AwaitingToken { block: usize },
+    ReceivingData { block: usize, data: Vec<u8> },
+}
+
+impl FakeSdCard {
+    /// Creates a card with `block_count` zeroed blocks, which must be a multiple of 1024.
+    pub fn new(block_count: usize, is_high_capacity: bool) -> Self {
+        assert_eq!(block_count % 1024, 0);
+
+        Self {
+            storage: vec![0; block_count * SD_CARD_BLOCK_SIZE],
+            is_present: true,
+            is_high_capacity,
+            is_idle: false,
+            is_app_command: false,
+            op_cond_attempts: 0,
+            command: Vec::new(),
+            responses: VecDeque::new(),
+            write_state: WriteState::None,
+        }
+    }
+
+    /// Creates a bus with no card attached, which never drives the data line.
+    pub fn absent() -> Self {
+        Self {
+            is_present: false,
+            ..Self::new(0, false)
+        }
+    }
+
+    pub fn storage(&self) -> &[u8] {
+        &self.storage
+    }
+
+    pub fn storage_mut(&mut self) -> &mut [u8] {
+        &mut self.storage
+    }
+
+    fn exchange(&mut self, byte: u8) -> u8 {
+        if !self.is_present {
+            return 0xFF;
+        }
+
+        match &mut self.write_state {
+            WriteState::None => {}
+            WriteState::AwaitingToken { block } => {
+                if byte == 0xFE {
+                    self.write_state = WriteState::ReceivingData {
+                        block: *block,
+                        data: Vec::new(),
+                    };
+
+                    return 0xFF;
+                }
+
+                return self.responses.pop_front().unwrap_or(0xFF);
+            }
+            WriteState::ReceivingData { block, data } => {
+                data.push(byte);
+
+                if data.len() == WRITE_PAYLOAD_LENGTH {
+                    let start = *block * SD_CARD_BLOCK_SIZE;
+
+                    self.storage[start..start + SD_CARD_BLOCK_SIZE]
+                        .copy_from_slice(&data[..SD_CARD_BLOCK_SIZE]);
+                    self.responses.extend([0x05, 0x00, 0x00, 0xFF]);
+                    self.write_state = WriteState::None;
+                }
+
+                return 0xFF;
+            }
+        }
+
+        if !self.command.is_empty() || byte & 0xC0 == 0x40 {
+            self.command.push(byte);
+
+            if self.command.len() == COMMAND_LENGTH {
+                self.process_command();
+                self.command.clear();
+            }
+
+            return 0xFF;
+        }
+
+        self.responses.pop_front().unwrap_or(0xFF)
+    }
+
+    fn process_command(&mut self) {
+        let index = self.command[0] & 0x3F;
+        let argument = u32::from_be_bytes(self.command[1..5].try_into().unwrap());
+
+        assert_eq!(
+            crc7(&self.command[..5]),
+            self.command[5],
+            "Command CRC should be valid"
+        );
+
+        let is_app_command = core::mem::take(&mut self.is_app_command);
+
+        self.responses.clear();
+
+        // Cards may take a byte before responding
+        self.responses.push_back(0xFF);
+
+        match (is_app_command, index) {
+            (_, 0) => {
+                self.is_idle = true;
+                self.responses.push_back(0x01);
+            }
+            (_, 8) if self.is_high_capacity => {
+                let [.., voltage, pattern] = argument.to_be_bytes();
+
+                self.responses
+                    .extend([self.r1(), 0x00, 0x00, voltage, pattern]);
+            }
+            (_, 55) => {
+                self.is_app_command = true;
+                self.responses.push_back(self.r1());
+            }
+            (true, 41) => {
+                self.op_cond_attempts += 1;
+
+                if self.op_cond_attempts >= 2 {
+                    self.is_idle = false;
+                }
+
+                self.responses.push_back(self.r1());
+            }
+            (_, 58) => {
+                let capacity_status = if self.is_high_capacity { 0xC0 } else { 0x80 };
+
+                self.responses
+                    .extend([self.r1(), capacity_status, 0xFF, 0x80, 0x00]);
+            }
+            (_, 16) => self.responses.push_back(self.r1()),
+            (_, 9) => {
+                let csd = self.csd();
+
+                self.push_data(&csd);
+            }
+            (_, 17) => {
+                let start = self.block_index(argument) * SD_CARD_BLOCK_SIZE;
+                let block = self.storage[start..start + SD_CARD_BLOCK_SIZE].to_vec();
+
+                self.push_data(&block);
+            }
+            (_, 24) => {
+                self.responses.push_back(self.r1());
+                self.write_state = WriteState::AwaitingToken {
+                    block: self.block_index(argument),
+                };
+            }
+            _ => self.responses.push_back(self.r1() | 0x04),
+        }
+    }
+
+    fn r1(&self) -> u8 {
+        self.is_idle as u8
+    }
+
+    fn block_index(&self, argument: u32) -> usize {
+        if self.is_high_capacity {
+            argument as usize
+        } else {
+            argument as usize / SD_CARD_BLOCK_SIZE
+        }
+    }
+
+    fn push_data(&mut self, data: &[u8]) {
+        self.responses.extend([self.r1(), 0xFF, 0xFE]);
+        self.responses.extend(data);
+        self.responses.extend([0x00, 0x00]);
+    }
+
+    fn csd(&self) -> [u8; 16] {
+        let block_count = self.storage.len() / SD_CARD_BLOCK_SIZE;
+        let mut csd = [0; 16];
+
+        if self.is_high_capacity {
+            let device_size = (block_count / 1024 - 1) as u32;
+
+            csd[0] = 0x40;
+            csd[7] = (device_size >> 16) as u8 & 0x3F;
+            csd[8] = (device_size >> 8) as u8;
+            csd[9] = device_size as u8;
+        } else {
+            // 512 byte blocks with a multiplier of 512
+            let device_size = (block_count / 512 - 1) as u32;
+
+            csd[5] = 0x09;
+            csd[6] = (device_size >> 10) as u8 & 0x03;
+            csd[7] = (device_size >> 2) as u8;
+            csd[8] = (device_size as u8 & 0x03) << 6;
+            csd[9] = 0x03;
+            csd[10] = 0x80;
+        }
+
+        csd
+    }
+}
+
+impl embedded_hal::spi::ErrorType for FakeSdCard {
+    type Error = Infallible;
+}
+
+impl SpiBus for FakeSdCard {
+    fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        for word in words {
+            *word = self.exchange(0xFF);
+        }
+
+        Ok(())
+    }
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        for word in words {
+            self.exchange(*word);
+        }
+
+        Ok(())
+    }
+
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        for index in 0..read.len().max(write.len()) {
+            let byte = self.exchange(write.get(index).copied().unwrap_or(0xFF));
+
+            if let Some(word) = read.get_mut(index) {
+                *word = byte;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        for word in words {
+            *word = self.exchange(*word);
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// A chip select pin which is not connected to anything.
+#[derive(Clone, Copy, Debug)]
+pub struct NoOpPin;
+
+impl embedded_hal::digital::ErrorType for NoOpPin {
+    type Error = Infallible;
+}
+
+impl OutputPin for NoOpPin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// A delay provider which returns immediately.
+#[derive(Clone, Copy, Debug)]
+pub struct NoDelay;
+
+impl DelayNs for NoDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
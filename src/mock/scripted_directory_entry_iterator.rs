@@ -1,7 +1,7 @@
 use crate::Device;
 use crate::directory_entry::{
     DirectoryEntry, DirectoryEntryIterationError, DirectoryEntryIterator,
-    DirectoryEntryIteratorResult,
+    DirectoryEntryIteratorResult, DirectoryEntryPosition,
 };
 use alloc::boxed::Box;
 use alloc::rc::Rc;
@@ -28,6 +28,7 @@ where
     D: Device,
 {
     call_index: usize,
+    is_finished: bool,
 
     peek: Rc<dyn Fn(usize) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> + 'a>,
     advance: Rc<dyn Fn(usize) -> DirectoryEntryIteratorResult<bool, D> + 'a>,
@@ -41,6 +42,7 @@ where
     pub fn new() -> Self {
         Self {
             call_index: 0,
+            is_finished: false,
 
             peek: Rc::new(|_| None),
             advance: Rc::new(|_| Ok(false)),
@@ -71,6 +73,15 @@ where
         self.next = Rc::new(next);
         self
     }
+
+    pub fn position(&self) -> Option<DirectoryEntryPosition> {
+        None
+    }
+
+    /// Ends the script, later calls behave as if the directory were exhausted.
+    pub fn finish(&mut self) {
+        self.is_finished = true;
+    }
 }
 
 impl<D> Debug for ScriptedDirectoryEntryIterator<'_, D>
@@ -90,10 +101,18 @@ where
     D: SyncDevice,
 {
     pub fn peek(&self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
+        if self.is_finished {
+            return None;
+        }
+
         (self.peek)(self.call_index)
     }
 
     pub fn advance(&mut self) -> DirectoryEntryIteratorResult<bool, D> {
+        if self.is_finished {
+            return Ok(false);
+        }
+
         let current_call_index = self.call_index;
         self.call_index += 1;
 
@@ -101,11 +120,19 @@ where
     }
 
     pub fn next(&mut self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
+        if self.is_finished {
+            return None;
+        }
+
         let current_call_index = self.call_index;
         self.call_index += 1;
 
         (self.next)(current_call_index)
     }
+
+    pub fn write(&self, _bytes: &[u8]) -> DirectoryEntryIteratorResult<(), D> {
+        Ok(())
+    }
 }
 
 #[cfg(feature = "async")]
@@ -114,10 +141,18 @@ where
     D: AsyncDevice,
 {
     pub async fn peek_async(&self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
+        if self.is_finished {
+            return None;
+        }
+
         (self.peek)(self.call_index)
     }
 
     pub async fn advance_async(&mut self) -> DirectoryEntryIteratorResult<bool, D> {
+        if self.is_finished {
+            return Ok(false);
+        }
+
         let current_call_index = self.call_index;
         self.call_index += 1;
 
@@ -125,9 +160,17 @@ where
     }
 
     pub async fn next_async(&mut self) -> Option<DirectoryEntryIteratorResult<DirectoryEntry, D>> {
+        if self.is_finished {
+            return None;
+        }
+
         let current_call_index = self.call_index;
         self.call_index += 1;
 
         (self.next)(current_call_index)
     }
+
+    pub async fn write_async(&self, _bytes: &[u8]) -> DirectoryEntryIteratorResult<(), D> {
+        Ok(())
+    }
 }
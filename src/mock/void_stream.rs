@@ -42,30 +42,35 @@ impl ErrorType for VoidStream {
     type Error = IoError;
 }
 
+#[cfg(feature = "sync")]
 impl Read for VoidStream {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
         self.read_internal(buf)
     }
 }
 
+#[cfg(feature = "async")]
 impl AsyncRead for VoidStream {
     async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
         self.read_internal(buf)
     }
 }
 
+#[cfg(feature = "sync")]
 impl Seek for VoidStream {
     fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
         self.seek_internal(pos)
     }
 }
 
+#[cfg(feature = "async")]
 impl AsyncSeek for VoidStream {
     async fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
         self.seek_internal(pos)
     }
 }
 
+#[cfg(feature = "sync")]
 impl Write for VoidStream {
     fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
         Ok(buf.len())
@@ -76,6 +81,7 @@ impl Write for VoidStream {
     }
 }
 
+#[cfg(feature = "async")]
 impl AsyncWrite for VoidStream {
     async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
         Ok(buf.len())
@@ -0,0 +1,320 @@
+mod entry;
+
+pub use entry::*;
+
+use crate::{DirectoryItem, MutationEvent};
+
+/// The most recently opened items of a volume keyed by their canonical paths, so opening them
+/// again skips walking the directory tree.  Caches hold up to `N` items without allocating, the
+/// least recently used item is dropped to make room for another.
+///
+/// Paths are looked up by comparing their components to the cached canonical paths, ignoring
+/// ASCII case as well as empty and `.` components.  Paths which differ in other ways, such as
+/// stepping back with `..`, or which are longer than 64 bytes are walked each time.
+///
+/// Cached entries aren't updated as the volume changes.  Changes made through the file system can
+/// be applied by passing its mutation events to `invalidate`, the cache must be cleared if the
+/// volume is changed through other means.
+#[derive(Clone, Debug)]
+pub struct PathCache<const N: usize> {
+    entries: [Option<PathCacheEntry>; N],
+    entry_count: usize,
+}
+
+impl<const N: usize> PathCache<N> {
+    pub const fn new() -> Self {
+        Self {
+            entries: [const { None }; N],
+            entry_count: 0,
+        }
+    }
+
+    /// The cached items, most recently used first.
+    pub fn entries(&self) -> impl Iterator<Item = &PathCacheEntry> {
+        self.entries[..self.entry_count].iter().flatten()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entry_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entry_count == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.entries[..self.entry_count].fill(None);
+        self.entry_count = 0;
+    }
+
+    /// Drops the entries of the item the event refers to and of any items beneath it.
+    pub fn invalidate(&mut self, event: &MutationEvent<'_>) {
+        let (MutationEvent::Created {
+            directory_path,
+            name,
+            ..
+        }
+        | MutationEvent::Deleted {
+            directory_path,
+            name,
+            ..
+        }
+        | MutationEvent::Renamed {
+            directory_path,
+            name,
+            ..
+        }
+        | MutationEvent::WriteCompleted {
+            directory_path,
+            name,
+            ..
+        }) = *event;
+
+        // Paths which step back can't be compared without walking them
+        if has_parent_components(directory_path) || name == ".." {
+            self.clear();
+
+            return;
+        }
+
+        let mut retained_count = 0;
+
+        for entry_index in 0..self.entry_count {
+            let is_invalidated = self.entries[entry_index]
+                .as_ref()
+                .is_some_and(|entry| is_within(entry.path(), directory_path, name));
+
+            if !is_invalidated {
+                self.entries.swap(retained_count, entry_index);
+                retained_count += 1;
+            }
+        }
+
+        self.entries[retained_count..self.entry_count].fill(None);
+        self.entry_count = retained_count;
+    }
+
+    /// Finds the entry for the path, marking it as the most recently used.
+    pub(crate) fn get(&mut self, path: &str) -> Option<&PathCacheEntry> {
+        if has_parent_components(path) {
+            return None;
+        }
+
+        let entry_index = self
+            .entries()
+            .position(|entry| is_same(entry.path(), path))?;
+        self.entries[..=entry_index].rotate_right(1);
+
+        self.entries[0].as_ref()
+    }
+
+    /// Records the item under its canonical path as the most recently used entry, dropping the
+    /// least recently used entry if the cache is full.
+    pub(crate) fn insert(&mut self, canonical_path: &str, item: &DirectoryItem) {
+        let Some(entry) = PathCacheEntry::new(canonical_path, item) else {
+            return;
+        };
+
+        if N == 0 {
+            return;
+        }
+
+        self.entry_count = (self.entry_count + 1).min(N);
+        self.entries[..self.entry_count].rotate_right(1);
+        self.entries[0] = Some(entry);
+    }
+}
+
+impl<const N: usize> Default for PathCache<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn components(path: &str) -> impl Iterator<Item = &str> {
+    path.split("/")
+        .filter(|component| !component.is_empty() && *component != ".")
+}
+
+fn has_parent_components(path: &str) -> bool {
+    path.split("/").any(|component| component == "..")
+}
+
+fn is_same(cached_path: &str, path: &str) -> bool {
+    let mut cached_components = components(cached_path);
+
+    components(path).all(|component| {
+        cached_components
+            .next()
+            .is_some_and(|cached_component| cached_component.eq_ignore_ascii_case(component))
+    }) && cached_components.next().is_none()
+}
+
+/// Whether the cached path is the item named within the directory path, or lies beneath it.
+fn is_within(cached_path: &str, directory_path: &str, name: &str) -> bool {
+    let mut cached_components = components(cached_path);
+
+    components(directory_path)
+        .chain(components(name))
+        .all(|component| {
+            cached_components
+                .next()
+                .is_some_and(|cached_component| cached_component.eq_ignore_ascii_case(component))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AsciiOnlyEncoder;
+    use crate::directory_entry::{DirectoryEntryAttributes, ShortNameDirectoryEntry};
+    use crate::file_name::ShortFileName;
+    use alloc::vec::Vec;
+    use core::str::FromStr;
+
+    fn item(first_cluster_number: u32) -> DirectoryItem {
+        DirectoryItem::new(
+            ShortNameDirectoryEntry::builder()
+                .name(ShortFileName::from_str(&AsciiOnlyEncoder, "file.txt").unwrap())
+                .attributes(DirectoryEntryAttributes::Archive)
+                .first_cluster_number(first_cluster_number)
+                .file_size(1)
+                .build(),
+            None,
+        )
+    }
+
+    fn cached_paths<const N: usize>(cache: &PathCache<N>) -> Vec<&str> {
+        cache.entries().map(PathCacheEntry::path).collect()
+    }
+
+    mod get {
+        use super::*;
+
+        #[test]
+        fn equivalent_path_found() {
+            let mut cache = PathCache::<2>::new();
+            cache.insert("Foo/Bar.txt", &item(5));
+
+            let entry = cache
+                .get("./FOO//bar.TXT")
+                .expect("Some should be returned");
+
+            assert_eq!(entry.first_cluster_number(), 5);
+        }
+
+        #[test]
+        fn prefix_and_extended_paths_not_found() {
+            let mut cache = PathCache::<2>::new();
+            cache.insert("Foo/Bar.txt", &item(5));
+
+            assert!(cache.get("Foo").is_none());
+            assert!(cache.get("Foo/Bar.txt/Baz").is_none());
+        }
+
+        #[test]
+        fn parent_components_not_found() {
+            let mut cache = PathCache::<2>::new();
+            cache.insert("Foo/Bar.txt", &item(5));
+
+            assert!(cache.get("Foo/../Foo/Bar.txt").is_none());
+        }
+
+        #[test]
+        fn entry_marked_most_recently_used() {
+            let mut cache = PathCache::<3>::new();
+            cache.insert("a", &item(2));
+            cache.insert("b", &item(3));
+            cache.insert("c", &item(4));
+
+            cache.get("a");
+
+            assert_eq!(cached_paths(&cache), ["a", "c", "b"]);
+        }
+    }
+
+    mod insert {
+        use super::*;
+
+        #[test]
+        fn least_recently_used_entry_dropped() {
+            let mut cache = PathCache::<2>::new();
+            cache.insert("a", &item(2));
+            cache.insert("b", &item(3));
+            cache.get("a");
+
+            cache.insert("c", &item(4));
+
+            assert_eq!(cached_paths(&cache), ["c", "a"]);
+        }
+
+        #[test]
+        fn over_long_path_skipped() {
+            let mut cache = PathCache::<2>::new();
+
+            cache.insert(&"a".repeat(MAX_CACHED_PATH_LENGTH + 1), &item(2));
+
+            assert!(cache.is_empty());
+        }
+
+        #[test]
+        fn zero_capacity_holds_nothing() {
+            let mut cache = PathCache::<0>::new();
+
+            cache.insert("a", &item(2));
+
+            assert!(cache.is_empty());
+        }
+    }
+
+    mod invalidate {
+        use super::*;
+
+        #[test]
+        fn item_and_descendants_dropped() {
+            let mut cache = PathCache::<4>::new();
+            cache.insert("Foo", &item(2));
+            cache.insert("Foo/Bar.txt", &item(3));
+            cache.insert("Foobar.txt", &item(4));
+            cache.insert("Baz/Foo", &item(5));
+
+            cache.invalidate(&MutationEvent::Deleted {
+                directory_path: "",
+                name: "FOO",
+                first_cluster_number: 2,
+            });
+
+            assert_eq!(cached_paths(&cache), ["Baz/Foo", "Foobar.txt"]);
+        }
+
+        #[test]
+        fn nested_item_dropped() {
+            let mut cache = PathCache::<2>::new();
+            cache.insert("Foo/Bar.txt", &item(3));
+            cache.insert("Bar.txt", &item(4));
+
+            cache.invalidate(&MutationEvent::WriteCompleted {
+                directory_path: "foo",
+                name: "bar.txt",
+                first_cluster_number: 3,
+                file_size: 10,
+            });
+
+            assert_eq!(cached_paths(&cache), ["Bar.txt"]);
+        }
+
+        #[test]
+        fn parent_components_clear_cache() {
+            let mut cache = PathCache::<2>::new();
+            cache.insert("Bar.txt", &item(4));
+
+            cache.invalidate(&MutationEvent::Created {
+                directory_path: "foo/..",
+                name: "baz.txt",
+                first_cluster_number: 5,
+            });
+
+            assert!(cache.is_empty());
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use crate::DirectoryItem;
+use crate::directory_item::DirectoryItemKind;
+
+/// The longest canonical path, in bytes, a `PathCache` records.
+pub(crate) const MAX_CACHED_PATH_LENGTH: usize = 64;
+
+/// An item recorded by a `PathCache` under its canonical path.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PathCacheEntry {
+    path: [u8; MAX_CACHED_PATH_LENGTH],
+    path_length: usize,
+
+    kind: DirectoryItemKind,
+    first_cluster_number: u32,
+    file_size: u64,
+    last_write_date: u16,
+    last_write_time: u16,
+}
+
+impl PathCacheEntry {
+    /// Records the item under the path, `None` if the path is too long to be recorded.
+    pub(crate) fn new(path: &str, item: &DirectoryItem) -> Option<Self> {
+        let mut path_bytes = [0; MAX_CACHED_PATH_LENGTH];
+        path_bytes
+            .get_mut(..path.len())?
+            .copy_from_slice(path.as_bytes());
+
+        Some(Self {
+            path: path_bytes,
+            path_length: path.len(),
+
+            kind: item.kind(),
+            first_cluster_number: item.first_cluster_number(),
+            file_size: item.extended_file_size(),
+            last_write_date: item.last_write_date(),
+            last_write_time: item.last_write_time(),
+        })
+    }
+
+    /// The canonical path of the item, as returned by `FileSystem::canonicalize`.
+    pub fn path(&self) -> &str {
+        // Only whole strings are ever copied in, so the bytes are always valid UTF-8
+        core::str::from_utf8(&self.path[..self.path_length]).unwrap_or_default()
+    }
+
+    pub fn kind(&self) -> DirectoryItemKind {
+        self.kind
+    }
+
+    pub fn is_directory(&self) -> bool {
+        self.kind == DirectoryItemKind::Directory
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.kind == DirectoryItemKind::File
+    }
+
+    pub fn first_cluster_number(&self) -> u32 {
+        self.first_cluster_number
+    }
+
+    pub fn file_size(&self) -> u64 {
+        self.file_size
+    }
+
+    pub fn last_write_date(&self) -> u16 {
+        self.last_write_date
+    }
+
+    pub fn last_write_time(&self) -> u16 {
+        self.last_write_time
+    }
+}
@@ -0,0 +1,119 @@
+use crate::units::ClusterNumber;
+
+/// The cluster chain of a file indexed into memory by `FileSystem::pin`, so files opened through
+/// `FileSystem::open_pinned` seek to any indexed position without reading the allocation table.
+///
+/// Up to `N` clusters are indexed without allocating.  Seeks past the indexed clusters of a longer
+/// file walk the allocation table from the last indexed cluster onwards, as seeks of other files
+/// walk it from their first cluster.
+///
+/// Indexed chains aren't updated as the volume changes, the file must be pinned again once it has
+/// been written to, replaced or deleted.
+#[derive(Clone, Debug)]
+pub struct PinnedFile<const N: usize> {
+    first_cluster_number: ClusterNumber,
+    file_size: u64,
+
+    clusters: [ClusterNumber; N],
+    cluster_count: usize,
+    is_fully_indexed: bool,
+}
+
+impl<const N: usize> PinnedFile<N> {
+    pub(crate) fn new(first_cluster_number: ClusterNumber, file_size: u64) -> Self {
+        Self {
+            first_cluster_number,
+            file_size,
+
+            clusters: [ClusterNumber::NONE; N],
+            cluster_count: 0,
+            // Empty files own no clusters to index
+            is_fully_indexed: first_cluster_number == ClusterNumber::NONE,
+        }
+    }
+
+    /// The number of bytes the file held when it was pinned.
+    pub fn size(&self) -> u64 {
+        self.file_size
+    }
+
+    /// The cluster the file's contents start in, zero for an empty file.
+    pub fn first_cluster_number(&self) -> u32 {
+        self.first_cluster_number.value()
+    }
+
+    /// The number of clusters of the chain held in memory.
+    pub fn indexed_cluster_count(&self) -> usize {
+        self.cluster_count
+    }
+
+    /// Whether every cluster of the file's chain is held in memory, so no seek within the file
+    /// reads the allocation table.
+    pub fn is_fully_indexed(&self) -> bool {
+        self.is_fully_indexed
+    }
+
+    pub(crate) fn indexed_clusters(&self) -> &[ClusterNumber] {
+        &self.clusters[..self.cluster_count]
+    }
+
+    pub(crate) fn last_indexed_cluster(&self) -> Option<ClusterNumber> {
+        self.indexed_clusters().last().copied()
+    }
+
+    /// Appends the next cluster of the chain, returning `false` once the index is full.
+    pub(crate) fn push(&mut self, cluster_number: ClusterNumber) -> bool {
+        let Some(cluster) = self.clusters.get_mut(self.cluster_count) else {
+            return false;
+        };
+
+        *cluster = cluster_number;
+        self.cluster_count += 1;
+
+        true
+    }
+
+    pub(crate) fn mark_fully_indexed(&mut self) {
+        self.is_fully_indexed = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod push {
+        use super::*;
+
+        #[test]
+        fn clusters_indexed_up_to_capacity() {
+            let mut pinned_file = PinnedFile::<2>::new(ClusterNumber::new(5), 3_000);
+
+            assert!(pinned_file.push(ClusterNumber::new(5)));
+            assert!(pinned_file.push(ClusterNumber::new(9)));
+            assert!(!pinned_file.push(ClusterNumber::new(10)));
+
+            assert_eq!(
+                pinned_file.indexed_clusters(),
+                [ClusterNumber::new(5), ClusterNumber::new(9)]
+            );
+            assert_eq!(
+                pinned_file.last_indexed_cluster(),
+                Some(ClusterNumber::new(9))
+            );
+            assert!(!pinned_file.is_fully_indexed());
+        }
+    }
+
+    mod is_fully_indexed {
+        use super::*;
+
+        #[test]
+        fn empty_file_fully_indexed() {
+            let pinned_file = PinnedFile::<0>::new(ClusterNumber::NONE, 0);
+
+            assert!(pinned_file.is_fully_indexed());
+            assert_eq!(pinned_file.indexed_cluster_count(), 0);
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Constants and helpers describing the on-disk layout of directory entries, for tooling which
+//! reads or writes entries itself instead of going through `FileSystem`.
+//!
+//! Everything here follows the FAT specification and won't change between releases.
+
+use crate::directory_entry::{DirectoryEntryAttributes, SHORT_NAME_CHARACTER_COUNT};
+use crate::timestamp::{FatDate, FatTime};
+
+pub use crate::directory_entry::DIRECTORY_ENTRY_SIZE;
+
+/// The first byte of the entry ending a directory, no entries after it are in use.
+pub const END_OF_DIRECTORY_MARKER: u8 = 0x00;
+/// The first byte of an entry which was deleted and may be reused.
+pub const FREE_ENTRY_MARKER: u8 = 0xE5;
+
+pub const ATTRIBUTE_READ_ONLY: u8 = DirectoryEntryAttributes::ReadOnly.bits();
+pub const ATTRIBUTE_HIDDEN: u8 = DirectoryEntryAttributes::Hidden.bits();
+pub const ATTRIBUTE_SYSTEM: u8 = DirectoryEntryAttributes::System.bits();
+pub const ATTRIBUTE_VOLUME_LABEL: u8 = DirectoryEntryAttributes::VolumeLabel.bits();
+pub const ATTRIBUTE_SUBDIRECTORY: u8 = DirectoryEntryAttributes::Subdirectory.bits();
+pub const ATTRIBUTE_ARCHIVE: u8 = DirectoryEntryAttributes::Archive.bits();
+/// The combination of attributes marking a long name entry.
+pub const ATTRIBUTE_LONG_NAME: u8 = DirectoryEntryAttributes::LongName.bits();
+
+/// Packs a calendar date into a FAT date, `None` if the date doesn't exist or is outside of 1980
+/// to 2107.  See `FatDate` for a typed equivalent.
+pub const fn encode_date(year: u16, month: u8, day: u8) -> Option<u16> {
+    match FatDate::new(year, month, day) {
+        Ok(date) => Some(date.to_packed()),
+        Err(_) => None,
+    }
+}
+
+/// Unpacks a FAT date into its year, month and day, `None` if it doesn't hold a date which exists.
+pub const fn decode_date(date: u16) -> Option<(u16, u8, u8)> {
+    match FatDate::from_packed(date) {
+        Ok(date) => Some((date.year(), date.month(), date.day())),
+        Err(_) => None,
+    }
+}
+
+/// Packs a time of day into a FAT time, `None` if any part is out of range.  FAT times have a
+/// resolution of two seconds, odd seconds are rounded down.  See `FatTime` for a typed equivalent.
+pub const fn encode_time(hour: u8, minute: u8, second: u8) -> Option<u16> {
+    match FatTime::new(hour, minute, second) {
+        Ok(time) => Some(time.to_packed()),
+        Err(_) => None,
+    }
+}
+
+/// Unpacks a FAT time into its hour, minute and second, `None` if any part is out of range.
+pub const fn decode_time(time: u16) -> Option<(u8, u8, u8)> {
+    match FatTime::from_packed(time) {
+        Ok(time) => Some((time.hour(), time.minute(), time.second())),
+        Err(_) => None,
+    }
+}
+
+/// The checksum of a short name as stored in its 11 padded bytes, held by each of the long name
+/// entries belonging to it.
+pub const fn short_name_checksum(bytes: &[u8; SHORT_NAME_CHARACTER_COUNT]) -> u8 {
+    let mut checksum: u8 = 0;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        checksum = checksum.rotate_right(1).wrapping_add(bytes[index]);
+        index += 1;
+    }
+
+    checksum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod encode_date {
+        use super::*;
+
+        #[test]
+        fn fields_packed() {
+            assert_eq!(encode_date(1980, 1, 1), Some(0x0021));
+            assert_eq!(encode_date(2024, 6, 15), Some((44 << 9) | (6 << 5) | 15));
+            assert_eq!(encode_date(2107, 12, 31), Some(0xFF9F));
+        }
+
+        #[test]
+        fn out_of_range_rejected() {
+            assert_eq!(encode_date(1979, 12, 31), None);
+            assert_eq!(encode_date(2108, 1, 1), None);
+            assert_eq!(encode_date(2024, 0, 1), None);
+            assert_eq!(encode_date(2024, 13, 1), None);
+            assert_eq!(encode_date(2024, 1, 0), None);
+            assert_eq!(encode_date(2024, 1, 32), None);
+            assert_eq!(encode_date(2023, 2, 29), None);
+        }
+    }
+
+    mod decode_date {
+        use super::*;
+
+        #[test]
+        fn round_trips() {
+            let date = encode_date(2024, 6, 15).unwrap();
+
+            assert_eq!(decode_date(date), Some((2024, 6, 15)));
+        }
+
+        #[test]
+        fn impossible_dates_rejected() {
+            assert_eq!(decode_date(0), None);
+        }
+    }
+
+    mod encode_time {
+        use super::*;
+
+        #[test]
+        fn fields_packed() {
+            assert_eq!(encode_time(0, 0, 0), Some(0));
+            assert_eq!(encode_time(23, 59, 58), Some(0xBF7D));
+        }
+
+        #[test]
+        fn odd_seconds_rounded_down() {
+            assert_eq!(encode_time(12, 30, 59), encode_time(12, 30, 58));
+        }
+
+        #[test]
+        fn out_of_range_rejected() {
+            assert_eq!(encode_time(24, 0, 0), None);
+            assert_eq!(encode_time(0, 60, 0), None);
+            assert_eq!(encode_time(0, 0, 60), None);
+        }
+    }
+
+    mod decode_time {
+        use super::*;
+
+        #[test]
+        fn round_trips() {
+            let time = encode_time(13, 45, 20).unwrap();
+
+            assert_eq!(decode_time(time), Some((13, 45, 20)));
+        }
+
+        #[test]
+        fn impossible_times_rejected() {
+            assert_eq!(decode_time(0x1F), None);
+        }
+    }
+
+    mod short_name_checksum {
+        use super::*;
+
+        #[test]
+        fn matches_test_vectors() {
+            assert_eq!(short_name_checksum(b"FOO     BAR"), 0x53);
+            assert_eq!(short_name_checksum(b"PICKLE  A  "), 0x32);
+        }
+    }
+}
@@ -0,0 +1,12 @@
+mod card;
+mod error;
+mod stream;
+
+pub use card::*;
+pub use error::*;
+pub use stream::*;
+
+use crate::SingleAccessDevice;
+
+/// A ready-made device over an SPI-connected SD card, created by [`SdCard::into_device`].
+pub type SdCardDevice<SPI, CS, D> = SingleAccessDevice<SdCardStream<SPI, CS, D>>;
@@ -0,0 +1,489 @@
+use crate::SingleAccessDevice;
+use crate::sd_card::{SdCardDevice, SdCardError, SdCardStream};
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+use embedded_hal::spi::SpiBus;
+
+/// The size of a single block transferred to or from an SD card.
+pub const SD_CARD_BLOCK_SIZE: usize = 512;
+
+const GO_IDLE_STATE: u8 = 0;
+const SEND_IF_COND: u8 = 8;
+const SEND_CSD: u8 = 9;
+const SET_BLOCKLEN: u8 = 16;
+const READ_SINGLE_BLOCK: u8 = 17;
+const WRITE_BLOCK: u8 = 24;
+const APP_CMD: u8 = 55;
+const READ_OCR: u8 = 58;
+const SD_SEND_OP_COND: u8 = 41;
+
+const R1_READY: u8 = 0x00;
+const R1_IDLE_STATE: u8 = 0x01;
+const R1_ILLEGAL_COMMAND: u8 = 0x04;
+
+const IF_COND_CHECK_PATTERN: u8 = 0xAA;
+const IF_COND_ARGUMENT: u32 = 0x100 | IF_COND_CHECK_PATTERN as u32;
+const OP_COND_HIGH_CAPACITY: u32 = 1 << 30;
+const OCR_CARD_CAPACITY_STATUS: u8 = 0x40;
+
+const DATA_START_TOKEN: u8 = 0xFE;
+const DATA_RESPONSE_MASK: u8 = 0x1F;
+const DATA_RESPONSE_ACCEPTED: u8 = 0x05;
+
+const IDLE_BYTE: u8 = 0xFF;
+const BUSY_BYTE: u8 = 0x00;
+
+const WAKE_UP_BYTES: usize = 10;
+const RESET_ATTEMPTS: usize = 32;
+const RESPONSE_ATTEMPTS: usize = 8;
+const OP_COND_ATTEMPTS: usize = 1000;
+const OP_COND_RETRY_DELAY_MS: u32 = 1;
+const DATA_ATTEMPTS: usize = 1 << 16;
+const BUSY_ATTEMPTS: usize = 1 << 20;
+
+type Result<T, SPI, CS> = core::result::Result<
+    T,
+    SdCardError<
+        <SPI as embedded_hal::spi::ErrorType>::Error,
+        <CS as embedded_hal::digital::ErrorType>::Error,
+    >,
+>;
+
+/// A block driver for an SD card connected over an SPI bus with a dedicated chip select pin.
+///
+/// Supports SDSC, SDHC and SDXC cards in SPI mode, transferring one block at a time. The bus should
+/// be clocked at no more than 400 kHz until [`SdCard::new`] returns, after which it may be raised to
+/// the card's rated speed.
+#[derive(Debug)]
+pub struct SdCard<SPI, CS, D> {
+    spi: SPI,
+    chip_select: CS,
+    delay: D,
+    is_high_capacity: bool,
+    block_count: u32,
+}
+
+impl<SPI, CS, D> SdCard<SPI, CS, D>
+where
+    SPI: SpiBus,
+    CS: OutputPin,
+    D: DelayNs,
+{
+    /// Initializes the card attached to `spi` and `chip_select`.
+    pub fn new(spi: SPI, chip_select: CS, delay: D) -> Result<Self, SPI, CS> {
+        let mut card = Self {
+            spi,
+            chip_select,
+            delay,
+            is_high_capacity: false,
+            block_count: 0,
+        };
+
+        card.initialize()?;
+
+        Ok(card)
+    }
+
+    /// The number of blocks available on the card.
+    pub fn block_count(&self) -> u32 {
+        self.block_count
+    }
+
+    /// Whether the card is addressed by block (SDHC/SDXC) rather than by byte (SDSC).
+    pub fn is_high_capacity(&self) -> bool {
+        self.is_high_capacity
+    }
+
+    /// Reads the block at index `block` into `buffer`.
+    pub fn read_block(
+        &mut self,
+        block: u32,
+        buffer: &mut [u8; SD_CARD_BLOCK_SIZE],
+    ) -> Result<(), SPI, CS> {
+        let address = self.block_address(block)?;
+
+        self.with_selected(|card| {
+            card.checked_command(READ_SINGLE_BLOCK, address)?;
+            card.read_data(buffer)
+        })
+    }
+
+    /// Writes `buffer` to the block at index `block`.
+    pub fn write_block(
+        &mut self,
+        block: u32,
+        buffer: &[u8; SD_CARD_BLOCK_SIZE],
+    ) -> Result<(), SPI, CS> {
+        let address = self.block_address(block)?;
+
+        self.with_selected(|card| {
+            card.checked_command(WRITE_BLOCK, address)?;
+            card.write_data(buffer)
+        })
+    }
+
+    /// Wraps the card in a [`SingleAccessDevice`] ready to be given to a `FileSystem`.
+    pub fn into_device(self) -> SdCardDevice<SPI, CS, D> {
+        SingleAccessDevice::new(SdCardStream::new(self))
+    }
+
+    /// Releases the bus, pin and delay provider.
+    pub fn release(self) -> (SPI, CS, D) {
+        (self.spi, self.chip_select, self.delay)
+    }
+
+    fn initialize(&mut self) -> Result<(), SPI, CS> {
+        self.chip_select
+            .set_high()
+            .map_err(SdCardError::ChipSelect)?;
+
+        for _ in 0..WAKE_UP_BYTES {
+            self.exchange(IDLE_BYTE)?;
+        }
+
+        self.with_selected(Self::initialize_selected)
+    }
+
+    fn initialize_selected(&mut self) -> Result<(), SPI, CS> {
+        self.reset()?;
+
+        let is_version_2 = self.check_interface_condition()?;
+
+        self.wait_for_operating_condition(is_version_2)?;
+
+        if is_version_2 {
+            self.checked_command(READ_OCR, 0)?;
+
+            let mut ocr = [IDLE_BYTE; 4];
+            self.transfer(&mut ocr)?;
+
+            self.is_high_capacity = ocr[0] & OCR_CARD_CAPACITY_STATUS != 0;
+        }
+
+        if !self.is_high_capacity {
+            self.checked_command(SET_BLOCKLEN, SD_CARD_BLOCK_SIZE as u32)?;
+        }
+
+        self.checked_command(SEND_CSD, 0)?;
+
+        let mut csd = [0; 16];
+        self.read_data(&mut csd)?;
+
+        self.block_count = csd_block_count(&csd).ok_or(SdCardError::UnsupportedCard)?;
+
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), SPI, CS> {
+        for _ in 0..RESET_ATTEMPTS {
+            match self.command(GO_IDLE_STATE, 0) {
+                Ok(R1_IDLE_STATE) => return Ok(()),
+                Ok(_) | Err(SdCardError::ResponseTimedOut) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(SdCardError::CardNotFound)
+    }
+
+    /// Determines whether the card implements version 2 of the physical layer specification.
+    fn check_interface_condition(&mut self) -> Result<bool, SPI, CS> {
+        let response = self.command(SEND_IF_COND, IF_COND_ARGUMENT)?;
+
+        if response & R1_ILLEGAL_COMMAND != 0 {
+            return Ok(false);
+        }
+
+        let mut r7 = [IDLE_BYTE; 4];
+        self.transfer(&mut r7)?;
+
+        if r7[3] != IF_COND_CHECK_PATTERN || r7[2] & 0x0F != 0x01 {
+            return Err(SdCardError::UnsupportedCard);
+        }
+
+        Ok(true)
+    }
+
+    fn wait_for_operating_condition(&mut self, is_version_2: bool) -> Result<(), SPI, CS> {
+        let argument = if is_version_2 {
+            OP_COND_HIGH_CAPACITY
+        } else {
+            0
+        };
+
+        for _ in 0..OP_COND_ATTEMPTS {
+            self.checked_command(APP_CMD, 0)?;
+
+            match self.command(SD_SEND_OP_COND, argument)? {
+                R1_READY => return Ok(()),
+                R1_IDLE_STATE => self.delay.delay_ms(OP_COND_RETRY_DELAY_MS),
+                response => {
+                    return Err(SdCardError::CommandFailed {
+                        command: SD_SEND_OP_COND,
+                        response,
+                    });
+                }
+            }
+        }
+
+        Err(SdCardError::InitializationTimedOut)
+    }
+
+    fn block_address(&self, block: u32) -> Result<u32, SPI, CS> {
+        if block >= self.block_count {
+            return Err(SdCardError::BlockOutOfRange { block });
+        }
+
+        Ok(if self.is_high_capacity {
+            block
+        } else {
+            block * SD_CARD_BLOCK_SIZE as u32
+        })
+    }
+
+    /// Runs `f` with the card selected, deselecting it afterwards regardless of the outcome.
+    fn with_selected<F, R>(&mut self, f: F) -> Result<R, SPI, CS>
+    where
+        F: FnOnce(&mut Self) -> Result<R, SPI, CS>,
+    {
+        self.chip_select
+            .set_low()
+            .map_err(SdCardError::ChipSelect)?;
+
+        let result = f(self);
+        let flush_result = self.spi.flush().map_err(SdCardError::Spi);
+        let deselect_result = self.chip_select.set_high().map_err(SdCardError::ChipSelect);
+
+        // The card only releases the data line after seeing a clock edge while deselected
+        let release_result = self.exchange(IDLE_BYTE);
+
+        let value = result?;
+        flush_result?;
+        deselect_result?;
+        release_result?;
+
+        Ok(value)
+    }
+
+    /// Sends a command, returning its R1 response without interpreting it.
+    fn command(&mut self, command: u8, argument: u32) -> Result<u8, SPI, CS> {
+        let mut frame = [0; 6];
+        frame[0] = 0x40 | command;
+        frame[1..5].copy_from_slice(&argument.to_be_bytes());
+        frame[5] = crc7(&frame[..5]);
+
+        self.spi.write(&frame).map_err(SdCardError::Spi)?;
+
+        for _ in 0..RESPONSE_ATTEMPTS {
+            let response = self.exchange(IDLE_BYTE)?;
+
+            if response & 0x80 == 0 {
+                return Ok(response);
+            }
+        }
+
+        Err(SdCardError::ResponseTimedOut)
+    }
+
+    /// Sends a command, failing if its R1 response reports any error.
+    fn checked_command(&mut self, command: u8, argument: u32) -> Result<(), SPI, CS> {
+        let response = self.command(command, argument)?;
+
+        if response & !R1_IDLE_STATE != 0 {
+            return Err(SdCardError::CommandFailed { command, response });
+        }
+
+        Ok(())
+    }
+
+    fn read_data(&mut self, buffer: &mut [u8]) -> Result<(), SPI, CS> {
+        let token = self.wait_while(IDLE_BYTE, DATA_ATTEMPTS)?;
+
+        if token != DATA_START_TOKEN {
+            return Err(SdCardError::ReadFailed { token });
+        }
+
+        buffer.fill(IDLE_BYTE);
+        self.transfer(buffer)?;
+
+        let mut crc = [IDLE_BYTE; 2];
+        self.transfer(&mut crc)
+    }
+
+    fn write_data(&mut self, buffer: &[u8]) -> Result<(), SPI, CS> {
+        self.spi
+            .write(&[IDLE_BYTE, DATA_START_TOKEN])
+            .map_err(SdCardError::Spi)?;
+        self.spi.write(buffer).map_err(SdCardError::Spi)?;
+        self.spi
+            .write(&[IDLE_BYTE, IDLE_BYTE])
+            .map_err(SdCardError::Spi)?;
+
+        let response = self.exchange(IDLE_BYTE)?;
+
+        if response & DATA_RESPONSE_MASK != DATA_RESPONSE_ACCEPTED {
+            return Err(SdCardError::WriteRejected { response });
+        }
+
+        self.wait_while(BUSY_BYTE, BUSY_ATTEMPTS)?;
+
+        Ok(())
+    }
+
+    /// Clocks the bus until the card returns something other than `filler`, returning that byte.
+    fn wait_while(&mut self, filler: u8, attempts: usize) -> Result<u8, SPI, CS> {
+        for _ in 0..attempts {
+            let byte = self.exchange(IDLE_BYTE)?;
+
+            if byte != filler {
+                return Ok(byte);
+            }
+        }
+
+        Err(SdCardError::ResponseTimedOut)
+    }
+
+    fn exchange(&mut self, byte: u8) -> Result<u8, SPI, CS> {
+        let mut buffer = [byte];
+        self.transfer(&mut buffer)?;
+
+        Ok(buffer[0])
+    }
+
+    fn transfer(&mut self, buffer: &mut [u8]) -> Result<(), SPI, CS> {
+        self.spi.transfer_in_place(buffer).map_err(SdCardError::Spi)
+    }
+}
+
+/// Computes the CRC7 of a command frame, already shifted into place alongside the end bit.
+pub(crate) fn crc7(bytes: &[u8]) -> u8 {
+    let mut crc = 0u8;
+
+    for byte in bytes {
+        for bit in (0..8).rev() {
+            let feedback = ((byte >> bit) ^ (crc >> 6)) & 1;
+
+            crc = (crc << 1) & 0x7F;
+
+            if feedback == 1 {
+                crc ^= 0x09;
+            }
+        }
+    }
+
+    (crc << 1) | 1
+}
+
+/// Computes the number of blocks on a card from its card-specific data register.
+fn csd_block_count(csd: &[u8; 16]) -> Option<u32> {
+    match csd[0] >> 6 {
+        0 => {
+            let read_block_length = (csd[5] & 0x0F) as u32;
+            let device_size =
+                ((csd[6] as u32 & 0x03) << 10) | ((csd[7] as u32) << 2) | (csd[8] as u32 >> 6);
+            let device_size_multiplier = ((csd[9] as u32 & 0x03) << 1) | (csd[10] as u32 >> 7);
+
+            let shift = (device_size_multiplier + 2 + read_block_length).checked_sub(9)?;
+
+            Some((device_size + 1) << shift)
+        }
+        1 => {
+            let device_size =
+                ((csd[7] as u32 & 0x3F) << 16) | ((csd[8] as u32) << 8) | csd[9] as u32;
+
+            (device_size + 1).checked_mul(1024)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{FakeSdCard, NoDelay, NoOpPin};
+
+    fn new_card(fake: FakeSdCard) -> SdCard<FakeSdCard, NoOpPin, NoDelay> {
+        SdCard::new(fake, NoOpPin, NoDelay).expect("Initialization should succeed")
+    }
+
+    mod new {
+        use super::*;
+
+        #[test]
+        fn high_capacity_card_initialized() {
+            let card = new_card(FakeSdCard::new(2048, true));
+
+            assert!(card.is_high_capacity());
+            assert_eq!(card.block_count(), 2048);
+        }
+
+        #[test]
+        fn standard_capacity_card_initialized() {
+            let card = new_card(FakeSdCard::new(1024, false));
+
+            assert!(!card.is_high_capacity());
+            assert_eq!(card.block_count(), 1024);
+        }
+
+        #[test]
+        fn unresponsive_bus_returns_err() {
+            let result = SdCard::new(FakeSdCard::absent(), NoOpPin, NoDelay);
+
+            assert!(matches!(result, Err(SdCardError::CardNotFound)));
+        }
+    }
+
+    mod read_block {
+        use super::*;
+
+        #[test]
+        fn block_contents_returned() {
+            let mut fake = FakeSdCard::new(1024, false);
+            fake.storage_mut()[1024..1536].fill(0x5A);
+
+            let mut card = new_card(fake);
+            let mut buffer = [0; SD_CARD_BLOCK_SIZE];
+
+            card.read_block(2, &mut buffer).unwrap();
+
+            assert_eq!(buffer, [0x5A; SD_CARD_BLOCK_SIZE]);
+        }
+
+        #[test]
+        fn block_beyond_end_returns_err() {
+            let mut card = new_card(FakeSdCard::new(1024, true));
+            let mut buffer = [0; SD_CARD_BLOCK_SIZE];
+
+            assert!(matches!(
+                card.read_block(1024, &mut buffer),
+                Err(SdCardError::BlockOutOfRange { block: 1024 })
+            ));
+        }
+    }
+
+    mod write_block {
+        use super::*;
+
+        #[test]
+        fn block_contents_stored() {
+            let mut card = new_card(FakeSdCard::new(1024, true));
+
+            card.write_block(3, &[0xA5; SD_CARD_BLOCK_SIZE]).unwrap();
+
+            let (fake, _, _) = card.release();
+
+            assert_eq!(fake.storage()[1536..2048], [0xA5; SD_CARD_BLOCK_SIZE]);
+            assert_eq!(fake.storage()[1535], 0);
+        }
+    }
+
+    mod crc7 {
+        use super::*;
+
+        #[test]
+        fn known_commands_match() {
+            assert_eq!(crc7(&[0x40, 0, 0, 0, 0]), 0x95);
+            assert_eq!(crc7(&[0x48, 0, 0, 0x01, 0xAA]), 0x87);
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use core::fmt::{Debug, Display, Formatter};
+use embedded_io::ErrorKind;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SdCardError<SE, PE>
+where
+    SE: embedded_hal::spi::Error,
+    PE: embedded_hal::digital::Error,
+{
+    /// Transferring bytes over the SPI bus failed
+    Spi(SE),
+
+    /// Driving the chip select pin failed
+    ChipSelect(PE),
+
+    /// No card responded to the reset command
+    CardNotFound,
+
+    /// The card does not support the host's voltage range or reported an unknown version
+    UnsupportedCard,
+
+    /// The card did not finish initializing within the allowed number of attempts
+    InitializationTimedOut,
+
+    /// The card did not respond within the allowed number of bytes
+    ResponseTimedOut,
+
+    /// The card responded to a command with an error
+    CommandFailed { command: u8, response: u8 },
+
+    /// The card responded to a read with an error token instead of data
+    ReadFailed { token: u8 },
+
+    /// The card rejected a block of written data
+    WriteRejected { response: u8 },
+
+    /// The block lies beyond the end of the card
+    BlockOutOfRange { block: u32 },
+
+    /// Seeking would move the position before the start of the card
+    SeekPositionImpossible(i64),
+}
+
+impl<SE, PE> core::error::Error for SdCardError<SE, PE>
+where
+    SE: embedded_hal::spi::Error,
+    PE: embedded_hal::digital::Error,
+{
+}
+
+impl<SE, PE> Display for SdCardError<SE, PE>
+where
+    SE: embedded_hal::spi::Error,
+    PE: embedded_hal::digital::Error,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SdCardError::Spi(e) => {
+                write!(f, "an error occurred while using the SPI bus: {:?}", e)
+            }
+            SdCardError::ChipSelect(e) => write!(
+                f,
+                "an error occurred while driving the chip select pin: {:?}",
+                e
+            ),
+            SdCardError::CardNotFound => write!(f, "no card responded to the reset command"),
+            SdCardError::UnsupportedCard => write!(f, "the card is not supported"),
+            SdCardError::InitializationTimedOut => {
+                write!(f, "the card did not finish initializing in time")
+            }
+            SdCardError::ResponseTimedOut => write!(f, "the card did not respond in time"),
+            SdCardError::CommandFailed { command, response } => write!(
+                f,
+                "the card responded to command {} with error 0x{:02X}",
+                command, response
+            ),
+            SdCardError::ReadFailed { token } => write!(
+                f,
+                "the card responded to a read with error token 0x{:02X}",
+                token
+            ),
+            SdCardError::WriteRejected { response } => write!(
+                f,
+                "the card rejected written data with response 0x{:02X}",
+                response
+            ),
+            SdCardError::BlockOutOfRange { block } => {
+                write!(f, "block {} lies beyond the end of the card", block)
+            }
+            SdCardError::SeekPositionImpossible(position) => write!(
+                f,
+                "seeking would move the position to {} which is before the start of the card",
+                position
+            ),
+        }
+    }
+}
+
+impl<SE, PE> embedded_io::Error for SdCardError<SE, PE>
+where
+    SE: embedded_hal::spi::Error,
+    PE: embedded_hal::digital::Error,
+{
+    fn kind(&self) -> ErrorKind {
+        match self {
+            SdCardError::BlockOutOfRange { .. } | SdCardError::SeekPositionImpossible(_) => {
+                ErrorKind::InvalidInput
+            }
+            SdCardError::ResponseTimedOut | SdCardError::InitializationTimedOut => {
+                ErrorKind::TimedOut
+            }
+            SdCardError::CardNotFound => ErrorKind::NotFound,
+            SdCardError::UnsupportedCard => ErrorKind::Unsupported,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use core::convert::Infallible;
+    use embedded_hal::spi::ErrorKind as SpiErrorKind;
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn produces_non_empty_value() {
+            let values: [SdCardError<SpiErrorKind, Infallible>; 10] = [
+                SdCardError::Spi(SpiErrorKind::Other),
+                SdCardError::CardNotFound,
+                SdCardError::UnsupportedCard,
+                SdCardError::InitializationTimedOut,
+                SdCardError::ResponseTimedOut,
+                SdCardError::CommandFailed {
+                    command: 17,
+                    response: 0x04,
+                },
+                SdCardError::ReadFailed { token: 0x08 },
+                SdCardError::WriteRejected { response: 0x0B },
+                SdCardError::BlockOutOfRange { block: 5 },
+                SdCardError::SeekPositionImpossible(-1),
+            ];
+
+            for value in values {
+                assert!(
+                    !value.to_string().is_empty(),
+                    "Display implementation should be non-empty"
+                );
+            }
+        }
+    }
+}
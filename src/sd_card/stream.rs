@@ -0,0 +1,255 @@
+use crate::sd_card::{SD_CARD_BLOCK_SIZE, SdCard, SdCardError};
+use core::cmp::min;
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+use embedded_hal::spi::SpiBus;
+use embedded_io::{ErrorType, Read, Seek, SeekFrom, Write};
+
+/// A byte stream over an [`SdCard`].
+///
+/// The most recently accessed block is cached so sequential small reads only transfer each block
+/// once. Writes are applied to the card immediately, so flushing is never required for durability.
+#[derive(Debug)]
+pub struct SdCardStream<SPI, CS, D> {
+    card: SdCard<SPI, CS, D>,
+    cache: [u8; SD_CARD_BLOCK_SIZE],
+    cached_block: Option<u32>,
+    position: u64,
+}
+
+impl<SPI, CS, D> SdCardStream<SPI, CS, D>
+where
+    SPI: SpiBus,
+    CS: OutputPin,
+    D: DelayNs,
+{
+    pub fn new(card: SdCard<SPI, CS, D>) -> Self {
+        Self {
+            card,
+            cache: [0; SD_CARD_BLOCK_SIZE],
+            cached_block: None,
+            position: 0,
+        }
+    }
+
+    pub fn into_inner(self) -> SdCard<SPI, CS, D> {
+        self.card
+    }
+
+    fn size(&self) -> u64 {
+        self.card.block_count() as u64 * SD_CARD_BLOCK_SIZE as u64
+    }
+
+    /// Splits the current position into its block index and offset within that block.
+    fn block_position(&self) -> (u32, usize) {
+        (
+            (self.position / SD_CARD_BLOCK_SIZE as u64) as u32,
+            (self.position % SD_CARD_BLOCK_SIZE as u64) as usize,
+        )
+    }
+
+    fn load_block(&mut self, block: u32) -> Result<(), <Self as ErrorType>::Error> {
+        if self.cached_block != Some(block) {
+            self.cached_block = None;
+            self.card.read_block(block, &mut self.cache)?;
+            self.cached_block = Some(block);
+        }
+
+        Ok(())
+    }
+}
+
+impl<SPI, CS, D> ErrorType for SdCardStream<SPI, CS, D>
+where
+    SPI: SpiBus,
+    CS: OutputPin,
+    D: DelayNs,
+{
+    type Error = SdCardError<SPI::Error, CS::Error>;
+}
+
+impl<SPI, CS, D> Read for SdCardStream<SPI, CS, D>
+where
+    SPI: SpiBus,
+    CS: OutputPin,
+    D: DelayNs,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let mut total_read = 0;
+
+        while total_read < buf.len() && self.position < self.size() {
+            let (block, offset) = self.block_position();
+            let length = min(SD_CARD_BLOCK_SIZE - offset, buf.len() - total_read);
+
+            self.load_block(block)?;
+
+            buf[total_read..total_read + length]
+                .copy_from_slice(&self.cache[offset..offset + length]);
+
+            total_read += length;
+            self.position += length as u64;
+        }
+
+        Ok(total_read)
+    }
+}
+
+impl<SPI, CS, D> Write for SdCardStream<SPI, CS, D>
+where
+    SPI: SpiBus,
+    CS: OutputPin,
+    D: DelayNs,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let mut total_written = 0;
+
+        while total_written < buf.len() && self.position < self.size() {
+            let (block, offset) = self.block_position();
+            let length = min(SD_CARD_BLOCK_SIZE - offset, buf.len() - total_written);
+
+            // Partial blocks must be merged with the existing contents before being written back
+            if length < SD_CARD_BLOCK_SIZE {
+                self.load_block(block)?;
+            }
+
+            self.cached_block = None;
+            self.cache[offset..offset + length]
+                .copy_from_slice(&buf[total_written..total_written + length]);
+
+            self.card.write_block(block, &self.cache)?;
+            self.cached_block = Some(block);
+
+            total_written += length;
+            self.position += length as u64;
+        }
+
+        Ok(total_written)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<SPI, CS, D> Seek for SdCardStream<SPI, CS, D>
+where
+    SPI: SpiBus,
+    CS: OutputPin,
+    D: DelayNs,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(value) => {
+                self.position = value;
+
+                return Ok(self.position);
+            }
+            SeekFrom::End(offset) => (self.size(), offset),
+            SeekFrom::Current(offset) => (self.position, offset),
+        };
+
+        self.position =
+            base.checked_add_signed(offset)
+                .ok_or(SdCardError::SeekPositionImpossible(
+                    (base as i64).saturating_add(offset),
+                ))?;
+
+        Ok(self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileSystemBuilder;
+    use crate::mock::{FakeSdCard, NoDelay, NoOpPin};
+
+    fn new_stream(fake: FakeSdCard) -> SdCardStream<FakeSdCard, NoOpPin, NoDelay> {
+        SdCardStream::new(SdCard::new(fake, NoOpPin, NoDelay).unwrap())
+    }
+
+    fn sequential_fake() -> FakeSdCard {
+        let mut fake = FakeSdCard::new(1024, true);
+
+        for (index, byte) in fake.storage_mut().iter_mut().enumerate() {
+            *byte = index as u8;
+        }
+
+        fake
+    }
+
+    mod read {
+        use super::*;
+
+        #[test]
+        fn read_spanning_blocks_reassembled() {
+            let mut stream = new_stream(sequential_fake());
+            let mut buf = [0; 8];
+
+            stream.seek(SeekFrom::Start(508)).unwrap();
+
+            assert_eq!(stream.read(&mut buf).unwrap(), 8);
+            assert_eq!(buf, [252, 253, 254, 255, 0, 1, 2, 3]);
+        }
+
+        #[test]
+        fn end_of_card_returns_short_count() {
+            let mut stream = new_stream(sequential_fake());
+            let mut buf = [0; 8];
+
+            stream.seek(SeekFrom::End(-3)).unwrap();
+
+            assert_eq!(stream.read(&mut buf).unwrap(), 3);
+            assert_eq!(stream.read(&mut buf).unwrap(), 0);
+        }
+    }
+
+    mod write {
+        use super::*;
+
+        #[test]
+        fn partial_blocks_merged() {
+            let mut stream = new_stream(sequential_fake());
+
+            stream.seek(SeekFrom::Start(510)).unwrap();
+            stream.write_all(&[0xEE; 4]).unwrap();
+
+            let (fake, _, _) = stream.into_inner().release();
+
+            assert_eq!(fake.storage()[508..514], [252, 253, 0xEE, 0xEE, 0xEE, 0xEE]);
+            assert_eq!(fake.storage()[514], 2);
+        }
+    }
+
+    mod seek {
+        use super::*;
+
+        #[test]
+        fn position_before_start_returns_err() {
+            let mut stream = new_stream(sequential_fake());
+
+            assert!(matches!(
+                stream.seek(SeekFrom::Current(-1)),
+                Err(SdCardError::SeekPositionImpossible(-1))
+            ));
+        }
+    }
+
+    #[test]
+    fn file_system_mounted_over_card() {
+        let mut fake = FakeSdCard::new(2048, true);
+        fake.storage_mut()
+            .copy_from_slice(&std::fs::read("disks/fat12.img").unwrap());
+
+        let device = SdCard::new(fake, NoOpPin, NoDelay).unwrap().into_device();
+        let file_system = FileSystemBuilder::from_device(device)
+            .build()
+            .expect("Mounting should succeed");
+        let mut file = file_system.open("TEST.TXT").unwrap();
+        let mut bytes = [0; 5];
+
+        file.read_exact(&mut bytes).unwrap();
+
+        assert_eq!(bytes, *b"test\n");
+    }
+}
@@ -0,0 +1,19 @@
+mod bounce_buffered;
+mod buffer_requirements;
+#[cfg(feature = "dyn-device")]
+mod dynamic;
+mod prefetching;
+mod retrying;
+mod sector_splitting;
+mod slice;
+mod sparse;
+
+pub use bounce_buffered::*;
+pub use buffer_requirements::*;
+#[cfg(feature = "dyn-device")]
+pub use dynamic::*;
+pub use prefetching::*;
+pub use retrying::*;
+pub use sector_splitting::*;
+pub use slice::*;
+pub use sparse::*;
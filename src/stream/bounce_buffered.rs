@@ -0,0 +1,331 @@
+use crate::stream::BufferRequirements;
+use core::cmp::min;
+use embedded_io::{ErrorType, SeekFrom};
+
+#[cfg(feature = "sync")]
+use embedded_io::{Read, Seek, Write};
+
+#[cfg(feature = "async")]
+use embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek, Write as AsyncWrite};
+
+/// A stream adapter which routes reads through an internal bounce buffer whenever the caller's
+/// buffer does not satisfy the wrapped stream's [`BufferRequirements`].
+///
+/// Reads into buffers which already satisfy the requirements are passed through untouched. When the
+/// length requirement forces more bytes to be read than were requested, the surplus is discarded
+/// and the wrapped stream is seeked back so the stream position stays byte-accurate. Writes and
+/// seeks are forwarded unchanged.
+///
+/// `N` is the size of the bounce buffer; larger buffers reduce the number of transfers needed to
+/// service a single unaligned read.
+#[derive(Clone, Debug)]
+pub struct BounceBufferedStream<S, const N: usize> {
+    inner: S,
+    bounce_buffer: [u8; N],
+}
+
+impl<S, const N: usize> BounceBufferedStream<S, N>
+where
+    S: BufferRequirements,
+{
+    /// Wraps `inner`, reading through a bounce buffer of `N` bytes when required.
+    ///
+    /// # Panics
+    /// Panics if the requirements of `inner` are not powers of two, or if `N` is too small to hold
+    /// an aligned region of at least one length multiple.
+    pub fn new(inner: S) -> Self {
+        let alignment = inner.required_alignment();
+        let length_multiple = inner.required_length_multiple();
+
+        assert!(
+            alignment.is_power_of_two(),
+            "required alignment must be a power of two"
+        );
+        assert!(
+            length_multiple > 0,
+            "required length multiple must be non-zero"
+        );
+        assert!(
+            N >= alignment - 1 + length_multiple,
+            "bounce buffer is too small for the stream's requirements"
+        );
+
+        Self {
+            inner,
+            bounce_buffer: [0; N],
+        }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, const N: usize> ErrorType for BounceBufferedStream<S, N>
+where
+    S: ErrorType,
+{
+    type Error = S::Error;
+}
+
+/// Selects the largest region of `bounce_buffer` which starts at `alignment` and whose length is a
+/// multiple of `length_multiple`.
+fn aligned_region(bounce_buffer: &mut [u8], alignment: usize, length_multiple: usize) -> &mut [u8] {
+    let offset = min(
+        bounce_buffer.as_ptr().align_offset(alignment),
+        bounce_buffer.len(),
+    );
+    let length = (bounce_buffer.len() - offset) / length_multiple * length_multiple;
+
+    &mut bounce_buffer[offset..offset + length]
+}
+
+/// Determines how many bytes to request from the wrapped stream in order to fill `remaining` bytes
+/// of the caller's buffer.
+fn request_length(remaining: usize, length_multiple: usize, region_length: usize) -> usize {
+    min(
+        remaining.div_ceil(length_multiple) * length_multiple,
+        region_length,
+    )
+}
+
+#[cfg(feature = "sync")]
+impl<S, const N: usize> Read for BounceBufferedStream<S, N>
+where
+    S: BufferRequirements + Read + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if self.inner.is_satisfied_by(buf) {
+            return self.inner.read(buf);
+        }
+
+        let alignment = self.inner.required_alignment();
+        let length_multiple = self.inner.required_length_multiple();
+        let region = aligned_region(&mut self.bounce_buffer, alignment, length_multiple);
+
+        let mut total_read = 0;
+
+        while total_read < buf.len() {
+            let remaining = buf.len() - total_read;
+            let requested = request_length(remaining, length_multiple, region.len());
+
+            let bytes_read = self.inner.read(&mut region[..requested])?;
+            let bytes_used = min(bytes_read, remaining);
+
+            buf[total_read..total_read + bytes_used].copy_from_slice(&region[..bytes_used]);
+            total_read += bytes_used;
+
+            if bytes_read > bytes_used {
+                self.inner
+                    .seek(SeekFrom::Current(-((bytes_read - bytes_used) as i64)))?;
+            }
+
+            if bytes_read < requested {
+                break;
+            }
+        }
+
+        Ok(total_read)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S, const N: usize> AsyncRead for BounceBufferedStream<S, N>
+where
+    S: BufferRequirements + AsyncRead + AsyncSeek,
+{
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if self.inner.is_satisfied_by(buf) {
+            return self.inner.read(buf).await;
+        }
+
+        let alignment = self.inner.required_alignment();
+        let length_multiple = self.inner.required_length_multiple();
+        let region = aligned_region(&mut self.bounce_buffer, alignment, length_multiple);
+
+        let mut total_read = 0;
+
+        while total_read < buf.len() {
+            let remaining = buf.len() - total_read;
+            let requested = request_length(remaining, length_multiple, region.len());
+
+            let bytes_read = self.inner.read(&mut region[..requested]).await?;
+            let bytes_used = min(bytes_read, remaining);
+
+            buf[total_read..total_read + bytes_used].copy_from_slice(&region[..bytes_used]);
+            total_read += bytes_used;
+
+            if bytes_read > bytes_used {
+                self.inner
+                    .seek(SeekFrom::Current(-((bytes_read - bytes_used) as i64)))
+                    .await?;
+            }
+
+            if bytes_read < requested {
+                break;
+            }
+        }
+
+        Ok(total_read)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<S, const N: usize> Write for BounceBufferedStream<S, N>
+where
+    S: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S, const N: usize> AsyncWrite for BounceBufferedStream<S, N>
+where
+    S: AsyncWrite,
+{
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.inner.write(buf).await
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush().await
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<S, const N: usize> Seek for BounceBufferedStream<S, N>
+where
+    S: Seek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        self.inner.seek(pos)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S, const N: usize> AsyncSeek for BounceBufferedStream<S, N>
+where
+    S: AsyncSeek,
+{
+    async fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        self.inner.seek(pos).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{DataStream, IoError};
+
+    /// Wraps a [`DataStream`], panicking on any read whose buffer violates its requirements.
+    struct RequirementCheckingStream {
+        inner: DataStream<[u8; 16]>,
+        read_count: usize,
+    }
+
+    impl RequirementCheckingStream {
+        fn new() -> Self {
+            Self {
+                inner: DataStream::from_bytes(core::array::from_fn(|index| index as u8)),
+                read_count: 0,
+            }
+        }
+    }
+
+    impl BufferRequirements for RequirementCheckingStream {
+        fn required_alignment(&self) -> usize {
+            4
+        }
+
+        fn required_length_multiple(&self) -> usize {
+            4
+        }
+    }
+
+    impl ErrorType for RequirementCheckingStream {
+        type Error = IoError;
+    }
+
+    #[cfg(feature = "sync")]
+    impl Read for RequirementCheckingStream {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            assert!(self.is_satisfied_by(buf), "buffer requirements violated");
+
+            self.read_count += 1;
+
+            Read::read(&mut self.inner, buf)
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    impl Seek for RequirementCheckingStream {
+        fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+            Seek::seek(&mut self.inner, pos)
+        }
+    }
+
+    #[repr(align(4))]
+    struct AlignedBytes([u8; 12]);
+
+    mod new {
+        use super::*;
+
+        #[test]
+        #[should_panic]
+        fn undersized_bounce_buffer_panics() {
+            BounceBufferedStream::<_, 6>::new(RequirementCheckingStream::new());
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    mod read {
+        use super::*;
+
+        #[test]
+        fn satisfying_buffer_passed_through() {
+            let mut stream = BounceBufferedStream::<_, 8>::new(RequirementCheckingStream::new());
+            let mut buf = AlignedBytes([0; 12]);
+
+            assert_eq!(stream.read(&mut buf.0[..8]).unwrap(), 8);
+            assert_eq!(buf.0[..8], [0, 1, 2, 3, 4, 5, 6, 7]);
+            assert_eq!(stream.into_inner().read_count, 1);
+        }
+
+        #[test]
+        fn unaligned_buffer_bounced() {
+            let mut stream = BounceBufferedStream::<_, 8>::new(RequirementCheckingStream::new());
+            let mut buf = AlignedBytes([0; 12]);
+
+            assert_eq!(stream.read(&mut buf.0[1..9]).unwrap(), 8);
+            assert_eq!(buf.0[1..9], [0, 1, 2, 3, 4, 5, 6, 7]);
+        }
+
+        #[test]
+        fn surplus_bytes_rewound() {
+            let mut stream = BounceBufferedStream::<_, 8>::new(RequirementCheckingStream::new());
+            let mut buf = AlignedBytes([0; 12]);
+
+            assert_eq!(stream.read(&mut buf.0[..3]).unwrap(), 3);
+            assert_eq!(stream.seek(SeekFrom::Current(0)).unwrap(), 3);
+            assert_eq!(stream.read(&mut buf.0[..6]).unwrap(), 6);
+            assert_eq!(buf.0[..6], [3, 4, 5, 6, 7, 8]);
+        }
+
+        #[test]
+        fn end_of_stream_returns_short_count() {
+            let mut stream = BounceBufferedStream::<_, 8>::new(RequirementCheckingStream::new());
+            let mut buf = AlignedBytes([0; 12]);
+
+            stream.seek(SeekFrom::Start(12)).unwrap();
+
+            assert_eq!(stream.read(&mut buf.0[1..11]).unwrap(), 4);
+            assert_eq!(buf.0[1..5], [12, 13, 14, 15]);
+        }
+    }
+}
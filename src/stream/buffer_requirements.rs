@@ -0,0 +1,22 @@
+/// Advertises the constraints a stream places on the buffers handed to it.
+///
+/// Implemented by streams backed by DMA-capable drivers, typically in combination with
+/// [`BounceBufferedStream`](crate::BounceBufferedStream) which satisfies the requirements on behalf
+/// of callers whose buffers do not.
+pub trait BufferRequirements {
+    /// Alignment, in bytes, required of a buffer's start address.
+    fn required_alignment(&self) -> usize {
+        1
+    }
+
+    /// Granularity, in bytes, required of a buffer's length.
+    fn required_length_multiple(&self) -> usize {
+        1
+    }
+
+    /// Determines whether `buffer` can be passed to the stream as-is.
+    fn is_satisfied_by(&self, buffer: &[u8]) -> bool {
+        buffer.as_ptr().align_offset(self.required_alignment()) == 0
+            && buffer.len().is_multiple_of(self.required_length_multiple())
+    }
+}
@@ -0,0 +1,144 @@
+use crate::SingleAccessDevice;
+use core::fmt::{Debug, Formatter};
+use embedded_io::{Error, ErrorKind, ErrorType, Read, Seek, SeekFrom, Write};
+
+/// A device over a [`DynStream`], shared by every storage backend a product mounts.
+pub type DynDevice<'a> = SingleAccessDevice<DynStream<'a>>;
+
+/// A stream adapter which dispatches to the wrapped stream through a trait object.
+///
+/// Every `FileSystem` mounted through a `DynStream` has the same type regardless of the backend
+/// underneath, so the file system code is only instantiated once no matter how many kinds of
+/// storage are used.  Errors are reduced to their [`ErrorKind`] in exchange.
+pub struct DynStream<'a> {
+    inner: &'a mut dyn ErasedStream,
+}
+
+impl<'a> DynStream<'a> {
+    pub fn new<S>(inner: &'a mut S) -> Self
+    where
+        S: Read + Write + Seek,
+    {
+        Self { inner }
+    }
+}
+
+impl Debug for DynStream<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DynStream").finish_non_exhaustive()
+    }
+}
+
+impl ErrorType for DynStream<'_> {
+    type Error = ErrorKind;
+}
+
+impl Read for DynStream<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.inner.read(buf)
+    }
+}
+
+impl Write for DynStream<'_> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+impl Seek for DynStream<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        self.inner.seek(pos)
+    }
+}
+
+/// The object-safe subset of a readable, writable and seekable stream.
+trait ErasedStream {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, ErrorKind>;
+
+    fn write(&mut self, buf: &[u8]) -> Result<usize, ErrorKind>;
+
+    fn flush(&mut self) -> Result<(), ErrorKind>;
+
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, ErrorKind>;
+}
+
+impl<S> ErasedStream for S
+where
+    S: Read + Write + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, ErrorKind> {
+        Read::read(self, buf).map_err(|e| e.kind())
+    }
+
+    fn write(&mut self, buf: &[u8]) -> Result<usize, ErrorKind> {
+        Write::write(self, buf).map_err(|e| e.kind())
+    }
+
+    fn flush(&mut self) -> Result<(), ErrorKind> {
+        Write::flush(self).map_err(|e| e.kind())
+    }
+
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, ErrorKind> {
+        Seek::seek(self, pos).map_err(|e| e.kind())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{DataStream, ErroringStream, ErroringStreamScenarios, IoError, VoidStream};
+
+    mod read {
+        use super::*;
+
+        #[test]
+        fn wrapped_stream_read() {
+            let mut inner = DataStream::from_bytes([1, 2, 3, 4]);
+            let mut stream = DynStream::new(&mut inner);
+            let mut buf = [0; 2];
+
+            Seek::seek(&mut stream, SeekFrom::Start(1)).unwrap();
+            Read::read_exact(&mut stream, &mut buf).unwrap();
+
+            assert_eq!(buf, [2, 3]);
+        }
+
+        #[test]
+        fn error_kind_propagated() {
+            let mut inner = ErroringStream::new(
+                VoidStream::new(),
+                IoError(ErrorKind::TimedOut),
+                ErroringStreamScenarios::READ,
+            );
+            let mut stream = DynStream::new(&mut inner);
+
+            assert_eq!(
+                Read::read(&mut stream, &mut [0; 4]),
+                Err(ErrorKind::TimedOut)
+            );
+        }
+    }
+
+    mod write {
+        use super::*;
+
+        #[test]
+        fn wrapped_stream_written() {
+            let mut inner = DataStream::from_bytes([0; 4]);
+            let mut stream = DynStream::new(&mut inner);
+
+            Seek::seek(&mut stream, SeekFrom::Start(2)).unwrap();
+            Write::write_all(&mut stream, &[5, 6]).unwrap();
+            Seek::seek(&mut stream, SeekFrom::Start(0)).unwrap();
+
+            let mut buf = [0; 4];
+            Read::read_exact(&mut stream, &mut buf).unwrap();
+
+            assert_eq!(buf, [0, 0, 5, 6]);
+        }
+    }
+}
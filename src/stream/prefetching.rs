@@ -0,0 +1,459 @@
+use core::cmp::min;
+use core::ops::Range;
+use embedded_io::{ErrorType, SeekFrom};
+
+#[cfg(feature = "sync")]
+use embedded_io::{Read, Seek, Write};
+
+#[cfg(feature = "async")]
+use embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek, Write as AsyncWrite};
+
+/// A stream adapter which holds a prefetched region of the wrapped stream of up to `N` bytes in
+/// memory, so reads within it never reach the wrapped stream.
+///
+/// Prefetching a volume's allocation table through
+/// [`FileSystem::prefetch_allocation_table`](crate::FileSystem::prefetch_allocation_table) turns
+/// cluster chain walks into memory accesses, which suits small volumes on slow media such as
+/// SPI NOR flash.  Writes are passed through and applied to the held region as well, seeks are
+/// only forwarded once the wrapped stream is accessed again.
+///
+/// The wrapped stream is expected to start at position zero.
+#[derive(Clone, Debug)]
+pub struct PrefetchingStream<S, const N: usize> {
+    inner: S,
+    position: u64,
+    is_inner_positioned: bool,
+
+    region: [u8; N],
+    region_start: u64,
+    region_length: usize,
+}
+
+impl<S, const N: usize> PrefetchingStream<S, N> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            position: 0,
+            is_inner_positioned: true,
+
+            region: [0; N],
+            region_start: 0,
+            region_length: 0,
+        }
+    }
+
+    /// The addresses of the bytes currently held in memory.
+    pub fn prefetched_range(&self) -> Range<u64> {
+        self.region_start..self.region_start + self.region_length as u64
+    }
+
+    /// Drops the held region, passing every read through to the wrapped stream again.
+    pub fn clear(&mut self) {
+        self.region_length = 0;
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// The held bytes from the current position onwards, empty if the position is outside of the
+    /// held region.
+    fn prefetched_bytes(&self) -> &[u8] {
+        match self.position.checked_sub(self.region_start) {
+            Some(offset) if offset < self.region_length as u64 => {
+                &self.region[offset as usize..self.region_length]
+            }
+            _ => &[],
+        }
+    }
+
+    /// Applies bytes written at the current position to the held region.
+    fn update_region(&mut self, buf: &[u8]) {
+        let write_range = self.position..self.position + buf.len() as u64;
+        let overlap_start = write_range.start.max(self.region_start);
+        let overlap_end = write_range.end.min(self.prefetched_range().end);
+
+        if overlap_start >= overlap_end {
+            return;
+        }
+
+        let region_offset = (overlap_start - self.region_start) as usize;
+        let buf_offset = (overlap_start - write_range.start) as usize;
+        let length = (overlap_end - overlap_start) as usize;
+
+        self.region[region_offset..region_offset + length]
+            .copy_from_slice(&buf[buf_offset..buf_offset + length]);
+    }
+
+    /// Copies held bytes from the current position into the buffer, returning how many were held.
+    fn read_prefetched(&mut self, buf: &mut [u8]) -> usize {
+        let prefetched_bytes = self.prefetched_bytes();
+        let length = min(prefetched_bytes.len(), buf.len());
+
+        buf[..length].copy_from_slice(&prefetched_bytes[..length]);
+        self.position += length as u64;
+
+        // Reads from memory leave the wrapped stream behind
+        if length > 0 {
+            self.is_inner_positioned = false;
+        }
+
+        length
+    }
+
+    /// The position after the seek, `None` if it can only be determined by the wrapped stream.
+    fn seek_position(&self, pos: SeekFrom) -> Option<u64> {
+        match pos {
+            SeekFrom::Start(position) => Some(position),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(_) => None,
+        }
+    }
+}
+
+impl<S, const N: usize> ErrorType for PrefetchingStream<S, N>
+where
+    S: ErrorType,
+{
+    type Error = S::Error;
+}
+
+#[cfg(feature = "sync")]
+impl<S, const N: usize> PrefetchingStream<S, N>
+where
+    S: Read + Seek,
+{
+    /// Reads as much of the given region as fits into memory, replacing any region held before,
+    /// and returns how many bytes are held.
+    pub fn prefetch(&mut self, start: u64, length: u64) -> Result<usize, S::Error> {
+        let region_length = min(length, N as u64) as usize;
+
+        self.region_length = 0;
+        self.inner.seek(SeekFrom::Start(start))?;
+        self.is_inner_positioned = false;
+
+        let mut total_read = 0;
+        while total_read < region_length {
+            let bytes_read = self
+                .inner
+                .read(&mut self.region[total_read..region_length])?;
+            if bytes_read == 0 {
+                break;
+            }
+
+            total_read += bytes_read;
+        }
+
+        self.region_start = start;
+        self.region_length = total_read;
+
+        Ok(total_read)
+    }
+
+    fn position_inner(&mut self) -> Result<(), S::Error> {
+        if !self.is_inner_positioned {
+            self.inner.seek(SeekFrom::Start(self.position))?;
+            self.is_inner_positioned = true;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<S, const N: usize> Read for PrefetchingStream<S, N>
+where
+    S: Read + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let prefetched_length = self.read_prefetched(buf);
+        if prefetched_length > 0 || buf.is_empty() {
+            return Ok(prefetched_length);
+        }
+
+        self.position_inner()?;
+
+        let bytes_read = self.inner.read(buf)?;
+        self.position += bytes_read as u64;
+
+        Ok(bytes_read)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<S, const N: usize> Write for PrefetchingStream<S, N>
+where
+    S: Read + Write + Seek,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.position_inner()?;
+
+        let bytes_written = self.inner.write(buf)?;
+        self.update_region(&buf[..bytes_written]);
+        self.position += bytes_written as u64;
+
+        Ok(bytes_written)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<S, const N: usize> Seek for PrefetchingStream<S, N>
+where
+    S: Read + Seek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        if let Some(position) = self.seek_position(pos) {
+            self.position = position;
+            self.is_inner_positioned = false;
+
+            return Ok(position);
+        }
+
+        self.position_inner()?;
+        self.position = self.inner.seek(pos)?;
+
+        Ok(self.position)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S, const N: usize> PrefetchingStream<S, N>
+where
+    S: AsyncRead + AsyncSeek,
+{
+    /// Reads as much of the given region as fits into memory, replacing any region held before,
+    /// and returns how many bytes are held.
+    pub async fn prefetch_async(&mut self, start: u64, length: u64) -> Result<usize, S::Error> {
+        let region_length = min(length, N as u64) as usize;
+
+        self.region_length = 0;
+        self.inner.seek(SeekFrom::Start(start)).await?;
+        self.is_inner_positioned = false;
+
+        let mut total_read = 0;
+        while total_read < region_length {
+            let bytes_read = self
+                .inner
+                .read(&mut self.region[total_read..region_length])
+                .await?;
+            if bytes_read == 0 {
+                break;
+            }
+
+            total_read += bytes_read;
+        }
+
+        self.region_start = start;
+        self.region_length = total_read;
+
+        Ok(total_read)
+    }
+
+    async fn position_inner_async(&mut self) -> Result<(), S::Error> {
+        if !self.is_inner_positioned {
+            self.inner.seek(SeekFrom::Start(self.position)).await?;
+            self.is_inner_positioned = true;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S, const N: usize> AsyncRead for PrefetchingStream<S, N>
+where
+    S: AsyncRead + AsyncSeek,
+{
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let prefetched_length = self.read_prefetched(buf);
+        if prefetched_length > 0 || buf.is_empty() {
+            return Ok(prefetched_length);
+        }
+
+        self.position_inner_async().await?;
+
+        let bytes_read = self.inner.read(buf).await?;
+        self.position += bytes_read as u64;
+
+        Ok(bytes_read)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S, const N: usize> AsyncWrite for PrefetchingStream<S, N>
+where
+    S: AsyncRead + AsyncWrite + AsyncSeek,
+{
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.position_inner_async().await?;
+
+        let bytes_written = self.inner.write(buf).await?;
+        self.update_region(&buf[..bytes_written]);
+        self.position += bytes_written as u64;
+
+        Ok(bytes_written)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush().await
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S, const N: usize> AsyncSeek for PrefetchingStream<S, N>
+where
+    S: AsyncRead + AsyncSeek,
+{
+    async fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        if let Some(position) = self.seek_position(pos) {
+            self.position = position;
+            self.is_inner_positioned = false;
+
+            return Ok(position);
+        }
+
+        self.position_inner_async().await?;
+        self.position = self.inner.seek(pos).await?;
+
+        Ok(self.position)
+    }
+}
+
+#[cfg(all(test, feature = "sync"))]
+mod tests {
+    use super::PrefetchingStream;
+    use crate::mock::{DataStream, ErroringStream, ErroringStreamScenarios, IoError};
+    use embedded_io::{ErrorKind, Read, Seek, SeekFrom, Write};
+
+    fn stream() -> PrefetchingStream<DataStream<[u8; 16]>, 4> {
+        PrefetchingStream::new(DataStream::from_bytes(core::array::from_fn(|index| {
+            index as u8
+        })))
+    }
+
+    mod prefetch {
+        use super::*;
+
+        #[test]
+        fn region_bounded_by_capacity() {
+            let mut stream = stream();
+
+            assert_eq!(stream.prefetch(2, 8).expect("Ok should be returned"), 4);
+            assert_eq!(stream.prefetched_range(), 2..6);
+        }
+
+        #[test]
+        fn region_bounded_by_stream_end() {
+            let mut stream = stream();
+
+            assert_eq!(stream.prefetch(14, 4).expect("Ok should be returned"), 2);
+            assert_eq!(stream.prefetched_range(), 14..16);
+        }
+    }
+
+    mod read {
+        use super::*;
+
+        #[test]
+        fn prefetched_bytes_read_from_memory() {
+            let mut stream = PrefetchingStream::<_, 4>::new(ErroringStream::new(
+                DataStream::from_bytes([1, 2, 3, 4, 5, 6]),
+                IoError(ErrorKind::TimedOut),
+                ErroringStreamScenarios::empty(),
+            ));
+            stream.prefetch(1, 4).expect("Ok should be returned");
+
+            // Any further access to the wrapped stream fails
+            stream.inner = ErroringStream::new(
+                DataStream::from_bytes([0; 6]),
+                IoError(ErrorKind::TimedOut),
+                ErroringStreamScenarios::READ | ErroringStreamScenarios::SEEK,
+            );
+
+            let mut buf = [0; 3];
+            stream
+                .seek(SeekFrom::Start(2))
+                .expect("Ok should be returned");
+            stream.read_exact(&mut buf).expect("Ok should be returned");
+
+            assert_eq!(buf, [3, 4, 5]);
+        }
+
+        #[test]
+        fn reads_beyond_region_passed_through() {
+            let mut stream = stream();
+            stream.prefetch(2, 4).expect("Ok should be returned");
+
+            let mut buf = [0; 6];
+            stream
+                .seek(SeekFrom::Start(4))
+                .expect("Ok should be returned");
+            stream.read_exact(&mut buf).expect("Ok should be returned");
+
+            assert_eq!(buf, [4, 5, 6, 7, 8, 9]);
+            assert_eq!(stream.stream_position().expect("Ok should be returned"), 10);
+        }
+    }
+
+    mod write {
+        use super::*;
+
+        #[test]
+        fn overlapping_bytes_applied_to_region() {
+            let mut stream = stream();
+            stream.prefetch(2, 4).expect("Ok should be returned");
+
+            stream
+                .seek(SeekFrom::Start(4))
+                .expect("Ok should be returned");
+            stream
+                .write_all(&[0xAA, 0xBB, 0xCC])
+                .expect("Ok should be returned");
+
+            let mut buf = [0; 4];
+            stream
+                .seek(SeekFrom::Start(2))
+                .expect("Ok should be returned");
+            stream.read_exact(&mut buf).expect("Ok should be returned");
+            assert_eq!(buf, [2, 3, 0xAA, 0xBB]);
+
+            let mut inner = stream.into_inner();
+            let mut buf = [0; 4];
+            inner
+                .seek(SeekFrom::Start(3))
+                .expect("Ok should be returned");
+            inner.read_exact(&mut buf).expect("Ok should be returned");
+            assert_eq!(
+                buf,
+                [3, 0xAA, 0xBB, 0xCC],
+                "Writes reach the wrapped stream"
+            );
+        }
+    }
+
+    mod seek {
+        use super::*;
+
+        #[test]
+        fn end_relative_seek_forwarded() {
+            let mut stream = stream();
+
+            assert_eq!(
+                stream
+                    .seek(SeekFrom::End(-2))
+                    .expect("Ok should be returned"),
+                14
+            );
+            assert_eq!(
+                stream
+                    .seek(SeekFrom::Current(-4))
+                    .expect("Ok should be returned"),
+                10
+            );
+        }
+    }
+}
@@ -0,0 +1,428 @@
+mod backoff;
+mod metrics;
+
+pub use backoff::*;
+pub use metrics::*;
+
+use embedded_io::{ErrorType, SeekFrom};
+
+#[cfg(feature = "sync")]
+use embedded_io::{Read, Seek, Write};
+
+#[cfg(feature = "async")]
+use embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek, Write as AsyncWrite};
+
+/// A stream adapter which retries failed operations before propagating their error.
+///
+/// Each read, write, seek or flush is attempted up to `max_retries + 1` times, waiting on the
+/// configured [`Backoff`] between attempts. Drivers which may have partially transferred data
+/// before failing should enable re-seeking, which restores the position the operation started from
+/// before every retry. The wrapped stream is assumed to be positioned at the start of the volume.
+#[derive(Clone, Debug)]
+pub struct RetryingStream<S, B = NoBackoff> {
+    inner: S,
+    max_retries: u32,
+    backoff: B,
+    reseek: bool,
+    position: u64,
+    metrics: RetryMetrics,
+}
+
+impl<S> RetryingStream<S> {
+    /// Wraps `inner`, retrying each failed operation up to `max_retries` times without waiting.
+    pub fn new(inner: S, max_retries: u32) -> Self {
+        Self {
+            inner,
+            max_retries,
+            backoff: NoBackoff,
+            reseek: false,
+            position: 0,
+            metrics: RetryMetrics::default(),
+        }
+    }
+}
+
+impl<S, B> RetryingStream<S, B> {
+    /// Waits on `backoff` before each retry.
+    pub fn with_backoff<B2>(self, backoff: B2) -> RetryingStream<S, B2> {
+        RetryingStream {
+            inner: self.inner,
+            max_retries: self.max_retries,
+            backoff,
+            reseek: self.reseek,
+            position: self.position,
+            metrics: self.metrics,
+        }
+    }
+
+    /// Controls whether the stream is seeked back to where the operation started before each retry.
+    pub fn with_reseek(mut self, reseek: bool) -> Self {
+        self.reseek = reseek;
+        self
+    }
+
+    pub fn metrics(&self) -> RetryMetrics {
+        self.metrics
+    }
+
+    pub fn reset_metrics(&mut self) {
+        self.metrics = RetryMetrics::default();
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn record_outcome(&mut self, succeeded: bool, attempts: u32) {
+        if !succeeded {
+            self.metrics.failed_operations += 1;
+        } else if attempts > 0 {
+            self.metrics.recovered_operations += 1;
+        }
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<S, B> RetryingStream<S, B>
+where
+    S: Seek,
+    B: Backoff,
+{
+    fn retry<T, F>(&mut self, mut operation: F) -> Result<T, S::Error>
+    where
+        F: FnMut(&mut S) -> Result<T, S::Error>,
+    {
+        let mut result = operation(&mut self.inner);
+        let mut attempt = 0;
+
+        while result.is_err() && attempt < self.max_retries {
+            attempt += 1;
+            self.metrics.retries += 1;
+            self.backoff.wait(attempt);
+
+            result = match self.reseek {
+                true => match self.inner.seek(SeekFrom::Start(self.position)) {
+                    Ok(_) => operation(&mut self.inner),
+                    Err(e) => Err(e),
+                },
+                false => operation(&mut self.inner),
+            };
+        }
+
+        self.record_outcome(result.is_ok(), attempt);
+
+        result
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S, B> RetryingStream<S, B>
+where
+    S: AsyncSeek,
+    B: AsyncBackoff,
+{
+    async fn retry_async<T, F>(&mut self, mut operation: F) -> Result<T, S::Error>
+    where
+        F: AsyncFnMut(&mut S) -> Result<T, S::Error>,
+    {
+        let mut result = operation(&mut self.inner).await;
+        let mut attempt = 0;
+
+        while result.is_err() && attempt < self.max_retries {
+            attempt += 1;
+            self.metrics.retries += 1;
+            self.backoff.wait(attempt).await;
+
+            result = match self.reseek {
+                true => match self.inner.seek(SeekFrom::Start(self.position)).await {
+                    Ok(_) => operation(&mut self.inner).await,
+                    Err(e) => Err(e),
+                },
+                false => operation(&mut self.inner).await,
+            };
+        }
+
+        self.record_outcome(result.is_ok(), attempt);
+
+        result
+    }
+}
+
+impl<S, B> ErrorType for RetryingStream<S, B>
+where
+    S: ErrorType,
+{
+    type Error = S::Error;
+}
+
+#[cfg(feature = "sync")]
+impl<S, B> Read for RetryingStream<S, B>
+where
+    S: Read + Seek,
+    B: Backoff,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let bytes_read = self.retry(|inner| inner.read(buf))?;
+        self.position += bytes_read as u64;
+
+        Ok(bytes_read)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S, B> AsyncRead for RetryingStream<S, B>
+where
+    S: AsyncRead + AsyncSeek,
+    B: AsyncBackoff,
+{
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let bytes_read = self
+            .retry_async(async |inner| inner.read(buf).await)
+            .await?;
+        self.position += bytes_read as u64;
+
+        Ok(bytes_read)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<S, B> Write for RetryingStream<S, B>
+where
+    S: Write + Seek,
+    B: Backoff,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let bytes_written = self.retry(|inner| inner.write(buf))?;
+        self.position += bytes_written as u64;
+
+        Ok(bytes_written)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.retry(|inner| inner.flush())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S, B> AsyncWrite for RetryingStream<S, B>
+where
+    S: AsyncWrite + AsyncSeek,
+    B: AsyncBackoff,
+{
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let bytes_written = self
+            .retry_async(async |inner| inner.write(buf).await)
+            .await?;
+        self.position += bytes_written as u64;
+
+        Ok(bytes_written)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.retry_async(async |inner| inner.flush().await).await
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<S, B> Seek for RetryingStream<S, B>
+where
+    S: Seek,
+    B: Backoff,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        self.position = self.retry(|inner| inner.seek(pos))?;
+
+        Ok(self.position)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S, B> AsyncSeek for RetryingStream<S, B>
+where
+    S: AsyncSeek,
+    B: AsyncBackoff,
+{
+    async fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        self.position = self
+            .retry_async(async |inner| inner.seek(pos).await)
+            .await?;
+
+        Ok(self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::IoError;
+
+    /// Reads from a fixed set of bytes, failing the next `failures` reads after consuming one byte
+    /// each.
+    struct FlakyStream {
+        bytes: [u8; 8],
+        position: usize,
+        failures: u32,
+    }
+
+    impl FlakyStream {
+        fn new(failures: u32) -> Self {
+            Self {
+                bytes: [0, 1, 2, 3, 4, 5, 6, 7],
+                position: 0,
+                failures,
+            }
+        }
+
+        fn read_internal(&mut self, buf: &mut [u8]) -> Result<usize, IoError> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                self.position = (self.position + 1).min(self.bytes.len());
+
+                return Err(IoError::default());
+            }
+
+            let remaining = &self.bytes[self.position..];
+            let read_size = remaining.len().min(buf.len());
+
+            buf[..read_size].copy_from_slice(&remaining[..read_size]);
+            self.position += read_size;
+
+            Ok(read_size)
+        }
+
+        fn seek_internal(&mut self, pos: SeekFrom) -> Result<u64, IoError> {
+            let position = match pos {
+                SeekFrom::Start(offset) => offset as i64,
+                SeekFrom::Current(offset) => self.position as i64 + offset,
+                SeekFrom::End(offset) => self.bytes.len() as i64 + offset,
+            };
+
+            self.position = usize::try_from(position).map_err(|_| IoError::default())?;
+
+            Ok(self.position as u64)
+        }
+    }
+
+    impl ErrorType for FlakyStream {
+        type Error = IoError;
+    }
+
+    #[cfg(feature = "sync")]
+    impl Read for FlakyStream {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            self.read_internal(buf)
+        }
+    }
+
+    #[cfg(feature = "async")]
+    impl AsyncRead for FlakyStream {
+        async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            self.read_internal(buf)
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    impl Seek for FlakyStream {
+        fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+            self.seek_internal(pos)
+        }
+    }
+
+    #[cfg(feature = "async")]
+    impl AsyncSeek for FlakyStream {
+        async fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+            self.seek_internal(pos)
+        }
+    }
+
+    mod read {
+        use super::*;
+
+        #[test]
+        #[cfg(feature = "sync")]
+        fn transient_failure_recovered() {
+            let mut stream = RetryingStream::new(FlakyStream::new(2), 2).with_reseek(true);
+            let mut buf = [0; 2];
+
+            assert_eq!(Read::read(&mut stream, &mut buf).unwrap(), 2);
+            assert_eq!(buf, [0, 1]);
+            assert_eq!(
+                stream.metrics(),
+                RetryMetrics {
+                    retries: 2,
+                    recovered_operations: 1,
+                    failed_operations: 0,
+                }
+            );
+        }
+
+        #[test]
+        #[cfg(feature = "sync")]
+        fn persistent_failure_propagated() {
+            let mut stream = RetryingStream::new(FlakyStream::new(3), 2);
+            let mut buf = [0; 2];
+
+            assert!(Read::read(&mut stream, &mut buf).is_err());
+            assert_eq!(
+                stream.metrics(),
+                RetryMetrics {
+                    retries: 2,
+                    recovered_operations: 0,
+                    failed_operations: 1,
+                }
+            );
+        }
+
+        #[test]
+        #[cfg(feature = "sync")]
+        fn without_reseek_position_not_restored() {
+            let mut stream = RetryingStream::new(FlakyStream::new(1), 1);
+            let mut buf = [0; 2];
+
+            assert_eq!(Read::read(&mut stream, &mut buf).unwrap(), 2);
+            assert_eq!(buf, [1, 2]);
+        }
+
+        #[test]
+        #[cfg(feature = "sync")]
+        fn backoff_waited_before_each_retry() {
+            let mut attempts = [0; 2];
+            let mut waits = 0;
+            let mut stream =
+                RetryingStream::new(FlakyStream::new(2), 2).with_backoff(|attempt: u32| {
+                    attempts[waits] = attempt;
+                    waits += 1;
+                });
+
+            Read::read(&mut stream, &mut [0; 2]).unwrap();
+
+            assert_eq!(attempts, [1, 2]);
+        }
+
+        #[tokio::test]
+        #[cfg(feature = "async")]
+        async fn async_transient_failure_recovered() {
+            let mut stream = RetryingStream::new(FlakyStream::new(1), 1).with_reseek(true);
+            let mut buf = [0; 2];
+
+            assert_eq!(AsyncRead::read(&mut stream, &mut buf).await.unwrap(), 2);
+            assert_eq!(buf, [0, 1]);
+            assert_eq!(stream.metrics().recovered_operations, 1);
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    mod reset_metrics {
+        use super::*;
+
+        #[test]
+        fn counters_cleared() {
+            let mut stream = RetryingStream::new(FlakyStream::new(1), 1);
+
+            Read::read(&mut stream, &mut [0; 2]).unwrap();
+            stream.reset_metrics();
+
+            assert_eq!(stream.metrics(), RetryMetrics::default());
+        }
+    }
+}
@@ -0,0 +1,34 @@
+/// Waits between attempts of a failed operation.
+pub trait Backoff {
+    /// Waits before retry number `attempt`, starting from 1.
+    fn wait(&mut self, attempt: u32);
+}
+
+/// Asynchronously waits between attempts of a failed operation.
+#[cfg(feature = "async")]
+pub trait AsyncBackoff {
+    /// Waits before retry number `attempt`, starting from 1.
+    fn wait(&mut self, attempt: u32) -> impl Future<Output = ()>;
+}
+
+/// Retries immediately.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoBackoff;
+
+impl Backoff for NoBackoff {
+    fn wait(&mut self, attempt: u32) {}
+}
+
+#[cfg(feature = "async")]
+impl AsyncBackoff for NoBackoff {
+    async fn wait(&mut self, attempt: u32) {}
+}
+
+impl<F> Backoff for F
+where
+    F: FnMut(u32),
+{
+    fn wait(&mut self, attempt: u32) {
+        self(attempt)
+    }
+}
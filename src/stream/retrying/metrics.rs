@@ -0,0 +1,12 @@
+/// Counters describing how often a [`RetryingStream`](crate::RetryingStream) has had to retry.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct RetryMetrics {
+    /// Total number of retries performed
+    pub retries: u32,
+
+    /// Operations which failed at least once before succeeding
+    pub recovered_operations: u32,
+
+    /// Operations which still failed once all retries were exhausted
+    pub failed_operations: u32,
+}
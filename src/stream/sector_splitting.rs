@@ -0,0 +1,336 @@
+use core::cmp::min;
+use embedded_io::{ErrorType, SeekFrom};
+
+#[cfg(feature = "sync")]
+use embedded_io::{Read, Seek, Write};
+
+#[cfg(feature = "async")]
+use embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek, Write as AsyncWrite};
+
+/// A stream adapter which never issues a read or write that crosses a sector boundary.
+///
+/// Transfers spanning multiple sectors are split into one call per sector and reassembled, allowing
+/// block drivers that can only service a single sector at a time to be used as a byte stream. The
+/// wrapped stream is assumed to be positioned at the start of the volume.
+#[derive(Clone, Debug)]
+pub struct SectorSplittingStream<S> {
+    inner: S,
+    sector_size: u32,
+    position: u64,
+}
+
+impl<S> SectorSplittingStream<S> {
+    /// Wraps `inner`, splitting transfers at every multiple of `sector_size`.
+    ///
+    /// # Panics
+    /// Panics if `sector_size` is zero.
+    pub fn new(inner: S, sector_size: u32) -> Self {
+        assert!(sector_size > 0, "sector size must be non-zero");
+
+        Self {
+            inner,
+            sector_size,
+            position: 0,
+        }
+    }
+
+    pub fn sector_size(&self) -> u32 {
+        self.sector_size
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn chunk_length(&self, remaining: usize) -> usize {
+        let sector_remaining = self.sector_size as u64 - self.position % self.sector_size as u64;
+
+        min(sector_remaining, remaining as u64) as usize
+    }
+}
+
+impl<S> ErrorType for SectorSplittingStream<S>
+where
+    S: ErrorType,
+{
+    type Error = S::Error;
+}
+
+#[cfg(feature = "sync")]
+impl<S> Read for SectorSplittingStream<S>
+where
+    S: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let mut total_read = 0;
+
+        while total_read < buf.len() {
+            let chunk_length = self.chunk_length(buf.len() - total_read);
+            let bytes_read = self
+                .inner
+                .read(&mut buf[total_read..total_read + chunk_length])?;
+
+            total_read += bytes_read;
+            self.position += bytes_read as u64;
+
+            if bytes_read < chunk_length {
+                break;
+            }
+        }
+
+        Ok(total_read)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S> AsyncRead for SectorSplittingStream<S>
+where
+    S: AsyncRead,
+{
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let mut total_read = 0;
+
+        while total_read < buf.len() {
+            let chunk_length = self.chunk_length(buf.len() - total_read);
+            let bytes_read = self
+                .inner
+                .read(&mut buf[total_read..total_read + chunk_length])
+                .await?;
+
+            total_read += bytes_read;
+            self.position += bytes_read as u64;
+
+            if bytes_read < chunk_length {
+                break;
+            }
+        }
+
+        Ok(total_read)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<S> Write for SectorSplittingStream<S>
+where
+    S: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let mut total_written = 0;
+
+        while total_written < buf.len() {
+            let chunk_length = self.chunk_length(buf.len() - total_written);
+            let bytes_written = self
+                .inner
+                .write(&buf[total_written..total_written + chunk_length])?;
+
+            total_written += bytes_written;
+            self.position += bytes_written as u64;
+
+            if bytes_written < chunk_length {
+                break;
+            }
+        }
+
+        Ok(total_written)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S> AsyncWrite for SectorSplittingStream<S>
+where
+    S: AsyncWrite,
+{
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let mut total_written = 0;
+
+        while total_written < buf.len() {
+            let chunk_length = self.chunk_length(buf.len() - total_written);
+            let bytes_written = self
+                .inner
+                .write(&buf[total_written..total_written + chunk_length])
+                .await?;
+
+            total_written += bytes_written;
+            self.position += bytes_written as u64;
+
+            if bytes_written < chunk_length {
+                break;
+            }
+        }
+
+        Ok(total_written)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush().await
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<S> Seek for SectorSplittingStream<S>
+where
+    S: Seek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        self.position = self.inner.seek(pos)?;
+
+        Ok(self.position)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S> AsyncSeek for SectorSplittingStream<S>
+where
+    S: AsyncSeek,
+{
+    async fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        self.position = self.inner.seek(pos).await?;
+
+        Ok(self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{DataStream, IoError};
+
+    const SECTOR_SIZE: u32 = 4;
+
+    /// Wraps a [`DataStream`], panicking on any transfer that crosses a sector boundary.
+    struct SectorCheckingStream {
+        inner: DataStream<[u8; 16]>,
+        position: u64,
+    }
+
+    impl SectorCheckingStream {
+        fn new(bytes: [u8; 16]) -> Self {
+            Self {
+                inner: DataStream::from_bytes(bytes),
+                position: 0,
+            }
+        }
+
+        fn assert_within_sector(&self, length: usize) {
+            let first_sector = self.position / SECTOR_SIZE as u64;
+            let last_sector = (self.position + length as u64 - 1) / SECTOR_SIZE as u64;
+
+            assert_eq!(
+                first_sector, last_sector,
+                "transfer crossed a sector boundary"
+            );
+        }
+    }
+
+    impl ErrorType for SectorCheckingStream {
+        type Error = IoError;
+    }
+
+    #[cfg(feature = "sync")]
+    impl Read for SectorCheckingStream {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            self.assert_within_sector(buf.len());
+
+            let bytes_read = Read::read(&mut self.inner, buf)?;
+            self.position += bytes_read as u64;
+
+            Ok(bytes_read)
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    impl Write for SectorCheckingStream {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            self.assert_within_sector(buf.len());
+
+            let bytes_written = Write::write(&mut self.inner, buf)?;
+            self.position += bytes_written as u64;
+
+            Ok(bytes_written)
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Write::flush(&mut self.inner)
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    impl Seek for SectorCheckingStream {
+        fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+            self.position = Seek::seek(&mut self.inner, pos)?;
+
+            Ok(self.position)
+        }
+    }
+
+    fn sequential_bytes() -> [u8; 16] {
+        core::array::from_fn(|index| index as u8)
+    }
+
+    mod new {
+        use super::*;
+
+        #[test]
+        #[should_panic]
+        fn zero_sector_size_panics() {
+            SectorSplittingStream::new(SectorCheckingStream::new([0; 16]), 0);
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    mod read {
+        use super::*;
+
+        #[test]
+        fn transfer_spanning_sectors_split() {
+            let mut stream = SectorSplittingStream::new(
+                SectorCheckingStream::new(sequential_bytes()),
+                SECTOR_SIZE,
+            );
+            let mut buf = [0; 10];
+
+            stream.seek(SeekFrom::Start(3)).unwrap();
+
+            assert_eq!(stream.read(&mut buf).unwrap(), 10);
+            assert_eq!(buf, [3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+        }
+
+        #[test]
+        fn end_of_stream_returns_short_count() {
+            let mut stream = SectorSplittingStream::new(
+                SectorCheckingStream::new(sequential_bytes()),
+                SECTOR_SIZE,
+            );
+            let mut buf = [0; 8];
+
+            stream.seek(SeekFrom::Start(11)).unwrap();
+
+            assert_eq!(stream.read(&mut buf).unwrap(), 5);
+            assert_eq!(buf[..5], [11, 12, 13, 14, 15]);
+            assert_eq!(stream.read(&mut buf).unwrap(), 0);
+        }
+    }
+
+    #[cfg(feature = "sync")]
+    mod write {
+        use super::*;
+
+        #[test]
+        fn bytes_written_at_position() {
+            let mut stream =
+                SectorSplittingStream::new(SectorCheckingStream::new([0; 16]), SECTOR_SIZE);
+            let mut buf = [0; 16];
+
+            stream.seek(SeekFrom::Start(2)).unwrap();
+            stream.write(&[1, 2, 3, 4, 5, 6, 7]).unwrap();
+            stream.seek(SeekFrom::Start(0)).unwrap();
+
+            assert_eq!(stream.read(&mut buf).unwrap(), 16);
+            assert_eq!(buf, [0, 0, 1, 2, 3, 4, 5, 6, 7, 0, 0, 0, 0, 0, 0, 0]);
+        }
+    }
+}
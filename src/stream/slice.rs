@@ -0,0 +1,148 @@
+mod error;
+
+pub use error::*;
+
+use core::cmp::min;
+use embedded_io::{ErrorType, SeekFrom};
+
+#[cfg(feature = "sync")]
+use embedded_io::{Read, Seek};
+
+#[cfg(feature = "async")]
+use embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek};
+
+/// A read-only stream over an in-memory volume image.
+///
+/// Seeking beyond the end of the slice is allowed, reads from such a position return no bytes.
+#[derive(Clone, Debug)]
+pub struct SliceStream<'a> {
+    bytes: &'a [u8],
+    position: u64,
+}
+
+impl<'a> SliceStream<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    fn read_internal(&mut self, buf: &mut [u8]) -> usize {
+        let start = min(self.position, self.bytes.len() as u64) as usize;
+        let end = min(start + buf.len(), self.bytes.len());
+
+        let bytes_read = end - start;
+
+        buf[..bytes_read].copy_from_slice(&self.bytes[start..end]);
+        self.position += bytes_read as u64;
+
+        bytes_read
+    }
+
+    fn seek_internal(&mut self, pos: SeekFrom) -> Result<u64, SliceStreamError> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(value) => {
+                self.position = value;
+
+                return Ok(self.position);
+            }
+            SeekFrom::End(offset) => (self.bytes.len() as u64, offset),
+            SeekFrom::Current(offset) => (self.position, offset),
+        };
+
+        self.position =
+            base.checked_add_signed(offset)
+                .ok_or(SliceStreamError::SeekPositionImpossible(
+                    (base as i64).saturating_add(offset),
+                ))?;
+
+        Ok(self.position)
+    }
+}
+
+impl ErrorType for SliceStream<'_> {
+    type Error = SliceStreamError;
+}
+
+#[cfg(feature = "sync")]
+impl Read for SliceStream<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(self.read_internal(buf))
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncRead for SliceStream<'_> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(self.read_internal(buf))
+    }
+}
+
+#[cfg(feature = "sync")]
+impl Seek for SliceStream<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        self.seek_internal(pos)
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncSeek for SliceStream<'_> {
+    async fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        self.seek_internal(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod read_internal {
+        use super::*;
+
+        #[test]
+        fn bytes_returned_from_position() {
+            let mut stream = SliceStream::new(&[1, 2, 3, 4]);
+            let mut buf = [0; 3];
+
+            stream
+                .seek_internal(SeekFrom::Start(1))
+                .expect("Ok should be returned");
+
+            assert_eq!(stream.read_internal(&mut buf), 3);
+            assert_eq!(buf, [2, 3, 4]);
+            assert_eq!(stream.read_internal(&mut buf), 0);
+        }
+
+        #[test]
+        fn position_beyond_end_reads_nothing() {
+            let mut stream = SliceStream::new(&[1, 2, 3, 4]);
+            let mut buf = [0; 3];
+
+            stream
+                .seek_internal(SeekFrom::Start(10))
+                .expect("Ok should be returned");
+
+            assert_eq!(stream.read_internal(&mut buf), 0);
+        }
+    }
+
+    mod seek_internal {
+        use super::*;
+
+        #[test]
+        fn relative_positions_resolved() {
+            let mut stream = SliceStream::new(&[1, 2, 3, 4]);
+
+            assert_eq!(stream.seek_internal(SeekFrom::End(-1)), Ok(3));
+            assert_eq!(stream.seek_internal(SeekFrom::Current(-2)), Ok(1));
+        }
+
+        #[test]
+        fn position_before_start_returns_err() {
+            let mut stream = SliceStream::new(&[1, 2, 3, 4]);
+
+            assert_eq!(
+                stream.seek_internal(SeekFrom::End(-5)),
+                Err(SliceStreamError::SeekPositionImpossible(-1))
+            );
+        }
+    }
+}
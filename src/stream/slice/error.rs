@@ -0,0 +1,52 @@
+use core::fmt::{Display, Formatter};
+use embedded_io::ErrorKind;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SliceStreamError {
+    /// Seeking would move the position before the start of the slice
+    SeekPositionImpossible(i64),
+}
+
+impl core::error::Error for SliceStreamError {}
+
+impl Display for SliceStreamError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SliceStreamError::SeekPositionImpossible(position) => write!(
+                f,
+                "seeking would move the position to {} which is before the start of the slice",
+                position
+            ),
+        }
+    }
+}
+
+impl embedded_io::Error for SliceStreamError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            SliceStreamError::SeekPositionImpossible(_) => ErrorKind::InvalidInput,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn produces_non_empty_value() {
+            let values = [SliceStreamError::SeekPositionImpossible(-1)];
+
+            for value in values {
+                assert!(
+                    !value.to_string().is_empty(),
+                    "Display implementation should be non-empty"
+                );
+            }
+        }
+    }
+}
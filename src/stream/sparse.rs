@@ -0,0 +1,384 @@
+use embedded_io::{ErrorType, SeekFrom};
+
+#[cfg(feature = "sync")]
+use embedded_io::{Read, Seek, Write};
+
+#[cfg(feature = "async")]
+use embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek, Write as AsyncWrite};
+
+/// A stream adapter for building images on a host, which seeks past writes made up entirely of
+/// zeros instead of passing them to the wrapped stream.
+///
+/// Formatting or populating an image writes whole clusters and tables of zeros, wrapping the
+/// output file with this stream leaves those regions as holes so the file is sparse and written
+/// much faster.  Since skipped regions are never written, the wrapped stream must start out empty
+/// or zeroed.
+///
+/// A single zero byte is written at the end of the last skipped region when flushing, reading, or
+/// seeking relative to the end, so the wrapped stream's length covers everything written to it.
+/// The wrapped stream is expected to start at position zero.
+#[derive(Clone, Debug)]
+pub struct SparseStream<S> {
+    inner: S,
+    position: u64,
+    is_inner_positioned: bool,
+
+    written_end: u64,
+    skipped_end: u64,
+}
+
+impl<S> SparseStream<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            position: 0,
+            is_inner_positioned: true,
+
+            written_end: 0,
+            skipped_end: 0,
+        }
+    }
+
+    /// Returns the wrapped stream, which should be flushed through this stream first so skipped
+    /// regions at its end are accounted for in its length.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Records a write of zeros at the current position without passing it through.
+    fn skip(&mut self, length: usize) {
+        self.position += length as u64;
+        self.skipped_end = self.skipped_end.max(self.position);
+        self.is_inner_positioned = false;
+    }
+
+    /// Records bytes written through to the wrapped stream at the current position.
+    fn advance(&mut self, length: usize) {
+        self.position += length as u64;
+        self.written_end = self.written_end.max(self.position);
+    }
+
+    /// Whether skipped regions reach beyond everything written to the wrapped stream.
+    fn has_sparse_tail(&self) -> bool {
+        self.skipped_end > self.written_end
+    }
+
+    /// The position after the seek, `None` if it can only be determined by the wrapped stream.
+    fn seek_position(&self, pos: SeekFrom) -> Option<u64> {
+        match pos {
+            SeekFrom::Start(position) => Some(position),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(_) => None,
+        }
+    }
+}
+
+impl<S> ErrorType for SparseStream<S>
+where
+    S: ErrorType,
+{
+    type Error = S::Error;
+}
+
+#[cfg(feature = "sync")]
+impl<S> SparseStream<S>
+where
+    S: Write + Seek,
+{
+    fn position_inner(&mut self) -> Result<(), S::Error> {
+        if !self.is_inner_positioned {
+            self.inner.seek(SeekFrom::Start(self.position))?;
+            self.is_inner_positioned = true;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the last byte of skipped regions beyond the wrapped stream's written bytes, extending
+    /// its length over them.
+    fn fill_sparse_tail(&mut self) -> Result<(), S::Error> {
+        if !self.has_sparse_tail() {
+            return Ok(());
+        }
+
+        self.inner.seek(SeekFrom::Start(self.skipped_end - 1))?;
+        self.inner.write_all(&[0])?;
+        self.written_end = self.skipped_end;
+        self.is_inner_positioned = false;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<S> Read for SparseStream<S>
+where
+    S: Read + Write + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.fill_sparse_tail()?;
+        self.position_inner()?;
+
+        let bytes_read = self.inner.read(buf)?;
+        self.position += bytes_read as u64;
+
+        Ok(bytes_read)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<S> Write for SparseStream<S>
+where
+    S: Write + Seek,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.iter().all(|&byte| byte == 0) {
+            self.skip(buf.len());
+
+            return Ok(buf.len());
+        }
+
+        self.position_inner()?;
+
+        let bytes_written = self.inner.write(buf)?;
+        self.advance(bytes_written);
+
+        Ok(bytes_written)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.fill_sparse_tail()?;
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<S> Seek for SparseStream<S>
+where
+    S: Write + Seek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        if let Some(position) = self.seek_position(pos) {
+            self.position = position;
+            self.is_inner_positioned = false;
+
+            return Ok(position);
+        }
+
+        self.fill_sparse_tail()?;
+        self.position_inner()?;
+        self.position = self.inner.seek(pos)?;
+
+        Ok(self.position)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S> SparseStream<S>
+where
+    S: AsyncWrite + AsyncSeek,
+{
+    async fn position_inner_async(&mut self) -> Result<(), S::Error> {
+        if !self.is_inner_positioned {
+            self.inner.seek(SeekFrom::Start(self.position)).await?;
+            self.is_inner_positioned = true;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the last byte of skipped regions beyond the wrapped stream's written bytes, extending
+    /// its length over them.
+    async fn fill_sparse_tail_async(&mut self) -> Result<(), S::Error> {
+        if !self.has_sparse_tail() {
+            return Ok(());
+        }
+
+        self.inner
+            .seek(SeekFrom::Start(self.skipped_end - 1))
+            .await?;
+        self.inner.write_all(&[0]).await?;
+        self.written_end = self.skipped_end;
+        self.is_inner_positioned = false;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S> AsyncRead for SparseStream<S>
+where
+    S: AsyncRead + AsyncWrite + AsyncSeek,
+{
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.fill_sparse_tail_async().await?;
+        self.position_inner_async().await?;
+
+        let bytes_read = self.inner.read(buf).await?;
+        self.position += bytes_read as u64;
+
+        Ok(bytes_read)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S> AsyncWrite for SparseStream<S>
+where
+    S: AsyncWrite + AsyncSeek,
+{
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.iter().all(|&byte| byte == 0) {
+            self.skip(buf.len());
+
+            return Ok(buf.len());
+        }
+
+        self.position_inner_async().await?;
+
+        let bytes_written = self.inner.write(buf).await?;
+        self.advance(bytes_written);
+
+        Ok(bytes_written)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.fill_sparse_tail_async().await?;
+        self.inner.flush().await
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S> AsyncSeek for SparseStream<S>
+where
+    S: AsyncWrite + AsyncSeek,
+{
+    async fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        if let Some(position) = self.seek_position(pos) {
+            self.position = position;
+            self.is_inner_positioned = false;
+
+            return Ok(position);
+        }
+
+        self.fill_sparse_tail_async().await?;
+        self.position_inner_async().await?;
+        self.position = self.inner.seek(pos).await?;
+
+        Ok(self.position)
+    }
+}
+
+#[cfg(all(test, feature = "sync"))]
+mod tests {
+    use super::SparseStream;
+    use crate::mock::DataStream;
+    use embedded_io::{Read, Seek, SeekFrom, Write};
+
+    fn stream() -> SparseStream<DataStream<[u8; 8]>> {
+        SparseStream::new(DataStream::from_bytes([0xFF; 8]))
+    }
+
+    fn inner_bytes(stream: SparseStream<DataStream<[u8; 8]>>) -> [u8; 8] {
+        let mut inner = stream.into_inner();
+        let mut bytes = [0; 8];
+
+        inner
+            .seek(SeekFrom::Start(0))
+            .expect("Ok should be returned");
+        inner.read_exact(&mut bytes).expect("Read should succeed");
+
+        bytes
+    }
+
+    mod write {
+        use super::*;
+
+        #[test]
+        fn zero_writes_skipped() {
+            let mut stream = stream();
+
+            stream.write_all(&[0; 3]).expect("Write should succeed");
+            stream.write_all(&[1, 0, 2]).expect("Write should succeed");
+
+            assert_eq!(stream.stream_position().expect("Ok should be returned"), 6);
+            assert_eq!(inner_bytes(stream), [0xFF, 0xFF, 0xFF, 1, 0, 2, 0xFF, 0xFF]);
+        }
+    }
+
+    mod flush {
+        use super::*;
+
+        #[test]
+        fn sparse_tail_ends_with_zero() {
+            let mut stream = stream();
+
+            stream.write_all(&[1]).expect("Write should succeed");
+            stream.write_all(&[0; 4]).expect("Write should succeed");
+            stream.flush().expect("Write should succeed");
+
+            assert_eq!(
+                inner_bytes(stream),
+                [1, 0xFF, 0xFF, 0xFF, 0, 0xFF, 0xFF, 0xFF]
+            );
+        }
+
+        #[test]
+        fn written_tail_untouched() {
+            let mut stream = stream();
+
+            stream.write_all(&[0; 4]).expect("Write should succeed");
+            stream.write_all(&[1]).expect("Write should succeed");
+            stream.flush().expect("Write should succeed");
+
+            assert_eq!(
+                inner_bytes(stream),
+                [0xFF, 0xFF, 0xFF, 0xFF, 1, 0xFF, 0xFF, 0xFF]
+            );
+        }
+    }
+
+    mod read {
+        use super::*;
+
+        #[test]
+        fn position_kept_after_skipped_writes() {
+            let mut stream = SparseStream::new(DataStream::from_bytes([0, 0, 0, 0, 5, 6, 7, 8]));
+
+            stream.write_all(&[0; 4]).expect("Write should succeed");
+
+            let mut buf = [0; 2];
+            stream.read_exact(&mut buf).expect("Read should succeed");
+
+            assert_eq!(buf, [5, 6]);
+            assert_eq!(stream.stream_position().expect("Ok should be returned"), 6);
+        }
+    }
+
+    mod seek {
+        use super::*;
+
+        #[test]
+        fn end_relative_seek_forwarded() {
+            let mut stream = stream();
+
+            stream.write_all(&[0; 2]).expect("Write should succeed");
+
+            assert_eq!(
+                stream
+                    .seek(SeekFrom::End(-2))
+                    .expect("Ok should be returned"),
+                6
+            );
+            assert_eq!(
+                stream
+                    .seek(SeekFrom::Current(-4))
+                    .expect("Ok should be returned"),
+                2
+            );
+            assert_eq!(
+                inner_bytes(stream),
+                [0xFF, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]
+            );
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use crate::directory::Directory;
+use crate::directory_entry::{DEFAULT_BUFFER_SIZE, DirectoryEntryAttributes, MAX_FILE_SIZE};
+use crate::directory_item::{DirectoryItemEntries, EntryLocation};
+use crate::timestamp::shift_minutes;
+use crate::units::ClusterNumber;
+use crate::{
+    CodePageEncoder, DeviceOperationError, FatDate, FatTime, FileSystem,
+    InvalidDirectoryEntryHandler, MutationEvent, OperationError, ShortFileName, SyncDevice,
+};
+use embedded_io::{Read, Seek, Write};
+
+/// A file which is deleted when dropped unless it is persisted under a name first.
+///
+/// Contents are appended with `append`, each call leaves the file's entry consistent with the data
+/// written so far.  This suits staging downloads which should only appear under their final name
+/// once complete.
+#[derive(Debug)]
+pub struct TempFile<'a, D, CPE, IDE, ME, const BUFFER_SIZE: usize = DEFAULT_BUFFER_SIZE>
+where
+    D: SyncDevice,
+    D::Stream: Read + Write + Seek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    file_system: &'a FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>,
+    directory: Directory<'a, D, BUFFER_SIZE>,
+    directory_path: &'a str,
+
+    location: EntryLocation,
+    short_name: ShortFileName,
+
+    first_cluster_number: ClusterNumber,
+    last_cluster_number: ClusterNumber,
+    file_size: u64,
+    last_write_date: u16,
+    last_write_time: u16,
+
+    is_persisted: bool,
+}
+
+impl<'a, D, CPE, IDE, ME, const BUFFER_SIZE: usize> TempFile<'a, D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: SyncDevice,
+    D::Stream: Read + Write + Seek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    pub(crate) fn new(
+        file_system: &'a FileSystem<D, CPE, IDE, ME, BUFFER_SIZE>,
+        directory: Directory<'a, D, BUFFER_SIZE>,
+        directory_path: &'a str,
+        location: EntryLocation,
+        short_name: ShortFileName,
+    ) -> Self {
+        Self {
+            file_system,
+            directory,
+            directory_path,
+
+            location,
+            short_name,
+
+            first_cluster_number: ClusterNumber::NONE,
+            last_cluster_number: ClusterNumber::NONE,
+            file_size: 0,
+            last_write_date: 0,
+            last_write_time: 0,
+
+            is_persisted: false,
+        }
+    }
+
+    /// The number of bytes written to the file so far.
+    pub fn file_size(&self) -> u64 {
+        self.file_size
+    }
+
+    /// Appends the bytes to the end of the file.
+    pub fn append(&mut self, bytes: &[u8]) -> Result<(), DeviceOperationError<D>> {
+        let file_size = u64::try_from(bytes.len())
+            .ok()
+            .and_then(|length| self.file_size.checked_add(length))
+            .filter(|file_size| *file_size <= MAX_FILE_SIZE)
+            .ok_or(OperationError::ContentsTooLarge)?;
+        ensure!(
+            self.file_system.is_within_quota(
+                &self.directory,
+                self.directory_path,
+                0,
+                file_size - self.file_size,
+            ),
+            OperationError::QuotaExceeded
+        );
+
+        let (first_cluster_number, last_cluster_number) =
+            self.file_system.append_to_cluster_chain(
+                self.first_cluster_number,
+                self.last_cluster_number,
+                self.file_size,
+                bytes,
+            )?;
+
+        self.first_cluster_number = first_cluster_number;
+        self.last_cluster_number = last_cluster_number;
+        self.file_size = file_size;
+        self.write_entry()
+    }
+
+    /// Sets the date and time the file's contents were last written, as FAT encoded values.
+    pub fn set_last_write(&mut self, date: u16, time: u16) -> Result<(), DeviceOperationError<D>> {
+        self.last_write_date = date;
+        self.last_write_time = time;
+        self.write_entry()
+    }
+
+    /// Sets the date and time the file's contents were last written.
+    pub fn set_last_write_timestamp(
+        &mut self,
+        date: FatDate,
+        time: FatTime,
+    ) -> Result<(), DeviceOperationError<D>> {
+        self.set_last_write(date.to_packed(), time.to_packed())
+    }
+
+    /// Sets the date and time the file's contents were last written from a UTC timestamp, stored
+    /// in the volume's local time as set by `FileSystemBuilder::with_utc_offset`.  Fails with
+    /// `TimestampInvalid` if the local time falls outside of the dates FAT can store.
+    pub fn set_last_write_timestamp_utc(
+        &mut self,
+        date: FatDate,
+        time: FatTime,
+    ) -> Result<(), DeviceOperationError<D>> {
+        let (date, time) =
+            shift_minutes(date, time, i32::from(self.file_system.utc_offset_minutes()))
+                .map_err(OperationError::TimestampInvalid)?;
+
+        self.set_last_write_timestamp(date, time)
+    }
+
+    /// Gives the file its final name, keeping it after the guard is dropped.
+    ///
+    /// The guard is returned along with the error if the file couldn't be renamed, so the contents
+    /// aren't lost.
+    #[allow(clippy::result_large_err)]
+    pub fn persist(mut self, name: &str) -> Result<(), (Self, DeviceOperationError<D>)> {
+        let item = self.file_system.find_item_in(&self.directory, |item| {
+            item.short_name() == &self.short_name
+        });
+
+        let result = match item {
+            Some(item) => self.file_system.rename_item(&self.directory, &item, name),
+            None => Err(OperationError::NotFound),
+        };
+
+        match result {
+            Ok(()) => {
+                self.is_persisted = true;
+
+                self.file_system.notify_mutation(MutationEvent::Created {
+                    directory_path: self.directory_path,
+                    name,
+                    first_cluster_number: self.first_cluster_number.value(),
+                });
+                self.file_system
+                    .notify_mutation(MutationEvent::WriteCompleted {
+                        directory_path: self.directory_path,
+                        name,
+                        first_cluster_number: self.first_cluster_number.value(),
+                        file_size: self.file_size,
+                    });
+
+                Ok(())
+            }
+            Err(error) => Err((self, error)),
+        }
+    }
+
+    /// Gives the file its final name, replacing the contents of an existing file with that name.
+    ///
+    /// An existing file keeps its names and creation time, but takes on this file's contents and
+    /// last write timestamp.  Its previous contents are released.
+    #[allow(clippy::result_large_err)]
+    pub fn persist_replacing(mut self, name: &str) -> Result<(), (Self, DeviceOperationError<D>)> {
+        let Some(target) = self.file_system.find_item_named(&self.directory, name) else {
+            return self.persist(name);
+        };
+
+        let result = if target.is_file() {
+            self.file_system
+                .find_item_in(&self.directory, |item| {
+                    item.short_name() == &self.short_name
+                })
+                .ok_or(OperationError::NotFound)
+                .and_then(|item| {
+                    self.file_system
+                        .replace_item(&self.directory, &target, &item)
+                })
+        } else {
+            Err(OperationError::NotAFile)
+        };
+
+        match result {
+            Ok(()) => {
+                self.is_persisted = true;
+
+                self.file_system
+                    .notify_mutation(MutationEvent::WriteCompleted {
+                        directory_path: self.directory_path,
+                        name,
+                        first_cluster_number: self.first_cluster_number.value(),
+                        file_size: self.file_size,
+                    });
+
+                Ok(())
+            }
+            Err(error) => Err((self, error)),
+        }
+    }
+
+    fn write_entry(&self) -> Result<(), DeviceOperationError<D>> {
+        let item_entries = DirectoryItemEntries::builder()
+            .short_name(self.short_name.clone())
+            .attributes(DirectoryEntryAttributes::Archive)
+            .last_write_time(self.last_write_time)
+            .last_write_date(self.last_write_date)
+            .first_cluster_number(self.first_cluster_number.value())
+            .file_size(self.file_size)
+            .build();
+
+        self.directory
+            .write_entries(self.location.position(), item_entries.entries())?;
+
+        Ok(())
+    }
+
+    fn delete(&self) -> Result<(), DeviceOperationError<D>> {
+        self.directory.write_entries(
+            self.location.position(),
+            &[FileSystem::<D, CPE, IDE, ME, BUFFER_SIZE>::deleted_entry()],
+        )?;
+
+        if self.first_cluster_number != ClusterNumber::NONE {
+            self.file_system
+                .release_cluster_chain(self.first_cluster_number)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<D, CPE, IDE, ME, const BUFFER_SIZE: usize> Drop for TempFile<'_, D, CPE, IDE, ME, BUFFER_SIZE>
+where
+    D: SyncDevice,
+    D::Stream: Read + Write + Seek,
+    CPE: CodePageEncoder,
+    IDE: InvalidDirectoryEntryHandler<D>,
+    ME: Fn(MutationEvent<'_>),
+{
+    fn drop(&mut self) {
+        if !self.is_persisted {
+            // Errors can't be reported from drop, the file is left behind if deletion fails
+            let _ = self.delete();
+        }
+    }
+}
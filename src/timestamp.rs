@@ -0,0 +1,12 @@
+mod date;
+mod error;
+mod invalid_timestamp_policy;
+mod shift;
+mod time;
+
+pub use date::*;
+pub use error::*;
+pub use invalid_timestamp_policy::*;
+pub use time::*;
+
+pub(crate) use shift::shift_minutes;
@@ -0,0 +1,288 @@
+use crate::timestamp::TimestampError;
+
+const EPOCH_YEAR: u16 = 1980;
+const MAX_YEAR: u16 = EPOCH_YEAR + 0x7F;
+
+/// A calendar date as stored in directory entries, ranging from 1980 to 2107.
+///
+/// Dates are held in their packed form, so they order chronologically.  Only dates which exist
+/// can be created, entries written by other hosts may hold packed values which don't and are
+/// rejected by `from_packed`.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct FatDate {
+    packed: u16,
+}
+
+impl FatDate {
+    pub const fn new(year: u16, month: u8, day: u8) -> Result<Self, TimestampError> {
+        if year < EPOCH_YEAR || year > MAX_YEAR {
+            return Err(TimestampError::YearOutOfRange { year });
+        }
+
+        if month < 1 || month > 12 {
+            return Err(TimestampError::MonthInvalid { month });
+        }
+
+        if day < 1 || day > days_in_month(year, month) {
+            return Err(TimestampError::DayInvalid { day });
+        }
+
+        Ok(Self {
+            packed: ((year - EPOCH_YEAR) << 9) | ((month as u16) << 5) | day as u16,
+        })
+    }
+
+    /// Unpacks a date read from a directory entry, bits 9 to 15 hold the years since 1980, bits 5
+    /// to 8 the month and bits 0 to 4 the day.
+    pub const fn from_packed(packed: u16) -> Result<Self, TimestampError> {
+        Self::new(
+            EPOCH_YEAR + (packed >> 9),
+            ((packed >> 5) & 0x0F) as u8,
+            (packed & 0x1F) as u8,
+        )
+    }
+
+    /// Unpacks a date read from a directory entry like `from_packed`, moving a month or day which
+    /// doesn't exist to the nearest one which does, such as the 31st of April to the 30th.
+    pub(crate) fn from_packed_clamped(packed: u16) -> Self {
+        let year = EPOCH_YEAR + (packed >> 9);
+        let month = match ((packed >> 5) & 0x0F) as u8 {
+            0 => 1,
+            month if month > 12 => 12,
+            month => month,
+        };
+        let day = match (packed & 0x1F) as u8 {
+            0 => 1,
+            day if day > days_in_month(year, month) => days_in_month(year, month),
+            day => day,
+        };
+
+        Self {
+            packed: ((year - EPOCH_YEAR) << 9) | ((month as u16) << 5) | day as u16,
+        }
+    }
+
+    pub const fn to_packed(self) -> u16 {
+        self.packed
+    }
+
+    pub const fn year(self) -> u16 {
+        EPOCH_YEAR + (self.packed >> 9)
+    }
+
+    pub const fn month(self) -> u8 {
+        ((self.packed >> 5) & 0x0F) as u8
+    }
+
+    pub const fn day(self) -> u8 {
+        (self.packed & 0x1F) as u8
+    }
+
+    pub(crate) fn next_day(self) -> Result<Self, TimestampError> {
+        let (year, month, day) = (self.year(), self.month(), self.day());
+
+        if day < days_in_month(year, month) {
+            Self::new(year, month, day + 1)
+        } else if month < 12 {
+            Self::new(year, month + 1, 1)
+        } else {
+            Self::new(year + 1, 1, 1)
+        }
+    }
+
+    pub(crate) fn previous_day(self) -> Result<Self, TimestampError> {
+        let (year, month, day) = (self.year(), self.month(), self.day());
+
+        if day > 1 {
+            Self::new(year, month, day - 1)
+        } else if month > 1 {
+            Self::new(year, month - 1, days_in_month(year, month - 1))
+        } else {
+            Self::new(year - 1, 12, 31)
+        }
+    }
+}
+
+const fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod new {
+        use super::*;
+
+        #[test]
+        fn fields_packed() {
+            assert_eq!(FatDate::new(1980, 1, 1).unwrap().to_packed(), 0x0021);
+            assert_eq!(FatDate::new(2107, 12, 31).unwrap().to_packed(), 0xFF9F);
+        }
+
+        #[test]
+        fn out_of_range_years_rejected() {
+            assert_eq!(
+                FatDate::new(1979, 12, 31),
+                Err(TimestampError::YearOutOfRange { year: 1979 })
+            );
+            assert_eq!(
+                FatDate::new(2108, 1, 1),
+                Err(TimestampError::YearOutOfRange { year: 2108 })
+            );
+        }
+
+        #[test]
+        fn invalid_months_rejected() {
+            assert_eq!(
+                FatDate::new(2024, 0, 1),
+                Err(TimestampError::MonthInvalid { month: 0 })
+            );
+            assert_eq!(
+                FatDate::new(2024, 13, 1),
+                Err(TimestampError::MonthInvalid { month: 13 })
+            );
+        }
+
+        #[test]
+        fn days_beyond_month_rejected() {
+            assert_eq!(
+                FatDate::new(2024, 1, 0),
+                Err(TimestampError::DayInvalid { day: 0 })
+            );
+            assert_eq!(
+                FatDate::new(2024, 4, 31),
+                Err(TimestampError::DayInvalid { day: 31 })
+            );
+            assert_eq!(
+                FatDate::new(2023, 2, 29),
+                Err(TimestampError::DayInvalid { day: 29 })
+            );
+            assert_eq!(
+                FatDate::new(2100, 2, 29),
+                Err(TimestampError::DayInvalid { day: 29 })
+            );
+        }
+
+        #[test]
+        fn leap_days_accepted() {
+            assert!(FatDate::new(2024, 2, 29).is_ok());
+            assert!(FatDate::new(2000, 2, 29).is_ok());
+        }
+    }
+
+    mod from_packed {
+        use super::*;
+
+        #[test]
+        fn round_trips() {
+            let date = FatDate::new(2024, 6, 15).unwrap();
+
+            assert_eq!(FatDate::from_packed(date.to_packed()), Ok(date));
+            assert_eq!((date.year(), date.month(), date.day()), (2024, 6, 15));
+        }
+
+        #[test]
+        fn zeroed_dates_rejected() {
+            assert_eq!(
+                FatDate::from_packed(0),
+                Err(TimestampError::MonthInvalid { month: 0 })
+            );
+        }
+    }
+
+    mod from_packed_clamped {
+        use super::*;
+
+        #[test]
+        fn valid_dates_kept() {
+            let date = FatDate::new(2024, 6, 15).unwrap();
+
+            assert_eq!(FatDate::from_packed_clamped(date.to_packed()), date);
+        }
+
+        #[test]
+        fn fields_moved_to_nearest_existing_date() {
+            let clamped = |year: u16, month: u16, day: u16| {
+                let date = FatDate::from_packed_clamped(((year - 1980) << 9) | (month << 5) | day);
+
+                (date.year(), date.month(), date.day())
+            };
+
+            assert_eq!(clamped(1980, 0, 0), (1980, 1, 1));
+            assert_eq!(clamped(2024, 15, 31), (2024, 12, 31));
+            assert_eq!(clamped(2024, 4, 31), (2024, 4, 30));
+            assert_eq!(clamped(2023, 2, 30), (2023, 2, 28));
+        }
+    }
+
+    mod next_day {
+        use super::*;
+
+        #[test]
+        fn month_and_year_ends_crossed() {
+            let next_day = |year, month, day| {
+                let date = FatDate::new(year, month, day).unwrap().next_day().unwrap();
+
+                (date.year(), date.month(), date.day())
+            };
+
+            assert_eq!(next_day(2024, 2, 28), (2024, 2, 29));
+            assert_eq!(next_day(2024, 2, 29), (2024, 3, 1));
+            assert_eq!(next_day(2024, 12, 31), (2025, 1, 1));
+        }
+
+        #[test]
+        fn end_of_range_rejected() {
+            assert_eq!(
+                FatDate::new(2107, 12, 31).unwrap().next_day(),
+                Err(TimestampError::YearOutOfRange { year: 2108 })
+            );
+        }
+    }
+
+    mod previous_day {
+        use super::*;
+
+        #[test]
+        fn month_and_year_starts_crossed() {
+            let previous_day = |year, month, day| {
+                let date = FatDate::new(year, month, day)
+                    .unwrap()
+                    .previous_day()
+                    .unwrap();
+
+                (date.year(), date.month(), date.day())
+            };
+
+            assert_eq!(previous_day(2023, 3, 1), (2023, 2, 28));
+            assert_eq!(previous_day(2024, 5, 1), (2024, 4, 30));
+            assert_eq!(previous_day(2024, 1, 1), (2023, 12, 31));
+        }
+
+        #[test]
+        fn start_of_range_rejected() {
+            assert_eq!(
+                FatDate::new(1980, 1, 1).unwrap().previous_day(),
+                Err(TimestampError::YearOutOfRange { year: 1979 })
+            );
+        }
+    }
+
+    mod ord {
+        use super::*;
+
+        #[test]
+        fn chronological() {
+            assert!(FatDate::new(2023, 12, 31).unwrap() < FatDate::new(2024, 1, 1).unwrap());
+            assert!(FatDate::new(2024, 1, 31).unwrap() < FatDate::new(2024, 2, 1).unwrap());
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use core::error::Error;
+use core::fmt::{Display, Formatter};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TimestampError {
+    YearOutOfRange { year: u16 },
+    MonthInvalid { month: u8 },
+    DayInvalid { day: u8 },
+    HourInvalid { hour: u8 },
+    MinuteInvalid { minute: u8 },
+    SecondInvalid { second: u8 },
+}
+
+impl Display for TimestampError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TimestampError::YearOutOfRange { year } => {
+                write!(f, "the year {year} is outside of the range 1980 to 2107")
+            }
+            TimestampError::MonthInvalid { month } => write!(f, "the month {month} is invalid"),
+            TimestampError::DayInvalid { day } => {
+                write!(f, "the day {day} is invalid for its month")
+            }
+            TimestampError::HourInvalid { hour } => write!(f, "the hour {hour} is invalid"),
+            TimestampError::MinuteInvalid { minute } => {
+                write!(f, "the minute {minute} is invalid")
+            }
+            TimestampError::SecondInvalid { second } => {
+                write!(f, "the second {second} is invalid")
+            }
+        }
+    }
+}
+
+impl Error for TimestampError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn produces_non_empty_value() {
+            let values = [
+                TimestampError::YearOutOfRange { year: 0 },
+                TimestampError::MonthInvalid { month: 0 },
+                TimestampError::DayInvalid { day: 0 },
+                TimestampError::HourInvalid { hour: 0 },
+                TimestampError::MinuteInvalid { minute: 0 },
+                TimestampError::SecondInvalid { second: 0 },
+            ];
+
+            for value in values {
+                assert!(
+                    !value.to_string().is_empty(),
+                    "Display implementation should be non-empty"
+                );
+            }
+        }
+    }
+}
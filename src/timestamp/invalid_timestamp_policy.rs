@@ -0,0 +1,130 @@
+use crate::timestamp::{FatDate, FatTime, TimestampError};
+
+/// How timestamps read from directory entries are treated when they don't exist, such as the
+/// zeroed or garbage values written by devices without a working clock.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum InvalidTimestampPolicy {
+    /// Reports such timestamps as a `TimestampError`.
+    #[default]
+    Reject,
+    /// Moves each field which doesn't exist to the nearest value which does, so a zeroed date
+    /// reads as the 1st of January 1980, then raises timestamps before `earliest` to its start.
+    ///
+    /// An `earliest` date such as the firmware's release date also hides the 1st of January 1980
+    /// which devices write after losing power to their clock, as no file can be older.
+    Clamp { earliest: FatDate },
+    /// Replaces such timestamps with the given date and time, such as the time the volume was
+    /// mounted.
+    Substitute { date: FatDate, time: FatTime },
+}
+
+impl InvalidTimestampPolicy {
+    /// Unpacks the date and time read from a directory entry according to the policy.
+    pub(crate) fn unpack(self, date: u16, time: u16) -> Result<(FatDate, FatTime), TimestampError> {
+        let unpacked =
+            FatDate::from_packed(date).and_then(|date| Ok((date, FatTime::from_packed(time)?)));
+
+        match self {
+            InvalidTimestampPolicy::Reject => unpacked,
+            InvalidTimestampPolicy::Clamp { earliest } => {
+                let date = FatDate::from_packed_clamped(date);
+                let time = FatTime::from_packed_clamped(time);
+
+                if date < earliest {
+                    Ok((earliest, FatTime::MIDNIGHT))
+                } else {
+                    Ok((date, time))
+                }
+            }
+            InvalidTimestampPolicy::Substitute { date, time } => {
+                Ok(unpacked.unwrap_or((date, time)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packed(year: u16, month: u8, day: u8, hour: u8, minute: u8) -> (u16, u16) {
+        (
+            FatDate::new(year, month, day).unwrap().to_packed(),
+            FatTime::new(hour, minute, 0).unwrap().to_packed(),
+        )
+    }
+
+    mod unpack {
+        use super::*;
+
+        #[test]
+        fn reject_returns_err() {
+            assert_eq!(
+                InvalidTimestampPolicy::Reject.unpack(0, 0),
+                Err(TimestampError::MonthInvalid { month: 0 })
+            );
+        }
+
+        #[test]
+        fn valid_timestamps_kept() {
+            let (date, time) = packed(2024, 6, 15, 13, 45);
+            let expected = Ok((
+                FatDate::from_packed(date).unwrap(),
+                FatTime::from_packed(time).unwrap(),
+            ));
+            let earliest = FatDate::new(1980, 1, 1).unwrap();
+
+            for policy in [
+                InvalidTimestampPolicy::Reject,
+                InvalidTimestampPolicy::Clamp { earliest },
+                InvalidTimestampPolicy::Substitute {
+                    date: earliest,
+                    time: FatTime::MIDNIGHT,
+                },
+            ] {
+                assert_eq!(policy.unpack(date, time), expected);
+            }
+        }
+
+        #[test]
+        fn clamp_moves_fields() {
+            let policy = InvalidTimestampPolicy::Clamp {
+                earliest: FatDate::new(1980, 1, 1).unwrap(),
+            };
+
+            assert_eq!(
+                policy.unpack(0, u16::MAX),
+                Ok((
+                    FatDate::new(1980, 1, 1).unwrap(),
+                    FatTime::new(23, 59, 58).unwrap()
+                ))
+            );
+        }
+
+        #[test]
+        fn clamp_raises_early_timestamps() {
+            let earliest = FatDate::new(2020, 3, 1).unwrap();
+            let policy = InvalidTimestampPolicy::Clamp { earliest };
+            let (date, time) = packed(1980, 1, 1, 12, 0);
+
+            assert_eq!(policy.unpack(date, time), Ok((earliest, FatTime::MIDNIGHT)));
+        }
+
+        #[test]
+        fn substitute_replaces_invalid_timestamps() {
+            let (date, time) = packed(2024, 6, 15, 13, 45);
+            let policy = InvalidTimestampPolicy::Substitute {
+                date: FatDate::from_packed(date).unwrap(),
+                time: FatTime::from_packed(time).unwrap(),
+            };
+
+            assert_eq!(
+                policy.unpack(0x0000, 0xFFFF),
+                Ok((
+                    FatDate::from_packed(date).unwrap(),
+                    FatTime::from_packed(time).unwrap()
+                ))
+            );
+        }
+    }
+}
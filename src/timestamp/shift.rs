@@ -0,0 +1,85 @@
+use crate::timestamp::{FatDate, FatTime, TimestampError};
+
+const MINUTES_PER_DAY: i32 = 24 * 60;
+
+/// Moves a date and time by the given number of minutes, such as between local time and UTC.  The
+/// seconds are kept.
+pub(crate) fn shift_minutes(
+    date: FatDate,
+    time: FatTime,
+    minutes: i32,
+) -> Result<(FatDate, FatTime), TimestampError> {
+    let minute_of_day = i32::from(time.hour()) * 60 + i32::from(time.minute()) + minutes;
+    let day_count = minute_of_day.div_euclid(MINUTES_PER_DAY);
+    let minute_of_day = minute_of_day.rem_euclid(MINUTES_PER_DAY);
+
+    let mut date = date;
+
+    for _ in 0..day_count {
+        date = date.next_day()?;
+    }
+
+    for _ in day_count..0 {
+        date = date.previous_day()?;
+    }
+
+    let time = FatTime::new(
+        (minute_of_day / 60) as u8,
+        (minute_of_day % 60) as u8,
+        time.second(),
+    )?;
+
+    Ok((date, time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timestamp(year: u16, month: u8, day: u8, hour: u8, minute: u8) -> (FatDate, FatTime) {
+        (
+            FatDate::new(year, month, day).unwrap(),
+            FatTime::new(hour, minute, 30).unwrap(),
+        )
+    }
+
+    mod shift_minutes {
+        use super::*;
+
+        #[test]
+        fn time_moved_within_day() {
+            let (date, time) = timestamp(2024, 6, 15, 12, 0);
+
+            assert_eq!(
+                shift_minutes(date, time, -90),
+                Ok(timestamp(2024, 6, 15, 10, 30))
+            );
+            assert_eq!(shift_minutes(date, time, 0), Ok((date, time)));
+        }
+
+        #[test]
+        fn day_boundaries_crossed() {
+            let (date, time) = timestamp(2024, 12, 31, 23, 0);
+            assert_eq!(
+                shift_minutes(date, time, 120),
+                Ok(timestamp(2025, 1, 1, 1, 0))
+            );
+
+            let (date, time) = timestamp(2024, 3, 1, 1, 0);
+            assert_eq!(
+                shift_minutes(date, time, -120),
+                Ok(timestamp(2024, 2, 29, 23, 0))
+            );
+        }
+
+        #[test]
+        fn out_of_range_dates_rejected() {
+            let (date, time) = timestamp(1980, 1, 1, 0, 30);
+
+            assert_eq!(
+                shift_minutes(date, time, -60),
+                Err(TimestampError::YearOutOfRange { year: 1979 })
+            );
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use crate::timestamp::TimestampError;
+use core::cmp::min;
+
+/// A time of day as stored in directory entries, with a resolution of two seconds.
+///
+/// Times are held in their packed form, so they order chronologically.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct FatTime {
+    packed: u16,
+}
+
+impl FatTime {
+    pub const MIDNIGHT: Self = Self { packed: 0 };
+
+    /// Odd seconds are rounded down, since they can't be stored.
+    pub const fn new(hour: u8, minute: u8, second: u8) -> Result<Self, TimestampError> {
+        if hour > 23 {
+            return Err(TimestampError::HourInvalid { hour });
+        }
+
+        if minute > 59 {
+            return Err(TimestampError::MinuteInvalid { minute });
+        }
+
+        if second > 59 {
+            return Err(TimestampError::SecondInvalid { second });
+        }
+
+        Ok(Self {
+            packed: ((hour as u16) << 11) | ((minute as u16) << 5) | (second / 2) as u16,
+        })
+    }
+
+    /// Unpacks a time read from a directory entry, bits 11 to 15 hold the hour, bits 5 to 10 the
+    /// minute and bits 0 to 4 the second divided by two.
+    pub const fn from_packed(packed: u16) -> Result<Self, TimestampError> {
+        Self::new(
+            (packed >> 11) as u8,
+            ((packed >> 5) & 0x3F) as u8,
+            (packed & 0x1F) as u8 * 2,
+        )
+    }
+
+    /// Unpacks a time read from a directory entry like `from_packed`, lowering a field which is out
+    /// of range to its largest value, such as minute 63 to 59.
+    pub(crate) fn from_packed_clamped(packed: u16) -> Self {
+        let hour = min((packed >> 11) as u8, 23);
+        let minute = min(((packed >> 5) & 0x3F) as u8, 59);
+        let half_second = min((packed & 0x1F) as u8, 29);
+
+        Self {
+            packed: ((hour as u16) << 11) | ((minute as u16) << 5) | half_second as u16,
+        }
+    }
+
+    pub const fn to_packed(self) -> u16 {
+        self.packed
+    }
+
+    pub const fn hour(self) -> u8 {
+        (self.packed >> 11) as u8
+    }
+
+    pub const fn minute(self) -> u8 {
+        ((self.packed >> 5) & 0x3F) as u8
+    }
+
+    pub const fn second(self) -> u8 {
+        (self.packed & 0x1F) as u8 * 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod new {
+        use super::*;
+
+        #[test]
+        fn fields_packed() {
+            assert_eq!(FatTime::new(0, 0, 0).unwrap().to_packed(), 0);
+            assert_eq!(FatTime::new(23, 59, 58).unwrap().to_packed(), 0xBF7D);
+        }
+
+        #[test]
+        fn odd_seconds_rounded_down() {
+            assert_eq!(FatTime::new(12, 30, 59), FatTime::new(12, 30, 58));
+        }
+
+        #[test]
+        fn out_of_range_values_rejected() {
+            assert_eq!(
+                FatTime::new(24, 0, 0),
+                Err(TimestampError::HourInvalid { hour: 24 })
+            );
+            assert_eq!(
+                FatTime::new(0, 60, 0),
+                Err(TimestampError::MinuteInvalid { minute: 60 })
+            );
+            assert_eq!(
+                FatTime::new(0, 0, 60),
+                Err(TimestampError::SecondInvalid { second: 60 })
+            );
+        }
+    }
+
+    mod from_packed_clamped {
+        use super::*;
+
+        #[test]
+        fn valid_times_kept() {
+            let time = FatTime::new(13, 45, 20).unwrap();
+
+            assert_eq!(FatTime::from_packed_clamped(time.to_packed()), time);
+        }
+
+        #[test]
+        fn fields_lowered_to_largest_value() {
+            let time = FatTime::from_packed_clamped(u16::MAX);
+
+            assert_eq!((time.hour(), time.minute(), time.second()), (23, 59, 58));
+        }
+    }
+
+    mod from_packed {
+        use super::*;
+
+        #[test]
+        fn round_trips() {
+            let time = FatTime::new(13, 45, 20).unwrap();
+
+            assert_eq!(FatTime::from_packed(time.to_packed()), Ok(time));
+            assert_eq!((time.hour(), time.minute(), time.second()), (13, 45, 20));
+        }
+
+        #[test]
+        fn impossible_seconds_rejected() {
+            assert_eq!(
+                FatTime::from_packed(0x1F),
+                Err(TimestampError::SecondInvalid { second: 62 })
+            );
+        }
+    }
+}
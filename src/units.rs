@@ -0,0 +1,7 @@
+mod byte_address;
+mod cluster_number;
+mod sector_index;
+
+pub use byte_address::*;
+pub use cluster_number::*;
+pub use sector_index::*;
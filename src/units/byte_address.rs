@@ -0,0 +1,56 @@
+use core::fmt::{Display, Formatter};
+
+/// An absolute byte address within the volume.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ByteAddress(u64);
+
+impl ByteAddress {
+    pub const fn new(value: u64) -> Self {
+        Self(value)
+    }
+
+    pub const fn value(self) -> u64 {
+        self.0
+    }
+
+    /// The address `byte_count` bytes after this one.
+    pub const fn offset_by(self, byte_count: u64) -> Self {
+        Self(self.0 + byte_count)
+    }
+}
+
+impl Display for ByteAddress {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:#X}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    mod offset_by {
+        use super::*;
+
+        #[test]
+        fn byte_count_added() {
+            assert_eq!(
+                ByteAddress::new(0x200).offset_by(0x20),
+                ByteAddress::new(0x220)
+            );
+        }
+    }
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn produces_non_empty_value() {
+            assert!(
+                !ByteAddress::new(0).to_string().is_empty(),
+                "Display implementation should be non-empty"
+            );
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::units::ByteAddress;
+use core::fmt::{Display, Formatter};
+
+/// The number of a cluster, as stored in allocation table and directory entries.
+///
+/// Cluster numbers 0 and 1 are reserved, so the first cluster of the data region is number 2.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ClusterNumber(u32);
+
+impl ClusterNumber {
+    /// Stored in place of a cluster number by entries which have no clusters, such as those of
+    /// empty files.
+    pub const NONE: Self = Self(0);
+
+    /// The number of the first cluster of the data region.
+    pub const FIRST_DATA_CLUSTER: Self = Self(2);
+
+    pub const fn new(value: u32) -> Self {
+        Self(value)
+    }
+
+    pub const fn value(self) -> u32 {
+        self.0
+    }
+
+    /// Whether the number refers to a cluster of the data region, rather than being reserved.
+    pub const fn is_data_cluster(self) -> bool {
+        self.0 >= Self::FIRST_DATA_CLUSTER.0
+    }
+
+    /// The cluster numbered directly after this one.
+    pub const fn next(self) -> Self {
+        Self(self.0 + 1)
+    }
+
+    /// The address of the first byte of the cluster, or `None` if the number is reserved.
+    pub const fn byte_address(
+        self,
+        data_region_base_address: ByteAddress,
+        bytes_per_cluster: u32,
+    ) -> Option<ByteAddress> {
+        match self.0.checked_sub(Self::FIRST_DATA_CLUSTER.0) {
+            Some(cluster_index) => Some(
+                data_region_base_address.offset_by(cluster_index as u64 * bytes_per_cluster as u64),
+            ),
+            None => None,
+        }
+    }
+}
+
+impl Display for ClusterNumber {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    mod is_data_cluster {
+        use super::*;
+
+        #[test]
+        fn reserved_numbers_return_false() {
+            assert!(!ClusterNumber::new(0).is_data_cluster());
+            assert!(!ClusterNumber::new(1).is_data_cluster());
+        }
+
+        #[test]
+        fn data_region_numbers_return_true() {
+            assert!(ClusterNumber::new(2).is_data_cluster());
+            assert!(ClusterNumber::new(0x0FFF_FFF6).is_data_cluster());
+        }
+    }
+
+    mod byte_address {
+        use super::*;
+
+        #[test]
+        fn offset_from_data_region() {
+            assert_eq!(
+                ClusterNumber::new(4).byte_address(ByteAddress::new(0x1000), 0x200),
+                Some(ByteAddress::new(0x1400))
+            );
+        }
+
+        #[test]
+        fn reserved_number_returns_none() {
+            assert_eq!(
+                ClusterNumber::new(1).byte_address(ByteAddress::new(0x1000), 0x200),
+                None
+            );
+        }
+    }
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn produces_non_empty_value() {
+            assert!(
+                !ClusterNumber::new(2).to_string().is_empty(),
+                "Display implementation should be non-empty"
+            );
+        }
+    }
+}
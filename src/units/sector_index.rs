@@ -0,0 +1,37 @@
+use crate::units::ByteAddress;
+
+/// The index of a sector, counted from the start of the volume.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct SectorIndex(u32);
+
+impl SectorIndex {
+    pub const fn new(value: u32) -> Self {
+        Self(value)
+    }
+
+    pub const fn value(self) -> u32 {
+        self.0
+    }
+
+    /// The address of the first byte of the sector.
+    pub const fn byte_address(self, bytes_per_sector: u16) -> ByteAddress {
+        ByteAddress::new(self.0 as u64 * bytes_per_sector as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod byte_address {
+        use super::*;
+
+        #[test]
+        fn scaled_by_sector_size() {
+            assert_eq!(
+                SectorIndex::new(3).byte_address(1024),
+                ByteAddress::new(3 * 1024)
+            );
+        }
+    }
+}
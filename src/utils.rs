@@ -23,6 +23,71 @@ macro_rules! propagate_device_iteration_errors {
     };
 }
 
+/// Expands an I/O call within a body shared between the sync and async code paths as a blocking
+/// call.
+///
+/// Shared bodies are written once as a local macro taking the mode as its first argument, which
+/// marks every call that waits on I/O with `$io!(..)` and is then invoked with `sync_io` from the
+/// sync implementation and `async_io` from the async one.  Calls through the `Read`, `Seek` and
+/// `Write` traits name the trait, `$io!(Read::read(file, buf))`, and are routed to the
+/// `embedded_io` or `embedded_io_async` trait of that name.  Methods which have a separate async
+/// counterpart give both calls, `$io!(file.read_chunk(buf); file.read_chunk_async(buf))`, and steps
+/// only the async path takes, such as yielding to the executor, leave the sync call out,
+/// `$io!(; yield_budget.tick())`.
+///
+/// Closures run against the device's stream are written once as
+/// `$io!(with_stream(device, |stream| -> Result<..> { .. }))`, becoming an async closure which is
+/// awaited in the async path.
+macro_rules! sync_io {
+    (Read::$method:ident($($argument:expr),* $(,)?)) => {
+        embedded_io::Read::$method($($argument),*)
+    };
+    (Seek::$method:ident($($argument:expr),* $(,)?)) => {
+        embedded_io::Seek::$method($($argument),*)
+    };
+    (Write::$method:ident($($argument:expr),* $(,)?)) => {
+        embedded_io::Write::$method($($argument),*)
+    };
+    (with_stream($device:expr, |$stream:ident| -> $result:ty { $($body:tt)* })) => {
+        $device.with_stream(|$stream| -> $result { $($body)* })
+    };
+    (; $async_operation:expr) => {
+        ()
+    };
+    ($sync_operation:expr; $async_operation:expr) => {
+        $sync_operation
+    };
+    ($operation:expr) => {
+        $operation
+    };
+}
+
+/// Expands an I/O call within a body shared between the sync and async code paths as an awaited
+/// call, see `sync_io`.
+macro_rules! async_io {
+    (Read::$method:ident($($argument:expr),* $(,)?)) => {
+        embedded_io_async::Read::$method($($argument),*).await
+    };
+    (Seek::$method:ident($($argument:expr),* $(,)?)) => {
+        embedded_io_async::Seek::$method($($argument),*).await
+    };
+    (Write::$method:ident($($argument:expr),* $(,)?)) => {
+        embedded_io_async::Write::$method($($argument),*).await
+    };
+    (with_stream($device:expr, |$stream:ident| -> $result:ty { $($body:tt)* })) => {
+        $device.with_stream(async |$stream| -> $result { $($body)* }).await
+    };
+    (; $async_operation:expr) => {
+        $async_operation.await
+    };
+    ($sync_operation:expr; $async_operation:expr) => {
+        $async_operation.await
+    };
+    ($operation:expr) => {
+        $operation.await
+    };
+}
+
 pub fn read_le_u16(bytes: &[u8], offset: usize) -> u16 {
     let mut value_bytes = [0; 2];
     value_bytes.copy_from_slice(&bytes[offset..offset + 2]);
@@ -218,6 +283,61 @@ mod tests {
         }
     }
 
+    mod sync_io {
+        use super::*;
+
+        #[test]
+        fn trait_call_routed_to_sync_trait() {
+            let mut reader: &[u8] = &[1, 2, 3];
+            let mut buffer = [0; 2];
+
+            let read_size = sync_io!(Read::read(&mut reader, &mut buffer)).unwrap();
+
+            assert_eq!(read_size, 2, "Bytes should be read");
+            assert_eq!(buffer, [1, 2], "Correct bytes should be read");
+        }
+
+        #[test]
+        fn paired_call_uses_sync_operation() {
+            let value = sync_io!(1; 2);
+
+            assert_eq!(value, 1, "Sync operation should be used");
+        }
+
+        #[test]
+        fn operation_used_as_is() {
+            assert_eq!(sync_io!(1), 1, "Operation should be used as is");
+        }
+    }
+
+    #[cfg(feature = "async")]
+    mod async_io {
+        use super::*;
+
+        #[tokio::test]
+        async fn trait_call_routed_to_async_trait() {
+            let mut reader: &[u8] = &[1, 2, 3];
+            let mut buffer = [0; 2];
+
+            let read_size = async_io!(Read::read(&mut reader, &mut buffer)).unwrap();
+
+            assert_eq!(read_size, 2, "Bytes should be read");
+            assert_eq!(buffer, [1, 2], "Correct bytes should be read");
+        }
+
+        #[tokio::test]
+        async fn paired_call_awaits_async_operation() {
+            let value = async_io!(async { 1 }; async { 2 });
+
+            assert_eq!(value, 2, "Async operation should be awaited");
+        }
+
+        #[tokio::test]
+        async fn operation_awaited() {
+            assert_eq!(async_io!(async { 1 }), 1, "Operation should be awaited");
+        }
+    }
+
     mod read_le_u16 {
         use super::*;
 
@@ -0,0 +1,88 @@
+use crate::directory_entry::SHORT_NAME_CHARACTER_COUNT;
+
+const LABEL_SEPARATOR: &str = ":/";
+
+/// The label a volume is known by, held by the volume label entry of its root directory.
+///
+/// Applications mounting several volumes can address them by label with paths such as
+/// `DATA:/cfg.ini`, stripping the label off with `strip_from` to find the volume a path belongs
+/// to.  Labels are read when `FileSystem::volume_label` is called, so they should be read again
+/// once a medium is swapped.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct VolumeLabel {
+    bytes: [u8; SHORT_NAME_CHARACTER_COUNT],
+}
+
+impl VolumeLabel {
+    pub(crate) fn new(bytes: [u8; SHORT_NAME_CHARACTER_COUNT]) -> Self {
+        Self { bytes }
+    }
+
+    /// The label's bytes without the spaces padding them.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.bytes.trim_ascii_end()
+    }
+
+    /// Whether the label is the given one ignoring ASCII case, since hosts store labels in upper
+    /// case.
+    pub fn matches(&self, label: &str) -> bool {
+        self.as_bytes().eq_ignore_ascii_case(label.as_bytes())
+    }
+
+    /// The path within the volume if the path is prefixed by the label followed by `:/`.
+    pub fn strip_from<'p>(&self, path: &'p str) -> Option<&'p str> {
+        let (label, path) = path.split_once(LABEL_SEPARATOR)?;
+
+        self.matches(label).then_some(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(bytes: &[u8; SHORT_NAME_CHARACTER_COUNT]) -> VolumeLabel {
+        VolumeLabel::new(*bytes)
+    }
+
+    mod as_bytes {
+        use super::*;
+
+        #[test]
+        fn padding_removed() {
+            assert_eq!(label(b"MY DATA    ").as_bytes(), b"MY DATA");
+        }
+    }
+
+    mod matches {
+        use super::*;
+
+        #[test]
+        fn case_ignored() {
+            assert!(label(b"DATA       ").matches("data"));
+            assert!(!label(b"DATA       ").matches("dat"));
+            assert!(!label(b"DATA       ").matches("DATA2"));
+        }
+    }
+
+    mod strip_from {
+        use super::*;
+
+        #[test]
+        fn prefixed_paths_stripped() {
+            let label = label(b"DATA       ");
+
+            assert_eq!(label.strip_from("DATA:/cfg.ini"), Some("cfg.ini"));
+            assert_eq!(label.strip_from("data:/logs/a.txt"), Some("logs/a.txt"));
+        }
+
+        #[test]
+        fn other_paths_rejected() {
+            let label = label(b"DATA       ");
+
+            assert_eq!(label.strip_from("LOGS:/cfg.ini"), None);
+            assert_eq!(label.strip_from("DATA/cfg.ini"), None);
+            assert_eq!(label.strip_from("cfg.ini"), None);
+        }
+    }
+}
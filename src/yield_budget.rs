@@ -0,0 +1,106 @@
+use core::future::poll_fn;
+use core::task::Poll;
+
+/// Suspends the current task once, immediately waking it so the executor can run other tasks
+/// before resuming it.
+pub(crate) async fn yield_now() {
+    let mut yielded = false;
+
+    poll_fn(|cx| {
+        if yielded {
+            return Poll::Ready(());
+        }
+
+        yielded = true;
+        cx.waker().wake_by_ref();
+
+        Poll::Pending
+    })
+    .await
+}
+
+/// Counts the steps of a long running async loop, yielding to the executor every `interval` of
+/// them.  An interval of zero never yields.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct YieldBudget {
+    interval: u32,
+    remaining: u32,
+}
+
+impl YieldBudget {
+    pub(crate) fn new(interval: u32) -> Self {
+        Self {
+            interval,
+            remaining: interval,
+        }
+    }
+
+    /// Records a step of the loop, yielding if the budget is exhausted.
+    pub(crate) async fn tick(&mut self) {
+        if self.interval == 0 {
+            return;
+        }
+
+        self.remaining -= 1;
+
+        if self.remaining == 0 {
+            self.remaining = self.interval;
+
+            yield_now().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::pin::pin;
+    use core::task::{Context, Waker};
+
+    /// Polls the future to completion, returning how many times it was pending.
+    fn count_pending<F>(future: F) -> usize
+    where
+        F: Future,
+    {
+        let mut future = pin!(future);
+        let mut context = Context::from_waker(Waker::noop());
+        let mut pending_count = 0;
+
+        while future.as_mut().poll(&mut context).is_pending() {
+            pending_count += 1;
+        }
+
+        pending_count
+    }
+
+    mod yield_now {
+        use super::*;
+
+        #[test]
+        fn pending_exactly_once() {
+            assert_eq!(count_pending(yield_now()), 1);
+        }
+    }
+
+    mod tick {
+        use super::*;
+
+        #[test]
+        fn yields_every_interval() {
+            let mut budget = YieldBudget::new(3);
+
+            let pending_counts: [usize; 6] = core::array::from_fn(|_| count_pending(budget.tick()));
+
+            assert_eq!(pending_counts, [0, 0, 1, 0, 0, 1]);
+        }
+
+        #[test]
+        fn zero_interval_never_yields() {
+            let mut budget = YieldBudget::new(0);
+
+            for _ in 0..10 {
+                assert_eq!(count_pending(budget.tick()), 0);
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use embedded_io::{ErrorType, Read as SyncRead, Seek as SyncSeek, SeekFrom, Write as SyncWrite};
+use std::io::{Cursor, Error, Read, Seek, Write};
+
+/// A writable stream over a copy of a disk image, leaving the image on disk untouched.
+#[derive(Debug)]
+pub struct MemoryStream {
+    cursor: Cursor<Vec<u8>>,
+}
+
+impl MemoryStream {
+    pub fn from_disk(file_name: &str) -> Self {
+        Self {
+            cursor: Cursor::new(std::fs::read(String::from("disks/") + file_name).unwrap()),
+        }
+    }
+
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self {
+            cursor: Cursor::new(bytes),
+        }
+    }
+}
+
+impl ErrorType for MemoryStream {
+    type Error = Error;
+}
+
+impl SyncRead for MemoryStream {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.cursor.read(buf)
+    }
+}
+
+impl SyncSeek for MemoryStream {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        self.cursor.seek(pos.into())
+    }
+}
+
+impl SyncWrite for MemoryStream {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.cursor.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.cursor.flush()
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_io_async::Read for MemoryStream {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.cursor.read(buf)
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_io_async::Seek for MemoryStream {
+    async fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        self.cursor.seek(pos.into())
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_io_async::Write for MemoryStream {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.cursor.write(buf)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.cursor.flush()
+    }
+}
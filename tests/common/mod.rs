@@ -1 +1,3 @@
+pub mod memory_stream;
 pub mod std_file;
+pub mod swappable_stream;
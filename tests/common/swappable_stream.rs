@@ -0,0 +1,80 @@
+use embedded_io::{ErrorType, Read, Seek, SeekFrom, Write};
+use std::cell::RefCell;
+use std::io::{Error, ErrorKind};
+use std::rc::Rc;
+
+/// A writable stream over a copy of a disk image which stays reachable through the returned
+/// handle, so tests can change the medium underneath a mounted file system.
+#[derive(Debug)]
+pub struct SwappableStream {
+    image: Rc<RefCell<Vec<u8>>>,
+    position: u64,
+}
+
+impl SwappableStream {
+    pub fn from_disk(file_name: &str) -> (Self, Rc<RefCell<Vec<u8>>>) {
+        let image = Rc::new(RefCell::new(
+            std::fs::read(String::from("disks/") + file_name).unwrap(),
+        ));
+
+        (
+            Self {
+                image: image.clone(),
+                position: 0,
+            },
+            image,
+        )
+    }
+}
+
+impl ErrorType for SwappableStream {
+    type Error = Error;
+}
+
+impl Read for SwappableStream {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let image = self.image.borrow();
+        let start = (self.position as usize).min(image.len());
+        let read = buf.len().min(image.len() - start);
+
+        buf[..read].copy_from_slice(&image[start..start + read]);
+        self.position += read as u64;
+
+        Ok(read)
+    }
+}
+
+impl Seek for SwappableStream {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => (self.image.borrow().len() as u64).checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+
+        self.position = position.ok_or(Error::from(ErrorKind::InvalidInput))?;
+
+        Ok(self.position)
+    }
+}
+
+impl Write for SwappableStream {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let mut image = self.image.borrow_mut();
+        let start = self.position as usize;
+        let end = start + buf.len();
+
+        if image.len() < end {
+            image.resize(end, 0);
+        }
+
+        image[start..end].copy_from_slice(buf);
+        self.position = end as u64;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
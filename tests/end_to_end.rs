@@ -1,74 +1,5185 @@
+#![cfg(feature = "sync")]
+
 mod common;
 
+use crate::common::memory_stream::MemoryStream;
 use crate::common::std_file::StdFile;
-use embedded_fat::{AllocationTableKind, FileSystemBuilder};
-use embedded_io::Read;
+use crate::common::swappable_stream::SwappableStream;
+use embedded_fat::{
+    AllocationTableKind, BootSector, CanonicalizeError, ConcatenatedFile, CopyError, Crc32,
+    DirChange, DirSnapshot, DirectoryItem, DirectoryItemFilter, DirectoryItemIterationError,
+    DirectoryQuota, FatDate, FatTime, FileError, FileSystem, FileSystemBuilder, FileSystemError,
+    HashError, Hasher, InvalidTimestampPolicy, Lines, ManifestError, ManifestMismatch,
+    MetadataError, MultiFileReader, MutationEvent, OperationError, PathCache, PrefetchingStream,
+    SearchError, ShortNameCase, SliceStream, SyncDevice, TimestampError,
+};
+use embedded_io::{Read, ReadExactError, ReadReady, Seek, SeekFrom, Write, WriteReady};
+use std::cell::{Cell, RefCell};
 use std::fs::File;
+use std::ops::ControlFlow;
 
 #[test]
+#[cfg(feature = "fat12")]
 fn fat12() {
     verify_disk("fat12.img", AllocationTableKind::Fat12);
 }
 
 #[test]
+#[cfg(feature = "fat16")]
 fn fat16() {
     verify_disk("fat16.img", AllocationTableKind::Fat16);
 }
 
 #[test]
+#[cfg(feature = "fat32")]
 fn fat32() {
     verify_disk("fat32.img", AllocationTableKind::Fat32);
 }
 
-fn verify_disk(file_name: &str, expected_allocation_table_kind: AllocationTableKind) {
-    let file_system = FileSystemBuilder::from_stream(StdFile::new(
-        File::open(String::from("disks/") + file_name).unwrap(),
-    ))
-    .build()
-    .expect("Opening disk works");
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_rename() {
+    verify_rename("fat12.img");
+}
 
-    assert_eq!(
-        file_system.allocation_table_kind(),
-        expected_allocation_table_kind
-    );
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_rename() {
+    verify_rename("fat16.img");
+}
 
-    {
-        let mut file = file_system
-            .open("TEST.TXT")
-            .expect("Opening a file with a basic short name works");
-        let mut bytes = [0; 5];
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_rename() {
+    verify_rename("fat32.img");
+}
 
-        file.read_exact(&mut bytes).unwrap();
-        assert_eq!(bytes, "test\n".as_bytes());
-    }
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_write_replace() {
+    verify_write_replace("fat12.img");
+}
 
-    {
-        let mut file = file_system
-            .open("long-File.name.txt")
-            .expect("Opening a file with a long name with matching casing works");
-        let mut bytes = [0; 9];
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_write_replace() {
+    verify_write_replace("fat16.img");
+}
 
-        file.read_exact(&mut bytes).unwrap();
-        assert_eq!(bytes, "much wow\n".as_bytes());
-    }
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_write_replace() {
+    verify_write_replace("fat32.img");
+}
 
-    {
-        let mut file = file_system
-            .open("long-file.name.txt")
-            .expect("Opening a file with a long name with wrong casing works");
-        let mut bytes = [0; 9];
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_tempfile() {
+    verify_tempfile("fat12.img");
+}
 
-        file.read_exact(&mut bytes).unwrap();
-        assert_eq!(bytes, "much wow\n".as_bytes());
-    }
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_tempfile() {
+    verify_tempfile("fat16.img");
+}
 
-    {
-        let mut file = file_system
-            .open("foo/bar.txt")
-            .expect("Opening a file in a subfolder works");
-        let mut bytes = [0; 7];
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_tempfile() {
+    verify_tempfile("fat32.img");
+}
 
-        file.read_exact(&mut bytes).unwrap();
-        assert_eq!(bytes, "redrum\n".as_bytes());
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_mutation_events() {
+    verify_mutation_events("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_mutation_events() {
+    verify_mutation_events("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_mutation_events() {
+    verify_mutation_events("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_dir_snapshot() {
+    verify_dir_snapshot("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_dir_snapshot() {
+    verify_dir_snapshot("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_dir_snapshot() {
+    verify_dir_snapshot("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_copy_if_newer() {
+    verify_copy_if_newer("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_copy_if_newer() {
+    verify_copy_if_newer("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_copy_if_newer() {
+    verify_copy_if_newer("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_dir_cursor() {
+    verify_dir_cursor("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_dir_cursor() {
+    verify_dir_cursor("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_dir_cursor() {
+    verify_dir_cursor("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_refresh() {
+    verify_refresh("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_refresh() {
+    verify_refresh("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_refresh() {
+    verify_refresh("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_hash() {
+    verify_hash("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_hash() {
+    verify_hash("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_hash() {
+    verify_hash("fat32.img");
+}
+
+#[test]
+#[cfg(all(feature = "fat12", feature = "dangerous"))]
+fn fat12_raw_cluster() {
+    verify_raw_cluster("fat12.img");
+}
+
+#[test]
+#[cfg(all(feature = "fat16", feature = "dangerous"))]
+fn fat16_raw_cluster() {
+    verify_raw_cluster("fat16.img");
+}
+
+#[test]
+#[cfg(all(feature = "fat32", feature = "dangerous"))]
+fn fat32_raw_cluster() {
+    verify_raw_cluster("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_find_all() {
+    verify_find_all("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_find_all() {
+    verify_find_all("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_find_all() {
+    verify_find_all("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_find() {
+    verify_find("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_find() {
+    verify_find("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_find() {
+    verify_find("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_dot_items() {
+    verify_dot_items("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_dot_items() {
+    verify_dot_items("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_dot_items() {
+    verify_dot_items("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_invalid_directory_entry() {
+    verify_invalid_directory_entry("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_invalid_directory_entry() {
+    verify_invalid_directory_entry("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_invalid_directory_entry() {
+    verify_invalid_directory_entry("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_write_past_end() {
+    verify_write_past_end("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_write_past_end() {
+    verify_write_past_end("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_write_past_end() {
+    verify_write_past_end("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_file_write_guard() {
+    verify_file_write_guard("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_file_write_guard() {
+    verify_file_write_guard("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_file_write_guard() {
+    verify_file_write_guard("fat32.img");
+}
+
+#[tokio::test]
+#[cfg(all(feature = "fat12", feature = "async"))]
+async fn fat12_file_write_guard_async() {
+    verify_file_write_guard_async("fat12.img").await;
+}
+
+#[tokio::test]
+#[cfg(all(feature = "fat16", feature = "async"))]
+async fn fat16_file_write_guard_async() {
+    verify_file_write_guard_async("fat16.img").await;
+}
+
+#[tokio::test]
+#[cfg(all(feature = "fat32", feature = "async"))]
+async fn fat32_file_write_guard_async() {
+    verify_file_write_guard_async("fat32.img").await;
+}
+
+#[test]
+#[cfg(all(feature = "fat12", feature = "dangerous"))]
+fn fat12_dir_entries() {
+    verify_dir_entries("fat12.img");
+}
+
+#[test]
+#[cfg(all(feature = "fat16", feature = "dangerous"))]
+fn fat16_dir_entries() {
+    verify_dir_entries("fat16.img");
+}
+
+#[test]
+#[cfg(all(feature = "fat32", feature = "dangerous"))]
+fn fat32_dir_entries() {
+    verify_dir_entries("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_canonicalize() {
+    verify_canonicalize("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_canonicalize() {
+    verify_canonicalize("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_canonicalize() {
+    verify_canonicalize("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_path_cache() {
+    verify_path_cache("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_path_cache() {
+    verify_path_cache("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_path_cache() {
+    verify_path_cache("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_geometry() {
+    verify_geometry("fat12.img", 16, 2);
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_geometry() {
+    verify_geometry("fat16.img", 32, 2);
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_geometry() {
+    verify_geometry("fat32.img", 32, 8);
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_suspend_resume() {
+    verify_suspend_resume("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_suspend_resume() {
+    verify_suspend_resume("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_suspend_resume() {
+    verify_suspend_resume("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_dir_entry_count() {
+    verify_dir_entry_count("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_dir_entry_count() {
+    verify_dir_entry_count("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_dir_entry_count() {
+    verify_dir_entry_count("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_name_collision() {
+    verify_name_collision("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_name_collision() {
+    verify_name_collision("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_name_collision() {
+    verify_name_collision("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_name_conflicts() {
+    verify_name_conflicts("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_name_conflicts() {
+    verify_name_conflicts("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_name_conflicts() {
+    verify_name_conflicts("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_allocation_table_prefetch() {
+    verify_allocation_table_prefetch("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_allocation_table_prefetch() {
+    verify_allocation_table_prefetch("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_allocation_table_prefetch() {
+    verify_allocation_table_prefetch("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_pinned_files() {
+    verify_pinned_files("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_pinned_files() {
+    verify_pinned_files("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_pinned_files() {
+    verify_pinned_files("fat32.img");
+}
+
+#[test]
+#[cfg(all(feature = "fat12", feature = "config"))]
+fn fat12_config() {
+    verify_config("fat12.img");
+}
+
+#[test]
+#[cfg(all(feature = "fat16", feature = "config"))]
+fn fat16_config() {
+    verify_config("fat16.img");
+}
+
+#[test]
+#[cfg(all(feature = "fat32", feature = "config"))]
+fn fat32_config() {
+    verify_config("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_latest() {
+    verify_latest("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_latest() {
+    verify_latest("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_latest() {
+    verify_latest("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_directory_quota() {
+    verify_directory_quota("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_directory_quota() {
+    verify_directory_quota("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_directory_quota() {
+    verify_directory_quota("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_evict_until_free() {
+    verify_evict_until_free("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_evict_until_free() {
+    verify_evict_until_free("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_evict_until_free() {
+    verify_evict_until_free("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_volume_label() {
+    verify_volume_label("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_volume_label() {
+    verify_volume_label("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_volume_label() {
+    verify_volume_label("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_revalidate() {
+    verify_revalidate("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_revalidate() {
+    verify_revalidate("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_revalidate() {
+    verify_revalidate("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_dir_change_detection() {
+    verify_dir_change_detection("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_dir_change_detection() {
+    verify_dir_change_detection("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_dir_change_detection() {
+    verify_dir_change_detection("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_entries_after_end_marker() {
+    verify_entries_after_end_marker("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_entries_after_end_marker() {
+    verify_entries_after_end_marker("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_entries_after_end_marker() {
+    verify_entries_after_end_marker("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_short_name_case() {
+    verify_short_name_case("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_short_name_case() {
+    verify_short_name_case("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_short_name_case() {
+    verify_short_name_case("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_timestamps() {
+    verify_timestamps("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_timestamps() {
+    verify_timestamps("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_timestamps() {
+    verify_timestamps("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_dump_tree() {
+    verify_dump_tree("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_dump_tree() {
+    verify_dump_tree("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_dump_tree() {
+    verify_dump_tree("fat32.img");
+}
+
+#[test]
+#[cfg(all(feature = "fat12", feature = "alloc"))]
+fn fat12_inventory() {
+    verify_inventory("fat12.img");
+}
+
+#[test]
+#[cfg(all(feature = "fat16", feature = "alloc"))]
+fn fat16_inventory() {
+    verify_inventory("fat16.img");
+}
+
+#[test]
+#[cfg(all(feature = "fat32", feature = "alloc"))]
+fn fat32_inventory() {
+    verify_inventory("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_verify_manifest() {
+    verify_manifest("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_verify_manifest() {
+    verify_manifest("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_verify_manifest() {
+    verify_manifest("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_allocation_table_statistics() {
+    verify_allocation_table_statistics("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_allocation_table_statistics() {
+    verify_allocation_table_statistics("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_allocation_table_statistics() {
+    verify_allocation_table_statistics("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_reserved_clusters() {
+    verify_reserved_clusters("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_reserved_clusters() {
+    verify_reserved_clusters("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_reserved_clusters() {
+    verify_reserved_clusters("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_fixed_files() {
+    verify_fixed_files("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_fixed_files() {
+    verify_fixed_files("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_fixed_files() {
+    verify_fixed_files("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_unused_regions() {
+    verify_unused_regions("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_unused_regions() {
+    verify_unused_regions("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_unused_regions() {
+    verify_unused_regions("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_metadata_backup() {
+    verify_metadata_backup("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_metadata_backup() {
+    verify_metadata_backup("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_metadata_backup() {
+    verify_metadata_backup("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_self_test() {
+    verify_self_test("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_self_test() {
+    verify_self_test("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_self_test() {
+    verify_self_test("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_reserved_entries_repair() {
+    verify_reserved_entries_repair("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_reserved_entries_repair() {
+    verify_reserved_entries_repair("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_reserved_entries_repair() {
+    verify_reserved_entries_repair("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_boot_sector_builder() {
+    verify_boot_sector_builder(AllocationTableKind::Fat12, 2880);
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_boot_sector_builder() {
+    verify_boot_sector_builder(AllocationTableKind::Fat16, 32768);
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_boot_sector_builder() {
+    verify_boot_sector_builder(AllocationTableKind::Fat32, 131_072);
+}
+
+#[test]
+#[cfg(all(feature = "fat12", feature = "fat16", feature = "fat32"))]
+fn fat12_fat32_conversion() {
+    verify_fat32_conversion("fat12.img");
+}
+
+#[test]
+#[cfg(all(feature = "fat16", feature = "fat32"))]
+fn fat16_fat32_conversion() {
+    verify_fat32_conversion("fat16.img");
+}
+
+#[test]
+#[cfg(all(feature = "fat16", feature = "fat32"))]
+fn fat32_fat32_conversion() {
+    verify_fat32_conversion("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_invalid_timestamp_policy() {
+    verify_invalid_timestamp_policy("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_invalid_timestamp_policy() {
+    verify_invalid_timestamp_policy("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_invalid_timestamp_policy() {
+    verify_invalid_timestamp_policy("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_extension_filter() {
+    verify_extension_filter("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_extension_filter() {
+    verify_extension_filter("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_extension_filter() {
+    verify_extension_filter("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_remove_where() {
+    verify_remove_where("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_remove_where() {
+    verify_remove_where("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_remove_where() {
+    verify_remove_where("fat32.img");
+}
+
+#[tokio::test]
+#[cfg(all(feature = "fat12", feature = "async"))]
+async fn fat12_write_past_end_async() {
+    verify_write_past_end_async("fat12.img").await;
+}
+
+#[tokio::test]
+#[cfg(all(feature = "fat16", feature = "async"))]
+async fn fat16_write_past_end_async() {
+    verify_write_past_end_async("fat16.img").await;
+}
+
+#[tokio::test]
+#[cfg(all(feature = "fat32", feature = "async"))]
+async fn fat32_write_past_end_async() {
+    verify_write_past_end_async("fat32.img").await;
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_multi_file_reader() {
+    verify_multi_file_reader("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_multi_file_reader() {
+    verify_multi_file_reader("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_multi_file_reader() {
+    verify_multi_file_reader("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_concatenated_file() {
+    verify_concatenated_file("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_concatenated_file() {
+    verify_concatenated_file("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_concatenated_file() {
+    verify_concatenated_file("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_file_slice() {
+    verify_file_slice("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_file_slice() {
+    verify_file_slice("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_file_slice() {
+    verify_file_slice("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_fs_info() {
+    verify_fs_info("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_fs_info() {
+    verify_fs_info("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_fs_info() {
+    verify_fs_info("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_buffer_size() {
+    verify_buffer_size("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_buffer_size() {
+    verify_buffer_size("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_buffer_size() {
+    verify_buffer_size("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn from_image() {
+    let image = std::fs::read("disks/fat12.img").expect("Reading the image works");
+    let file_system = FileSystem::from_image(&image).expect("Opening image works");
+    let mut file = file_system
+        .open("TEST.TXT")
+        .expect("Opening a file from an image works");
+    let mut bytes = [0; 5];
+
+    file.read_exact(&mut bytes).expect("Reading the file works");
+    assert_eq!(bytes, "test\n".as_bytes());
+}
+
+#[tokio::test]
+#[cfg(all(feature = "fat12", feature = "async"))]
+async fn from_image_async() {
+    use embedded_io_async::Read as AsyncRead;
+
+    let image = std::fs::read("disks/fat12.img").expect("Reading the image works");
+    let file_system = FileSystem::from_image_async(&image)
+        .await
+        .expect("Opening image works");
+    let mut file = file_system
+        .open_async("foo/bar.txt")
+        .await
+        .expect("Opening a file in a subfolder works");
+    let mut bytes = [0; 7];
+
+    AsyncRead::read_exact(&mut file, &mut bytes)
+        .await
+        .expect("Reading the file works");
+    assert_eq!(bytes, "redrum\n".as_bytes());
+}
+
+#[test]
+#[cfg(all(feature = "dyn-device", feature = "fat12", feature = "fat32"))]
+fn dyn_device() {
+    use embedded_fat::{AsciiOnlyEncoder, DynDevice, DynStream};
+
+    let mut memory_stream = MemoryStream::from_disk("fat12.img");
+    let mut std_file = StdFile::new(File::open("disks/fat32.img").unwrap());
+
+    let file_systems: [FileSystem<DynDevice<'_>, AsciiOnlyEncoder>; 2] = [
+        FileSystem::from_device(
+            DynDevice::new(DynStream::new(&mut memory_stream)),
+            AsciiOnlyEncoder,
+        )
+        .expect("Opening the in-memory disk works"),
+        FileSystem::from_device(
+            DynDevice::new(DynStream::new(&mut std_file)),
+            AsciiOnlyEncoder,
+        )
+        .expect("Opening the disk file works"),
+    ];
+
+    for file_system in &file_systems {
+        let mut file = file_system
+            .open("foo/bar.txt")
+            .expect("Opening a file through a dyn device works");
+        let mut bytes = [0; 7];
+
+        file.read_exact(&mut bytes).unwrap();
+        assert_eq!(bytes, "redrum\n".as_bytes());
+    }
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn from_device() {
+    use embedded_fat::{AsciiOnlyEncoder, SingleAccessDevice};
+
+    let file_system: FileSystem<SingleAccessDevice<MemoryStream>, AsciiOnlyEncoder> =
+        FileSystem::from_device(
+            SingleAccessDevice::new(MemoryStream::from_disk("fat12.img")),
+            AsciiOnlyEncoder,
+        )
+        .expect("Opening disk works");
+
+    assert!(file_system.open("TEST.TXT").is_some());
+}
+
+#[test]
+#[cfg(all(feature = "fat12", feature = "async"))]
+fn async_search_yields() {
+    let image = std::fs::read("disks/fat12.img").unwrap();
+    let count_pending = |yield_interval| {
+        let file_system = poll_to_completion(
+            FileSystemBuilder::from_stream(SliceStream::new(&image))
+                .with_yield_interval(yield_interval)
+                .build_async(),
+        )
+        .0
+        .expect("Opening image works");
+        let mut path_buffer = [0; 64];
+
+        poll_to_completion(file_system.find_all_async::<4, _, _>(
+            &mut path_buffer,
+            |_| true,
+            |_, _| ControlFlow::Continue(()),
+        ))
+        .1
+    };
+
+    assert!(count_pending(1) > 0, "Search should yield to the executor");
+    assert_eq!(count_pending(0), 0, "Search should never yield");
+}
+
+/// Polls the future with a no-op waker until it completes, returning its output along with how
+/// many times it was pending.
+#[cfg(feature = "async")]
+fn poll_to_completion<F>(future: F) -> (F::Output, usize)
+where
+    F: Future,
+{
+    let mut future = std::pin::pin!(future);
+    let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+    let mut pending_count = 0;
+
+    loop {
+        match future.as_mut().poll(&mut context) {
+            std::task::Poll::Ready(output) => return (output, pending_count),
+            std::task::Poll::Pending => pending_count += 1,
+        }
+    }
+}
+
+fn verify_disk(file_name: &str, expected_allocation_table_kind: AllocationTableKind) {
+    let file_system = FileSystemBuilder::from_stream(StdFile::new(
+        File::open(String::from("disks/") + file_name).unwrap(),
+    ))
+    .build()
+    .expect("Opening disk works");
+
+    assert_eq!(
+        file_system.allocation_table_kind(),
+        expected_allocation_table_kind
+    );
+
+    {
+        let mut file = file_system
+            .open("TEST.TXT")
+            .expect("Opening a file with a basic short name works");
+        let mut bytes = [0; 5];
+
+        assert_eq!(file.size(), 5);
+        assert!(!file.is_empty());
+        assert!(file.first_cluster_number() >= 2);
+        assert!(file.read_ready().unwrap());
+
+        file.read_exact(&mut bytes[..2]).unwrap();
+        assert_eq!(file.position(), 2);
+        assert_eq!(file.remaining(), 3);
+
+        file.read_exact(&mut bytes[2..]).unwrap();
+        assert_eq!(bytes, "test\n".as_bytes());
+        assert_eq!(file.remaining(), 0);
+        assert!(
+            file.read_ready().unwrap(),
+            "Files are ready to read at their end"
+        );
+    }
+
+    {
+        let mut file = file_system
+            .open("TEST.TXT")
+            .expect("Opening a file with a basic short name works");
+
+        assert!(matches!(
+            file.seek(SeekFrom::Start(6)),
+            Err(FileError::SeekPositionBeyondEnd(6))
+        ));
+        assert_eq!(file.seek(SeekFrom::End(0)).unwrap(), 5);
+
+        assert!(file.write_ready().unwrap());
+
+        let mut writer = file_system
+            .open_writer("TEST.TXT")
+            .expect("Opening a file for writing works");
+        let mut bytes = [0; 4];
+
+        assert_eq!(writer.seek(SeekFrom::Start(100)).unwrap(), 100);
+        assert_eq!(writer.file().position(), 100);
+        assert_eq!(
+            writer.read(&mut bytes).unwrap(),
+            0,
+            "Nothing is read past the end"
+        );
+
+        assert_eq!(writer.seek(SeekFrom::Current(-99)).unwrap(), 1);
+        writer.read_exact(&mut bytes).unwrap();
+        assert_eq!(bytes, "est\n".as_bytes());
+    }
+
+    {
+        let mut file = file_system
+            .open("TEST.TXT")
+            .expect("Opening a file with a basic short name works");
+        let mut bytes = [0; 3];
+
+        assert!(file.read_exact_or_eof(&mut bytes).unwrap());
+        assert_eq!(bytes, "tes".as_bytes());
+        assert!(matches!(
+            file.read_exact_or_eof(&mut bytes),
+            Err(ReadExactError::UnexpectedEof)
+        ));
+        assert!(
+            !file.read_exact_or_eof(&mut bytes).unwrap(),
+            "Reads at the end are reported as such"
+        );
+
+        #[cfg(feature = "async")]
+        {
+            let image = std::fs::read(format!("disks/{file_name}")).unwrap();
+            let async_file_system = poll_to_completion(
+                FileSystemBuilder::from_stream(SliceStream::new(&image)).build_async(),
+            )
+            .0
+            .expect("Opening image works");
+            let mut file = poll_to_completion(async_file_system.open_async("TEST.TXT"))
+                .0
+                .expect("Opening a file asynchronously works");
+
+            let (result, _) = poll_to_completion(file.read_exact_or_eof_async(&mut bytes));
+            assert!(result.unwrap());
+            let (result, _) = poll_to_completion(file.read_exact_or_eof_async(&mut bytes));
+            assert!(matches!(result, Err(ReadExactError::UnexpectedEof)));
+            let (result, _) = poll_to_completion(file.read_exact_or_eof_async(&mut bytes));
+            assert!(!result.unwrap());
+        }
+    }
+
+    {
+        let mut file = file_system
+            .open("long-File.name.txt")
+            .expect("Opening a file with a long name with matching casing works");
+        let mut bytes = [0; 9];
+
+        file.read_exact(&mut bytes).unwrap();
+        assert_eq!(bytes, "much wow\n".as_bytes());
+    }
+
+    {
+        let mut lines = Lines::<_, 16>::new(
+            file_system
+                .open("long-File.name.txt")
+                .expect("Opening a file with a long name with matching casing works"),
+        );
+
+        assert_eq!(lines.next_line().unwrap().unwrap(), "much wow");
+        assert!(lines.next_line().is_none());
+    }
+
+    {
+        let mut file = file_system
+            .open("long-file.name.txt")
+            .expect("Opening a file with a long name with wrong casing works");
+        let mut bytes = [0; 9];
+
+        file.read_exact(&mut bytes).unwrap();
+        assert_eq!(bytes, "much wow\n".as_bytes());
+    }
+
+    {
+        let mut file = file_system
+            .open("foo/bar.txt")
+            .expect("Opening a file in a subfolder works");
+        let mut bytes = [0; 7];
+
+        file.read_exact(&mut bytes).unwrap();
+        assert_eq!(bytes, "redrum\n".as_bytes());
+    }
+
+    {
+        let names = file_system
+            .read_dir("foo")
+            .expect("Listing a subfolder works")
+            .filter_map(Result::ok)
+            .map(|item| {
+                let short_name = item.short_name();
+
+                (
+                    short_name.base_name().to_vec(),
+                    short_name.extension().to_vec(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert!(names.contains(&(b"BAR".to_vec(), b"TXT".to_vec())));
+    }
+}
+
+fn verify_rename(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    let read_file = |path: &str, length: usize| {
+        let mut file = file_system.open(path)?;
+        let mut bytes = vec![0; length];
+
+        file.read_exact(&mut bytes).unwrap();
+        Some(bytes)
+    };
+
+    file_system
+        .rename("TEST.TXT", "A Longer Name.txt")
+        .expect("Renaming to a name needing more entries works");
+    assert_eq!(
+        read_file("A Longer Name.txt", 5),
+        Some("test\n".as_bytes().to_vec())
+    );
+    assert_eq!(
+        read_file("ALONGE~1.TXT", 5),
+        Some("test\n".as_bytes().to_vec())
+    );
+    assert_eq!(read_file("TEST.TXT", 5), None, "Old name is released");
+
+    file_system
+        .rename("long-File.name.txt", "SHORT.TXT")
+        .expect("Renaming to a name needing fewer entries works");
+    assert_eq!(
+        read_file("SHORT.TXT", 9),
+        Some("much wow\n".as_bytes().to_vec())
+    );
+    assert_eq!(
+        read_file("long-File.name.txt", 9),
+        None,
+        "Old name is released"
+    );
+
+    file_system
+        .rename("foo/bar.txt", "Bar.txt")
+        .expect("Renaming to a name differing by case works");
+    assert_eq!(
+        read_file("foo/bar.txt", 7),
+        Some("redrum\n".as_bytes().to_vec())
+    );
+
+    let root_names = file_system
+        .read_dir("")
+        .expect("Listing the root directory works")
+        .map(|item| {
+            let item = item.expect("Every root item is valid after renaming");
+
+            item.long_name()
+                .map(|long_name| long_name.chars().collect::<String>())
+        })
+        .collect::<Vec<_>>();
+    assert!(root_names.contains(&Some("A Longer Name.txt".to_owned())));
+
+    assert!(matches!(
+        file_system.rename("SHORT.TXT", "foo"),
+        Err(OperationError::AlreadyExists)
+    ));
+    assert!(matches!(
+        file_system.rename("missing.txt", "other.txt"),
+        Err(OperationError::NotFound)
+    ));
+    assert!(matches!(
+        file_system.rename("SHORT.TXT", "in/valid.txt"),
+        Err(OperationError::NameInvalid(_))
+    ));
+}
+
+fn verify_write_replace(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    let read_file = |path: &str| {
+        let mut file = file_system.open(path)?;
+        let mut bytes = Vec::new();
+        let mut buffer = [0; 512];
+
+        loop {
+            match file.read(&mut buffer).unwrap() {
+                0 => return Some(bytes),
+                read => bytes.extend_from_slice(&buffer[..read]),
+            }
+        }
+    };
+
+    file_system
+        .write_replace("TEST.TXT", "replaced\n".as_bytes())
+        .expect("Replacing an existing file works");
+    assert_eq!(
+        read_file("TEST.TXT"),
+        Some("replaced\n".as_bytes().to_vec())
+    );
+
+    let large_contents = (0..20_000).map(|index| index as u8).collect::<Vec<_>>();
+    file_system
+        .write_replace("foo/New File.bin", &large_contents)
+        .expect("Creating a file spanning several clusters works");
+    assert_eq!(read_file("foo/New File.bin"), Some(large_contents.clone()));
+
+    file_system
+        .write_replace("foo/New File.bin", &[])
+        .expect("Replacing with empty contents works");
+    assert_eq!(read_file("foo/New File.bin"), Some(Vec::new()));
+
+    file_system
+        .write_replace("long-File.name.txt", &large_contents)
+        .expect("Released clusters can be reused");
+    assert_eq!(read_file("long-File.name.txt"), Some(large_contents));
+    assert_eq!(
+        read_file("foo/bar.txt"),
+        Some("redrum\n".as_bytes().to_vec())
+    );
+
+    for directory_path in ["", "foo"] {
+        let short_names = file_system
+            .read_dir(directory_path)
+            .expect("Listing a directory works")
+            .map(|item| {
+                let item = item.expect("Every item is valid after replacing");
+
+                item.short_name().base_name().to_vec()
+            })
+            .collect::<Vec<_>>();
+
+        assert!(
+            !short_names.iter().any(|name| name.starts_with(b"~REPLA")),
+            "Temporary files are removed"
+        );
+    }
+
+    assert!(matches!(
+        file_system.write_replace("foo", &[]),
+        Err(OperationError::NotAFile)
+    ));
+    assert!(matches!(
+        file_system.write_replace("missing/file.txt", &[]),
+        Err(OperationError::NotFound)
+    ));
+}
+
+fn verify_tempfile(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    let contents = (0..7_000)
+        .map(|index| (index % 251) as u8)
+        .collect::<Vec<_>>();
+
+    {
+        let mut temp_file = file_system
+            .tempfile("foo")
+            .expect("Creating a temporary file works");
+
+        for chunk in contents.chunks(1_000) {
+            temp_file.append(chunk).expect("Appending works");
+        }
+        assert_eq!(temp_file.file_size(), 7_000);
+
+        let (temp_file, error) = temp_file
+            .persist("bar.txt")
+            .expect_err("Persisting over an existing item fails");
+        assert!(matches!(error, OperationError::AlreadyExists));
+
+        temp_file
+            .persist("firmware.bin")
+            .expect("Persisting under a new name works");
+    }
+
+    {
+        let mut file = file_system
+            .open("foo/firmware.bin")
+            .expect("Persisted file is kept");
+        let mut bytes = vec![0; contents.len()];
+
+        file.read_exact(&mut bytes).unwrap();
+        assert_eq!(bytes, contents);
+    }
+
+    {
+        let mut temp_file = file_system
+            .tempfile("")
+            .expect("Creating a temporary file in the root directory works");
+
+        temp_file.append(&contents).expect("Appending works");
+    }
+
+    let short_names = file_system
+        .read_dir("")
+        .expect("Listing the root directory works")
+        .map(|item| {
+            item.expect("Every item is valid after dropping a temporary file")
+                .short_name()
+                .base_name()
+                .to_vec()
+        })
+        .collect::<Vec<_>>();
+
+    assert!(
+        !short_names.iter().any(|name| name.starts_with(b"~STAGE")),
+        "Dropped temporary files are removed"
+    );
+}
+
+fn verify_mutation_events(file_name: &str) {
+    let events = RefCell::new(Vec::new());
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .on_mutation(|event| {
+            let event = match event {
+                MutationEvent::Created {
+                    directory_path,
+                    name,
+                    ..
+                } => format!("created {directory_path}/{name}"),
+                MutationEvent::Deleted {
+                    directory_path,
+                    name,
+                    ..
+                } => format!("deleted {directory_path}/{name}"),
+                MutationEvent::Renamed {
+                    directory_path,
+                    name,
+                    new_name,
+                    ..
+                } => format!("renamed {directory_path}/{name} to {new_name}"),
+                MutationEvent::WriteCompleted {
+                    directory_path,
+                    name,
+                    file_size,
+                    ..
+                } => format!("wrote {file_size} bytes to {directory_path}/{name}"),
+            };
+
+            events.borrow_mut().push(event);
+        })
+        .build()
+        .expect("Opening disk works");
+
+    file_system
+        .rename("foo/bar.txt", "baz.txt")
+        .expect("Renaming works");
+    file_system
+        .write_replace("TEST.TXT", "replaced\n".as_bytes())
+        .expect("Replacing an existing file works");
+    file_system
+        .write_replace("new.txt", "new\n".as_bytes())
+        .expect("Creating a file works");
+
+    {
+        let mut temp_file = file_system
+            .tempfile("foo")
+            .expect("Creating a temporary file works");
+
+        temp_file.append(&[1, 2, 3]).expect("Appending works");
+        temp_file
+            .persist("staged.bin")
+            .map_err(|(_, error)| error)
+            .expect("Persisting under a new name works");
+    }
+
+    {
+        file_system
+            .tempfile("")
+            .expect("Creating a temporary file works");
+    }
+
+    assert!(file_system.rename("missing.txt", "other.txt").is_err());
+
+    assert_eq!(
+        events.into_inner(),
+        [
+            "renamed foo/bar.txt to baz.txt",
+            "wrote 9 bytes to /TEST.TXT",
+            "created /new.txt",
+            "wrote 4 bytes to /new.txt",
+            "created foo/staged.bin",
+            "wrote 3 bytes to foo/staged.bin",
+        ]
+    );
+}
+
+fn verify_dir_snapshot(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    let capture = || {
+        DirSnapshot::<16>::capture(file_system.read_dir("").expect("Root directory exists"))
+            .expect("Capturing the root directory works")
+    };
+
+    let earlier = capture();
+    assert_eq!(
+        capture().changes_since(&earlier).count(),
+        0,
+        "Unchanged directory reports nothing"
+    );
+
+    file_system
+        .write_replace("TEST.TXT", "replaced\n".as_bytes())
+        .expect("Replacing an existing file works");
+    file_system
+        .write_replace("new.txt", "new\n".as_bytes())
+        .expect("Creating a file works");
+
+    let later = capture();
+    let changes = later.changes_since(&earlier).collect::<Vec<_>>();
+
+    assert_eq!(changes.len(), 2);
+    assert!(matches!(
+        changes[0],
+        DirChange::Modified { previous, current }
+            if previous.short_name().base_name() == b"TEST" && current.file_size() == 9
+    ));
+    assert!(matches!(
+        changes[1],
+        DirChange::Added(entry) if entry.short_name().base_name() == b"NEW"
+    ));
+}
+
+fn verify_copy_if_newer(file_name: &str) {
+    let source = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening source disk works");
+    let destination = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening destination disk works");
+
+    let large_contents = (0..20_000).map(|index| index as u8).collect::<Vec<_>>();
+    source
+        .write_replace("foo/large.bin", &large_contents)
+        .expect("Creating the source file works");
+
+    let read_file = |path: &str| {
+        let mut file = destination.open(path)?;
+        let mut bytes = Vec::new();
+        let mut buffer = [0; 512];
+
+        loop {
+            match file.read(&mut buffer).unwrap() {
+                0 => return Some(bytes),
+                read => bytes.extend_from_slice(&buffer[..read]),
+            }
+        }
+    };
+    let mut buffer = [0; 700];
+
+    assert!(
+        destination
+            .copy_if_newer(&source, "foo/large.bin", "copied.bin", &mut buffer)
+            .expect("Copying to a new file works"),
+        "Missing destination is copied"
+    );
+    assert_eq!(read_file("copied.bin"), Some(large_contents.clone()));
+
+    assert!(
+        !destination
+            .copy_if_newer(&source, "foo/large.bin", "copied.bin", &mut buffer)
+            .expect("Copying an unchanged file works"),
+        "Unchanged destination is skipped"
+    );
+
+    destination
+        .write_replace("copied.bin", "stale\n".as_bytes())
+        .expect("Replacing the destination works");
+    assert!(
+        destination
+            .copy_if_newer(&source, "foo/large.bin", "copied.bin", &mut buffer)
+            .expect("Copying over a file of a different size works"),
+        "Destination of a different size is copied over"
+    );
+    assert_eq!(read_file("copied.bin"), Some(large_contents));
+
+    let mut temp_file = destination
+        .tempfile("")
+        .expect("Creating a temporary file works");
+    temp_file.append(b"newer\n").expect("Appending works");
+    temp_file
+        .set_last_write(u16::MAX, 0)
+        .expect("Setting the last write timestamp works");
+    temp_file
+        .persist_replacing("copied.bin")
+        .map_err(|(_, error)| error)
+        .expect("Persisting over an existing file works");
+
+    assert!(
+        !destination
+            .copy_if_newer(&source, "foo/large.bin", "copied.bin", &mut buffer)
+            .expect("Copying an older file works"),
+        "Newer destination is skipped"
+    );
+    assert_eq!(read_file("copied.bin"), Some("newer\n".as_bytes().to_vec()));
+
+    assert!(matches!(
+        destination.copy_if_newer(&source, "missing.txt", "copied.bin", &mut buffer),
+        Err(CopyError::SourceNotFound)
+    ));
+    assert!(matches!(
+        destination.copy_if_newer(&source, "foo", "copied.bin", &mut buffer),
+        Err(CopyError::SourceNotAFile)
+    ));
+    assert!(matches!(
+        destination.copy_if_newer(&source, "TEST.TXT", "foo", &mut buffer),
+        Err(CopyError::DestinationError(OperationError::NotAFile))
+    ));
+    assert!(matches!(
+        destination.copy_if_newer(&source, "TEST.TXT", "copied.bin", &mut []),
+        Err(CopyError::BufferEmpty)
+    ));
+}
+
+fn verify_dir_cursor(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    let short_names = |dir: &mut embedded_fat::Dir<'_, _>| {
+        dir.map(|item| {
+            item.expect("Every item is valid")
+                .short_name()
+                .base_name()
+                .to_vec()
+        })
+        .collect::<Vec<_>>()
+    };
+
+    for directory_path in ["", "foo"] {
+        let mut dir = file_system
+            .read_dir(directory_path)
+            .expect("Directory exists");
+        let all_short_names = short_names(&mut dir);
+        assert!(all_short_names.len() >= 2);
+
+        dir.rewind();
+        dir.next();
+        let cursor = dir.cursor();
+        assert!(!cursor.is_end());
+
+        let mut resumed_dir = file_system
+            .read_dir(directory_path)
+            .expect("Directory exists");
+        resumed_dir.resume(cursor);
+        assert_eq!(
+            short_names(&mut resumed_dir),
+            all_short_names[1..],
+            "Resumed listing continues after the first item"
+        );
+
+        let end_cursor = resumed_dir.cursor();
+        assert!(end_cursor.is_end());
+
+        dir.resume(end_cursor);
+        assert!(dir.next().is_none(), "Listing resumed at the end is empty");
+    }
+
+    for directory_path in ["", "foo"] {
+        let mut dir = file_system
+            .read_dir(directory_path)
+            .expect("Directory exists");
+        let all_short_names = short_names(&mut dir);
+
+        for (index, short_name) in all_short_names.iter().enumerate().rev() {
+            let item = dir
+                .item_at(index)
+                .expect("Item exists")
+                .expect("Item is valid");
+
+            assert_eq!(item.short_name().base_name(), short_name.as_slice());
+            assert_eq!(
+                short_names(&mut dir),
+                all_short_names[index + 1..],
+                "Listing continues after the item"
+            );
+        }
+
+        assert!(dir.item_at(all_short_names.len()).is_none());
+    }
+}
+
+fn verify_refresh(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    let find_item = |directory_path: &str, base_name: &[u8]| {
+        file_system
+            .read_dir(directory_path)
+            .expect("Directory exists")
+            .map(|item| item.expect("Every item is valid"))
+            .find(|item| item.short_name().base_name() == base_name)
+            .expect("Item exists")
+    };
+
+    for (directory_path, name, base_name, entry_count) in [
+        ("", "TEST.TXT", b"TEST".as_slice(), 2),
+        ("foo", "bar.txt", b"BAR".as_slice(), 2),
+    ] {
+        let path = match directory_path {
+            "" => name.to_owned(),
+            _ => format!("{directory_path}/{name}"),
+        };
+        let mut item = find_item(directory_path, base_name);
+        let location = item.location().expect("Listed items have a location");
+        assert_eq!(
+            location.entry_count(),
+            entry_count,
+            "Long name entries are included"
+        );
+
+        file_system
+            .write_replace(&path, &[0; 3_000])
+            .expect("Replacing the file works");
+        assert_ne!(item.file_size(), 3_000);
+
+        item.refresh(&file_system).expect("Refreshing works");
+        assert_eq!(item.file_size(), 3_000);
+        assert_eq!(
+            item.first_cluster_number(),
+            find_item(directory_path, base_name).first_cluster_number()
+        );
+
+        file_system
+            .rename(&path, "A Much Longer Name.txt")
+            .expect("Renaming the file works");
+        assert!(matches!(
+            item.refresh(&file_system),
+            Err(OperationError::NotFound)
+        ));
+    }
+}
+
+fn verify_hash(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    let mut crc32 = Crc32::new();
+    file_system
+        .hash("TEST.TXT", &mut crc32)
+        .expect("Hashing a file works");
+    assert_eq!(crc32.finalize(), 0x3BB9_35C6);
+
+    let contents = (0..3_000).map(|index| index as u8).collect::<Vec<_>>();
+    file_system
+        .write_replace("foo/bar.txt", &contents)
+        .expect("Replacing the file works");
+
+    let mut expected_crc32 = Crc32::new();
+    expected_crc32.update(&contents);
+
+    let mut crc32 = Crc32::new();
+    file_system
+        .hash("foo/bar.txt", &mut crc32)
+        .expect("Hashing a file spanning several clusters works");
+    assert_eq!(crc32.finalize(), expected_crc32.finalize());
+
+    assert!(matches!(
+        file_system.hash("missing.txt", &mut Crc32::new()),
+        Err(HashError::NotFound)
+    ));
+    assert!(matches!(
+        file_system.hash("foo", &mut Crc32::new()),
+        Err(HashError::NotAFile)
+    ));
+}
+
+#[cfg(feature = "dangerous")]
+fn verify_raw_cluster(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    let bytes_per_cluster = file_system.bytes_per_cluster() as usize;
+    let last_cluster_number = file_system.last_cluster_number();
+    let cluster_number = file_system
+        .read_dir("")
+        .expect("Root directory exists")
+        .map(|item| item.expect("Every item is valid"))
+        .find(|item| item.short_name().base_name() == b"TEST")
+        .expect("Item exists")
+        .first_cluster_number();
+
+    let mut buffer = vec![0; bytes_per_cluster];
+    file_system
+        .read_cluster(cluster_number, &mut buffer)
+        .expect("Reading a file's cluster works");
+    assert_eq!(&buffer[..5], b"test\n");
+
+    buffer[..5].copy_from_slice(b"best\n");
+    file_system
+        .write_cluster(cluster_number, &buffer)
+        .expect("Writing a file's cluster works");
+
+    let mut bytes = [0; 5];
+    file_system
+        .open("TEST.TXT")
+        .expect("File exists")
+        .read_exact(&mut bytes)
+        .unwrap();
+    assert_eq!(&bytes, b"best\n", "The file layer sees the raw write");
+
+    for invalid_cluster_number in [0, 1, last_cluster_number + 1] {
+        assert!(matches!(
+            file_system.read_cluster(invalid_cluster_number, &mut buffer),
+            Err(OperationError::ClusterNumberInvalid { cluster_number })
+                if cluster_number == invalid_cluster_number
+        ));
+    }
+
+    assert!(matches!(
+        file_system.write_cluster(cluster_number, &buffer[1..]),
+        Err(OperationError::BufferSizeInvalid { expected }) if expected == bytes_per_cluster
+    ));
+}
+
+fn verify_find_all(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    let is_text_file =
+        |item: &DirectoryItem| item.is_file() && item.short_name().extension() == b"TXT";
+    let mut path_buffer = [0; 256];
+
+    let mut paths = Vec::new();
+    file_system
+        .find_all::<4, _, _>(&mut path_buffer, is_text_file, |path, item| {
+            assert!(
+                file_system.open(path).is_some(),
+                "Reported paths can be opened"
+            );
+            assert!(item.is_file());
+
+            paths.push(path.to_owned());
+            ControlFlow::Continue(())
+        })
+        .expect("Searching works");
+    paths.sort();
+    assert_eq!(paths, ["foo/BaR.tXt", "long-File.name.txt", "test.txt"]);
+
+    let mut paths = Vec::new();
+    file_system
+        .find_all::<1, _, _>(&mut path_buffer, is_text_file, |path, _| {
+            paths.push(path.to_owned());
+            ControlFlow::Continue(())
+        })
+        .expect("Searching only the root directory works");
+    paths.sort();
+    assert_eq!(paths, ["long-File.name.txt", "test.txt"]);
+
+    let mut match_count = 0;
+    file_system
+        .find_all::<4, _, _>(&mut path_buffer, is_text_file, |_, _| {
+            match_count += 1;
+            ControlFlow::Break(())
+        })
+        .expect("Ending the search early works");
+    assert_eq!(match_count, 1);
+
+    assert_eq!(
+        file_system.find_all::<4, _, _>(&mut [0; 8], is_text_file, |_, _| {
+            ControlFlow::Continue(())
+        }),
+        Err(SearchError::PathBufferTooSmall)
+    );
+}
+
+fn verify_find(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    let find_base_names = |filter: DirectoryItemFilter| {
+        let mut base_names = file_system
+            .find::<4>(filter)
+            .map(|item| item.short_name().base_name().to_vec())
+            .collect::<Vec<_>>();
+        base_names.sort();
+
+        base_names
+    };
+
+    assert_eq!(
+        find_base_names(
+            DirectoryItemFilter::builder()
+                .extension("txt")
+                .files_only(true)
+                .build()
+        ),
+        [b"BAR".to_vec(), b"LONG-F~1".to_vec(), b"TEST".to_vec()]
+    );
+    assert_eq!(
+        find_base_names(DirectoryItemFilter::builder().min_size(6).build()),
+        [b"BAR".to_vec(), b"LONG-F~1".to_vec()]
+    );
+    assert_eq!(
+        find_base_names(
+            DirectoryItemFilter::builder()
+                .max_size(5)
+                .files_only(true)
+                .build()
+        ),
+        [b"TEST".to_vec()]
+    );
+
+    file_system
+        .write_replace("TEST.TXT", b"newer\n")
+        .expect("Replacing the file works");
+    let test_item = file_system
+        .find::<1>(
+            DirectoryItemFilter::builder()
+                .min_size(6)
+                .max_size(6)
+                .build(),
+        )
+        .next()
+        .expect("Replaced file is found");
+    let (date, time) = (test_item.last_write_date(), test_item.last_write_time());
+
+    assert!(
+        file_system
+            .find::<4>(
+                DirectoryItemFilter::builder()
+                    .modified_since(date, time)
+                    .build()
+            )
+            .all(|item| (item.last_write_date(), item.last_write_time()) >= (date, time))
+    );
+}
+
+fn verify_dot_items(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    let items = file_system
+        .read_dir("foo")
+        .expect("Directory exists")
+        .map(|item| item.expect("Every item is valid"))
+        .collect::<Vec<_>>();
+    assert!(items[0].is_dot());
+    assert!(items[1].is_dotdot());
+    assert!(!items[2].is_dot() && !items[2].is_dotdot());
+
+    let mut dir = file_system
+        .read_dir("foo")
+        .expect("Directory exists")
+        .with_dot_items_skipped(true);
+    let base_names = dir
+        .by_ref()
+        .map(|item| {
+            item.expect("Every item is valid")
+                .short_name()
+                .base_name()
+                .to_vec()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(base_names, [b"BAR".to_vec()]);
+
+    let item = dir.item_at(0).expect("Item exists").expect("Item is valid");
+    assert_eq!(
+        item.short_name().base_name(),
+        b"BAR",
+        "Indexes skip the dot items"
+    );
+}
+
+fn verify_invalid_directory_entry(file_name: &str) {
+    let mut image = std::fs::read(String::from("disks/") + file_name).unwrap();
+    let entry_offset = image
+        .windows(11)
+        .position(|name| name == b"TEST    TXT")
+        .expect("Image contains TEST.TXT");
+
+    // A first cluster number of zero with a non-zero file size makes the entry invalid
+    image[entry_offset + 20..entry_offset + 22].fill(0);
+    image[entry_offset + 26..entry_offset + 28].fill(0);
+
+    let invalid_entry_count = Cell::new(0);
+    let file_system = FileSystemBuilder::from_stream(SliceStream::new(&image))
+        .on_invalid_directory_entry(|_| {
+            invalid_entry_count.set(invalid_entry_count.get() + 1);
+            ControlFlow::Continue(())
+        })
+        .build()
+        .expect("Opening disk works");
+
+    assert!(file_system.open("TEST.TXT").is_none());
+    assert!(
+        file_system.open("long-File.name.txt").is_some(),
+        "Items after the invalid entry are still found"
+    );
+    assert_eq!(invalid_entry_count.get(), 2);
+
+    let invalid_entry_count = Cell::new(0);
+    let file_system = FileSystemBuilder::from_stream(SliceStream::new(&image))
+        .on_invalid_directory_entry(|_| {
+            invalid_entry_count.set(invalid_entry_count.get() + 1);
+            ControlFlow::Break(())
+        })
+        .build()
+        .expect("Opening disk works");
+
+    assert!(
+        file_system.open("long-File.name.txt").is_none(),
+        "Lookups stop at the invalid entry"
+    );
+
+    let mut paths = Vec::new();
+    file_system
+        .find_all::<4, _, _>(
+            &mut [0; 256],
+            |_| true,
+            |path, _| {
+                paths.push(path.to_owned());
+                ControlFlow::Continue(())
+            },
+        )
+        .expect("Stopping the search works");
+    assert!(!paths.contains(&String::from("long-File.name.txt")));
+
+    assert_eq!(
+        file_system
+            .find::<4>(DirectoryItemFilter::builder().build())
+            .filter(|item| item.short_name().base_name() == b"LONG-F~1")
+            .count(),
+        0
+    );
+    assert_eq!(invalid_entry_count.get(), 3);
+}
+
+fn verify_write_past_end(file_name: &str) {
+    // Bytes a previous host left behind the end of the file in its last cluster
+    let mut image = std::fs::read(format!("disks/{file_name}")).unwrap();
+    let contents_position = image
+        .windows(5)
+        .position(|window| window == b"test\n")
+        .unwrap();
+    image[contents_position + 5..][..4].copy_from_slice(b"XXXX");
+
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_bytes(image))
+        .build()
+        .expect("Opening disk works");
+
+    assert!(matches!(
+        file_system.open("TEST.TXT").unwrap().write(b"no"),
+        Err(FileError::NotOpenForWriting)
+    ));
+
+    let mut writer = file_system.open_writer("TEST.TXT").unwrap();
+    assert_eq!(writer.file().size(), 5);
+
+    writer.seek(SeekFrom::Start(12)).unwrap();
+    assert_eq!(writer.write(b"!").unwrap(), 1);
+    assert_eq!(writer.file().size(), 13);
+    assert_eq!(writer.file().position(), 13);
+
+    let mut bytes = [0xFF; 13];
+    writer.seek(SeekFrom::Start(0)).unwrap();
+    writer.read_exact(&mut bytes).unwrap();
+    assert_eq!(&bytes, b"test\n\0\0\0\0\0\0\0!");
+
+    // Spans several clusters on each of the disks
+    let end_position = 5000;
+    writer.seek(SeekFrom::Start(end_position)).unwrap();
+    writer.write_all(b"end").unwrap();
+    assert_eq!(writer.file().size(), end_position + 3);
+
+    let mut bytes = vec![0xFF; end_position as usize - 13];
+    writer.seek(SeekFrom::Start(13)).unwrap();
+    writer.read_exact(&mut bytes).unwrap();
+    assert!(
+        bytes.iter().all(|byte| *byte == 0),
+        "Gaps spanning new clusters read as zeros"
+    );
+
+    let mut bytes = [0; 3];
+    writer.read_exact(&mut bytes).unwrap();
+    assert_eq!(&bytes, b"end");
+    assert_eq!(writer.file().remaining(), 0);
+
+    writer.close().expect("Closing the guard works");
+    assert_eq!(
+        file_system.open("TEST.TXT").unwrap().size(),
+        end_position + 3,
+        "Closing the guard records the new size"
+    );
+}
+
+fn verify_file_write_guard(file_name: &str) {
+    let written_sizes = RefCell::new(Vec::new());
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .on_mutation(|event| {
+            if let MutationEvent::WriteCompleted { file_size, .. } = event {
+                written_sizes.borrow_mut().push(file_size);
+            }
+        })
+        .build()
+        .expect("Opening disk works");
+
+    {
+        let mut writer = file_system.open_writer("TEST.TXT").unwrap();
+
+        assert!(!writer.is_dirty());
+        writer.seek(SeekFrom::End(0)).unwrap();
+        writer.write_all(b"more\n").unwrap();
+        assert!(writer.is_dirty());
+    }
+
+    let mut file = file_system.open("TEST.TXT").unwrap();
+    let mut bytes = [0; 10];
+    assert_eq!(file.size(), 10, "Dropping the guard records the new size");
+    file.read_exact(&mut bytes).unwrap();
+    assert_eq!(&bytes, b"test\nmore\n");
+
+    let mut writer = file_system.open_writer("long-File.name.txt").unwrap();
+    writer.write_all(b"MUCH").unwrap();
+    writer.flush().unwrap();
+    assert!(!writer.is_dirty());
+    writer.close().expect("Closing a flushed guard works");
+
+    let mut file = file_system
+        .open("long-File.name.txt")
+        .expect("The long name is kept");
+    let mut bytes = [0; 9];
+    file.read_exact(&mut bytes).unwrap();
+    assert_eq!(&bytes, b"MUCH wow\n");
+
+    assert_eq!(*written_sizes.borrow(), [10, 9]);
+
+    assert!(matches!(
+        file_system.open_writer("foo"),
+        Err(OperationError::NotAFile)
+    ));
+    assert!(matches!(
+        file_system.open_writer("missing.txt"),
+        Err(OperationError::NotFound)
+    ));
+}
+
+#[cfg(feature = "async")]
+async fn verify_file_write_guard_async(file_name: &str) {
+    use embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek, Write as AsyncWrite};
+
+    let written_sizes = RefCell::new(Vec::new());
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .on_mutation(|event| {
+            if let MutationEvent::WriteCompleted { file_size, .. } = event {
+                written_sizes.borrow_mut().push(file_size);
+            }
+        })
+        .with_quota_policy(|directory_path| {
+            (directory_path == "foo").then(|| DirectoryQuota::new().with_max_bytes(64))
+        })
+        .build_async()
+        .await
+        .expect("Opening disk works");
+
+    // Spans several clusters on each of the disks
+    let contents = (0..5000).map(|index| index as u8).collect::<Vec<_>>();
+    let mut writer = file_system
+        .open_writer_async("TEST.TXT")
+        .await
+        .expect("Opening a writer works");
+
+    assert!(!writer.is_dirty());
+    AsyncSeek::seek(&mut writer, SeekFrom::End(0))
+        .await
+        .expect("Seeking works");
+    AsyncWrite::write_all(&mut writer, &contents)
+        .await
+        .expect("Writing works");
+    assert!(writer.is_dirty());
+    writer.close().await.expect("Closing the guard works");
+
+    // foo/bar.txt already holds 7 bytes
+    let mut writer = file_system
+        .open_writer_async("foo/bar.txt")
+        .await
+        .expect("Opening a writer works");
+
+    AsyncSeek::seek(&mut writer, SeekFrom::End(0))
+        .await
+        .expect("Seeking works");
+    assert!(matches!(
+        AsyncWrite::write(&mut writer, &[b'b'; 58]).await,
+        Err(FileError::QuotaExceeded)
+    ));
+    assert!(!writer.is_dirty(), "Refused write writes nothing");
+    AsyncWrite::write_all(&mut writer, &[b'b'; 57])
+        .await
+        .expect("Writing within the quota works");
+    writer.close().await.expect("Closing the guard works");
+
+    assert!(matches!(
+        file_system.open_writer_async("foo").await,
+        Err(OperationError::NotAFile)
+    ));
+    assert!(matches!(
+        file_system.open_writer_async("missing.txt").await,
+        Err(OperationError::NotFound)
+    ));
+    assert_eq!(*written_sizes.borrow(), [5005, 64]);
+
+    // Only what reached the directory entries and allocation table survives remounting
+    let Ok(suspended) = file_system.suspend_async().await else {
+        panic!("Suspending should work");
+    };
+    let Ok(file_system) = suspended.resume_async().await else {
+        panic!("Resuming a valid volume should work");
+    };
+
+    let mut file = file_system
+        .open_async("TEST.TXT")
+        .await
+        .expect("Opening a file works");
+    assert_eq!(file.size(), 5005, "Closing the guard records the new size");
+
+    let mut bytes = vec![0; 5005];
+    AsyncRead::read_exact(&mut file, &mut bytes)
+        .await
+        .expect("Reading the file works");
+    assert_eq!(&bytes[..5], b"test\n");
+    assert_eq!(&bytes[5..], contents);
+
+    let mut file = file_system
+        .open_async("foo/bar.txt")
+        .await
+        .expect("Opening a file works");
+    let mut bytes = [0; 64];
+    AsyncRead::read_exact(&mut file, &mut bytes)
+        .await
+        .expect("Reading the file works");
+    assert_eq!(bytes[7..], [b'b'; 57]);
+}
+
+#[cfg(feature = "dangerous")]
+fn verify_dir_entries(file_name: &str) {
+    use embedded_fat::DirectoryEntry;
+
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+    let dir = file_system.read_dir("").expect("Root directory exists");
+
+    let long_name_item_location = file_system
+        .read_dir("")
+        .unwrap()
+        .map(|item| item.expect("Every item is valid"))
+        .find(|item| item.long_name().is_some())
+        .and_then(|item| item.location())
+        .expect("An item with a long name exists");
+    let (first_long_name_location, _) = dir
+        .entries()
+        .map(|entry| entry.expect("Every entry is valid"))
+        .find(|(_, entry)| matches!(entry, DirectoryEntry::LongName(_)))
+        .expect("A long name entry exists");
+    assert_eq!(
+        first_long_name_location.position(),
+        long_name_item_location.position(),
+        "Items start at their first long name entry"
+    );
+
+    let (location, mut entry_bytes) = dir
+        .entries()
+        .map(|entry| entry.expect("Every entry is valid"))
+        .find_map(|(location, entry)| match entry {
+            DirectoryEntry::ShortName(entry) if entry.name().base_name() == b"TEST" => {
+                Some((location, entry.to_bytes()))
+            }
+            _ => None,
+        })
+        .expect("The short name entry exists");
+    assert_eq!(location.entry_count(), 1);
+
+    entry_bytes[28..32].copy_from_slice(&2u32.to_le_bytes());
+    dir.write_entries(location, &[entry_bytes])
+        .expect("Writing the examined entry works");
+
+    let mut file = file_system.open("TEST.TXT").unwrap();
+    let mut bytes = [0; 4];
+    assert_eq!(file.size(), 2);
+    assert_eq!(file.read(&mut bytes).unwrap(), 2);
+    assert_eq!(&bytes[..2], b"te");
+}
+
+fn verify_canonicalize(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+    #[cfg(feature = "async")]
+    let image = std::fs::read(format!("disks/{file_name}")).unwrap();
+    #[cfg(feature = "async")]
+    let async_file_system =
+        poll_to_completion(FileSystemBuilder::from_stream(SliceStream::new(&image)).build_async())
+            .0
+            .expect("Opening image works");
+    let mut path_buffer = [0; 64];
+
+    let cases = [
+        ("long-file.NAME.txt", Ok("long-File.name.txt")),
+        ("/./FOO/../foo//BAR.TXT", Ok("foo/BaR.tXt")),
+        ("../test.txt", Ok("test.txt")),
+        ("foo/..", Ok("")),
+        ("", Ok("")),
+        (
+            "foo/missing.txt",
+            Err(CanonicalizeError::NotFound {
+                component_offset: 4,
+            }),
+        ),
+        (
+            "./missing/bar.txt",
+            Err(CanonicalizeError::NotFound {
+                component_offset: 2,
+            }),
+        ),
+        (
+            "test.txt/..",
+            Err(CanonicalizeError::NotADirectory {
+                component_offset: 9,
+            }),
+        ),
+    ];
+
+    for (path, expected) in cases {
+        assert_eq!(
+            file_system.canonicalize(path, &mut path_buffer),
+            expected,
+            "{path:?} canonicalizes as expected"
+        );
+
+        #[cfg(feature = "async")]
+        assert_eq!(
+            poll_to_completion(async_file_system.canonicalize_async(path, &mut path_buffer)).0,
+            expected,
+            "{path:?} canonicalizes asynchronously as expected"
+        );
+    }
+
+    assert_eq!(
+        file_system.canonicalize("foo/bar.txt", &mut [0; 6]),
+        Err(CanonicalizeError::PathBufferTooSmall)
+    );
+}
+
+fn verify_path_cache(file_name: &str) {
+    let cache = RefCell::new(PathCache::<2>::new());
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .on_mutation(|event| cache.borrow_mut().invalidate(&event))
+        .build()
+        .expect("Opening disk works");
+
+    let mut file = file_system
+        .open_cached(&mut cache.borrow_mut(), "foo/bar.txt")
+        .unwrap();
+    let mut bytes = [0; 7];
+    file.read_exact(&mut bytes).unwrap();
+    assert_eq!(&bytes, b"redrum\n");
+
+    let paths: Vec<_> = cache
+        .borrow()
+        .entries()
+        .map(|entry| entry.path().to_owned())
+        .collect();
+    assert_eq!(paths, ["foo/BaR.tXt"], "Items are cached by canonical path");
+
+    let mut file = file_system
+        .open_cached(&mut cache.borrow_mut(), "./FOO/bar.TXT")
+        .expect("Equivalent paths are found in the cache");
+    file.read_exact(&mut bytes).unwrap();
+    assert_eq!(&bytes, b"redrum\n");
+    assert_eq!(cache.borrow().len(), 1);
+
+    file_system
+        .open_cached(&mut cache.borrow_mut(), "TEST.TXT")
+        .unwrap();
+    file_system
+        .open_cached(&mut cache.borrow_mut(), "long-File.name.txt")
+        .unwrap();
+    assert_eq!(cache.borrow().len(), 2);
+    assert!(
+        cache
+            .borrow()
+            .entries()
+            .all(|entry| entry.path() != "foo/BaR.tXt"),
+        "The least recently used item is dropped"
+    );
+
+    {
+        let mut writer = file_system.open_writer("TEST.TXT").unwrap();
+        writer.seek(SeekFrom::End(0)).unwrap();
+        writer.write_all(b"more\n").unwrap();
+    }
+
+    assert_eq!(cache.borrow().len(), 1, "Written items are invalidated");
+    let file = file_system
+        .open_cached(&mut cache.borrow_mut(), "TEST.TXT")
+        .unwrap();
+    assert_eq!(file.size(), 10);
+
+    assert!(
+        file_system
+            .open_cached(&mut cache.borrow_mut(), "foo")
+            .is_none()
+    );
+    assert!(
+        file_system
+            .open_cached(&mut cache.borrow_mut(), "missing.txt")
+            .is_none()
+    );
+}
+
+fn verify_geometry(file_name: &str, expected_sectors_per_track: u16, expected_head_count: u16) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    assert_eq!(&file_system.oem_name(), b"mkfs.fat");
+    assert_eq!(file_system.jump_code()[0], 0xEB);
+    assert_eq!(file_system.media_type(), 0xF8);
+    assert_eq!(file_system.sectors_per_track(), expected_sectors_per_track);
+    assert_eq!(file_system.head_count(), expected_head_count);
+    assert_eq!(file_system.hidden_sector_count(), 0);
+}
+
+fn verify_suspend_resume(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+    assert!(!file_system.is_remounted());
+
+    let suspended = file_system.suspend().expect("Suspending works");
+
+    // The host leaves the allocation table inconsistent with the boot sector
+    let allocation_table_address = suspended
+        .device()
+        .with_stream(|stream| {
+            let mut reserved_sector_count = [0; 2];
+            stream.seek(SeekFrom::Start(14)).unwrap();
+            stream.read_exact(&mut reserved_sector_count).unwrap();
+
+            let allocation_table_address = u16::from_le_bytes(reserved_sector_count) as u64 * 512;
+            stream
+                .seek(SeekFrom::Start(allocation_table_address))
+                .unwrap();
+            stream.write_all(&[0xF0]).unwrap();
+
+            allocation_table_address
+        })
+        .unwrap();
+
+    let Err((suspended, error)) = suspended.resume() else {
+        panic!("Resuming an inconsistent volume should fail");
+    };
+    assert!(matches!(
+        error,
+        FileSystemError::AllocationTableMediaTypeMismatch
+    ));
+
+    // The host repairs the volume before handing it back
+    suspended
+        .device()
+        .with_stream(|stream| {
+            stream
+                .seek(SeekFrom::Start(allocation_table_address))
+                .unwrap();
+            stream.write_all(&[0xF8]).unwrap();
+        })
+        .unwrap();
+
+    let Ok(file_system) = suspended.resume() else {
+        panic!("Resuming a valid volume should work");
+    };
+    assert!(file_system.is_remounted());
+
+    let mut file = file_system.open("TEST.TXT").unwrap();
+    let mut bytes = [0; 5];
+    file.read_exact(&mut bytes).unwrap();
+    assert_eq!(&bytes, b"test\n");
+}
+
+fn verify_dir_entry_count(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    for directory_path in ["", "foo"] {
+        let mut dir = file_system
+            .read_dir(directory_path)
+            .unwrap()
+            .with_dot_items_skipped(true);
+        dir.next().unwrap().unwrap();
+
+        let entry_count = dir.entry_count().unwrap();
+        let (_, upper_bound) = dir.size_hint();
+        let remaining_items: Vec<_> = dir.by_ref().map(Result::unwrap).collect();
+
+        assert_eq!(
+            entry_count,
+            remaining_items.len() + 1,
+            "Counting doesn't move the listing"
+        );
+        assert!(upper_bound.is_none_or(|upper_bound| upper_bound >= remaining_items.len()));
+        assert_eq!(dir.size_hint(), (0, Some(0)));
+    }
+
+    #[cfg(feature = "async")]
+    {
+        let image = std::fs::read(format!("disks/{file_name}")).unwrap();
+        let async_file_system = poll_to_completion(
+            FileSystemBuilder::from_stream(SliceStream::new(&image))
+                .with_yield_interval(1)
+                .build_async(),
+        )
+        .0
+        .expect("Opening image works");
+        let dir = poll_to_completion(async_file_system.read_dir_async("foo"))
+            .0
+            .unwrap();
+
+        let (entry_count, pending_count) = poll_to_completion(dir.entry_count_async());
+
+        assert_eq!(
+            entry_count.unwrap(),
+            file_system.read_dir("foo").unwrap().entry_count().unwrap()
+        );
+        assert!(pending_count > 0, "Counting yields to the executor");
+    }
+}
+
+fn verify_name_collision(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+    let long_named_file = file_system.open("long-File.name.txt").unwrap();
+
+    let Err(OperationError::NameCollision { existing }) =
+        file_system.rename("TEST.TXT", "LONG-F~1.TXT")
+    else {
+        panic!("Renaming to another item's alias should collide");
+    };
+    assert_eq!(existing.name().base_name(), b"LONG-F~1");
+    assert_eq!(
+        existing.first_cluster_number(),
+        long_named_file.first_cluster_number()
+    );
+    assert_eq!(existing.extended_file_size(), long_named_file.size());
+
+    let temp_file = file_system.tempfile("foo").unwrap();
+    let (temp_file, error) = temp_file
+        .persist("BAR.TXT")
+        .expect_err("Persisting under a name differing by case should collide");
+    let OperationError::NameCollision { existing } = error else {
+        panic!("A short name collision should be reported, got {error:?}");
+    };
+    assert_eq!(existing.name().base_name(), b"BAR");
+    assert_eq!(existing.file_size(), 7);
+
+    let (_, error) = temp_file
+        .persist("bar.txt")
+        .expect_err("Persisting under an existing long name should fail");
+    assert!(matches!(error, OperationError::AlreadyExists));
+}
+
+fn verify_name_conflicts(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+    let mut conflict_count = 0;
+
+    for directory_path in ["", "foo"] {
+        file_system
+            .find_name_conflicts(directory_path, |_| {
+                conflict_count += 1;
+
+                ControlFlow::Continue(())
+            })
+            .expect("Scanning works");
+    }
+    assert_eq!(conflict_count, 0, "Disks written by mkfs have no conflicts");
+
+    #[cfg(feature = "async")]
+    {
+        let image = std::fs::read(format!("disks/{file_name}")).unwrap();
+        let async_file_system = poll_to_completion(
+            FileSystemBuilder::from_stream(SliceStream::new(&image)).build_async(),
+        )
+        .0
+        .expect("Opening image works");
+
+        let (result, _) = poll_to_completion(
+            async_file_system.find_name_conflicts_async("", |_| ControlFlow::Continue(())),
+        );
+        result.expect("Scanning asynchronously works");
+    }
+
+    assert!(matches!(
+        file_system.find_name_conflicts("missing", |_| ControlFlow::Continue(())),
+        Err(OperationError::NotFound)
+    ));
+
+    #[cfg(feature = "dangerous")]
+    {
+        use embedded_fat::{DirectoryEntry, NameConflict};
+
+        // Give TEST.TXT the short name of the long named file, as a faulty writer might
+        let dir = file_system.read_dir("").unwrap();
+        let (location, mut entry_bytes) = dir
+            .entries()
+            .map(|entry| entry.expect("Every entry is valid"))
+            .find_map(|(location, entry)| match entry {
+                DirectoryEntry::ShortName(entry) if entry.name().base_name() == b"TEST" => {
+                    Some((location, entry.to_bytes()))
+                }
+                _ => None,
+            })
+            .expect("The short name entry exists");
+        entry_bytes[..11].copy_from_slice(b"LONG-F~1TXT");
+        dir.write_entries(location, &[entry_bytes]).unwrap();
+
+        let mut conflicting_sizes = Vec::new();
+        file_system
+            .find_name_conflicts("", |conflict| {
+                let NameConflict::ShortNames { first, second } = conflict else {
+                    panic!("Only the short names should conflict, got {conflict:?}");
+                };
+                let mut sizes = [first.file_size(), second.file_size()];
+                sizes.sort();
+                conflicting_sizes.push(sizes);
+
+                ControlFlow::Continue(())
+            })
+            .expect("Scanning works");
+
+        assert_eq!(conflicting_sizes, [[5, 9]]);
+    }
+}
+
+fn verify_allocation_table_prefetch(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(PrefetchingStream::<_, 4096>::new(
+        MemoryStream::from_disk(file_name),
+    ))
+    .build()
+    .expect("Opening disk works");
+
+    let read_file = |path: &str| {
+        let mut file = file_system.open(path)?;
+        let mut bytes = Vec::new();
+        let mut buffer = [0; 512];
+
+        loop {
+            match file.read(&mut buffer).unwrap() {
+                0 => return Some(bytes),
+                read => bytes.extend_from_slice(&buffer[..read]),
+            }
+        }
+    };
+
+    let prefetched_length = file_system
+        .prefetch_allocation_table()
+        .expect("Prefetching works");
+    assert!(prefetched_length > 0 && prefetched_length <= 4096);
+
+    assert_eq!(
+        read_file("foo/bar.txt"),
+        Some("redrum\n".as_bytes().to_vec())
+    );
+
+    let contents = (0..20_000).map(|index| index as u8).collect::<Vec<_>>();
+    file_system
+        .write_replace("foo/New File.bin", &contents)
+        .expect("Writing with a prefetched allocation table works");
+    assert_eq!(read_file("foo/New File.bin"), Some(contents.clone()));
+
+    file_system
+        .prefetch_allocation_table()
+        .expect("Prefetching again works");
+    assert_eq!(
+        read_file("foo/New File.bin"),
+        Some(contents),
+        "Allocation table writes reached the disk"
+    );
+
+    #[cfg(feature = "async")]
+    {
+        let image = std::fs::read(format!("disks/{file_name}")).unwrap();
+        let async_file_system = poll_to_completion(
+            FileSystemBuilder::from_stream(PrefetchingStream::<_, 4096>::new(SliceStream::new(
+                &image,
+            )))
+            .build_async(),
+        )
+        .0
+        .expect("Opening image works");
+
+        let (result, _) = poll_to_completion(async_file_system.prefetch_allocation_table_async());
+        assert_eq!(
+            result.expect("Prefetching asynchronously works"),
+            prefetched_length
+        );
+    }
+}
+
+fn verify_pinned_files(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    let contents = (0..20_000).map(|index| index as u8).collect::<Vec<_>>();
+    file_system
+        .write_replace("foo/Asset.bin", &contents)
+        .expect("Creating a file spanning several clusters works");
+
+    let pinned_file = file_system
+        .pin::<64>("foo/Asset.bin")
+        .expect("Pinning a file works");
+    assert!(pinned_file.is_fully_indexed());
+    assert_eq!(pinned_file.size(), 20_000);
+
+    let partially_pinned_file = file_system
+        .pin::<2>("foo/Asset.bin")
+        .expect("Pinning a file longer than the index works");
+    assert_eq!(partially_pinned_file.indexed_cluster_count(), 2);
+    assert!(!partially_pinned_file.is_fully_indexed());
+
+    for mut file in [
+        file_system.open_pinned(&pinned_file),
+        file_system.open_pinned(&partially_pinned_file),
+    ] {
+        for position in [19_000, 3, 10_000, 512, 19_984, 0] {
+            let mut buffer = [0; 16];
+            file.seek(SeekFrom::Start(position as u64)).unwrap();
+            file.read_exact(&mut buffer).unwrap();
+
+            assert_eq!(buffer, contents[position..position + 16]);
+        }
+    }
+
+    assert!(matches!(
+        file_system.pin::<4>("missing.bin"),
+        Err(OperationError::NotFound)
+    ));
+    assert!(matches!(
+        file_system.pin::<4>("foo"),
+        Err(OperationError::NotAFile)
+    ));
+
+    #[cfg(feature = "async")]
+    {
+        let image = std::fs::read(format!("disks/{file_name}")).unwrap();
+        let async_file_system = poll_to_completion(
+            FileSystemBuilder::from_stream(SliceStream::new(&image)).build_async(),
+        )
+        .0
+        .expect("Opening image works");
+
+        let (result, _) =
+            poll_to_completion(async_file_system.pin_async::<4>("long-File.name.txt"));
+        let pinned_file = result.expect("Pinning asynchronously works");
+
+        assert!(pinned_file.is_fully_indexed());
+        assert_eq!(pinned_file.indexed_cluster_count(), 1);
+    }
+}
+
+#[cfg(feature = "config")]
+fn verify_config(file_name: &str) {
+    use embedded_fat::{ConfigItem, ConfigReader};
+
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    file_system
+        .write_replace(
+            "CONFIG.TXT",
+            b"# Device settings\r\n[wifi]\r\nssid = Home Network\r\n\r\n[log]\r\nlevel=debug\r\n",
+        )
+        .expect("Writing the config works");
+
+    let mut reader = ConfigReader::<_, 64>::new(
+        file_system
+            .open("config.txt")
+            .expect("Opening the config works"),
+    );
+    let mut items = Vec::new();
+
+    while let Some(item) = reader.next_item() {
+        items.push(match item.expect("Reading the config works") {
+            ConfigItem::Section(name) => format!("[{name}]"),
+            ConfigItem::Entry { key, value } => format!("{key}={value}"),
+        });
+    }
+
+    assert_eq!(
+        items,
+        ["[wifi]", "ssid=Home Network", "[log]", "level=debug"]
+    );
+}
+
+fn verify_latest(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    let write_file = |name: &str, date: u16, time: u16| {
+        let mut temp_file = file_system.tempfile("foo").expect("Creating works");
+        temp_file.append(name.as_bytes()).expect("Appending works");
+        temp_file.set_last_write(date, time).expect("Dating works");
+        temp_file
+            .persist(name)
+            .map_err(|(_, error)| error)
+            .expect("Persisting works");
+    };
+
+    // 2024-03-01, 2024-05-01 and 2026-05-01
+    write_file("FW_A.BIN", 0x5861, 0);
+    write_file("fw_c.bin", 0x58A1, 0x6000);
+    write_file("FW_B.BIN", 0x58A1, 0x6000);
+    write_file("FW_D.HEX", 0x5CA1, 0);
+
+    let latest = file_system
+        .latest("foo/FW*.BIN")
+        .expect("Matching files are found");
+    assert_eq!(
+        latest.short_name().base_name(),
+        b"FW_C",
+        "Files written at the same time are ordered by name"
+    );
+
+    let latest = file_system
+        .latest("foo/f?_a.*")
+        .expect("Single character wildcards match");
+    assert_eq!(latest.short_name().base_name(), b"FW_A");
+
+    assert!(file_system.latest("foo/*.exe").is_none());
+    assert!(file_system.latest("missing/*").is_none());
+    assert!(
+        file_system.latest("*").is_some_and(|item| item.is_file()),
+        "Directories are skipped"
+    );
+
+    #[cfg(feature = "async")]
+    {
+        let image = std::fs::read(format!("disks/{file_name}")).unwrap();
+        let async_file_system = poll_to_completion(
+            FileSystemBuilder::from_stream(SliceStream::new(&image)).build_async(),
+        )
+        .0
+        .expect("Opening image works");
+
+        let (latest, _) = poll_to_completion(async_file_system.latest_async("foo/*.TXT"));
+        assert_eq!(
+            latest
+                .expect("Matching files are found")
+                .short_name()
+                .base_name(),
+            b"BAR"
+        );
+    }
+}
+
+fn verify_directory_quota(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .with_quota_policy(|directory_path| {
+            (directory_path == "foo")
+                .then(|| DirectoryQuota::new().with_max_files(3).with_max_bytes(64))
+        })
+        .build()
+        .expect("Opening disk works");
+
+    // foo/bar.txt already holds 7 bytes
+    file_system
+        .write_replace("foo/a.txt", &[b'a'; 20])
+        .expect("Writing within the quota works");
+    assert!(matches!(
+        file_system.write_replace("foo/b.txt", &[b'b'; 40]),
+        Err(OperationError::QuotaExceeded)
+    ));
+    assert!(
+        file_system.open("foo/b.txt").is_none(),
+        "Refused file isn't created"
+    );
+
+    file_system
+        .write_replace("foo/a.txt", &[b'a'; 57])
+        .expect("Only the growth of a replaced file counts");
+    file_system
+        .write_replace("foo/a.txt", &[b'a'; 10])
+        .expect("Shrinking a file works");
+
+    {
+        let mut temp_file = file_system
+            .tempfile("foo")
+            .expect("Creating the third file works");
+
+        assert!(matches!(
+            temp_file.append(&[b'c'; 48]),
+            Err(OperationError::QuotaExceeded)
+        ));
+        assert_eq!(temp_file.file_size(), 0, "Refused append writes nothing");
+
+        temp_file
+            .append(&[b'c'; 10])
+            .expect("Appending within the quota works");
+        temp_file
+            .persist("c.txt")
+            .map_err(|(_, error)| error)
+            .expect("Persisting works");
+    }
+
+    assert!(matches!(
+        file_system.tempfile("foo"),
+        Err(OperationError::QuotaExceeded)
+    ));
+
+    {
+        let mut writer = file_system.open_writer("foo/a.txt").unwrap();
+
+        writer.seek(SeekFrom::End(0)).unwrap();
+        assert!(matches!(
+            writer.write(&[b'a'; 38]),
+            Err(FileError::QuotaExceeded)
+        ));
+        writer
+            .write_all(&[b'a'; 37])
+            .expect("Writing up to the quota works");
+        writer.flush().unwrap();
+
+        assert!(matches!(writer.write(b"a"), Err(FileError::QuotaExceeded)));
+        writer.seek(SeekFrom::Start(0)).unwrap();
+        writer
+            .write_all(b"A")
+            .expect("Overwriting existing bytes works");
+    }
+
+    assert_eq!(file_system.open("foo/a.txt").unwrap().size(), 47);
+
+    file_system
+        .write_replace("big.bin", &[0; 1_000])
+        .expect("Directories without a quota are unrestricted");
+}
+
+fn verify_evict_until_free(file_name: &str) {
+    let deleted_names = RefCell::new(Vec::new());
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .on_mutation(|event| {
+            if let MutationEvent::Deleted {
+                directory_path,
+                name,
+                ..
+            } = event
+            {
+                deleted_names
+                    .borrow_mut()
+                    .push(format!("{directory_path}/{name}"));
+            }
+        })
+        .build()
+        .expect("Opening disk works");
+
+    // Logs written on consecutive days in 1990, before any file of the image
+    for (name, date) in [
+        ("log-2.txt", 0x1422),
+        ("log-1.txt", 0x1421),
+        ("log-3.txt", 0x1423),
+    ] {
+        let mut temp_file = file_system.tempfile("foo").unwrap();
+
+        temp_file.append(&[b'l'; 5_000]).unwrap();
+        temp_file.set_last_write(date, 0).unwrap();
+        temp_file.persist(name).map_err(|(_, error)| error).unwrap();
+    }
+
+    assert_eq!(
+        file_system.evict_until_free("foo", 0).unwrap(),
+        0,
+        "Nothing is deleted while there is room"
+    );
+
+    {
+        let mut filler = file_system.tempfile("").unwrap();
+
+        for chunk_size in [65_536, 512] {
+            while filler.append(&vec![0; chunk_size]).is_ok() {}
+        }
+
+        filler
+            .persist("filler.bin")
+            .map_err(|(_, error)| error)
+            .unwrap();
+    }
+
+    assert_eq!(file_system.evict_until_free("foo", 5_000).unwrap(), 1);
+    assert!(file_system.open("foo/log-1.txt").is_none());
+    assert!(file_system.open("foo/log-2.txt").is_some());
+
+    assert_eq!(file_system.evict_until_free("foo", 10_000).unwrap(), 1);
+    assert!(file_system.open("foo/log-2.txt").is_none());
+    assert!(file_system.open("foo/log-3.txt").is_some());
+
+    file_system
+        .write_replace("foo/new.txt", &[b'n'; 10_000])
+        .expect("Evicted space is reused");
+
+    assert!(matches!(
+        file_system.evict_until_free("foo", u64::MAX),
+        Err(OperationError::VolumeFull)
+    ));
+    assert!(
+        file_system
+            .read_dir("foo")
+            .unwrap()
+            .all(|item| !item.unwrap().is_file()),
+        "Every file of the directory is deleted"
+    );
+    assert!(
+        file_system.open("filler.bin").is_some(),
+        "Files of other directories are kept"
+    );
+    assert!(matches!(
+        file_system.evict_until_free("missing", 0),
+        Err(OperationError::NotFound)
+    ));
+
+    // The replaced file has no last write timestamp, so it's deleted before the remaining log
+    assert_eq!(
+        deleted_names.borrow()[..4],
+        [
+            "foo/log-1.txt",
+            "foo/log-2.txt",
+            "foo/new.txt",
+            "foo/log-3.txt"
+        ]
+    );
+    assert_eq!(deleted_names.borrow().len(), 5);
+}
+
+fn verify_volume_label(file_name: &str) {
+    let mut image = std::fs::read(format!("disks/{file_name}")).unwrap();
+
+    {
+        let file_system = FileSystem::from_image(&image).expect("Opening image works");
+
+        assert!(
+            file_system.volume_label().is_none(),
+            "Images are unlabelled"
+        );
+    }
+
+    // Label the volume through the first unused slot following the root directory's entries
+    let entry_offset = image
+        .windows(11)
+        .position(|window| window == b"TEST    TXT")
+        .expect("Root directory holds TEST.TXT");
+    let label_offset = (entry_offset..)
+        .step_by(32)
+        .find(|offset| image[*offset] == 0x00)
+        .unwrap();
+    image[label_offset..label_offset + 11].copy_from_slice(b"DATA       ");
+    image[label_offset + 11] = 0x08;
+
+    let file_system = FileSystem::from_image(&image).expect("Opening image works");
+    let label = file_system.volume_label().expect("Label is found");
+
+    assert_eq!(label.as_bytes(), b"DATA");
+    assert!(label.matches("data"));
+    assert_eq!(label.strip_from("DATA:/foo/bar.txt"), Some("foo/bar.txt"));
+    assert_eq!(label.strip_from("LOGS:/foo/bar.txt"), None);
+    assert!(
+        file_system.open("TEST.TXT").is_some(),
+        "Items are still found past the label"
+    );
+
+    #[cfg(feature = "async")]
+    {
+        let async_file_system = poll_to_completion(
+            FileSystemBuilder::from_stream(SliceStream::new(&image)).build_async(),
+        )
+        .0
+        .expect("Opening image works");
+
+        assert_eq!(
+            poll_to_completion(async_file_system.volume_label_async()).0,
+            Some(label)
+        );
+    }
+}
+
+fn verify_revalidate(file_name: &str) {
+    let (stream, image) = SwappableStream::from_disk(file_name);
+    let file_system = FileSystemBuilder::from_stream(stream)
+        .build()
+        .expect("Opening disk works");
+    let serial_number = file_system
+        .volume_serial_number()
+        .expect("Images record a serial number");
+    let serial_number_offset = match file_system.allocation_table_kind() {
+        #[cfg(feature = "fat32")]
+        AllocationTableKind::Fat32 => 67,
+        #[allow(unreachable_patterns)]
+        _ => 39,
+    };
+
+    file_system
+        .revalidate()
+        .expect("The mounted medium is unchanged");
+
+    let mut file = file_system.open("TEST.TXT").unwrap();
+    let mut bytes = [0; 5];
+
+    // Another card formatted the same way differs in its serial number
+    image.borrow_mut()[serial_number_offset..serial_number_offset + 4]
+        .copy_from_slice(&(serial_number ^ 1).to_le_bytes());
+
+    assert!(matches!(
+        file_system.revalidate(),
+        Err(OperationError::MediaChanged)
+    ));
+    assert!(file_system.is_media_changed());
+    assert!(matches!(
+        file.read(&mut bytes),
+        Err(FileError::MediaChanged)
+    ));
+    assert!(matches!(
+        file_system.open("TEST.TXT").unwrap().read(&mut bytes),
+        Err(FileError::MediaChanged)
+    ));
+
+    image.borrow_mut()[serial_number_offset..serial_number_offset + 4]
+        .copy_from_slice(&serial_number.to_le_bytes());
+    assert!(
+        matches!(file_system.revalidate(), Err(OperationError::MediaChanged)),
+        "A detected change is kept until the volume is mounted again"
+    );
+
+    let file_system = file_system
+        .suspend()
+        .unwrap()
+        .resume()
+        .map_err(|(_, error)| error)
+        .expect("Resuming works");
+
+    file_system
+        .revalidate()
+        .expect("The remounted medium is unchanged");
+    file_system
+        .open("TEST.TXT")
+        .unwrap()
+        .read_exact(&mut bytes)
+        .unwrap();
+    assert_eq!(&bytes, b"test\n");
+
+    image.borrow_mut()[510] = 0;
+    assert!(
+        matches!(file_system.revalidate(), Err(OperationError::MediaChanged)),
+        "A medium without a valid boot sector is a changed medium"
+    );
+
+    #[cfg(feature = "async")]
+    {
+        let image = std::fs::read(format!("disks/{file_name}")).unwrap();
+        let async_file_system = poll_to_completion(
+            FileSystemBuilder::from_stream(SliceStream::new(&image)).build_async(),
+        )
+        .0
+        .expect("Opening image works");
+
+        assert!(
+            poll_to_completion(async_file_system.revalidate_async())
+                .0
+                .is_ok()
+        );
+    }
+}
+
+fn verify_dir_change_detection(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    let item_count = file_system
+        .read_dir("foo")
+        .expect("Directory exists")
+        .count();
+    let mut unchecked_dir = file_system.read_dir("foo").expect("Directory exists");
+    let mut dir = file_system
+        .read_dir("foo")
+        .expect("Directory exists")
+        .with_change_detection(true);
+    assert!(dir.next().expect("Item exists").is_ok());
+    assert!(unchecked_dir.next().expect("Item exists").is_ok());
+
+    file_system
+        .write_replace("foo/new.txt", b"new")
+        .expect("Writing works");
+
+    assert!(
+        matches!(
+            dir.next(),
+            Some(Err(DirectoryItemIterationError::ListingChanged))
+        ),
+        "Write during the listing is reported"
+    );
+    assert!(dir.next().is_none(), "Changed listing ends");
+    assert!(
+        unchecked_dir.all(|item| item.is_ok()),
+        "Listings without change detection continue"
+    );
+
+    dir.rewind();
+    assert_eq!(
+        dir.by_ref().filter(|item| item.is_ok()).count(),
+        item_count + 1,
+        "Rewound listing includes the new file"
+    );
+
+    let cursor = {
+        dir.rewind();
+        dir.next();
+        dir.cursor()
+    };
+    file_system
+        .write_replace("TEST.TXT", b"other directory\n")
+        .expect("Writing works");
+    dir.resume(cursor);
+    assert!(
+        dir.all(|item| item.is_ok()),
+        "Resumed listing watches for changes afresh"
+    );
+
+    let mut dir = file_system
+        .read_dir("")
+        .expect("Directory exists")
+        .with_change_detection(true);
+    file_system
+        .write_replace("foo/new.txt", b"newer")
+        .expect("Writing works");
+    assert!(
+        matches!(
+            dir.next(),
+            Some(Err(DirectoryItemIterationError::ListingChanged))
+        ),
+        "Writes to other directories are reported too"
+    );
+}
+
+fn verify_entries_after_end_marker(file_name: &str) {
+    let mut image = std::fs::read(format!("disks/{file_name}")).unwrap();
+
+    let item_names = |image: &[u8], directory_path| {
+        FileSystem::from_image(image)
+            .expect("Opening image works")
+            .read_dir(directory_path)
+            .expect("Directory exists")
+            .map(|item| {
+                item.expect("Every item is valid")
+                    .short_name()
+                    .base_name()
+                    .to_vec()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let root_names = item_names(&image, "");
+    let foo_names = item_names(&image, "foo");
+
+    // Leftovers of a previous directory past the end marker of the root directory, whether a
+    // table or a file, and of a subdirectory
+    for last_entry_name in [b"TEST    TXT", b"BAR     TXT"] {
+        let entry_offset = image
+            .windows(11)
+            .position(|window| window == last_entry_name)
+            .expect("Entry exists");
+        let end_marker_offset = (entry_offset..)
+            .step_by(32)
+            .find(|offset| image[*offset] == 0x00)
+            .unwrap();
+
+        let ghost_offset = end_marker_offset + 32;
+        image[ghost_offset..ghost_offset + 11].copy_from_slice(b"GHOST   TXT");
+        image[ghost_offset + 11] = 0x20;
+
+        // A long name fragment whose short name entry is missing
+        let fragment_offset = ghost_offset + 32;
+        image[fragment_offset] = 0x41;
+        image[fragment_offset + 11] = 0x0F;
+
+        let second_ghost_offset = fragment_offset + 32;
+        image[second_ghost_offset..second_ghost_offset + 11].copy_from_slice(b"GHOST2  TXT");
+        image[second_ghost_offset + 11] = 0x20;
+    }
+
+    assert_eq!(item_names(&image, ""), root_names);
+    assert_eq!(item_names(&image, "foo"), foo_names);
+
+    let file_system = FileSystem::from_image(&image).expect("Opening image works");
+    assert!(file_system.open("GHOST.TXT").is_none());
+    assert!(file_system.open("foo/GHOST.TXT").is_none());
+    assert_eq!(
+        file_system
+            .read_dir("foo")
+            .expect("Directory exists")
+            .entry_count()
+            .unwrap(),
+        foo_names.len()
+    );
+    assert!(
+        file_system
+            .read_dir("foo")
+            .expect("Directory exists")
+            .item_at(foo_names.len())
+            .is_none()
+    );
+
+    #[cfg(feature = "async")]
+    {
+        let async_file_system = poll_to_completion(
+            FileSystemBuilder::from_stream(SliceStream::new(&image)).build_async(),
+        )
+        .0
+        .expect("Opening image works");
+
+        for (directory_path, names) in [("", &root_names), ("foo", &foo_names)] {
+            let mut dir = poll_to_completion(async_file_system.read_dir_async(directory_path))
+                .0
+                .expect("Directory exists");
+            let mut async_names = Vec::new();
+
+            while let Some(item) = poll_to_completion(dir.next_async()).0 {
+                async_names.push(
+                    item.expect("Every item is valid")
+                        .short_name()
+                        .base_name()
+                        .to_vec(),
+                );
+            }
+
+            assert_eq!(&async_names, names);
+            assert_eq!(
+                poll_to_completion(dir.entry_count_async()).0.unwrap(),
+                names.len()
+            );
+        }
+
+        assert!(
+            poll_to_completion(async_file_system.open_async("foo/GHOST.TXT"))
+                .0
+                .is_none()
+        );
+    }
+
+    // Creating items writes over the end markers, which are written again past the new entries
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_bytes(image))
+        .build()
+        .expect("Opening image works");
+
+    for (path, directory_path, names) in [
+        ("Newer file name.txt", "", root_names),
+        ("foo/Newer file name.txt", "foo", foo_names),
+    ] {
+        file_system
+            .write_replace(path, b"new\n")
+            .expect("Creating a file works");
+
+        let written_names = file_system
+            .read_dir(directory_path)
+            .expect("Directory exists")
+            .map(|item| {
+                item.expect("Every item is valid")
+                    .short_name()
+                    .base_name()
+                    .to_vec()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(written_names.len(), names.len() + 1);
+        assert!(names.iter().all(|name| written_names.contains(name)));
+    }
+}
+
+fn verify_short_name_case(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+    let find = |file_system: &FileSystem<_, _, _, _>, base_name: &[u8]| {
+        file_system
+            .read_dir("foo")
+            .expect("Directory exists")
+            .map(|item| item.expect("Every item is valid"))
+            .find(|item| item.short_name().base_name() == base_name)
+            .expect("Item exists")
+    };
+    let mut path_buffer = [0; 64];
+
+    for (name, base_name, expected_case) in [
+        ("readme.txt", b"README".as_slice(), ShortNameCase::all()),
+        ("LOG.txt", b"LOG", ShortNameCase::LowerCaseExtension),
+        ("data", b"DATA", ShortNameCase::LowerCaseBaseName),
+    ] {
+        let path = format!("foo/{name}");
+        file_system
+            .write_replace(&path, b"contents")
+            .expect("Writing works");
+
+        let item = find(&file_system, base_name);
+        assert!(item.long_name().is_none(), "{name} has no long name");
+        assert_eq!(item.location().unwrap().entry_count(), 1);
+        assert_eq!(item.short_name_case(), expected_case);
+        assert_eq!(
+            file_system.canonicalize(&path.to_uppercase(), &mut path_buffer),
+            Ok(path.as_str()),
+            "{name} is displayed in its case"
+        );
+    }
+
+    file_system
+        .write_replace("foo/readme.txt", b"rewritten")
+        .expect("Rewriting works");
+    let mut writer = file_system
+        .open_writer("foo/readme.txt")
+        .expect("Opening works");
+    writer.write_all(b"appended").expect("Writing works");
+    writer.close().expect("Closing works");
+    assert_eq!(
+        find(&file_system, b"README").short_name_case(),
+        ShortNameCase::all(),
+        "Rewriting keeps the case"
+    );
+
+    file_system
+        .write_replace("foo/ReadMe.md", b"mixed")
+        .expect("Writing works");
+    assert_eq!(
+        file_system.canonicalize("foo/README.MD", &mut path_buffer),
+        Ok("foo/ReadMe.md"),
+        "Mixed case names keep a long name"
+    );
+
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .with_short_name_case(false)
+        .build()
+        .expect("Opening disk works");
+
+    file_system
+        .write_replace("foo/notes.txt", b"contents")
+        .expect("Writing works");
+    let item = find(&file_system, b"NOTES");
+    assert!(
+        item.long_name().is_some(),
+        "Lower case names get a long name once disabled"
+    );
+    assert!(item.short_name_case().is_empty());
+}
+
+fn verify_timestamps(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    let date = FatDate::new(2024, 2, 29).expect("Leap days exist");
+    let time = FatTime::new(23, 59, 58).expect("The time exists");
+
+    let mut temp_file = file_system.tempfile("foo").expect("Creating works");
+    temp_file
+        .set_last_write_timestamp(date, time)
+        .expect("Dating works");
+    temp_file
+        .persist("STAMP.TXT")
+        .map_err(|(_, error)| error)
+        .expect("Persisting works");
+
+    let item = file_system
+        .latest("foo/STAMP.TXT")
+        .expect("The file is found");
+    assert_eq!(item.last_write_timestamp(), Ok((date, time)));
+    assert_eq!(item.last_write_date(), date.to_packed());
+
+    let mut temp_file = file_system.tempfile("foo").expect("Creating works");
+    temp_file.set_last_write(0, 0).expect("Dating works");
+    temp_file
+        .persist("ZEROED.TXT")
+        .map_err(|(_, error)| error)
+        .expect("Persisting works");
+
+    let item = file_system
+        .latest("foo/ZEROED.TXT")
+        .expect("The file is found");
+    assert_eq!(
+        item.last_write_timestamp(),
+        Err(TimestampError::MonthInvalid { month: 0 }),
+        "Zeroed dates don't exist"
+    );
+    assert_eq!(
+        item.last_write_timestamp_utc(),
+        Err(TimestampError::MonthInvalid { month: 0 })
+    );
+
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .with_utc_offset(60)
+        .build()
+        .expect("Opening disk works");
+    assert_eq!(file_system.utc_offset_minutes(), 60);
+
+    let utc_date = FatDate::new(2024, 12, 31).unwrap();
+    let utc_time = FatTime::new(23, 30, 0).unwrap();
+
+    let mut temp_file = file_system.tempfile("foo").expect("Creating works");
+    temp_file
+        .set_last_write_timestamp_utc(utc_date, utc_time)
+        .expect("Dating works");
+    temp_file
+        .persist("UTC.TXT")
+        .map_err(|(_, error)| error)
+        .expect("Persisting works");
+
+    let item = file_system
+        .latest("foo/UTC.TXT")
+        .expect("The file is found");
+    assert_eq!(
+        item.last_write_timestamp(),
+        Ok((
+            FatDate::new(2025, 1, 1).unwrap(),
+            FatTime::new(0, 30, 0).unwrap()
+        )),
+        "Local time is stored"
+    );
+    assert_eq!(item.last_write_timestamp_utc(), Ok((utc_date, utc_time)));
+
+    let mut temp_file = file_system.tempfile("foo").expect("Creating works");
+    temp_file
+        .set_last_write_timestamp(date, time)
+        .expect("Dating works");
+    temp_file
+        .persist("STAMP.TXT")
+        .map_err(|(_, error)| error)
+        .expect("Persisting works");
+
+    let item = file_system
+        .latest("foo/STAMP.TXT")
+        .expect("The file is found");
+    assert_eq!(
+        item.last_write_timestamp_utc(),
+        Ok((date, FatTime::new(22, 59, 58).unwrap())),
+        "Local timestamps are converted"
+    );
+
+    let mut temp_file = file_system.tempfile("foo").expect("Creating works");
+    assert!(matches!(
+        temp_file.set_last_write_timestamp_utc(
+            FatDate::new(2107, 12, 31).unwrap(),
+            FatTime::new(23, 30, 0).unwrap()
+        ),
+        Err(OperationError::TimestampInvalid(
+            TimestampError::YearOutOfRange { year: 2108 }
+        ))
+    ));
+}
+
+fn verify_dump_tree(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    // Timestamps differ between the images, so only the columns before them are compared
+    let listed_items = |tree: &str| {
+        let mut items = tree
+            .lines()
+            .map(|line| {
+                let (item, timestamp) = line.rsplit_once("  ").expect("Columns are separated");
+                assert_eq!(timestamp.len(), "2026-01-03 20:52:52".len());
+
+                item.to_string()
+            })
+            .collect::<Vec<_>>();
+        items.sort();
+
+        items
+    };
+
+    let mut tree = String::new();
+    file_system
+        .dump_tree::<4, _>(&mut tree)
+        .expect("Writing to a string works");
+
+    assert!(
+        tree.contains("foo/  ") && tree.contains("\n  BaR.tXt  7  "),
+        "Directory contents follow the directory, indented"
+    );
+    assert_eq!(
+        listed_items(&tree),
+        [
+            "  BaR.tXt  7",
+            "foo/",
+            "long-File.name.txt  9",
+            "test.txt  5"
+        ]
+    );
+
+    let mut shallow_tree = String::new();
+    file_system
+        .dump_tree::<1, _>(&mut shallow_tree)
+        .expect("Writing to a string works");
+    assert_eq!(
+        listed_items(&shallow_tree),
+        ["foo/", "long-File.name.txt  9", "test.txt  5"],
+        "Directories beyond the maximum depth aren't listed"
+    );
+
+    #[cfg(feature = "async")]
+    {
+        let image = std::fs::read(format!("disks/{file_name}")).unwrap();
+        let async_file_system = poll_to_completion(
+            FileSystemBuilder::from_stream(SliceStream::new(&image)).build_async(),
+        )
+        .0
+        .expect("Opening image works");
+
+        let mut async_tree = String::new();
+        poll_to_completion(async_file_system.dump_tree_async::<4, _>(&mut async_tree))
+            .0
+            .expect("Writing to a string works");
+        assert_eq!(async_tree, tree);
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn verify_inventory(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    let inventory = file_system
+        .inventory::<4>(true)
+        .expect("Taking the inventory works");
+
+    let mut paths = inventory
+        .items()
+        .iter()
+        .map(|item| item.path())
+        .collect::<Vec<_>>();
+    paths.sort();
+    assert_eq!(
+        paths,
+        ["foo", "foo/BaR.tXt", "long-File.name.txt", "test.txt"]
+    );
+
+    let directory = inventory.find("foo").expect("The directory is recorded");
+    assert!(directory.is_directory());
+    assert_eq!(directory.crc32(), None, "Directories aren't hashed");
+
+    let file = inventory
+        .find("long-File.name.txt")
+        .expect("The file is recorded");
+    assert!(!file.is_directory());
+    assert_eq!(file.file_size(), 9);
+
+    let mut crc32 = Crc32::new();
+    file_system
+        .hash("long-File.name.txt", &mut crc32)
+        .expect("Hashing works");
+    assert_eq!(file.crc32(), Some(crc32.finalize()));
+
+    let item = file_system
+        .latest("long-File.name.txt")
+        .expect("The file is found");
+    assert_eq!(file.last_write_date(), item.last_write_date());
+    assert_eq!(file.last_write_time(), item.last_write_time());
+
+    let unhashed_inventory = file_system
+        .inventory::<4>(false)
+        .expect("Taking the inventory works");
+    assert!(
+        unhashed_inventory
+            .items()
+            .iter()
+            .all(|item| item.crc32().is_none())
+    );
+    assert_eq!(unhashed_inventory.items().len(), inventory.items().len());
+
+    #[cfg(feature = "serde")]
+    {
+        let json = serde_json::to_value(&inventory).expect("Serializing works");
+        let file_json = json["items"]
+            .as_array()
+            .expect("Items are serialized as an array")
+            .iter()
+            .find(|item| item["path"] == "foo/BaR.tXt")
+            .expect("Nested items are serialized");
+
+        assert_eq!(file_json["file_size"], 7);
+        assert_eq!(file_json["is_directory"], false);
+        assert!(file_json["crc32"].is_u64());
+    }
+
+    #[cfg(feature = "async")]
+    {
+        let image = std::fs::read(format!("disks/{file_name}")).unwrap();
+        let async_file_system = poll_to_completion(
+            FileSystemBuilder::from_stream(SliceStream::new(&image)).build_async(),
+        )
+        .0
+        .expect("Opening image works");
+
+        let async_inventory = poll_to_completion(async_file_system.inventory_async::<4>(true))
+            .0
+            .expect("Taking the inventory works");
+        assert_eq!(async_inventory, inventory);
+    }
+}
+
+fn verify_manifest(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    let crc32_of = |path: &str| {
+        let mut crc32 = Crc32::new();
+        file_system.hash(path, &mut crc32).expect("Hashing works");
+
+        crc32.finalize()
+    };
+    let describe = |mismatch: ManifestMismatch<'_>| match mismatch {
+        ManifestMismatch::Missing { path } => format!("missing {path}"),
+        ManifestMismatch::Extra { path } => format!("extra {path}"),
+        ManifestMismatch::SizeMismatch {
+            path,
+            expected,
+            actual,
+        } => format!("size {path} {expected} {actual}"),
+        ManifestMismatch::Crc32Mismatch { path, .. } => format!("crc32 {path}"),
+    };
+
+    let matching_manifest = [
+        ("test.txt", 5, crc32_of("test.txt")),
+        ("foo/bar.txt", 7, crc32_of("foo/BaR.tXt")),
+        ("long-File.name.txt", 9, crc32_of("long-File.name.txt")),
+    ];
+    let mut mismatches = Vec::new();
+
+    file_system
+        .verify_manifest::<4, _, _>(matching_manifest, &mut [0; 64], |mismatch| {
+            mismatches.push(describe(mismatch));
+            ControlFlow::Continue(())
+        })
+        .expect("Verifying works");
+    assert!(
+        mismatches.is_empty(),
+        "Unexpected mismatches {mismatches:?}"
+    );
+
+    let manifest = [
+        ("foo/bar.txt", 7, !crc32_of("foo/BaR.tXt")),
+        ("long-File.name.txt", 10, 0),
+        ("missing.bin", 1, 0),
+        ("foo", 0, 0),
+    ];
+
+    file_system
+        .verify_manifest::<4, _, _>(manifest, &mut [0; 64], |mismatch| {
+            mismatches.push(describe(mismatch));
+            ControlFlow::Continue(())
+        })
+        .expect("Verifying works");
+    assert_eq!(
+        mismatches,
+        [
+            "crc32 foo/bar.txt",
+            "size long-File.name.txt 10 9",
+            "missing missing.bin",
+            "missing foo",
+            "extra test.txt",
+        ]
+    );
+
+    let mut mismatch_count = 0;
+    file_system
+        .verify_manifest::<4, _, _>(manifest, &mut [0; 64], |_| {
+            mismatch_count += 1;
+            ControlFlow::Break(())
+        })
+        .expect("Verifying works");
+    assert_eq!(mismatch_count, 1, "Breaking ends the verification");
+
+    assert!(matches!(
+        file_system.verify_manifest::<4, _, _>(
+            manifest,
+            &mut [0; 4],
+            |_| ControlFlow::Continue(())
+        ),
+        Err(ManifestError::PathBufferTooSmall)
+    ));
+
+    #[cfg(feature = "async")]
+    {
+        let image = std::fs::read(format!("disks/{file_name}")).unwrap();
+        let async_file_system = poll_to_completion(
+            FileSystemBuilder::from_stream(SliceStream::new(&image)).build_async(),
+        )
+        .0
+        .expect("Opening image works");
+
+        let mut async_mismatches = Vec::new();
+        poll_to_completion(async_file_system.verify_manifest_async::<4, _, _>(
+            manifest,
+            &mut [0; 64],
+            |mismatch| {
+                async_mismatches.push(describe(mismatch));
+                ControlFlow::Continue(())
+            },
+        ))
+        .0
+        .expect("Verifying works");
+        assert_eq!(async_mismatches, mismatches);
+    }
+}
+
+fn verify_allocation_table_statistics(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+    let statistics = file_system
+        .allocation_table_statistics(0)
+        .expect("Gathering works");
+
+    assert!(statistics.free_cluster_count() > 0);
+    assert!(statistics.allocated_cluster_count() > 0);
+    assert_eq!(statistics.bad_cluster_count(), 0);
+    assert_eq!(statistics.reserved_cluster_count(), 0);
+    assert!(statistics.longest_free_run_length() <= statistics.free_cluster_count());
+    assert!(statistics.longest_free_run_start().is_some());
+
+    for table_index in 1..file_system.allocation_table_count() {
+        assert_eq!(
+            file_system
+                .allocation_table_statistics(table_index)
+                .unwrap(),
+            statistics,
+            "Copies agree"
+        );
+    }
+    assert!(matches!(
+        file_system.allocation_table_statistics(file_system.allocation_table_count()),
+        Err(OperationError::AllocationTableIndexInvalid { .. })
+    ));
+
+    file_system.write_replace("new.bin", b"x").unwrap();
+
+    let updated_statistics = file_system.allocation_table_statistics(0).unwrap();
+    assert_eq!(
+        updated_statistics.free_cluster_count(),
+        statistics.free_cluster_count() - 1
+    );
+    assert_eq!(
+        updated_statistics.allocated_cluster_count(),
+        statistics.allocated_cluster_count() + 1
+    );
+
+    #[cfg(feature = "async")]
+    {
+        let image = std::fs::read(format!("disks/{file_name}")).unwrap();
+        let file_system = poll_to_completion(
+            FileSystemBuilder::from_stream(SliceStream::new(&image)).build_async(),
+        )
+        .0
+        .expect("Opening image works");
+
+        assert_eq!(
+            poll_to_completion(file_system.allocation_table_statistics_async(0))
+                .0
+                .unwrap(),
+            statistics
+        );
+    }
+}
+
+fn verify_reserved_clusters(file_name: &str) {
+    let free_run_start = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works")
+        .allocation_table_statistics(0)
+        .unwrap()
+        .longest_free_run_start()
+        .expect("Images have free clusters");
+    let reserved_cluster_ranges: &'static [_] = Vec::leak(vec![2..=free_run_start]);
+
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .with_reserved_clusters(reserved_cluster_ranges)
+        .build()
+        .expect("Opening disk works");
+    let statistics = file_system.allocation_table_statistics(0).unwrap();
+
+    assert!(statistics.reserved_cluster_count() > 0);
+    assert_eq!(
+        statistics.longest_free_run_start(),
+        Some(free_run_start + 1)
+    );
+
+    file_system.write_replace("new.bin", b"x").unwrap();
+    assert_eq!(
+        file_system.open("new.bin").unwrap().first_cluster_number(),
+        free_run_start + 1,
+        "Reserved clusters are skipped"
+    );
+
+    let file_system = file_system
+        .suspend()
+        .unwrap()
+        .resume()
+        .map_err(|(_, error)| error)
+        .expect("Resuming works");
+    file_system.write_replace("other.bin", b"x").unwrap();
+    assert_eq!(
+        file_system
+            .open("other.bin")
+            .unwrap()
+            .first_cluster_number(),
+        free_run_start + 2,
+        "Reservations are kept when resuming"
+    );
+
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .with_reserved_clusters(&[0..=u32::MAX])
+        .build()
+        .expect("Opening disk works");
+
+    assert_eq!(
+        file_system
+            .allocation_table_statistics(0)
+            .unwrap()
+            .free_cluster_count(),
+        0
+    );
+    assert!(matches!(
+        file_system.write_replace("new.bin", b"x"),
+        Err(OperationError::VolumeFull)
+    ));
+}
+
+fn verify_fixed_files(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .with_fixed_file_policy(|directory_path, name| {
+            directory_path == "foo" && name.eq_ignore_ascii_case("LOG-1.TXT")
+        })
+        .build()
+        .expect("Opening disk works");
+
+    for (name, date) in [("log-1.txt", 0x1421), ("log-2.txt", 0x1422)] {
+        let mut temp_file = file_system.tempfile("foo").unwrap();
+
+        temp_file.append(&[b'l'; 5_000]).unwrap();
+        temp_file.set_last_write(date, 0).unwrap();
+        temp_file.persist(name).map_err(|(_, error)| error).unwrap();
+    }
+
+    {
+        let mut filler = file_system.tempfile("").unwrap();
+
+        for chunk_size in [65_536, 512] {
+            while filler.append(&vec![0; chunk_size]).is_ok() {}
+        }
+
+        filler
+            .persist("filler.bin")
+            .map_err(|(_, error)| error)
+            .unwrap();
+    }
+
+    assert_eq!(file_system.evict_until_free("foo", 5_000).unwrap(), 1);
+    assert!(
+        file_system.open("foo/log-1.txt").is_some(),
+        "Fixed files are kept despite being the oldest"
+    );
+    assert!(file_system.open("foo/log-2.txt").is_none());
+
+    assert!(matches!(
+        file_system.evict_until_free("foo", u64::MAX),
+        Err(OperationError::VolumeFull)
+    ));
+    assert!(file_system.open("foo/log-1.txt").is_some());
+}
+
+fn verify_unused_regions(file_name: &str) {
+    let mut image = std::fs::read(format!("disks/{file_name}")).unwrap();
+    let (regions, statistics) = {
+        let file_system = FileSystem::from_image(&image).expect("Opening image works");
+
+        (
+            file_system.unused_regions().collect::<Vec<_>>(),
+            file_system.allocation_table_statistics(0).unwrap(),
+        )
+    };
+
+    for region in &regions {
+        assert!(!region.is_empty());
+        assert!(region.end <= image.len() as u64);
+
+        image[region.start as usize..region.end as usize].fill(0xA5);
+    }
+
+    let file_system = FileSystem::from_image(&image).expect("Stashed data leaves the volume valid");
+    let mut bytes = [0; 5];
+
+    file_system
+        .open("TEST.TXT")
+        .unwrap()
+        .read_exact(&mut bytes)
+        .unwrap();
+    assert_eq!(&bytes, b"test\n");
+    assert_eq!(
+        file_system.allocation_table_statistics(0).unwrap(),
+        statistics
+    );
+}
+
+fn verify_metadata_backup(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+    let statistics = file_system.allocation_table_statistics(0).unwrap();
+    let mut backup = vec![0; file_system.metadata_size() as usize];
+
+    file_system
+        .backup_metadata(&mut backup.as_mut_slice())
+        .expect("Backing up works");
+    assert_eq!(
+        &backup[..512],
+        &std::fs::read(format!("disks/{file_name}")).unwrap()[..512],
+        "The backup starts with the boot sector"
+    );
+
+    file_system.write_replace("new.bin", &[1; 5_000]).unwrap();
+    assert_ne!(
+        file_system.allocation_table_statistics(0).unwrap(),
+        statistics
+    );
+
+    assert!(matches!(
+        file_system.restore_metadata(&mut &backup[..backup.len() - 1]),
+        Err(MetadataError::BackupEndReached)
+    ));
+    assert!(matches!(
+        file_system.restore_metadata(&mut &[0; 512][..]),
+        Err(MetadataError::BackupMismatch)
+    ));
+
+    file_system
+        .restore_metadata(&mut backup.as_slice())
+        .expect("Restoring works");
+    assert_eq!(
+        file_system.allocation_table_statistics(0).unwrap(),
+        statistics
+    );
+
+    #[cfg(feature = "async")]
+    {
+        let image = std::fs::read(format!("disks/{file_name}")).unwrap();
+        let file_system = poll_to_completion(
+            FileSystemBuilder::from_stream(SliceStream::new(&image)).build_async(),
+        )
+        .0
+        .expect("Opening image works");
+        let mut async_backup = vec![0; backup.len()];
+
+        poll_to_completion(file_system.backup_metadata_async(&mut async_backup.as_mut_slice()))
+            .0
+            .expect("Backing up works");
+        assert_eq!(async_backup, backup);
+    }
+}
+
+/// Whether the kind is FAT32, without naming the variant in builds which leave it out.
+fn is_fat32(allocation_table_kind: AllocationTableKind) -> bool {
+    match allocation_table_kind {
+        #[cfg(feature = "fat32")]
+        AllocationTableKind::Fat32 => true,
+        #[allow(unreachable_patterns)]
+        _ => false,
+    }
+}
+
+fn verify_self_test(file_name: &str) {
+    let mut image = std::fs::read(format!("disks/{file_name}")).unwrap();
+
+    let file_system = FileSystem::from_image(&image).expect("Opening image works");
+    assert_eq!(file_system.health_summary(), None);
+
+    let file_system = FileSystemBuilder::from_stream(SliceStream::new(&image))
+        .with_self_test(true)
+        .build()
+        .expect("Opening image works");
+    let health_summary = file_system.health_summary().expect("Self test was run");
+    let is_fat32 = is_fat32(file_system.allocation_table_kind());
+
+    assert!(health_summary.is_healthy());
+    assert!(health_summary.are_media_entries_valid());
+    assert!(health_summary.are_end_of_chain_entries_valid());
+    assert!(health_summary.is_root_directory_readable());
+    assert_eq!(
+        health_summary.is_backup_boot_sector_valid(),
+        is_fat32.then_some(true)
+    );
+    assert_eq!(health_summary.is_fs_info_valid(), is_fat32.then_some(true));
+
+    // Clearing the media descriptor from the first allocation table leaves the volume mountable
+    let bytes_per_sector = u16::from_le_bytes([image[11], image[12]]) as usize;
+    let reserved_sector_count = u16::from_le_bytes([image[14], image[15]]) as usize;
+    image[bytes_per_sector * reserved_sector_count] = 0;
+
+    #[cfg(feature = "async")]
+    {
+        let file_system = poll_to_completion(
+            FileSystemBuilder::from_stream(SliceStream::new(&image))
+                .with_self_test(true)
+                .build_async(),
+        )
+        .0
+        .expect("Opening image works");
+        let health_summary = file_system.health_summary().expect("Self test was run");
+
+        assert!(!health_summary.is_healthy());
+        assert!(!health_summary.are_media_entries_valid());
+        assert!(health_summary.is_root_directory_readable());
+    }
+
+    let file_system = FileSystemBuilder::from_stream(SliceStream::new(&image))
+        .with_self_test(true)
+        .build()
+        .expect("Opening image works");
+    let health_summary = file_system.health_summary().expect("Self test was run");
+
+    assert!(!health_summary.is_healthy());
+    assert!(!health_summary.are_media_entries_valid());
+    assert!(health_summary.are_end_of_chain_entries_valid());
+}
+
+fn verify_reserved_entries_repair(file_name: &str) {
+    let image = std::fs::read(format!("disks/{file_name}")).unwrap();
+    let bytes_per_sector = u16::from_le_bytes([image[11], image[12]]) as u64;
+    let reserved_sector_count = u16::from_le_bytes([image[14], image[15]]) as u64;
+    let allocation_table_address = bytes_per_sector * reserved_sector_count;
+    let reserved_entries_size = match FileSystem::from_image(&image)
+        .unwrap()
+        .allocation_table_kind()
+    {
+        #[cfg(feature = "fat12")]
+        AllocationTableKind::Fat12 => 3,
+        #[cfg(feature = "fat16")]
+        AllocationTableKind::Fat16 => 4,
+        #[cfg(feature = "fat32")]
+        AllocationTableKind::Fat32 => 8,
+    };
+
+    let mut stream = MemoryStream::from_disk(file_name);
+    stream
+        .seek(SeekFrom::Start(allocation_table_address))
+        .unwrap();
+    stream.write_all(&vec![0; reserved_entries_size]).unwrap();
+
+    let file_system = FileSystemBuilder::from_stream(stream)
+        .with_self_test(true)
+        .build()
+        .expect("Damaged reserved entries leave the volume mountable");
+    let health_summary = file_system.health_summary().unwrap();
+
+    assert!(!health_summary.are_media_entries_valid());
+    assert!(!health_summary.are_end_of_chain_entries_valid());
+    assert!(file_system.repair_reserved_entries().unwrap());
+    assert!(!file_system.repair_reserved_entries().unwrap());
+
+    let mut metadata = vec![0; file_system.metadata_size() as usize];
+    file_system
+        .backup_metadata(&mut metadata.as_mut_slice())
+        .unwrap();
+
+    let start = allocation_table_address as usize;
+    assert_eq!(metadata[start..start + 8], image[start..start + 8]);
+}
+
+fn verify_boot_sector_builder(allocation_table_kind: AllocationTableKind, total_sector_count: u32) {
+    let boot_sector = BootSector::builder()
+        .allocation_table_kind(allocation_table_kind)
+        .total_sector_count(total_sector_count)
+        .sectors_per_cluster(1)
+        .volume_serial_number(0xCAFE_F00D)
+        .oem_name(*b"PARTTOOL")
+        .build();
+    let boot_sector_bytes = boot_sector.to_bytes().expect("Parameters are valid");
+    let mut image = vec![0; total_sector_count as usize * 512];
+    image[..512].copy_from_slice(&boot_sector_bytes);
+
+    // The remaining metadata is laid out by hand, as a partitioning tool would
+    let reserved_entries: &[u8] = match allocation_table_kind {
+        #[cfg(feature = "fat12")]
+        AllocationTableKind::Fat12 => &[0xF8, 0xFF, 0xFF],
+        #[cfg(feature = "fat16")]
+        AllocationTableKind::Fat16 => &[0xF8, 0xFF, 0xFF, 0xFF],
+        #[cfg(feature = "fat32")]
+        AllocationTableKind::Fat32 => &[
+            0xF8, 0xFF, 0xFF, 0x0F, 0xFF, 0xFF, 0xFF, 0x0F, 0xFF, 0xFF, 0xFF, 0x0F,
+        ],
+    };
+    let sectors_per_allocation_table = boot_sector.sectors_per_allocation_table().unwrap() as usize;
+
+    for table_index in 0..2 {
+        let start = (boot_sector.reserved_sector_count() as usize
+            + table_index * sectors_per_allocation_table)
+            * 512;
+
+        image[start..start + reserved_entries.len()].copy_from_slice(reserved_entries);
+    }
+
+    if is_fat32(allocation_table_kind) {
+        image[512..516].copy_from_slice(&0x4161_5252_u32.to_le_bytes());
+        image[512 + 484..512 + 488].copy_from_slice(&0x6141_7272_u32.to_le_bytes());
+        image[512 + 488..512 + 496].fill(0xFF);
+        image[512 + 508..512 + 512].copy_from_slice(&0xAA55_0000_u32.to_le_bytes());
+        image[6 * 512..7 * 512].copy_from_slice(&boot_sector_bytes);
+    }
+
+    let file_system = FileSystemBuilder::from_stream(SliceStream::new(&image))
+        .with_self_test(true)
+        .build()
+        .expect("Built boot sector mounts");
+
+    assert_eq!(file_system.allocation_table_kind(), allocation_table_kind);
+    assert_eq!(file_system.volume_serial_number(), Some(0xCAFE_F00D));
+    assert_eq!(&file_system.oem_name(), b"PARTTOOL");
+    assert!(file_system.health_summary().unwrap().is_healthy());
+    assert_eq!(file_system.read_dir("").unwrap().count(), 0);
+}
+
+#[cfg(all(feature = "fat16", feature = "fat32"))]
+fn verify_fat32_conversion(file_name: &str) {
+    use embedded_fat::ConversionError;
+
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+    let allocation_table_kind = file_system.allocation_table_kind();
+    let volume_serial_number = file_system.volume_serial_number();
+    let short_names = file_system
+        .read_dir("")
+        .expect("Root directory exists")
+        .map(|item| item.expect("Every item is valid").short_name().clone())
+        .collect::<Vec<_>>();
+
+    let suspended = match file_system.convert_to_fat32() {
+        Ok(suspended) => suspended,
+        Err((_, error)) => {
+            assert_ne!(allocation_table_kind, AllocationTableKind::Fat16);
+            assert!(matches!(
+                error,
+                ConversionError::AllocationTableKindUnsupported
+            ));
+
+            return;
+        }
+    };
+    assert_eq!(allocation_table_kind, AllocationTableKind::Fat16);
+
+    let image = suspended
+        .device()
+        .with_stream(|stream| {
+            let mut image = vec![0; stream.seek(SeekFrom::End(0)).unwrap() as usize];
+            stream.seek(SeekFrom::Start(0)).unwrap();
+            stream.read_exact(&mut image).unwrap();
+
+            image
+        })
+        .unwrap();
+    let converted = FileSystemBuilder::from_stream(SliceStream::new(&image))
+        .with_self_test(true)
+        .build()
+        .expect("Converted volume mounts");
+
+    assert_eq!(
+        converted.allocation_table_kind(),
+        AllocationTableKind::Fat32
+    );
+    assert!(converted.health_summary().unwrap().is_healthy());
+    assert_eq!(
+        converted
+            .health_summary()
+            .unwrap()
+            .is_backup_boot_sector_valid(),
+        Some(true)
+    );
+
+    let file_system = suspended
+        .resume()
+        .map_err(|(_, error)| error)
+        .expect("Resuming the converted volume works");
+    let mut bytes = [0; 5];
+
+    assert_eq!(
+        file_system.allocation_table_kind(),
+        AllocationTableKind::Fat32
+    );
+    assert_eq!(file_system.volume_serial_number(), volume_serial_number);
+    assert_eq!(
+        file_system
+            .read_dir("")
+            .expect("Root directory exists")
+            .map(|item| item.expect("Every item is valid").short_name().clone())
+            .collect::<Vec<_>>(),
+        short_names
+    );
+    file_system
+        .open("TEST.TXT")
+        .unwrap()
+        .read_exact(&mut bytes)
+        .unwrap();
+    assert_eq!(&bytes, b"test\n");
+}
+
+fn verify_invalid_timestamp_policy(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    let mut temp_file = file_system.tempfile("").expect("Creating works");
+    temp_file.set_last_write(0, 0xFFFF).expect("Dating works");
+    temp_file
+        .persist("UNSET.TXT")
+        .map_err(|(_, error)| error)
+        .expect("Persisting works");
+
+    let suspended = file_system
+        .suspend()
+        .map_err(|(_, error)| error)
+        .expect("Suspending works");
+    let image = suspended
+        .device()
+        .with_stream(|stream| {
+            let mut image = vec![0; stream.seek(SeekFrom::End(0)).unwrap() as usize];
+            stream.seek(SeekFrom::Start(0)).unwrap();
+            stream.read_exact(&mut image).unwrap();
+
+            image
+        })
+        .unwrap();
+
+    let read_timestamp = |policy: InvalidTimestampPolicy| {
+        let file_system = FileSystemBuilder::from_stream(SliceStream::new(&image))
+            .with_invalid_timestamp_policy(policy)
+            .build()
+            .expect("Opening image works");
+        assert_eq!(file_system.invalid_timestamp_policy(), policy);
+
+        let item = file_system.latest("UNSET.TXT").expect("The file is found");
+        assert!(!item.is_last_write_timestamp_valid());
+
+        item.last_write_timestamp()
+    };
+
+    let first_day = FatDate::new(1980, 1, 1).unwrap();
+    let release_day = FatDate::new(2024, 6, 1).unwrap();
+    let last_time = FatTime::new(23, 59, 58).unwrap();
+
+    assert_eq!(
+        read_timestamp(InvalidTimestampPolicy::default()),
+        Err(TimestampError::MonthInvalid { month: 0 })
+    );
+    assert_eq!(
+        read_timestamp(InvalidTimestampPolicy::Clamp {
+            earliest: first_day
+        }),
+        Ok((first_day, last_time))
+    );
+    assert_eq!(
+        read_timestamp(InvalidTimestampPolicy::Clamp {
+            earliest: release_day
+        }),
+        Ok((release_day, FatTime::MIDNIGHT)),
+        "Timestamps before the earliest date are raised to its start"
+    );
+    assert_eq!(
+        read_timestamp(InvalidTimestampPolicy::Substitute {
+            date: release_day,
+            time: last_time
+        }),
+        Ok((release_day, last_time))
+    );
+
+    let file_system = FileSystemBuilder::from_stream(SliceStream::new(&image))
+        .with_invalid_timestamp_policy(InvalidTimestampPolicy::Clamp {
+            earliest: first_day,
+        })
+        .with_self_test(true)
+        .build()
+        .expect("Opening image works");
+    let health_summary = file_system.health_summary().expect("Self test was run");
+
+    assert_eq!(
+        health_summary.invalid_timestamp_count(),
+        1,
+        "Invalid timestamps are counted whatever the policy"
+    );
+    assert!(health_summary.is_healthy());
+
+    #[cfg(feature = "async")]
+    {
+        let file_system = poll_to_completion(
+            FileSystemBuilder::from_stream(SliceStream::new(&image))
+                .with_self_test(true)
+                .build_async(),
+        )
+        .0
+        .expect("Opening image works");
+
+        assert_eq!(
+            file_system
+                .health_summary()
+                .unwrap()
+                .invalid_timestamp_count(),
+            1
+        );
+    }
+}
+
+fn verify_extension_filter(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    for path in [
+        "foo/LOG1.CSV",
+        "foo/log2.csv",
+        "foo/Data.Log.Csv",
+        "foo/NOTES.TXT",
+        "foo/CSV",
+    ] {
+        file_system
+            .write_replace(path, b"contents")
+            .expect("Writing works");
+    }
+
+    let name_of = |item: DirectoryItem| match item.long_name() {
+        Some(long_name) => long_name.chars().collect::<String>(),
+        None => {
+            let short_name = item.short_name();
+            let mut name = String::from_utf8(short_name.base_name().to_vec()).unwrap();
+
+            if !short_name.extension().is_empty() {
+                name.push('.');
+                name.push_str(std::str::from_utf8(short_name.extension()).unwrap());
+            }
+
+            name
+        }
+    };
+
+    let names = file_system
+        .read_dir("foo")
+        .expect("Directory exists")
+        .with_extension("CSV")
+        .map(|item| name_of(item.expect("Every item is valid")))
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["LOG1.CSV", "LOG2.CSV", "Data.Log.Csv"]);
+
+    let names = file_system
+        .read_dir("foo")
+        .expect("Directory exists")
+        .with_dot_items_skipped(true)
+        .with_extension("")
+        .map(|item| name_of(item.expect("Every item is valid")))
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["CSV"], "Empty extensions match items without one");
+
+    let mut filtered_dir = file_system
+        .read_dir("foo")
+        .expect("Directory exists")
+        .with_filter(
+            DirectoryItemFilter::builder()
+                .extension("csv")
+                .max_size(100)
+                .build(),
+        );
+    filtered_dir.next().expect("Item exists").unwrap();
+    let cursor = filtered_dir.cursor();
+    let remaining = filtered_dir.by_ref().count();
+    assert_eq!(remaining, 2);
+
+    filtered_dir.resume(cursor);
+    let mut dir = filtered_dir.into_inner();
+    assert_eq!(
+        name_of(dir.next().expect("Item exists").unwrap()),
+        "LOG2.CSV",
+        "The unfiltered listing continues from the same position"
+    );
+
+    #[cfg(feature = "async")]
+    {
+        let image = std::fs::read(format!("disks/{file_name}")).unwrap();
+        let file_system = poll_to_completion(
+            FileSystemBuilder::from_stream(SliceStream::new(&image)).build_async(),
+        )
+        .0
+        .expect("Opening image works");
+        let mut filtered_dir = file_system
+            .read_dir("foo")
+            .expect("Directory exists")
+            .with_extension("txt");
+        let item = poll_to_completion(filtered_dir.next_async())
+            .0
+            .expect("Item exists")
+            .unwrap();
+
+        assert!(name_of(item).eq_ignore_ascii_case("bar.txt"));
+        assert!(poll_to_completion(filtered_dir.next_async()).0.is_none());
+    }
+}
+
+fn verify_remove_where(file_name: &str) {
+    let deleted_names = RefCell::new(Vec::new());
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .with_fixed_file_policy(|directory_path, name| {
+            directory_path == "foo" && name.eq_ignore_ascii_case("keep.csv")
+        })
+        .on_mutation(|event| {
+            if let MutationEvent::Deleted {
+                directory_path,
+                name,
+                ..
+            } = event
+            {
+                deleted_names
+                    .borrow_mut()
+                    .push(format!("{directory_path}/{name}"));
+            }
+        })
+        .build()
+        .expect("Opening disk works");
+
+    for (path, size) in [
+        ("foo/sensor-1.csv", 5_000),
+        ("foo/Sensor-2.CSV", 0),
+        ("foo/keep.csv", 100),
+        ("foo/notes.txt", 100),
+    ] {
+        file_system
+            .write_replace(path, &vec![b's'; size])
+            .expect("Writing works");
+    }
+    let free_cluster_count = file_system
+        .allocation_table_statistics(0)
+        .unwrap()
+        .free_cluster_count();
+
+    assert_eq!(
+        file_system
+            .remove_where("foo", |item| item.name_ends_with(".csv"))
+            .unwrap(),
+        2
+    );
+    assert_eq!(
+        *deleted_names.borrow(),
+        ["foo/sensor-1.csv", "foo/Sensor-2.CSV"]
+    );
+    assert!(file_system.open("foo/sensor-1.csv").is_none());
+    assert!(file_system.open("foo/sensor-2.csv").is_none());
+    assert!(
+        file_system.open("foo/keep.csv").is_some(),
+        "Fixed files are kept"
+    );
+    assert!(file_system.open("foo/notes.txt").is_some());
+    assert!(
+        file_system
+            .allocation_table_statistics(0)
+            .unwrap()
+            .free_cluster_count()
+            > free_cluster_count,
+        "The clusters of removed files are released"
+    );
+
+    assert_eq!(
+        file_system
+            .remove_where("", |item| item.is_directory())
+            .unwrap(),
+        0,
+        "Subdirectories are never removed"
+    );
+    assert!(file_system.read_dir("foo").is_some());
+    assert!(matches!(
+        file_system.remove_where("missing", |_| true),
+        Err(OperationError::NotFound)
+    ));
+}
+
+#[cfg(feature = "async")]
+async fn verify_write_past_end_async(file_name: &str) {
+    use embedded_io_async::{Read as AsyncRead, Seek as AsyncSeek, Write as AsyncWrite};
+
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build_async()
+        .await
+        .expect("Opening disk works");
+    let free_cluster_count = file_system
+        .allocation_table_statistics_async(0)
+        .await
+        .expect("Reading the allocation table works")
+        .free_cluster_count();
+
+    let mut file = file_system
+        .open_async("TEST.TXT")
+        .await
+        .expect("Opening a file works");
+    assert!(matches!(
+        AsyncWrite::write(&mut file, b"no").await,
+        Err(FileError::NotOpenForWriting)
+    ));
+
+    let mut writer = file_system
+        .open_writer_async("TEST.TXT")
+        .await
+        .expect("Opening a file for writing works");
+
+    AsyncSeek::seek(&mut writer, SeekFrom::Start(12))
+        .await
+        .expect("Seeking works");
+    assert_eq!(
+        AsyncWrite::write(&mut writer, b"!")
+            .await
+            .expect("Writing past the end works"),
+        1
+    );
+    assert_eq!(writer.file().size(), 13);
+    assert_eq!(writer.file().position(), 13);
+
+    let mut bytes = [0xFF; 13];
+    AsyncSeek::seek(&mut writer, SeekFrom::Start(0))
+        .await
+        .expect("Seeking works");
+    AsyncRead::read_exact(&mut writer, &mut bytes)
+        .await
+        .expect("Reading the file works");
+    assert_eq!(&bytes, b"test\n\0\0\0\0\0\0\0!");
+
+    // Spans several clusters on each of the disks
+    let end_position = 5000;
+    AsyncSeek::seek(&mut writer, SeekFrom::Start(end_position))
+        .await
+        .expect("Seeking works");
+    AsyncWrite::write_all(&mut writer, b"end")
+        .await
+        .expect("Writing past the end works");
+    AsyncWrite::flush(&mut writer)
+        .await
+        .expect("Flushing works");
+    assert_eq!(writer.file().size(), end_position + 3);
+
+    let mut bytes = vec![0xFF; end_position as usize - 13];
+    AsyncSeek::seek(&mut writer, SeekFrom::Start(13))
+        .await
+        .expect("Seeking works");
+    AsyncRead::read_exact(&mut writer, &mut bytes)
+        .await
+        .expect("Reading the file works");
+    assert!(
+        bytes.iter().all(|byte| *byte == 0),
+        "Gaps spanning new clusters read as zeros"
+    );
+
+    let mut bytes = [0; 3];
+    AsyncRead::read_exact(&mut writer, &mut bytes)
+        .await
+        .expect("Reading the file works");
+    assert_eq!(&bytes, b"end");
+    assert_eq!(writer.file().remaining(), 0);
+
+    writer.close().await.expect("Closing the guard works");
+    assert_eq!(
+        file_system
+            .open_async("TEST.TXT")
+            .await
+            .expect("Opening a file works")
+            .size(),
+        end_position + 3,
+        "Closing the guard records the new size"
+    );
+
+    let image = std::fs::read(format!("disks/{file_name}")).expect("Reading the image works");
+    let bytes_per_cluster =
+        u64::from(u16::from_le_bytes([image[11], image[12]])) * u64::from(image[13]);
+    assert_eq!(
+        file_system
+            .allocation_table_statistics_async(0)
+            .await
+            .expect("Reading the allocation table works")
+            .free_cluster_count(),
+        free_cluster_count - ((end_position + 3).div_ceil(bytes_per_cluster) - 1) as u32,
+        "Clusters are allocated as the file grows"
+    );
+}
+
+fn verify_multi_file_reader(file_name: &str) {
+    let image = std::fs::read(format!("disks/{file_name}")).unwrap();
+    let bytes_per_cluster =
+        usize::from(u16::from_le_bytes([image[11], image[12]])) * usize::from(image[13]);
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+    let contents = [
+        vec![b'a'; bytes_per_cluster * 5 / 2],
+        vec![b'b'; bytes_per_cluster + 10],
+        Vec::new(),
+    ];
+
+    for (name, contents) in ["a.log", "b.log", "c.log"].iter().zip(&contents) {
+        file_system
+            .write_replace(&format!("foo/{name}"), contents)
+            .expect("Writing works");
+    }
+
+    let open_all = || {
+        ["a.log", "b.log", "c.log"].map(|name| file_system.open(&format!("foo/{name}")).unwrap())
+    };
+
+    let mut reader = MultiFileReader::new(open_all());
+    let mut buffer = vec![0; bytes_per_cluster * 4];
+    let mut chunks = Vec::new();
+
+    while let Some((index, read_size)) = reader.read_chunk(&mut buffer).unwrap() {
+        assert!(
+            buffer[..read_size]
+                .iter()
+                .all(|byte| *byte == contents[index][0])
+        );
+        chunks.push((index, read_size));
+    }
+    assert_eq!(
+        chunks,
+        [
+            (0, bytes_per_cluster),
+            (1, bytes_per_cluster),
+            (0, bytes_per_cluster),
+            (1, 10),
+            (0, bytes_per_cluster / 2),
+        ],
+        "Files take turns of one cluster until they end"
+    );
+    assert_eq!(reader.current_index(), None);
+
+    let mut files = open_all();
+    files[0].seek(SeekFrom::Start(10)).unwrap();
+    let mut reader = MultiFileReader::new(files);
+    let mut merged = Vec::new();
+    let mut small_buffer = [0; 100];
+
+    loop {
+        match reader.read(&mut small_buffer).unwrap() {
+            0 => break,
+            read_size => merged.extend_from_slice(&small_buffer[..read_size]),
+        }
+    }
+
+    let mut expected = Vec::new();
+    expected.extend(vec![b'a'; bytes_per_cluster - 10]);
+    expected.extend(vec![b'b'; bytes_per_cluster]);
+    expected.extend(vec![b'a'; bytes_per_cluster]);
+    expected.extend(vec![b'b'; 10]);
+    expected.extend(vec![b'a'; bytes_per_cluster / 2]);
+    assert_eq!(merged, expected, "Turns continue over small reads");
+}
+
+fn verify_concatenated_file(file_name: &str) {
+    let image = std::fs::read(format!("disks/{file_name}")).unwrap();
+    let bytes_per_cluster =
+        usize::from(u16::from_le_bytes([image[11], image[12]])) * usize::from(image[13]);
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+    let names = ["FW.001", "FW.002", "FW.003", "FW.004"];
+    let chunks = [
+        (0..bytes_per_cluster + 100)
+            .map(|index| index as u8)
+            .collect::<Vec<_>>(),
+        Vec::new(),
+        (0..bytes_per_cluster * 2)
+            .map(|index| (index * 7) as u8)
+            .collect::<Vec<_>>(),
+        vec![0xAB; 10],
+    ];
+    let image_contents = chunks.concat();
+
+    for (name, chunk) in names.iter().zip(&chunks) {
+        file_system
+            .write_replace(&format!("foo/{name}"), chunk)
+            .expect("Writing works");
+    }
+
+    let mut firmware =
+        ConcatenatedFile::new(names.map(|name| file_system.open(&format!("foo/{name}")).unwrap()));
+    assert_eq!(firmware.size(), image_contents.len() as u64);
+
+    let mut contents = Vec::new();
+    let mut buffer = [0; 300];
+
+    loop {
+        match firmware.read(&mut buffer).unwrap() {
+            0 => break,
+            read_size => contents.extend_from_slice(&buffer[..read_size]),
+        }
+    }
+    assert_eq!(contents, image_contents, "Files are read in order as one");
+    assert_eq!(firmware.remaining(), 0);
+
+    // Reads across the boundaries between files, including the empty one
+    let boundary = (bytes_per_cluster + 100) as u64;
+    let mut across = [0; 20];
+    firmware.seek(SeekFrom::Start(boundary - 10)).unwrap();
+    firmware.read_exact(&mut across).unwrap();
+    assert_eq!(across[..], image_contents[boundary as usize - 10..][..20]);
+
+    let mut tail = [0; 15];
+    firmware.seek(SeekFrom::End(-15)).unwrap();
+    firmware.read_exact(&mut tail).unwrap();
+    assert_eq!(tail[..], image_contents[image_contents.len() - 15..]);
+
+    firmware.seek(SeekFrom::Start(5)).unwrap();
+    assert_eq!(firmware.seek(SeekFrom::Current(3)).unwrap(), 8);
+    firmware.read_exact(&mut across).unwrap();
+    assert_eq!(across[..], image_contents[8..28]);
+
+    assert!(matches!(
+        firmware.seek(SeekFrom::End(1)),
+        Err(FileError::SeekPositionBeyondEnd(_))
+    ));
+    assert!(matches!(
+        firmware.seek(SeekFrom::Current(-100)),
+        Err(FileError::SeekPositionImpossible(-72))
+    ));
+}
+
+fn verify_file_slice(file_name: &str) {
+    let image = std::fs::read(format!("disks/{file_name}")).unwrap();
+    let bytes_per_cluster =
+        usize::from(u16::from_le_bytes([image[11], image[12]])) * usize::from(image[13]);
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+    let data = (0..bytes_per_cluster * 2)
+        .map(|index| (index * 3) as u8)
+        .collect::<Vec<_>>();
+    let contents = [b"RIFF header ".as_slice(), &data, b"trailer"].concat();
+
+    file_system
+        .write_replace("foo/sound.wav", &contents)
+        .expect("Writing works");
+
+    let mut file = file_system.open("foo/sound.wav").unwrap();
+    let mut slice = file.slice(12, data.len() as u64).unwrap();
+    assert_eq!(slice.offset(), 12);
+    assert_eq!(slice.len(), data.len() as u64);
+
+    let mut sliced = Vec::new();
+    let mut buffer = [0; 300];
+
+    loop {
+        match slice.read(&mut buffer).unwrap() {
+            0 => break,
+            read_size => sliced.extend_from_slice(&buffer[..read_size]),
+        }
+    }
+    assert_eq!(sliced, data, "Reads end at the end of the region");
+    assert_eq!(slice.remaining(), 0);
+
+    let mut tail = [0; 10];
+    assert_eq!(
+        slice.seek(SeekFrom::End(-10)).unwrap(),
+        data.len() as u64 - 10
+    );
+    slice.read_exact(&mut tail).unwrap();
+    assert_eq!(tail[..], data[data.len() - 10..]);
+
+    slice.rewind().unwrap();
+    slice.read_exact(&mut tail).unwrap();
+    assert_eq!(tail[..], data[..10]);
+
+    assert!(matches!(
+        slice.seek(SeekFrom::End(1)),
+        Err(FileError::SeekPositionBeyondEnd(_))
+    ));
+    assert!(matches!(
+        slice.seek(SeekFrom::Current(-11)),
+        Err(FileError::SeekPositionImpossible(-1))
+    ));
+
+    assert!(matches!(
+        file.slice(12, contents.len() as u64),
+        Err(FileError::SeekPositionBeyondEnd(_))
+    ));
+
+    let mut trailer = [0; 7];
+    file.seek(SeekFrom::End(-7)).unwrap();
+    file.read_exact(&mut trailer).unwrap();
+    assert_eq!(
+        &trailer, b"trailer",
+        "The file remains usable after the slice"
+    );
+}
+
+fn verify_fs_info(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+
+    let fs_info = file_system.fs_info().expect("Reading FS info works");
+
+    if !is_fat32(file_system.allocation_table_kind()) {
+        assert_eq!(fs_info, None, "Only FAT32 volumes have FS info");
+        return;
+    }
+
+    let fs_info = fs_info.expect("FAT32 volumes have FS info");
+    let statistics = file_system.allocation_table_statistics(0).unwrap();
+
+    assert_eq!(
+        fs_info.free_cluster_count(),
+        Some(statistics.free_cluster_count()),
+        "The free cluster count matches the allocation table"
+    );
+    assert!(fs_info.next_free_cluster_hint().is_some());
+}
+
+fn verify_buffer_size(file_name: &str) {
+    let file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .build()
+        .expect("Opening disk works");
+    let small_file_system = FileSystemBuilder::from_stream(MemoryStream::from_disk(file_name))
+        .with_buffer_size::<64>()
+        .build()
+        .expect("Opening disk with small buffers works");
+
+    small_file_system
+        .write_replace("foo/a long file name.txt", &[1; 3_000])
+        .expect("Replacing the file works");
+    file_system
+        .write_replace("foo/a long file name.txt", &[1; 3_000])
+        .expect("Replacing the file works");
+
+    for path in ["", "foo"] {
+        let names = small_file_system
+            .read_dir(path)
+            .expect("Directory exists")
+            .map(|item| item.expect("Every item is valid").short_name().clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            names,
+            file_system
+                .read_dir(path)
+                .expect("Directory exists")
+                .map(|item| item.expect("Every item is valid").short_name().clone())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    let mut crc32 = Crc32::new();
+    small_file_system
+        .hash("foo/a long file name.txt", &mut crc32)
+        .expect("Hashing a file works");
+    let mut expected_crc32 = Crc32::new();
+    expected_crc32.update(&[1; 3_000]);
+    assert_eq!(crc32.finalize(), expected_crc32.finalize());
+
+    let mut backup = vec![0; file_system.metadata_size() as usize];
+    let mut small_backup = vec![0; backup.len()];
+
+    file_system
+        .backup_metadata(&mut backup.as_mut_slice())
+        .expect("Backing up works");
+    small_file_system
+        .backup_metadata(&mut small_backup.as_mut_slice())
+        .expect("Backing up works");
+    assert_eq!(small_backup, backup);
+    small_file_system
+        .restore_metadata(&mut small_backup.as_slice())
+        .expect("Restoring works");
+
+    #[cfg(all(feature = "fat16", feature = "fat32"))]
+    if file_system.allocation_table_kind() == AllocationTableKind::Fat16 {
+        let read_image = |stream: &mut MemoryStream| {
+            let mut image = vec![0; stream.seek(SeekFrom::End(0)).unwrap() as usize];
+            stream.seek(SeekFrom::Start(0)).unwrap();
+            stream.read_exact(&mut image).unwrap();
+
+            image
+        };
+        let Ok(suspended) = file_system.convert_to_fat32() else {
+            panic!("Converting works");
+        };
+        let Ok(small_suspended) = small_file_system.convert_to_fat32() else {
+            panic!("Converting with small buffers works");
+        };
+
+        assert!(
+            small_suspended.device().with_stream(read_image).unwrap()
+                == suspended.device().with_stream(read_image).unwrap(),
+            "Converting with small buffers writes the same volume"
+        );
     }
 }
@@ -0,0 +1,257 @@
+#![cfg(feature = "ffi")]
+
+use core::ffi::{c_int, c_void};
+use embedded_fat::ffi::*;
+use std::ffi::{CStr, CString};
+use std::fs::File;
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::mem::MaybeUninit;
+
+unsafe extern "C" fn read<T: Read>(context: *mut c_void, buffer: *mut u8, length: usize) -> isize {
+    let stream = unsafe { &mut *context.cast::<T>() };
+    let buffer = unsafe { std::slice::from_raw_parts_mut(buffer, length) };
+
+    stream.read(buffer).map_or(-1, |read| read as isize)
+}
+
+unsafe extern "C" fn write<T: Write>(
+    context: *mut c_void,
+    buffer: *const u8,
+    length: usize,
+) -> isize {
+    let stream = unsafe { &mut *context.cast::<T>() };
+    let buffer = unsafe { std::slice::from_raw_parts(buffer, length) };
+
+    stream.write(buffer).map_or(-1, |written| written as isize)
+}
+
+unsafe extern "C" fn seek<T: Seek>(context: *mut c_void, offset: i64, whence: c_int) -> i64 {
+    let stream = unsafe { &mut *context.cast::<T>() };
+    let position = match whence {
+        EMBEDDED_FAT_SEEK_SET => SeekFrom::Start(offset as u64),
+        EMBEDDED_FAT_SEEK_CUR => SeekFrom::Current(offset),
+        EMBEDDED_FAT_SEEK_END => SeekFrom::End(offset),
+        _ => return -1,
+    };
+
+    stream.seek(position).map_or(-1, |position| position as i64)
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12() {
+    verify_disk("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16() {
+    verify_disk("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32() {
+    verify_disk("fat32.img");
+}
+
+#[test]
+#[cfg(feature = "fat12")]
+fn fat12_write() {
+    verify_write("fat12.img");
+}
+
+#[test]
+#[cfg(feature = "fat16")]
+fn fat16_write() {
+    verify_write("fat16.img");
+}
+
+#[test]
+#[cfg(feature = "fat32")]
+fn fat32_write() {
+    verify_write("fat32.img");
+}
+
+fn verify_disk(file_name: &str) {
+    let mut disk = File::open(String::from("disks/") + file_name).unwrap();
+    let io = EmbeddedFatIo {
+        context: (&raw mut disk).cast(),
+        read: Some(read::<File>),
+        write: None,
+        seek: Some(seek::<File>),
+        flush: None,
+    };
+
+    let mut fs = MaybeUninit::<EmbeddedFatFileSystem>::uninit();
+
+    unsafe {
+        assert_eq!(
+            embedded_fat_mount(fs.as_mut_ptr(), &io),
+            EMBEDDED_FAT_OK,
+            "Mounting the disk works"
+        );
+
+        {
+            let mut file = MaybeUninit::<EmbeddedFatFile>::uninit();
+            let mut bytes = [0; 16];
+
+            assert_eq!(
+                embedded_fat_open(fs.as_ptr(), c"foo/bar.txt".as_ptr(), file.as_mut_ptr()),
+                EMBEDDED_FAT_OK
+            );
+            assert_eq!(
+                embedded_fat_read(file.as_mut_ptr(), bytes.as_mut_ptr(), bytes.len()),
+                7
+            );
+            assert_eq!(&bytes[..7], "redrum\n".as_bytes());
+            assert_eq!(
+                embedded_fat_write(file.as_mut_ptr(), bytes.as_ptr(), bytes.len()),
+                EMBEDDED_FAT_ERROR_INVALID_ARGUMENT as isize,
+                "Files opened for reading aren't written"
+            );
+            assert_eq!(embedded_fat_close(file.as_mut_ptr()), EMBEDDED_FAT_OK);
+        }
+
+        {
+            let mut file = MaybeUninit::<EmbeddedFatFile>::uninit();
+
+            assert_eq!(
+                embedded_fat_open(fs.as_ptr(), c"missing.txt".as_ptr(), file.as_mut_ptr()),
+                EMBEDDED_FAT_ERROR_NOT_FOUND
+            );
+        }
+
+        {
+            let mut dir = MaybeUninit::<EmbeddedFatDir>::uninit();
+            let mut entry = MaybeUninit::<EmbeddedFatDirEntry>::uninit();
+            let mut short_names = Vec::new();
+            let mut long_names = Vec::new();
+
+            assert_eq!(
+                embedded_fat_opendir(fs.as_ptr(), c"".as_ptr(), dir.as_mut_ptr()),
+                EMBEDDED_FAT_OK
+            );
+
+            while embedded_fat_readdir(dir.as_mut_ptr(), entry.as_mut_ptr()) == 1 {
+                let entry = entry.assume_init_ref();
+
+                short_names.push(
+                    CStr::from_bytes_until_nul(&entry.short_name)
+                        .unwrap()
+                        .to_str()
+                        .unwrap()
+                        .to_owned(),
+                );
+                long_names.push(
+                    CStr::from_bytes_until_nul(&entry.long_name)
+                        .unwrap()
+                        .to_str()
+                        .unwrap()
+                        .to_owned(),
+                );
+            }
+
+            assert_eq!(embedded_fat_closedir(dir.as_mut_ptr()), EMBEDDED_FAT_OK);
+            assert!(short_names.iter().any(|name| name == "TEST.TXT"));
+            assert!(short_names.iter().any(|name| name == "FOO"));
+            assert!(long_names.iter().any(|name| name == "long-File.name.txt"));
+        }
+
+        assert_eq!(embedded_fat_unmount(fs.as_mut_ptr()), EMBEDDED_FAT_OK);
+    }
+}
+
+fn verify_write(file_name: &str) {
+    let mut disk = Cursor::new(std::fs::read(String::from("disks/") + file_name).unwrap());
+    let io = EmbeddedFatIo {
+        context: (&raw mut disk).cast(),
+        read: Some(read::<Cursor<Vec<u8>>>),
+        write: Some(write::<Cursor<Vec<u8>>>),
+        seek: Some(seek::<Cursor<Vec<u8>>>),
+        flush: None,
+    };
+    // Spans several clusters on each of the disks
+    let contents = (0..5000).map(|index| index as u8).collect::<Vec<_>>();
+
+    let mut fs = MaybeUninit::<EmbeddedFatFileSystem>::uninit();
+
+    unsafe {
+        assert_eq!(
+            embedded_fat_mount(fs.as_mut_ptr(), &io),
+            EMBEDDED_FAT_OK,
+            "Mounting the disk works"
+        );
+
+        {
+            let mut file = MaybeUninit::<EmbeddedFatFile>::uninit();
+            let mut bytes = [0; 4];
+            let mut path = *b"foo/bar.txt\0";
+
+            assert_eq!(
+                embedded_fat_open_writer(fs.as_ptr(), path.as_ptr().cast(), file.as_mut_ptr()),
+                EMBEDDED_FAT_OK
+            );
+            // The path isn't needed once the file is open
+            path.fill(0);
+            assert_eq!(
+                embedded_fat_read(file.as_mut_ptr(), bytes.as_mut_ptr(), bytes.len()),
+                4
+            );
+            assert_eq!(&bytes, b"redr", "Written files can be read");
+            assert_eq!(
+                embedded_fat_write(file.as_mut_ptr(), contents.as_ptr(), contents.len()),
+                contents.len() as isize
+            );
+            assert_eq!(embedded_fat_close(file.as_mut_ptr()), EMBEDDED_FAT_OK);
+        }
+
+        {
+            let mut file = MaybeUninit::<EmbeddedFatFile>::uninit();
+
+            assert_eq!(
+                embedded_fat_open_writer(fs.as_ptr(), c"foo".as_ptr(), file.as_mut_ptr()),
+                EMBEDDED_FAT_ERROR_NOT_FOUND,
+                "Directories aren't opened for writing"
+            );
+            assert_eq!(
+                embedded_fat_open_writer(fs.as_ptr(), c"missing.txt".as_ptr(), file.as_mut_ptr()),
+                EMBEDDED_FAT_ERROR_NOT_FOUND
+            );
+
+            let long_path =
+                CString::new("a/".repeat(EMBEDDED_FAT_PATH_SIZE / 2) + "b.txt").unwrap();
+
+            assert_eq!(
+                embedded_fat_open_writer(fs.as_ptr(), long_path.as_ptr(), file.as_mut_ptr()),
+                EMBEDDED_FAT_ERROR_INVALID_ARGUMENT,
+                "Paths longer than the handle holds aren't opened for writing"
+            );
+        }
+
+        assert_eq!(embedded_fat_unmount(fs.as_mut_ptr()), EMBEDDED_FAT_OK);
+
+        // Only what reached the directory entries and allocation table survives remounting
+        assert_eq!(embedded_fat_mount(fs.as_mut_ptr(), &io), EMBEDDED_FAT_OK);
+
+        {
+            let mut file = MaybeUninit::<EmbeddedFatFile>::uninit();
+            let mut bytes = vec![0; contents.len() + 16];
+
+            assert_eq!(
+                embedded_fat_open(fs.as_ptr(), c"foo/bar.txt".as_ptr(), file.as_mut_ptr()),
+                EMBEDDED_FAT_OK
+            );
+            assert_eq!(
+                embedded_fat_read(file.as_mut_ptr(), bytes.as_mut_ptr(), bytes.len()),
+                (contents.len() + 4) as isize,
+                "Closing the file records its new size"
+            );
+            assert_eq!(&bytes[..4], b"redr");
+            assert_eq!(&bytes[4..][..contents.len()], contents);
+            assert_eq!(embedded_fat_close(file.as_mut_ptr()), EMBEDDED_FAT_OK);
+        }
+
+        assert_eq!(embedded_fat_unmount(fs.as_mut_ptr()), EMBEDDED_FAT_OK);
+    }
+}
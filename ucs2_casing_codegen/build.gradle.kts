@@ -46,7 +46,7 @@ tasks {
         val outputFile = project.parent!!.layout.projectDirectory.file("src/encoding/ucs2_character/case_folding.rs")
 
         commandLine(
-            "cargo", "run", "--",
+            "cargo", "run", "--", "case-folding",
             "--case-folding-file", caseFoldingFileLocation,
             "--output-file", outputFile
         )
@@ -63,6 +63,62 @@ tasks {
         }
     }
 
+    val regenerateNormalization by registering(Exec::class) {
+        val unicodeDataFileLocation = getTemporaryDir().toPath().resolve("UnicodeData.txt").toFile()
+        val compositionExclusionsFileLocation = getTemporaryDir().toPath().resolve("CompositionExclusions.txt").toFile()
+        val outputFile = project.parent!!.layout.projectDirectory.file("src/encoding/ucs2_character/normalization.rs")
+
+        commandLine(
+            "cargo", "run", "--", "normalization",
+            "--unicode-data-file", unicodeDataFileLocation,
+            "--composition-exclusions-file", compositionExclusionsFileLocation,
+            "--output-file", outputFile
+        )
+
+        doFirst {
+            val yesterday = Instant.now().minus(Duration.ofDays(1))
+            val shouldOverwrite = unicodeDataFileLocation.lastModified() < yesterday.toEpochMilli()
+
+            download.run {
+                src(listOf(
+                    "https://www.unicode.org/Public/UCD/latest/ucd/UnicodeData.txt",
+                    "https://www.unicode.org/Public/UCD/latest/ucd/CompositionExclusions.txt"
+                ))
+                dest(getTemporaryDir())
+                overwrite(shouldOverwrite)
+            }
+        }
+    }
+
+    regenerateUcs2Casing.configure {
+        dependsOn(regenerateNormalization)
+    }
+
+    listOf("437", "850").forEach { codePage ->
+        val regenerateCodePage = register("regenerateCodePage$codePage", Exec::class) {
+            val mappingFileLocation = getTemporaryDir().toPath().resolve("CP$codePage.TXT").toFile()
+            val outputFile = project.parent!!.layout.projectDirectory.file("src/encoding/cp${codePage}_encoder/code_page.rs")
+
+            commandLine(
+                "cargo", "run", "--", "code-page",
+                "--mapping-file", mappingFileLocation,
+                "--output-file", outputFile
+            )
+
+            doFirst {
+                download.run {
+                    src("https://www.unicode.org/Public/MAPPINGS/VENDORS/MICSFT/PC/CP$codePage.TXT")
+                    dest(mappingFileLocation)
+                    overwrite(false)
+                }
+            }
+        }
+
+        regenerateUcs2Casing.configure {
+            dependsOn(regenerateCodePage)
+        }
+    }
+
     register("fmt") {
         dependsOn(cargoFormat)
     }
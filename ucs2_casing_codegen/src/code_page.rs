@@ -0,0 +1,230 @@
+use clio::Input;
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, BufReader};
+
+// Windows stores short names by uppercasing each character and then converting it to the volume's
+// OEM code page, falling back to the original character when its uppercase form doesn't exist in
+// the code page.  Only mappings where both characters are encodable in the code page are emitted,
+// so uppercasing never turns an encodable character into an unencodable one.
+#[derive(Clone, Debug)]
+pub struct CodePage {
+    parsed_lookup: Vec<(u8, u16)>,
+
+    encode_lookup: Vec<(u16, u8)>,
+    uppercase_lookup: Vec<(u16, u16)>,
+}
+
+impl CodePage {
+    pub fn parse_from(mapping_file: &mut Input) -> CodePage {
+        let reader = BufReader::new(mapping_file);
+        let mut parsed_lookup = Vec::with_capacity(256);
+
+        for line_result in reader.lines() {
+            let line = line_result.as_ref().unwrap().trim_ascii();
+
+            if line.is_empty() || line.starts_with("#") {
+                continue;
+            }
+
+            let parts: Vec<&str> = line
+                .split('#')
+                .next()
+                .unwrap()
+                .split_ascii_whitespace()
+                .collect();
+
+            // Bytes without a mapping only list the byte value
+            if parts.len() < 2 {
+                continue;
+            }
+
+            let code = u32::from_str_radix(parts[0].trim_start_matches("0x"), 16).unwrap();
+            let mapping = u32::from_str_radix(parts[1].trim_start_matches("0x"), 16).unwrap();
+
+            assert!(code <= 0xFF, "OEM code pages are single byte encodings");
+            assert!(
+                mapping <= 0xFFFF,
+                "Short names can only be compared against UCS-2 characters"
+            );
+
+            if code <= 0x7F {
+                assert_eq!(
+                    code, mapping,
+                    "Handling for the range [0x00, 0x7F] is hard-coded in this code generator \
+                        with the assumption that OEM code pages are ASCII compatible"
+                );
+
+                continue;
+            }
+
+            parsed_lookup.push((code as u8, mapping as u16));
+        }
+
+        parsed_lookup.sort_by_key(|(code, _)| *code);
+
+        let mut encode_lookup: Vec<(u16, u8)> = parsed_lookup
+            .iter()
+            .map(|(code, mapping)| (*mapping, *code))
+            .collect();
+
+        encode_lookup.sort_by_key(|(mapping, _)| *mapping);
+        encode_lookup.dedup_by_key(|(mapping, _)| *mapping);
+
+        let mut uppercase_lookup = Vec::with_capacity(encode_lookup.len());
+
+        for (mapping, _) in encode_lookup.iter() {
+            let character = char::from_u32(*mapping as u32).unwrap();
+            let mut uppercase = character.to_uppercase();
+
+            // Characters which uppercase to multiple characters, such as 'ß', are left as-is
+            let (Some(uppercase_character), None) = (uppercase.next(), uppercase.next()) else {
+                continue;
+            };
+
+            let uppercase_mapping = uppercase_character as u32;
+
+            if uppercase_mapping == *mapping as u32 || uppercase_mapping > 0xFFFF {
+                continue;
+            }
+
+            let is_encodable = uppercase_mapping <= 0x7F
+                || encode_lookup
+                    .binary_search_by_key(&(uppercase_mapping as u16), |(key, _)| *key)
+                    .is_ok();
+
+            if is_encodable {
+                uppercase_lookup.push((*mapping, uppercase_mapping as u16));
+            }
+        }
+
+        CodePage {
+            parsed_lookup,
+            encode_lookup,
+            uppercase_lookup,
+        }
+    }
+}
+
+impl Display for CodePage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "static ENCODE_LOOKUP: [(u16, u8); {}] = [",
+            self.encode_lookup.len()
+        )?;
+
+        for (key, value) in self.encode_lookup.iter() {
+            writeln!(f, "    (0x{key:04X}, 0x{value:02X}),")?;
+        }
+
+        writeln!(f, "];")?;
+        writeln!(f)?;
+
+        writeln!(
+            f,
+            "static UPPERCASE_LOOKUP: [(u16, u16); {}] = [",
+            self.uppercase_lookup.len()
+        )?;
+
+        for (key, value) in self.uppercase_lookup.iter() {
+            writeln!(f, "    (0x{key:04X}, 0x{value:04X}),")?;
+        }
+
+        writeln!(f, "];")?;
+        writeln!(f)?;
+
+        writeln!(f, "pub fn encode_character(character: u16) -> Option<u8> {{")?;
+        writeln!(
+            f,
+            "    // Handle ASCII range explicitly to optimize for the most common characters"
+        )?;
+        writeln!(f, "    if matches!(character, 0x0000..=0x007F) {{")?;
+        writeln!(f, "        return Some(character as u8);")?;
+        writeln!(f, "    }}")?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "    match ENCODE_LOOKUP.binary_search_by_key(&character, |&(key, _)| key) {{"
+        )?;
+        writeln!(f, "        Ok(index) => Some(ENCODE_LOOKUP[index].1),")?;
+        writeln!(f, "        Err(_) => None,")?;
+        writeln!(f, "    }}")?;
+        writeln!(f, "}}")?;
+        writeln!(f)?;
+
+        writeln!(f, "pub fn uppercase_character(character: u16) -> u16 {{")?;
+        writeln!(
+            f,
+            "    // Handle ASCII range explicitly to optimize for the most common characters"
+        )?;
+        writeln!(f, "    if matches!(character, 0x0000..=0x007F) {{")?;
+        writeln!(f, "        return match character {{")?;
+        writeln!(f, "            0x0061..=0x007A => character - 32,")?;
+        writeln!(f, "            _ => character,")?;
+        writeln!(f, "        }};")?;
+        writeln!(f, "    }}")?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "    match UPPERCASE_LOOKUP.binary_search_by_key(&character, |&(key, _)| key) {{"
+        )?;
+        writeln!(f, "        Ok(index) => UPPERCASE_LOOKUP[index].1,")?;
+        writeln!(f, "        Err(_) => character,")?;
+        writeln!(f, "    }}")?;
+        writeln!(f, "}}")?;
+        writeln!(f)?;
+
+        writeln!(f, "#[cfg(test)]")?;
+        writeln!(f, "pub mod tests {{")?;
+        writeln!(f, "    use super::*;")?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "    static PARSED_LOOKUP: [(u8, u16); {}] = [",
+            self.parsed_lookup.len()
+        )?;
+        for (code, mapping) in self.parsed_lookup.iter() {
+            writeln!(f, "        (0x{:02X}, 0x{:04X}),", *code, *mapping)?;
+        }
+        writeln!(f, "    ];")?;
+        writeln!(f)?;
+
+        writeln!(f, "    #[test]")?;
+        writeln!(f, "    fn encode_character_matches_parsed_lookup() {{")?;
+        writeln!(f, "        for (code, mapping) in PARSED_LOOKUP {{")?;
+        writeln!(f, "            assert_eq!(")?;
+        writeln!(f, "                encode_character(mapping),")?;
+        writeln!(f, "                Some(code),")?;
+        writeln!(
+            f,
+            "                \"Encoded result should match parsed result for {{:04X}}\","
+        )?;
+        writeln!(f, "                mapping")?;
+        writeln!(f, "            );")?;
+        writeln!(f, "        }}")?;
+        writeln!(f, "    }}")?;
+        writeln!(f)?;
+
+        writeln!(f, "    #[test]")?;
+        writeln!(f, "    fn uppercase_character_remains_encodable() {{")?;
+        writeln!(f, "        for character in 0x0000..=0xFFFF {{")?;
+        writeln!(f, "            if encode_character(character).is_some() {{")?;
+        writeln!(f, "                assert!(")?;
+        writeln!(
+            f,
+            "                    encode_character(uppercase_character(character)).is_some(),"
+        )?;
+        writeln!(
+            f,
+            "                    \"Uppercase result should be encodable for {{:04X}}\","
+        )?;
+        writeln!(f, "                    character")?;
+        writeln!(f, "                );")?;
+        writeln!(f, "            }}")?;
+        writeln!(f, "        }}")?;
+        writeln!(f, "    }}")?;
+        writeln!(f, "}}")?;
+
+        Ok(())
+    }
+}
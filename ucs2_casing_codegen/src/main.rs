@@ -1,33 +1,82 @@
 mod case_folding;
+mod code_page;
+mod normalization;
 
 use crate::case_folding::CaseFolding;
-use clap::Parser;
+use crate::code_page::CodePage;
+use crate::normalization::Normalization;
+use clap::{Parser, Subcommand};
 use clio::{Input, Output};
+use std::fmt::Display;
 use std::io::{BufWriter, Write};
 
 #[derive(Clone, Debug, Parser)]
 #[command(name = "ucs2-casing-codegen")]
 struct Args {
-    #[arg(long, value_parser)]
-    case_folding_file: Input,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+enum Command {
+    /// Generates the case folding table used to compare long names
+    CaseFolding {
+        #[arg(long, value_parser)]
+        case_folding_file: Input,
+
+        #[arg(long, value_parser)]
+        output_file: Output,
+    },
+
+    /// Generates the encoding and uppercase tables of an OEM code page used to store short names
+    CodePage {
+        #[arg(long, value_parser)]
+        mapping_file: Input,
+
+        #[arg(long, value_parser)]
+        output_file: Output,
+    },
 
-    #[arg(long, value_parser)]
-    output_file: Output,
+    /// Generates the combining class and composition tables used to compose long names
+    Normalization {
+        #[arg(long, value_parser)]
+        unicode_data_file: Input,
+
+        #[arg(long, value_parser)]
+        composition_exclusions_file: Input,
+
+        #[arg(long, value_parser)]
+        output_file: Output,
+    },
 }
 
 fn main() {
-    let mut args = Args::parse();
+    match Args::parse().command {
+        Command::CaseFolding {
+            mut case_folding_file,
+            output_file,
+        } => write_output(output_file, CaseFolding::parse_from(&mut case_folding_file)),
+        Command::CodePage {
+            mut mapping_file,
+            output_file,
+        } => write_output(output_file, CodePage::parse_from(&mut mapping_file)),
+        Command::Normalization {
+            mut unicode_data_file,
+            mut composition_exclusions_file,
+            output_file,
+        } => write_output(
+            output_file,
+            Normalization::parse_from(&mut unicode_data_file, &mut composition_exclusions_file),
+        ),
+    }
+}
 
+fn write_output(mut output_file: Output, generated: impl Display) {
     {
-        let mut file = BufWriter::new(&mut args.output_file);
-
-        write!(
-            &mut file,
-            "{}",
-            CaseFolding::parse_from(&mut args.case_folding_file)
-        )
-        .unwrap();
+        let mut file = BufWriter::new(&mut output_file);
+
+        write!(&mut file, "{generated}").unwrap();
     }
 
-    args.output_file.finish().unwrap();
+    output_file.finish().unwrap();
 }
@@ -0,0 +1,247 @@
+use clio::Input;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, BufReader};
+
+// Composing the characters of a name is the final step of NFC normalization, the preceding
+// decomposition and canonical ordering steps are skipped since names are expected to either be
+// fully composed or fully decomposed as written by macOS.  Hangul syllables are composed
+// algorithmically and so are left out of the composition table.
+const HANGUL_SYLLABLES: (u32, u32) = (0xAC00, 0xD7A3);
+
+#[derive(Clone, Debug)]
+pub struct Normalization {
+    parsed_combining_classes: Vec<(u16, u8)>,
+
+    combining_class_runs: Vec<(u16, u16, u8)>,
+    composition_lookup: Vec<(u32, u16)>,
+}
+
+impl Normalization {
+    pub fn parse_from(
+        unicode_data_file: &mut Input,
+        composition_exclusions_file: &mut Input,
+    ) -> Normalization {
+        let exclusions = Self::parse_exclusions(composition_exclusions_file);
+
+        let mut combining_classes = HashMap::new();
+        let mut decompositions = Vec::with_capacity(1000);
+
+        for line_result in BufReader::new(unicode_data_file).lines() {
+            let line = line_result.as_ref().unwrap().trim_ascii();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let parts: Vec<&str> = line.split(';').collect();
+
+            let code = u32::from_str_radix(parts[0], 16).unwrap();
+            let combining_class: u8 = parts[3].parse().unwrap();
+
+            if code > 0xFFFF {
+                continue;
+            }
+
+            if combining_class != 0 {
+                combining_classes.insert(code as u16, combining_class);
+            }
+
+            // Compatibility decompositions are tagged and never take part in composition
+            if parts[5].is_empty() || parts[5].starts_with('<') {
+                continue;
+            }
+
+            let mapping: Vec<u32> = parts[5]
+                .split(' ')
+                .map(|value| u32::from_str_radix(value, 16).unwrap())
+                .collect();
+
+            // Singleton decompositions are excluded from composition
+            if let [first, second] = mapping[..] {
+                decompositions.push((code, first, second));
+            }
+        }
+
+        let mut composition_lookup = Vec::with_capacity(decompositions.len());
+
+        for (code, first, second) in decompositions {
+            if exclusions.contains(&code) || first > 0xFFFF || second > 0xFFFF {
+                continue;
+            }
+
+            assert!(
+                !(HANGUL_SYLLABLES.0..=HANGUL_SYLLABLES.1).contains(&code),
+                "Hangul syllables are composed algorithmically"
+            );
+
+            // Non-starter decompositions are excluded from composition
+            if combining_classes.contains_key(&(code as u16))
+                || combining_classes.contains_key(&(first as u16))
+            {
+                continue;
+            }
+
+            composition_lookup.push(((first << 16) | second, code as u16));
+        }
+
+        composition_lookup.sort_by_key(|(key, _)| *key);
+
+        let mut parsed_combining_classes: Vec<(u16, u8)> = combining_classes.into_iter().collect();
+        parsed_combining_classes.sort_by_key(|(code, _)| *code);
+
+        let mut combining_class_runs: Vec<(u16, u16, u8)> = Vec::with_capacity(500);
+
+        for (code, combining_class) in parsed_combining_classes.iter() {
+            match combining_class_runs.last_mut() {
+                Some((_, end, run_class)) if *end + 1 == *code && run_class == combining_class => {
+                    *end = *code
+                }
+                _ => combining_class_runs.push((*code, *code, *combining_class)),
+            }
+        }
+
+        Normalization {
+            parsed_combining_classes,
+            combining_class_runs,
+            composition_lookup,
+        }
+    }
+
+    fn parse_exclusions(composition_exclusions_file: &mut Input) -> HashSet<u32> {
+        let reader = BufReader::new(composition_exclusions_file);
+        let mut exclusions = HashSet::new();
+
+        for line_result in reader.lines() {
+            let line = line_result.as_ref().unwrap();
+            let value = line.split('#').next().unwrap().trim_ascii();
+
+            if value.is_empty() {
+                continue;
+            }
+
+            exclusions.insert(u32::from_str_radix(value, 16).unwrap());
+        }
+
+        exclusions
+    }
+}
+
+impl Display for Normalization {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "static COMBINING_CLASS_LOOKUP: [(u16, u16, u8); {}] = [",
+            self.combining_class_runs.len()
+        )?;
+
+        for (start, end, combining_class) in self.combining_class_runs.iter() {
+            writeln!(f, "    (0x{start:04X}, 0x{end:04X}, {combining_class}),")?;
+        }
+
+        writeln!(f, "];")?;
+        writeln!(f)?;
+
+        writeln!(
+            f,
+            "static COMPOSITION_LOOKUP: [(u32, u16); {}] = [",
+            self.composition_lookup.len()
+        )?;
+
+        for (key, value) in self.composition_lookup.iter() {
+            writeln!(f, "    (0x{key:08X}, 0x{value:04X}),")?;
+        }
+
+        writeln!(f, "];")?;
+        writeln!(f)?;
+
+        writeln!(f, "pub fn combining_class(character: u16) -> u8 {{")?;
+        writeln!(
+            f,
+            "    // Handle ASCII range explicitly to optimize for the most common characters"
+        )?;
+        writeln!(f, "    if matches!(character, 0x0000..=0x007F) {{")?;
+        writeln!(f, "        return 0;")?;
+        writeln!(f, "    }}")?;
+        writeln!(f)?;
+        writeln!(f, "    match COMBINING_CLASS_LOOKUP.binary_search_by(|&(start, end, _)| {{")?;
+        writeln!(f, "        if end < character {{")?;
+        writeln!(f, "            core::cmp::Ordering::Less")?;
+        writeln!(f, "        }} else if start > character {{")?;
+        writeln!(f, "            core::cmp::Ordering::Greater")?;
+        writeln!(f, "        }} else {{")?;
+        writeln!(f, "            core::cmp::Ordering::Equal")?;
+        writeln!(f, "        }}")?;
+        writeln!(f, "    }}) {{")?;
+        writeln!(f, "        Ok(index) => COMBINING_CLASS_LOOKUP[index].2,")?;
+        writeln!(f, "        Err(_) => 0,")?;
+        writeln!(f, "    }}")?;
+        writeln!(f, "}}")?;
+        writeln!(f)?;
+
+        writeln!(
+            f,
+            "pub fn compose_characters(first: u16, second: u16) -> Option<u16> {{"
+        )?;
+        writeln!(
+            f,
+            "    let key = ((first as u32) << 16) | second as u32;"
+        )?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "    match COMPOSITION_LOOKUP.binary_search_by_key(&key, |&(key, _)| key) {{"
+        )?;
+        writeln!(f, "        Ok(index) => Some(COMPOSITION_LOOKUP[index].1),")?;
+        writeln!(f, "        Err(_) => None,")?;
+        writeln!(f, "    }}")?;
+        writeln!(f, "}}")?;
+        writeln!(f)?;
+
+        writeln!(f, "#[cfg(test)]")?;
+        writeln!(f, "pub mod tests {{")?;
+        writeln!(f, "    use super::*;")?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "    static PARSED_COMBINING_CLASSES: [(u16, u8); {}] = [",
+            self.parsed_combining_classes.len()
+        )?;
+        for (code, combining_class) in self.parsed_combining_classes.iter() {
+            writeln!(f, "        (0x{:04X}, {}),", *code, *combining_class)?;
+        }
+        writeln!(f, "    ];")?;
+        writeln!(f)?;
+
+        writeln!(f, "    #[test]")?;
+        writeln!(f, "    fn combining_class_matches_parsed_lookup() {{")?;
+        writeln!(f, "        for character in 0x0000..=0xFFFF {{")?;
+        writeln!(f, "            assert_eq!(")?;
+        writeln!(f, "                combining_class(character),")?;
+        writeln!(f, "                unoptimized_combining_class(character),")?;
+        writeln!(
+            f,
+            "                \"Optimized result should match unoptimized result for {{:04X}}\","
+        )?;
+        writeln!(f, "                character")?;
+        writeln!(f, "            );")?;
+        writeln!(f, "        }}")?;
+        writeln!(f, "    }}")?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "    pub fn unoptimized_combining_class(character: u16) -> u8 {{"
+        )?;
+        writeln!(
+            f,
+            "        match PARSED_COMBINING_CLASSES.binary_search_by_key(&character, |&(key, _)| key) {{"
+        )?;
+        writeln!(f, "            Ok(index) => PARSED_COMBINING_CLASSES[index].1,")?;
+        writeln!(f, "            Err(_) => 0,")?;
+        writeln!(f, "        }}")?;
+        writeln!(f, "    }}")?;
+        writeln!(f, "}}")?;
+
+        Ok(())
+    }
+}